@@ -0,0 +1,238 @@
+//! Per tick costs of the PocketOption client: parsing `updateStream` frames, aggregating ticks
+//! into candles, converting prices between `f64` and `Decimal` and recording ticks in the
+//! shared state while other tasks read it, along with the maps the state could keep them in.
+//!
+//! Run with `cargo bench -p binary_options_tools --bench hot_paths`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use binary_options_tools::pocketoption::candle::{BaseCandle, Candle, SubscriptionType};
+use binary_options_tools::pocketoption::ssid::Ssid;
+use binary_options_tools::pocketoption::state::{State, StateBuilder};
+use binary_options_tools::pocketoption::types::{Condition, StreamData};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use dashmap::DashMap;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+const TICKS: usize = 1_000;
+
+/// `TICKS` ticks of a random walk, two a second.
+fn ticks() -> Vec<BaseCandle> {
+    let mut price = 1.08123;
+    (0..TICKS)
+        .map(|i| {
+            // Deterministic walk, no need for a generator
+            price += ((i * 7919 % 13) as f64 - 6.0) * 0.00001;
+            BaseCandle::from((1_754_529_180 + i as i64 / 2, price))
+        })
+        .collect()
+}
+
+fn bench_stream_data(c: &mut Criterion) {
+    let binary = br#"[["EURUSD_otc",1754529180.123,1.08123]]"#.to_vec();
+    let text = String::from_utf8(binary.clone()).unwrap();
+
+    let mut group = c.benchmark_group("stream_data");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("binary", |b| {
+        b.iter(|| serde_json::from_slice::<StreamData>(black_box(&binary)).unwrap())
+    });
+    group.bench_function("text", |b| {
+        b.iter(|| serde_json::from_str::<StreamData>(black_box(&text)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_candle_aggregation(c: &mut Criterion) {
+    let ticks = ticks();
+    let subscriptions = [
+        ("none", SubscriptionType::none()),
+        ("chunk_10", SubscriptionType::chunk(10)),
+        ("time_60s", SubscriptionType::time(Duration::from_secs(60))),
+        (
+            "time_aligned_60s",
+            SubscriptionType::time_aligned(Duration::from_secs(60)).unwrap(),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("candle_aggregation");
+    group.throughput(Throughput::Elements(TICKS as u64));
+    for (name, subscription) in subscriptions {
+        group.bench_with_input(BenchmarkId::new("update", name), &ticks, |b, ticks| {
+            b.iter_batched_ref(
+                || subscription.clone(),
+                |subscription| {
+                    for tick in ticks {
+                        black_box(subscription.update(tick).unwrap());
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_decimal(c: &mut Criterion) {
+    let price = 1.081_234_5_f64;
+    let decimal = Decimal::from_f64_retain(price).unwrap();
+    let candle = ticks()[0].clone();
+
+    let mut group = c.benchmark_group("decimal");
+    group.throughput(Throughput::Elements(1));
+    // Tick prices, in StreamData
+    group.bench_function("from_f64_retain", |b| {
+        b.iter(|| Decimal::from_f64_retain(black_box(price)))
+    });
+    // Candle prices
+    group.bench_function("from_f64", |b| {
+        b.iter(|| Decimal::from_f64(black_box(price)))
+    });
+    // Tick prices handed to the candle aggregation
+    group.bench_function("to_f64", |b| b.iter(|| black_box(decimal).to_f64()));
+    group.bench_function("candle_try_from", |b| {
+        b.iter_batched(
+            || (candle.clone(), "EURUSD_otc".to_string()),
+            |input| Candle::try_from(input).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Assets of the concurrent streams.
+const STREAMS: [&str; 4] = ["EURUSD_otc", "GBPUSD_otc", "USDJPY_otc", "AUDCAD_otc"];
+
+fn state() -> Arc<State> {
+    let ssid =
+        Ssid::parse(r#"42["auth",{"session":"bench","isDemo":1,"uid":42,"platform":2}]"#).unwrap();
+    Arc::new(StateBuilder::default().ssid(ssid).build().unwrap())
+}
+
+fn bench_state(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .build()
+        .expect("failed to build the runtime");
+    let state = state();
+
+    let mut group = c.benchmark_group("state");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("record_tick", |b| {
+        b.to_async(&runtime)
+            .iter(|| state.record_tick(black_box("EURUSD_otc"), 1_754_529_180, Decimal::ONE))
+    });
+    // The stream sends every tick, strategies and waiters read the state meanwhile
+    group.throughput(Throughput::Elements((STREAMS.len() * TICKS) as u64));
+    group.bench_function("4_streams_with_readers", |b| {
+        b.to_async(&runtime).iter(|| async {
+            let mut tasks = tokio::task::JoinSet::new();
+            for asset in STREAMS {
+                let stream = state.clone();
+                tasks.spawn(async move {
+                    for i in 0..TICKS {
+                        stream.record_tick(asset, i as i64, Decimal::ONE).await;
+                        black_box(stream.last_price(asset).await);
+                    }
+                });
+                let reader = state.clone();
+                tasks.spawn(async move {
+                    let active = Condition::SubscriptionActive(asset.to_string());
+                    for _ in 0..TICKS {
+                        black_box(reader.is_satisfied(&active).await);
+                    }
+                });
+            }
+            while tasks.join_next().await.is_some() {}
+        })
+    });
+    group.finish();
+}
+
+/// Runs the streams and readers of `4_streams_with_readers` against a bare map of the last
+/// ticks, `record` and `read` standing for `record_tick` and `last_price`.
+macro_rules! bench_map {
+    (
+        $group:expr, $runtime:expr, $name:literal, $map:expr,
+        record: |$m:ident, $asset:ident, $tick:ident| $record:expr,
+        read: |$rm:ident, $rasset:ident| $read:expr $(,)?
+    ) => {
+        let map = Arc::new($map);
+        $group.bench_function($name, |b| {
+            b.to_async(&$runtime).iter(|| async {
+                let mut tasks = tokio::task::JoinSet::new();
+                for asset in STREAMS {
+                    let stream = map.clone();
+                    tasks.spawn(async move {
+                        for i in 0..TICKS {
+                            let ($m, $asset, $tick) = (&stream, asset, (i as i64, Decimal::ONE));
+                            $record;
+                            let ($rm, $rasset) = (&stream, asset);
+                            black_box($read);
+                        }
+                    });
+                    let reader = map.clone();
+                    tasks.spawn(async move {
+                        for _ in 0..TICKS {
+                            let ($rm, $rasset) = (&reader, asset);
+                            black_box($read);
+                        }
+                    });
+                }
+                while tasks.join_next().await.is_some() {}
+            })
+        });
+    };
+}
+
+type Tick = (i64, Decimal);
+
+fn bench_hot_map(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .build()
+        .expect("failed to build the runtime");
+
+    let mut group = c.benchmark_group("hot_map");
+    group.throughput(Throughput::Elements((STREAMS.len() * TICKS) as u64));
+    bench_map!(
+        group,
+        runtime,
+        "mutex",
+        Mutex::new(HashMap::<String, Tick>::new()),
+        record: |map, asset, tick| map.lock().unwrap().insert(asset.to_string(), tick),
+        read: |map, asset| map.lock().unwrap().get(asset).copied(),
+    );
+    // What the state kept the ticks in before `DashMap`
+    bench_map!(
+        group,
+        runtime,
+        "rwlock",
+        tokio::sync::RwLock::new(HashMap::<String, Tick>::new()),
+        record: |map, asset, tick| map.write().await.insert(asset.to_string(), tick),
+        read: |map, asset| map.read().await.get(asset).copied(),
+    );
+    bench_map!(
+        group,
+        runtime,
+        "dashmap",
+        DashMap::<String, Tick>::new(),
+        record: |map, asset, tick| map.insert(asset.to_string(), tick),
+        read: |map, asset| map.get(asset).map(|tick| *tick),
+    );
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_stream_data,
+    bench_candle_aggregation,
+    bench_decimal,
+    bench_state,
+    bench_hot_map
+);
+criterion_main!(benches);
@@ -0,0 +1,6 @@
+fn main() {
+    // The gRPC service is generated from the proto file, which requires `protoc` on the PATH.
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/pocketoption.proto")
+        .expect("Failed to compile proto/pocketoption.proto");
+}
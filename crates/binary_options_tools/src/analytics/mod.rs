@@ -0,0 +1,8 @@
+//! Market statistics computed from the tick and candle streams of a client.
+//!
+//! - [`volatility`]: rolling standard deviation of returns, average true range and an estimate
+//!   of the spread, to skip trading while an asset is dead or moving too much.
+
+pub mod volatility;
+
+pub use volatility::{volatility, Volatility, VolatilitySample, VolatilityStream};
@@ -0,0 +1,243 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+
+use crate::pocketoption::candle::{Candle, SubscriptionType};
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::modules::subscriptions::SubscriptionStream;
+use crate::pocketoption::PocketOption;
+
+/// Volatility of an asset over the last `period` candles.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VolatilitySample {
+    pub asset: String,
+    /// Time of the candle that completed this sample.
+    pub timestamp: DateTime<Utc>,
+    /// Close of that candle.
+    pub price: f64,
+    /// Sample standard deviation of the close-to-close returns, as a fraction of the price.
+    pub stddev: f64,
+    /// Average true range, in price units.
+    pub atr: f64,
+    /// Spread estimated from the negative autocorrelation of the price changes (Roll's
+    /// estimator), `None` when the changes are not negatively correlated over the window.
+    pub spread: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Bar {
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+/// Rolling volatility estimator fed one candle at a time.
+///
+/// Works on ticks too: a tick is a candle whose high, low and close are its price, so the
+/// average true range becomes the average absolute price change.
+#[derive(Clone, Debug)]
+pub struct Volatility {
+    period: usize,
+    bars: VecDeque<Bar>,
+}
+
+impl Volatility {
+    /// `period` is the number of returns in the window, at least 2.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(2);
+        Self {
+            period,
+            bars: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Adds a candle and returns the volatility of the window once it holds `period` returns.
+    pub fn update(&mut self, candle: &Candle) -> Option<VolatilitySample> {
+        let close = candle.close.to_f64()?;
+        let bar = Bar {
+            high: candle.high.to_f64().unwrap_or(close),
+            low: candle.low.to_f64().unwrap_or(close),
+            close,
+        };
+        if self.bars.len() > self.period {
+            self.bars.pop_front();
+        }
+        self.bars.push_back(bar);
+        if self.bars.len() <= self.period {
+            return None;
+        }
+
+        let pairs = || self.bars.iter().zip(self.bars.iter().skip(1));
+        let returns: Vec<f64> = pairs()
+            .filter(|(prev, _)| prev.close != 0.0)
+            .map(|(prev, next)| (next.close - prev.close) / prev.close)
+            .collect();
+        let true_ranges = pairs().map(|(prev, next)| {
+            (next.high - next.low)
+                .max((next.high - prev.close).abs())
+                .max((next.low - prev.close).abs())
+        });
+        let changes: Vec<f64> = pairs()
+            .map(|(prev, next)| next.close - prev.close)
+            .collect();
+
+        Some(VolatilitySample {
+            asset: candle.symbol.clone(),
+            timestamp: candle.datetime(),
+            price: close,
+            stddev: stddev(&returns),
+            atr: true_ranges.sum::<f64>() / self.period as f64,
+            spread: roll_spread(&changes),
+        })
+    }
+
+    /// Clears the window, e.g. after a gap in the data.
+    pub fn reset(&mut self) {
+        self.bars.clear();
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn stddev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(values);
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+fn roll_spread(changes: &[f64]) -> Option<f64> {
+    if changes.len() < 3 {
+        return None;
+    }
+    let (current, previous) = (&changes[1..], &changes[..changes.len() - 1]);
+    let (mean_current, mean_previous) = (mean(current), mean(previous));
+    let covariance = current
+        .iter()
+        .zip(previous)
+        .map(|(c, p)| (c - mean_current) * (p - mean_previous))
+        .sum::<f64>()
+        / (current.len() - 1) as f64;
+    if covariance < 0.0 {
+        Some(2.0 * (-covariance).sqrt())
+    } else {
+        None
+    }
+}
+
+/// A subscription turned into a stream of [`VolatilitySample`]s, one per candle once the
+/// window is full. Dropping it unsubscribes.
+pub struct VolatilityStream {
+    stream: SubscriptionStream,
+    estimator: Volatility,
+}
+
+impl VolatilityStream {
+    /// Computes the volatility of the candles of `stream` over `period` returns.
+    pub fn new(stream: SubscriptionStream, period: usize) -> Self {
+        Self {
+            stream,
+            estimator: Volatility::new(period),
+        }
+    }
+
+    pub fn asset(&self) -> &str {
+        self.stream.asset()
+    }
+
+    /// Waits for the next sample.
+    pub async fn next(&mut self) -> PocketResult<VolatilitySample> {
+        loop {
+            let candle = self.stream.receive().await?;
+            if let Some(sample) = self.estimator.update(&candle) {
+                return Ok(sample);
+            }
+        }
+    }
+
+    /// Converts into a stream of samples.
+    pub fn to_stream(
+        self,
+    ) -> impl futures_util::Stream<Item = PocketResult<VolatilitySample>> + Send + 'static {
+        futures_util::stream::unfold(self, |mut stream| async move {
+            let result = stream.next().await;
+            Some((result, stream))
+        })
+    }
+}
+
+/// Subscribes to the ticks of `asset` and computes their volatility over `period` returns.
+///
+/// Use [`VolatilityStream::new`] with a candle subscription to measure it on candles instead.
+pub async fn volatility(
+    client: &PocketOption,
+    asset: impl ToString,
+    period: usize,
+) -> PocketResult<VolatilityStream> {
+    if period < 2 {
+        return Err(PocketError::General(
+            "volatility needs a period of at least 2".into(),
+        ));
+    }
+    let stream = client.subscribe(asset, SubscriptionType::none()).await?;
+    Ok(VolatilityStream::new(stream, period))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn tick(timestamp: i64, price: Decimal) -> Candle {
+        Candle {
+            symbol: "EURUSD_otc".into(),
+            timestamp,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: None,
+            is_closed: true,
+            synthetic: false,
+        }
+    }
+
+    #[test]
+    fn test_volatility_needs_a_full_window() {
+        let mut estimator = Volatility::new(3);
+        assert!(estimator.update(&tick(1, dec!(1.0))).is_none());
+        assert!(estimator.update(&tick(2, dec!(1.1))).is_none());
+        assert!(estimator.update(&tick(3, dec!(1.0))).is_none());
+        let sample = estimator.update(&tick(4, dec!(1.1))).unwrap();
+        assert_eq!(sample.asset, "EURUSD_otc");
+        assert!((sample.atr - 0.1).abs() < 1e-9);
+        assert!(sample.stddev > 0.0);
+        // Alternating prices bounce like a bid/ask spread of 0.1.
+        assert!(sample.spread.is_some());
+    }
+
+    #[test]
+    fn test_flat_prices_have_no_volatility() {
+        let mut estimator = Volatility::new(2);
+        for t in 0..4 {
+            estimator.update(&tick(t, dec!(1.5)));
+        }
+        let sample = estimator.update(&tick(4, dec!(1.5))).unwrap();
+        assert_eq!(sample.stddev, 0.0);
+        assert_eq!(sample.atr, 0.0);
+        assert_eq!(sample.spread, None);
+    }
+}
@@ -0,0 +1,98 @@
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use rust_decimal::Decimal;
+
+use crate::deriv::Deriv;
+use crate::framework::market::Market;
+use crate::pocketoption::candle::SubscriptionType;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::PocketOption;
+
+/// Live prices of an asset, one per tick.
+pub type PriceStream = Pin<Box<dyn Stream<Item = PocketResult<Decimal>> + Send>>;
+
+/// A broker client the scanner can read prices from and trade on.
+#[async_trait]
+pub trait PriceFeed: Market {
+    /// Name of the broker, reported in the opportunities.
+    fn broker(&self) -> &str;
+
+    /// The broker's symbol for the normalized `symbol`, see [`normalize_symbol`].
+    fn broker_symbol(&self, symbol: &str) -> String {
+        symbol.to_string()
+    }
+
+    /// Subscribes to the ticks of `asset`, a symbol of the broker.
+    async fn prices(&self, asset: &str) -> PocketResult<PriceStream>;
+
+    /// Payout of `asset` in percent, `None` if the broker only prices contracts on demand.
+    async fn payout(&self, asset: &str) -> Option<i32>;
+}
+
+/// The broker independent form of `symbol`: upper case without separators, the `_otc` suffix
+/// of PocketOption or the `frx` prefix of Deriv, so `EURUSD_otc`, `frxEURUSD` and `EUR/USD`
+/// are all `EURUSD`.
+pub fn normalize_symbol(symbol: &str) -> String {
+    let symbol = symbol.trim();
+    let symbol = symbol
+        .strip_prefix("frx")
+        .or_else(|| symbol.strip_prefix("FRX"))
+        .unwrap_or(symbol);
+    let symbol = symbol.to_ascii_uppercase();
+    let symbol = symbol.strip_suffix("_OTC").unwrap_or(&symbol);
+    symbol
+        .chars()
+        .filter(|c| !matches!(c, '/' | '-' | '_' | ' '))
+        .collect()
+}
+
+#[async_trait]
+impl PriceFeed for PocketOption {
+    fn broker(&self) -> &str {
+        "PocketOption"
+    }
+
+    async fn prices(&self, asset: &str) -> PocketResult<PriceStream> {
+        let stream = self
+            .subscribe(asset, SubscriptionType::None)
+            .await?
+            .to_stream()
+            .map(|candle| candle.map(|candle| candle.close));
+        Ok(Box::pin(stream))
+    }
+
+    async fn payout(&self, asset: &str) -> Option<i32> {
+        self.assets().await?.get(asset).map(|asset| asset.payout)
+    }
+}
+
+#[async_trait]
+impl PriceFeed for Deriv {
+    fn broker(&self) -> &str {
+        "Deriv"
+    }
+
+    /// Forex pairs are prefixed with `frx`, synthetic indices keep their symbol.
+    fn broker_symbol(&self, symbol: &str) -> String {
+        if symbol.len() == 6 && symbol.chars().all(|c| c.is_ascii_uppercase()) {
+            format!("frx{symbol}")
+        } else {
+            symbol.to_string()
+        }
+    }
+
+    async fn prices(&self, asset: &str) -> PocketResult<PriceStream> {
+        let stream = self
+            .subscribe(asset, SubscriptionType::None)
+            .await?
+            .to_stream()
+            .map(|candle| candle.map(|candle| candle.close).map_err(PocketError::from));
+        Ok(Box::pin(stream))
+    }
+
+    async fn payout(&self, _: &str) -> Option<i32> {
+        None
+    }
+}
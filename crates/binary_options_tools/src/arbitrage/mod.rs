@@ -0,0 +1,487 @@
+//! Cross-broker price comparison and arbitrage scanner.
+//!
+//! An [`ArbitrageScanner`] subscribes to the same normalized symbol on two [`PriceFeed`]s and
+//! compares their latest prices on every tick. When they diverge by more than
+//! [`ArbitrageConfig::min_divergence`], the broker whose price lags is expected to catch up:
+//! a call on the lower price, a put on the higher one. Each side is rated by its
+//! payout-adjusted edge, the expected return per unit staked
+//! `win_rate * payout - (1 - win_rate)`. The win rate is measured by the scanner itself, from
+//! whether the price of each past divergence moved the expected way after
+//! [`ArbitrageConfig::expiry`] seconds, so a pair whose divergences are noise never reaches a
+//! positive edge.
+//!
+//! Sides whose edge reaches [`ArbitrageConfig::min_edge`] are broadcast as [`Opportunity`]s,
+//! which convert to a [`Signal`] to execute on the broker they name.
+
+mod feed;
+
+pub use feed::{normalize_symbol, PriceFeed, PriceStream};
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::Action;
+use crate::signals::Signal;
+
+/// Opportunities kept for receivers that fall behind before they get `RecvError::Lagged`.
+const EVENTS_CAPACITY: usize = 256;
+/// How often the payouts of the brokers are read again.
+const PAYOUT_REFRESH: Duration = Duration::from_secs(30);
+
+/// Scanner settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageConfig {
+    /// Relative divergence of the prices, `0.0002` being 2 basis points, opening an
+    /// opportunity. Once open, the next one needs the prices to converge below it first.
+    pub min_divergence: Decimal,
+    /// Minimum expected return per unit staked of the opportunities broadcast.
+    pub min_edge: Decimal,
+    /// Expiration of the suggested trades in seconds, and the horizon their outcome is
+    /// measured at.
+    pub expiry: u32,
+    /// Prices older than this are not compared.
+    pub max_staleness: Duration,
+    /// Number of most recent outcomes the win rate is measured over.
+    pub window: usize,
+    /// Outcomes measured before the win rate replaces `prior_win_rate`.
+    pub min_samples: usize,
+    /// Win rate assumed until `min_samples` outcomes are measured.
+    pub prior_win_rate: Decimal,
+    /// Payout in percent of brokers that don't publish one, such as Deriv.
+    pub default_payout: i32,
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        Self {
+            min_divergence: dec!(0.0002),
+            min_edge: Decimal::ZERO,
+            expiry: 60,
+            max_staleness: Duration::from_secs(2),
+            window: 200,
+            min_samples: 20,
+            prior_win_rate: dec!(0.5),
+            default_payout: 80,
+        }
+    }
+}
+
+/// A side of a divergence worth trading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Opportunity {
+    /// Normalized symbol of the pair.
+    pub symbol: String,
+    /// Broker to trade on, the one whose price lags.
+    pub broker: String,
+    /// Symbol of the asset on that broker.
+    pub asset: String,
+    pub action: Action,
+    /// Expiration in seconds.
+    pub expiry: u32,
+    pub price: Decimal,
+    /// Broker whose price leads.
+    pub reference_broker: String,
+    pub reference_price: Decimal,
+    /// `(reference_price - price) / price`, positive for a call.
+    pub divergence: Decimal,
+    /// Payout of the asset in percent.
+    pub payout: i32,
+    /// Measured win rate of this side, or the prior while `samples` is below the minimum.
+    pub win_rate: Decimal,
+    /// Outcomes the win rate was measured over.
+    pub samples: usize,
+    /// Expected return per unit staked.
+    pub edge: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Opportunity {
+    /// The trade of the opportunity, staking the default amount of whoever executes it.
+    pub fn to_signal(&self) -> Signal {
+        Signal {
+            asset: self.asset.clone(),
+            action: self.action,
+            amount: None,
+            expiry: self.expiry,
+        }
+    }
+}
+
+/// Expected return per unit staked of a trade won with probability `win_rate`, paying
+/// `payout` percent of the stake.
+pub fn payout_edge(win_rate: Decimal, payout: i32) -> Decimal {
+    win_rate * Decimal::from(payout) / Decimal::ONE_HUNDRED - (Decimal::ONE - win_rate)
+}
+
+/// A divergence whose outcome is measured once `deadline` is reached.
+#[derive(Debug, Clone)]
+struct PendingOutcome {
+    deadline: DateTime<Utc>,
+    entry: Decimal,
+    action: Action,
+}
+
+#[derive(Debug, Clone)]
+struct Side {
+    broker: String,
+    asset: String,
+    last: Option<(Decimal, DateTime<Utc>)>,
+    payout: Option<i32>,
+    pending: VecDeque<PendingOutcome>,
+    outcomes: VecDeque<bool>,
+}
+
+impl Side {
+    fn new(broker: String, asset: String) -> Self {
+        Self {
+            broker,
+            asset,
+            last: None,
+            payout: None,
+            pending: VecDeque::new(),
+            outcomes: VecDeque::new(),
+        }
+    }
+
+    /// Measures the outcomes due at `now`, a tie being a loss.
+    fn resolve(&mut self, price: Decimal, now: DateTime<Utc>, window: usize) {
+        while let Some(outcome) = self.pending.front() {
+            if outcome.deadline > now {
+                break;
+            }
+            let won = match outcome.action {
+                Action::Call => price > outcome.entry,
+                Action::Put => price < outcome.entry,
+            };
+            self.pending.pop_front();
+            self.outcomes.push_back(won);
+            while self.outcomes.len() > window {
+                self.outcomes.pop_front();
+            }
+        }
+    }
+
+    fn win_rate(&self, config: &ArbitrageConfig) -> Decimal {
+        if self.outcomes.len() < config.min_samples.max(1) {
+            return config.prior_win_rate;
+        }
+        let wins = self.outcomes.iter().filter(|won| **won).count();
+        Decimal::from(wins) / Decimal::from(self.outcomes.len())
+    }
+}
+
+/// The two sides of a scanned pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leg {
+    A,
+    B,
+}
+
+/// Compares the prices of the two legs of a pair, without any I/O so it can be fed recorded
+/// ticks. [`ArbitrageScanner`] drives it from live feeds.
+#[derive(Debug, Clone)]
+pub struct DivergenceTracker {
+    symbol: String,
+    config: ArbitrageConfig,
+    sides: [Side; 2],
+    /// Whether the prices converged since the last divergence.
+    armed: bool,
+}
+
+impl DivergenceTracker {
+    /// Tracks `symbol`, traded as `asset_a` on `broker_a` and `asset_b` on `broker_b`.
+    pub fn new(
+        symbol: impl ToString,
+        (broker_a, asset_a): (impl ToString, impl ToString),
+        (broker_b, asset_b): (impl ToString, impl ToString),
+        config: ArbitrageConfig,
+    ) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            config,
+            sides: [
+                Side::new(broker_a.to_string(), asset_a.to_string()),
+                Side::new(broker_b.to_string(), asset_b.to_string()),
+            ],
+            armed: true,
+        }
+    }
+
+    pub fn config(&self) -> &ArbitrageConfig {
+        &self.config
+    }
+
+    /// Sets the payout of `leg` in percent, `None` falls back to the default payout.
+    pub fn set_payout(&mut self, leg: Leg, payout: Option<i32>) {
+        self.sides[leg as usize].payout = payout;
+    }
+
+    /// Win rate of trading `leg` and the number of outcomes it was measured over.
+    pub fn win_rate(&self, leg: Leg) -> (Decimal, usize) {
+        let side = &self.sides[leg as usize];
+        (side.win_rate(&self.config), side.outcomes.len())
+    }
+
+    /// Latest price of `leg`.
+    pub fn price(&self, leg: Leg) -> Option<Decimal> {
+        self.sides[leg as usize].last.map(|(price, _)| price)
+    }
+
+    /// Relative divergence `(b - a) / a` of the latest prices, `None` unless both are fresh
+    /// at `now`.
+    pub fn divergence(&self, now: DateTime<Utc>) -> Option<Decimal> {
+        let max_age = chrono::Duration::from_std(self.config.max_staleness).ok()?;
+        let [(a, at_a), (b, at_b)] = [self.sides[0].last?, self.sides[1].last?];
+        if now - at_a > max_age || now - at_b > max_age || a.is_zero() {
+            return None;
+        }
+        Some((b - a) / a)
+    }
+
+    /// Records a tick of `leg` received at `now`, returns the opportunities it opens.
+    pub fn update(&mut self, leg: Leg, price: Decimal, now: DateTime<Utc>) -> Vec<Opportunity> {
+        let window = self.config.window;
+        let side = &mut self.sides[leg as usize];
+        side.last = Some((price, now));
+        side.resolve(price, now, window);
+
+        let Some(divergence) = self.divergence(now) else {
+            return Vec::new();
+        };
+        if divergence.abs() < self.config.min_divergence {
+            self.armed = true;
+            return Vec::new();
+        }
+        if !self.armed {
+            return Vec::new();
+        }
+        self.armed = false;
+
+        let deadline = now + chrono::Duration::seconds(i64::from(self.config.expiry));
+        let mut opportunities = Vec::new();
+        for (leg, other) in [(0, 1), (1, 0)] {
+            let (Some((price, _)), Some((reference_price, _))) =
+                (self.sides[leg].last, self.sides[other].last)
+            else {
+                continue;
+            };
+            let action = if reference_price > price {
+                Action::Call
+            } else {
+                Action::Put
+            };
+            let side = &self.sides[leg];
+            let payout = side.payout.unwrap_or(self.config.default_payout);
+            let win_rate = side.win_rate(&self.config);
+            let edge = payout_edge(win_rate, payout);
+            if edge >= self.config.min_edge {
+                opportunities.push(Opportunity {
+                    symbol: self.symbol.clone(),
+                    broker: side.broker.clone(),
+                    asset: side.asset.clone(),
+                    action,
+                    expiry: self.config.expiry,
+                    price,
+                    reference_broker: self.sides[other].broker.clone(),
+                    reference_price,
+                    divergence: (reference_price - price) / price,
+                    payout,
+                    win_rate,
+                    samples: side.outcomes.len(),
+                    edge,
+                    timestamp: now,
+                });
+            }
+            // Measured whether it was broadcast or not, so a side can earn its edge.
+            self.sides[leg].pending.push_back(PendingOutcome {
+                deadline,
+                entry: price,
+                action,
+            });
+        }
+        opportunities
+    }
+}
+
+/// Scans a symbol quoted by two brokers for divergences, see the [module](self) docs.
+pub struct ArbitrageScanner {
+    feeds: [Arc<dyn PriceFeed>; 2],
+    assets: [String; 2],
+    tracker: DivergenceTracker,
+    events: broadcast::Sender<Opportunity>,
+}
+
+impl ArbitrageScanner {
+    /// Scans the normalized `symbol` on `a` and `b`, each under its own
+    /// [`broker_symbol`](PriceFeed::broker_symbol).
+    pub fn new(
+        symbol: &str,
+        a: Arc<dyn PriceFeed>,
+        b: Arc<dyn PriceFeed>,
+        config: ArbitrageConfig,
+    ) -> Self {
+        let symbol = normalize_symbol(symbol);
+        let assets = [a.broker_symbol(&symbol), b.broker_symbol(&symbol)];
+        Self::with_assets(symbol, (a, &assets[0]), (b, &assets[1]), config)
+    }
+
+    /// Scans `symbol` as `asset_a` on `a` and `asset_b` on `b`, for brokers naming it
+    /// differently than their [`broker_symbol`](PriceFeed::broker_symbol), like the OTC
+    /// assets of PocketOption.
+    pub fn with_assets(
+        symbol: impl ToString,
+        (a, asset_a): (Arc<dyn PriceFeed>, &str),
+        (b, asset_b): (Arc<dyn PriceFeed>, &str),
+        config: ArbitrageConfig,
+    ) -> Self {
+        let tracker =
+            DivergenceTracker::new(symbol, (a.broker(), asset_a), (b.broker(), asset_b), config);
+        Self {
+            feeds: [a, b],
+            assets: [asset_a.to_string(), asset_b.to_string()],
+            tracker,
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+        }
+    }
+
+    /// Receiver of the opportunities found once [`run`](Self::run) is called.
+    pub fn opportunities(&self) -> broadcast::Receiver<Opportunity> {
+        self.events.subscribe()
+    }
+
+    /// Subscribes to both feeds and scans until one of them fails or ends.
+    pub async fn run(mut self) -> PocketResult<()> {
+        let mut a = self.feeds[0].prices(&self.assets[0]).await?;
+        let mut b = self.feeds[1].prices(&self.assets[1]).await?;
+        let mut payouts = tokio::time::interval(PAYOUT_REFRESH);
+        info!(target: "ArbitrageScanner", "Scanning {} on {} and {}", self.tracker.symbol, self.feeds[0].broker(), self.feeds[1].broker());
+        loop {
+            let (leg, tick) = tokio::select! {
+                tick = a.next() => (Leg::A, tick),
+                tick = b.next() => (Leg::B, tick),
+                _ = payouts.tick() => {
+                    for leg in [Leg::A, Leg::B] {
+                        let feed = &self.feeds[leg as usize];
+                        let payout = feed.payout(&self.assets[leg as usize]).await;
+                        self.tracker.set_payout(leg, payout);
+                    }
+                    continue;
+                }
+            };
+            let feed = &self.feeds[leg as usize];
+            let price = match tick {
+                Some(price) => price?,
+                None => {
+                    return Err(PocketError::General(format!(
+                        "Price stream of {} on {} ended",
+                        self.assets[leg as usize],
+                        feed.broker()
+                    )))
+                }
+            };
+            for opportunity in self.tracker.update(leg, price, Utc::now()) {
+                debug!(target: "ArbitrageScanner", "{:?} {} on {}, edge {}", opportunity.action, opportunity.asset, opportunity.broker, opportunity.edge);
+                // No receiver is fine, the scan goes on.
+                let _ = self.events.send(opportunity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    fn tracker(config: ArbitrageConfig) -> DivergenceTracker {
+        let mut tracker = DivergenceTracker::new(
+            "EURUSD",
+            ("PocketOption", "EURUSD"),
+            ("Deriv", "frxEURUSD"),
+            config,
+        );
+        tracker.set_payout(Leg::A, Some(92));
+        tracker
+    }
+
+    #[test]
+    fn test_normalize_symbol() {
+        assert_eq!(normalize_symbol("EURUSD_otc"), "EURUSD");
+        assert_eq!(normalize_symbol("frxEURUSD"), "EURUSD");
+        assert_eq!(normalize_symbol(" eur/usd "), "EURUSD");
+        assert_eq!(normalize_symbol("R_100"), "R100");
+    }
+
+    #[test]
+    fn test_divergence_opens_once_per_episode() {
+        let mut tracker = tracker(ArbitrageConfig {
+            prior_win_rate: dec!(0.6),
+            ..Default::default()
+        });
+        assert!(tracker.update(Leg::A, dec!(1.1000), at(0)).is_empty());
+        assert!(tracker.update(Leg::B, dec!(1.1001), at(0)).is_empty());
+
+        let opportunities = tracker.update(Leg::B, dec!(1.1010), at(1));
+        assert_eq!(opportunities.len(), 2);
+        let call = &opportunities[0];
+        assert_eq!(
+            (call.broker.as_str(), call.action),
+            ("PocketOption", Action::Call)
+        );
+        assert_eq!((call.payout, call.edge), (92, dec!(0.152)));
+        let put = &opportunities[1];
+        assert_eq!((put.asset.as_str(), put.action), ("frxEURUSD", Action::Put));
+        assert_eq!(put.edge, dec!(0.08));
+        assert_eq!(call.to_signal().expiry, 60);
+
+        // Still diverged, then converged and diverged again
+        assert!(tracker.update(Leg::B, dec!(1.1012), at(2)).is_empty());
+        assert!(tracker.update(Leg::A, dec!(1.1011), at(3)).is_empty());
+        assert_eq!(tracker.update(Leg::A, dec!(1.0980), at(4)).len(), 2);
+
+        // Stale prices are not compared
+        assert!(tracker.update(Leg::A, dec!(1.0900), at(10)).is_empty());
+        assert_eq!(tracker.divergence(at(10)), None);
+    }
+
+    #[test]
+    fn test_win_rate_is_measured() {
+        let mut tracker = tracker(ArbitrageConfig {
+            min_samples: 2,
+            expiry: 10,
+            ..Default::default()
+        });
+        let mut time = 0;
+        for _ in 0..2 {
+            // Pocket lags Deriv, then catches up
+            tracker.update(Leg::A, dec!(1.1000), at(time));
+            let opportunities = tracker.update(Leg::B, dec!(1.1010), at(time));
+            // The prior win rate gives no edge
+            assert!(opportunities.is_empty());
+            tracker.update(Leg::A, dec!(1.1010), at(time + 10));
+            tracker.update(Leg::B, dec!(1.1010), at(time + 10));
+            time += 20;
+        }
+        assert_eq!(tracker.win_rate(Leg::A), (Decimal::ONE, 2));
+        assert_eq!(tracker.win_rate(Leg::B), (Decimal::ZERO, 2));
+
+        tracker.update(Leg::A, dec!(1.1000), at(time));
+        let opportunities = tracker.update(Leg::B, dec!(1.1010), at(time));
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].broker, "PocketOption");
+        assert_eq!(opportunities[0].edge, dec!(0.92));
+    }
+}
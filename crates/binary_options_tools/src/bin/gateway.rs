@@ -0,0 +1,32 @@
+//! Standalone gateway server, see [`binary_options_tools::gateway`].
+//!
+//! Configured through environment variables:
+//! - `POCKET_OPTION_SSID`: session id used to connect (required)
+//! - `GATEWAY_TOKEN`: token clients must present (required)
+//! - `GATEWAY_ADDR`: listen address, `127.0.0.1:8080` by default
+
+use binary_options_tools::gateway::{self, GatewayConfig};
+use binary_options_tools::pocketoption::PocketOption;
+
+fn required(name: &str) -> anyhow::Result<String> {
+    std::env::var(name).map_err(|_| anyhow::anyhow!("{name} must be set"))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let ssid = required("POCKET_OPTION_SSID")?;
+    let mut config = GatewayConfig::new(required("GATEWAY_TOKEN")?);
+    if let Ok(addr) = std::env::var("GATEWAY_ADDR") {
+        config = config.with_addr(addr.parse()?);
+    }
+
+    let client = PocketOption::new(ssid).await?;
+    tokio::select! {
+        res = gateway::serve(client.clone(), config) => res?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    client.shutdown().await?;
+    Ok(())
+}
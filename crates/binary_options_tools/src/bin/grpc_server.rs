@@ -0,0 +1,29 @@
+//! Standalone gRPC server, see [`binary_options_tools::grpc`].
+//!
+//! Configured through environment variables:
+//! - `POCKET_OPTION_SSID`: session id used to connect (required)
+//! - `GRPC_TOKEN`: token clients must present, calls are not authenticated when unset
+//! - `GRPC_ADDR`: listen address, `127.0.0.1:50051` by default
+
+use binary_options_tools::grpc;
+use binary_options_tools::pocketoption::PocketOption;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let ssid = std::env::var("POCKET_OPTION_SSID")
+        .map_err(|_| anyhow::anyhow!("POCKET_OPTION_SSID must be set"))?;
+    let token = std::env::var("GRPC_TOKEN").ok();
+    let addr = std::env::var("GRPC_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:50051".to_string())
+        .parse()?;
+
+    let client = PocketOption::new(ssid).await?;
+    tokio::select! {
+        res = grpc::serve(client.clone(), addr, token) => res?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    client.shutdown().await?;
+    Ok(())
+}
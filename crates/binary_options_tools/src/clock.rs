@@ -0,0 +1,152 @@
+//! Source of the current time for everything that depends on it (server time estimation,
+//! keep-alive pings, candle history requests, asset schedules and the virtual market).
+//!
+//! Clients use [`SystemClock`]. Tests and backtests can use a [`SimClock`] instead and move
+//! time forward themselves, so time dependent code runs deterministically and without
+//! waiting.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use tokio::sync::watch;
+
+/// A source of the current time that can also wait for time to pass.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Completes once `duration` has passed on this clock.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// A clock shared between the components of a client.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The wall clock, backed by `Utc::now` and `tokio::time::sleep`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        tokio::time::sleep(duration).boxed()
+    }
+}
+
+/// Returns the wall clock as a [`SharedClock`].
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to.
+///
+/// Sleeps complete as soon as [`SimClock::advance`] or [`SimClock::set`] moves the time past
+/// their deadline. Clones share the same time.
+#[derive(Clone)]
+pub struct SimClock {
+    time: Arc<watch::Sender<DateTime<Utc>>>,
+}
+
+impl SimClock {
+    /// Creates a clock stopped at `start`.
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            time: Arc::new(watch::channel(start).0),
+        }
+    }
+
+    /// Creates a clock stopped at the Unix timestamp `timestamp`.
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        Self::new(DateTime::from_timestamp(timestamp, 0).unwrap_or_default())
+    }
+
+    /// Moves the time forward by `duration` and wakes up the sleeps that are due.
+    pub fn advance(&self, duration: Duration) {
+        let duration = chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX);
+        self.time
+            .send_modify(|time| *time = time.checked_add_signed(duration).unwrap_or(*time));
+    }
+
+    /// Moves the time to `time`, it never goes backwards.
+    pub fn set(&self, time: DateTime<Utc>) {
+        self.time.send_if_modified(|current| {
+            let later = time > *current;
+            if later {
+                *current = time;
+            }
+            later
+        });
+    }
+
+    /// Returns the clock as a [`SharedClock`].
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl fmt::Debug for SimClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimClock")
+            .field("now", &*self.time.borrow())
+            .finish()
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.time.borrow()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let deadline = chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| self.now().checked_add_signed(duration));
+        let mut time = self.time.subscribe();
+        async move {
+            match deadline {
+                // The sender lives as long as a clone of the clock does, a dropped clock never
+                // wakes its sleeps up, like time that stopped.
+                Some(deadline) => {
+                    if time.wait_for(|now| *now >= deadline).await.is_err() {
+                        std::future::pending::<()>().await
+                    }
+                }
+                None => std::future::pending().await,
+            }
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sim_clock_sleep_completes_on_advance() {
+        let clock = SimClock::from_timestamp(1_700_000_000);
+        let mut sleep = clock.sleep(Duration::from_secs(20));
+        assert!((&mut sleep).now_or_never().is_none());
+        clock.advance(Duration::from_secs(19));
+        assert!((&mut sleep).now_or_never().is_none());
+        clock.advance(Duration::from_secs(1));
+        assert!(sleep.now_or_never().is_some());
+        assert_eq!(clock.now().timestamp(), 1_700_000_020);
+    }
+
+    #[test]
+    fn test_sim_clock_never_goes_back() {
+        let clock = SimClock::from_timestamp(100);
+        clock.set(DateTime::from_timestamp(50, 0).unwrap());
+        assert_eq!(clock.now().timestamp(), 100);
+        clock.set(DateTime::from_timestamp(150, 0).unwrap());
+        assert_eq!(clock.shared().now().timestamp(), 150);
+    }
+}
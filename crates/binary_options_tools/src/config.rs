@@ -1,41 +1,247 @@
+use crate::clock::{system_clock, SharedClock};
+use crate::pocketoption::{blacklist::BlacklistConfig, dns::DnsConfig, types::AmountLimits};
+use crate::timezone::TimeZoneConfig;
+use binary_options_tools_core::reconnect::{LimitAction, ReconnectPolicy};
+use binary_options_tools_core::supervisor::RestartPolicy;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 use url::Url;
 
+/// Optional PocketOption modules, all enabled by default.
+///
+/// A disabled module is not registered, which saves its message routing and memory. Its
+/// client methods then fail with `PocketError::ModuleNotFound`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModuleFlags {
+    /// Pending orders (`open_pending_order`, `cancel_pending_order`, ...).
+    pub pending_trades: bool,
+    /// Historical ticks and candles (`ticks`, `ticks_range`, `compile_candles`, ...).
+    pub historical_data: bool,
+    /// Raw handlers and validators (`raw_handle`, `create_raw_handler`, ...).
+    pub raw: bool,
+}
+
+impl Default for ModuleFlags {
+    fn default() -> Self {
+        Self {
+            pending_trades: true,
+            historical_data: true,
+            raw: true,
+        }
+    }
+}
+
+/// What the client loads after authenticating, and what `PocketOption::new` waits for.
+///
+/// All requests are sent at once right after authentication, the balance first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InitProfile {
+    /// Requests the balance and the assets only, returns once the balance is known. For
+    /// latency-critical use, trades still need the assets to be validated.
+    Minimal,
+    /// Requests the balance, assets, indicators, favorites and price alerts and subscribes to
+    /// the default symbol, returns once authenticated.
+    #[default]
+    Standard,
+    /// Same requests as `Standard`, returns once the balance and assets are known and the
+    /// server time is synced from the default symbol's history.
+    Full,
+}
+
+/// Keep-alive behaviour of the connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeepAliveConfig {
+    /// Time between two pings.
+    pub interval: Duration,
+    /// Frame sent as ping.
+    pub payload: String,
+    /// Prefix of the server frame answering the ping. When set, the round trip time of every
+    /// ping is measured, see `PocketOption::ping_stats`.
+    pub pong: Option<String>,
+    /// Re-sends the auth message at this interval, for servers dropping sessions that only ping.
+    pub reauth_interval: Option<Duration>,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(20),
+            payload: r#"42["ps"]"#.to_string(),
+            pong: None,
+            reauth_interval: None,
+        }
+    }
+}
+
+impl KeepAliveConfig {
+    /// The entry of `overrides` for `url`. Keys match the URL host (`api-eu.po.market`) or a
+    /// prefix of the URL.
+    pub fn override_for<'a>(
+        overrides: &'a HashMap<String, KeepAliveConfig>,
+        url: &str,
+    ) -> Option<&'a KeepAliveConfig> {
+        let host = Url::parse(url).ok();
+        let host = host.as_ref().and_then(Url::host_str);
+        overrides
+            .iter()
+            .find(|(key, _)| host == Some(key.as_str()) || url.starts_with(key.as_str()))
+            .map(|(_, config)| config)
+    }
+}
+
+/// Limits on the trades of each asset, enforced by the trades module for every order
+/// whichever task places it. Both are disabled by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradeGuardConfig {
+    /// Maximum number of deals open at once on one asset, the orders sent and not yet
+    /// confirmed included. Further orders fail with `PocketError::TooManyOpenDeals`.
+    pub max_open_per_asset: Option<usize>,
+    /// Minimum time between two orders on one asset, shorter ones fail with
+    /// `PocketError::TradeCooldown`.
+    pub cooldown: Duration,
+}
+
+/// Daily limits on the result of the closed deals, once one is hit every order fails with
+/// `PocketError::KillSwitchTripped` until the next server midnight. Both are disabled by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KillSwitchConfig {
+    /// Net profit of the day at which trading stops.
+    pub daily_profit_target: Option<Decimal>,
+    /// Largest drop of the day's net profit from its highest point (the start of the day
+    /// included) at which trading stops.
+    pub max_drawdown: Option<Decimal>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub max_allowed_loops: u32,
+    /// What the runner does once `max_allowed_loops` failed attempts are reached: shut down
+    /// (the default), wait `reconnect_max_delay` or start over with a fresh connection.
+    pub loop_limit_action: LimitAction,
     pub sleep_interval: Duration,
     pub reconnect_time: Duration,
+    /// Factor applied to the reconnect delay after each failed attempt.
+    pub reconnect_multiplier: f64,
+    /// Upper bound for the reconnect delay.
+    pub reconnect_max_delay: Duration,
+    /// Relative jitter applied to the reconnect delay (e.g. `0.2` for ±20%).
+    pub reconnect_jitter: f64,
+    /// How modules that fail or panic are restarted.
+    pub module_restart_policy: RestartPolicy,
     pub connection_initialization_timeout: Duration,
     pub timeout: Duration,
+    /// Trade amount limits keyed by currency code (case insensitive). Without an entry USD
+    /// accounts use `AmountLimits::USD` and other currencies are only checked by the server.
+    pub amount_limits: HashMap<String, AmountLimits>,
     pub urls: Vec<Url>,
     pub proxy: Option<String>,
+    /// Use the proxy of the environment or of the operating system when `proxy` is not set,
+    /// see [`SystemProxy`](crate::pocketoption::system_proxy::SystemProxy).
+    pub system_proxy: bool,
+    /// Static host overrides and DNS-over-HTTPS endpoint used instead of the system resolver.
+    pub dns: DnsConfig,
+    /// How server URLs that keep failing are skipped on reconnects.
+    pub url_blacklist: BlacklistConfig,
     pub user_agent: Option<String>,
     pub origin: Option<String>,
     pub sec_websocket_extensions: Option<String>,
     pub tls_cipher_suites: Option<Vec<String>>,
     pub tls_alpn: Option<Vec<String>>,
+    /// Identical trades (same asset, direction, expiry and amount) placed within this window
+    /// are rejected as duplicates, `Duration::ZERO` disables the check.
+    pub duplicate_trade_window: Duration,
+    /// Per-asset limits on open deals and time between orders.
+    pub trade_guard: TradeGuardConfig,
+    /// Daily profit target and drawdown limit, see `PocketOption::kill_switch_status`.
+    pub kill_switch: KillSwitchConfig,
+    /// Optional modules to register.
+    pub modules: ModuleFlags,
+    /// File the session is saved to on shutdown, see `PocketOption::resume`.
+    pub session_path: Option<PathBuf>,
+    /// File every WebSocket frame is dumped to, gzip compressed with the session masked, see
+    /// [`FrameDumpMiddleware`](binary_options_tools_core::frame_dump::FrameDumpMiddleware).
+    pub frame_dump: Option<PathBuf>,
+    /// Number of sent messages kept with the module sending them, see
+    /// `PocketOption::audit_log`. `0` (the default) disables the audit trail.
+    pub audit_log: usize,
+    /// Source of the current time, a `SimClock` lets tests fast-forward it.
+    pub clock: SharedClock,
+    /// Ping cadence, payload and re-authentication of the connection.
+    pub keep_alive: KeepAliveConfig,
+    /// Keep-alive settings replacing `keep_alive` on some servers, keyed by URL host or prefix.
+    pub keep_alive_overrides: HashMap<String, KeepAliveConfig>,
+    /// The server time re-syncs when its offset to the local time changes by more than this.
+    pub server_time_drift_threshold: Duration,
+    /// What is loaded on connect and waited for before the client is returned.
+    pub init_profile: InitProfile,
+    /// Time zone user supplied times without an offset are read in, UTC by default.
+    pub timezone: TimeZoneConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             max_allowed_loops: 100,
+            loop_limit_action: LimitAction::Abort,
             sleep_interval: Duration::from_millis(100),
             reconnect_time: Duration::from_secs(5),
+            reconnect_multiplier: 2.0,
+            reconnect_max_delay: Duration::from_secs(3600),
+            reconnect_jitter: 0.2,
+            module_restart_policy: RestartPolicy::default(),
             connection_initialization_timeout: Duration::from_secs(60),
             timeout: Duration::from_secs(30),
+            amount_limits: HashMap::new(),
             urls: Vec::new(),
             proxy: None,
+            system_proxy: false,
+            dns: DnsConfig::default(),
+            url_blacklist: BlacklistConfig::default(),
             user_agent: None,
             origin: None,
             sec_websocket_extensions: None,
             tls_cipher_suites: None,
             tls_alpn: None,
+            duplicate_trade_window: Duration::from_secs(2),
+            trade_guard: TradeGuardConfig::default(),
+            kill_switch: KillSwitchConfig::default(),
+            modules: ModuleFlags::default(),
+            session_path: None,
+            frame_dump: None,
+            audit_log: 0,
+            clock: system_clock(),
+            keep_alive: KeepAliveConfig::default(),
+            keep_alive_overrides: HashMap::new(),
+            server_time_drift_threshold: Duration::from_secs(5),
+            init_profile: InitProfile::default(),
+            timezone: TimeZoneConfig::default(),
         }
     }
 }
 
+impl Config {
+    /// Builds the reconnection policy described by this configuration.
+    ///
+    /// `reconnect_time` is the initial delay, `max_allowed_loops` the attempt limit and
+    /// `loop_limit_action` what happens once it is reached.
+    pub fn reconnect_policy(&self) -> ReconnectPolicy {
+        ReconnectPolicy::default()
+            .with_initial_delay(self.reconnect_time)
+            .with_multiplier(self.reconnect_multiplier)
+            .with_max_delay(self.reconnect_max_delay)
+            .with_jitter(self.reconnect_jitter)
+            .with_max_attempts(self.max_allowed_loops)
+            .with_limit_action(self.loop_limit_action)
+    }
+
+    /// Keep-alive settings used on `url`.
+    pub fn keep_alive_for(&self, url: &str) -> &KeepAliveConfig {
+        KeepAliveConfig::override_for(&self.keep_alive_overrides, url).unwrap_or(&self.keep_alive)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,8 +258,49 @@ mod tests {
             Duration::from_secs(60)
         );
         assert_eq!(config.timeout, Duration::from_secs(30));
+        assert!(config.amount_limits.is_empty());
         assert!(config.urls.is_empty());
         assert!(config.proxy.is_none());
+        assert!(!config.system_proxy);
+        assert!(config.frame_dump.is_none());
+        assert_eq!(config.audit_log, 0);
+        assert_eq!(config.duplicate_trade_window, Duration::from_secs(2));
+        assert_eq!(config.trade_guard, TradeGuardConfig::default());
+        assert_eq!(config.kill_switch, KillSwitchConfig::default());
+        assert!(config.modules.pending_trades);
+        assert!(config.modules.historical_data);
+        assert!(config.modules.raw);
+        assert!(config.session_path.is_none());
+        assert_eq!(config.timezone, TimeZoneConfig::default());
+        let drift = config.clock.now() - chrono::Utc::now();
+        assert!(drift.num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn test_keep_alive_overrides() {
+        let mut config = Config::default();
+        let slow = KeepAliveConfig {
+            interval: Duration::from_secs(45),
+            reauth_interval: Some(Duration::from_secs(600)),
+            ..KeepAliveConfig::default()
+        };
+        config
+            .keep_alive_overrides
+            .insert("api-asia.po.market".into(), slow.clone());
+        config.keep_alive_overrides.insert(
+            "wss://demo-api-eu.po.market/".into(),
+            KeepAliveConfig {
+                payload: "2".into(),
+                ..KeepAliveConfig::default()
+            },
+        );
+
+        let asia = "wss://api-asia.po.market/socket.io/?EIO=4&transport=websocket";
+        assert_eq!(config.keep_alive_for(asia), &slow);
+        let demo = "wss://demo-api-eu.po.market/socket.io/?EIO=4&transport=websocket";
+        assert_eq!(config.keep_alive_for(demo).payload, "2");
+        let eu = "wss://api-eu.po.market/socket.io/?EIO=4&transport=websocket";
+        assert_eq!(config.keep_alive_for(eu), &KeepAliveConfig::default());
     }
 
     #[test]
@@ -62,6 +309,11 @@ mod tests {
         let cloned = config.clone();
         assert_eq!(cloned.max_allowed_loops, config.max_allowed_loops);
 
+        let policy = config.reconnect_policy();
+        assert_eq!(policy.initial_delay, config.reconnect_time);
+        assert_eq!(policy.max_attempts, config.max_allowed_loops);
+        assert_eq!(policy.limit_action, LimitAction::Abort);
+
         let debug_str = format!("{:?}", config);
         assert!(debug_str.contains("Config"));
         assert!(debug_str.contains("max_allowed_loops: 100"));
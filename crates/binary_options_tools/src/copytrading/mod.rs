@@ -0,0 +1,426 @@
+//! Copy trading: a leader account publishes the deals it opens and followers replicate them.
+//!
+//! - [`Leader`] forwards every deal opened on its account, including the ones placed from the
+//!   web terminal, to a [`CopySink`] as a [`CopySignal`]
+//! - [`Follower`] reads signals from a [`CopySource`] and places the same trade on its own
+//!   [`Market`], with stake scaling and asset mapping from [`FollowerConfig`]. Every copy is
+//!   reported as a [`CopyReport`] with its latency and entry slippage
+//!
+//! Signals travel in process ([`ChannelSink`]), through a JSON lines file ([`FileSink`] /
+//! [`FileSource`]) or over TCP ([`TcpSink`] / [`TcpSource`]), any other transport can
+//! implement the two traits.
+
+mod transport;
+
+pub use transport::{ChannelSink, ChannelSource, FileSink, FileSource, TcpSink, TcpSource};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::framework::market::Market;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::{Action, Deal};
+use crate::pocketoption::PocketOption;
+
+/// Number of reports buffered for slow [`Follower::reports`] receivers.
+const REPORTS_CAPACITY: usize = 256;
+/// Number of leader deal ids remembered to ignore replayed signals.
+const SEEN_SIGNALS: usize = 1024;
+
+/// A deal opened by the leader.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CopySignal {
+    pub deal_id: Uuid,
+    pub asset: String,
+    pub action: Action,
+    pub amount: Decimal,
+    /// Expiration in seconds.
+    pub expiry: u32,
+    pub open_price: Decimal,
+    pub opened_at: DateTime<Utc>,
+    /// Leader balance when the deal opened, used by [`StakeScaling::BalanceRatio`].
+    #[serde(default)]
+    pub leader_balance: Option<Decimal>,
+}
+
+impl CopySignal {
+    pub fn from_deal(deal: &Deal, leader_balance: Option<Decimal>) -> Self {
+        let expiry = (deal.close_timestamp - deal.open_timestamp)
+            .num_seconds()
+            .max(0) as u32;
+        Self {
+            deal_id: deal.id,
+            asset: deal.asset.clone(),
+            action: if deal.command == 0 {
+                Action::Call
+            } else {
+                Action::Put
+            },
+            amount: deal.amount,
+            expiry,
+            open_price: deal.open_price,
+            opened_at: deal.open_timestamp,
+            leader_balance,
+        }
+    }
+}
+
+/// A destination for the leader's signals.
+#[async_trait]
+pub trait CopySink: Send + Sync {
+    async fn emit(&self, signal: &CopySignal) -> PocketResult<()>;
+}
+
+/// A stream of leader signals read by a follower.
+#[async_trait]
+pub trait CopySource: Send {
+    /// Waits for the next signal, `None` once the source is exhausted or the leader went away.
+    async fn next_signal(&mut self) -> PocketResult<Option<CopySignal>>;
+}
+
+/// Publishes the deals opened on a client's account to a [`CopySink`].
+pub struct Leader {
+    client: PocketOption,
+    sink: Arc<dyn CopySink>,
+}
+
+impl Leader {
+    pub fn new(client: PocketOption, sink: Arc<dyn CopySink>) -> Self {
+        Self { client, sink }
+    }
+
+    /// Runs the leader, sink failures are logged so a follower hiccup does not stop it.
+    pub async fn run(self) -> PocketResult<()> {
+        let mut deals = self.client.opened_deal_events();
+        info!(target: "CopyTrading", "Leader started");
+        loop {
+            match deals.recv().await {
+                Ok(deal) => {
+                    let balance = self.client.balance().await;
+                    let signal = CopySignal::from_deal(&deal, Some(balance));
+                    if let Err(e) = self.sink.emit(&signal).await {
+                        warn!(target: "CopyTrading", "Failed to emit deal {}: {}", deal.id, e);
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(target: "CopyTrading", "Leader is lagging, {} opened deals were not sent", missed);
+                }
+                Err(RecvError::Closed) => {
+                    return Err(PocketError::General("Opened deals channel closed".into()))
+                }
+            }
+        }
+    }
+}
+
+/// How a follower sizes its copy of a leader deal.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "value", rename_all = "snake_case")]
+pub enum StakeScaling {
+    /// The leader's amount.
+    Mirror,
+    /// Always the same amount.
+    Fixed(Decimal),
+    /// The leader's amount times this factor.
+    Multiplier(Decimal),
+    /// The same share of the balance as the leader, falls back to the leader's amount when the
+    /// signal carries no leader balance.
+    BalanceRatio,
+}
+
+/// Follower settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowerConfig {
+    pub stake: StakeScaling,
+    /// Lower bound of the scaled amount.
+    pub min_amount: Option<Decimal>,
+    /// Upper bound of the scaled amount.
+    pub max_amount: Option<Decimal>,
+    /// Leader asset -> follower asset, unmapped assets are traded as is.
+    pub asset_map: HashMap<String, String>,
+    /// Signals opened longer ago than this, by the local clock, are skipped.
+    pub max_delay: Option<Duration>,
+}
+
+impl Default for FollowerConfig {
+    fn default() -> Self {
+        Self {
+            stake: StakeScaling::Mirror,
+            min_amount: None,
+            max_amount: None,
+            asset_map: HashMap::new(),
+            max_delay: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+impl FollowerConfig {
+    pub fn with_stake(mut self, stake: StakeScaling) -> Self {
+        self.stake = stake;
+        self
+    }
+
+    pub fn with_amount_bounds(mut self, min: Option<Decimal>, max: Option<Decimal>) -> Self {
+        self.min_amount = min;
+        self.max_amount = max;
+        self
+    }
+
+    pub fn with_asset(mut self, leader: impl ToString, follower: impl ToString) -> Self {
+        self.asset_map
+            .insert(leader.to_string(), follower.to_string());
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Option<Duration>) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+/// Outcome of copying one leader deal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CopyReport {
+    pub leader_deal_id: Uuid,
+    pub deal_id: Uuid,
+    pub asset: String,
+    pub action: Action,
+    pub amount: Decimal,
+    /// Time between the leader and follower deals opening, from the deal timestamps.
+    pub latency_ms: i64,
+    /// Time spent placing the follower order.
+    pub execution_ms: u64,
+    /// Entry price difference against the follower, e.g. a higher entry on a call.
+    /// Only meaningful when both accounts trade the same asset.
+    pub slippage: Decimal,
+}
+
+/// Replicates leader signals on a [`Market`].
+pub struct Follower {
+    market: Arc<dyn Market>,
+    config: FollowerConfig,
+    reports: broadcast::Sender<CopyReport>,
+}
+
+impl Follower {
+    pub fn new(market: Arc<dyn Market>, config: FollowerConfig) -> Self {
+        Self {
+            market,
+            config,
+            reports: broadcast::channel(REPORTS_CAPACITY).0,
+        }
+    }
+
+    /// Subscribes to the reports of the deals copied by [`Follower::run`].
+    pub fn reports(&self) -> broadcast::Receiver<CopyReport> {
+        self.reports.subscribe()
+    }
+
+    /// Computes the follower amount for a signal, rounded to cents and clamped to the bounds.
+    pub async fn stake(&self, signal: &CopySignal) -> Decimal {
+        let amount = match self.config.stake {
+            StakeScaling::Mirror => signal.amount,
+            StakeScaling::Fixed(amount) => amount,
+            StakeScaling::Multiplier(factor) => signal.amount * factor,
+            StakeScaling::BalanceRatio => match signal.leader_balance {
+                Some(leader) if leader > Decimal::ZERO => {
+                    signal.amount / leader * self.market.balance().await
+                }
+                _ => signal.amount,
+            },
+        };
+        let mut amount = amount.round_dp(2);
+        if let Some(min) = self.config.min_amount {
+            amount = amount.max(min);
+        }
+        if let Some(max) = self.config.max_amount {
+            amount = amount.min(max);
+        }
+        amount
+    }
+
+    /// Places the copy of a signal and measures it.
+    pub async fn copy(&self, signal: &CopySignal) -> PocketResult<CopyReport> {
+        let asset = self
+            .config
+            .asset_map
+            .get(&signal.asset)
+            .unwrap_or(&signal.asset);
+        let amount = self.stake(signal).await;
+        let started = Instant::now();
+        let (deal_id, deal) = match signal.action {
+            Action::Call => self.market.buy(asset, amount, signal.expiry).await?,
+            Action::Put => self.market.sell(asset, amount, signal.expiry).await?,
+        };
+        let execution_ms = started.elapsed().as_millis() as u64;
+        let slippage = match signal.action {
+            Action::Call => deal.open_price - signal.open_price,
+            Action::Put => signal.open_price - deal.open_price,
+        };
+        Ok(CopyReport {
+            leader_deal_id: signal.deal_id,
+            deal_id,
+            asset: asset.clone(),
+            action: signal.action,
+            amount,
+            latency_ms: (deal.open_timestamp - signal.opened_at).num_milliseconds(),
+            execution_ms,
+            slippage,
+        })
+    }
+
+    /// Copies every signal of `source` until it is exhausted.
+    ///
+    /// Replayed and stale signals are skipped, failed copies are logged and do not stop the
+    /// follower.
+    pub async fn run(&self, mut source: impl CopySource) -> PocketResult<()> {
+        let mut seen = HashSet::new();
+        let mut order = VecDeque::new();
+        while let Some(signal) = source.next_signal().await? {
+            if !seen.insert(signal.deal_id) {
+                continue;
+            }
+            order.push_back(signal.deal_id);
+            if order.len() > SEEN_SIGNALS {
+                if let Some(oldest) = order.pop_front() {
+                    seen.remove(&oldest);
+                }
+            }
+            if let Some(max_delay) = self.config.max_delay {
+                let age = (Utc::now() - signal.opened_at).to_std().unwrap_or_default();
+                if age > max_delay {
+                    warn!(target: "CopyTrading", "Skipping deal {}, opened {:?} ago", signal.deal_id, age);
+                    continue;
+                }
+            }
+            match self.copy(&signal).await {
+                Ok(report) => {
+                    info!(target: "CopyTrading", "Copied deal {} as {} ({} ms, slippage {})", report.leader_deal_id, report.deal_id, report.latency_ms, report.slippage);
+                    let _ = self.reports.send(report);
+                }
+                Err(e) => {
+                    warn!(target: "CopyTrading", "Failed to copy deal {}: {}", signal.deal_id, e)
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::virtual_market::VirtualMarket;
+    use rust_decimal_macros::dec;
+
+    fn signal(action: Action, amount: Decimal) -> CopySignal {
+        CopySignal {
+            deal_id: Uuid::new_v4(),
+            asset: "EURUSD_otc".to_string(),
+            action,
+            amount,
+            expiry: 60,
+            open_price: dec!(1.1000),
+            opened_at: Utc::now(),
+            leader_balance: Some(dec!(1000)),
+        }
+    }
+
+    async fn market() -> Arc<VirtualMarket> {
+        let market = Arc::new(VirtualMarket::new(dec!(500)));
+        market.update_price("EURUSD", dec!(1.1002)).await;
+        market.update_price("EURUSD_otc", dec!(1.1002)).await;
+        market
+    }
+
+    async fn stake(
+        market: &Arc<VirtualMarket>,
+        scaling: StakeScaling,
+        leader: &CopySignal,
+    ) -> Decimal {
+        Follower::new(
+            market.clone(),
+            FollowerConfig::default().with_stake(scaling),
+        )
+        .stake(leader)
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_stake_scaling() {
+        let market = market().await;
+        let leader = signal(Action::Call, dec!(10));
+        assert_eq!(
+            stake(&market, StakeScaling::Mirror, &leader).await,
+            dec!(10)
+        );
+        assert_eq!(
+            stake(&market, StakeScaling::Fixed(dec!(3)), &leader).await,
+            dec!(3)
+        );
+        assert_eq!(
+            stake(&market, StakeScaling::Multiplier(dec!(0.25)), &leader).await,
+            dec!(2.5)
+        );
+        // 1% of the leader balance -> 1% of the follower balance.
+        assert_eq!(
+            stake(&market, StakeScaling::BalanceRatio, &leader).await,
+            dec!(5)
+        );
+
+        let bounded = Follower::new(
+            market.clone(),
+            FollowerConfig::default()
+                .with_stake(StakeScaling::Multiplier(dec!(0.01)))
+                .with_amount_bounds(Some(dec!(1)), Some(dec!(50))),
+        );
+        assert_eq!(bounded.stake(&leader).await, dec!(1));
+    }
+
+    #[tokio::test]
+    async fn test_copy_maps_asset_and_reports_slippage() {
+        let follower = Follower::new(
+            market().await,
+            FollowerConfig::default().with_asset("EURUSD_otc", "EURUSD"),
+        );
+        let report = follower
+            .copy(&signal(Action::Call, dec!(10)))
+            .await
+            .unwrap();
+        assert_eq!(report.asset, "EURUSD");
+        assert_eq!(report.amount, dec!(10));
+        assert_eq!(report.slippage, dec!(0.0002));
+
+        let report = follower.copy(&signal(Action::Put, dec!(10))).await.unwrap();
+        assert_eq!(report.slippage, dec!(-0.0002));
+    }
+
+    #[tokio::test]
+    async fn test_run_skips_replayed_and_stale_signals() {
+        let follower = Follower::new(market().await, FollowerConfig::default());
+        let mut reports = follower.reports();
+        let sink = ChannelSink::new(16);
+        let source = sink.subscribe();
+
+        let fresh = signal(Action::Call, dec!(10));
+        let mut stale = signal(Action::Call, dec!(10));
+        stale.opened_at = Utc::now() - chrono::Duration::seconds(30);
+        sink.emit(&fresh).await.unwrap();
+        sink.emit(&fresh).await.unwrap();
+        sink.emit(&stale).await.unwrap();
+        drop(sink);
+
+        follower.run(source).await.unwrap();
+        assert_eq!(reports.recv().await.unwrap().leader_deal_id, fresh.deal_id);
+        assert!(reports.try_recv().is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::error::CoreError;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::{CopySignal, CopySink, CopySource};
+use crate::pocketoption::error::PocketResult;
+
+/// How often a [`FileSource`] checks the file for new signals.
+const FILE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn encode(signal: &CopySignal) -> PocketResult<Vec<u8>> {
+    let mut line = serde_json::to_vec(signal).map_err(CoreError::from)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Parses a JSON line, malformed lines are logged and skipped.
+fn decode(line: &str) -> Option<CopySignal> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    serde_json::from_str(line)
+        .inspect_err(|e| warn!(target: "CopyTrading", "Ignoring malformed signal {}: {}", line, e))
+        .ok()
+}
+
+/// Sends signals to followers in the same process.
+pub struct ChannelSink {
+    sender: broadcast::Sender<CopySignal>,
+}
+
+impl ChannelSink {
+    /// `capacity` signals are buffered for slow followers.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Adds a follower, it only receives the signals emitted from now on.
+    pub fn subscribe(&self) -> ChannelSource {
+        ChannelSource {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+#[async_trait]
+impl CopySink for ChannelSink {
+    async fn emit(&self, signal: &CopySignal) -> PocketResult<()> {
+        // No follower subscribed is not an error.
+        let _ = self.sender.send(signal.clone());
+        Ok(())
+    }
+}
+
+/// Receives the signals of a [`ChannelSink`], exhausted once the sink is dropped.
+pub struct ChannelSource {
+    receiver: broadcast::Receiver<CopySignal>,
+}
+
+#[async_trait]
+impl CopySource for ChannelSource {
+    async fn next_signal(&mut self) -> PocketResult<Option<CopySignal>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(signal) => return Ok(Some(signal)),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(target: "CopyTrading", "Follower is lagging, {} signals were missed", missed);
+                }
+                Err(RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Appends signals to a file, one JSON object per line.
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub async fn open(path: impl AsRef<Path>) -> PocketResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(CoreError::from)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl CopySink for FileSink {
+    async fn emit(&self, signal: &CopySignal) -> PocketResult<()> {
+        let line = encode(signal)?;
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await.map_err(CoreError::from)?;
+        file.flush().await.map_err(CoreError::from)?;
+        Ok(())
+    }
+}
+
+/// Follows a file written by a [`FileSink`], like `tail -f`. It is never exhausted.
+pub struct FileSource {
+    reader: BufReader<File>,
+    line: String,
+}
+
+impl FileSource {
+    /// Opens `path` and only reads the signals appended from now on.
+    pub async fn open(path: impl AsRef<Path>) -> PocketResult<Self> {
+        let mut file = File::open(path).await.map_err(CoreError::from)?;
+        file.seek(std::io::SeekFrom::End(0))
+            .await
+            .map_err(CoreError::from)?;
+        Ok(Self::new(file))
+    }
+
+    /// Opens `path` and replays the signals already in it first.
+    pub async fn from_start(path: impl AsRef<Path>) -> PocketResult<Self> {
+        let file = File::open(path).await.map_err(CoreError::from)?;
+        Ok(Self::new(file))
+    }
+
+    fn new(file: File) -> Self {
+        Self {
+            reader: BufReader::new(file),
+            line: String::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl CopySource for FileSource {
+    async fn next_signal(&mut self) -> PocketResult<Option<CopySignal>> {
+        loop {
+            let read = self
+                .reader
+                .read_line(&mut self.line)
+                .await
+                .map_err(CoreError::from)?;
+            // A line without its newline is still being written, keep it and wait for the rest.
+            if read == 0 || !self.line.ends_with('\n') {
+                tokio::time::sleep(FILE_POLL_INTERVAL).await;
+                continue;
+            }
+            let signal = decode(&self.line);
+            self.line.clear();
+            if signal.is_some() {
+                return Ok(signal);
+            }
+        }
+    }
+}
+
+/// Serves signals to the followers connected over TCP, one JSON object per line.
+pub struct TcpSink {
+    local_addr: SocketAddr,
+    followers: Arc<Mutex<Vec<TcpStream>>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl TcpSink {
+    /// Listens on `addr`, use port 0 to pick a free port and read it with [`TcpSink::local_addr`].
+    pub async fn bind(addr: impl ToSocketAddrs) -> PocketResult<Self> {
+        let listener = TcpListener::bind(addr).await.map_err(CoreError::from)?;
+        let local_addr = listener.local_addr().map_err(CoreError::from)?;
+        let followers = Arc::new(Mutex::new(Vec::new()));
+        let accepted = followers.clone();
+        let accept_task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        info!(target: "CopyTrading", "Follower connected from {}", peer);
+                        accepted.lock().await.push(stream);
+                    }
+                    Err(e) => warn!(target: "CopyTrading", "Failed to accept a follower: {}", e),
+                }
+            }
+        });
+        info!(target: "CopyTrading", "Serving signals on {}", local_addr);
+        Ok(Self {
+            local_addr,
+            followers,
+            accept_task,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Number of connected followers.
+    pub async fn followers(&self) -> usize {
+        self.followers.lock().await.len()
+    }
+}
+
+impl Drop for TcpSink {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+#[async_trait]
+impl CopySink for TcpSink {
+    /// Sends the signal to every follower, the ones that cannot be written to are dropped.
+    async fn emit(&self, signal: &CopySignal) -> PocketResult<()> {
+        let line = encode(signal)?;
+        let mut followers = self.followers.lock().await;
+        let mut connected = Vec::with_capacity(followers.len());
+        for mut stream in followers.drain(..) {
+            match stream.write_all(&line).await {
+                Ok(()) => connected.push(stream),
+                Err(e) => warn!(target: "CopyTrading", "Dropping follower: {}", e),
+            }
+        }
+        *followers = connected;
+        Ok(())
+    }
+}
+
+/// Receives the signals of a [`TcpSink`], exhausted once the leader closes the connection.
+pub struct TcpSource {
+    reader: BufReader<TcpStream>,
+    line: String,
+}
+
+impl TcpSource {
+    pub async fn connect(addr: impl ToSocketAddrs) -> PocketResult<Self> {
+        let stream = TcpStream::connect(addr).await.map_err(CoreError::from)?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+            line: String::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl CopySource for TcpSource {
+    async fn next_signal(&mut self) -> PocketResult<Option<CopySignal>> {
+        loop {
+            self.line.clear();
+            let read = self
+                .reader
+                .read_line(&mut self.line)
+                .await
+                .map_err(CoreError::from)?;
+            if read == 0 {
+                return Ok(None);
+            }
+            if let Some(signal) = decode(&self.line) {
+                return Ok(Some(signal));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::types::Action;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn signal() -> CopySignal {
+        CopySignal {
+            deal_id: Uuid::new_v4(),
+            asset: "EURUSD_otc".to_string(),
+            action: Action::Put,
+            amount: dec!(5),
+            expiry: 60,
+            open_price: dec!(1.1),
+            opened_at: Utc::now(),
+            leader_balance: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("copytrading-{}.jsonl", Uuid::new_v4()));
+        let sink = FileSink::open(&path).await.unwrap();
+        let replayed = signal();
+        sink.emit(&replayed).await.unwrap();
+
+        let mut tail = FileSource::open(&path).await.unwrap();
+        let mut replay = FileSource::from_start(&path).await.unwrap();
+        let sent = signal();
+        sink.emit(&sent).await.unwrap();
+
+        assert_eq!(replay.next_signal().await.unwrap(), Some(replayed));
+        assert_eq!(tail.next_signal().await.unwrap(), Some(sent));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_round_trip() {
+        let sink = TcpSink::bind("127.0.0.1:0").await.unwrap();
+        let mut source = TcpSource::connect(sink.local_addr()).await.unwrap();
+        while sink.followers().await == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let sent = signal();
+        sink.emit(&sent).await.unwrap();
+        assert_eq!(source.next_signal().await.unwrap(), Some(sent));
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{builder::ClientBuilder, client::Client, error::CoreError};
+use rust_decimal::Decimal;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::deriv::{
+    connect::DerivConnect,
+    error::{DerivError, DerivResult},
+    modules::{
+        authorize::AuthorizeModule,
+        keep_alive::PingModule,
+        ticks::{DerivSubscription, TicksModule},
+        trades::TradesModule,
+    },
+    state::{Config, State},
+    types::{Account, Contract, ContractRequest},
+};
+use crate::framework::market::Market;
+use crate::pocketoption::candle::SubscriptionType;
+use crate::pocketoption::error::PocketResult;
+use crate::pocketoption::types::{Action, Deal};
+
+/// How long to wait for the server to authorize the token or answer a purchase.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client of the Deriv API.
+///
+/// Authenticated with an API token with the `trade` scope, it buys rise/fall contracts on
+/// the account of the token, virtual or real, and streams the ticks of its symbols. It
+/// implements [`Market`], so the strategies of the [framework](crate::framework) can trade on
+/// it. Contracts are identified by [`Uuid`]s wrapping their contract id, see
+/// [`Contract::deal_id`].
+#[derive(Clone)]
+pub struct Deriv {
+    client: Client<State>,
+    _runner: Arc<JoinHandle<()>>,
+}
+
+impl Deriv {
+    fn builder(state: State) -> ClientBuilder<State> {
+        ClientBuilder::new(DerivConnect, state)
+            .with_lightweight_module::<PingModule>()
+            .with_module::<AuthorizeModule>()
+            .with_module::<TradesModule>()
+            .with_module::<TicksModule>()
+    }
+
+    /// Connects as the application of the API documentation, see [`Config`] to use your own.
+    pub async fn new(token: impl ToString) -> DerivResult<Self> {
+        Self::new_with_config(token, Config::default()).await
+    }
+
+    /// Connects and waits until the token is authorized, failing if it is rejected.
+    pub async fn new_with_config(token: impl ToString, config: Config) -> DerivResult<Self> {
+        let state = State::new(token.to_string(), config);
+        let (client, mut runner) = Self::builder(state).build().await?;
+
+        let _runner = tokio::spawn(async move { runner.run().await });
+        client.wait_connected().await;
+
+        let deriv = Self {
+            client,
+            _runner: Arc::new(_runner),
+        };
+        deriv.account().await?;
+        Ok(deriv)
+    }
+
+    /// The account of the token.
+    pub async fn account(&self) -> DerivResult<Account> {
+        let handle = self
+            .client
+            .get_handle::<AuthorizeModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("AuthorizeModule".into()))?;
+        tokio::time::timeout(REQUEST_TIMEOUT, handle.account())
+            .await
+            .map_err(|_| CoreError::TimeoutError {
+                task: "authorize".into(),
+                duration: REQUEST_TIMEOUT,
+            })?
+    }
+
+    /// Checks if the account is a virtual (demo) one.
+    pub async fn is_demo(&self) -> bool {
+        self.client.state.is_virtual().await
+    }
+
+    /// Balance of the account, -1 until the server sent it.
+    pub async fn balance(&self) -> Decimal {
+        self.client.state.get_balance().await
+    }
+
+    /// Buys `contract`, priced by a proposal first.
+    pub async fn buy_contract(&self, contract: ContractRequest) -> DerivResult<Contract> {
+        if contract.amount <= Decimal::ZERO {
+            return Err(DerivError::InvalidAmount(contract.amount));
+        }
+        let handle = self
+            .client
+            .get_handle::<TradesModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("TradesModule".into()))?;
+        tokio::time::timeout(REQUEST_TIMEOUT, handle.buy(contract))
+            .await
+            .map_err(|_| CoreError::TimeoutError {
+                task: "buy".into(),
+                duration: REQUEST_TIMEOUT,
+            })?
+    }
+
+    /// Buys a rise/fall contract of `amount` on `symbol` for `time` seconds, in the currency
+    /// of the account.
+    pub async fn trade(
+        &self,
+        symbol: impl ToString,
+        action: Action,
+        time: u32,
+        amount: Decimal,
+    ) -> DerivResult<(Uuid, Deal)> {
+        let account = self.account().await?;
+        let contract = ContractRequest::rise_fall(symbol, action, time, amount, account.currency);
+        let contract = self.buy_contract(contract).await?;
+        let deal = contract.to_deal(account.is_virtual);
+        Ok((deal.id, deal))
+    }
+
+    pub async fn buy(
+        &self,
+        symbol: impl ToString,
+        time: u32,
+        amount: Decimal,
+    ) -> DerivResult<(Uuid, Deal)> {
+        self.trade(symbol, Action::Call, time, amount).await
+    }
+
+    pub async fn sell(
+        &self,
+        symbol: impl ToString,
+        time: u32,
+        amount: Decimal,
+    ) -> DerivResult<(Uuid, Deal)> {
+        self.trade(symbol, Action::Put, time, amount).await
+    }
+
+    /// Waits until the contract `contract_id` is sold and returns it.
+    pub async fn contract_result(&self, contract_id: u64) -> DerivResult<Contract> {
+        if self.client.state.contract(contract_id).await.is_none() {
+            return Err(DerivError::ContractNotFound(contract_id));
+        }
+        let handle = self
+            .client
+            .get_handle::<TradesModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("TradesModule".into()))?;
+        handle.result(contract_id).await
+    }
+
+    /// Waits until the deal `id` closes and returns it.
+    pub async fn result(&self, id: Uuid) -> DerivResult<Deal> {
+        let contract_id = u64::try_from(id.as_u128())
+            .map_err(|_| DerivError::General(format!("{id} is not the id of a Deriv deal")))?;
+        let contract = self.contract_result(contract_id).await?;
+        Ok(contract.to_deal(self.is_demo().await))
+    }
+
+    /// Contracts not sold yet, by contract id.
+    pub async fn opened_contracts(&self) -> HashMap<u64, Contract> {
+        self.client.state.opened_contracts.read().await.clone()
+    }
+
+    /// Contracts sold since the client connected, by contract id.
+    pub async fn closed_contracts(&self) -> HashMap<u64, Contract> {
+        self.client.state.closed_contracts.read().await.clone()
+    }
+
+    /// Subscribes to the ticks of `symbol`, aggregated as `sub_type`.
+    pub async fn subscribe(
+        &self,
+        symbol: impl ToString,
+        sub_type: SubscriptionType,
+    ) -> DerivResult<DerivSubscription> {
+        let handle = self
+            .client
+            .get_handle::<TicksModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("TicksModule".into()))?;
+        let symbol = symbol.to_string();
+        tokio::time::timeout(REQUEST_TIMEOUT, handle.subscribe(symbol.clone(), sub_type))
+            .await
+            .map_err(|_| CoreError::TimeoutError {
+                task: format!("ticks of {symbol}"),
+                duration: REQUEST_TIMEOUT,
+            })?
+    }
+
+    /// Disconnects and reconnects the client.
+    pub async fn reconnect(&self) -> DerivResult<()> {
+        self.client.reconnect().await.map_err(DerivError::from)
+    }
+
+    /// Shuts down the client and stops the runner.
+    pub async fn shutdown(self) -> DerivResult<()> {
+        self.client.shutdown().await.map_err(DerivError::from)
+    }
+}
+
+#[async_trait]
+impl Market for Deriv {
+    async fn buy(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        Ok(self.buy(asset, time, amount).await?)
+    }
+
+    async fn sell(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        Ok(self.sell(asset, time, amount).await?)
+    }
+
+    async fn balance(&self) -> Decimal {
+        self.balance().await
+    }
+
+    async fn result(&self, trade_id: Uuid) -> PocketResult<Deal> {
+        Ok(self.result(trade_id).await?)
+    }
+}
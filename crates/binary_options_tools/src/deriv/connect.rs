@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use binary_options_tools_core::{
+    connector::{Connector as ConnectorTrait, ConnectorError, ConnectorResult},
+    reimports::{
+        connect_async_tls_with_config, generate_key, Connector, MaybeTlsStream, Request,
+        WebSocketStream,
+    },
+};
+use tokio::net::TcpStream;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::deriv::state::State;
+use crate::utils::init_crypto_provider;
+
+#[derive(Clone)]
+pub struct DerivConnect;
+
+#[async_trait::async_trait]
+impl ConnectorTrait<State> for DerivConnect {
+    async fn connect(
+        &self,
+        state: Arc<State>,
+    ) -> ConnectorResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        for url in state.config.urls() {
+            debug!(target: "DerivConnect", "Connecting to Deriv at {url}");
+            match try_connect(&url).await {
+                Ok(stream) => {
+                    debug!(target: "DerivConnect", "Successfully connected to Deriv");
+                    return Ok(stream);
+                }
+                Err(e) => warn!(target: "DerivConnect", "Failed to connect to {}: {}", url, e),
+            }
+        }
+        Err(ConnectorError::Custom(
+            "Failed to connect to any of the provided URLs".to_string(),
+        ))
+    }
+
+    async fn disconnect(&self) -> ConnectorResult<()> {
+        debug!(target: "DerivConnect", "Closing WebSocket transport.");
+        Ok(())
+    }
+}
+
+pub async fn try_connect(url: &str) -> ConnectorResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    init_crypto_provider();
+    let mut root_store = rustls::RootCertStore::empty();
+    let certs_result = rustls_native_certs::load_native_certs();
+    if !certs_result.errors.is_empty() {
+        warn!(target: "DerivConnect", "Some native certificates failed to load: {:?}", certs_result.errors);
+    }
+    if certs_result.certs.is_empty() {
+        return Err(ConnectorError::Custom(
+            "Could not load any native certificates".to_string(),
+        ));
+    }
+    for cert in certs_result.certs {
+        root_store.add(cert).ok();
+    }
+    let tls_config = rustls::ClientConfig::builder()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    let connector = Connector::Rustls(Arc::new(tls_config));
+
+    let t_url = Url::parse(url).map_err(|e| ConnectorError::UrlParsing(e.to_string()))?;
+    let host = t_url
+        .host_str()
+        .ok_or(ConnectorError::UrlParsing("Host not found".into()))?;
+    let request = Request::builder()
+        .uri(t_url.to_string())
+        .header("Cache-Control", "no-cache")
+        .header("Upgrade", "websocket")
+        .header("Connection", "upgrade")
+        .header("Sec-Websocket-Key", generate_key())
+        .header("Sec-Websocket-Version", "13")
+        .header("Host", host)
+        .body(())
+        .map_err(|e| ConnectorError::HttpRequestBuild(e.to_string()))?;
+
+    let (ws, _) = connect_async_tls_with_config(request, None, false, Some(connector))
+        .await
+        .map_err(|e| ConnectorError::Custom(e.to_string()))?;
+    Ok(ws)
+}
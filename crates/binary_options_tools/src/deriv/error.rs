@@ -0,0 +1,47 @@
+use binary_options_tools_core::error::CoreError;
+use rust_decimal::Decimal;
+
+use crate::pocketoption::error::PocketError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DerivError {
+    #[error("Serde JSON deserialization error: {0}")]
+    Deserializing(#[from] serde_json::Error),
+
+    #[error("Core error: {0}")]
+    Core(#[from] Box<CoreError>),
+
+    /// Error returned by the API, see its `code` in the API documentation.
+    #[error("Deriv API error {code}: {message}")]
+    Api { code: String, message: String },
+
+    #[error("Not authorized yet")]
+    NotAuthorized,
+
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(Decimal),
+
+    #[error("Contract {0} not found")]
+    ContractNotFound(u64),
+
+    #[error("General error: {0}")]
+    General(String),
+}
+
+pub type DerivResult<T> = Result<T, DerivError>;
+
+impl From<CoreError> for DerivError {
+    fn from(err: CoreError) -> Self {
+        DerivError::Core(Box::new(err))
+    }
+}
+
+/// For the [`Market`](crate::framework::market::Market) implementation.
+impl From<DerivError> for PocketError {
+    fn from(err: DerivError) -> Self {
+        match err {
+            DerivError::Core(err) => PocketError::Core(*err),
+            err => PocketError::General(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Deriv (formerly binary.com) client over its documented WebSocket API.
+//!
+//! Requests and responses are JSON text frames. Every response names its request in
+//! `msg_type` and echoes the `req_id` the request was sent with, streams also carry a
+//! `subscription` id to `forget` them. The session is authorized with an API token of the
+//! account, created in its settings, and the client connects as the application `app_id`.
+pub mod client;
+pub mod connect;
+pub mod error;
+pub mod modules;
+pub mod state;
+pub mod types;
+
+pub use client::Deriv;
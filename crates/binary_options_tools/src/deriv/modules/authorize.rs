@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::status::ConnectionStatus;
+use binary_options_tools_core::traits::{ApiModule, ReconnectCallback, Rule, RunnerCommand};
+use serde_json::json;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::{debug, error, warn};
+
+use crate::deriv::error::{DerivError, DerivResult};
+use crate::deriv::state::State;
+use crate::deriv::types::{request, Account, BalanceUpdate, MsgTypeRule, Response};
+
+const AUTHORIZE: &str = "authorize";
+const BALANCE: &str = "balance";
+
+#[derive(Debug)]
+pub enum Request {
+    /// Waits until the token is authorized.
+    Account {
+        responder: oneshot::Sender<DerivResult<Account>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct AuthorizeHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl AuthorizeHandle {
+    /// The account of the token, once authorized.
+    pub async fn account(&self) -> DerivResult<Account> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::Account { responder })
+            .await
+            .map_err(CoreError::from)?;
+        response.await.map_err(|_| {
+            DerivError::General("AuthorizeModule stopped before answering the request".into())
+        })?
+    }
+}
+
+/// Authorizes the token on every connection and keeps the balance up to date.
+///
+/// A rejected token shuts the client down, since the server closes the session.
+pub struct AuthorizeModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    command_receiver: AsyncReceiver<Request>,
+    runner_command_tx: AsyncSender<RunnerCommand>,
+    state: Arc<State>,
+    waiting: Vec<oneshot::Sender<DerivResult<Account>>>,
+}
+
+impl AuthorizeModule {
+    async fn wait_account(&mut self, responder: oneshot::Sender<DerivResult<Account>>) {
+        match &*self.state.account.read().await {
+            Some(account) => {
+                let _ = responder.send(Ok(account.clone()));
+            }
+            None => self.waiting.push(responder),
+        }
+    }
+
+    async fn handle(&mut self, response: Response) -> CoreResult<()> {
+        match response.msg_type.as_str() {
+            AUTHORIZE => match response.data::<Account>() {
+                Ok(account) => {
+                    debug!(target: "AuthorizeModule", "Authorized as {}", account.loginid);
+                    *self.state.balance.write().await = Some(account.balance);
+                    *self.state.account.write().await = Some(account.clone());
+                    for responder in self.waiting.drain(..) {
+                        let _ = responder.send(Ok(account.clone()));
+                    }
+                    self.runner_command_tx
+                        .send(RunnerCommand::SetStatus(ConnectionStatus::Authenticated))
+                        .await?;
+                }
+                Err(e) => {
+                    error!(target: "AuthorizeModule", "Deriv rejected the token: {e}");
+                    for responder in self.waiting.drain(..) {
+                        let _ = responder.send(response.data());
+                    }
+                    if let Err(e) = self.runner_command_tx.send(RunnerCommand::Shutdown).await {
+                        warn!(target: "AuthorizeModule", "Failed to send shutdown command to runner: {}", e);
+                    }
+                }
+            },
+            BALANCE => match response.data::<BalanceUpdate>() {
+                Ok(update) => {
+                    debug!(target: "AuthorizeModule", "Balance: {} {}", update.balance, update.currency);
+                    *self.state.balance.write().await = Some(update.balance);
+                }
+                Err(e) => warn!(target: "AuthorizeModule", "Failed to parse balance: {e}"),
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Sends the `authorize` request and subscribes to the balance, on every connection.
+struct AuthorizeCallback;
+
+#[async_trait::async_trait]
+impl ReconnectCallback<State> for AuthorizeCallback {
+    async fn call(&self, state: Arc<State>, ws_sender: &AsyncSender<Message>) -> CoreResult<()> {
+        ws_sender
+            .send(request(json!({ "authorize": state.token })))
+            .await?;
+        ws_sender
+            .send(request(json!({ "balance": 1, "subscribe": 1 })))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for AuthorizeModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = AuthorizeHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<Message>,
+        runner_command_tx: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            command_receiver,
+            runner_command_tx,
+            state: shared_state,
+            waiting: Vec::new(),
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        AuthorizeHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Some(response) = Response::parse(&msg) {
+                                self.handle(response).await?;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::Account { responder }) => self.wait_account(responder).await,
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MsgTypeRule::new(&[AUTHORIZE, BALANCE]))
+    }
+
+    fn callback(
+        _shared_state: Arc<State>,
+        _command_receiver: AsyncReceiver<Self::Command>,
+        _command_responder: AsyncSender<Self::CommandResponse>,
+        _message_receiver: AsyncReceiver<Arc<Message>>,
+        _to_ws_sender: AsyncSender<Message>,
+    ) -> CoreResult<Option<Box<dyn ReconnectCallback<State>>>> {
+        Ok(Some(Box::new(AuthorizeCallback)))
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{
+    error::{CoreError, CoreResult},
+    reimports::{AsyncReceiver, AsyncSender, Message},
+    traits::{LightweightModule, Rule, RunnerCommand},
+};
+use serde_json::json;
+
+use crate::deriv::state::State;
+use crate::deriv::types::request;
+
+/// The server closes connections idle for two minutes.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Sends a `ping` request every [`PING_INTERVAL`], the `pong` answers are ignored.
+pub struct PingModule {
+    ws_sender: AsyncSender<Message>,
+}
+
+#[async_trait]
+impl LightweightModule<State> for PingModule {
+    fn new(
+        _: Arc<State>,
+        ws_sender: AsyncSender<Message>,
+        _: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { ws_sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            tokio::time::sleep(PING_INTERVAL).await;
+            if self
+                .ws_sender
+                .send(request(json!({"ping": 1})))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+        Err(CoreError::LightweightModuleLoop("PingModule".into()))
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|_: &Message| false)
+    }
+}
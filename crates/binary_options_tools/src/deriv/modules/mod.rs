@@ -0,0 +1,4 @@
+pub mod authorize;
+pub mod keep_alive;
+pub mod ticks;
+pub mod trades;
@@ -0,0 +1,352 @@
+//! Live ticks of Deriv symbols.
+//!
+//! Symbols are subscribed with a `ticks` request and their prices pushed in `tick`
+//! responses. Every [`DerivSubscription`] of a symbol receives its ticks and builds candles
+//! from them with a [`SubscriptionType`], as the PocketOption subscriptions do. The stream is
+//! forgotten when the last subscription of the symbol is dropped, and subscribed again after
+//! a reconnection.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{bounded_async, AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, ReconnectCallback, Rule, RunnerCommand};
+use futures_util::stream::unfold;
+use serde_json::json;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::deriv::error::{DerivError, DerivResult};
+use crate::deriv::state::State;
+use crate::deriv::types::{request, MsgTypeRule, Response, Tick};
+use crate::pocketoption::candle::{BaseCandle, Candle, SubscriptionType};
+
+const TICK: &str = "tick";
+/// Type of the answers rejecting a `ticks` request.
+const TICKS: &str = "ticks";
+/// Ticks buffered per subscription, newer ticks are dropped while it is full.
+const SUBSCRIPTION_BUFFER: usize = 1024;
+
+/// Answers the subscription to a symbol, once the server confirmed or rejected it.
+type Responder = oneshot::Sender<DerivResult<()>>;
+
+#[derive(Debug)]
+pub enum Request {
+    Subscribe {
+        symbol: String,
+        id: Uuid,
+        sender: AsyncSender<BaseCandle>,
+        responder: Responder,
+    },
+    Unsubscribe {
+        symbol: String,
+        id: Uuid,
+    },
+}
+
+#[derive(Clone)]
+pub struct TicksHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl TicksHandle {
+    /// Subscribes to the ticks of `symbol`, fails if the server rejects the symbol.
+    pub async fn subscribe(
+        &self,
+        symbol: String,
+        sub_type: SubscriptionType,
+    ) -> DerivResult<DerivSubscription> {
+        let (sender, receiver) = bounded_async(SUBSCRIPTION_BUFFER);
+        let (responder, response) = oneshot::channel();
+        let id = Uuid::new_v4();
+        self.sender
+            .send(Request::Subscribe {
+                symbol: symbol.clone(),
+                id,
+                sender,
+                responder,
+            })
+            .await
+            .map_err(CoreError::from)?;
+        let subscription = DerivSubscription {
+            receiver,
+            sender: self.sender.clone(),
+            id,
+            symbol,
+            sub_type,
+        };
+        response.await.map_err(|_| {
+            DerivError::General("TicksModule stopped before answering the request".into())
+        })??;
+        Ok(subscription)
+    }
+}
+
+/// Candles of a symbol, built from its live ticks.
+///
+/// Dropping it unsubscribes.
+pub struct DerivSubscription {
+    receiver: AsyncReceiver<BaseCandle>,
+    sender: AsyncSender<Request>,
+    id: Uuid,
+    symbol: String,
+    sub_type: SubscriptionType,
+}
+
+impl DerivSubscription {
+    pub fn asset(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Receives the next candle.
+    pub async fn receive(&mut self) -> DerivResult<Candle> {
+        loop {
+            let tick = self.receiver.recv().await.map_err(CoreError::from)?;
+            let bar = self
+                .sub_type
+                .update(&tick)
+                .map_err(|e| DerivError::General(e.to_string()))?;
+            if let Some(bar) = bar {
+                let mut candle = Candle::try_from((bar, self.symbol.clone()))
+                    .map_err(|e| DerivError::General(e.to_string()))?;
+                candle.is_closed = !matches!(self.sub_type, SubscriptionType::None);
+                return Ok(candle);
+            }
+        }
+    }
+
+    /// Converts the subscription into a stream, dropping the stream unsubscribes.
+    pub fn to_stream(self) -> impl futures_util::Stream<Item = DerivResult<Candle>> + 'static {
+        Box::pin(unfold(self, |mut subscription| async move {
+            let result = subscription.receive().await;
+            Some((result, subscription))
+        }))
+    }
+}
+
+impl Drop for DerivSubscription {
+    fn drop(&mut self) {
+        // The module also drops subscriptions whose receiver is gone, if this doesn't fit.
+        let _ = self.sender.try_send(Request::Unsubscribe {
+            symbol: self.symbol.clone(),
+            id: self.id,
+        });
+    }
+}
+
+/// The `ticks` request subscribing to `symbol`.
+fn ticks(symbol: &str, req_id: Option<u64>) -> Message {
+    let mut value = json!({ "ticks": symbol, "subscribe": 1 });
+    if let Some(req_id) = req_id {
+        value["req_id"] = req_id.into();
+    }
+    request(value)
+}
+
+/// Ticks module, routes the ticks of the subscribed symbols.
+///
+/// The first answer to a `ticks` request confirms the subscription, or rejects the symbol.
+/// Every tick carries the id of its stream, kept to `forget` the stream later.
+pub struct TicksModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    state: Arc<State>,
+    subscribers: HashMap<String, Vec<(Uuid, AsyncSender<BaseCandle>)>>,
+    /// Stream id by symbol
+    streams: HashMap<String, String>,
+    /// Subscriptions waiting for their first answer, by `req_id`
+    pending: HashMap<u64, (String, Vec<Responder>)>,
+    next_req_id: u64,
+}
+
+impl TicksModule {
+    async fn subscribe(
+        &mut self,
+        symbol: String,
+        id: Uuid,
+        sender: AsyncSender<BaseCandle>,
+        responder: Responder,
+    ) -> CoreResult<()> {
+        let subscribers = self.subscribers.entry(symbol.clone()).or_default();
+        subscribers.push((id, sender));
+        if subscribers.len() > 1 {
+            // Confirmed along with the first subscriber if it is still pending
+            match self.pending.values_mut().find(|(s, _)| *s == symbol) {
+                Some((_, responders)) => responders.push(responder),
+                None => {
+                    let _ = responder.send(Ok(()));
+                }
+            }
+            return Ok(());
+        }
+        self.next_req_id += 1;
+        let req_id = self.next_req_id;
+        self.ws_sender.send(ticks(&symbol, Some(req_id))).await?;
+        self.state
+            .tick_subscriptions
+            .write()
+            .await
+            .insert(symbol.clone());
+        self.pending.insert(req_id, (symbol, vec![responder]));
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, symbol: &str, id: Option<Uuid>) -> CoreResult<()> {
+        let Some(subscribers) = self.subscribers.get_mut(symbol) else {
+            return Ok(());
+        };
+        subscribers.retain(|(sub_id, sender)| Some(*sub_id) != id && !sender.is_disconnected());
+        if subscribers.is_empty() {
+            self.subscribers.remove(symbol);
+            self.state.tick_subscriptions.write().await.remove(symbol);
+            if let Some(stream) = self.streams.remove(symbol) {
+                self.ws_sender
+                    .send(request(json!({ "forget": stream })))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle(&mut self, response: Response) -> CoreResult<()> {
+        if let Some((symbol, responders)) = response.req_id.and_then(|id| self.pending.remove(&id))
+        {
+            if let Some(error) = &response.error {
+                // Nothing was subscribed
+                self.subscribers.remove(&symbol);
+                self.state.tick_subscriptions.write().await.remove(&symbol);
+                for responder in responders {
+                    let _ = responder.send(Err(error.clone().into()));
+                }
+                return Ok(());
+            }
+            for responder in responders {
+                let _ = responder.send(Ok(()));
+            }
+        }
+        let tick = match response.data::<Tick>() {
+            Ok(tick) => tick,
+            Err(e) => {
+                debug!(target: "TicksModule", "Tick without data: {e}");
+                return Ok(());
+            }
+        };
+        if let Some(stream) = response.subscription_id() {
+            self.streams.insert(tick.symbol.clone(), stream.to_string());
+        }
+        let Some(subscribers) = self.subscribers.get_mut(&tick.symbol) else {
+            return Ok(());
+        };
+        let point = BaseCandle::from((tick.epoch, tick.quote));
+        subscribers.retain(|(_, sender)| match sender.try_send(point.clone()) {
+            Ok(true) => true,
+            Ok(false) => {
+                warn!(target: "TicksModule", "Subscription to {} is full, dropping a tick", tick.symbol);
+                true
+            }
+            Err(_) => false,
+        });
+        if subscribers.is_empty() {
+            self.unsubscribe(&tick.symbol, None).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Subscribes again to the symbols subscribed before the reconnection.
+struct TicksCallback;
+
+#[async_trait::async_trait]
+impl ReconnectCallback<State> for TicksCallback {
+    async fn call(&self, state: Arc<State>, ws_sender: &AsyncSender<Message>) -> CoreResult<()> {
+        let symbols = state.tick_subscriptions.read().await.clone();
+        for symbol in symbols {
+            ws_sender.send(ticks(&symbol, None)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for TicksModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = TicksHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            state: shared_state,
+            subscribers: HashMap::new(),
+            streams: HashMap::new(),
+            pending: HashMap::new(),
+            next_req_id: 0,
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        TicksHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Some(response) = Response::parse(&msg) {
+                                self.handle(response).await?;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::Subscribe { symbol, id, sender, responder }) => {
+                            self.subscribe(symbol, id, sender, responder).await?;
+                        }
+                        Ok(Request::Unsubscribe { symbol, id }) => {
+                            self.unsubscribe(&symbol, Some(id)).await?;
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MsgTypeRule::new(&[TICK, TICKS]))
+    }
+
+    fn callback(
+        _shared_state: Arc<State>,
+        _command_receiver: AsyncReceiver<Self::Command>,
+        _command_responder: AsyncSender<Self::CommandResponse>,
+        _message_receiver: AsyncReceiver<Arc<Message>>,
+        _to_ws_sender: AsyncSender<Message>,
+    ) -> CoreResult<Option<Box<dyn ReconnectCallback<State>>>> {
+        Ok(Some(Box::new(TicksCallback)))
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, ReconnectCallback, Rule, RunnerCommand};
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::json;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::deriv::error::{DerivError, DerivResult};
+use crate::deriv::state::State;
+use crate::deriv::types::{
+    request, BuyReceipt, Contract, ContractRequest, MsgTypeRule, Proposal, Response,
+};
+
+const PROPOSAL: &str = "proposal";
+const BUY: &str = "buy";
+const OPEN_CONTRACT: &str = "proposal_open_contract";
+
+#[derive(Debug)]
+pub enum Request {
+    /// Prices and buys `contract`.
+    Buy {
+        contract: ContractRequest,
+        responder: oneshot::Sender<DerivResult<Contract>>,
+    },
+    /// Waits until the contract `contract_id` is sold.
+    Result {
+        contract_id: u64,
+        responder: oneshot::Sender<Contract>,
+    },
+}
+
+#[derive(Clone)]
+pub struct TradesHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl TradesHandle {
+    pub async fn buy(&self, contract: ContractRequest) -> DerivResult<Contract> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::Buy {
+                contract,
+                responder,
+            })
+            .await
+            .map_err(CoreError::from)?;
+        response.await.map_err(|_| stopped())?
+    }
+
+    /// The contract `contract_id` once sold.
+    pub async fn result(&self, contract_id: u64) -> DerivResult<Contract> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::Result {
+                contract_id,
+                responder,
+            })
+            .await
+            .map_err(CoreError::from)?;
+        response.await.map_err(|_| stopped())
+    }
+}
+
+fn stopped() -> DerivError {
+    DerivError::General("TradesModule stopped before answering the request".into())
+}
+
+/// Step of a purchase waiting for the answer of the server.
+enum Pending {
+    Proposal {
+        contract: ContractRequest,
+        responder: oneshot::Sender<DerivResult<Contract>>,
+    },
+    Buy {
+        contract: ContractRequest,
+        responder: oneshot::Sender<DerivResult<Contract>>,
+    },
+}
+
+/// The `proposal_open_contract` subscription following `contract_id`.
+fn follow(contract_id: u64) -> Message {
+    request(json!({
+        "proposal_open_contract": 1,
+        "contract_id": contract_id,
+        "subscribe": 1,
+    }))
+}
+
+/// Trades module, buys contracts and follows them until they are sold.
+///
+/// A purchase is a `proposal` pricing the contract, then a `buy` of the proposal at its ask
+/// price. Answers are matched to the purchases by the `req_id` they echo. Bought contracts
+/// are followed with `proposal_open_contract`, which streams them until sold.
+pub struct TradesModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    state: Arc<State>,
+    pending: HashMap<u64, Pending>,
+    waiting_results: HashMap<u64, Vec<oneshot::Sender<Contract>>>,
+    next_req_id: u64,
+}
+
+impl TradesModule {
+    fn req_id(&mut self) -> u64 {
+        self.next_req_id += 1;
+        self.next_req_id
+    }
+
+    async fn buy(
+        &mut self,
+        contract: ContractRequest,
+        responder: oneshot::Sender<DerivResult<Contract>>,
+    ) -> CoreResult<()> {
+        let req_id = self.req_id();
+        self.ws_sender.send(contract.proposal(req_id)).await?;
+        self.pending.insert(
+            req_id,
+            Pending::Proposal {
+                contract,
+                responder,
+            },
+        );
+        Ok(())
+    }
+
+    async fn wait_result(&mut self, contract_id: u64, responder: oneshot::Sender<Contract>) {
+        if let Some(contract) = self.state.closed_contracts.read().await.get(&contract_id) {
+            let _ = responder.send(contract.clone());
+            return;
+        }
+        self.waiting_results
+            .entry(contract_id)
+            .or_default()
+            .push(responder);
+    }
+
+    async fn handle(&mut self, response: Response) -> CoreResult<()> {
+        if response.msg_type == OPEN_CONTRACT {
+            match response.data::<Contract>() {
+                Ok(contract) => self.update(contract, response.subscription_id()).await?,
+                // Also sent for contracts the account doesn't own
+                Err(e) => debug!(target: "TradesModule", "Open contract without data: {e}"),
+            }
+            return Ok(());
+        }
+        let Some(pending) = response.req_id.and_then(|id| self.pending.remove(&id)) else {
+            debug!(target: "TradesModule", "{} answer without a pending purchase", response.msg_type);
+            return Ok(());
+        };
+        match pending {
+            Pending::Proposal {
+                contract,
+                responder,
+            } => match response.data::<Proposal>() {
+                Ok(proposal) => {
+                    let req_id = self.req_id();
+                    let buy = json!({
+                        "buy": proposal.id,
+                        "price": proposal.ask_price.to_f64(),
+                        "req_id": req_id,
+                    });
+                    self.ws_sender.send(request(buy)).await?;
+                    self.pending.insert(
+                        req_id,
+                        Pending::Buy {
+                            contract,
+                            responder,
+                        },
+                    );
+                }
+                Err(e) => {
+                    let _ = responder.send(Err(e));
+                }
+            },
+            Pending::Buy {
+                contract,
+                responder,
+            } => match response.data::<BuyReceipt>() {
+                Ok(receipt) => {
+                    let contract = Contract::bought(&contract, &receipt);
+                    self.state.update_contract(contract.clone()).await;
+                    self.ws_sender.send(follow(contract.contract_id)).await?;
+                    let _ = responder.send(Ok(contract));
+                }
+                Err(e) => {
+                    let _ = responder.send(Err(e));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    async fn update(&mut self, contract: Contract, subscription: Option<&str>) -> CoreResult<()> {
+        if !contract.is_sold {
+            self.state.update_contract(contract).await;
+            return Ok(());
+        }
+        if let Some(subscription) = subscription {
+            self.ws_sender
+                .send(request(json!({ "forget": subscription })))
+                .await?;
+        }
+        for responder in self
+            .waiting_results
+            .remove(&contract.contract_id)
+            .unwrap_or_default()
+        {
+            let _ = responder.send(contract.clone());
+        }
+        self.state.update_contract(contract).await;
+        Ok(())
+    }
+}
+
+/// Follows again the contracts still open, the subscriptions end with the connection.
+struct TradesCallback;
+
+#[async_trait::async_trait]
+impl ReconnectCallback<State> for TradesCallback {
+    async fn call(&self, state: Arc<State>, ws_sender: &AsyncSender<Message>) -> CoreResult<()> {
+        let contract_ids: Vec<u64> = state
+            .opened_contracts
+            .read()
+            .await
+            .keys()
+            .copied()
+            .collect();
+        for contract_id in contract_ids {
+            ws_sender.send(follow(contract_id)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for TradesModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = TradesHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            state: shared_state,
+            pending: HashMap::new(),
+            waiting_results: HashMap::new(),
+            next_req_id: 0,
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        TradesHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Some(response) = Response::parse(&msg) {
+                                self.handle(response).await?;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::Buy { contract, responder }) => self.buy(contract, responder).await?,
+                        Ok(Request::Result { contract_id, responder }) => self.wait_result(contract_id, responder).await,
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MsgTypeRule::new(&[PROPOSAL, BUY, OPEN_CONTRACT]))
+    }
+
+    fn callback(
+        _shared_state: Arc<State>,
+        _command_receiver: AsyncReceiver<Self::Command>,
+        _command_responder: AsyncSender<Self::CommandResponse>,
+        _message_receiver: AsyncReceiver<Arc<Message>>,
+        _to_ws_sender: AsyncSender<Message>,
+    ) -> CoreResult<Option<Box<dyn ReconnectCallback<State>>>> {
+        Ok(Some(Box::new(TradesCallback)))
+    }
+}
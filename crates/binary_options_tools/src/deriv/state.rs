@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+
+use binary_options_tools_core::traits::AppState;
+use rust_decimal::Decimal;
+use tokio::sync::RwLock;
+
+use crate::deriv::types::{Account, Contract, DEFAULT_APP_ID};
+
+/// WebSocket endpoints of the API, tried in order.
+pub const DEFAULT_ENDPOINTS: &[&str] = &[
+    "wss://ws.derivws.com/websockets/v3",
+    "wss://ws.binaryws.com/websockets/v3",
+];
+
+pub struct Config {
+    /// Id of the registered application the client connects as
+    pub app_id: u32,
+    pub endpoints: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            app_id: DEFAULT_APP_ID,
+            endpoints: DEFAULT_ENDPOINTS
+                .iter()
+                .map(|url| url.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Config {
+    /// URLs of the endpoints for the application.
+    pub fn urls(&self) -> impl Iterator<Item = String> + '_ {
+        self.endpoints
+            .iter()
+            .map(|endpoint| format!("{endpoint}?app_id={}&l=EN", self.app_id))
+    }
+}
+
+pub struct State {
+    /// API token of the account
+    pub token: String,
+    /// Configuration for the Deriv client
+    pub config: Config,
+    /// Account of the token, set once authorized
+    pub account: RwLock<Option<Account>>,
+    /// Balance of the account, kept up to date by the `balance` subscription
+    pub balance: RwLock<Option<Decimal>>,
+    /// Contracts not sold yet, by contract id
+    pub opened_contracts: RwLock<HashMap<u64, Contract>>,
+    /// Sold contracts, by contract id
+    pub closed_contracts: RwLock<HashMap<u64, Contract>>,
+    /// Symbols of the tick streams subscribed to
+    pub tick_subscriptions: RwLock<HashSet<String>>,
+}
+
+#[async_trait::async_trait]
+impl AppState for State {
+    async fn clear_temporal_data(&self) {
+        // Sent again after authorizing
+        *self.balance.write().await = None;
+    }
+}
+
+impl State {
+    pub fn new(token: String, config: Config) -> Self {
+        State {
+            token,
+            config,
+            account: RwLock::new(None),
+            balance: RwLock::new(None),
+            opened_contracts: RwLock::new(HashMap::new()),
+            closed_contracts: RwLock::new(HashMap::new()),
+            tick_subscriptions: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Balance of the account, -1 until the server sent it.
+    pub async fn get_balance(&self) -> Decimal {
+        self.balance.read().await.unwrap_or(Decimal::NEGATIVE_ONE)
+    }
+
+    /// Checks if the account is a virtual (demo) one, false until authorized.
+    pub async fn is_virtual(&self) -> bool {
+        self.account
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|account| account.is_virtual)
+    }
+
+    /// Records `contract`, moving it to the closed contracts once sold.
+    pub async fn update_contract(&self, contract: Contract) {
+        if contract.is_sold {
+            self.opened_contracts
+                .write()
+                .await
+                .remove(&contract.contract_id);
+            self.closed_contracts
+                .write()
+                .await
+                .insert(contract.contract_id, contract);
+        } else {
+            self.opened_contracts
+                .write()
+                .await
+                .insert(contract.contract_id, contract);
+        }
+    }
+
+    /// The contract `contract_id`, sold or still open.
+    pub async fn contract(&self, contract_id: u64) -> Option<Contract> {
+        if let Some(contract) = self.closed_contracts.read().await.get(&contract_id) {
+            return Some(contract.clone());
+        }
+        self.opened_contracts
+            .read()
+            .await
+            .get(&contract_id)
+            .cloned()
+    }
+}
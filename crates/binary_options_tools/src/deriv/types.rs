@@ -0,0 +1,347 @@
+use binary_options_tools_core::reimports::Message;
+use binary_options_tools_core::traits::Rule;
+use chrono::DateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::deriv::error::DerivError;
+use crate::pocketoption::types::{Action, Deal, RequestId};
+use crate::utils::serialize::bool2int;
+
+/// Application id of the Deriv API documentation, for testing. Register an application to get
+/// your own.
+pub const DEFAULT_APP_ID: u32 = 1089;
+
+/// A request of the API.
+pub fn request(value: Value) -> Message {
+    Message::text(value.to_string())
+}
+
+/// Routes the responses whose `msg_type` is one of `msg_types`.
+pub struct MsgTypeRule {
+    patterns: Vec<String>,
+}
+
+impl MsgTypeRule {
+    pub fn new(msg_types: &[&str]) -> Self {
+        Self {
+            patterns: msg_types
+                .iter()
+                .map(|msg_type| format!(r#""msg_type":"{msg_type}""#))
+                .collect(),
+        }
+    }
+}
+
+impl Rule for MsgTypeRule {
+    fn call(&self, msg: &Message) -> bool {
+        match msg {
+            Message::Text(text) => self.patterns.iter().any(|p| text.contains(p.as_str())),
+            _ => false,
+        }
+    }
+
+    fn reset(&self) { /* stateless */
+    }
+}
+
+/// Error of a response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+}
+
+impl From<ApiError> for DerivError {
+    fn from(error: ApiError) -> Self {
+        DerivError::Api {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
+/// A response, its data is under the key named after its `msg_type`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response {
+    pub msg_type: String,
+    #[serde(default)]
+    pub req_id: Option<u64>,
+    #[serde(default)]
+    pub error: Option<ApiError>,
+    #[serde(default)]
+    pub echo_req: Value,
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, Value>,
+}
+
+impl Response {
+    pub fn parse(msg: &Message) -> Option<Self> {
+        match msg {
+            Message::Text(text) => serde_json::from_str(text).ok(),
+            _ => None,
+        }
+    }
+
+    /// The data of the response, or its error.
+    pub fn data<T: for<'de> Deserialize<'de>>(&self) -> Result<T, DerivError> {
+        if let Some(error) = &self.error {
+            return Err(error.clone().into());
+        }
+        let data = self.fields.get(&self.msg_type).cloned().unwrap_or_default();
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Id of the subscription the response belongs to.
+    pub fn subscription_id(&self) -> Option<&str> {
+        self.fields.get("subscription")?.get("id")?.as_str()
+    }
+}
+
+/// The account of the `authorize` response.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Account {
+    pub loginid: String,
+    pub currency: String,
+    pub balance: Decimal,
+    #[serde(with = "bool2int")]
+    pub is_virtual: bool,
+}
+
+/// The `balance` subscription update.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BalanceUpdate {
+    pub balance: Decimal,
+    pub currency: String,
+}
+
+/// A tick of a `ticks` subscription.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tick {
+    pub symbol: String,
+    pub epoch: i64,
+    pub quote: f64,
+}
+
+/// Contract to buy, priced by a `proposal`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractRequest {
+    pub symbol: String,
+    /// `CALL` (rise) or `PUT` (fall).
+    pub contract_type: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
+    pub basis: String,
+    pub currency: String,
+    pub duration: u32,
+    /// `s` for seconds, `m` for minutes, `t` for ticks.
+    pub duration_unit: String,
+}
+
+impl ContractRequest {
+    /// A rise/fall contract staking `amount` for `time` seconds.
+    pub fn rise_fall(
+        symbol: impl ToString,
+        action: Action,
+        time: u32,
+        amount: Decimal,
+        currency: impl ToString,
+    ) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            contract_type: match action {
+                Action::Call => "CALL",
+                Action::Put => "PUT",
+            }
+            .to_string(),
+            amount,
+            basis: "stake".to_string(),
+            currency: currency.to_string(),
+            duration: time,
+            duration_unit: "s".to_string(),
+        }
+    }
+
+    /// The `proposal` request pricing the contract.
+    pub fn proposal(&self, req_id: u64) -> Message {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Value::Object(fields) = &mut value {
+            fields.insert("proposal".into(), 1.into());
+            fields.insert("req_id".into(), req_id.into());
+        }
+        request(value)
+    }
+}
+
+/// The `proposal` response, the price of a contract.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Proposal {
+    pub id: String,
+    pub ask_price: Decimal,
+    pub payout: Decimal,
+}
+
+/// The `buy` response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuyReceipt {
+    pub contract_id: u64,
+    pub buy_price: Decimal,
+    pub payout: Decimal,
+    pub start_time: i64,
+}
+
+/// A bought contract, as reported by `proposal_open_contract`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Contract {
+    pub contract_id: u64,
+    pub underlying: String,
+    pub contract_type: String,
+    pub currency: String,
+    pub buy_price: Decimal,
+    pub payout: Decimal,
+    #[serde(default)]
+    pub profit: Decimal,
+    #[serde(default)]
+    pub entry_spot: Option<Decimal>,
+    #[serde(default)]
+    pub exit_tick: Option<Decimal>,
+    #[serde(default)]
+    pub date_start: i64,
+    #[serde(default)]
+    pub date_expiry: i64,
+    #[serde(with = "bool2int")]
+    pub is_sold: bool,
+    /// `open`, `won`, `lost` or `sold`.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl Contract {
+    /// The contract `receipt` bought, until `proposal_open_contract` reports it.
+    pub fn bought(request: &ContractRequest, receipt: &BuyReceipt) -> Self {
+        let duration = match request.duration_unit.as_str() {
+            "m" => i64::from(request.duration) * 60,
+            "h" => i64::from(request.duration) * 3600,
+            _ => i64::from(request.duration),
+        };
+        Self {
+            contract_id: receipt.contract_id,
+            underlying: request.symbol.clone(),
+            contract_type: request.contract_type.clone(),
+            currency: request.currency.clone(),
+            buy_price: receipt.buy_price,
+            payout: receipt.payout,
+            profit: Decimal::ZERO,
+            entry_spot: None,
+            exit_tick: None,
+            date_start: receipt.start_time,
+            date_expiry: receipt.start_time + duration,
+            is_sold: false,
+            status: Some("open".to_string()),
+        }
+    }
+
+    /// Id of the deal of the contract `contract_id`.
+    pub fn deal_id(contract_id: u64) -> Uuid {
+        Uuid::from_u128(u128::from(contract_id))
+    }
+
+    /// The contract as a [`Deal`], for the [`Market`](crate::framework::market::Market)
+    /// implementation. Open contracts have no close price.
+    pub fn to_deal(&self, is_demo: bool) -> Deal {
+        let timestamp = |epoch| DateTime::from_timestamp(epoch, 0).unwrap_or_default();
+        let percent_profit = if self.buy_price.is_zero() {
+            0
+        } else {
+            ((self.payout / self.buy_price - Decimal::ONE) * Decimal::ONE_HUNDRED)
+                .round()
+                .try_into()
+                .unwrap_or_default()
+        };
+        let close_price = if self.is_sold {
+            self.exit_tick.unwrap_or_default()
+        } else {
+            Decimal::ZERO
+        };
+        Deal {
+            id: Self::deal_id(self.contract_id),
+            open_time: String::new(),
+            close_time: String::new(),
+            open_timestamp: timestamp(self.date_start),
+            close_timestamp: timestamp(self.date_expiry),
+            refund_time: None,
+            refund_timestamp: None,
+            uid: 0,
+            request_id: Some(RequestId::Number(self.contract_id)),
+            amount: self.buy_price,
+            profit: self.profit,
+            percent_profit,
+            percent_loss: 100,
+            open_price: self.entry_spot.unwrap_or_default(),
+            close_price,
+            command: i32::from(self.contract_type == "PUT"),
+            asset: self.underlying.clone(),
+            is_demo: u32::from(is_demo),
+            copy_ticket: String::new(),
+            open_ms: 0,
+            close_ms: None,
+            option_type: 0,
+            is_rollover: None,
+            is_copy_signal: None,
+            is_ai: None,
+            currency: self.currency.clone(),
+            amount_usd: None,
+            amount_usd2: None,
+            slippage: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_responses() {
+        let rule = MsgTypeRule::new(&["proposal"]);
+        let msg = Message::text(
+            r#"{"echo_req":{"proposal":1},"msg_type":"proposal","proposal":{"id":"5b1b","ask_price":10,"payout":19.54,"spot":1234.5},"req_id":3}"#,
+        );
+        assert!(rule.call(&msg));
+        assert!(!rule.call(&Message::text(r#"{"msg_type":"proposal_open_contract"}"#)));
+        let response = Response::parse(&msg).unwrap();
+        assert_eq!(response.req_id, Some(3));
+        let proposal: Proposal = response.data().unwrap();
+        assert_eq!(
+            (proposal.id.as_str(), proposal.payout),
+            ("5b1b", dec!(19.54))
+        );
+
+        let error = Response::parse(&Message::text(
+            r#"{"error":{"code":"InvalidToken","message":"The token is invalid."},"msg_type":"authorize"}"#,
+        ))
+        .unwrap();
+        assert!(matches!(
+            error.data::<Account>(),
+            Err(DerivError::Api { code, .. }) if code == "InvalidToken"
+        ));
+
+        let contract: Contract = serde_json::from_str(
+            r#"{"contract_id":204,"underlying":"R_100","contract_type":"PUT","currency":"USD","buy_price":10,"payout":19.5,"profit":9.5,"entry_spot":"812.25","exit_tick":811.9,"date_start":1700000000,"date_expiry":1700000060,"is_sold":1,"status":"won"}"#,
+        )
+        .unwrap();
+        let deal = contract.to_deal(true);
+        assert_eq!(deal.id, Contract::deal_id(204));
+        assert_eq!(
+            (deal.command, deal.percent_profit, deal.is_demo),
+            (1, 95, 1)
+        );
+        assert_eq!(
+            (deal.open_price, deal.close_price),
+            (dec!(812.25), dec!(811.9))
+        );
+    }
+}
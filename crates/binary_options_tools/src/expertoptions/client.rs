@@ -6,18 +6,28 @@ use binary_options_tools_core::{
     error::CoreError,
     testing::{TestingWrapper, TestingWrapperBuilder},
 };
+use chrono::Utc;
 use tokio::task::JoinHandle;
 
 use crate::{
     expertoptions::{
         connect::ExpertConnect,
         error::{ExpertOptionsError, ExpertOptionsResult},
-        modules::{keep_alive::PongModule, profile::ProfileModule},
+        modules::{
+            candles::CandlesModule,
+            keep_alive::PongModule,
+            profile::ProfileModule,
+            subscriptions::{ExpertSubscription, SubscriptionsModule},
+        },
         state::State,
     },
+    pocketoption::candle::{Candle, SubscriptionType},
     utils::PrintMiddleware,
 };
 
+/// How long to wait for the server to answer a history request.
+const HISTORY_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Clone)]
 
 pub struct ExpertOptions {
@@ -33,7 +43,9 @@ impl ExpertOptions {
             .with_middleware(Box::new(PrintMiddleware))
             // .with_lightweight_handler(|msg, _, _| Box::pin(print_handler(msg)))
             .with_lightweight_module::<PongModule>()
-            .with_module::<ProfileModule>())
+            .with_module::<ProfileModule>()
+            .with_module::<SubscriptionsModule>()
+            .with_module::<CandlesModule>())
     }
 
     pub async fn new(token: impl ToString, demo: bool) -> ExpertOptionsResult<Self> {
@@ -64,6 +76,90 @@ impl ExpertOptions {
         self.client.state.is_demo().await
     }
 
+    /// Subscribes to the live prices of `asset`, aggregated into candles by `sub_type`.
+    ///
+    /// Dropping the subscription, or the stream made from it, unsubscribes.
+    pub async fn subscribe(
+        &self,
+        asset: impl ToString,
+        sub_type: SubscriptionType,
+    ) -> ExpertOptionsResult<ExpertSubscription> {
+        let asset = asset.to_string();
+        let asset_id = self.asset_id(&asset).await?;
+        if let Some(handle) = self.client.get_handle::<SubscriptionsModule>().await {
+            Ok(handle.subscribe(asset_id, asset, sub_type).await?)
+        } else {
+            Err(CoreError::ModuleNotFound("SubscriptionsModule".into()).into())
+        }
+    }
+
+    /// Gets the candles of `period` seconds of `asset` over the last `offset` seconds, oldest
+    /// first. The last candle is still forming if it ends after now.
+    pub async fn get_candles(
+        &self,
+        asset: impl ToString,
+        period: u32,
+        offset: i64,
+    ) -> ExpertOptionsResult<Vec<Candle>> {
+        let asset = asset.to_string();
+        let asset_id = self.asset_id(&asset).await?;
+        let timeframes = self
+            .client
+            .state
+            .get_candles_timeframes
+            .read()
+            .await
+            .clone();
+        // Not checked until the server has sent its timeframes
+        if !timeframes.is_empty() && !timeframes.contains(&period) {
+            return Err(ExpertOptionsError::InvalidTimeframe(period, timeframes));
+        }
+        let Some(handle) = self.client.get_handle::<CandlesModule>().await else {
+            return Err(CoreError::ModuleNotFound("CandlesModule".into()).into());
+        };
+        let now = Utc::now().timestamp();
+        let history = tokio::time::timeout(
+            HISTORY_TIMEOUT,
+            handle.history(asset_id, period, now - offset, now),
+        )
+        .await
+        .map_err(|_| CoreError::TimeoutError {
+            task: format!("candles of {asset}"),
+            duration: HISTORY_TIMEOUT,
+        })??;
+        history
+            .into_iter()
+            .map(|bar| {
+                let closed = bar.timestamp + i64::from(period) <= now;
+                let mut candle = Candle::try_from((bar, asset.clone()))
+                    .map_err(|e| ExpertOptionsError::General(e.to_string()))?;
+                candle.is_closed = closed;
+                Ok(candle)
+            })
+            .collect()
+    }
+
+    /// Gets the last 1000 candles of `period` seconds of `asset`.
+    pub async fn candles(
+        &self,
+        asset: impl ToString,
+        period: u32,
+    ) -> ExpertOptionsResult<Vec<Candle>> {
+        self.get_candles(asset, period, 1000 * i64::from(period))
+            .await
+    }
+
+    async fn asset_id(&self, asset: &str) -> ExpertOptionsResult<u32> {
+        if self.client.state.assets.read().await.is_none() {
+            return Err(ExpertOptionsError::General("Assets not loaded".into()));
+        }
+        self.client
+            .state
+            .asset_id(asset)
+            .await
+            .ok_or_else(|| ExpertOptionsError::InvalidAsset(asset.to_string()))
+    }
+
     /// Disconnects and reconnects the client.
     pub async fn reconnect(&self) -> ExpertOptionsResult<()> {
         self.client
@@ -10,6 +10,15 @@ pub enum ExpertOptionsError {
 
     #[error("Failed to join task: {0}")]
     Core(#[from] Box<CoreError>),
+
+    #[error("Invalid asset: {0}")]
+    InvalidAsset(String),
+
+    #[error("Invalid timeframe: {0}, the server only gives candles of {1:?} seconds")]
+    InvalidTimeframe(u32, Vec<u32>),
+
+    #[error("General error: {0}")]
+    General(String),
 }
 
 pub type ExpertOptionsResult<T> = Result<T, ExpertOptionsError>;
@@ -0,0 +1,320 @@
+//! Historical candles of ExpertOption assets.
+//!
+//! The layout of the candle messages isn't documented. [`parse_candles`] reads a `message`
+//! holding either one group of candles or a `candles` list of groups. A group has the asset
+//! id (`assetId`), the timeframe in seconds (`tf`, 0 for the points of the live feed) and its
+//! `periods`, each `[time, open, high, low, close]`, `[time, [open, high, low, close]]` or
+//! `[time, price]`. Entries of any other shape are skipped.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, Rule, RunnerCommand};
+use binary_options_tools_macros::ActionImpl;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+use crate::expertoptions::state::State;
+use crate::expertoptions::{Action, ActionName};
+use crate::pocketoption::candle::BaseCandle;
+
+/// First `ns` of the history requests, the startup actions use the lower ones.
+const FIRST_NS: u64 = 100;
+
+/// Candles of one asset and timeframe found in a message.
+#[derive(Debug, Clone)]
+pub struct CandleGroup {
+    pub asset_id: Option<u32>,
+    /// Seconds per candle, 0 for points.
+    pub timeframe: u32,
+    pub candles: Vec<BaseCandle>,
+}
+
+/// Reads the candle groups of the `message` of a candle action, see the
+/// [module documentation](self).
+pub fn parse_candles(message: &Value) -> Vec<CandleGroup> {
+    let groups: Vec<&Value> = match message.get("candles").and_then(Value::as_array) {
+        Some(groups) if !groups.is_empty() && groups.iter().all(Value::is_object) => {
+            groups.iter().collect()
+        }
+        _ => vec![message],
+    };
+    groups
+        .into_iter()
+        .filter_map(|group| {
+            let periods = group
+                .get("periods")
+                .or_else(|| group.get("candles"))
+                .and_then(Value::as_array)?;
+            Some(CandleGroup {
+                asset_id: field_u32(group, &["assetId", "assetid", "asset_id"]),
+                timeframe: field_u32(group, &["tf", "timeframe"]).unwrap_or(0),
+                candles: periods.iter().filter_map(parse_period).collect(),
+            })
+        })
+        .collect()
+}
+
+fn field_u32(group: &Value, keys: &[&str]) -> Option<u32> {
+    keys.iter()
+        .find_map(|key| group.get(key))
+        .and_then(Value::as_u64)
+        .and_then(|value| u32::try_from(value).ok())
+}
+
+fn parse_period(period: &Value) -> Option<BaseCandle> {
+    let items = period.as_array()?;
+    // Points come twice a second, their time is truncated to the second.
+    let timestamp = items.first()?.as_f64()?.floor() as i64;
+    let prices = match items.get(1)? {
+        Value::Array(prices) => prices,
+        _ => &items[1..],
+    };
+    let prices: Vec<f64> = prices.iter().map(Value::as_f64).collect::<Option<_>>()?;
+    match prices[..] {
+        [price] => Some(BaseCandle::from((timestamp, price))),
+        [open, high, low, close, ..] => {
+            Some(BaseCandle::new(timestamp, open, high, low, close, None))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ActionImpl)]
+#[action(name = "assetHistoryCandles")]
+pub struct AssetHistoryCandles {
+    #[serde(rename = "assetid")]
+    asset_id: u32,
+    /// `[from, to]` Unix timestamps.
+    periods: Vec<[i64; 2]>,
+    timeframes: Vec<u32>,
+}
+
+impl AssetHistoryCandles {
+    pub fn new(asset_id: u32, timeframe: u32, from: i64, to: i64) -> Self {
+        Self {
+            asset_id,
+            periods: vec![[from, to]],
+            timeframes: vec![timeframe],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Request {
+    History {
+        request: AssetHistoryCandles,
+        responder: oneshot::Sender<Vec<BaseCandle>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct CandlesHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl CandlesHandle {
+    /// Candles of `timeframe` seconds of the asset `asset_id` between the `from` and `to` Unix
+    /// timestamps, oldest first.
+    pub async fn history(
+        &self,
+        asset_id: u32,
+        timeframe: u32,
+        from: i64,
+        to: i64,
+    ) -> CoreResult<Vec<BaseCandle>> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::History {
+                request: AssetHistoryCandles::new(asset_id, timeframe, from, to),
+                responder,
+            })
+            .await?;
+        response.await.map_err(|_| {
+            CoreError::Other("CandlesModule stopped before answering the request".into())
+        })
+    }
+}
+
+/// A history request waiting for its answer.
+struct Pending {
+    ns: u64,
+    asset_id: u32,
+    timeframe: u32,
+    responder: oneshot::Sender<Vec<BaseCandle>>,
+}
+
+/// Candles module, answers `assetHistoryCandles` requests.
+///
+/// Answers are matched to requests by their `ns`, or by asset and timeframe when the server
+/// leaves it out.
+pub struct CandlesModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    state: Arc<State>,
+    pending: VecDeque<Pending>,
+    next_ns: u64,
+}
+
+impl CandlesModule {
+    async fn request(
+        &mut self,
+        request: AssetHistoryCandles,
+        responder: oneshot::Sender<Vec<BaseCandle>>,
+    ) -> CoreResult<()> {
+        let mut action = request
+            .action(self.state.token.clone())
+            .map_err(|e| CoreError::Other(e.to_string()))?;
+        let ns = self.next_ns;
+        self.next_ns += 1;
+        action.ns = Some(ns);
+        self.ws_sender.send(action.to_message()?).await?;
+        self.pending.push_back(Pending {
+            ns,
+            asset_id: request.asset_id,
+            timeframe: request.timeframes[0],
+            responder,
+        });
+        Ok(())
+    }
+
+    fn answer(&mut self, action: Action) {
+        // Requests whose caller gave up
+        self.pending
+            .retain(|pending| !pending.responder.is_closed());
+        let groups = parse_candles(&action.message);
+        let position = action
+            .ns
+            .and_then(|ns| self.pending.iter().position(|pending| pending.ns == ns))
+            .or_else(|| {
+                self.pending.iter().position(|pending| {
+                    groups.iter().any(|group| {
+                        group.asset_id.is_none_or(|id| id == pending.asset_id)
+                            && group.timeframe == pending.timeframe
+                    })
+                })
+            });
+        let Some(pending) = position.and_then(|position| self.pending.remove(position)) else {
+            debug!(target: "CandlesModule", "History candles without a pending request");
+            return;
+        };
+        let mut candles: Vec<BaseCandle> = groups
+            .into_iter()
+            .filter(|group| group.asset_id.is_none_or(|id| id == pending.asset_id))
+            .flat_map(|group| group.candles)
+            .collect();
+        candles.sort_by_key(|candle| candle.timestamp);
+        let _ = pending.responder.send(candles);
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for CandlesModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = CandlesHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            state: shared_state,
+            pending: VecDeque::new(),
+            next_ns: FIRST_NS,
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        CandlesHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Message::Binary(data) = msg.as_ref() {
+                                match serde_json::from_slice::<Action>(data) {
+                                    Ok(action) => self.answer(action),
+                                    Err(e) => warn!(target: "CandlesModule", "Failed to parse history candles: {e}"),
+                                }
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::History { request, responder }) => {
+                            self.request(request, responder).await?;
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(AssetHistoryCandlesRule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_candle_layouts() {
+        let live = json!({"candles": [
+            {"assetId": 240, "tf": 0, "periods": [[1700000000.5, 1.25], [1700000001, [1.26]]]},
+            {"assetId": 240, "tf": 5, "periods": [[1700000000, [1.2, 1.3, 1.1, 1.25]], "bad"]}
+        ]});
+        let groups = parse_candles(&live);
+        assert_eq!(groups.len(), 2);
+        assert_eq!((groups[0].asset_id, groups[0].timeframe), (Some(240), 0));
+        let points: Vec<(i64, f64)> = groups[0]
+            .candles
+            .iter()
+            .map(|c| (c.timestamp, c.close))
+            .collect();
+        assert_eq!(points, vec![(1_700_000_000, 1.25), (1_700_000_001, 1.26)]);
+        let bar = &groups[1].candles;
+        assert_eq!(bar.len(), 1);
+        assert_eq!(
+            (bar[0].open, bar[0].high, bar[0].low, bar[0].close),
+            (1.2, 1.3, 1.1, 1.25)
+        );
+
+        let history =
+            json!({"assetid": 7, "timeframe": 60, "candles": [[60, 1, 3, 0.5, 2], [120, 2]]});
+        let groups = parse_candles(&history);
+        assert_eq!((groups[0].asset_id, groups[0].timeframe), (Some(7), 60));
+        assert_eq!(groups[0].candles.len(), 2);
+        assert_eq!(groups[0].candles[0].high, 3.0);
+        assert!(parse_candles(&json!({"result": "ok"})).is_empty());
+    }
+}
@@ -1,7 +1,9 @@
 use uuid::Uuid;
 
+pub mod candles;
 pub mod keep_alive;
 pub mod profile;
+pub mod subscriptions;
 
 #[derive(Debug)]
 pub struct Command<T> {
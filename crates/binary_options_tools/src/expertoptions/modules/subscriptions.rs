@@ -0,0 +1,360 @@
+//! Live prices of ExpertOption assets.
+//!
+//! Assets subscribed with `subscribeCandles` get their points, timeframe 0, pushed in
+//! `candles` actions, read by [`parse_candles`]. Every [`ExpertSubscription`] of an asset
+//! receives its points and builds candles from them with a
+//! [`SubscriptionType`], as the PocketOption subscriptions do. The asset is unsubscribed when
+//! its last subscription is dropped, and subscribed again after a reconnection.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{bounded_async, AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, ReconnectCallback, Rule, RunnerCommand};
+use binary_options_tools_core::Rule;
+use binary_options_tools_macros::ActionImpl;
+use futures_util::stream::unfold;
+use serde::Serialize;
+use tokio::select;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::expertoptions::error::{ExpertOptionsError, ExpertOptionsResult};
+use crate::expertoptions::modules::candles::parse_candles;
+use crate::expertoptions::state::State;
+use crate::expertoptions::{Action, ActionName};
+use crate::pocketoption::candle::{BaseCandle, Candle, SubscriptionType};
+
+/// Timeframe of the points of the live feed.
+const POINTS_TIMEFRAME: u32 = 0;
+/// Points buffered per subscription, newer points are dropped while it is full.
+const SUBSCRIPTION_BUFFER: usize = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetTimeframes {
+    id: u32,
+    timeframes: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, ActionImpl)]
+#[action(name = "subscribeCandles")]
+pub struct SubscribeCandles {
+    assets: Vec<AssetTimeframes>,
+}
+
+#[derive(Debug, Clone, Serialize, ActionImpl)]
+#[action(name = "unsubscribeCandles")]
+pub struct UnsubscribeCandles {
+    assets: Vec<AssetTimeframes>,
+}
+
+fn points_of(asset_ids: impl IntoIterator<Item = u32>) -> Vec<AssetTimeframes> {
+    asset_ids
+        .into_iter()
+        .map(|id| AssetTimeframes {
+            id,
+            timeframes: vec![POINTS_TIMEFRAME],
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum Request {
+    Subscribe {
+        asset_id: u32,
+        id: Uuid,
+        sender: AsyncSender<BaseCandle>,
+    },
+    Unsubscribe {
+        asset_id: u32,
+        id: Uuid,
+    },
+}
+
+#[derive(Clone)]
+pub struct SubscriptionsHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl SubscriptionsHandle {
+    /// Subscribes to the asset `asset_id`, named `symbol` in the candles.
+    pub async fn subscribe(
+        &self,
+        asset_id: u32,
+        symbol: String,
+        sub_type: SubscriptionType,
+    ) -> CoreResult<ExpertSubscription> {
+        let (sender, receiver) = bounded_async(SUBSCRIPTION_BUFFER);
+        let id = Uuid::new_v4();
+        self.sender
+            .send(Request::Subscribe {
+                asset_id,
+                id,
+                sender,
+            })
+            .await?;
+        Ok(ExpertSubscription {
+            receiver,
+            sender: self.sender.clone(),
+            id,
+            asset_id,
+            symbol,
+            sub_type,
+        })
+    }
+}
+
+/// Candles of an asset, built from its live points.
+///
+/// Dropping it unsubscribes.
+pub struct ExpertSubscription {
+    receiver: AsyncReceiver<BaseCandle>,
+    sender: AsyncSender<Request>,
+    id: Uuid,
+    asset_id: u32,
+    symbol: String,
+    sub_type: SubscriptionType,
+}
+
+impl ExpertSubscription {
+    pub fn asset(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Receives the next candle.
+    pub async fn receive(&mut self) -> ExpertOptionsResult<Candle> {
+        loop {
+            let point = self.receiver.recv().await.map_err(CoreError::from)?;
+            let bar = self
+                .sub_type
+                .update(&point)
+                .map_err(|e| ExpertOptionsError::General(e.to_string()))?;
+            if let Some(bar) = bar {
+                let mut candle = Candle::try_from((bar, self.symbol.clone()))
+                    .map_err(|e| ExpertOptionsError::General(e.to_string()))?;
+                candle.is_closed = !matches!(self.sub_type, SubscriptionType::None);
+                return Ok(candle);
+            }
+        }
+    }
+
+    /// Converts the subscription into a stream, dropping the stream unsubscribes.
+    pub fn to_stream(
+        self,
+    ) -> impl futures_util::Stream<Item = ExpertOptionsResult<Candle>> + 'static {
+        Box::pin(unfold(self, |mut subscription| async move {
+            let result = subscription.receive().await;
+            Some((result, subscription))
+        }))
+    }
+}
+
+impl Drop for ExpertSubscription {
+    fn drop(&mut self) {
+        // The module also drops subscriptions whose receiver is gone, if this doesn't fit.
+        let _ = self.sender.try_send(Request::Unsubscribe {
+            asset_id: self.asset_id,
+            id: self.id,
+        });
+    }
+}
+
+/// Subscriptions module, routes the live points of the subscribed assets.
+pub struct SubscriptionsModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    state: Arc<State>,
+    subscribers: HashMap<u32, Vec<(Uuid, AsyncSender<BaseCandle>)>>,
+}
+
+impl SubscriptionsModule {
+    async fn subscribe(
+        &mut self,
+        asset_id: u32,
+        id: Uuid,
+        sender: AsyncSender<BaseCandle>,
+    ) -> CoreResult<()> {
+        let subscribers = self.subscribers.entry(asset_id).or_default();
+        subscribers.push((id, sender));
+        if subscribers.len() == 1 {
+            self.state
+                .active_subscriptions
+                .write()
+                .await
+                .insert(asset_id);
+            let action = SubscribeCandles {
+                assets: points_of([asset_id]),
+            };
+            self.send(action).await?;
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, asset_id: u32, id: Option<Uuid>) -> CoreResult<()> {
+        let Some(subscribers) = self.subscribers.get_mut(&asset_id) else {
+            return Ok(());
+        };
+        subscribers.retain(|(sub_id, sender)| Some(*sub_id) != id && !sender.is_disconnected());
+        if subscribers.is_empty() {
+            self.subscribers.remove(&asset_id);
+            self.state
+                .active_subscriptions
+                .write()
+                .await
+                .remove(&asset_id);
+            let action = UnsubscribeCandles {
+                assets: points_of([asset_id]),
+            };
+            self.send(action).await?;
+        }
+        Ok(())
+    }
+
+    async fn send(&self, action: impl ActionName) -> CoreResult<()> {
+        let msg = action
+            .action(self.state.token.clone())
+            .map_err(|e| CoreError::Other(e.to_string()))?
+            .to_message()?;
+        self.ws_sender.send(msg).await?;
+        Ok(())
+    }
+
+    async fn route(&mut self, action: Action) -> CoreResult<()> {
+        let mut emptied = Vec::new();
+        for group in parse_candles(&action.message) {
+            let Some(asset_id) = group.asset_id else {
+                continue;
+            };
+            if group.timeframe != POINTS_TIMEFRAME {
+                continue;
+            }
+            let Some(subscribers) = self.subscribers.get_mut(&asset_id) else {
+                continue;
+            };
+            for point in group.candles {
+                subscribers.retain(|(_, sender)| match sender.try_send(point.clone()) {
+                    Ok(true) => true,
+                    Ok(false) => {
+                        warn!(target: "SubscriptionsModule", "Subscription to asset {asset_id} is full, dropping a point");
+                        true
+                    }
+                    Err(_) => false,
+                });
+            }
+            if subscribers.is_empty() {
+                emptied.push(asset_id);
+            }
+        }
+        for asset_id in emptied {
+            self.unsubscribe(asset_id, None).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Subscribes again to the assets subscribed before the reconnection.
+struct SubscriptionCallback;
+
+#[async_trait::async_trait]
+impl ReconnectCallback<State> for SubscriptionCallback {
+    async fn call(&self, state: Arc<State>, ws_sender: &AsyncSender<Message>) -> CoreResult<()> {
+        let asset_ids = state.active_subscriptions.read().await.clone();
+        if asset_ids.is_empty() {
+            return Ok(());
+        }
+        let msg = SubscribeCandles {
+            assets: points_of(asset_ids),
+        }
+        .action(state.token.clone())
+        .map_err(|e| CoreError::Other(e.to_string()))?
+        .to_message()?;
+        ws_sender.send(msg).await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for SubscriptionsModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = SubscriptionsHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            state: shared_state,
+            subscribers: HashMap::new(),
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        SubscriptionsHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Message::Binary(data) = msg.as_ref() {
+                                match serde_json::from_slice::<Action>(data) {
+                                    Ok(action) => self.route(action).await?,
+                                    Err(e) => debug!(target: "SubscriptionsModule", "Failed to parse candles: {e}"),
+                                }
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::Subscribe { asset_id, id, sender }) => {
+                            self.subscribe(asset_id, id, sender).await?;
+                        }
+                        Ok(Request::Unsubscribe { asset_id, id }) => {
+                            self.unsubscribe(asset_id, Some(id)).await?;
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(CandlesRule::new())
+    }
+
+    fn callback(
+        _shared_state: Arc<State>,
+        _command_receiver: AsyncReceiver<Self::Command>,
+        _command_responder: AsyncSender<Self::CommandResponse>,
+        _message_receiver: AsyncReceiver<Arc<Message>>,
+        _to_ws_sender: AsyncSender<Message>,
+    ) -> CoreResult<Option<Box<dyn ReconnectCallback<State>>>> {
+        Ok(Some(Box::new(SubscriptionCallback)))
+    }
+}
+
+#[Rule]
+#[rule({ binary_starts_with(b"{{\"action\":\"candles\"") })]
+struct CandlesRule;
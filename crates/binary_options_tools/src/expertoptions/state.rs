@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use binary_options_tools_core::traits::AppState;
 use chrono::Local;
 use rust_decimal::{dec, Decimal};
@@ -32,6 +34,8 @@ pub struct State {
     pub points_timeframe: RwLock<Decimal>,
     /// Assets
     pub assets: RwLock<Option<Assets>>,
+    /// Ids of the assets with live subscriptions, subscribed again after a reconnection
+    pub active_subscriptions: RwLock<HashSet<u32>>,
 }
 
 impl Config {
@@ -68,6 +72,7 @@ impl State {
             get_candles_timeframes: RwLock::new(Vec::new()),
             assets: RwLock::new(None),
             points_timeframe: RwLock::new(dec!(0.5)), // Default to .5 seconds
+            active_subscriptions: RwLock::new(HashSet::new()),
         }
     }
 
@@ -110,6 +115,10 @@ impl State {
         }
     }
 
+    pub async fn asset_id(&self, asset: &str) -> Option<u32> {
+        self.assets.read().await.as_ref()?.id(asset)
+    }
+
     pub async fn get_points_timeframe(&self) -> Decimal {
         *self.points_timeframe.read().await
     }
@@ -0,0 +1,320 @@
+//! Replay of historical candles through a strategy on a [`VirtualMarket`].
+//!
+//! A [`Backtest`] drives a [`SimClock`] from the candles, so trades expire in candle time: each
+//! candle sets the clock to its start and the price of its asset to its close, settles the
+//! expired trades, then goes to [`Strategy::on_candle`]. A trade of 60 seconds opened on a
+//! minute candle is therefore settled at the close of the next one.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::clock::SimClock;
+use crate::framework::market::Market;
+use crate::framework::virtual_market::VirtualMarket;
+use crate::framework::{Context, Params, Strategy};
+use crate::pocketoption::candle::Candle;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::pocket_client::PocketOption;
+use crate::pocketoption::types::Deal;
+
+/// Candles to replay and the market they are traded on.
+///
+/// Clones share the candles.
+#[derive(Clone)]
+pub struct Backtest {
+    client: Arc<PocketOption>,
+    candles: Arc<[Candle]>,
+    initial_balance: Decimal,
+    payout: i32,
+}
+
+impl Backtest {
+    /// `client` is only given to the strategies through their [`Context`], it can be built
+    /// with `build_offline` as the backtest doesn't trade on it.
+    pub fn new(client: Arc<PocketOption>, mut candles: Vec<Candle>) -> Self {
+        candles.sort_by_key(|candle| candle.timestamp);
+        Self {
+            client,
+            candles: candles.into(),
+            initial_balance: dec!(1000),
+            payout: 80,
+        }
+    }
+
+    /// Balance of the virtual account at the start of every run, 1000 by default.
+    pub fn with_initial_balance(mut self, balance: Decimal) -> Self {
+        self.initial_balance = balance;
+        self
+    }
+
+    /// Payout of every asset in percent, 80 by default.
+    pub fn with_payout(mut self, payout: i32) -> Self {
+        self.payout = payout;
+        self
+    }
+
+    /// The candles, oldest first.
+    pub fn candles(&self) -> &[Candle] {
+        &self.candles
+    }
+
+    /// Replays all the candles, see [`run_range`](Self::run_range).
+    pub async fn run(
+        &self,
+        strategy: &dyn Strategy,
+        params: &Params,
+    ) -> PocketResult<BacktestResult> {
+        self.run_range(strategy, params, 0..self.candles.len())
+            .await
+    }
+
+    /// Replays the candles of `range` through `strategy`, on a new virtual account.
+    ///
+    /// `params` go to [`Strategy::on_params_update`] after `on_start` unless empty. Closed
+    /// trades are delivered to `on_deal_closed` followed by `on_balance_update`, before the
+    /// candle that settled them. Errors of `on_start` and `on_params_update` end the run, the
+    /// others are logged and counted like the [`Bot`](super::Bot) does.
+    pub async fn run_range(
+        &self,
+        strategy: &dyn Strategy,
+        params: &Params,
+        range: Range<usize>,
+    ) -> PocketResult<BacktestResult> {
+        let candles = self.candles.get(range.clone()).ok_or_else(|| {
+            PocketError::General(format!(
+                "Candles {:?} out of range, the backtest has {}",
+                range,
+                self.candles.len()
+            ))
+        })?;
+        let clock = SimClock::from_timestamp(candles.first().map_or(0, |c| c.timestamp));
+        let virtual_market = VirtualMarket::new(self.initial_balance).with_clock(clock.shared());
+        let assets: BTreeSet<&str> = candles.iter().map(|c| c.symbol.as_str()).collect();
+        for asset in assets {
+            virtual_market.set_payout(asset, self.payout).await;
+        }
+        let market = Arc::new(BacktestMarket::new(virtual_market));
+        let ctx = Context {
+            market: market.clone(),
+            client: self.client.clone(),
+            journal: None,
+        };
+
+        strategy.on_start(&ctx).await?;
+        if !params.is_empty() {
+            strategy.on_params_update(&ctx, params).await?;
+        }
+        let mut deals = Vec::new();
+        let mut errors = 0;
+        for candle in candles {
+            clock.set(DateTime::from_timestamp(candle.timestamp, 0).unwrap_or_default());
+            market
+                .inner
+                .update_price(&candle.symbol, candle.close)
+                .await;
+            let closed = market.settle().await;
+            for deal in &closed {
+                if let Err(e) = strategy.on_deal_closed(&ctx, deal).await {
+                    warn!(target: "Framework", "Strategy on_deal_closed error in backtest: {:?}", e);
+                    errors += 1;
+                }
+            }
+            if !closed.is_empty() {
+                let balance = market.balance().await;
+                if let Err(e) = strategy.on_balance_update(&ctx, balance).await {
+                    warn!(target: "Framework", "Strategy on_balance_update error in backtest: {:?}", e);
+                    errors += 1;
+                }
+            }
+            deals.extend(closed);
+            if let Err(e) = strategy.on_candle(&ctx, &candle.symbol, candle).await {
+                warn!(target: "Framework", "Strategy on_candle error in backtest for {}: {:?}", candle.symbol, e);
+                errors += 1;
+            }
+        }
+
+        let open = market.open().len();
+        Ok(BacktestResult {
+            metrics: BacktestMetrics::from_deals(&deals),
+            deals,
+            open,
+            final_balance: market.balance().await,
+            errors,
+        })
+    }
+}
+
+/// Outcome of a [`Backtest`] run.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestResult {
+    pub metrics: BacktestMetrics,
+    /// Closed trades, in the order they closed.
+    pub deals: Vec<Deal>,
+    /// Trades still open after the last candle, not counted in the metrics.
+    pub open: usize,
+    /// Balance after the last candle, without the stakes of the open trades.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub final_balance: Decimal,
+    /// Strategy callbacks that returned an error.
+    pub errors: u64,
+}
+
+/// Performance of the closed trades of one or several runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BacktestMetrics {
+    pub trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_profit: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub gross_profit: Decimal,
+    /// Sum of the losses, as a positive amount.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub gross_loss: Decimal,
+    /// Wins over decided trades (draws excluded), between 0 and 1.
+    pub win_rate: f64,
+    /// Largest fall of the profit from a previous high.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub max_drawdown: Decimal,
+}
+
+impl BacktestMetrics {
+    /// Metrics of `deals`, in the order they closed.
+    pub fn from_deals(deals: &[Deal]) -> Self {
+        let mut metrics = Self::default();
+        let mut peak = Decimal::ZERO;
+        for deal in deals {
+            metrics.trades += 1;
+            match deal.profit.cmp(&Decimal::ZERO) {
+                std::cmp::Ordering::Greater => {
+                    metrics.wins += 1;
+                    metrics.gross_profit += deal.profit;
+                }
+                std::cmp::Ordering::Less => {
+                    metrics.losses += 1;
+                    metrics.gross_loss -= deal.profit;
+                }
+                std::cmp::Ordering::Equal => metrics.draws += 1,
+            }
+            metrics.net_profit += deal.profit;
+            peak = peak.max(metrics.net_profit);
+            metrics.max_drawdown = metrics.max_drawdown.max(peak - metrics.net_profit);
+        }
+        metrics.update_win_rate();
+        metrics
+    }
+
+    /// Adds the trades of another run, e.g. the next fold of a walk-forward. The drawdown is
+    /// the largest of the two runs.
+    pub fn merge(&mut self, other: &Self) {
+        self.trades += other.trades;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.draws += other.draws;
+        self.net_profit += other.net_profit;
+        self.gross_profit += other.gross_profit;
+        self.gross_loss += other.gross_loss;
+        self.max_drawdown = self.max_drawdown.max(other.max_drawdown);
+        self.update_win_rate();
+    }
+
+    fn update_win_rate(&mut self) {
+        let decided = self.wins + self.losses;
+        self.win_rate = if decided == 0 {
+            0.0
+        } else {
+            self.wins as f64 / decided as f64
+        };
+    }
+}
+
+/// Market of a backtest: keeps the trades of the strategy until the backtest settles them,
+/// and the results of the settled ones so the strategy can still query them.
+struct BacktestMarket {
+    inner: VirtualMarket,
+    open: Mutex<Vec<Uuid>>,
+    closed: Mutex<HashMap<Uuid, Deal>>,
+    /// Trades closed since the last [`settle`](Self::settle).
+    unreported: Mutex<Vec<Deal>>,
+}
+
+impl BacktestMarket {
+    fn new(inner: VirtualMarket) -> Self {
+        Self {
+            inner,
+            open: Mutex::new(Vec::new()),
+            closed: Mutex::new(HashMap::new()),
+            unreported: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn open(&self) -> MutexGuard<'_, Vec<Uuid>> {
+        self.open.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn closed(&self) -> MutexGuard<'_, HashMap<Uuid, Deal>> {
+        self.closed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Closes the expired trades, returns the trades closed since the last call, including
+    /// those the strategy closed by asking for their result.
+    async fn settle(&self) -> Vec<Deal> {
+        let open = self.open().clone();
+        for id in open {
+            if let Err(e) = self.result(id).await {
+                warn!(target: "Framework", "Failed to settle backtest trade {}: {}", id, e);
+            }
+        }
+        std::mem::take(&mut *self.unreported.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn record(&self, opened: PocketResult<(Uuid, Deal)>) -> PocketResult<(Uuid, Deal)> {
+        if let Ok((id, _)) = &opened {
+            self.open().push(*id);
+        }
+        opened
+    }
+}
+
+#[async_trait]
+impl Market for BacktestMarket {
+    async fn buy(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        self.record(self.inner.buy(asset, amount, time).await)
+    }
+
+    async fn sell(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        self.record(self.inner.sell(asset, amount, time).await)
+    }
+
+    async fn balance(&self) -> Decimal {
+        self.inner.balance().await
+    }
+
+    async fn result(&self, trade_id: Uuid) -> PocketResult<Deal> {
+        if let Some(deal) = self.closed().get(&trade_id) {
+            return Ok(deal.clone());
+        }
+        // `VirtualMarket` forgets the trades once it returned their result.
+        let deal = self.inner.result(trade_id).await?;
+        if !deal.close_price.is_zero() {
+            self.open().retain(|id| *id != trade_id);
+            self.closed().insert(trade_id, deal.clone());
+            self.unreported
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(deal.clone());
+        }
+        Ok(deal)
+    }
+}
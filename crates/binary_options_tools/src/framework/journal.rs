@@ -0,0 +1,346 @@
+//! Journal of the trades of strategies, and their performance per strategy and signal.
+//!
+//! A [`TradeJournal`] given to [`Bot::with_journal`](super::Bot::with_journal) or
+//! [`Orchestrator::with_journal`](super::orchestrator::Orchestrator::with_journal) records
+//! every trade opened through the strategy's market with the name of the strategy, and its
+//! result once it closes. Strategies can tag a trade with the signal that triggered it using
+//! [`Context::tag_signal`](super::Context::tag_signal). The same journal works for live
+//! trading and for backtests on a `VirtualMarket`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::framework::market::Market;
+use crate::framework::settled;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::Deal;
+
+/// A trade recorded by a [`TradeJournal`].
+#[derive(Debug, Clone, Serialize)]
+pub struct JournalEntry {
+    pub trade_id: Uuid,
+    pub strategy: String,
+    pub signal: Option<String>,
+    pub asset: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
+    /// Payout offered when the trade was opened, in percent.
+    pub payout: i32,
+    pub opened_at: DateTime<Utc>,
+    /// Set once the trade closed.
+    pub closed_at: Option<DateTime<Utc>>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub profit: Option<Decimal>,
+}
+
+/// Results of the closed trades of a strategy, or of one of its signals.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Performance {
+    pub trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_profit: Decimal,
+    /// Wins over decided trades (draws excluded), between 0 and 1.
+    pub win_rate: f64,
+    /// Mean payout offered on the trades, in percent.
+    pub average_payout: f64,
+    #[serde(skip)]
+    payout_sum: i64,
+}
+
+impl Performance {
+    fn record(&mut self, entry: &JournalEntry, profit: Decimal) {
+        self.trades += 1;
+        match profit.cmp(&Decimal::ZERO) {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Less => self.losses += 1,
+            std::cmp::Ordering::Equal => self.draws += 1,
+        }
+        self.net_profit += profit;
+        let decided = self.wins + self.losses;
+        self.win_rate = if decided == 0 {
+            0.0
+        } else {
+            self.wins as f64 / decided as f64
+        };
+        self.payout_sum += i64::from(entry.payout);
+        self.average_payout = self.payout_sum as f64 / self.trades as f64;
+    }
+}
+
+/// Performance of the closed trades of a journal, see [`TradeJournal::report`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttributionReport {
+    pub strategies: BTreeMap<String, Performance>,
+    /// Performance of the tagged signals, by strategy then signal.
+    pub signals: BTreeMap<String, BTreeMap<String, Performance>>,
+    /// Trades still open, not counted in the performance.
+    pub open: usize,
+}
+
+impl AttributionReport {
+    pub fn to_json(&self) -> PocketResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PocketError::General(format!("Failed to encode the report: {e}")))
+    }
+
+    /// One row per strategy, with an empty `signal`, followed by one row per signal.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "strategy,signal,trades,wins,losses,draws,net_profit,win_rate,average_payout\n",
+        );
+        for (strategy, performance) in &self.strategies {
+            csv_row(&mut csv, strategy, "", performance);
+            for (signal, performance) in self.signals.get(strategy).into_iter().flatten() {
+                csv_row(&mut csv, strategy, signal, performance);
+            }
+        }
+        csv
+    }
+}
+
+fn csv_row(csv: &mut String, strategy: &str, signal: &str, p: &Performance) {
+    let _ = writeln!(
+        csv,
+        "{},{},{},{},{},{},{},{:.4},{:.2}",
+        csv_field(strategy),
+        csv_field(signal),
+        p.trades,
+        p.wins,
+        p.losses,
+        p.draws,
+        p.net_profit.normalize(),
+        p.win_rate,
+        p.average_payout
+    );
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Trades of the strategies with their results, see the [module documentation](self).
+///
+/// Clones share the same journal.
+#[derive(Debug, Clone, Default)]
+pub struct TradeJournal {
+    entries: Arc<Mutex<HashMap<Uuid, JournalEntry>>>,
+}
+
+impl TradeJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries_mut(&self) -> MutexGuard<'_, HashMap<Uuid, JournalEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records a trade opened by `strategy`.
+    pub fn open(&self, trade_id: Uuid, strategy: &str, deal: &Deal) {
+        self.entries_mut().insert(
+            trade_id,
+            JournalEntry {
+                trade_id,
+                strategy: strategy.to_string(),
+                signal: None,
+                asset: deal.asset.clone(),
+                amount: deal.amount,
+                payout: deal.percent_profit,
+                opened_at: deal.open_timestamp,
+                closed_at: None,
+                profit: None,
+            },
+        );
+    }
+
+    /// Tags a recorded trade with the signal that triggered it, returns `false` if the trade
+    /// is not in the journal.
+    pub fn tag_signal(&self, trade_id: Uuid, signal: impl Into<String>) -> bool {
+        match self.entries_mut().get_mut(&trade_id) {
+            Some(entry) => {
+                entry.signal = Some(signal.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records the result of a closed trade, returns `false` if the trade is not in the
+    /// journal.
+    pub fn close(&self, trade_id: Uuid, deal: &Deal) -> bool {
+        match self.entries_mut().get_mut(&trade_id) {
+            Some(entry) => {
+                entry.closed_at = Some(deal.close_timestamp);
+                entry.profit = Some(deal.profit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The recorded trades, oldest first.
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        let mut entries: Vec<_> = self.entries_mut().values().cloned().collect();
+        entries.sort_by_key(|entry| entry.opened_at);
+        entries
+    }
+
+    /// Performance of the closed trades per strategy and signal.
+    pub fn report(&self) -> AttributionReport {
+        let mut report = AttributionReport::default();
+        for entry in self.entries() {
+            let Some(profit) = entry.profit else {
+                report.open += 1;
+                continue;
+            };
+            report
+                .strategies
+                .entry(entry.strategy.clone())
+                .or_default()
+                .record(&entry, profit);
+            if let Some(signal) = &entry.signal {
+                report
+                    .signals
+                    .entry(entry.strategy.clone())
+                    .or_default()
+                    .entry(signal.clone())
+                    .or_default()
+                    .record(&entry, profit);
+            }
+        }
+        report
+    }
+}
+
+/// Market of a [`Bot`](super::Bot) with a journal: records the trades of its strategy.
+pub(crate) struct JournaledMarket {
+    strategy: String,
+    inner: Arc<dyn Market>,
+    journal: TradeJournal,
+}
+
+impl JournaledMarket {
+    pub(crate) fn new(strategy: String, inner: Arc<dyn Market>, journal: TradeJournal) -> Self {
+        Self {
+            strategy,
+            inner,
+            journal,
+        }
+    }
+
+    fn record(&self, opened: &(Uuid, Deal)) {
+        let (id, deal) = opened;
+        self.journal.open(*id, &self.strategy, deal);
+        let (id, inner, journal) = (*id, self.inner.clone(), self.journal.clone());
+        tokio::spawn(async move {
+            match settled(inner.as_ref(), id).await {
+                Ok(deal) => {
+                    journal.close(id, &deal);
+                }
+                Err(e) => {
+                    warn!(target: "Framework", "Failed to get the result of trade {}: {}", id, e)
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl Market for JournaledMarket {
+    async fn buy(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        let opened = self.inner.buy(asset, amount, time).await?;
+        self.record(&opened);
+        Ok(opened)
+    }
+
+    async fn sell(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        let opened = self.inner.sell(asset, amount, time).await?;
+        self.record(&opened);
+        Ok(opened)
+    }
+
+    async fn balance(&self) -> Decimal {
+        self.inner.balance().await
+    }
+
+    async fn result(&self, trade_id: Uuid) -> PocketResult<Deal> {
+        self.inner.result(trade_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SimClock;
+    use crate::framework::virtual_market::VirtualMarket;
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_backtest_trades_are_attributed() {
+        let clock = SimClock::from_timestamp(1_700_000_000);
+        let virtual_market = VirtualMarket::new(dec!(100)).with_clock(clock.shared());
+        virtual_market.update_price("EURUSD_otc", dec!(1.0)).await;
+        virtual_market.set_payout("EURUSD_otc", 90).await;
+        let virtual_market = Arc::new(virtual_market);
+        let journal = TradeJournal::new();
+        let rsi = JournaledMarket::new("rsi".into(), virtual_market.clone(), journal.clone());
+        let macd =
+            JournaledMarket::new("macd, fast".into(), virtual_market.clone(), journal.clone());
+
+        let (win, _) = rsi.buy("EURUSD_otc", dec!(10), 60).await.unwrap();
+        assert!(journal.tag_signal(win, "oversold"));
+        let (loss, _) = rsi.sell("EURUSD_otc", dec!(10), 60).await.unwrap();
+        macd.buy("EURUSD_otc", dec!(5), 60).await.unwrap();
+        rsi.buy("EURUSD_otc", dec!(1), 600).await.unwrap();
+        clock.advance(Duration::from_secs(61));
+        virtual_market.update_price("EURUSD_otc", dec!(1.1)).await;
+
+        let report = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let report = journal.report();
+                if report.open == 1 {
+                    break report;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the trades were not settled");
+
+        let rsi_stats = &report.strategies["rsi"];
+        assert_eq!(
+            (rsi_stats.trades, rsi_stats.wins, rsi_stats.losses),
+            (2, 1, 1)
+        );
+        assert_eq!(rsi_stats.net_profit, dec!(-1));
+        assert_eq!(rsi_stats.win_rate, 0.5);
+        assert_eq!(rsi_stats.average_payout, 90.0);
+        assert_eq!(report.signals["rsi"]["oversold"].net_profit, dec!(9));
+        assert_eq!(report.strategies["macd, fast"].net_profit, dec!(4.5));
+        assert!(journal.entries().iter().any(|e| e.trade_id == loss));
+
+        let csv = report.to_csv();
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], "\"macd, fast\",,1,1,0,0,4.5,1.0000,90.00");
+        assert_eq!(rows[3], "rsi,oversold,1,1,0,0,9,1.0000,90.00");
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["strategies"]["rsi"]["trades"], 2);
+    }
+}
@@ -1,24 +1,40 @@
+pub mod backtest;
+pub mod journal;
 pub mod market;
+pub mod monte_carlo;
+pub mod optimizer;
+pub mod orchestrator;
 pub mod virtual_market;
 
+use crate::framework::journal::{JournaledMarket, TradeJournal};
 use crate::framework::market::Market;
 use crate::pocketoption::candle::{Candle, SubscriptionType};
 use crate::pocketoption::error::PocketResult;
+use crate::pocketoption::modules::subscriptions::StreamUpdate;
 use crate::pocketoption::pocket_client::PocketOption;
 use crate::pocketoption::types::Deal;
 use async_trait::async_trait;
-use futures_util::stream::select_all;
+use futures_util::stream::{select_all, BoxStream};
 use futures_util::StreamExt;
 use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
 use tracing::{error, info, warn};
+use uuid::Uuid;
 
 /// The Context provides strategies with access to the trading market and other utilities.
 #[derive(Clone)]
 pub struct Context {
     pub market: Arc<dyn Market>,
     pub client: Arc<PocketOption>,
+    /// Journal recording the trades of the strategy, see [`Bot::with_journal`].
+    pub journal: Option<TradeJournal>,
 }
 
 impl Context {
@@ -26,11 +42,36 @@ impl Context {
         Self {
             market: client.clone(),
             client,
+            journal: None,
+        }
+    }
+
+    /// Tags a trade opened through `market` with the signal that triggered it, for the
+    /// per-signal breakdown of the journal. Does nothing without a journal.
+    pub fn tag_signal(&self, trade_id: Uuid, signal: impl Into<String>) {
+        if let Some(journal) = &self.journal {
+            journal.tag_signal(trade_id, signal);
         }
     }
 }
 
 /// The Strategy trait defines the interface for trading strategies.
+///
+/// # Delivery
+///
+/// A [`Bot`] calls the callbacks one at a time from its run loop, never concurrently, so a
+/// callback sees the effects of the previous ones. Events are delivered in the order they
+/// arrive; when several are ready at once, deal events come before candles and balance
+/// updates, so a strategy sees `on_deal_closed` before the next `on_candle`. A closed deal
+/// is followed by `on_balance_update` if it changed the balance, which is otherwise polled
+/// every update interval (see [`Bot::with_update_interval`]).
+///
+/// An [`Orchestrator`](orchestrator::Orchestrator) calls its bots the same way, one bot after
+/// the other, and sends the deal events and balance updates to every bot.
+///
+/// Deal events come from the client and cover every deal of the account, including deals
+/// opened elsewhere; they are still delivered while the bot is paused. Custom markets such
+/// as `VirtualMarket` don't produce them.
 #[async_trait]
 pub trait Strategy: Send + Sync {
     /// Called when the bot starts.
@@ -43,17 +84,19 @@ pub trait Strategy: Send + Sync {
         Ok(())
     }
 
-    /// Called when a new tick (price update) is received.
+    /// Called on every raw price update of the assets added with [`Bot::enable_ticks`] or
+    /// [`Orchestrator::enable_ticks`](orchestrator::Orchestrator::enable_ticks), before the
+    /// candle the update completes, if any.
     async fn on_tick(&self, _ctx: &Context, _asset: &str, _price: Decimal) -> PocketResult<()> {
         Ok(())
     }
 
-    /// Called when a new deal is opened.
+    /// Called once when a deal is opened.
     async fn on_deal_opened(&self, _ctx: &Context, _deal: &Deal) -> PocketResult<()> {
         Ok(())
     }
 
-    /// Called when a new deal is closed
+    /// Called once when a deal is closed, with its result.
     async fn on_deal_closed(&self, _ctx: &Context, _deal: &Deal) -> PocketResult<()> {
         Ok(())
     }
@@ -62,6 +105,154 @@ pub trait Strategy: Send + Sync {
     async fn on_balance_update(&self, _ctx: &Context, _balance: Decimal) -> PocketResult<()> {
         Ok(())
     }
+
+    /// Called with all the parameters when one is set with [`BotHandle::set_param`], and once
+    /// after `on_start` if some were set before the bot started.
+    async fn on_params_update(&self, _ctx: &Context, _params: &Params) -> PocketResult<()> {
+        Ok(())
+    }
+}
+
+/// Strategy parameters tunable at runtime, e.g. an RSI threshold.
+pub type Params = HashMap<String, Value>;
+
+/// Returns the deal of a deal event, logging the events missed by a slow strategy.
+fn deal_event(event: Result<Deal, RecvError>) -> Option<Deal> {
+    match event {
+        Ok(deal) => Some(deal),
+        Err(RecvError::Lagged(missed)) => {
+            warn!(target: "Framework", "Strategy is too slow, missed {} deal events", missed);
+            None
+        }
+        Err(RecvError::Closed) => None,
+    }
+}
+
+/// Waits until the trade `id` of `market` closed and returns its deal.
+pub(crate) async fn settled(market: &dyn Market, id: Uuid) -> PocketResult<Deal> {
+    loop {
+        match market.result(id).await? {
+            // Markets such as `VirtualMarket` return open deals without a close price.
+            deal if deal.close_price.is_zero() => {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            deal => return Ok(deal),
+        }
+    }
+}
+
+/// Resolves once the bot is asked to stop.
+///
+/// Used in the run loops instead of `wait_for` directly, whose watch guard would otherwise be
+/// kept alive across the other `select!` branches and make the run future `!Send`.
+async fn stopping(status: &mut watch::Receiver<BotStatus>) {
+    let _ = status.wait_for(|s| *s == BotStatus::Stopping).await;
+}
+
+/// Lifecycle of a [`Bot`], see [`BotHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotStatus {
+    /// `run()` was not called yet.
+    Idle,
+    Running,
+    /// Subscriptions are kept but candles and balance updates are not sent to the strategy.
+    Paused,
+    /// `stop()` was called, the bot is unsubscribing.
+    Stopping,
+    Stopped,
+}
+
+/// Controls a [`Bot`] from outside its run loop, e.g. from a dashboard.
+#[derive(Clone)]
+pub struct BotHandle {
+    status: Arc<watch::Sender<BotStatus>>,
+    params: Arc<watch::Sender<Params>>,
+}
+
+impl Default for BotHandle {
+    fn default() -> Self {
+        Self {
+            status: Arc::new(watch::channel(BotStatus::Idle).0),
+            params: Arc::new(watch::channel(Params::new()).0),
+        }
+    }
+}
+
+impl BotHandle {
+    pub fn status(&self) -> BotStatus {
+        *self.status.borrow()
+    }
+
+    /// Pauses a running bot, returns `false` if it is not running.
+    pub fn pause(&self) -> bool {
+        self.transition(BotStatus::Running, BotStatus::Paused)
+    }
+
+    /// Resumes a paused bot, returns `false` if it is not paused.
+    pub fn resume(&self) -> bool {
+        self.transition(BotStatus::Paused, BotStatus::Running)
+    }
+
+    /// Stops the bot, which unsubscribes from its assets and returns from `run()`.
+    ///
+    /// A bot stopped before `run()` returns from it right away.
+    pub fn stop(&self) {
+        self.status.send_if_modified(|status| match status {
+            BotStatus::Stopping | BotStatus::Stopped => false,
+            _ => {
+                *status = BotStatus::Stopping;
+                true
+            }
+        });
+    }
+
+    /// Sets a strategy parameter, the strategy gets it through `Strategy::on_params_update`
+    /// without restarting the bot.
+    pub fn set_param(&self, key: impl Into<String>, value: impl Into<Value>) {
+        let (key, value) = (key.into(), value.into());
+        self.params.send_if_modified(|params| {
+            if params.get(&key) == Some(&value) {
+                false
+            } else {
+                params.insert(key, value);
+                true
+            }
+        });
+    }
+
+    pub fn param(&self, key: &str) -> Option<Value> {
+        self.params.borrow().get(key).cloned()
+    }
+
+    pub fn params(&self) -> Params {
+        self.params.borrow().clone()
+    }
+
+    /// Waits until the bot has stopped.
+    pub async fn stopped(&self) {
+        let mut receiver = self.status.subscribe();
+        let _ = receiver.wait_for(|s| *s == BotStatus::Stopped).await;
+    }
+
+    fn transition(&self, from: BotStatus, to: BotStatus) -> bool {
+        self.status.send_if_modified(|status| {
+            if *status == from {
+                *status = to;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    fn set(&self, status: BotStatus) {
+        self.status.send_replace(status);
+    }
+
+    fn is_paused(&self) -> bool {
+        self.status() == BotStatus::Paused
+    }
 }
 
 /// The Bot manages the execution of a strategy.
@@ -69,8 +260,9 @@ pub struct Bot {
     ctx: Context,
     strategy: Arc<Box<dyn Strategy>>,
     assets: Vec<(String, SubscriptionType)>,
-    background_tasks: Vec<tokio::task::JoinHandle<()>>,
-    update_time: Duration, // Each how much time the task is called
+    ticks: HashSet<String>,
+    update_time: Duration, // Each how much time the balance is polled
+    handle: BotHandle,
 }
 
 impl Bot {
@@ -79,11 +271,22 @@ impl Bot {
             ctx: Context::new(Arc::new(client)),
             strategy: Arc::new(strategy),
             assets: Vec::new(),
-            background_tasks: Vec::new(),
+            ticks: HashSet::new(),
             update_time: Duration::from_secs(5), // Default to 5 seconds
+            handle: BotHandle::default(),
         }
     }
 
+    /// Returns a handle to pause, resume or stop the bot while it runs.
+    pub fn handle(&self) -> BotHandle {
+        self.handle.clone()
+    }
+
+    /// Sets a strategy parameter, see [`BotHandle::set_param`].
+    pub fn set_param(&self, key: impl Into<String>, value: impl Into<Value>) {
+        self.handle.set_param(key, value);
+    }
+
     pub fn with_update_interval(&mut self, duration: Duration) {
         self.update_time = duration;
     }
@@ -94,18 +297,54 @@ impl Bot {
         self
     }
 
+    /// Records the trades of the strategy in `journal` under the name `strategy`.
+    ///
+    /// Must be called after [`Bot::with_market`].
+    pub fn with_journal(mut self, journal: TradeJournal, strategy: impl Into<String>) -> Self {
+        self.ctx.market = Arc::new(JournaledMarket::new(
+            strategy.into(),
+            self.ctx.market.clone(),
+            journal.clone(),
+        ));
+        self.ctx.journal = Some(journal);
+        self
+    }
+
     /// Adds an asset to monitor with a specific subscription type.
     pub fn add_asset(&mut self, asset: impl Into<String>, sub_type: SubscriptionType) {
         self.assets.push((asset.into(), sub_type));
     }
 
+    /// Delivers every raw price update of `asset` to [`Strategy::on_tick`], on top of its
+    /// candles. The asset still has to be added with [`Bot::add_asset`].
+    pub fn enable_ticks(&mut self, asset: impl Into<String>) {
+        self.ticks.insert(asset.into());
+    }
+
     /// Starts the bot and its strategy loop.
     pub async fn run(&mut self) -> PocketResult<()> {
+        if self.handle.status() == BotStatus::Stopping {
+            self.handle.set(BotStatus::Stopped);
+            return Ok(());
+        }
         info!("Starting bot...");
+        self.handle.set(BotStatus::Running);
+        let result = self.run_strategy().await;
+        self.shutdown().await;
+        result
+    }
+
+    async fn run_strategy(&mut self) -> PocketResult<()> {
+        let mut params = self.handle.params.subscribe();
+        let mut opened = self.ctx.client.opened_deal_events();
+        let mut closed = self.ctx.client.closed_deal_events();
         self.strategy.on_start(&self.ctx).await?;
-        self.spawn_balance_task();
+        let initial = params.borrow_and_update().clone();
+        if !initial.is_empty() {
+            self.update_params(&initial).await;
+        }
 
-        let mut streams = Vec::new();
+        let mut streams: Vec<BoxStream<'static, (String, PocketResult<StreamUpdate>)>> = Vec::new();
 
         for (asset, sub_type) in &self.assets {
             info!("Subscribing to {}...", asset);
@@ -114,10 +353,22 @@ impl Bot {
                 .client
                 .subscribe(asset.clone(), sub_type.clone())
                 .await?;
-            streams.push(stream.to_stream().map({
-                let asset = asset.clone();
-                move |res| (asset.clone(), res)
-            }));
+            let asset = asset.clone();
+            if self.ticks.contains(&asset) {
+                streams.push(
+                    stream
+                        .to_update_stream()
+                        .map(move |res| (asset.clone(), res))
+                        .boxed(),
+                );
+            } else {
+                streams.push(
+                    stream
+                        .to_stream()
+                        .map(move |res| (asset.clone(), res.map(StreamUpdate::Candle)))
+                        .boxed(),
+                );
+            }
         }
 
         if streams.is_empty() {
@@ -126,49 +377,336 @@ impl Bot {
         }
 
         let mut combined_stream = select_all(streams);
+        let mut status = self.handle.status.subscribe();
+        let mut balance_timer = tokio::time::interval(self.update_time);
+        balance_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_balance = Decimal::ZERO;
 
         info!("Bot is now running.");
-        while let Some((asset, result)) = combined_stream.next().await {
-            match result {
-                Ok(candle) => {
-                    if let Err(e) = self.strategy.on_candle(&self.ctx, &asset, &candle).await {
-                        warn!(target: "Framework", "Strategy on_candle error for {}: {:?}", asset, e);
+        loop {
+            // Biased so that simultaneous events are delivered in the documented order.
+            tokio::select! {
+                biased;
+                _ = stopping(&mut status) => {
+                    info!("Stopping bot...");
+                    break;
+                }
+                Ok(()) = params.changed() => {
+                    let updated = params.borrow_and_update().clone();
+                    self.update_params(&updated).await;
+                }
+                deal = opened.recv() => {
+                    if let Some(deal) = deal_event(deal) {
+                        if let Err(e) = self.strategy.on_deal_opened(&self.ctx, &deal).await {
+                            warn!(target: "Framework", "Strategy on_deal_opened error for {}: {:?}", deal.id, e);
+                        }
                     }
                 }
-                Err(e) => {
-                    error!("Stream error for {}: {:?}", asset, e);
+                deal = closed.recv() => {
+                    if let Some(deal) = deal_event(deal) {
+                        if let Err(e) = self.strategy.on_deal_closed(&self.ctx, &deal).await {
+                            warn!(target: "Framework", "Strategy on_deal_closed error for {}: {:?}", deal.id, e);
+                        }
+                        self.update_balance(&mut last_balance).await;
+                    }
                 }
+                item = combined_stream.next() => {
+                    let Some((asset, result)) = item else {
+                        break;
+                    };
+                    if self.handle.is_paused() {
+                        continue;
+                    }
+                    match result {
+                        Ok(StreamUpdate::Candle(candle)) => {
+                            if let Err(e) = self.strategy.on_candle(&self.ctx, &asset, &candle).await {
+                                warn!(target: "Framework", "Strategy on_candle error for {}: {:?}", asset, e);
+                            }
+                        }
+                        Ok(StreamUpdate::Tick { price, .. }) => {
+                            if let Err(e) = self.strategy.on_tick(&self.ctx, &asset, price).await {
+                                warn!(target: "Framework", "Strategy on_tick error for {}: {:?}", asset, e);
+                            }
+                        }
+                        Err(e) => {
+                            error!("Stream error for {}: {:?}", asset, e);
+                        }
+                    }
+                }
+                _ = balance_timer.tick() => self.update_balance(&mut last_balance).await,
             }
         }
 
         Ok(())
     }
 
-    fn spawn_balance_task(&mut self) {
-        info!(
-            "Spawning balance update task with interval of {:?}...",
-            self.update_time
-        );
-        let ctx = self.ctx.clone();
-        let strategy = self.strategy.clone();
-        let time = self.update_time;
-        let mut last_balance = Decimal::ZERO;
-        let task = tokio::spawn(async move {
-            loop {
-                let balance = ctx.market.balance().await;
-                if balance != last_balance {
-                    info!("Balance updated: {}", balance);
-                    last_balance = balance;
-                    if let Err(e) = strategy.on_balance_update(&ctx, balance).await {
-                        warn!(
-                            "Strategy on_balance_update error sharing balance {}: {:?}",
-                            balance, e
-                        );
-                    }
+    /// Sends the balance to the strategy if it changed since `last_balance`.
+    async fn update_balance(&self, last_balance: &mut Decimal) {
+        if self.handle.is_paused() {
+            return;
+        }
+        let balance = self.ctx.market.balance().await;
+        if balance == *last_balance {
+            return;
+        }
+        info!("Balance updated: {}", balance);
+        *last_balance = balance;
+        if let Err(e) = self.strategy.on_balance_update(&self.ctx, balance).await {
+            warn!(
+                "Strategy on_balance_update error sharing balance {}: {:?}",
+                balance, e
+            );
+        }
+    }
+
+    async fn update_params(&self, params: &Params) {
+        info!(target: "Framework", "Strategy parameters updated: {:?}", params);
+        if let Err(e) = self.strategy.on_params_update(&self.ctx, params).await {
+            warn!(target: "Framework", "Strategy on_params_update error: {:?}", e);
+        }
+    }
+
+    /// Unsubscribes from the assets when the bot was stopped.
+    async fn shutdown(&mut self) {
+        if self.handle.status() == BotStatus::Stopping {
+            for (asset, _) in &self.assets {
+                if let Err(e) = self.ctx.client.unsubscribe(asset).await {
+                    warn!(target: "Framework", "Failed to unsubscribe from {}: {:?}", asset, e);
                 }
-                tokio::time::sleep(time).await;
             }
+        }
+        self.handle.set(BotStatus::Stopped);
+        info!("Bot stopped.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::pocketoption::fixtures::{self, MOCK_SSID};
+    use crate::pocketoption::types::{Action, OpenOrder};
+    use binary_options_tools_core::reimports::Message;
+    use binary_options_tools_core::testing::MockConnector;
+    use rust_decimal_macros::dec;
+    use tokio::sync::{mpsc, Semaphore};
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Reports the callbacks it gets, `on_tick` waits for a permit of `gate`.
+    struct Recorder {
+        events: mpsc::UnboundedSender<String>,
+        gate: Arc<Semaphore>,
+    }
+
+    #[async_trait]
+    impl Strategy for Recorder {
+        async fn on_candle(&self, _: &Context, asset: &str, candle: &Candle) -> PocketResult<()> {
+            let _ = self.events.send(format!("candle {asset} {}", candle.close));
+            Ok(())
+        }
+
+        async fn on_tick(&self, _: &Context, asset: &str, price: Decimal) -> PocketResult<()> {
+            let _ = self.events.send(format!("tick {asset} {price}"));
+            let _ = self.gate.acquire().await;
+            Ok(())
+        }
+
+        async fn on_deal_closed(&self, _: &Context, deal: &Deal) -> PocketResult<()> {
+            let _ = self.events.send(format!("closed {}", deal.asset));
+            Ok(())
+        }
+    }
+
+    async fn recorded_bot(
+        server: &MockConnector,
+        gate: Arc<Semaphore>,
+    ) -> (Bot, mpsc::UnboundedReceiver<String>) {
+        let config = Config {
+            connection_initialization_timeout: TIMEOUT,
+            ..Config::default()
+        };
+        let client = PocketOption::new_with_connector(MOCK_SSID, server.clone(), config)
+            .await
+            .unwrap();
+        client.wait_for_assets(TIMEOUT).await.unwrap();
+        let (events, received) = mpsc::unbounded_channel();
+        let bot = Bot::new(client, Box::new(Recorder { events, gate }));
+        (bot, received)
+    }
+
+    async fn next_event(events: &mut mpsc::UnboundedReceiver<String>) -> String {
+        tokio::time::timeout(TIMEOUT, events.recv())
+            .await
+            .expect("no strategy callback")
+            .unwrap()
+    }
+
+    fn push(server: &MockConnector, frames: impl IntoIterator<Item = Message>) {
+        for frame in frames {
+            assert!(server.push(frame));
+        }
+    }
+
+    async fn subscribed(server: &MockConnector, asset: &str) {
+        server
+            .wait_for(TIMEOUT, |msg| {
+                msg.to_text()
+                    .is_ok_and(|text| text.contains("changeSymbol") && text.contains(asset))
+            })
+            .await
+            .expect("the bot did not subscribe");
+    }
+
+    #[tokio::test]
+    async fn test_bot_handle_transitions() {
+        let handle = BotHandle::default();
+        assert_eq!(handle.status(), BotStatus::Idle);
+        assert!(!handle.pause());
+
+        handle.set(BotStatus::Running);
+        assert!(handle.pause());
+        assert!(handle.is_paused());
+        assert!(!handle.pause());
+        assert!(handle.resume());
+        assert_eq!(handle.status(), BotStatus::Running);
+
+        handle.stop();
+        assert_eq!(handle.status(), BotStatus::Stopping);
+        assert!(!handle.resume());
+
+        let waiter = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.stopped().await }
         });
-        self.background_tasks.push(task);
+        handle.set(BotStatus::Stopped);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        handle.stop();
+        assert_eq!(handle.status(), BotStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_bot_handle_params() {
+        let handle = BotHandle::default();
+        let mut params = handle.params.subscribe();
+        handle.set_param("rsi_threshold", 70);
+        assert!(params.has_changed().unwrap());
+        assert_eq!(
+            params.borrow_and_update().get("rsi_threshold"),
+            Some(&70.into())
+        );
+
+        // Setting the same value again is not an update.
+        handle.set_param("rsi_threshold", 70);
+        assert!(!params.has_changed().unwrap());
+        handle.set_param("mode", "aggressive");
+        assert!(params.has_changed().unwrap());
+        assert_eq!(handle.params().len(), 2);
+        assert_eq!(handle.param("mode"), Some(Value::from("aggressive")));
+    }
+
+    #[tokio::test]
+    async fn test_deal_events_come_before_pending_ticks_and_candles() {
+        let server = fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)]);
+        let gate = Arc::new(Semaphore::new(0));
+        let (mut bot, mut events) = recorded_bot(&server, gate.clone()).await;
+        bot.add_asset("EURUSD_otc", SubscriptionType::none());
+        bot.enable_ticks("EURUSD_otc");
+        let handle = bot.handle();
+        let run = tokio::spawn(async move { bot.run().await });
+        subscribed(&server, "EURUSD_otc").await;
+
+        // The strategy is busy with the first tick while another tick and a closed deal arrive.
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_000.0, 1.5),
+        );
+        assert_eq!(next_event(&mut events).await, "tick EURUSD_otc 1.5");
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_001.0, 1.25),
+        );
+        let order = OpenOrder::new(
+            dec!(10),
+            "EURUSD_otc".into(),
+            Action::Call,
+            60,
+            1,
+            Uuid::new_v4(),
+        );
+        let deal = fixtures::deal(&order, dec!(1.1), 92, chrono::Utc::now());
+        push(
+            &server,
+            fixtures::update_closed_deals(&[fixtures::closed(&deal, dec!(1.2))]),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        gate.add_permits(1);
+
+        let mut delivered = Vec::new();
+        for _ in 0..4 {
+            delivered.push(next_event(&mut events).await);
+        }
+        assert_eq!(
+            delivered,
+            [
+                "closed EURUSD_otc",
+                "candle EURUSD_otc 1.5",
+                "tick EURUSD_otc 1.25",
+                "candle EURUSD_otc 1.25",
+            ]
+        );
+        handle.stop();
+        run.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ticks_are_delivered_only_for_enabled_assets() {
+        let server = fixtures::mock_server(
+            dec!(1000),
+            &[
+                fixtures::asset(1, "EURUSD_otc", 92),
+                fixtures::asset(2, "GBPUSD_otc", 90),
+            ],
+        );
+        let (mut bot, mut events) = recorded_bot(&server, Arc::new(Semaphore::new(1))).await;
+        bot.add_asset("EURUSD_otc", SubscriptionType::chunk(2));
+        bot.add_asset("GBPUSD_otc", SubscriptionType::chunk(2));
+        bot.enable_ticks("EURUSD_otc");
+        let handle = bot.handle();
+        let run = tokio::spawn(async move { bot.run().await });
+        subscribed(&server, "EURUSD_otc").await;
+        subscribed(&server, "GBPUSD_otc").await;
+
+        // Every update is a tick, the candle follows the update completing it.
+        for (timestamp, price) in [(1_700_000_000.0, 1.5), (1_700_000_001.0, 1.25)] {
+            push(
+                &server,
+                fixtures::update_stream("EURUSD_otc", timestamp, price),
+            );
+        }
+        for expected in [
+            "tick EURUSD_otc 1.5",
+            "tick EURUSD_otc 1.25",
+            "candle EURUSD_otc 1.25",
+        ] {
+            assert_eq!(next_event(&mut events).await, expected);
+        }
+
+        // Without ticks enabled only the candles are delivered.
+        for (timestamp, price) in [(1_700_000_000.0, 2.5), (1_700_000_001.0, 2.25)] {
+            push(
+                &server,
+                fixtures::update_stream("GBPUSD_otc", timestamp, price),
+            );
+        }
+        assert_eq!(next_event(&mut events).await, "candle GBPUSD_otc 2.25");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(events.try_recv().is_err());
+
+        handle.stop();
+        run.await.unwrap().unwrap();
     }
 }
@@ -0,0 +1,328 @@
+//! Monte Carlo simulation of the equity curve of a list of trades.
+//!
+//! A backtest gives one order of the trades, and so one drawdown. [`MonteCarlo`] replays the
+//! outcomes of the trades (their profit over their amount) in random orders with a
+//! [`MoneyManagement`] policy sizing each stake from the running balance, and reports the
+//! distribution of the final balance and of the drawdowns, and how often the balance fell
+//! below the ruin level.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::{AmountLimits, Deal};
+
+/// How the stake of each simulated trade is sized.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "value", rename_all = "snake_case")]
+pub enum MoneyManagement {
+    /// Always the same amount.
+    Fixed(Decimal),
+    /// This share of the current balance, e.g. `0.02` for 2%.
+    Fraction(Decimal),
+    /// `base` times `multiplier` after each loss in a row, back to `base` after a win or
+    /// after `max_steps` losses.
+    Martingale {
+        base: Decimal,
+        multiplier: Decimal,
+        max_steps: u32,
+    },
+}
+
+impl MoneyManagement {
+    fn stake(&self, balance: f64, losses_in_row: u32) -> f64 {
+        match *self {
+            Self::Fixed(amount) => to_f64(amount),
+            Self::Fraction(share) => balance * to_f64(share),
+            Self::Martingale {
+                base,
+                multiplier,
+                max_steps,
+            } => {
+                let steps = if max_steps == 0 {
+                    0
+                } else {
+                    losses_in_row % max_steps
+                };
+                to_f64(base) * to_f64(multiplier).powi(steps as i32)
+            }
+        }
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// How the trade outcomes are drawn for each simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resampling {
+    /// The same outcomes in a random order.
+    #[default]
+    Shuffle,
+    /// As many outcomes drawn with replacement, so some repeat and others are left out.
+    Bootstrap,
+}
+
+/// Simulation settings, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    initial_balance: Decimal,
+    policy: MoneyManagement,
+    simulations: usize,
+    resampling: Resampling,
+    limits: AmountLimits,
+    ruin_level: Option<Decimal>,
+    seed: Option<u64>,
+}
+
+impl MonteCarlo {
+    /// Defaults to 1000 shuffles with the USD amount limits.
+    pub fn new(initial_balance: Decimal, policy: MoneyManagement) -> Self {
+        Self {
+            initial_balance,
+            policy,
+            simulations: 1000,
+            resampling: Resampling::default(),
+            limits: AmountLimits::USD,
+            ruin_level: None,
+            seed: None,
+        }
+    }
+
+    pub fn with_simulations(mut self, simulations: usize) -> Self {
+        self.simulations = simulations;
+        self
+    }
+
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// Bounds of the stakes. A stake above the balance is lowered to the balance.
+    pub fn with_limits(mut self, limits: AmountLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Balance under which a simulation is ruined and stops trading, the minimum amount of
+    /// the limits by default.
+    pub fn with_ruin_level(mut self, level: Decimal) -> Self {
+        self.ruin_level = Some(level);
+        self
+    }
+
+    /// Draws the same simulations for the same seed, random by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Simulates the closed trades of `deals`, e.g. the deals of a
+    /// [`BacktestResult`](super::backtest::BacktestResult).
+    pub fn run(&self, deals: &[Deal]) -> PocketResult<MonteCarloReport> {
+        let outcomes: Vec<f64> = deals
+            .iter()
+            .filter(|deal| !deal.amount.is_zero())
+            .map(|deal| to_f64(deal.profit / deal.amount))
+            .collect();
+        self.run_outcomes(&outcomes)
+    }
+
+    /// Simulates trades returning `outcomes` times their stake, e.g. `0.8` for a win at a
+    /// payout of 80% and `-1.0` for a loss.
+    pub fn run_outcomes(&self, outcomes: &[f64]) -> PocketResult<MonteCarloReport> {
+        if outcomes.is_empty() || self.simulations == 0 {
+            return Err(PocketError::General(
+                "Monte Carlo simulation needs at least one trade and one simulation".into(),
+            ));
+        }
+        let mut rng = StdRng::seed_from_u64(self.seed.unwrap_or_else(rand::random));
+        let mut order = outcomes.to_vec();
+        let mut paths = Vec::with_capacity(self.simulations);
+        for _ in 0..self.simulations {
+            match self.resampling {
+                Resampling::Shuffle => order.shuffle(&mut rng),
+                Resampling::Bootstrap => {
+                    for outcome in order.iter_mut() {
+                        *outcome = outcomes[rng.random_range(0..outcomes.len())];
+                    }
+                }
+            }
+            paths.push(self.simulate(&order));
+        }
+
+        let ruined = paths.iter().filter(|path| path.ruined).count();
+        let stat = |value: fn(&Path) -> f64| Percentiles::of(paths.iter().map(value).collect());
+        Ok(MonteCarloReport {
+            simulations: self.simulations,
+            trades: outcomes.len(),
+            risk_of_ruin: ruined as f64 / self.simulations as f64,
+            final_balance: stat(|path| path.final_balance),
+            max_drawdown: stat(|path| path.max_drawdown),
+            max_drawdown_ratio: stat(|path| path.max_drawdown_ratio),
+            longest_losing_streak: stat(|path| path.longest_losing_streak as f64),
+        })
+    }
+
+    fn simulate(&self, outcomes: &[f64]) -> Path {
+        let (min, max) = (to_f64(self.limits.min), to_f64(self.limits.max));
+        let ruin_level = self.ruin_level.map_or(min, to_f64);
+        let mut balance = to_f64(self.initial_balance);
+        let mut path = Path::default();
+        let (mut peak, mut losses_in_row) = (balance, 0);
+        for outcome in outcomes {
+            if balance < ruin_level || balance < min {
+                path.ruined = true;
+                break;
+            }
+            let stake = self
+                .policy
+                .stake(balance, losses_in_row)
+                .clamp(min, max)
+                .min(balance);
+            balance += stake * outcome;
+            if *outcome < 0.0 {
+                losses_in_row += 1;
+                path.longest_losing_streak = path.longest_losing_streak.max(losses_in_row);
+            } else if *outcome > 0.0 {
+                losses_in_row = 0;
+            }
+            peak = peak.max(balance);
+            if peak - balance > path.max_drawdown {
+                path.max_drawdown = peak - balance;
+            }
+            if peak > 0.0 {
+                path.max_drawdown_ratio = path.max_drawdown_ratio.max((peak - balance) / peak);
+            }
+        }
+        path.ruined |= balance < ruin_level;
+        path.final_balance = balance;
+        path
+    }
+}
+
+/// One simulated equity curve.
+#[derive(Debug, Default)]
+struct Path {
+    final_balance: f64,
+    max_drawdown: f64,
+    max_drawdown_ratio: f64,
+    longest_losing_streak: u32,
+    ruined: bool,
+}
+
+/// Distribution of a value over the simulations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Percentiles {
+    pub min: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Percentiles {
+    fn of(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        // Nearest rank, `values` is never empty.
+        let at = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        Self {
+            min: values[0],
+            p5: at(0.05),
+            p25: at(0.25),
+            median: at(0.5),
+            p75: at(0.75),
+            p95: at(0.95),
+            max: values[values.len() - 1],
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+        }
+    }
+}
+
+/// Outcome of [`MonteCarlo::run`].
+#[derive(Debug, Clone, Serialize)]
+pub struct MonteCarloReport {
+    pub simulations: usize,
+    /// Trades per simulation.
+    pub trades: usize,
+    /// Share of the simulations whose balance fell below the ruin level, between 0 and 1.
+    pub risk_of_ruin: f64,
+    pub final_balance: Percentiles,
+    /// Largest fall of the balance from a previous high.
+    pub max_drawdown: Percentiles,
+    /// Largest fall of the balance from a previous high, over that high.
+    pub max_drawdown_ratio: Percentiles,
+    pub longest_losing_streak: Percentiles,
+}
+
+impl MonteCarloReport {
+    pub fn to_json(&self) -> PocketResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PocketError::General(format!("Failed to encode the report: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_shuffles_keep_the_final_balance_of_fixed_stakes() {
+        // 6 wins at 80% and 4 losses, always 10 staked: +8.
+        let outcomes = [0.8, -1.0, 0.8, 0.8, -1.0, 0.8, -1.0, 0.8, -1.0, 0.8];
+        let simulation = MonteCarlo::new(dec!(100), MoneyManagement::Fixed(dec!(10)))
+            .with_simulations(200)
+            .with_seed(3);
+        let report = simulation.run_outcomes(&outcomes).unwrap();
+
+        assert_eq!(report.risk_of_ruin, 0.0);
+        assert!((report.final_balance.min - 108.0).abs() < 1e-9);
+        assert!((report.final_balance.max - 108.0).abs() < 1e-9);
+        assert!(report.max_drawdown.min >= 10.0 - 1e-9);
+        assert!((report.max_drawdown.max - 40.0).abs() < 1e-9);
+        assert!(report.longest_losing_streak.p5 <= report.longest_losing_streak.p95);
+        assert_eq!(
+            simulation.run_outcomes(&outcomes).unwrap().max_drawdown,
+            report.max_drawdown
+        );
+    }
+
+    #[test]
+    fn test_martingale_risk_of_ruin() {
+        // A streak of 4 losses costs 10 + 20 + 40 + 30, the whole balance.
+        let outcomes = [-1.0, -1.0, -1.0, -1.0, 0.9, 0.9, 0.9, 0.9];
+        let martingale = MoneyManagement::Martingale {
+            base: dec!(10),
+            multiplier: dec!(2),
+            max_steps: 5,
+        };
+        let report = MonteCarlo::new(dec!(100), martingale)
+            .with_seed(1)
+            .run_outcomes(&outcomes)
+            .unwrap();
+        assert!(report.risk_of_ruin > 0.0 && report.risk_of_ruin < 1.0);
+        assert_eq!(report.final_balance.min, 0.0);
+
+        let report = MonteCarlo::new(dec!(100), MoneyManagement::Fraction(dec!(0.05)))
+            .with_resampling(Resampling::Bootstrap)
+            .with_ruin_level(dec!(90))
+            .with_seed(1)
+            .run_outcomes(&[-1.0])
+            .unwrap();
+        assert_eq!(report.risk_of_ruin, 0.0);
+        assert!(MonteCarlo::new(dec!(100), martingale)
+            .run_outcomes(&[])
+            .is_err());
+    }
+}
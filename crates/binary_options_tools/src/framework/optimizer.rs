@@ -0,0 +1,610 @@
+//! Search of the best parameters of a strategy with walk-forward validation.
+//!
+//! An [`Optimizer`] splits the candles of a [`Backtest`] into consecutive folds, each made of an
+//! in-sample window followed by an out-of-sample window. Every parameter set of the
+//! [`ParamSpace`] is backtested on both windows of every fold; the in-sample results pick the
+//! best parameters and the out-of-sample results tell how they hold up on candles they were
+//! not chosen on. The backtests run concurrently on the tokio runtime, each on a new strategy
+//! from the factory.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinSet;
+
+use crate::framework::backtest::{Backtest, BacktestMetrics};
+use crate::framework::{Params, Strategy};
+use crate::pocketoption::error::{PocketError, PocketResult};
+
+/// Values a parameter takes in a [`ParamSpace`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamRange {
+    Values(Vec<Value>),
+    /// From `start` to `end` included, by `step`. The values are integers when `start` and
+    /// `step` are.
+    Range {
+        start: f64,
+        end: f64,
+        step: f64,
+    },
+}
+
+impl ParamRange {
+    /// The values of the range, empty if it is invalid.
+    pub fn values(&self) -> Vec<Value> {
+        match self {
+            Self::Values(values) => values.clone(),
+            Self::Range { start, end, step } => {
+                if !(*step > 0.0 && start <= end && start.is_finite() && end.is_finite()) {
+                    return Vec::new();
+                }
+                let count = ((end - start) / step + 1e-9).floor() as usize + 1;
+                let integers = start.fract() == 0.0 && step.fract() == 0.0;
+                (0..count)
+                    .map(|i| start + step * i as f64)
+                    .map(|value| {
+                        if integers {
+                            Value::from(value as i64)
+                        } else {
+                            // Rounded so that 0.1 + 0.2 gives 0.3.
+                            Value::from((value * 1e10).round() / 1e10)
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Parameters to search and their values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamSpace {
+    params: BTreeMap<String, ParamRange>,
+}
+
+impl ParamSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tries each of `values` for `name`.
+    pub fn with_values<V: Into<Value>>(
+        mut self,
+        name: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        self.params.insert(name.into(), ParamRange::Values(values));
+        self
+    }
+
+    /// Tries `name` from `start` to `end` included, by `step`.
+    pub fn with_range(mut self, name: impl Into<String>, start: f64, end: f64, step: f64) -> Self {
+        self.params
+            .insert(name.into(), ParamRange::Range { start, end, step });
+        self
+    }
+
+    pub fn params(&self) -> &BTreeMap<String, ParamRange> {
+        &self.params
+    }
+
+    /// Number of parameter sets of the [`grid`](Self::grid).
+    pub fn size(&self) -> usize {
+        self.params
+            .values()
+            .map(|range| range.values().len())
+            .product()
+    }
+
+    /// Every combination of the values, empty if a parameter has no value.
+    pub fn grid(&self) -> Vec<Params> {
+        let mut grid = vec![Params::new()];
+        for (name, range) in &self.params {
+            let values = range.values();
+            grid = grid
+                .into_iter()
+                .flat_map(|params| {
+                    values.iter().map(move |value| {
+                        let mut params = params.clone();
+                        params.insert(name.clone(), value.clone());
+                        params
+                    })
+                })
+                .collect();
+        }
+        grid
+    }
+
+    /// `count` combinations drawn at random, the same for the same `seed`. Combinations may
+    /// repeat.
+    pub fn sample(&self, count: usize, seed: u64) -> Vec<Params> {
+        let dimensions: Vec<(&String, Vec<Value>)> = self
+            .params
+            .iter()
+            .map(|(name, range)| (name, range.values()))
+            .collect();
+        if dimensions.iter().any(|(_, values)| values.is_empty()) {
+            return Vec::new();
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| {
+                dimensions
+                    .iter()
+                    .map(|(name, values)| {
+                        let value = values[rng.random_range(0..values.len())].clone();
+                        ((*name).clone(), value)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// How the parameter sets are picked from the [`ParamSpace`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Search {
+    /// Every combination.
+    #[default]
+    Grid,
+    /// `samples` random combinations, see [`ParamSpace::sample`].
+    Random { samples: usize, seed: u64 },
+}
+
+/// What the best parameters maximize.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    #[default]
+    NetProfit,
+    WinRate,
+    /// Gross profit over gross loss.
+    ProfitFactor,
+}
+
+impl Objective {
+    /// Score of `metrics`, the higher the better.
+    pub fn score(&self, metrics: &BacktestMetrics) -> f64 {
+        match self {
+            Self::NetProfit => metrics.net_profit.to_f64().unwrap_or_default(),
+            Self::WinRate => metrics.win_rate,
+            Self::ProfitFactor => {
+                if metrics.gross_loss.is_zero() {
+                    if metrics.gross_profit.is_zero() {
+                        0.0
+                    } else {
+                        f64::INFINITY
+                    }
+                } else {
+                    (metrics.gross_profit / metrics.gross_loss)
+                        .to_f64()
+                        .unwrap_or_default()
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NetProfit => write!(f, "net_profit"),
+            Self::WinRate => write!(f, "win_rate"),
+            Self::ProfitFactor => write!(f, "profit_factor"),
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = PocketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "net_profit" => Ok(Self::NetProfit),
+            "win_rate" => Ok(Self::WinRate),
+            "profit_factor" => Ok(Self::ProfitFactor),
+            _ => Err(PocketError::General(format!(
+                "Unknown objective '{s}', expected net_profit, win_rate or profit_factor"
+            ))),
+        }
+    }
+}
+
+/// Creates the strategy of each backtest.
+pub type StrategyFactory = Arc<dyn Fn() -> PocketResult<Box<dyn Strategy>> + Send + Sync>;
+
+/// Backtests a strategy over a parameter space, see the [module documentation](self).
+pub struct Optimizer {
+    backtest: Backtest,
+    space: ParamSpace,
+    factory: StrategyFactory,
+    search: Search,
+    objective: Objective,
+    folds: usize,
+    train_ratio: f64,
+    concurrency: usize,
+    top: usize,
+}
+
+impl Optimizer {
+    /// Defaults to a grid search over a single fold, 70% of it in-sample, maximizing the net
+    /// profit.
+    pub fn new(
+        backtest: Backtest,
+        space: ParamSpace,
+        factory: impl Fn() -> PocketResult<Box<dyn Strategy>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            backtest,
+            space,
+            factory: Arc::new(factory),
+            search: Search::default(),
+            objective: Objective::default(),
+            folds: 1,
+            train_ratio: 0.7,
+            concurrency: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            top: 5,
+        }
+    }
+
+    pub fn with_search(mut self, search: Search) -> Self {
+        self.search = search;
+        self
+    }
+
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Splits the candles into `folds` consecutive folds, the first `train_ratio` of each
+    /// being in-sample.
+    pub fn with_walk_forward(mut self, folds: usize, train_ratio: f64) -> Self {
+        self.folds = folds;
+        self.train_ratio = train_ratio;
+        self
+    }
+
+    /// Maximum number of backtests running at once, the number of CPUs by default.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Number of parameter sets in [`OptimizationReport::best`], 5 by default.
+    pub fn with_top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Runs the backtests, fails if the parameter space is empty, the folds don't fit in the
+    /// candles or a backtest failed.
+    pub async fn run(&self) -> PocketResult<OptimizationReport> {
+        let candidates = match self.search {
+            Search::Grid => self.space.grid(),
+            Search::Random { samples, seed } => self.space.sample(samples, seed),
+        };
+        if candidates.is_empty() {
+            return Err(PocketError::General(
+                "The parameter space has no parameter set to try".into(),
+            ));
+        }
+        let folds = self.folds()?;
+        let candidates: Arc<[Params]> = candidates.into();
+
+        // results[candidate][fold] = (in-sample, out-of-sample)
+        let mut results =
+            vec![
+                vec![(BacktestMetrics::default(), BacktestMetrics::default()); folds.len()];
+                candidates.len()
+            ];
+        let mut tasks = JoinSet::new();
+        let mut runs = Vec::new();
+        for candidate in 0..candidates.len() {
+            for (fold, (train, test)) in folds.iter().enumerate() {
+                runs.push((candidate, fold, true, train.clone()));
+                runs.push((candidate, fold, false, test.clone()));
+            }
+        }
+        let mut runs = runs.into_iter();
+        loop {
+            while tasks.len() < self.concurrency {
+                let Some((candidate, fold, in_sample, range)) = runs.next() else {
+                    break;
+                };
+                let (backtest, factory, candidates) = (
+                    self.backtest.clone(),
+                    self.factory.clone(),
+                    candidates.clone(),
+                );
+                tasks.spawn(async move {
+                    let strategy = factory()?;
+                    let result = backtest
+                        .run_range(strategy.as_ref(), &candidates[candidate], range)
+                        .await?;
+                    PocketResult::Ok((candidate, fold, in_sample, result.metrics))
+                });
+            }
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (candidate, fold, in_sample, metrics) = joined
+                .map_err(|e| PocketError::General(format!("Backtest task failed: {e}")))??;
+            let slot = &mut results[candidate][fold];
+            if in_sample {
+                slot.0 = metrics;
+            } else {
+                slot.1 = metrics;
+            }
+        }
+
+        let candles = self.backtest.candles();
+        let window = |range: &Range<usize>| Window {
+            from: candles[range.start].timestamp,
+            to: candles[range.end - 1].timestamp,
+            candles: range.len(),
+        };
+        let mut walk_forward = BacktestMetrics::default();
+        let fold_results = folds
+            .iter()
+            .enumerate()
+            .map(|(fold, (train, test))| {
+                let best = self.best_of((0..candidates.len()).map(|c| &results[c][fold].0));
+                let (in_sample, out_of_sample) = results[best][fold].clone();
+                walk_forward.merge(&out_of_sample);
+                FoldResult {
+                    fold,
+                    train: window(train),
+                    test: window(test),
+                    params: candidates[best].clone(),
+                    in_sample,
+                    out_of_sample,
+                }
+            })
+            .collect();
+
+        let mut ranking: Vec<ParamResult> = results
+            .iter()
+            .zip(candidates.iter())
+            .map(|(folds, params)| {
+                let mut in_sample = BacktestMetrics::default();
+                let mut out_of_sample = BacktestMetrics::default();
+                for (train, test) in folds {
+                    in_sample.merge(train);
+                    out_of_sample.merge(test);
+                }
+                ParamResult {
+                    params: params.clone(),
+                    score: self.objective.score(&in_sample),
+                    in_sample,
+                    out_of_sample,
+                }
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.score.total_cmp(&a.score));
+        ranking.truncate(self.top);
+
+        Ok(OptimizationReport {
+            objective: self.objective,
+            evaluated: candidates.len(),
+            folds: fold_results,
+            walk_forward,
+            best: ranking,
+        })
+    }
+
+    /// Index of the best metrics, the first one on ties.
+    fn best_of<'a>(&self, metrics: impl Iterator<Item = &'a BacktestMetrics>) -> usize {
+        let mut best = (0, f64::NEG_INFINITY);
+        for (index, metrics) in metrics.enumerate() {
+            let score = self.objective.score(metrics);
+            if score > best.1 {
+                best = (index, score);
+            }
+        }
+        best.0
+    }
+
+    /// In-sample and out-of-sample candles of each fold.
+    fn folds(&self) -> PocketResult<Vec<(Range<usize>, Range<usize>)>> {
+        if self.folds == 0 || !(self.train_ratio > 0.0 && self.train_ratio < 1.0) {
+            return Err(PocketError::General(format!(
+                "Invalid walk-forward of {} folds with a train ratio of {}",
+                self.folds, self.train_ratio
+            )));
+        }
+        let len = self.backtest.candles().len();
+        let fold_len = len / self.folds;
+        let train_len = (fold_len as f64 * self.train_ratio).round() as usize;
+        if train_len == 0 || train_len >= fold_len {
+            return Err(PocketError::General(format!(
+                "Not enough candles ({len}) for {} folds",
+                self.folds
+            )));
+        }
+        Ok((0..self.folds)
+            .map(|fold| {
+                let start = fold * fold_len;
+                let end = if fold + 1 == self.folds {
+                    len
+                } else {
+                    start + fold_len
+                };
+                (start..start + train_len, start + train_len..end)
+            })
+            .collect())
+    }
+}
+
+/// Candles of a fold window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Window {
+    /// Timestamp of the first candle.
+    pub from: i64,
+    /// Timestamp of the last candle.
+    pub to: i64,
+    pub candles: usize,
+}
+
+/// Best parameters of a fold.
+#[derive(Debug, Clone, Serialize)]
+pub struct FoldResult {
+    pub fold: usize,
+    pub train: Window,
+    pub test: Window,
+    pub params: Params,
+    pub in_sample: BacktestMetrics,
+    pub out_of_sample: BacktestMetrics,
+}
+
+/// Results of a parameter set over all the folds.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamResult {
+    pub params: Params,
+    /// Score of the in-sample metrics.
+    pub score: f64,
+    pub in_sample: BacktestMetrics,
+    pub out_of_sample: BacktestMetrics,
+}
+
+/// Outcome of [`Optimizer::run`].
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimizationReport {
+    pub objective: Objective,
+    /// Number of parameter sets backtested.
+    pub evaluated: usize,
+    pub folds: Vec<FoldResult>,
+    /// Out-of-sample results of the best parameters of each fold, traded one fold after the
+    /// other.
+    pub walk_forward: BacktestMetrics,
+    /// Parameter sets with the best in-sample score over all the folds, best first.
+    pub best: Vec<ParamResult>,
+}
+
+impl OptimizationReport {
+    pub fn to_json(&self) -> PocketResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PocketError::General(format!("Failed to encode the report: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::Context;
+    use crate::pocketoption::candle::Candle;
+    use crate::pocketoption::fixtures::{self, MOCK_SSID};
+    use crate::pocketoption::pocket_client::PocketOption;
+    use async_trait::async_trait;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::sync::Mutex;
+
+    /// Trades every candle in the direction of its `direction` parameter.
+    #[derive(Default)]
+    struct Follow {
+        params: Mutex<Params>,
+    }
+
+    #[async_trait]
+    impl Strategy for Follow {
+        async fn on_candle(&self, ctx: &Context, asset: &str, _: &Candle) -> PocketResult<()> {
+            let (up, amount) = {
+                let params = self.params.lock().unwrap();
+                (
+                    params["direction"] == "up",
+                    Decimal::from(params["amount"].as_i64().unwrap()),
+                )
+            };
+            if up {
+                ctx.market.buy(asset, amount, 60).await?;
+            } else {
+                ctx.market.sell(asset, amount, 60).await?;
+            }
+            Ok(())
+        }
+
+        async fn on_params_update(&self, _: &Context, params: &Params) -> PocketResult<()> {
+            *self.params.lock().unwrap() = params.clone();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_param_space_grid_and_sample() {
+        let space = ParamSpace::new()
+            .with_range("period", 10.0, 20.0, 5.0)
+            .with_range("threshold", 0.1, 0.3, 0.1)
+            .with_values("mode", ["fast", "slow"]);
+        assert_eq!(space.size(), 18);
+        let grid = space.grid();
+        assert_eq!(grid.len(), 18);
+        assert!(grid
+            .iter()
+            .any(|p| p["period"] == 20 && p["threshold"] == 0.3));
+        assert!(grid.iter().all(|p| p["period"].is_i64()));
+
+        let sample = space.sample(10, 7);
+        assert_eq!(sample.len(), 10);
+        assert_eq!(sample, space.sample(10, 7));
+        assert!(sample.iter().all(|p| grid.contains(p)));
+        assert!(space
+            .with_range("empty", 1.0, 0.0, 1.0)
+            .sample(3, 7)
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_walk_forward_picks_the_best_parameters() {
+        let client = PocketOption::builder()
+            .ssid(MOCK_SSID)
+            .connector(fixtures::mock_server(dec!(1000), &[]))
+            .build_offline()
+            .await
+            .unwrap();
+        // Rising prices, calls always win.
+        let candles = (0..20)
+            .map(|i| Candle {
+                symbol: "EURUSD_otc".into(),
+                timestamp: 1_700_000_000 + i * 60,
+                close: dec!(1.1) + Decimal::new(i, 3),
+                ..Candle::default()
+            })
+            .collect();
+        let backtest = Backtest::new(Arc::new(client), candles);
+        let space = ParamSpace::new()
+            .with_values("direction", ["up", "down"])
+            .with_range("amount", 1.0, 2.0, 1.0);
+        let report = Optimizer::new(backtest, space, || Ok(Box::new(Follow::default())))
+            .with_walk_forward(2, 0.5)
+            .with_top(2)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.evaluated, 4);
+        assert_eq!(report.folds.len(), 2);
+        let fold = &report.folds[1];
+        assert_eq!((fold.train.candles, fold.test.candles), (5, 5));
+        assert_eq!(fold.test.from, 1_700_000_000 + 15 * 60);
+        assert_eq!(fold.params["direction"], "up");
+        assert_eq!(fold.params["amount"], 2);
+        // 5 trades per window, the last one still open.
+        assert_eq!(fold.out_of_sample.wins, 4);
+        assert_eq!(report.walk_forward.net_profit, dec!(12.8));
+        assert_eq!(report.best.len(), 2);
+        assert_eq!(report.best[1].params["amount"], 1);
+        assert_eq!(report.best[1].out_of_sample.net_profit, dec!(6.4));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["objective"], "net_profit");
+    }
+}
@@ -0,0 +1,800 @@
+//! Several strategies sharing one [`PocketOption`] client.
+//!
+//! An [`Orchestrator`] subscribes once per asset and sends each candle to every bot
+//! watching it, so bots don't each need their own connection and subscription slots.
+//! Trades of all bots go through the same [`RiskLimits`], and [`Orchestrator::stats`]
+//! aggregates their results.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::stream::{select_all, BoxStream};
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::framework::journal::TradeJournal;
+use crate::framework::market::Market;
+use crate::framework::{
+    deal_event, settled, stopping, BotHandle, BotStatus, Context, Params, Strategy,
+};
+use crate::pocketoption::candle::SubscriptionType;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::modules::subscriptions::StreamUpdate;
+use crate::pocketoption::pocket_client::PocketOption;
+use crate::pocketoption::stats::TradeStats;
+use crate::pocketoption::types::Deal;
+
+/// Limits applied to the trades of all the bots together, `None` disables a limit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RiskLimits {
+    /// Trades open at the same time.
+    pub max_open_trades: Option<usize>,
+    /// Amount of a single trade.
+    pub max_stake: Option<Decimal>,
+    /// Net loss after which no trade is opened anymore.
+    pub max_loss: Option<Decimal>,
+}
+
+/// Results of one bot.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BotStats {
+    pub trades: TradeStats,
+    pub open: usize,
+    /// Trades refused by the risk limits.
+    pub rejected: u64,
+}
+
+/// Results of all the bots, see [`Orchestrator::stats`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrchestratorStats {
+    pub total: TradeStats,
+    pub open: usize,
+    pub rejected: u64,
+    pub bots: BTreeMap<String, BotStats>,
+}
+
+/// Open trades and results shared by the bots.
+#[derive(Default)]
+struct Ledger {
+    limits: RiskLimits,
+    stats: Mutex<OrchestratorStats>,
+}
+
+impl Ledger {
+    fn stats(&self) -> MutexGuard<'_, OrchestratorStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Counts a trade as open, unless it breaks a limit.
+    fn reserve(&self, bot: &str, amount: Decimal) -> PocketResult<()> {
+        let mut guard = self.stats();
+        let stats = &mut *guard;
+        let limits = &self.limits;
+        let reason = if let Some(max) = limits.max_stake.filter(|max| amount > *max) {
+            Some(format!("amount {amount} is above the {max} stake limit"))
+        } else if let Some(max) = limits.max_open_trades.filter(|max| stats.open >= *max) {
+            Some(format!("{max} trades are already open"))
+        } else {
+            limits
+                .max_loss
+                .filter(|max| -stats.total.net_profit >= *max)
+                .map(|max| format!("the {max} loss limit is reached"))
+        };
+        let bot_stats = stats.bots.entry(bot.to_string()).or_default();
+        if let Some(reason) = reason {
+            bot_stats.rejected += 1;
+            stats.rejected += 1;
+            warn!(target: "Orchestrator", "Trade of {} rejected: {}", bot, reason);
+            return Err(PocketError::RiskLimit(reason));
+        }
+        bot_stats.open += 1;
+        stats.open += 1;
+        Ok(())
+    }
+
+    fn release(&self, bot: &str) {
+        let mut guard = self.stats();
+        let stats = &mut *guard;
+        let bot_stats = stats.bots.entry(bot.to_string()).or_default();
+        bot_stats.open = bot_stats.open.saturating_sub(1);
+        stats.open = stats.open.saturating_sub(1);
+    }
+
+    fn settle(&self, bot: &str, deal: &Deal) {
+        let mut guard = self.stats();
+        let stats = &mut *guard;
+        if !stats.total.record(deal) {
+            return;
+        }
+        let bot_stats = stats.bots.entry(bot.to_string()).or_default();
+        bot_stats.trades.record(deal);
+        bot_stats.open = bot_stats.open.saturating_sub(1);
+        stats.open = stats.open.saturating_sub(1);
+    }
+}
+
+/// Market of a bot run by an [`Orchestrator`]: applies the risk limits and records results.
+struct BotMarket {
+    bot: String,
+    inner: Arc<dyn Market>,
+    ledger: Arc<Ledger>,
+    journal: Option<TradeJournal>,
+}
+
+impl BotMarket {
+    async fn open(
+        &self,
+        asset: &str,
+        amount: Decimal,
+        time: u32,
+        call: bool,
+    ) -> PocketResult<(Uuid, Deal)> {
+        self.ledger.reserve(&self.bot, amount)?;
+        let opened = if call {
+            self.inner.buy(asset, amount, time).await
+        } else {
+            self.inner.sell(asset, amount, time).await
+        };
+        match opened {
+            Ok((id, deal)) => {
+                if let Some(journal) = &self.journal {
+                    journal.open(id, &self.bot, &deal);
+                }
+                self.watch(id);
+                Ok((id, deal))
+            }
+            Err(e) => {
+                self.ledger.release(&self.bot);
+                Err(e)
+            }
+        }
+    }
+
+    /// Records the result of the trade once it closes.
+    fn watch(&self, id: Uuid) {
+        let (bot, inner, ledger) = (self.bot.clone(), self.inner.clone(), self.ledger.clone());
+        let journal = self.journal.clone();
+        tokio::spawn(async move {
+            match settled(inner.as_ref(), id).await {
+                Ok(deal) => {
+                    ledger.settle(&bot, &deal);
+                    if let Some(journal) = journal {
+                        journal.close(id, &deal);
+                    }
+                }
+                Err(e) => {
+                    warn!(target: "Orchestrator", "Failed to get the result of trade {}: {}", id, e);
+                    ledger.release(&bot);
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl Market for BotMarket {
+    async fn buy(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        self.open(asset, amount, time, true).await
+    }
+
+    async fn sell(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        self.open(asset, amount, time, false).await
+    }
+
+    async fn balance(&self) -> Decimal {
+        self.inner.balance().await
+    }
+
+    async fn result(&self, trade_id: Uuid) -> PocketResult<Deal> {
+        self.inner.result(trade_id).await
+    }
+}
+
+struct OrchestratedBot {
+    name: String,
+    ctx: Context,
+    strategy: Arc<dyn Strategy>,
+    assets: Vec<String>,
+}
+
+/// Runs several strategies on one client.
+pub struct Orchestrator {
+    client: Arc<PocketOption>,
+    market: Arc<dyn Market>,
+    ledger: Arc<Ledger>,
+    journal: Option<TradeJournal>,
+    bots: Vec<OrchestratedBot>,
+    subscriptions: Vec<(String, SubscriptionType)>,
+    ticks: HashSet<String>,
+    update_time: Duration,
+    handle: BotHandle,
+}
+
+impl Orchestrator {
+    pub fn new(client: PocketOption) -> Self {
+        let client = Arc::new(client);
+        Self {
+            market: client.clone(),
+            client,
+            ledger: Arc::new(Ledger::default()),
+            journal: None,
+            bots: Vec::new(),
+            subscriptions: Vec::new(),
+            ticks: HashSet::new(),
+            update_time: Duration::from_secs(5),
+            handle: BotHandle::default(),
+        }
+    }
+
+    /// Sets a custom market implementation (e.g., VirtualMarket for backtesting).
+    ///
+    /// Must be called before adding bots.
+    pub fn with_market(mut self, market: Arc<dyn Market>) -> Self {
+        self.market = market;
+        self
+    }
+
+    /// Sets the limits applied to the trades of all the bots.
+    ///
+    /// Must be called before adding bots.
+    pub fn with_risk_limits(mut self, limits: RiskLimits) -> Self {
+        self.ledger = Arc::new(Ledger {
+            limits,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Records the trades of every bot in `journal`, under the name of the bot.
+    ///
+    /// Must be called before adding bots.
+    pub fn with_journal(mut self, journal: TradeJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn with_update_interval(&mut self, duration: Duration) {
+        self.update_time = duration;
+    }
+
+    /// Adds a bot watching `assets`.
+    ///
+    /// Bots watching the same asset share its subscription, so they must use the same
+    /// candle type for it.
+    pub fn add_bot(
+        &mut self,
+        name: impl Into<String>,
+        strategy: Box<dyn Strategy>,
+        assets: impl IntoIterator<Item = (String, SubscriptionType)>,
+    ) -> PocketResult<()> {
+        let name = name.into();
+        if self.bots.iter().any(|bot| bot.name == name) {
+            return Err(PocketError::Configuration(format!(
+                "A bot named {name} was already added"
+            )));
+        }
+        let mut bot_assets = Vec::new();
+        for (asset, sub_type) in assets {
+            match self.subscriptions.iter().find(|(a, _)| *a == asset) {
+                Some((_, existing)) if !same_candles(existing, &sub_type) => {
+                    return Err(PocketError::Configuration(format!(
+                        "{asset} is already subscribed with another candle type"
+                    )));
+                }
+                Some(_) => {}
+                None => self.subscriptions.push((asset.clone(), sub_type)),
+            }
+            bot_assets.push(asset);
+        }
+        let market = BotMarket {
+            bot: name.clone(),
+            inner: self.market.clone(),
+            ledger: self.ledger.clone(),
+            journal: self.journal.clone(),
+        };
+        self.ledger.stats().bots.entry(name.clone()).or_default();
+        self.bots.push(OrchestratedBot {
+            ctx: Context {
+                market: Arc::new(market),
+                client: self.client.clone(),
+                journal: self.journal.clone(),
+            },
+            name,
+            strategy: Arc::from(strategy),
+            assets: bot_assets,
+        });
+        Ok(())
+    }
+
+    /// Delivers every raw price update of `asset` to [`Strategy::on_tick`] of the bots
+    /// watching it, on top of its candles.
+    pub fn enable_ticks(&mut self, asset: impl Into<String>) {
+        self.ticks.insert(asset.into());
+    }
+
+    /// Returns a handle to pause, resume or stop all the bots.
+    pub fn handle(&self) -> BotHandle {
+        self.handle.clone()
+    }
+
+    /// Results of all the bots and of each of them.
+    pub fn stats(&self) -> OrchestratorStats {
+        self.ledger.stats().clone()
+    }
+
+    /// Starts the bots and dispatches the candles until stopped.
+    pub async fn run(&mut self) -> PocketResult<()> {
+        if self.handle.status() == BotStatus::Stopping {
+            self.handle.set(BotStatus::Stopped);
+            return Ok(());
+        }
+        info!(target: "Orchestrator", "Starting {} bots...", self.bots.len());
+        self.handle.set(BotStatus::Running);
+        let result = self.dispatch().await;
+        if self.handle.status() == BotStatus::Stopping {
+            for (asset, _) in &self.subscriptions {
+                if let Err(e) = self.client.unsubscribe(asset).await {
+                    warn!(target: "Orchestrator", "Failed to unsubscribe from {}: {:?}", asset, e);
+                }
+            }
+        }
+        self.handle.set(BotStatus::Stopped);
+        info!(target: "Orchestrator", "Orchestrator stopped.");
+        result
+    }
+
+    async fn dispatch(&mut self) -> PocketResult<()> {
+        let mut params = self.handle.params.subscribe();
+        let mut opened = self.client.opened_deal_events();
+        let mut closed = self.client.closed_deal_events();
+        for bot in &self.bots {
+            bot.strategy.on_start(&bot.ctx).await?;
+        }
+        let initial = params.borrow_and_update().clone();
+        if !initial.is_empty() {
+            self.update_params(&initial).await;
+        }
+
+        let mut streams: Vec<BoxStream<'static, (String, PocketResult<StreamUpdate>)>> = Vec::new();
+        for (asset, sub_type) in &self.subscriptions {
+            info!(target: "Orchestrator", "Subscribing to {}...", asset);
+            let stream = self
+                .client
+                .subscribe(asset.clone(), sub_type.clone())
+                .await?;
+            let asset = asset.clone();
+            if self.ticks.contains(&asset) {
+                streams.push(
+                    stream
+                        .to_update_stream()
+                        .map(move |res| (asset.clone(), res))
+                        .boxed(),
+                );
+            } else {
+                streams.push(
+                    stream
+                        .to_stream()
+                        .map(move |res| (asset.clone(), res.map(StreamUpdate::Candle)))
+                        .boxed(),
+                );
+            }
+        }
+        if streams.is_empty() {
+            error!(target: "Orchestrator", "No assets added to the bots. Exiting.");
+            return Ok(());
+        }
+
+        let mut combined_stream = select_all(streams);
+        let mut status = self.handle.status.subscribe();
+        // Polled here rather than in a task so that a bot is never called concurrently
+        let mut balance_timer = tokio::time::interval(self.update_time);
+        balance_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_balance = Decimal::ZERO;
+        loop {
+            // Biased so that simultaneous events are delivered in the order `Bot` documents.
+            tokio::select! {
+                biased;
+                _ = stopping(&mut status) => break,
+                Ok(()) = params.changed() => {
+                    let updated = params.borrow_and_update().clone();
+                    self.update_params(&updated).await;
+                }
+                deal = opened.recv() => {
+                    if let Some(deal) = deal_event(deal) {
+                        for bot in &self.bots {
+                            if let Err(e) = bot.strategy.on_deal_opened(&bot.ctx, &deal).await {
+                                warn!(target: "Orchestrator", "Bot {} on_deal_opened error for {}: {:?}", bot.name, deal.id, e);
+                            }
+                        }
+                    }
+                }
+                deal = closed.recv() => {
+                    if let Some(deal) = deal_event(deal) {
+                        for bot in &self.bots {
+                            if let Err(e) = bot.strategy.on_deal_closed(&bot.ctx, &deal).await {
+                                warn!(target: "Orchestrator", "Bot {} on_deal_closed error for {}: {:?}", bot.name, deal.id, e);
+                            }
+                        }
+                        self.update_balance(&mut last_balance).await;
+                    }
+                }
+                item = combined_stream.next() => {
+                    let Some((asset, result)) = item else {
+                        break;
+                    };
+                    if self.handle.is_paused() {
+                        continue;
+                    }
+                    let update = match result {
+                        Ok(update) => update,
+                        Err(e) => {
+                            error!(target: "Orchestrator", "Stream error for {}: {:?}", asset, e);
+                            continue;
+                        }
+                    };
+                    for bot in self.bots.iter().filter(|bot| bot.assets.contains(&asset)) {
+                        match &update {
+                            StreamUpdate::Candle(candle) => {
+                                if let Err(e) = bot.strategy.on_candle(&bot.ctx, &asset, candle).await {
+                                    warn!(target: "Orchestrator", "Bot {} on_candle error for {}: {:?}", bot.name, asset, e);
+                                }
+                            }
+                            StreamUpdate::Tick { price, .. } => {
+                                if let Err(e) = bot.strategy.on_tick(&bot.ctx, &asset, *price).await {
+                                    warn!(target: "Orchestrator", "Bot {} on_tick error for {}: {:?}", bot.name, asset, e);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ = balance_timer.tick() => self.update_balance(&mut last_balance).await,
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_params(&self, params: &Params) {
+        for bot in &self.bots {
+            if let Err(e) = bot.strategy.on_params_update(&bot.ctx, params).await {
+                warn!(target: "Orchestrator", "Bot {} on_params_update error: {:?}", bot.name, e);
+            }
+        }
+    }
+
+    /// Sends the balance to every bot if it changed since `last_balance`.
+    async fn update_balance(&self, last_balance: &mut Decimal) {
+        if self.handle.is_paused() {
+            return;
+        }
+        let balance = self.market.balance().await;
+        if balance == *last_balance {
+            return;
+        }
+        info!(target: "Orchestrator", "Balance updated: {}", balance);
+        *last_balance = balance;
+        for bot in &self.bots {
+            if let Err(e) = bot.strategy.on_balance_update(&bot.ctx, balance).await {
+                warn!(target: "Orchestrator", "Bot {} on_balance_update error: {:?}", bot.name, e);
+            }
+        }
+    }
+}
+
+/// Whether two subscription types build the same candles.
+fn same_candles(a: &SubscriptionType, b: &SubscriptionType) -> bool {
+    match (a, b) {
+        (SubscriptionType::None, SubscriptionType::None) => true,
+        (SubscriptionType::Chunk { size: a, .. }, SubscriptionType::Chunk { size: b, .. }) => {
+            a == b
+        }
+        (
+            SubscriptionType::Time { duration: a, .. },
+            SubscriptionType::Time { duration: b, .. },
+        )
+        | (
+            SubscriptionType::TimeAligned { duration: a, .. },
+            SubscriptionType::TimeAligned { duration: b, .. },
+        ) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::pocketoption::candle::Candle;
+    use crate::pocketoption::fixtures::{self, MOCK_SSID};
+    use crate::pocketoption::types::{Action, OpenOrder};
+    use binary_options_tools_core::reimports::Message;
+    use binary_options_tools_core::testing::MockConnector;
+    use rust_decimal_macros::dec;
+    use tokio::sync::{mpsc, Semaphore};
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Reports the callbacks it gets, `on_candle` waits for a permit of `gate`.
+    struct Recorder {
+        events: mpsc::UnboundedSender<String>,
+        gate: Arc<Semaphore>,
+    }
+
+    #[async_trait]
+    impl Strategy for Recorder {
+        async fn on_candle(&self, _: &Context, asset: &str, candle: &Candle) -> PocketResult<()> {
+            let _ = self.events.send(format!("candle {asset} {}", candle.close));
+            let _ = self.gate.acquire().await;
+            Ok(())
+        }
+
+        async fn on_tick(&self, _: &Context, asset: &str, price: Decimal) -> PocketResult<()> {
+            let _ = self.events.send(format!("tick {asset} {price}"));
+            Ok(())
+        }
+
+        async fn on_deal_opened(&self, _: &Context, deal: &Deal) -> PocketResult<()> {
+            let _ = self.events.send(format!("opened {}", deal.asset));
+            Ok(())
+        }
+
+        async fn on_deal_closed(&self, _: &Context, deal: &Deal) -> PocketResult<()> {
+            let _ = self.events.send(format!("closed {}", deal.asset));
+            Ok(())
+        }
+
+        async fn on_balance_update(&self, _: &Context, balance: Decimal) -> PocketResult<()> {
+            let _ = self.events.send(format!("balance {balance}"));
+            Ok(())
+        }
+    }
+
+    async fn recorded_orchestrator(
+        server: &MockConnector,
+        gate: Arc<Semaphore>,
+    ) -> (Orchestrator, mpsc::UnboundedReceiver<String>) {
+        let config = Config {
+            connection_initialization_timeout: TIMEOUT,
+            ..Config::default()
+        };
+        let client = PocketOption::new_with_connector(MOCK_SSID, server.clone(), config)
+            .await
+            .unwrap();
+        client.wait_for_assets(TIMEOUT).await.unwrap();
+        let (events, received) = mpsc::unbounded_channel();
+        let mut orchestrator = Orchestrator::new(client);
+        orchestrator
+            .add_bot(
+                "recorder",
+                Box::new(Recorder { events, gate }),
+                [("EURUSD_otc".to_string(), SubscriptionType::none())],
+            )
+            .unwrap();
+        (orchestrator, received)
+    }
+
+    async fn next_event(events: &mut mpsc::UnboundedReceiver<String>) -> String {
+        tokio::time::timeout(TIMEOUT, events.recv())
+            .await
+            .expect("no strategy callback")
+            .unwrap()
+    }
+
+    fn push(server: &MockConnector, frames: impl IntoIterator<Item = Message>) {
+        for frame in frames {
+            assert!(server.push(frame));
+        }
+    }
+
+    async fn subscribed(server: &MockConnector) {
+        server
+            .wait_for(TIMEOUT, |msg| {
+                msg.to_text()
+                    .is_ok_and(|text| text.contains("changeSymbol"))
+            })
+            .await
+            .expect("the orchestrator did not subscribe");
+    }
+
+    fn deal(profit: f64) -> Deal {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 1_700_000_000, "closeTimestamp": 1_700_000_060,
+            "uid": 1, "amount": 1.0, "profit": profit,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 1.1,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ledger_risk_limits() {
+        let ledger = Ledger {
+            limits: RiskLimits {
+                max_open_trades: Some(2),
+                max_stake: Some(dec!(10)),
+                max_loss: Some(dec!(1)),
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            ledger.reserve("a", dec!(11)),
+            Err(PocketError::RiskLimit(_))
+        ));
+        ledger.reserve("a", dec!(1)).unwrap();
+        ledger.reserve("b", dec!(1)).unwrap();
+        assert!(ledger.reserve("b", dec!(1)).is_err());
+
+        let (win, loss) = (deal(0.92), deal(-1.0));
+        ledger.settle("a", &win);
+        ledger.settle("a", &win);
+        ledger.settle("b", &loss);
+        let stats = ledger.stats().clone();
+        assert_eq!(stats.open, 0);
+        assert_eq!(stats.rejected, 2);
+        assert_eq!(stats.total.total_trades, 2);
+        assert_eq!(stats.bots["a"].trades.wins, 1);
+        assert_eq!(stats.bots["b"].trades.losses, 1);
+        assert_eq!(stats.bots["b"].rejected, 1);
+
+        ledger.reserve("a", dec!(1)).unwrap();
+        ledger.settle("a", &deal(-1.0));
+        assert!(ledger.reserve("b", dec!(1)).is_err(), "loss limit reached");
+    }
+
+    #[tokio::test]
+    async fn test_balance_updates_wait_for_the_running_callback() {
+        let server = fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)]);
+        let gate = Arc::new(Semaphore::new(0));
+        let (mut orchestrator, mut events) = recorded_orchestrator(&server, gate.clone()).await;
+        orchestrator.with_update_interval(Duration::from_millis(10));
+        let handle = orchestrator.handle();
+        let run = tokio::spawn(async move { orchestrator.run().await });
+        assert_eq!(next_event(&mut events).await, "balance 1000");
+        subscribed(&server).await;
+
+        // The balance changes while the bot is busy with a candle
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_000.0, 1.5),
+        );
+        assert_eq!(next_event(&mut events).await, "candle EURUSD_otc 1.5");
+        push(&server, fixtures::balance(dec!(900), true));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(events.try_recv().is_err(), "called during on_candle");
+
+        gate.add_permits(1);
+        assert_eq!(next_event(&mut events).await, "balance 900");
+        handle.stop();
+        run.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deal_events_come_before_pending_candles() {
+        let server = fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)]);
+        let gate = Arc::new(Semaphore::new(0));
+        let (mut orchestrator, mut events) = recorded_orchestrator(&server, gate.clone()).await;
+        let handle = orchestrator.handle();
+        let run = tokio::spawn(async move { orchestrator.run().await });
+        assert_eq!(next_event(&mut events).await, "balance 1000");
+        subscribed(&server).await;
+
+        // The bot is busy with a candle while another candle and a deal arrive
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_000.0, 1.5),
+        );
+        assert_eq!(next_event(&mut events).await, "candle EURUSD_otc 1.5");
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_001.0, 1.25),
+        );
+        let order = OpenOrder::new(
+            dec!(10),
+            "EURUSD_otc".into(),
+            Action::Call,
+            60,
+            1,
+            Uuid::new_v4(),
+        );
+        let deal = fixtures::deal(&order, dec!(1.1), 92, chrono::Utc::now());
+        push(
+            &server,
+            fixtures::update_opened_deals(std::slice::from_ref(&deal)),
+        );
+        push(
+            &server,
+            fixtures::update_closed_deals(&[fixtures::closed(&deal, dec!(1.2))]),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        gate.add_permits(1);
+
+        let mut delivered = Vec::new();
+        for _ in 0..3 {
+            delivered.push(next_event(&mut events).await);
+        }
+        assert_eq!(
+            delivered,
+            [
+                "opened EURUSD_otc",
+                "closed EURUSD_otc",
+                "candle EURUSD_otc 1.25"
+            ]
+        );
+        handle.stop();
+        run.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ticks_are_delivered_only_for_enabled_assets() {
+        let server = fixtures::mock_server(
+            dec!(1000),
+            &[
+                fixtures::asset(1, "EURUSD_otc", 92),
+                fixtures::asset(2, "GBPUSD_otc", 90),
+            ],
+        );
+        let (mut orchestrator, mut events) =
+            recorded_orchestrator(&server, Arc::new(Semaphore::new(1))).await;
+        let (watcher, mut watched) = mpsc::unbounded_channel();
+        orchestrator
+            .add_bot(
+                "watcher",
+                Box::new(Recorder {
+                    events: watcher,
+                    gate: Arc::new(Semaphore::new(1)),
+                }),
+                [("GBPUSD_otc".to_string(), SubscriptionType::none())],
+            )
+            .unwrap();
+        orchestrator.enable_ticks("EURUSD_otc");
+        let handle = orchestrator.handle();
+        let run = tokio::spawn(async move { orchestrator.run().await });
+        assert_eq!(next_event(&mut events).await, "balance 1000");
+        assert_eq!(next_event(&mut watched).await, "balance 1000");
+        subscribed(&server).await;
+
+        // The tick comes before the candle it completes, only to the bot watching the asset
+        push(
+            &server,
+            fixtures::update_stream("EURUSD_otc", 1_700_000_000.0, 1.5),
+        );
+        assert_eq!(next_event(&mut events).await, "tick EURUSD_otc 1.5");
+        assert_eq!(next_event(&mut events).await, "candle EURUSD_otc 1.5");
+
+        // Without ticks enabled only the candles are delivered
+        push(
+            &server,
+            fixtures::update_stream("GBPUSD_otc", 1_700_000_000.0, 2.5),
+        );
+        assert_eq!(next_event(&mut watched).await, "candle GBPUSD_otc 2.5");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(events.try_recv().is_err());
+        assert!(watched.try_recv().is_err());
+
+        handle.stop();
+        run.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_same_candles() {
+        let minute = SubscriptionType::time_aligned(Duration::from_secs(60)).unwrap();
+        let five = SubscriptionType::time_aligned(Duration::from_secs(300)).unwrap();
+        assert!(same_candles(&minute, &minute.clone()));
+        assert!(!same_candles(&minute, &five));
+        assert!(!same_candles(&minute, &SubscriptionType::None));
+        assert!(same_candles(
+            &SubscriptionType::chunk(5),
+            &SubscriptionType::chunk(5)
+        ));
+    }
+}
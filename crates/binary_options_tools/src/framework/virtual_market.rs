@@ -1,8 +1,9 @@
+use crate::clock::{system_clock, SharedClock};
 use crate::framework::market::Market;
 use crate::pocketoption::error::PocketResult;
 use crate::pocketoption::types::{Deal, RequestId};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -33,6 +34,7 @@ pub struct VirtualMarket {
     open_trades: Mutex<HashMap<Uuid, VirtualTrade>>,
     current_prices: Mutex<HashMap<String, Decimal>>,
     payouts: Mutex<HashMap<String, i32>>,
+    clock: SharedClock,
 }
 
 impl VirtualMarket {
@@ -42,9 +44,17 @@ impl VirtualMarket {
             open_trades: Mutex::new(HashMap::new()),
             current_prices: Mutex::new(HashMap::new()),
             payouts: Mutex::new(HashMap::new()),
+            clock: system_clock(),
         }
     }
 
+    /// Uses `clock` for trade times and expiries, e.g. a
+    /// [`SimClock`](crate::clock::SimClock) driven by a backtest.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub async fn update_price(&self, asset: &str, price: Decimal) {
         self.current_prices
             .lock()
@@ -86,7 +96,7 @@ impl Market for VirtualMarket {
         *balance -= amount;
 
         let id = Uuid::new_v4();
-        let entry_time = Utc::now();
+        let entry_time = self.clock.now();
 
         let trade = VirtualTrade {
             id,
@@ -131,6 +141,7 @@ impl Market for VirtualMarket {
             currency: "USD".to_string(),
             amount_usd: Some(amount),
             amount_usd2: Some(amount),
+            slippage: None,
         };
 
         Ok((id, deal))
@@ -163,7 +174,7 @@ impl Market for VirtualMarket {
         *balance -= amount;
 
         let id = Uuid::new_v4();
-        let entry_time = Utc::now();
+        let entry_time = self.clock.now();
 
         let trade = VirtualTrade {
             id,
@@ -208,6 +219,7 @@ impl Market for VirtualMarket {
             currency: "USD".to_string(),
             amount_usd: Some(amount),
             amount_usd2: Some(amount),
+            slippage: None,
         };
 
         Ok((id, deal))
@@ -230,7 +242,7 @@ impl Market for VirtualMarket {
                 })?
                 .clone();
 
-            let current_time = Utc::now().timestamp();
+            let current_time = self.clock.now().timestamp();
             let expiry_time = trade.entry_time + trade.duration as i64;
 
             if current_time >= expiry_time {
@@ -280,6 +292,7 @@ impl Market for VirtualMarket {
                 currency: "USD".to_string(),
                 amount_usd: Some(trade.amount),
                 amount_usd2: Some(trade.amount),
+                slippage: None,
             });
         }
 
@@ -355,6 +368,7 @@ impl Market for VirtualMarket {
             currency: "USD".to_string(),
             amount_usd: Some(trade.amount),
             amount_usd2: Some(trade.amount),
+            slippage: None,
         };
 
         Ok(deal)
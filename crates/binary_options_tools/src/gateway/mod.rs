@@ -0,0 +1,208 @@
+//! Embedded HTTP/WebSocket gateway around a [`PocketOption`] client.
+//!
+//! Lets tools that cannot link against this crate (TradingView webhooks, n8n, shell scripts)
+//! trade through a running client. Enabled with the `gateway` feature, which also builds the
+//! `gateway` binary.
+//!
+//! | Method | Path            | Description                                             |
+//! |--------|-----------------|---------------------------------------------------------|
+//! | GET    | `/balance`      | `{"balance": 12.5}`                                     |
+//! | POST   | `/trade`        | body `{"asset", "action": "call"/"put", "amount", "time"}` |
+//! | GET    | `/result/{id}`  | waits for the trade to close and returns the deal       |
+//! | GET    | `/candles`      | query `asset`, `period`, `offset`                       |
+//! | GET    | `/ws`           | WebSocket streaming candles, query `asset`, `period`    |
+//! | GET    | `/heartbeat`    | connection status, last message age, subscriptions, queues |
+//! | GET    | `/heartbeat/ws` | WebSocket streaming heartbeats, query `interval` (seconds) |
+//!
+//! Every request must carry the configured token, either as `Authorization: Bearer <token>`
+//! or as a `token` query parameter for callers that cannot set headers.
+
+mod routes;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use crate::pocketoption::{error::PocketError, PocketOption};
+use crate::utils::constant_time_eq;
+
+/// Settings of the gateway server.
+#[derive(Debug, Clone)]
+pub struct GatewayConfig {
+    /// Address the server listens on, `127.0.0.1:8080` by default.
+    pub addr: SocketAddr,
+    /// Token every request must present.
+    pub token: String,
+}
+
+impl GatewayConfig {
+    pub fn new(token: impl ToString) -> Self {
+        Self {
+            addr: SocketAddr::from(([127, 0, 0, 1], 8080)),
+            token: token.to_string(),
+        }
+    }
+
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct GatewayState {
+    pub(crate) client: PocketOption,
+    token: Arc<str>,
+}
+
+/// Builds the gateway routes, for embedding them into an existing axum application.
+///
+/// Fails with `PocketError::Configuration` if `token` is empty or only whitespace, which would
+/// let every request through.
+pub fn router(client: PocketOption, token: impl AsRef<str>) -> Result<Router, PocketError> {
+    if token.as_ref().trim().is_empty() {
+        return Err(PocketError::Configuration(
+            "The gateway token must not be empty".into(),
+        ));
+    }
+    let state = GatewayState {
+        client,
+        token: Arc::from(token.as_ref()),
+    };
+    Ok(Router::new()
+        .route("/balance", get(routes::balance))
+        .route("/trade", post(routes::trade))
+        .route("/result/{id}", get(routes::result))
+        .route("/candles", get(routes::candles))
+        .route("/ws", get(routes::stream))
+        .route("/heartbeat", get(routes::heartbeat))
+        .route("/heartbeat/ws", get(routes::heartbeat_stream))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state))
+}
+
+/// Serves the gateway on `config.addr` until the client shuts down or the task is dropped.
+pub async fn serve(client: PocketOption, config: GatewayConfig) -> Result<(), PocketError> {
+    let router = router(client, &config.token)?;
+    let listener = tokio::net::TcpListener::bind(config.addr)
+        .await
+        .map_err(|e| PocketError::Configuration(format!("Failed to bind {}: {e}", config.addr)))?;
+    info!(target: "Gateway", "Listening on {}", config.addr);
+    axum::serve(listener, router)
+        .await
+        .map_err(|e| PocketError::General(format!("Gateway server failed: {e}")))
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+async fn authorize(
+    State(state): State<GatewayState>,
+    Query(query): Query<TokenQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let provided = bearer.or(query.token.as_deref());
+    match provided {
+        Some(token) if constant_time_eq(&state.token, token) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid token" })),
+        )
+            .into_response(),
+    }
+}
+
+/// Client errors rendered as JSON with a matching HTTP status.
+pub(crate) struct GatewayError(PocketError);
+
+impl From<PocketError> for GatewayError {
+    fn from(e: PocketError) -> Self {
+        Self(e)
+    }
+}
+
+impl GatewayError {
+    fn status(&self) -> StatusCode {
+        match &self.0 {
+            PocketError::InvalidAsset(_)
+            | PocketError::InvalidPeriod(_)
+            | PocketError::AmountOutOfRange { .. }
+            | PocketError::AssetInactive(_)
+            | PocketError::PayoutTooLow { .. }
+            | PocketError::EarlyCloseRejected { .. } => StatusCode::BAD_REQUEST,
+            PocketError::DealNotFound(_) => StatusCode::NOT_FOUND,
+            PocketError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            PocketError::ShuttingDown(_)
+            | PocketError::ConnectionLost(_)
+            | PocketError::NotAuthorized(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        let body = json!({ "error": self.0.to_string(), "code": self.0.code() });
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::pocketoption::fixtures::{self, MOCK_SSID};
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+
+    #[test]
+    fn test_error_status() {
+        let timeout = GatewayError::from(PocketError::Timeout {
+            task: "trade".into(),
+            context: "gateway".into(),
+            duration: Duration::from_secs(5),
+        });
+        assert_eq!(timeout.status(), StatusCode::GATEWAY_TIMEOUT);
+        let asset = GatewayError::from(PocketError::InvalidAsset("FOO".into()));
+        assert_eq!(asset.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_router_rejects_empty_token() {
+        let server = fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)]);
+        let config = Config {
+            connection_initialization_timeout: Duration::from_secs(10),
+            ..Config::default()
+        };
+        let client = PocketOption::new_with_connector(MOCK_SSID, server, config)
+            .await
+            .unwrap();
+        for token in ["", "  "] {
+            assert!(matches!(
+                router(client.clone(), token),
+                Err(PocketError::Configuration(_))
+            ));
+        }
+        assert!(router(client.clone(), "secret").is_ok());
+        client.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    response::Response,
+    Json,
+};
+use futures_util::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::debug;
+use uuid::Uuid;
+
+use super::{GatewayError, GatewayState};
+use crate::pocketoption::{
+    candle::{Candle, SubscriptionType},
+    error::PocketError,
+    modules::subscriptions::SubscriptionStream,
+    stats::Heartbeat,
+    types::{Action, Deal},
+};
+
+/// Default seconds between two heartbeats of `/heartbeat/ws`.
+const DEFAULT_HEARTBEAT_INTERVAL: u64 = 5;
+
+#[derive(Deserialize)]
+pub(crate) struct TradeRequest {
+    asset: String,
+    action: Action,
+    amount: Decimal,
+    /// Expiration in seconds.
+    time: u32,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ResultQuery {
+    /// Seconds to wait for the trade to close before answering with a timeout.
+    timeout: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CandlesQuery {
+    asset: String,
+    period: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StreamQuery {
+    asset: String,
+    /// Candle length in seconds, every price update is sent when missing.
+    period: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct HeartbeatQuery {
+    /// Seconds between two heartbeats, at least one.
+    interval: Option<u64>,
+}
+
+pub(crate) async fn balance(State(state): State<GatewayState>) -> Json<Value> {
+    Json(json!({ "balance": state.client.balance().await }))
+}
+
+pub(crate) async fn trade(
+    State(state): State<GatewayState>,
+    Json(request): Json<TradeRequest>,
+) -> Result<Json<Value>, GatewayError> {
+    let (id, deal) = state
+        .client
+        .trade(request.asset, request.action, request.time, request.amount)
+        .await?;
+    Ok(Json(json!({ "id": id, "deal": deal })))
+}
+
+pub(crate) async fn result(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ResultQuery>,
+) -> Result<Json<Deal>, GatewayError> {
+    let deal = match query.timeout {
+        Some(secs) => {
+            state
+                .client
+                .result_with_timeout(id, Duration::from_secs(secs))
+                .await?
+        }
+        None => state.client.result(id).await?,
+    };
+    Ok(Json(deal))
+}
+
+pub(crate) async fn candles(
+    State(state): State<GatewayState>,
+    Query(query): Query<CandlesQuery>,
+) -> Result<Json<Vec<Candle>>, GatewayError> {
+    Ok(Json(
+        state
+            .client
+            .get_candles(query.asset, query.period, query.offset)
+            .await?,
+    ))
+}
+
+pub(crate) async fn stream(
+    State(state): State<GatewayState>,
+    Query(query): Query<StreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, GatewayError> {
+    let sub_type = match query.period {
+        Some(secs) => SubscriptionType::time_aligned(Duration::from_secs(secs))?,
+        None => SubscriptionType::none(),
+    };
+    let subscription = state.client.subscribe(query.asset, sub_type).await?;
+    Ok(ws.on_upgrade(move |socket| forward_candles(socket, subscription)))
+}
+
+/// Sends every candle of `subscription` as a JSON text frame until either side closes.
+///
+/// A stream error is sent as `{"error", "code"}` before the socket is closed.
+async fn forward_candles(mut socket: WebSocket, mut subscription: SubscriptionStream) {
+    loop {
+        tokio::select! {
+            candle = subscription.receive() => {
+                let (frame, failed) = match candle {
+                    Ok(candle) => (json!(candle), false),
+                    Err(e) => (stream_error(&e), true),
+                };
+                if socket.send(Message::Text(frame.to_string().into())).await.is_err() || failed {
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    if let Err(e) = subscription.unsubscribe().await {
+        debug!(target: "Gateway", "Failed to unsubscribe from {}: {}", subscription.asset(), e);
+    }
+}
+
+pub(crate) async fn heartbeat(State(state): State<GatewayState>) -> Json<Heartbeat> {
+    Json(state.client.heartbeat_now().await)
+}
+
+pub(crate) async fn heartbeat_stream(
+    State(state): State<GatewayState>,
+    Query(query): Query<HeartbeatQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let secs = query.interval.unwrap_or(DEFAULT_HEARTBEAT_INTERVAL).max(1);
+    let heartbeats = state.client.heartbeat(Duration::from_secs(secs));
+    ws.on_upgrade(move |socket| forward_heartbeats(socket, heartbeats))
+}
+
+/// Sends every heartbeat as a JSON text frame until the caller closes the socket.
+async fn forward_heartbeats(
+    mut socket: WebSocket,
+    heartbeats: impl Stream<Item = Heartbeat> + Send + 'static,
+) {
+    let mut heartbeats = std::pin::pin!(heartbeats);
+    loop {
+        tokio::select! {
+            heartbeat = heartbeats.next() => {
+                let Some(heartbeat) = heartbeat else { break };
+                let frame = json!(heartbeat).to_string();
+                if socket.send(Message::Text(frame.into())).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn stream_error(e: &PocketError) -> Value {
+    json!({ "error": e.to_string(), "code": e.code() })
+}
@@ -0,0 +1,267 @@
+//! gRPC service exposing a [`PocketOption`] client, enabled with the `grpc` feature.
+//!
+//! The service is defined in `proto/pocketoption.proto`. It lets remote bots trade through a
+//! single process that owns the websocket connection, for example:
+//!
+//! ```no_run
+//! # async fn run(client: binary_options_tools::pocketoption::PocketOption) -> Result<(), Box<dyn std::error::Error>> {
+//! binary_options_tools::grpc::serve(client, "127.0.0.1:50051".parse()?, Some("token".into())).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures_util::{stream, Stream};
+use rust_decimal::Decimal;
+use tonic::{metadata::MetadataValue, Request, Response, Status};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::pocketoption::{
+    candle::{Candle, SubscriptionType},
+    error::PocketError,
+    types::{Action, Deal},
+    PocketOption,
+};
+use crate::utils::constant_time_eq;
+
+/// Types generated from `proto/pocketoption.proto`.
+pub mod proto {
+    tonic::include_proto!("pocketoption.v1");
+}
+
+use proto::pocket_option_server::{PocketOption as PocketOptionRpc, PocketOptionServer};
+
+/// Implementation of the `pocketoption.v1.PocketOption` service.
+#[derive(Clone)]
+pub struct GrpcService {
+    client: PocketOption,
+}
+
+impl GrpcService {
+    pub fn new(client: PocketOption) -> Self {
+        Self { client }
+    }
+}
+
+/// Serves the service on `addr`. When `token` is set, calls without a matching
+/// `authorization: Bearer <token>` metadata entry are rejected as `UNAUTHENTICATED`.
+pub async fn serve(
+    client: PocketOption,
+    addr: SocketAddr,
+    token: Option<String>,
+) -> Result<(), PocketError> {
+    let service = PocketOptionServer::with_interceptor(
+        GrpcService::new(client),
+        move |request: Request<()>| authorize(token.as_deref(), request),
+    );
+    info!(target: "Grpc", "Listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve(addr)
+        .await
+        .map_err(|e| PocketError::General(format!("gRPC server failed: {e}")))
+}
+
+fn authorize(token: Option<&str>, request: Request<()>) -> Result<Request<()>, Status> {
+    let Some(token) = token else {
+        return Ok(request);
+    };
+    let provided = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match provided {
+        Some(provided) if constant_time_eq(token, provided) => Ok(request),
+        _ => Err(Status::unauthenticated("Missing or invalid token")),
+    }
+}
+
+/// Maps client errors to gRPC statuses, the stable error code is sent in `x-error-code`.
+fn to_status(e: PocketError) -> Status {
+    let code = e.code();
+    let mut status = match &e {
+        PocketError::InvalidAsset(_)
+        | PocketError::InvalidPeriod(_)
+        | PocketError::AmountOutOfRange { .. } => Status::invalid_argument(e.to_string()),
+        PocketError::AssetInactive(_)
+        | PocketError::PayoutTooLow { .. }
+        | PocketError::EarlyCloseRejected { .. } => Status::failed_precondition(e.to_string()),
+        PocketError::DealNotFound(_) => Status::not_found(e.to_string()),
+        PocketError::Timeout { .. } => Status::deadline_exceeded(e.to_string()),
+        PocketError::ShuttingDown(_)
+        | PocketError::ConnectionLost(_)
+        | PocketError::NotAuthorized(_) => Status::unavailable(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    };
+    status
+        .metadata_mut()
+        .insert("x-error-code", MetadataValue::from(code));
+    status
+}
+
+fn parse_decimal(field: &str, value: &str) -> Result<Decimal, Status> {
+    Decimal::from_str(value)
+        .map_err(|e| Status::invalid_argument(format!("Invalid {field} {value:?}: {e}")))
+}
+
+fn parse_id(value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value).map_err(|e| Status::invalid_argument(format!("Invalid id: {e}")))
+}
+
+impl From<Deal> for proto::Deal {
+    fn from(deal: Deal) -> Self {
+        let action = if deal.command == 0 {
+            proto::Action::Call
+        } else {
+            proto::Action::Put
+        };
+        Self {
+            id: deal.id.to_string(),
+            asset: deal.asset,
+            action: action.into(),
+            amount: deal.amount.to_string(),
+            profit: deal.profit.to_string(),
+            open_price: deal.open_price.to_string(),
+            close_price: deal.close_price.to_string(),
+            open_timestamp: deal.open_timestamp.timestamp(),
+            close_timestamp: deal.close_timestamp.timestamp(),
+            currency: deal.currency,
+        }
+    }
+}
+
+impl From<Candle> for proto::Candle {
+    fn from(candle: Candle) -> Self {
+        Self {
+            symbol: candle.symbol,
+            timestamp: candle.timestamp,
+            open: candle.open.to_string(),
+            high: candle.high.to_string(),
+            low: candle.low.to_string(),
+            close: candle.close.to_string(),
+        }
+    }
+}
+
+type CandleStream = Pin<Box<dyn Stream<Item = Result<proto::Candle, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl PocketOptionRpc for GrpcService {
+    async fn balance(
+        &self,
+        _request: Request<proto::BalanceRequest>,
+    ) -> Result<Response<proto::BalanceReply>, Status> {
+        Ok(Response::new(proto::BalanceReply {
+            balance: self.client.balance().await.to_string(),
+            currency: self.client.currency().await.unwrap_or_default(),
+        }))
+    }
+
+    async fn trade(
+        &self,
+        request: Request<proto::TradeRequest>,
+    ) -> Result<Response<proto::TradeReply>, Status> {
+        let request = request.into_inner();
+        let action = match request.action() {
+            proto::Action::Call => Action::Call,
+            proto::Action::Put => Action::Put,
+        };
+        let amount = parse_decimal("amount", &request.amount)?;
+        let (id, deal) = self
+            .client
+            .trade(request.asset, action, request.time, amount)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(proto::TradeReply {
+            id: id.to_string(),
+            deal: Some(deal.into()),
+        }))
+    }
+
+    async fn result(
+        &self,
+        request: Request<proto::ResultRequest>,
+    ) -> Result<Response<proto::Deal>, Status> {
+        let request = request.into_inner();
+        let id = parse_id(&request.id)?;
+        let deal = match request.timeout_secs {
+            0 => self.client.result(id).await,
+            secs => {
+                self.client
+                    .result_with_timeout(id, Duration::from_secs(secs))
+                    .await
+            }
+        }
+        .map_err(to_status)?;
+        Ok(Response::new(deal.into()))
+    }
+
+    type SubscribeStream = CandleStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let request = request.into_inner();
+        let sub_type = match request.period_secs {
+            0 => SubscriptionType::none(),
+            secs => SubscriptionType::time_aligned(Duration::from_secs(secs)).map_err(to_status)?,
+        };
+        let subscription = self
+            .client
+            .subscribe(request.asset, sub_type)
+            .await
+            .map_err(to_status)?;
+        // Ends the stream after the first error instead of repeating it forever.
+        let candles = stream::unfold(Some(subscription), |subscription| async move {
+            let mut subscription = subscription?;
+            match subscription.receive().await {
+                Ok(candle) => Some((Ok(candle.into()), Some(subscription))),
+                Err(e) => Some((Err(to_status(e)), None)),
+            }
+        });
+        Ok(Response::new(Box::pin(candles)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize() {
+        let request = |token: &str| {
+            let mut request = Request::new(());
+            request
+                .metadata_mut()
+                .insert("authorization", format!("Bearer {token}").parse().unwrap());
+            request
+        };
+        assert!(authorize(Some("secret"), request("secret")).is_ok());
+        assert!(authorize(Some("secret"), request("wrong")).is_err());
+        assert!(authorize(Some("secret"), Request::new(())).is_err());
+        assert!(authorize(None, Request::new(())).is_ok());
+    }
+
+    #[test]
+    fn test_error_status_carries_code() {
+        let status = to_status(PocketError::InvalidAsset("FOO".into()));
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        let code = PocketError::InvalidAsset("FOO".into()).code().to_string();
+        assert_eq!(
+            status
+                .metadata()
+                .get("x-error-code")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            code
+        );
+    }
+}
@@ -9,9 +9,21 @@
 //!
 //! - `pocketoption` - Integration with PocketOption platform
 //! - `expertoptions` - Integration with ExpertOption platform  
+//! - `quotex` - Integration with Quotex platform
+//! - `deriv` - Integration with the Deriv (binary.com) WebSocket API
 //! - `reimports` - Common re-exports for convenience
 //! - `error` - Error handling types and utilities
 //! - `stream` - Streaming utilities including receiver streams and logging layers
+//! - `reconnect` - Reconnection policy and reconnect events
+//! - `status` - Connection lifecycle status
+//! - `gateway` - HTTP/WebSocket gateway exposing a client to other tools (`gateway` feature)
+//! - `grpc` - gRPC service for remote control of a client (`grpc` feature)
+//! - `signals` - Trade signals from external sources such as TradingView alerts
+//! - `publish` - Mirrors candles, balance and closed deals to MQTT or Redis (`mqtt` / `redis` features)
+//! - `copytrading` - Replicates the deals of a leader account on follower accounts
+//! - `analytics` - Volatility and spread estimates computed from the tick and candle streams
+//! - `arbitrage` - Cross-broker price divergence scanner emitting payout-adjusted opportunities
+//! - `clock` - Clock abstraction with a controllable `SimClock` for tests and backtests
 //!
 //! ## Features
 //!
@@ -26,18 +38,49 @@
 //! - Use the streaming utilities for real-time data processing
 //! - Serialize and deserialize data with the provided macros
 //! - Apply timeouts to async operations
+pub mod analytics;
+pub mod arbitrage;
+pub mod clock;
 pub mod config;
+pub mod copytrading;
+pub mod deriv;
 pub mod error;
 pub mod expertoptions;
 pub mod framework;
+#[cfg(feature = "gateway")]
+pub mod gateway;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod pocketoption;
+pub mod publish;
+pub mod quotex;
+pub mod signals;
+pub mod timezone;
 pub mod traits;
 pub mod utils;
 pub mod validator;
 pub mod stream {
     pub use binary_options_tools_core::reimports::*;
     pub use binary_options_tools_core::utils::stream::RecieverStream;
-    pub use binary_options_tools_core::utils::tracing::stream_logs_layer;
+    pub use binary_options_tools_core::utils::tracing::{
+        log_redaction, redact_logs, redacted, set_log_redaction, stream_logs_layer, target_filter,
+        Redacted, RotatingFile, Rotation,
+    };
+}
+pub mod middleware {
+    pub use binary_options_tools_core::error::CoreResult;
+    pub use binary_options_tools_core::middleware::{MiddlewareContext, WebSocketMiddleware};
+}
+pub mod reconnect {
+    pub use binary_options_tools_core::reconnect::{
+        LimitAction, LoopDiagnostics, ReconnectEvent, ReconnectPolicy,
+    };
+}
+pub mod status {
+    pub use binary_options_tools_core::status::ConnectionStatus;
+}
+pub mod supervisor {
+    pub use binary_options_tools_core::supervisor::{ModuleExit, RestartPolicy};
 }
 
 #[cfg(test)]
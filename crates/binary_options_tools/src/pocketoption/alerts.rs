@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::pocketoption::modules::subscriptions::SubscriptionStream;
+
+/// Events kept for receivers that fall behind before they get `RecvError::Lagged`.
+const EVENTS_CAPACITY: usize = 256;
+
+/// Price level an alert watches for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "price", rename_all = "snake_case")]
+pub enum Condition {
+    /// The price moves from at or below the level to above it. The first tick only sets the
+    /// starting side, so an asset already trading above the level does not fire.
+    CrossesAbove(Decimal),
+    /// The price moves from at or above the level to below it.
+    CrossesBelow(Decimal),
+    /// The price is above the level, fires on the first tick if it already is.
+    Above(Decimal),
+    /// The price is below the level, fires on the first tick if it already is.
+    Below(Decimal),
+}
+
+impl Condition {
+    pub fn level(&self) -> Decimal {
+        match self {
+            Self::CrossesAbove(level)
+            | Self::CrossesBelow(level)
+            | Self::Above(level)
+            | Self::Below(level) => *level,
+        }
+    }
+
+    fn is_met(&self, price: Decimal) -> bool {
+        match self {
+            Self::CrossesAbove(level) | Self::Above(level) => price > *level,
+            Self::CrossesBelow(level) | Self::Below(level) => price < *level,
+        }
+    }
+
+    fn requires_cross(&self) -> bool {
+        matches!(self, Self::CrossesAbove(_) | Self::CrossesBelow(_))
+    }
+}
+
+/// Whether an alert is removed once it fired.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMode {
+    /// Fires once, then is removed.
+    #[default]
+    Once,
+    /// Fires every time the condition becomes true again.
+    Repeat,
+}
+
+/// An alert registered with [`PocketOption::add_alert`](crate::pocketoption::PocketOption::add_alert).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    pub id: Uuid,
+    pub asset: String,
+    pub condition: Condition,
+    pub mode: AlertMode,
+}
+
+/// Emitted when the condition of an alert fires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AlertEvent {
+    pub alert_id: Uuid,
+    pub asset: String,
+    pub condition: Condition,
+    /// Price of the tick that fired the alert.
+    pub price: Decimal,
+    /// Server time of that tick.
+    pub timestamp: DateTime<Utc>,
+    /// Whether the alert stays registered after this event.
+    pub repeat: bool,
+}
+
+#[derive(Debug)]
+struct Entry {
+    alert: Alert,
+    /// Whether the condition held on the previous tick, `None` before the first one.
+    was_met: Option<bool>,
+}
+
+impl Entry {
+    /// Records `price` and returns whether the alert fires on it.
+    fn update(&mut self, price: Decimal) -> bool {
+        let met = self.alert.condition.is_met(price);
+        let fires = met
+            && match self.was_met {
+                Some(was_met) => !was_met,
+                None => !self.alert.condition.requires_cross(),
+            };
+        self.was_met = Some(met);
+        fires
+    }
+}
+
+#[derive(Debug, Default)]
+struct Book {
+    alerts: HashMap<Uuid, Entry>,
+    /// Assets with a running watcher.
+    watched: HashSet<String>,
+}
+
+/// Evaluates price alerts against the subscription tick stream of their asset.
+///
+/// One watcher task per asset holds a tick subscription for as long as the asset has alerts,
+/// so every tick is checked in Rust and only the fired alerts reach the caller as
+/// [`AlertEvent`]s. The watchers stop when the last client handle is dropped.
+#[derive(Clone)]
+pub(crate) struct PriceAlerts {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    book: Arc<Mutex<Book>>,
+    events: broadcast::Sender<AlertEvent>,
+    // Dropping the set aborts the watchers.
+    watchers: std::sync::Mutex<JoinSet<()>>,
+}
+
+impl Default for PriceAlerts {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                book: Arc::new(Mutex::new(Book::default())),
+                events: broadcast::channel(EVENTS_CAPACITY).0,
+                watchers: std::sync::Mutex::new(JoinSet::new()),
+            }),
+        }
+    }
+}
+
+impl PriceAlerts {
+    /// Registers an alert, `subscribe` is only called when `asset` has no watcher yet.
+    pub(crate) async fn add<F, Fut, E>(
+        &self,
+        asset: String,
+        condition: Condition,
+        mode: AlertMode,
+        subscribe: F,
+    ) -> Result<Uuid, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<SubscriptionStream, E>>,
+    {
+        let mut book = self.inner.book.lock().await;
+        if !book.watched.contains(&asset) {
+            let stream = subscribe().await?;
+            let mut watchers = self.inner.watchers.lock().expect("watchers lock poisoned");
+            while watchers.try_join_next().is_some() {}
+            watchers.spawn(watch(
+                stream,
+                self.inner.book.clone(),
+                self.inner.events.clone(),
+            ));
+            book.watched.insert(asset.clone());
+        }
+        let id = Uuid::new_v4();
+        let alert = Alert {
+            id,
+            asset,
+            condition,
+            mode,
+        };
+        book.alerts.insert(
+            id,
+            Entry {
+                alert,
+                was_met: None,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Removes an alert, returns `false` if it already fired or never existed.
+    pub(crate) async fn remove(&self, id: Uuid) -> bool {
+        self.inner.book.lock().await.alerts.remove(&id).is_some()
+    }
+
+    pub(crate) async fn list(&self) -> Vec<Alert> {
+        self.inner
+            .book
+            .lock()
+            .await
+            .alerts
+            .values()
+            .map(|entry| entry.alert.clone())
+            .collect()
+    }
+
+    pub(crate) fn events(&self) -> broadcast::Receiver<AlertEvent> {
+        self.inner.events.subscribe()
+    }
+}
+
+/// Checks the alerts of the stream's asset on every tick until none are left.
+async fn watch(
+    mut stream: SubscriptionStream,
+    book: Arc<Mutex<Book>>,
+    events: broadcast::Sender<AlertEvent>,
+) {
+    let asset = stream.asset().to_string();
+    loop {
+        // The watcher only ends when the alerts are gone, an idle market must not stop it.
+        let tick = stream.receive_with_timeout(Duration::MAX).await;
+        let mut book = book.lock().await;
+        let candle = match tick {
+            Ok(candle) => candle,
+            Err(e) => {
+                warn!(target: "PriceAlerts", "Dropping the alerts of {}: {}", asset, e);
+                book.alerts.retain(|_, entry| entry.alert.asset != asset);
+                book.watched.remove(&asset);
+                return;
+            }
+        };
+        let timestamp = candle.datetime();
+        let mut fired = Vec::new();
+        for entry in book.alerts.values_mut() {
+            if entry.alert.asset == asset && entry.update(candle.close) {
+                fired.push(AlertEvent {
+                    alert_id: entry.alert.id,
+                    asset: asset.clone(),
+                    condition: entry.alert.condition,
+                    price: candle.close,
+                    timestamp,
+                    repeat: entry.alert.mode == AlertMode::Repeat,
+                });
+            }
+        }
+        for event in fired {
+            if !event.repeat {
+                book.alerts.remove(&event.alert_id);
+            }
+            // No receiver is fine, the alert still fired.
+            let _ = events.send(event);
+        }
+        if !book.alerts.values().any(|entry| entry.alert.asset == asset) {
+            debug!(target: "PriceAlerts", "No alerts left for {}, unsubscribing", asset);
+            book.watched.remove(&asset);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn entry(condition: Condition) -> Entry {
+        Entry {
+            alert: Alert {
+                id: Uuid::new_v4(),
+                asset: "EURUSD_otc".into(),
+                condition,
+                mode: AlertMode::Repeat,
+            },
+            was_met: None,
+        }
+    }
+
+    #[test]
+    fn test_crosses_above_needs_a_cross() {
+        let mut alert = entry(Condition::CrossesAbove(dec!(1.1)));
+        assert!(!alert.update(dec!(1.2)));
+        assert!(!alert.update(dec!(1.0)));
+        assert!(!alert.update(dec!(1.1)));
+        assert!(alert.update(dec!(1.15)));
+        assert!(!alert.update(dec!(1.2)));
+        assert!(!alert.update(dec!(1.05)));
+        assert!(alert.update(dec!(1.11)));
+    }
+
+    #[test]
+    fn test_below_fires_on_first_tick() {
+        let mut alert = entry(Condition::Below(dec!(1.1)));
+        assert!(alert.update(dec!(1.0)));
+        assert!(!alert.update(dec!(1.05)));
+        assert!(!alert.update(dec!(1.2)));
+        assert!(alert.update(dec!(1.09)));
+    }
+
+    #[test]
+    fn test_condition_serialization() {
+        let json = serde_json::to_string(&Condition::CrossesBelow(dec!(1.5))).unwrap();
+        assert_eq!(json, r#"{"type":"crosses_below","price":"1.5"}"#);
+    }
+}
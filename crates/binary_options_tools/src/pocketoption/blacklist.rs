@@ -0,0 +1,233 @@
+//! Temporary blacklisting of server URLs that keep failing, so reconnects stop waiting on a
+//! dead region before reaching a working one.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When a server URL is skipped by the connector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlacklistConfig {
+    /// Consecutive failures after which the URL is blacklisted, `0` disables the blacklist.
+    pub threshold: u32,
+    /// Cooldown of the first blacklisting, doubled each time the URL is blacklisted again
+    /// without a successful connection in between.
+    pub cooldown: Duration,
+    /// Upper bound for the cooldown.
+    pub max_cooldown: Duration,
+}
+
+impl Default for BlacklistConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 2,
+            cooldown: Duration::from_secs(30),
+            max_cooldown: Duration::from_secs(600),
+        }
+    }
+}
+
+/// A URL currently skipped by the connector, see [`UrlBlacklist::blacklisted`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlacklistedUrl {
+    pub url: String,
+    /// Time left before the URL is tried first again.
+    pub remaining: Duration,
+    /// Times the URL was blacklisted since its last successful connection.
+    pub strikes: u32,
+}
+
+#[derive(Debug, Default)]
+struct UrlRecord {
+    consecutive_failures: u32,
+    strikes: u32,
+    until: Option<Instant>,
+}
+
+/// Failures of every server URL, shared across reconnects.
+#[derive(Debug, Default)]
+pub struct UrlBlacklist {
+    config: BlacklistConfig,
+    records: Mutex<HashMap<String, UrlRecord>>,
+}
+
+impl UrlBlacklist {
+    pub fn new(config: BlacklistConfig) -> Self {
+        Self {
+            config,
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn records(&self) -> std::sync::MutexGuard<'_, HashMap<String, UrlRecord>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Forgets the failures of `url` after a successful connection.
+    pub fn record_success(&self, url: &str) {
+        self.records().remove(url);
+    }
+
+    /// Records a failed connection to `url`, returns the cooldown if it got blacklisted.
+    pub fn record_failure(&self, url: &str) -> Option<Duration> {
+        self.record_failure_at(url, Instant::now())
+    }
+
+    fn record_failure_at(&self, url: &str, now: Instant) -> Option<Duration> {
+        if self.config.threshold == 0 {
+            return None;
+        }
+        let mut records = self.records();
+        let record = records.entry(url.to_string()).or_default();
+        record.consecutive_failures += 1;
+        if record.consecutive_failures < self.config.threshold {
+            return None;
+        }
+        let factor = 2u32.saturating_pow(record.strikes);
+        let cooldown = self
+            .config
+            .cooldown
+            .saturating_mul(factor)
+            .min(self.config.max_cooldown);
+        record.consecutive_failures = 0;
+        record.strikes += 1;
+        record.until = Some(now + cooldown);
+        Some(cooldown)
+    }
+
+    /// Whether `url` is in its cooldown.
+    pub fn is_blacklisted(&self, url: &str) -> bool {
+        self.remaining_at(url, Instant::now()).is_some()
+    }
+
+    fn remaining_at(&self, url: &str, now: Instant) -> Option<Duration> {
+        let until = self.records().get(url)?.until?;
+        until
+            .checked_duration_since(now)
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// `urls` without duplicates, in their order, with the blacklisted ones moved to the end
+    /// by end of cooldown so they are still tried when every other URL fails.
+    pub fn order(&self, urls: impl IntoIterator<Item = String>) -> Vec<String> {
+        self.order_at(urls, Instant::now())
+    }
+
+    fn order_at(&self, urls: impl IntoIterator<Item = String>, now: Instant) -> Vec<String> {
+        let mut available = Vec::new();
+        let mut cooling = Vec::new();
+        for url in urls {
+            if available.contains(&url) || cooling.iter().any(|(u, _)| u == &url) {
+                continue;
+            }
+            match self.remaining_at(&url, now) {
+                Some(remaining) => cooling.push((url, remaining)),
+                None => available.push(url),
+            }
+        }
+        cooling.sort_by_key(|(_, remaining)| *remaining);
+        available.extend(cooling.into_iter().map(|(url, _)| url));
+        available
+    }
+
+    /// URLs in their cooldown, the shortest remaining first.
+    pub fn blacklisted(&self) -> Vec<BlacklistedUrl> {
+        let now = Instant::now();
+        let mut urls: Vec<BlacklistedUrl> = self
+            .records()
+            .iter()
+            .filter_map(|(url, record)| {
+                let until = record.until.filter(|until| *until > now)?;
+                Some(BlacklistedUrl {
+                    url: url.clone(),
+                    remaining: until - now,
+                    strikes: record.strikes,
+                })
+            })
+            .collect();
+        urls.sort_by_key(|url| url.remaining);
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EU: &str = "wss://api-eu.po.market";
+    const US: &str = "wss://api-us-south.po.market";
+
+    fn urls() -> Vec<String> {
+        vec![EU.to_string(), US.to_string(), EU.to_string()]
+    }
+
+    #[test]
+    fn test_blacklist_with_exponential_cooldown() {
+        let blacklist = UrlBlacklist::new(BlacklistConfig {
+            threshold: 2,
+            cooldown: Duration::from_secs(10),
+            max_cooldown: Duration::from_secs(25),
+        });
+        let now = Instant::now();
+        assert_eq!(blacklist.record_failure_at(EU, now), None);
+        assert_eq!(blacklist.order_at(urls(), now), vec![EU, US]);
+
+        assert_eq!(
+            blacklist.record_failure_at(EU, now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(blacklist.order_at(urls(), now), vec![US, EU]);
+        assert_eq!(
+            blacklist.remaining_at(EU, now),
+            Some(Duration::from_secs(10))
+        );
+
+        // The cooldown is over, the URL is tried first again.
+        let later = now + Duration::from_secs(11);
+        assert_eq!(blacklist.order_at(urls(), later), vec![EU, US]);
+
+        // Failing again doubles the cooldown, up to the maximum.
+        blacklist.record_failure_at(EU, later);
+        assert_eq!(
+            blacklist.record_failure_at(EU, later),
+            Some(Duration::from_secs(20))
+        );
+        blacklist.record_failure_at(EU, later);
+        assert_eq!(
+            blacklist.record_failure_at(EU, later),
+            Some(Duration::from_secs(25))
+        );
+
+        blacklist.record_success(EU);
+        assert_eq!(blacklist.order_at(urls(), later), vec![EU, US]);
+        assert!(blacklist.blacklisted().is_empty());
+    }
+
+    #[test]
+    fn test_blacklisted_urls_keep_their_cooldown_order() {
+        let blacklist = UrlBlacklist::new(BlacklistConfig {
+            threshold: 1,
+            ..BlacklistConfig::default()
+        });
+        blacklist.record_failure(EU);
+        blacklist.record_failure(EU);
+        blacklist.record_failure(US);
+        assert!(blacklist.is_blacklisted(EU));
+        assert_eq!(blacklist.order(urls()), vec![US, EU]);
+        let listed = blacklist.blacklisted();
+        assert_eq!(listed[0].url, US);
+        assert_eq!(listed[1].strikes, 2);
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_the_blacklist() {
+        let blacklist = UrlBlacklist::new(BlacklistConfig {
+            threshold: 0,
+            ..BlacklistConfig::default()
+        });
+        for _ in 0..5 {
+            assert_eq!(blacklist.record_failure(EU), None);
+        }
+        assert!(!blacklist.is_blacklisted(EU));
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::items_after_test_module)]
 
+use std::collections::VecDeque;
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
@@ -40,6 +41,10 @@ pub struct Candle {
     pub volume: Option<Decimal>,
     /// Whether this candle is closed/finalized
     pub is_closed: bool,
+    /// Made up by the client to fill a gap of a live stream, see
+    /// [`GapFill`](crate::pocketoption::modules::subscriptions::GapFill)
+    #[serde(default)]
+    pub synthetic: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -229,6 +234,7 @@ impl Candle {
             close: price,
             volume: None, // PocketOption doesn't provide volume
             is_closed: false,
+            synthetic: false,
         })
     }
 
@@ -406,6 +412,7 @@ impl Candle {
     ///
     /// # Returns
     /// New Candle instance with specified values
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_closed_status(
         symbol: String,
         timestamp: i64,
@@ -437,6 +444,7 @@ impl Candle {
                 .ok_or(BinaryOptionsError::General("Couldn't parse close".into()))?,
             volume: volume_decimal,
             is_closed,
+            synthetic: false,
         })
     }
 }
@@ -765,6 +773,117 @@ impl From<(i64, f64)> for BaseCandle {
     }
 }
 
+/// Candles stored column by column, e.g. to build a pandas or polars dataframe in one step.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CandleColumns {
+    pub timestamp: Vec<i64>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+    pub volume: Vec<Option<f64>>,
+}
+
+impl CandleColumns {
+    pub fn len(&self) -> usize {
+        self.timestamp.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_empty()
+    }
+
+    pub fn push(&mut self, candle: &Candle) {
+        self.timestamp.push(candle.timestamp);
+        self.open.push(candle.open.to_f64().unwrap_or_default());
+        self.high.push(candle.high.to_f64().unwrap_or_default());
+        self.low.push(candle.low.to_f64().unwrap_or_default());
+        self.close.push(candle.close.to_f64().unwrap_or_default());
+        self.volume
+            .push(candle.volume.and_then(|volume| volume.to_f64()));
+    }
+}
+
+impl<'a> FromIterator<&'a Candle> for CandleColumns {
+    fn from_iter<I: IntoIterator<Item = &'a Candle>>(candles: I) -> Self {
+        let mut columns = Self::default();
+        for candle in candles {
+            columns.push(candle);
+        }
+        columns
+    }
+}
+
+impl From<&[Candle]> for CandleColumns {
+    fn from(candles: &[Candle]) -> Self {
+        candles.iter().collect()
+    }
+}
+
+/// Rolling window of the last candles of a stream, oldest first.
+///
+/// A candle with the timestamp of the newest one replaces it, so updates of a forming candle
+/// don't push older candles out of the window.
+#[derive(Debug, Clone)]
+pub struct CandleWindow {
+    capacity: usize,
+    candles: VecDeque<Candle>,
+}
+
+impl CandleWindow {
+    /// Window keeping at most `capacity` candles, at least one.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            candles: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.candles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+
+    /// Adds `candle`, dropping the oldest candle when the window is full.
+    pub fn push(&mut self, candle: Candle) {
+        if let Some(last) = self.candles.back_mut() {
+            if last.timestamp == candle.timestamp {
+                *last = candle;
+                return;
+            }
+        }
+        if self.candles.len() == self.capacity {
+            self.candles.pop_front();
+        }
+        self.candles.push_back(candle);
+    }
+
+    /// The newest candle.
+    pub fn latest(&self) -> Option<&Candle> {
+        self.candles.back()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Candle> {
+        self.candles.iter()
+    }
+
+    pub fn columns(&self) -> CandleColumns {
+        self.candles.iter().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.candles.clear();
+    }
+}
+
 impl TryFrom<(BaseCandle, String)> for Candle {
     type Error = BinaryOptionsError;
 
@@ -790,6 +909,7 @@ impl TryFrom<(BaseCandle, String)> for Candle {
                 .ok_or(BinaryOptionsError::General("Couldn't parse close".into()))?,
             volume,
             is_closed: false,
+            synthetic: false,
         })
     }
 }
@@ -1117,6 +1237,50 @@ mod tests {
         assert_eq!(ts, 1000);
         assert_eq!(price, 1.5);
     }
+
+    #[test]
+    fn test_candle_columns() {
+        let candles = compile_candles_from_tuples(
+            &[(60, 1.0), (70, 1.5), (80, 0.5), (120, 2.0)],
+            60,
+            "EURUSD_otc",
+        );
+        let columns = CandleColumns::from(candles.as_slice());
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns.timestamp, vec![60, 120]);
+        assert_eq!(columns.open, vec![1.0, 2.0]);
+        assert_eq!(columns.high, vec![1.5, 2.0]);
+        assert_eq!(columns.low, vec![0.5, 2.0]);
+        assert_eq!(columns.close, vec![0.5, 2.0]);
+        assert!(CandleColumns::default().is_empty());
+    }
+
+    #[test]
+    fn test_candle_window() {
+        let candles = compile_candles_from_tuples(
+            &[(60, 1.0), (120, 2.0), (180, 3.0), (240, 4.0)],
+            60,
+            "EURUSD_otc",
+        );
+        let mut window = CandleWindow::new(3);
+        for candle in candles.iter().cloned() {
+            window.push(candle);
+        }
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.columns().timestamp, vec![120, 180, 240]);
+
+        // An update of the newest candle replaces it.
+        let mut forming = candles[3].clone();
+        forming.close = dec!(4.5);
+        window.push(forming);
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.latest().unwrap().close, dec!(4.5));
+        assert_eq!(window.iter().next().unwrap().timestamp, 120);
+
+        assert_eq!(CandleWindow::new(0).capacity(), 1);
+        window.clear();
+        assert!(window.is_empty());
+    }
 }
 
 /// Compiles raw tick data (timestamp, price tuples) into custom-period candles.
@@ -0,0 +1,561 @@
+//! Long running download of the history of assets into a [`CollectorStore`].
+//!
+//! Each [`CollectorJob`] is an asset and a [`Series`]. The collector asks the store where the
+//! series stops, downloads what is missing up to the current server time in windows of
+//! [`CollectorConfig::window`], appends it to the store, then waits
+//! [`CollectorConfig::interval`] and starts over. Since the store is the only record of the
+//! progress, a collector started again after a restart picks up where the last one stopped.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::error::CoreError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::pocketoption::{
+    candle::{compile_candles_from_tuples, Candle},
+    error::{PocketError, PocketResult},
+    pocket_client::PocketOption,
+};
+
+/// What is downloaded for an asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Series {
+    /// Closed candles of `period` seconds, compiled from the ticks.
+    Candles { period: u32 },
+    /// The raw ticks, as `(timestamp, price)`.
+    Ticks,
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Series::Candles { period } => write!(f, "candles_{period}"),
+            Series::Ticks => write!(f, "ticks"),
+        }
+    }
+}
+
+/// An asset and the series downloaded for it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CollectorJob {
+    pub asset: String,
+    pub series: Series,
+}
+
+impl CollectorJob {
+    pub fn candles(asset: impl ToString, period: u32) -> Self {
+        Self {
+            asset: asset.to_string(),
+            series: Series::Candles { period },
+        }
+    }
+
+    pub fn ticks(asset: impl ToString) -> Self {
+        Self {
+            asset: asset.to_string(),
+            series: Series::Ticks,
+        }
+    }
+}
+
+impl fmt::Display for CollectorJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.asset, self.series)
+    }
+}
+
+/// Where a [`DataCollector`] stores the downloaded data.
+///
+/// Records are appended in timestamp order, so the timestamp of the last record of a job is
+/// where the download of that job resumes.
+#[async_trait]
+pub trait CollectorStore: Send + Sync {
+    /// Timestamp of the last record stored for `job`, `None` if nothing was stored yet.
+    async fn last_timestamp(&self, job: &CollectorJob) -> PocketResult<Option<i64>>;
+
+    async fn store_candles(&self, job: &CollectorJob, candles: &[Candle]) -> PocketResult<()>;
+
+    async fn store_ticks(&self, job: &CollectorJob, ticks: &[(i64, f64)]) -> PocketResult<()>;
+}
+
+/// Stores every job in its own JSON lines file, `<dir>/<asset>/<series>.jsonl`.
+///
+/// Candles are written as [`Candle`] objects, ticks as `[timestamp, price]` arrays.
+pub struct JsonLinesStore {
+    dir: PathBuf,
+}
+
+impl JsonLinesStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self, job: &CollectorJob) -> PathBuf {
+        self.dir
+            .join(&job.asset)
+            .join(format!("{}.jsonl", job.series))
+    }
+
+    async fn append(&self, job: &CollectorJob, lines: Vec<String>) -> PocketResult<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let path = self.path(job);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(CoreError::from)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(CoreError::from)?;
+        let mut data = String::new();
+        // Start on a new line if the last write was cut short.
+        if ends_mid_line(&path).await? {
+            data.push('\n');
+        }
+        data.push_str(&lines.join("\n"));
+        data.push('\n');
+        file.write_all(data.as_bytes())
+            .await
+            .map_err(CoreError::from)?;
+        file.flush().await.map_err(CoreError::from)?;
+        Ok(())
+    }
+}
+
+async fn ends_mid_line(path: &std::path::Path) -> PocketResult<bool> {
+    let mut file = File::open(path).await.map_err(CoreError::from)?;
+    let len = file.metadata().await.map_err(CoreError::from)?.len();
+    if len == 0 {
+        return Ok(false);
+    }
+    file.seek(SeekFrom::Start(len - 1))
+        .await
+        .map_err(CoreError::from)?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last).await.map_err(CoreError::from)?;
+    Ok(last[0] != b'\n')
+}
+
+/// How much of the end of a file is read to find its last line.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+#[async_trait]
+impl CollectorStore for JsonLinesStore {
+    async fn last_timestamp(&self, job: &CollectorJob) -> PocketResult<Option<i64>> {
+        let mut file = match File::open(self.path(job)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(CoreError::from(e).into()),
+        };
+        let len = file.metadata().await.map_err(CoreError::from)?.len();
+        file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))
+            .await
+            .map_err(CoreError::from)?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail).await.map_err(CoreError::from)?;
+        let tail = String::from_utf8_lossy(&tail);
+        // The last line may be cut short by a crash during a write, use the last readable one.
+        for line in tail.lines().rev().filter(|line| !line.trim().is_empty()) {
+            let timestamp = match job.series {
+                Series::Candles { .. } => serde_json::from_str::<Candle>(line)
+                    .ok()
+                    .map(|candle| candle.timestamp),
+                Series::Ticks => serde_json::from_str::<(i64, f64)>(line)
+                    .ok()
+                    .map(|(timestamp, _)| timestamp),
+            };
+            if timestamp.is_some() {
+                return Ok(timestamp);
+            }
+        }
+        Ok(None)
+    }
+
+    async fn store_candles(&self, job: &CollectorJob, candles: &[Candle]) -> PocketResult<()> {
+        let lines = candles
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()
+            .map_err(|e| PocketError::General(format!("Failed to encode candles: {e}")))?;
+        self.append(job, lines).await
+    }
+
+    async fn store_ticks(&self, job: &CollectorJob, ticks: &[(i64, f64)]) -> PocketResult<()> {
+        let lines = ticks
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()
+            .map_err(|e| PocketError::General(format!("Failed to encode ticks: {e}")))?;
+        self.append(job, lines).await
+    }
+}
+
+/// Settings of a [`DataCollector`].
+#[derive(Clone, Debug)]
+pub struct CollectorConfig {
+    /// How far back the download of a job with nothing stored starts.
+    pub backfill: Duration,
+    /// Longest range of history requested at once.
+    pub window: Duration,
+    /// Pause between two passes over the jobs once they caught up.
+    pub interval: Duration,
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            backfill: Duration::from_secs(24 * 60 * 60),
+            window: Duration::from_secs(60 * 60),
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl CollectorConfig {
+    pub fn with_backfill(mut self, backfill: Duration) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// Progress of a job, sent by [`DataCollector::events`].
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CollectorEvent {
+    /// `records` were stored, the job is downloaded up to `until`.
+    Stored {
+        job: String,
+        records: usize,
+        until: DateTime<Utc>,
+    },
+    /// The job is downloaded up to the server time `until`.
+    CaughtUp { job: String, until: DateTime<Utc> },
+    /// A download or write failed, it is retried on the next pass.
+    Failed { job: String, error: String },
+}
+
+/// Downloads the history of a list of jobs into a store, see the [module docs](self).
+///
+/// Created by [`PocketOption::collect_data`]. Dropping the collector stops it.
+pub struct DataCollector {
+    events: broadcast::Sender<CollectorEvent>,
+    progress: Arc<RwLock<HashMap<CollectorJob, i64>>>,
+    task: JoinHandle<()>,
+}
+
+impl DataCollector {
+    pub(crate) fn start(
+        client: PocketOption,
+        jobs: Vec<CollectorJob>,
+        store: Arc<dyn CollectorStore>,
+        config: CollectorConfig,
+    ) -> Self {
+        let (events, _) = broadcast::channel(256);
+        let progress = Arc::new(RwLock::new(HashMap::new()));
+        let task = tokio::spawn(collect(
+            client,
+            jobs,
+            store,
+            config,
+            events.clone(),
+            progress.clone(),
+        ));
+        Self {
+            events,
+            progress,
+            task,
+        }
+    }
+
+    /// Receives the progress of the jobs from now on.
+    pub fn events(&self) -> broadcast::Receiver<CollectorEvent> {
+        self.events.subscribe()
+    }
+
+    /// Timestamp up to which each job was downloaded, jobs appear after their first window.
+    pub async fn progress(&self) -> HashMap<CollectorJob, DateTime<Utc>> {
+        self.progress
+            .read()
+            .await
+            .iter()
+            .filter_map(|(job, until)| Some((job.clone(), DateTime::from_timestamp(*until, 0)?)))
+            .collect()
+    }
+
+    /// Returns `true` once the collector stopped, which only happens when it is aborted.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for DataCollector {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn collect(
+    client: PocketOption,
+    jobs: Vec<CollectorJob>,
+    store: Arc<dyn CollectorStore>,
+    config: CollectorConfig,
+    events: broadcast::Sender<CollectorEvent>,
+    progress: Arc<RwLock<HashMap<CollectorJob, i64>>>,
+) {
+    if jobs.is_empty() {
+        return;
+    }
+    // First timestamp not downloaded yet, read from the store on the first pass.
+    let mut cursors: HashMap<CollectorJob, i64> = HashMap::new();
+    loop {
+        for job in &jobs {
+            let cursor = match cursors.get(job) {
+                Some(cursor) => *cursor,
+                None => match store.last_timestamp(job).await {
+                    Ok(last) => {
+                        let now = client.server_time().await.timestamp();
+                        let cursor = start_of(job.series, last, now, config.backfill);
+                        debug!(target: "DataCollector", "Resuming {} from {}", job, cursor);
+                        cursors.insert(job.clone(), cursor);
+                        cursor
+                    }
+                    Err(e) => {
+                        fail(&events, job, e);
+                        continue;
+                    }
+                },
+            };
+            match catch_up(&client, job, store.as_ref(), &config, cursor, &events).await {
+                Ok(cursor) => {
+                    cursors.insert(job.clone(), cursor);
+                    progress.write().await.insert(job.clone(), cursor);
+                }
+                Err((cursor, e)) => {
+                    cursors.insert(job.clone(), cursor);
+                    fail(&events, job, e);
+                }
+            }
+        }
+        tokio::time::sleep(config.interval).await;
+    }
+}
+
+/// Downloads `job` from `cursor` up to the server time, returns the new cursor, or the cursor
+/// reached before the error.
+async fn catch_up(
+    client: &PocketOption,
+    job: &CollectorJob,
+    store: &dyn CollectorStore,
+    config: &CollectorConfig,
+    mut cursor: i64,
+    events: &broadcast::Sender<CollectorEvent>,
+) -> Result<i64, (i64, PocketError)> {
+    let now = client.server_time().await.timestamp();
+    let window = config.window.as_secs().max(1) as i64;
+    while let Some((from, to)) = next_window(job.series, cursor, now, window) {
+        let range = |timestamp| DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        let ticks = client
+            .ticks_range(&job.asset, range(from), range(to))
+            .await
+            .map_err(|e| (cursor, e))?;
+        let records = match job.series {
+            Series::Candles { period } => {
+                let candles = closed_candles(&ticks, &job.asset, period, from, to);
+                store
+                    .store_candles(job, &candles)
+                    .await
+                    .map_err(|e| (cursor, e))?;
+                candles.len()
+            }
+            Series::Ticks => {
+                let ticks: Vec<_> = ticks
+                    .into_iter()
+                    .filter(|(timestamp, _)| (from..=to).contains(timestamp))
+                    .collect();
+                store
+                    .store_ticks(job, &ticks)
+                    .await
+                    .map_err(|e| (cursor, e))?;
+                ticks.len()
+            }
+        };
+        cursor = match job.series {
+            Series::Candles { .. } => to,
+            Series::Ticks => to + 1,
+        };
+        let _ = events.send(CollectorEvent::Stored {
+            job: job.to_string(),
+            records,
+            until: range(cursor),
+        });
+    }
+    let _ = events.send(CollectorEvent::CaughtUp {
+        job: job.to_string(),
+        until: DateTime::from_timestamp(now, 0).unwrap_or_default(),
+    });
+    Ok(cursor)
+}
+
+fn fail(events: &broadcast::Sender<CollectorEvent>, job: &CollectorJob, error: PocketError) {
+    warn!(target: "DataCollector", "Failed to collect {}: {}", job, error);
+    let _ = events.send(CollectorEvent::Failed {
+        job: job.to_string(),
+        error: error.to_string(),
+    });
+}
+
+/// First timestamp to download for a job whose last stored record is at `last`.
+fn start_of(series: Series, last: Option<i64>, now: i64, backfill: Duration) -> i64 {
+    match (series, last) {
+        (Series::Candles { period }, Some(last)) => last + i64::from(period.max(1)),
+        (Series::Ticks, Some(last)) => last + 1,
+        (Series::Candles { period }, None) => {
+            let start = now - backfill.as_secs() as i64;
+            start - start.rem_euclid(i64::from(period.max(1)))
+        }
+        (Series::Ticks, None) => now - backfill.as_secs() as i64,
+    }
+}
+
+/// Next range to request from `cursor`, at most `window` seconds long.
+///
+/// Candle ranges end on a period boundary so that only closed candles are stored, tick ranges
+/// end a second before `now` since more ticks of the current second may still come.
+fn next_window(series: Series, cursor: i64, now: i64, window: i64) -> Option<(i64, i64)> {
+    let to = match series {
+        Series::Candles { period } => {
+            let period = i64::from(period.max(1));
+            let to = (cursor + window.max(period)).min(now);
+            to - to.rem_euclid(period)
+        }
+        Series::Ticks => (cursor + window - 1).min(now - 1),
+    };
+    match series {
+        Series::Candles { .. } if to > cursor => Some((cursor, to)),
+        Series::Ticks if to >= cursor => Some((cursor, to)),
+        _ => None,
+    }
+}
+
+/// The candles of `period` starting in `[from, to)` compiled from `ticks`.
+fn closed_candles(
+    ticks: &[(i64, f64)],
+    asset: &str,
+    period: u32,
+    from: i64,
+    to: i64,
+) -> Vec<Candle> {
+    let ticks: Vec<_> = ticks
+        .iter()
+        .filter(|(timestamp, _)| (from..to).contains(timestamp))
+        .copied()
+        .collect();
+    let mut candles = compile_candles_from_tuples(&ticks, period, asset);
+    for candle in &mut candles {
+        candle.is_closed = true;
+    }
+    candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_windows_cover_the_range_once() {
+        let series = Series::Candles { period: 60 };
+        let mut cursor = start_of(series, None, 10_030, Duration::from_secs(600));
+        assert_eq!(cursor, 9_420);
+        let mut windows = Vec::new();
+        while let Some((from, to)) = next_window(series, cursor, 10_030, 300) {
+            windows.push((from, to));
+            cursor = to;
+        }
+        assert_eq!(windows, [(9_420, 9_720), (9_720, 10_020)]);
+        assert_eq!(start_of(series, Some(9_900), 10_030, Duration::ZERO), 9_960);
+
+        let mut cursor = start_of(Series::Ticks, Some(99), 200, Duration::ZERO);
+        let mut windows = Vec::new();
+        while let Some((from, to)) = next_window(Series::Ticks, cursor, 200, 60) {
+            windows.push((from, to));
+            cursor = to + 1;
+        }
+        assert_eq!(windows, [(100, 159), (160, 199)]);
+    }
+
+    #[test]
+    fn test_only_closed_candles_are_kept() {
+        let ticks = [(59, 1.0), (60, 1.1), (90, 1.3), (119, 1.2), (120, 1.4)];
+        let candles = closed_candles(&ticks, "EURUSD_otc", 60, 60, 120);
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].timestamp, 60);
+        assert!(candles[0].is_closed);
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_store_resumes_from_the_last_record() {
+        let store =
+            JsonLinesStore::new(std::env::temp_dir().join(format!("collector-{}", Uuid::new_v4())));
+        let ticks = CollectorJob::ticks("EURUSD_otc");
+        let candles = CollectorJob::candles("EURUSD_otc", 60);
+        assert_eq!(store.last_timestamp(&ticks).await.unwrap(), None);
+
+        store
+            .store_ticks(&ticks, &[(100, 1.1), (101, 1.2)])
+            .await
+            .unwrap();
+        store.store_ticks(&ticks, &[(105, 1.3)]).await.unwrap();
+        assert_eq!(store.last_timestamp(&ticks).await.unwrap(), Some(105));
+
+        let compiled = closed_candles(
+            &[(60, 1.0), (130, 1.1), (190, 1.2)],
+            "EURUSD_otc",
+            60,
+            60,
+            180,
+        );
+        store.store_candles(&candles, &compiled).await.unwrap();
+        assert_eq!(store.last_timestamp(&candles).await.unwrap(), Some(120));
+
+        // A line cut short by a crash is skipped.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(store.path(&ticks))
+            .await
+            .unwrap();
+        file.write_all(b"[106,1.").await.unwrap();
+        assert_eq!(store.last_timestamp(&ticks).await.unwrap(), Some(105));
+        store.store_ticks(&ticks, &[(107, 1.4)]).await.unwrap();
+        assert_eq!(store.last_timestamp(&ticks).await.unwrap(), Some(107));
+
+        tokio::fs::remove_dir_all(&store.dir).await.unwrap();
+    }
+}
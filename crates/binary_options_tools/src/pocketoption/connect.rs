@@ -27,9 +27,9 @@ impl PocketConnect {
         url: Vec<String>,
         state: Arc<State>,
     ) -> ConnectorResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
-        for u in url {
+        for u in state.url_blacklist.order(url) {
             info!(target: "PocketConnectThread", "Connecting to PocketOption at {}", u);
-            match try_connect(state.clone(), u.clone()).await {
+            match Self::try_url(&state, &u).await {
                 Ok(stream) => {
                     debug!(target: "PocketConnect", "Successfully connected to PocketOption");
                     return Ok(stream);
@@ -46,6 +46,23 @@ impl PocketConnect {
             "Failed to connect to any of the provided URLs".to_string(),
         ))
     }
+
+    /// Connects to `url`, recording the outcome in the state's URL blacklist.
+    async fn try_url(
+        state: &Arc<State>,
+        url: &str,
+    ) -> ConnectorResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        let result = try_connect(state.clone(), url.to_string()).await;
+        match &result {
+            Ok(_) => state.url_blacklist.record_success(url),
+            Err(_) => {
+                if let Some(cooldown) = state.url_blacklist.record_failure(url) {
+                    warn!(target: "PocketConnect", "Skipping {} for {:?} after repeated failures", url, cooldown);
+                }
+            }
+        }
+        result
+    }
 }
 
 #[async_trait::async_trait]
@@ -56,22 +73,25 @@ impl Connector<State> for PocketConnect {
     ) -> ConnectorResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
         let creds = state.ssid.clone();
         let url = state.default_connection_url.clone();
+        let mut fallbacks = state.urls.clone();
         if let Some(url) = url {
-            debug!(target: "PocketConnect", "Connecting to PocketOption at {}", url);
-            match try_connect(state.clone(), url.clone()).await {
-                Ok(stream) => return Ok(stream),
-                Err(e) => {
-                    warn!(target: "PocketConnect", "Failed to connect to default URL {}: {}", url, e)
+            if state.url_blacklist.is_blacklisted(&url) {
+                debug!(target: "PocketConnect", "Default URL {} is blacklisted, trying it last", url);
+                fallbacks.push(url);
+            } else {
+                debug!(target: "PocketConnect", "Connecting to PocketOption at {}", url);
+                match Self::try_url(&state, &url).await {
+                    Ok(stream) => return Ok(stream),
+                    Err(e) => {
+                        warn!(target: "PocketConnect", "Failed to connect to default URL {}: {}", url, e)
+                    }
                 }
             }
         }
 
-        if !state.urls.is_empty() {
+        if !fallbacks.is_empty() {
             debug!(target: "PocketConnect", "Trying fallback URLs from config...");
-            if let Ok(stream) = self
-                .connect_multiple(state.urls.clone(), state.clone())
-                .await
-            {
+            if let Ok(stream) = self.connect_multiple(fallbacks, state.clone()).await {
                 return Ok(stream);
             }
         }
@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::atomic::{AtomicU64, Ordering},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// How often modules sweep their pending request maps for expired or abandoned waiters.
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counters describing how well requests were correlated with their responses.
+#[derive(Debug, Default)]
+pub struct CorrelationStats {
+    timed_out: AtomicU64,
+    abandoned: AtomicU64,
+    orphaned: AtomicU64,
+}
+
+/// Point in time copy of [`CorrelationStats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CorrelationCounters {
+    /// Waiters completed with a timeout error because no response arrived before their deadline.
+    pub timed_out: u64,
+    /// Waiters dropped by the sweeper because the caller stopped waiting.
+    pub abandoned: u64,
+    /// Responses that arrived for a request nobody was waiting for (anymore).
+    pub orphaned: u64,
+}
+
+impl CorrelationStats {
+    pub fn record_orphan(&self) {
+        self.orphaned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> CorrelationCounters {
+        CorrelationCounters {
+            timed_out: self.timed_out.load(Ordering::Relaxed),
+            abandoned: self.abandoned.load(Ordering::Relaxed),
+            orphaned: self.orphaned.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl std::ops::Add for CorrelationCounters {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            timed_out: self.timed_out + rhs.timed_out,
+            abandoned: self.abandoned + rhs.abandoned,
+            orphaned: self.orphaned + rhs.orphaned,
+        }
+    }
+}
+
+/// Waiters for in-flight requests keyed by their correlation id, each with its own deadline.
+///
+/// Responses are delivered with [`PendingRequests::remove`]; [`PendingRequests::sweep`] must be
+/// called periodically to hand back waiters whose deadline passed, so they can be completed
+/// with a timeout error instead of leaking.
+#[derive(Debug)]
+pub struct PendingRequests<K, T> {
+    entries: HashMap<K, Vec<(Instant, T)>>,
+    stats: Arc<CorrelationStats>,
+}
+
+impl<K: Eq + Hash + Clone, T> PendingRequests<K, T> {
+    pub fn new(stats: Arc<CorrelationStats>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            stats,
+        }
+    }
+
+    /// Registers a waiter for `key`, it expires after `timeout`.
+    pub fn insert(&mut self, key: K, waiter: T, timeout: Duration) {
+        self.insert_until(key, waiter, Instant::now() + timeout);
+    }
+
+    /// Registers a waiter for `key` that expires at `deadline`.
+    pub fn insert_until(&mut self, key: K, waiter: T, deadline: Instant) {
+        self.entries
+            .entry(key)
+            .or_default()
+            .push((deadline, waiter));
+    }
+
+    /// Removes every waiter registered for `key`, counting an orphaned response if there was none.
+    pub fn remove(&mut self, key: &K) -> Vec<T> {
+        match self.entries.remove(key) {
+            Some(waiters) => waiters.into_iter().map(|(_, waiter)| waiter).collect(),
+            None => {
+                self.stats.record_orphan();
+                Vec::new()
+            }
+        }
+    }
+
+    /// Removes the waiters for `key` without counting a missing entry as orphaned,
+    /// for responses that are broadcast rather than requested.
+    pub fn take(&mut self, key: &K) -> Vec<T> {
+        self.entries
+            .remove(key)
+            .map(|waiters| waiters.into_iter().map(|(_, waiter)| waiter).collect())
+            .unwrap_or_default()
+    }
+
+    /// Drops waiters for which `is_abandoned` returns `true` and returns the ones whose
+    /// deadline passed at `now`, the caller is responsible for completing them.
+    pub fn sweep(&mut self, now: Instant, is_abandoned: impl Fn(&T) -> bool) -> Vec<(K, T)> {
+        let mut expired = Vec::new();
+        let mut abandoned = 0;
+        self.entries.retain(|key, waiters| {
+            let mut i = 0;
+            while i < waiters.len() {
+                if is_abandoned(&waiters[i].1) {
+                    waiters.swap_remove(i);
+                    abandoned += 1;
+                } else if waiters[i].0 <= now {
+                    let (_, waiter) = waiters.swap_remove(i);
+                    expired.push((key.clone(), waiter));
+                } else {
+                    i += 1;
+                }
+            }
+            !waiters.is_empty()
+        });
+        self.stats.abandoned.fetch_add(abandoned, Ordering::Relaxed);
+        self.stats
+            .timed_out
+            .fetch_add(expired.len() as u64, Ordering::Relaxed);
+        expired
+    }
+
+    /// Removes and returns every waiter, used when the owning module stops.
+    pub fn drain(&mut self) -> impl Iterator<Item = (K, T)> + '_ {
+        self.entries
+            .drain()
+            .flat_map(|(key, waiters)| waiters.into_iter().map(move |(_, w)| (key.clone(), w)))
+    }
+
+    /// Returns the first waiter registered for `key`.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut T> {
+        self.entries
+            .get_mut(key)
+            .and_then(|waiters| waiters.first_mut())
+            .map(|(_, waiter)| waiter)
+    }
+
+    /// Iterates over every registered waiter.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.entries
+            .values()
+            .flat_map(|waiters| waiters.iter().map(|(_, waiter)| waiter))
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Number of registered waiters.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_expires_and_prunes_abandoned() {
+        let stats = Arc::new(CorrelationStats::default());
+        let mut pending = PendingRequests::new(stats.clone());
+        let now = Instant::now();
+        pending.insert_until(1, "expired", now - Duration::from_secs(1));
+        pending.insert_until(1, "abandoned", now + Duration::from_secs(10));
+        pending.insert_until(2, "alive", now + Duration::from_secs(10));
+        assert_eq!(pending.len(), 3);
+
+        let expired = pending.sweep(now, |w| *w == "abandoned");
+        assert_eq!(expired, vec![(1, "expired")]);
+        assert!(!pending.contains(&1));
+        assert_eq!(pending.remove(&2), vec!["alive"]);
+        assert!(pending.remove(&2).is_empty());
+        assert!(pending.take(&3).is_empty());
+
+        assert_eq!(
+            stats.snapshot(),
+            CorrelationCounters {
+                timed_out: 1,
+                abandoned: 1,
+                orphaned: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::pocketoption::{
+    candle::SubscriptionType,
+    state::State,
+    types::{AccountType, Action, Deal, PendingOrder, ServerTimeQuality},
+};
+
+/// Point-in-time view of the state of a client, see
+/// [`PocketOption::debug_snapshot`](crate::pocketoption::PocketOption::debug_snapshot).
+///
+/// Meant to be attached to bug reports and shown on dashboards, it is not a stable format.
+/// The session token is never included. When `redacted` is set the uid and the balances are
+/// left out as well, and the `uid` of the deals is zeroed.
+#[derive(Clone, Debug, Serialize)]
+pub struct DebugSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub redacted: bool,
+    pub uid: Option<u32>,
+    pub demo: bool,
+    pub currency: Option<String>,
+    pub balance: Option<Decimal>,
+    /// Last balance reported for each account type, amounts are `None` when redacted.
+    pub balances: BTreeMap<AccountType, DebugBalance>,
+    pub server_time: ServerTimeQuality,
+    pub connected_url: Option<String>,
+    /// Number of assets received, `None` before the first asset list.
+    pub assets: Option<usize>,
+    /// Active subscriptions by asset.
+    pub subscriptions: BTreeMap<String, Vec<String>>,
+    /// Timestamp and price of the last update received per asset.
+    pub last_ticks: BTreeMap<String, (i64, Decimal)>,
+    /// History requests awaiting their data, as asset and period.
+    pub histories: Vec<(String, u32)>,
+    pub opened_deals: Vec<Deal>,
+    pub closed_deals: Vec<Deal>,
+    pub pending_orders: Vec<DebugPendingOrder>,
+    /// Market orders sent but not confirmed by the server yet.
+    pub pending_market_orders: Vec<DebugMarketOrder>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DebugBalance {
+    pub amount: Option<Decimal>,
+    pub currency: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DebugPendingOrder {
+    #[serde(flatten)]
+    pub order: PendingOrder,
+    /// Seconds since the order was created according to server time, `None` if the creation
+    /// date could not be read.
+    pub age_seconds: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DebugMarketOrder {
+    pub request_id: Uuid,
+    pub asset: String,
+    pub action: Action,
+    pub amount: Decimal,
+    pub time: u32,
+    pub age_seconds: f64,
+}
+
+impl DebugSnapshot {
+    pub(crate) async fn capture(state: &State, redact: bool) -> Self {
+        let balances = state
+            .balances()
+            .await
+            .into_iter()
+            .map(|(account, balance)| {
+                let balance = DebugBalance {
+                    amount: (!redact).then_some(balance.amount),
+                    currency: balance.currency,
+                    updated_at: balance.updated_at,
+                };
+                (account, balance)
+            })
+            .collect();
+        let subscriptions = state
+            .active_subscriptions
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| {
+                let kinds = entry
+                    .value()
+                    .iter()
+                    .map(|(_, kind, _)| describe(kind))
+                    .collect();
+                (entry.key().clone(), kinds)
+            })
+            .collect();
+        let histories = state
+            .histories
+            .read()
+            .await
+            .iter()
+            .map(|(asset, period, _)| (asset.clone(), *period))
+            .collect();
+        let deals = |deals: std::collections::HashMap<Uuid, Deal>| {
+            let mut deals: Vec<Deal> = deals.into_values().collect();
+            deals.sort_by_key(|deal| deal.open_timestamp);
+            if redact {
+                deals.iter_mut().for_each(|deal| deal.uid = 0);
+            }
+            deals
+        };
+        let server_now = state.get_server_datetime().await;
+        let mut pending_orders: Vec<DebugPendingOrder> = state
+            .trade_state
+            .pending_deals
+            .read()
+            .await
+            .values()
+            .map(|order| DebugPendingOrder {
+                age_seconds: NaiveDateTime::parse_from_str(
+                    &order.date_created,
+                    "%Y-%m-%d %H:%M:%S",
+                )
+                .ok()
+                .map(|created| (server_now - created.and_utc()).num_seconds()),
+                order: order.clone(),
+            })
+            .collect();
+        pending_orders.sort_by(|a, b| a.order.date_created.cmp(&b.order.date_created));
+        let mut pending_market_orders: Vec<DebugMarketOrder> = state
+            .trade_state
+            .pending_market_orders
+            .read()
+            .await
+            .values()
+            .map(|(order, sent)| DebugMarketOrder {
+                request_id: order.request_id,
+                asset: order.asset.clone(),
+                action: order.action,
+                amount: order.amount,
+                time: order.time,
+                age_seconds: sent.elapsed().as_secs_f64(),
+            })
+            .collect();
+        pending_market_orders.sort_by(|a, b| b.age_seconds.total_cmp(&a.age_seconds));
+        let connected_url = state
+            .connected_url
+            .read()
+            .expect("connected url lock poisoned")
+            .clone();
+        Self {
+            taken_at: state.clock.now(),
+            redacted: redact,
+            uid: (!redact).then(|| state.ssid.uid()),
+            demo: state.ssid.demo(),
+            currency: state.currency.read().await.clone(),
+            balance: if redact {
+                None
+            } else {
+                state.get_balance().await
+            },
+            balances,
+            server_time: state.server_time_quality().await,
+            connected_url,
+            assets: state
+                .assets
+                .read()
+                .await
+                .as_ref()
+                .map(|assets| assets.0.len()),
+            subscriptions,
+            last_ticks: state
+                .last_ticks
+                .iter()
+                .map(|entry| (entry.key().clone(), *entry.value()))
+                .collect(),
+            histories,
+            opened_deals: deals(state.trade_state.get_opened_deals().await),
+            closed_deals: deals(state.trade_state.get_closed_deals().await),
+            pending_orders,
+            pending_market_orders,
+        }
+    }
+}
+
+fn describe(kind: &SubscriptionType) -> String {
+    match kind {
+        SubscriptionType::None => "none".to_string(),
+        SubscriptionType::Chunk { size, .. } => format!("chunk:{size}"),
+        SubscriptionType::Time { duration, .. } => format!("time:{}s", duration.as_secs()),
+        SubscriptionType::TimeAligned { duration, .. } => {
+            format!("time_aligned:{}s", duration.as_secs())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::{fixtures, ssid::Ssid, state::StateBuilder, types::OpenOrder};
+
+    #[tokio::test]
+    async fn test_snapshot_redacts_sensitive_fields() {
+        let ssid = Ssid::parse(
+            r#"42["auth",{"session":"secret_session_id","isDemo":1,"uid":4242,"platform":2}]"#,
+        )
+        .unwrap();
+        let state = StateBuilder::default().ssid(ssid).build().unwrap();
+        state
+            .set_account_balance(AccountType::Demo, Decimal::new(1234, 1), Some("USD".into()))
+            .await;
+        state
+            .record_tick("EURUSD_otc", 1700000000, Decimal::new(10842, 4))
+            .await;
+        let order = OpenOrder {
+            asset: "EURUSD_otc".into(),
+            action: Action::Call,
+            amount: Decimal::ONE,
+            is_demo: 1,
+            option_type: 100,
+            request_id: Uuid::new_v4(),
+            time: 60,
+        };
+        let deal = fixtures::deal(&order, Decimal::ONE, 92, Utc::now());
+        state.trade_state.add_opened_deal(deal).await;
+        state
+            .trade_state
+            .pending_market_orders
+            .write()
+            .await
+            .insert(order.request_id, (order, std::time::Instant::now()));
+
+        let snapshot = DebugSnapshot::capture(&state, true).await;
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("secret_session_id"));
+        assert!(!json.contains("4242"));
+        assert!(!json.contains("123.4"));
+        assert_eq!(snapshot.balances[&AccountType::Demo].amount, None);
+        assert_eq!(snapshot.opened_deals[0].uid, 0);
+        assert_eq!(snapshot.pending_market_orders.len(), 1);
+        assert_eq!(snapshot.last_ticks["EURUSD_otc"].0, 1700000000);
+
+        let snapshot = DebugSnapshot::capture(&state, false).await;
+        assert_eq!(snapshot.uid, Some(4242));
+        assert_eq!(snapshot.balance, Some(Decimal::new(1234, 1)));
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("secret_session_id"));
+    }
+}
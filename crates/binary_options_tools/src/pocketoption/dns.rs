@@ -0,0 +1,166 @@
+//! Host resolution used by the connector before opening the TCP connection, so PocketOption
+//! stays reachable when the local DNS blocks its domains.
+//!
+//! The TLS handshake still uses the host name, only the IP lookup is replaced.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use binary_options_tools_core::connector::{ConnectorError, ConnectorResult};
+use serde_json::Value;
+use url::Url;
+
+use crate::utils::init_crypto_provider;
+
+/// Record types of the DoH JSON answers.
+const RECORD_A: u64 = 1;
+const RECORD_AAAA: u64 = 28;
+
+const DOH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How the connector turns server host names into IP addresses.
+///
+/// `overrides` are checked first, then `doh`, then the system resolver.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsConfig {
+    /// Addresses used for a host instead of resolving it, keyed by lower case host name.
+    pub overrides: HashMap<String, Vec<IpAddr>>,
+    /// DNS-over-HTTPS endpoint answering JSON queries (`application/dns-json`), such as
+    /// `https://1.1.1.1/dns-query` or `https://dns.google/resolve`.
+    ///
+    /// The endpoint's own host goes through the system resolver, an IP address avoids that.
+    pub doh: Option<Url>,
+}
+
+impl DnsConfig {
+    /// Uses `addrs` for `host` instead of resolving it.
+    pub fn with_override(mut self, host: impl AsRef<str>, addrs: Vec<IpAddr>) -> Self {
+        self.overrides
+            .insert(host.as_ref().to_ascii_lowercase(), addrs);
+        self
+    }
+
+    /// Resolves `host` through the DNS-over-HTTPS endpoint `url`.
+    pub fn with_doh(mut self, url: Url) -> Self {
+        self.doh = Some(url);
+        self
+    }
+
+    /// Addresses to connect to for `host:port`.
+    ///
+    /// A failing DoH endpoint falls back to the system resolver.
+    pub async fn resolve(&self, host: &str, port: u16) -> ConnectorResult<Vec<SocketAddr>> {
+        let to_addrs = |ips: Vec<IpAddr>| -> Vec<SocketAddr> {
+            ips.into_iter()
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect()
+        };
+        if let Ok(ip) = host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            return Ok(vec![SocketAddr::new(ip, port)]);
+        }
+        if let Some(ips) = self.overrides.get(&host.to_ascii_lowercase()) {
+            if !ips.is_empty() {
+                return Ok(to_addrs(ips.clone()));
+            }
+        }
+        if let Some(doh) = &self.doh {
+            match resolve_doh(doh, host).await {
+                Ok(ips) if !ips.is_empty() => return Ok(to_addrs(ips)),
+                Ok(_) => tracing::warn!(
+                    target: "PocketDns",
+                    "DoH returned no address for {host}, using the system resolver"
+                ),
+                Err(e) => tracing::warn!(
+                    target: "PocketDns",
+                    "DoH lookup of {host} failed, using the system resolver: {e}"
+                ),
+            }
+        }
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| ConnectorError::Custom(format!("Failed to resolve {host}: {e}")))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(ConnectorError::Custom(format!(
+                "No address found for {host}"
+            )));
+        }
+        Ok(addrs)
+    }
+}
+
+/// Queries the A and AAAA records of `host`, IPv4 addresses first.
+async fn resolve_doh(endpoint: &Url, host: &str) -> ConnectorResult<Vec<IpAddr>> {
+    init_crypto_provider();
+    let client = reqwest::Client::builder()
+        .timeout(DOH_TIMEOUT)
+        .build()
+        .map_err(|e| ConnectorError::Custom(format!("Failed to build DoH client: {e}")))?;
+    let mut ips = Vec::new();
+    for record in ["A", "AAAA"] {
+        let mut url = endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("name", host)
+            .append_pair("type", record);
+        let answer: Value = client
+            .get(url)
+            .header("Accept", "application/dns-json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ConnectorError::Custom(format!("DoH request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| ConnectorError::Custom(format!("Invalid DoH answer: {e}")))?;
+        ips.extend(parse_doh_answer(&answer));
+    }
+    Ok(ips)
+}
+
+/// Addresses of the A and AAAA records of a JSON DoH answer, CNAMEs are skipped.
+pub fn parse_doh_answer(answer: &Value) -> Vec<IpAddr> {
+    answer["Answer"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|record| matches!(record["type"].as_u64(), Some(RECORD_A | RECORD_AAAA)))
+        .filter_map(|record| record["data"].as_str()?.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_parse_doh_answer() {
+        let answer = json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "api-eu.po.market.", "type": 5, "TTL": 300, "data": "edge.po.market."},
+                {"name": "edge.po.market.", "type": 1, "TTL": 60, "data": "104.18.1.1"},
+                {"name": "edge.po.market.", "type": 28, "TTL": 60, "data": "2606:4700::6812:101"}
+            ]
+        });
+        let ips = parse_doh_answer(&answer);
+        assert_eq!(ips.len(), 2);
+        assert_eq!(ips[0], IpAddr::V4(Ipv4Addr::new(104, 18, 1, 1)));
+        assert!(ips[1].is_ipv6());
+        assert!(parse_doh_answer(&json!({"Status": 3})).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overrides_and_literal_ips() {
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7));
+        let dns = DnsConfig::default().with_override("API-EU.po.market", vec![ip]);
+        let addrs = dns.resolve("api-eu.po.market", 443).await.unwrap();
+        assert_eq!(addrs, vec![SocketAddr::new(ip, 443)]);
+
+        let addrs = dns.resolve("127.0.0.1", 8080).await.unwrap();
+        assert_eq!(addrs[0].port(), 8080);
+        assert!(addrs[0].ip().is_loopback());
+    }
+}
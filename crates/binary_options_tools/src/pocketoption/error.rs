@@ -1,11 +1,15 @@
 use std::time::Duration;
 
 use binary_options_tools_core::error::CoreError;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::error::BinaryOptionsError;
+use crate::pocketoption::kill_switch::KillSwitchReason;
 use crate::pocketoption::modules::subscriptions::SubscriptionError;
+use crate::pocketoption::types::AccountType;
 
 #[derive(thiserror::Error, Debug)]
 pub enum PocketError {
@@ -54,6 +58,74 @@ pub enum PocketError {
     #[error("General error: {0}")]
     General(String),
 
+    #[error("Client is shutting down, rejected new {0}")]
+    ShuttingDown(String),
+
+    /// The server rejected the session (expired or invalid SSID).
+    #[error("Not authorized: {0}")]
+    NotAuthorized(String),
+
+    /// The asset exists but is currently closed for trading.
+    #[error("Asset {0} is not active")]
+    AssetInactive(String),
+
+    /// The asset payout is below the minimum required by the order.
+    #[error("Payout of {asset} is {payout}%, below the required {min}%")]
+    PayoutTooLow {
+        asset: String,
+        payout: i32,
+        min: i32,
+    },
+
+    /// The server refused to close a deal before its expiry, e.g. because the account
+    /// type does not allow it or the deal is already closed.
+    #[error("Early close of deal {deal_id} rejected: {reason}")]
+    EarlyCloseRejected { deal_id: Uuid, reason: String },
+
+    /// The trade amount is outside the account currency limits.
+    #[error("Amount {amount} is out of range, must be between {min} and {max}")]
+    AmountOutOfRange {
+        amount: Decimal,
+        min: Decimal,
+        max: Decimal,
+    },
+
+    /// No balance was received yet for the account type the trade would be placed on.
+    #[error("Balance of the {0} account is unknown")]
+    BalanceUnknown(AccountType),
+
+    /// A trade was refused by risk limits, see `framework::orchestrator::RiskLimits`.
+    #[error("Risk limit reached: {0}")]
+    RiskLimit(String),
+
+    /// The asset already has `Config::trade_guard.max_open_per_asset` deals open.
+    #[error("{asset} already has {open} open deals, the maximum is {max}")]
+    TooManyOpenDeals {
+        asset: String,
+        open: usize,
+        max: usize,
+    },
+
+    /// The last order on the asset was placed less than `Config::trade_guard.cooldown` ago.
+    #[error("{asset} is cooling down, next trade allowed in {remaining:?}")]
+    TradeCooldown { asset: String, remaining: Duration },
+
+    /// A limit of `Config::kill_switch` was hit today.
+    #[error("Trading stopped until {resets_at}: {reason} (daily P&L {pnl})")]
+    KillSwitchTripped {
+        reason: KillSwitchReason,
+        pnl: Decimal,
+        resets_at: DateTime<Utc>,
+    },
+
+    /// The server answered a request with an error.
+    #[error("Server rejected request: {reason}")]
+    ServerRejected { reason: String },
+
+    /// The websocket connection is down.
+    #[error("Connection lost: {0}")]
+    ConnectionLost(String),
+
     #[error("Subscription error: {0}")]
     Subscription(#[from] SubscriptionError),
 
@@ -63,6 +135,70 @@ pub enum PocketError {
 
 pub type PocketResult<T> = Result<T, PocketError>;
 
+/// Why the runner of a client stopped without a shutdown, see `PocketOption::fatal_error`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FatalError {
+    /// The server rejected the session, the SSID may be invalid or expired.
+    #[error("The server rejected the session")]
+    NotAuthorized,
+    /// The reconnect policy gave up, see `Config::max_allowed_loops`.
+    #[error("Gave up reconnecting after {attempts} attempts")]
+    ReconnectExhausted {
+        attempts: u32,
+        /// Why the last attempt failed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        last_error: Option<String>,
+    },
+    /// A module asked the runner to stop.
+    #[error("The runner stopped")]
+    Stopped,
+    /// The runner panicked.
+    #[error("The runner panicked: {message}")]
+    Panicked { message: String },
+}
+
+impl PocketError {
+    /// Stable numeric code identifying the error kind.
+    ///
+    /// Codes are grouped by category and never reused:
+    /// - `1xxx`: client and configuration errors
+    /// - `2xxx`: connection and session errors
+    /// - `3xxx`: timeouts
+    /// - `4xxx`: trading errors
+    /// - `5xxx`: market data errors
+    pub fn code(&self) -> u32 {
+        match self {
+            PocketError::General(_) => 1000,
+            PocketError::Core(_) => 1001,
+            PocketError::StateBuilder(_) => 1002,
+            PocketError::Configuration(_) => 1003,
+            PocketError::ModuleNotFound(_) => 1004,
+            PocketError::ModuleStopped { .. } => 1005,
+            PocketError::ShuttingDown(_) => 1006,
+            PocketError::ConnectionLost(_) => 2000,
+            PocketError::NotAuthorized(_) => 2001,
+            PocketError::Http(_) => 2002,
+            PocketError::Timeout { .. } => 3000,
+            PocketError::InvalidAsset(_) => 4000,
+            PocketError::AssetInactive(_) => 4001,
+            PocketError::AmountOutOfRange { .. } => 4002,
+            PocketError::FailOpenOrder { .. } => 4003,
+            PocketError::ServerRejected { .. } => 4004,
+            PocketError::DealNotFound(_) => 4005,
+            PocketError::PayoutTooLow { .. } => 4006,
+            PocketError::EarlyCloseRejected { .. } => 4007,
+            PocketError::RiskLimit(_) => 4008,
+            PocketError::BalanceUnknown(_) => 4009,
+            PocketError::TooManyOpenDeals { .. } => 4010,
+            PocketError::TradeCooldown { .. } => 4011,
+            PocketError::KillSwitchTripped { .. } => 4012,
+            PocketError::InvalidPeriod(_) => 5000,
+            PocketError::Subscription(_) => 5001,
+        }
+    }
+}
+
 impl From<BinaryOptionsError> for PocketError {
     fn from(error: BinaryOptionsError) -> Self {
         match error {
@@ -129,9 +265,87 @@ mod tests {
         assert_eq!(err.to_string(), "Configuration error: missing config");
     }
 
+    #[test]
+    fn test_pocket_error_codes() {
+        let err = PocketError::AmountOutOfRange {
+            amount: Decimal::new(5, 1),
+            min: Decimal::ONE,
+            max: Decimal::new(20000, 0),
+        };
+        assert_eq!(err.code(), 4002);
+        assert!(err.to_string().contains("between 1 and 20000"));
+        assert_eq!(PocketError::AssetInactive("EURUSD".into()).code(), 4001);
+        let err = PocketError::PayoutTooLow {
+            asset: "EURUSD_otc".into(),
+            payout: 70,
+            min: 85,
+        };
+        assert_eq!(err.code(), 4006);
+        assert_eq!(
+            err.to_string(),
+            "Payout of EURUSD_otc is 70%, below the required 85%"
+        );
+        let err = PocketError::EarlyCloseRejected {
+            deal_id: Uuid::nil(),
+            reason: "not available".into(),
+        };
+        assert_eq!(err.code(), 4007);
+        let err = PocketError::BalanceUnknown(AccountType::Real);
+        assert_eq!(err.code(), 4009);
+        assert_eq!(err.to_string(), "Balance of the real account is unknown");
+        let err = PocketError::TooManyOpenDeals {
+            asset: "EURUSD_otc".into(),
+            open: 2,
+            max: 2,
+        };
+        assert_eq!(err.code(), 4010);
+        assert_eq!(
+            err.to_string(),
+            "EURUSD_otc already has 2 open deals, the maximum is 2"
+        );
+        let err = PocketError::TradeCooldown {
+            asset: "EURUSD_otc".into(),
+            remaining: Duration::from_secs(3),
+        };
+        assert_eq!(err.code(), 4011);
+        assert_eq!(
+            err.to_string(),
+            "EURUSD_otc is cooling down, next trade allowed in 3s"
+        );
+        let err = PocketError::KillSwitchTripped {
+            reason: KillSwitchReason::MaxDrawdown,
+            pnl: Decimal::new(-105, 1),
+            resets_at: DateTime::from_timestamp(1704153600, 0).unwrap(),
+        };
+        assert_eq!(err.code(), 4012);
+        assert_eq!(
+            err.to_string(),
+            "Trading stopped until 2024-01-02 00:00:00 UTC: maximum drawdown reached (daily P&L -10.5)"
+        );
+        assert_eq!(PocketError::ConnectionLost("closed".into()).code(), 2000);
+        assert_eq!(PocketError::General("x".into()).code(), 1000);
+    }
+
+    #[test]
+    fn test_fatal_error_serializes_its_kind() {
+        let err = FatalError::ReconnectExhausted {
+            attempts: 5,
+            last_error: None,
+        };
+        assert_eq!(err.to_string(), "Gave up reconnecting after 5 attempts");
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({"kind": "reconnect_exhausted", "attempts": 5})
+        );
+        assert_eq!(
+            serde_json::to_value(FatalError::NotAuthorized).unwrap(),
+            serde_json::json!({"kind": "not_authorized"})
+        );
+    }
+
     #[test]
     fn test_pocket_result_type_alias() {
         let ok: PocketResult<i32> = Ok(42);
-        assert_eq!(ok.unwrap(), 42);
+        assert!(matches!(ok, Ok(42)));
     }
 }
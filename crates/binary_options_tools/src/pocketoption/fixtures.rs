@@ -0,0 +1,288 @@
+//! Scripted PocketOption server frames for tests.
+//!
+//! [`mock_server`] returns a [`MockConnector`] that logs in, reports a balance and the assets,
+//! and confirms every order, so [`PocketOption::new_with_connector`] gets a working client
+//! without a real ssid. The other functions build the individual frames to push from a test,
+//! e.g. ticks with [`update_stream`] or results with [`update_closed_deals`].
+//!
+//! [`PocketOption::new_with_connector`]: crate::pocketoption::PocketOption::new_with_connector
+//!
+//! Events carrying data are sent the way the server does, as a `451-` placeholder frame
+//! followed by a binary frame with the payload.
+
+use binary_options_tools_core::reimports::Message;
+use binary_options_tools_core::testing::{MockConnector, ScriptStep};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::pocketoption::types::{
+    Action, Asset, AssetType, CandleLength, Deal, OpenOrder, RequestId,
+};
+
+/// Engine.IO session id used by the fixtures.
+pub const MOCK_SID: &str = "mock-sid";
+
+/// Ssid accepted by [`mock_server`], for demo account `42`.
+pub const MOCK_SSID: &str =
+    r#"42["auth",{"session":"mock_session","isDemo":1,"uid":42,"platform":2}]"#;
+
+/// Engine.IO open packet, the first frame of a connection.
+pub fn engine_open() -> Message {
+    Message::text(format!(
+        r#"0{{"sid":"{MOCK_SID}","upgrades":[],"pingInterval":25000,"pingTimeout":20000,"maxPayload":1000000}}"#
+    ))
+}
+
+/// Socket.IO connect acknowledgement, answers the client's `40`.
+pub fn socket_connected() -> Message {
+    Message::text(format!(r#"40{{"sid":"{MOCK_SID}"}}"#))
+}
+
+/// Answers the client's `auth` message.
+pub fn success_auth() -> Message {
+    Message::text(r#"42["successauth",{"id":"mock_session"}]"#)
+}
+
+/// Engine.IO ping, the client answers with `3`.
+pub fn ping() -> Message {
+    Message::text("2")
+}
+
+/// Server rejection of the session.
+pub fn disconnect() -> Message {
+    Message::text("41")
+}
+
+/// An event sent as a placeholder frame and a binary frame holding `payload`.
+pub fn binary_event(event: &str, payload: &impl Serialize) -> [Message; 2] {
+    let payload = serde_json::to_vec(payload).expect("fixture payloads are valid JSON");
+    [
+        Message::text(format!(
+            r#"451-["{event}",{{"_placeholder":true,"num":0}}]"#
+        )),
+        Message::binary(payload),
+    ]
+}
+
+pub fn balance(balance: Decimal, demo: bool) -> [Message; 2] {
+    binary_event(
+        "successupdateBalance",
+        &json!({
+            "isDemo": demo as u32,
+            "balance": balance.to_f64(),
+            "currency": "USD"
+        }),
+    )
+}
+
+/// An active currency asset allowing 1 minute candles, with a payout of `payout` percent.
+pub fn asset(id: i32, symbol: &str, payout: i32) -> Asset {
+    Asset {
+        id,
+        name: symbol.replace('_', " "),
+        symbol: symbol.to_string(),
+        is_otc: symbol.ends_with("_otc"),
+        is_active: true,
+        payout,
+        allowed_candles: vec![CandleLength::new(60)],
+        asset_type: AssetType::Currency,
+    }
+}
+
+/// The assets in the server's array format.
+pub fn update_assets(assets: &[Asset]) -> [Message; 2] {
+    let rows: Vec<Value> = assets
+        .iter()
+        .map(|asset| {
+            json!([
+                asset.id,
+                asset.symbol,
+                asset.name,
+                asset.asset_type,
+                2,
+                asset.payout,
+                60,
+                30,
+                3,
+                asset.is_otc as u8,
+                0,
+                0,
+                [],
+                0,
+                asset.is_active,
+                asset.allowed_candles,
+                [],
+                0,
+                0
+            ])
+        })
+        .collect();
+    binary_event("updateAssets", &rows)
+}
+
+/// A tick of `asset` at the unix time `timestamp` (seconds, fractions allowed).
+pub fn update_stream(asset: &str, timestamp: f64, price: f64) -> [Message; 2] {
+    binary_event("updateStream", &json!([[asset, timestamp, price]]))
+}
+
+/// The deal the server opens for `order`, at `open_price` and `opened_at`.
+pub fn deal(order: &OpenOrder, open_price: Decimal, payout: i32, opened_at: DateTime<Utc>) -> Deal {
+    let close_at = opened_at + Duration::seconds(order.time as i64);
+    Deal {
+        id: Uuid::new_v4(),
+        open_time: opened_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        close_time: close_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        open_timestamp: opened_at,
+        close_timestamp: close_at,
+        refund_time: None,
+        refund_timestamp: None,
+        uid: 42,
+        request_id: Some(RequestId::Uuid(order.request_id)),
+        amount: order.amount,
+        profit: Decimal::ZERO,
+        percent_profit: payout,
+        percent_loss: 100,
+        open_price,
+        close_price: Decimal::ZERO,
+        command: match order.action {
+            Action::Call => 0,
+            Action::Put => 1,
+        },
+        asset: order.asset.clone(),
+        is_demo: order.is_demo,
+        copy_ticket: String::new(),
+        open_ms: 0,
+        close_ms: None,
+        option_type: order.option_type as i32,
+        is_rollover: None,
+        is_copy_signal: None,
+        is_ai: None,
+        currency: "USD".to_string(),
+        amount_usd: None,
+        amount_usd2: None,
+        slippage: None,
+    }
+}
+
+/// `deal` closed at `close_price`, with the profit the payout gives.
+pub fn closed(deal: &Deal, close_price: Decimal) -> Deal {
+    let won = match deal.command {
+        0 => close_price > deal.open_price,
+        _ => close_price < deal.open_price,
+    };
+    let profit = if close_price == deal.open_price {
+        Decimal::ZERO
+    } else if won {
+        deal.amount * Decimal::from(deal.percent_profit) / Decimal::ONE_HUNDRED
+    } else {
+        -deal.amount * Decimal::from(deal.percent_loss) / Decimal::ONE_HUNDRED
+    };
+    Deal {
+        close_price,
+        profit,
+        close_ms: Some(0),
+        ..deal.clone()
+    }
+}
+
+pub fn success_open_order(deal: &Deal) -> [Message; 2] {
+    binary_event("successopenOrder", deal)
+}
+
+pub fn update_opened_deals(deals: &[Deal]) -> [Message; 2] {
+    binary_event("updateOpenedDeals", &deals)
+}
+
+pub fn update_closed_deals(deals: &[Deal]) -> [Message; 2] {
+    binary_event("updateClosedDeals", &deals)
+}
+
+pub fn success_close_order(deals: &[Deal]) -> [Message; 2] {
+    let profit: Decimal = deals.iter().map(|deal| deal.profit).sum();
+    binary_event(
+        "successcloseOrder",
+        &json!({ "profit": profit, "deals": deals }),
+    )
+}
+
+/// Parses an `openOrder` frame sent by the client.
+pub fn parse_open_order(message: &Message) -> Option<OpenOrder> {
+    let text = message.to_text().ok()?.strip_prefix("42")?;
+    let (event, order): (String, OpenOrder) = serde_json::from_str(text).ok()?;
+    (event == "openOrder").then_some(order)
+}
+
+/// Confirms every order with a deal opened at `open_price`.
+pub fn open_order_responder(
+    open_price: Decimal,
+    payout: i32,
+) -> impl Fn(&Message) -> Option<Vec<Message>> + Send + Sync + 'static {
+    move |message| {
+        let order = parse_open_order(message)?;
+        let deal = deal(&order, open_price, payout, Utc::now());
+        Some(success_open_order(&deal).into())
+    }
+}
+
+/// A server that accepts [`MOCK_SSID`], reports `balance` on a demo account, lists `assets` and
+/// confirms every order at a price of 1.
+///
+/// Extend it with more steps or responders, or push frames once the client is connected.
+pub fn mock_server(balance_amount: Decimal, assets: &[Asset]) -> MockConnector {
+    let payout = assets.first().map_or(80, |asset| asset.payout);
+    MockConnector::new()
+        .send(engine_open())
+        .expect_text("40")
+        .send(socket_connected())
+        .expect_text(r#"42["auth""#)
+        .send(success_auth())
+        .steps(
+            balance(balance_amount, true)
+                .into_iter()
+                .chain(update_assets(assets))
+                .map(ScriptStep::Send),
+        )
+        .respond(open_order_responder(Decimal::ONE, payout))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::pocketoption::types::Assets;
+
+    #[test]
+    fn test_update_assets_matches_the_wire_format() {
+        let [_, payload] = update_assets(&[asset(1, "EURUSD_otc", 92)]);
+        let assets: Assets = serde_json::from_slice(&payload.into_data()).unwrap();
+        let asset = assets.get("EURUSD_otc").unwrap();
+        assert_eq!(asset.payout, 92);
+        assert!(asset.is_otc && asset.is_active);
+    }
+
+    #[test]
+    fn test_open_order_round_trip() {
+        let order = OpenOrder::new(
+            dec!(5),
+            "EURUSD_otc".into(),
+            Action::Put,
+            60,
+            1,
+            Uuid::new_v4(),
+        );
+        let parsed = parse_open_order(&Message::text(order.to_string())).unwrap();
+        assert_eq!(parsed.request_id, order.request_id);
+
+        let opened = deal(&parsed, dec!(1.1), 90, Utc::now());
+        let [_, payload] = success_open_order(&opened);
+        let decoded: Deal = serde_json::from_slice(&payload.into_data()).unwrap();
+        assert_eq!(decoded.request_id, Some(RequestId::Uuid(order.request_id)));
+        assert_eq!(closed(&opened, dec!(1.0)).profit, dec!(4.5));
+        assert_eq!(closed(&opened, dec!(1.2)).profit, dec!(-5));
+    }
+}
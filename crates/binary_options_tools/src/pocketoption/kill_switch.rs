@@ -0,0 +1,273 @@
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::config::KillSwitchConfig;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::Deal;
+
+/// Events kept for receivers that fall behind before they get `RecvError::Lagged`.
+const EVENTS_CAPACITY: usize = 16;
+
+/// Limit of [`KillSwitchConfig`] that stopped the trading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillSwitchReason {
+    ProfitTarget,
+    MaxDrawdown,
+}
+
+impl fmt::Display for KillSwitchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProfitTarget => write!(f, "daily profit target reached"),
+            Self::MaxDrawdown => write!(f, "maximum drawdown reached"),
+        }
+    }
+}
+
+/// Emitted once when a limit is hit, the trading stays stopped until `resets_at`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct KillSwitchEvent {
+    pub reason: KillSwitchReason,
+    /// Net profit of the day when the limit was hit.
+    pub pnl: Decimal,
+    /// Highest net profit of the day until then.
+    pub peak: Decimal,
+    /// Close time of the deal that hit the limit.
+    pub at: DateTime<Utc>,
+    /// Next server midnight.
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Result of the current server day, see
+/// [`PocketOption::kill_switch_status`](crate::pocketoption::PocketOption::kill_switch_status).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct KillSwitchStatus {
+    /// Server day the figures are for, `None` before the first closed deal.
+    pub day: Option<NaiveDate>,
+    pub pnl: Decimal,
+    pub peak: Decimal,
+    /// Set while the trading is stopped.
+    pub tripped: Option<KillSwitchEvent>,
+}
+
+impl KillSwitchStatus {
+    /// Starts `day` over, returns `false` for the days before the current one.
+    fn roll(&mut self, day: NaiveDate) -> bool {
+        match self.day {
+            Some(current) if day < current => false,
+            Some(current) if day == current => true,
+            _ => {
+                *self = Self {
+                    day: Some(day),
+                    ..Self::default()
+                };
+                true
+            }
+        }
+    }
+}
+
+/// Stops the trading for the rest of the server day once the closed deals of the day reach
+/// the profit target or the maximum drawdown of [`KillSwitchConfig`].
+///
+/// Deals are fed in as they close (see `TradeState::update_closed_deals`) and bucketed by the
+/// day of their close time, the trades module checks it before sending every order.
+#[derive(Debug)]
+pub struct KillSwitch {
+    config: Mutex<KillSwitchConfig>,
+    status: Mutex<KillSwitchStatus>,
+    events: broadcast::Sender<KillSwitchEvent>,
+}
+
+impl Default for KillSwitch {
+    fn default() -> Self {
+        Self {
+            config: Mutex::new(KillSwitchConfig::default()),
+            status: Mutex::new(KillSwitchStatus::default()),
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+        }
+    }
+}
+
+impl KillSwitch {
+    pub fn configure(&self, config: KillSwitchConfig) {
+        *self.config.lock().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    pub fn config(&self) -> KillSwitchConfig {
+        self.config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn status_lock(&self) -> MutexGuard<'_, KillSwitchStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Adds the result of a closed deal, each deal must be recorded once.
+    pub fn record(&self, deal: &Deal) {
+        let config = self.config();
+        let mut status = self.status_lock();
+        if !status.roll(deal.close_timestamp.date_naive()) {
+            return;
+        }
+        status.pnl += deal.profit;
+        status.peak = status.peak.max(status.pnl);
+        if status.tripped.is_some() {
+            return;
+        }
+        let reason = if config
+            .daily_profit_target
+            .is_some_and(|target| status.pnl >= target)
+        {
+            KillSwitchReason::ProfitTarget
+        } else if config
+            .max_drawdown
+            .is_some_and(|max| status.peak - status.pnl >= max)
+        {
+            KillSwitchReason::MaxDrawdown
+        } else {
+            return;
+        };
+        let event = KillSwitchEvent {
+            reason,
+            pnl: status.pnl,
+            peak: status.peak,
+            at: deal.close_timestamp,
+            resets_at: next_midnight(deal.close_timestamp),
+        };
+        warn!(target: "KillSwitch", "Trading stopped until {}: {} (day P&L {}, peak {})", event.resets_at, reason, event.pnl, event.peak);
+        status.tripped = Some(event.clone());
+        // No receiver is fine, the orders are refused anyway.
+        let _ = self.events.send(event);
+    }
+
+    /// Fails while the trading is stopped at the server time `now`.
+    pub fn check(&self, now: DateTime<Utc>) -> PocketResult<()> {
+        let mut status = self.status_lock();
+        status.roll(now.date_naive());
+        match &status.tripped {
+            Some(event) => Err(PocketError::KillSwitchTripped {
+                reason: event.reason,
+                pnl: event.pnl,
+                resets_at: event.resets_at,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Result of the server day of `now`.
+    pub fn status(&self, now: DateTime<Utc>) -> KillSwitchStatus {
+        let mut status = self.status_lock();
+        status.roll(now.date_naive());
+        status.clone()
+    }
+
+    pub fn events(&self) -> broadcast::Receiver<KillSwitchEvent> {
+        self.events.subscribe()
+    }
+}
+
+fn next_midnight(time: DateTime<Utc>) -> DateTime<Utc> {
+    let day = time.date_naive();
+    day.succ_opt()
+        .unwrap_or(day)
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn closed(profit: Decimal, hour: u32) -> Deal {
+        let mut deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 1, "amount": 10.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 1.0,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        deal.profit = profit;
+        deal.close_timestamp = Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        deal
+    }
+
+    #[test]
+    fn test_kill_switch_trips_on_drawdown_until_midnight() {
+        let switch = KillSwitch::default();
+        switch.configure(KillSwitchConfig {
+            daily_profit_target: Some(dec!(50)),
+            max_drawdown: Some(dec!(20)),
+        });
+        let mut events = switch.events();
+
+        switch.record(&closed(dec!(9.2), 10));
+        switch.record(&closed(dec!(-10), 11));
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(switch.check(now).is_ok());
+
+        switch.record(&closed(dec!(-10), 12));
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.reason, KillSwitchReason::MaxDrawdown);
+        assert_eq!(event.pnl, dec!(-10.8));
+        assert_eq!(event.peak, dec!(9.2));
+        assert_eq!(
+            event.resets_at,
+            Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()
+        );
+        let err = switch.check(now).unwrap_err();
+        assert!(matches!(
+            err,
+            PocketError::KillSwitchTripped {
+                reason: KillSwitchReason::MaxDrawdown,
+                ..
+            }
+        ));
+
+        // Later deals of the day don't fire again, the next day starts over.
+        switch.record(&closed(dec!(-5), 13));
+        assert!(events.try_recv().is_err());
+        let tomorrow = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 1).unwrap();
+        assert!(switch.check(tomorrow).is_ok());
+        assert_eq!(switch.status(tomorrow).pnl, Decimal::ZERO);
+        // Yesterday's deals no longer count.
+        switch.record(&closed(dec!(-50), 23));
+        assert!(switch.check(tomorrow).is_ok());
+    }
+
+    #[test]
+    fn test_kill_switch_trips_on_profit_target() {
+        let switch = KillSwitch::default();
+        switch.configure(KillSwitchConfig {
+            daily_profit_target: Some(dec!(15)),
+            max_drawdown: None,
+        });
+        switch.record(&closed(dec!(9.2), 10));
+        switch.record(&closed(dec!(9.2), 11));
+        let status = switch.status(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap());
+        assert_eq!(status.pnl, dec!(18.4));
+        assert_eq!(
+            status.tripped.map(|event| event.reason),
+            Some(KillSwitchReason::ProfitTarget)
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Headless PocketOption login (`login` feature).
+//!
+//! [`Login`] runs the website login form over HTTP and returns an [`Ssid`] ready for
+//! [`PocketOption::new`](crate::pocketoption::PocketOption::new), no browser needed. The form is
+//! protected by a reCAPTCHA: when the login page has one, the [`CaptchaSolver`] gets its site key
+//! and must return a solved token, e.g. from a solving service such as 2captcha.
+//!
+//! ```no_run
+//! # async fn run() -> binary_options_tools::pocketoption::error::PocketResult<()> {
+//! use binary_options_tools::pocketoption::error::PocketResult;
+//! use binary_options_tools::pocketoption::login::{CaptchaChallenge, Login};
+//!
+//! let ssid = Login::new()?
+//!     .captcha_solver(|challenge: CaptchaChallenge| async move {
+//!         // Send `challenge.site_key` and `challenge.page_url` to the solving service.
+//!         PocketResult::Ok("solved-token".to_string())
+//!     })
+//!     .login("me@example.com", "password", true)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use regex::Regex;
+use reqwest::header::{CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE, USER_AGENT};
+use reqwest::{Response, StatusCode};
+use tracing::{debug, info};
+
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::ssid::Ssid;
+use crate::utils::init_crypto_provider;
+
+const POCKET_OPTION_URL: &str = "https://pocketoption.com";
+
+const MAX_REDIRECTS: usize = 10;
+
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/132.0.0.0 Safari/537.36";
+
+/// The reCAPTCHA of the login form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptchaChallenge {
+    /// `data-sitekey` of the captcha widget.
+    pub site_key: String,
+    /// Page showing the captcha.
+    pub page_url: String,
+}
+
+/// Solves the login captcha, returning the `g-recaptcha-response` token.
+///
+/// Implemented for closures `Fn(CaptchaChallenge) -> impl Future<Output = PocketResult<String>>`.
+pub trait CaptchaSolver: Send + Sync {
+    fn solve(&self, challenge: CaptchaChallenge) -> BoxFuture<'static, PocketResult<String>>;
+}
+
+impl<F, Fut> CaptchaSolver for F
+where
+    F: Fn(CaptchaChallenge) -> Fut + Send + Sync,
+    Fut: Future<Output = PocketResult<String>> + Send + 'static,
+{
+    fn solve(&self, challenge: CaptchaChallenge) -> BoxFuture<'static, PocketResult<String>> {
+        Box::pin(self(challenge))
+    }
+}
+
+/// Logs in to PocketOption with an email and a password.
+#[derive(Clone)]
+pub struct Login {
+    http: reqwest::Client,
+    base_url: String,
+    solver: Option<Arc<dyn CaptchaSolver>>,
+}
+
+impl Login {
+    pub fn new() -> PocketResult<Self> {
+        Self::with_base_url(POCKET_OPTION_URL)
+    }
+
+    /// Same as [`Login::new`] against another site, e.g. a PocketOption mirror.
+    pub fn with_base_url(base_url: &str) -> PocketResult<Self> {
+        init_crypto_provider();
+        // Redirects are followed by hand to keep the cookies they set.
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| PocketError::General(format!("Failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            solver: None,
+        })
+    }
+
+    /// Sets the solver called when the login form has a captcha. Without one such a login fails.
+    pub fn captcha_solver(mut self, solver: impl CaptchaSolver + 'static) -> Self {
+        self.solver = Some(Arc::new(solver));
+        self
+    }
+
+    /// Logs in and returns the ssid of the demo or the real account.
+    pub async fn login(&self, email: &str, password: &str, demo: bool) -> PocketResult<Ssid> {
+        let mut cookies = CookieJar::default();
+        let login_url = format!("{}/en/login/", self.base_url);
+
+        let page = self.get(&login_url, &mut cookies).await?;
+        let mut form = vec![
+            ("email", email.to_string()),
+            ("password", password.to_string()),
+            ("remember", "1".to_string()),
+        ];
+        if let Some(token) = csrf_token(&page) {
+            form.push(("_token", token));
+        }
+        if let Some(site_key) = captcha_site_key(&page) {
+            let solver = self.solver.as_ref().ok_or_else(|| {
+                PocketError::Configuration(
+                    "The login form has a captcha but no captcha solver is set".into(),
+                )
+            })?;
+            debug!(target: "Login", "Solving login captcha");
+            let token = solver
+                .solve(CaptchaChallenge {
+                    site_key,
+                    page_url: login_url.clone(),
+                })
+                .await?;
+            form.push(("g-recaptcha-response", token));
+        }
+
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&form)
+            .finish();
+        let response = self
+            .http
+            .post(&login_url)
+            .header(USER_AGENT, BROWSER_USER_AGENT)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(COOKIE, cookies.header())
+            .body(body)
+            .send()
+            .await?;
+        cookies.store(&response);
+        if response.status() == StatusCode::OK {
+            // A successful login redirects, the form is shown again with the error otherwise.
+            let page = response.text().await?;
+            return Err(PocketError::NotAuthorized(format!(
+                "Login rejected: {}",
+                form_error(&page).unwrap_or_else(|| "invalid credentials or captcha".into())
+            )));
+        }
+        if let Some(location) = redirect_target(&response) {
+            self.get(&self.absolute(&location), &mut cookies).await?;
+        }
+
+        let cabinet = if demo {
+            "/en/cabinet/demo-quick-high-low/"
+        } else {
+            "/en/cabinet/quick-high-low/"
+        };
+        let page = self
+            .get(&format!("{}{cabinet}", self.base_url), &mut cookies)
+            .await?;
+        let uid = account_uid(&page).ok_or_else(|| {
+            PocketError::NotAuthorized("Logged in but the account id was not found".into())
+        })?;
+        let ssid = match demo_session(&page).filter(|_| demo) {
+            Some(session) => Ssid::from_parts(session, uid, 2, true, true, true)?,
+            None => Ssid::from_cookie_header(&cookies.header(), uid, demo)?,
+        };
+        info!(target: "Login", "Logged in to account {}", uid);
+        Ok(ssid)
+    }
+
+    /// Fetches `url`, following redirects, and returns the page.
+    async fn get(&self, url: &str, cookies: &mut CookieJar) -> PocketResult<String> {
+        let mut url = url.to_string();
+        for _ in 0..MAX_REDIRECTS {
+            let response = self
+                .http
+                .get(&url)
+                .header(USER_AGENT, BROWSER_USER_AGENT)
+                .header(COOKIE, cookies.header())
+                .send()
+                .await?;
+            cookies.store(&response);
+            match redirect_target(&response) {
+                Some(location) => url = self.absolute(&location),
+                None => return Ok(response.error_for_status()?.text().await?),
+            }
+        }
+        Err(PocketError::General(format!(
+            "Too many redirects from {url}"
+        )))
+    }
+
+    fn absolute(&self, location: &str) -> String {
+        if location.starts_with("http") {
+            location.to_string()
+        } else {
+            format!("{}/{}", self.base_url, location.trim_start_matches('/'))
+        }
+    }
+}
+
+fn redirect_target(response: &Response) -> Option<String> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    response
+        .headers()
+        .get(LOCATION)?
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Cookies set by the site, sent back with every request.
+#[derive(Debug, Default)]
+struct CookieJar(BTreeMap<String, String>);
+
+impl CookieJar {
+    fn store(&mut self, response: &Response) {
+        for header in response.headers().get_all(SET_COOKIE) {
+            if let Some((name, value)) = header.to_str().ok().and_then(parse_set_cookie) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    fn header(&self) -> String {
+        self.0
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Name and value of a `Set-Cookie` header, attributes are ignored.
+fn parse_set_cookie(header: &str) -> Option<(String, String)> {
+    let pair = header.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    Some((name.trim().to_string(), value.trim().to_string()))
+}
+
+fn capture(regex: &'static OnceLock<Regex>, pattern: &str, page: &str) -> Option<String> {
+    regex
+        .get_or_init(|| Regex::new(pattern).expect("login page patterns are valid"))
+        .captures(page)
+        .map(|caps| caps[1].to_string())
+}
+
+fn csrf_token(page: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    capture(&RE, r#"name="_token"\s+value="([^"]+)""#, page)
+}
+
+fn captcha_site_key(page: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    capture(&RE, r#"data-sitekey="([^"]+)""#, page)
+}
+
+fn form_error(page: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    capture(&RE, r#"class="[^"]*error[^"]*"[^>]*>\s*([^<]+?)\s*<"#, page)
+}
+
+fn account_uid(page: &str) -> Option<u32> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    capture(&RE, r#""uid"\s*:\s*"?(\d+)"#, page)?.parse().ok()
+}
+
+/// Session token of the demo account, embedded in the demo cabinet page.
+fn demo_session(page: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    capture(&RE, r#""session"\s*:\s*"([0-9a-zA-Z]+)""#, page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGIN_PAGE: &str = r#"<form method="post">
+        <input type="hidden" name="_token" value="csrf123">
+        <div class="g-recaptcha" data-sitekey="6LeSiteKey"></div>
+        <div class="form-error">Wrong email or password</div>
+    </form>"#;
+
+    #[test]
+    fn test_login_page_parsing() {
+        assert_eq!(csrf_token(LOGIN_PAGE).as_deref(), Some("csrf123"));
+        assert_eq!(captcha_site_key(LOGIN_PAGE).as_deref(), Some("6LeSiteKey"));
+        assert_eq!(
+            form_error(LOGIN_PAGE).as_deref(),
+            Some("Wrong email or password")
+        );
+        assert_eq!(csrf_token("<form></form>"), None);
+    }
+
+    #[test]
+    fn test_cabinet_page_parsing() {
+        let page = r#"<script>window.settings = {"uid":87654321,"session":"a1b2c3d4","isDemo":1};</script>"#;
+        assert_eq!(account_uid(page), Some(87654321));
+        assert_eq!(demo_session(page).as_deref(), Some("a1b2c3d4"));
+        assert_eq!(account_uid(r#"{"uid":"123"}"#), Some(123));
+    }
+
+    #[test]
+    fn test_set_cookie_parsing() {
+        assert_eq!(
+            parse_set_cookie("ci_session=a%3A4%3A; expires=Thu, 01 Jan 2099 00:00:00 GMT; path=/"),
+            Some(("ci_session".into(), "a%3A4%3A".into()))
+        );
+        assert_eq!(parse_set_cookie("invalid"), None);
+
+        let mut jar = CookieJar::default();
+        jar.0.insert("lang".into(), "en".into());
+        jar.0.insert("ci_session".into(), "abc".into());
+        assert_eq!(jar.header(), "ci_session=abc; lang=en");
+    }
+}
@@ -1,10 +1,31 @@
+pub mod alerts;
+pub mod blacklist;
 pub mod candle;
+pub mod collector;
 pub mod connect;
+pub mod correlation;
+pub mod debug;
+pub mod dns;
 pub mod error;
+pub mod fixtures;
+pub mod kill_switch;
+#[cfg(feature = "login")]
+pub mod login;
 pub mod modules;
+pub mod observer;
+pub mod protocol;
+pub mod quality;
 pub mod regions;
+pub mod resample;
+pub mod scanner;
+pub mod schedule;
+pub mod session;
 pub mod ssid;
 pub mod state;
+pub mod stats;
+pub mod system_proxy;
+pub mod trailing;
+pub mod warmup;
 
 /// Contains types used across multiple modules.
 pub mod types;
@@ -12,4 +33,5 @@ pub mod types;
 pub mod utils;
 
 pub mod pocket_client;
-pub use pocket_client::PocketOption;
+pub use observer::PocketObserver;
+pub use pocket_client::{PocketOption, PocketOptionBuilder};
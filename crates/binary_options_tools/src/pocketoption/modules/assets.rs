@@ -7,7 +7,7 @@ use binary_options_tools_core::{
     reimports::{AsyncReceiver, AsyncSender, Message},
     traits::{LightweightModule, Rule, RunnerCommand},
 };
-use tracing::{debug, warn};
+use tracing::debug;
 
 /// Module for handling asset updates in PocketOption
 /// This module listens for asset-related messages and processes them accordingly.
@@ -32,14 +32,13 @@ impl LightweightModule<State> for AssetsModule {
     async fn run(&mut self) -> CoreResult<()> {
         while let Ok(msg) = self.receiver.recv().await {
             match &*msg {
-                Message::Binary(data) => {
-                    if let Ok(assets) = serde_json::from_slice::<Assets>(data) {
+                Message::Binary(data) => match serde_json::from_slice::<Assets>(data) {
+                    Ok(assets) => {
                         debug!("Loaded assets (binary): {:?}", assets.names());
                         self.state.set_assets(assets).await;
-                    } else {
-                        warn!("Failed to parse assets message (binary): {:?}", data);
                     }
-                }
+                    Err(e) => self.state.quarantine("AssetsModule", &msg, e),
+                },
                 Message::Text(text) => {
                     if let Ok(assets) = serde_json::from_str::<Assets>(text) {
                         debug!("Loaded assets (text): {:?}", assets.names());
@@ -53,15 +52,18 @@ impl LightweightModule<State> for AssetsModule {
                             {
                                 if let Some(arr) = value.as_array_mut() {
                                     if arr.len() >= 2 && arr[0] == "updateAssets" {
-                                        if let Ok(assets) =
-                                            serde_json::from_value::<Assets>(arr[1].take())
-                                        {
-                                            debug!(
-                                                "Loaded assets (text 1-step): {:?}",
-                                                assets.names()
-                                            );
-                                            self.state.set_assets(assets).await;
-                                            parsed_1step = true;
+                                        match serde_json::from_value::<Assets>(arr[1].take()) {
+                                            Ok(assets) => {
+                                                debug!(
+                                                    "Loaded assets (text 1-step): {:?}",
+                                                    assets.names()
+                                                );
+                                                self.state.set_assets(assets).await;
+                                                parsed_1step = true;
+                                            }
+                                            Err(e) => {
+                                                self.state.quarantine("AssetsModule", &msg, e)
+                                            }
                                         }
                                     }
                                 }
@@ -90,7 +92,8 @@ impl LightweightModule<State> for AssetsModule {
 
 #[cfg(test)]
 mod tests {
-    use crate::pocketoption::types::{Asset, AssetType, Assets, CandleLength};
+    use crate::pocketoption::modules::trades_tests::common::create_mock_state;
+    use crate::pocketoption::types::{Asset, AssetChange, AssetType, Assets, CandleLength};
 
     #[test]
     fn test_asset_deserialization() {
@@ -262,4 +265,103 @@ mod tests {
         let active = assets.active();
         assert_eq!(active.0.len(), 0);
     }
+
+    fn asset(symbol: &str, payout: i32, is_active: bool) -> Asset {
+        Asset {
+            id: 1,
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            asset_type: AssetType::Currency,
+            payout,
+            is_otc: true,
+            is_active,
+            allowed_candles: vec![CandleLength::new(60)],
+        }
+    }
+
+    fn assets(list: &[Asset]) -> Assets {
+        Assets(list.iter().map(|a| (a.symbol.clone(), a.clone())).collect())
+    }
+
+    #[test]
+    fn test_assets_diff() {
+        let old = assets(&[
+            asset("AUDCAD_otc", 80, false),
+            asset("EURUSD_otc", 92, true),
+            asset("GBPJPY_otc", 70, true),
+        ]);
+        let new = assets(&[
+            asset("AUDCAD_otc", 85, true),
+            asset("EURUSD_otc", 92, false),
+            asset("USDJPY_otc", 90, true),
+        ]);
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                AssetChange::Opened {
+                    symbol: "AUDCAD_otc".into()
+                },
+                AssetChange::PayoutChanged {
+                    symbol: "AUDCAD_otc".into(),
+                    old: 80,
+                    new: 85
+                },
+                AssetChange::Closed {
+                    symbol: "EURUSD_otc".into()
+                },
+                AssetChange::Removed {
+                    symbol: "GBPJPY_otc".into()
+                },
+                AssetChange::Added {
+                    symbol: "USDJPY_otc".into(),
+                    payout: 90,
+                    is_active: true
+                },
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn test_assets_search_and_filters() {
+        let mut apple = asset("#AAPL", 50, true);
+        apple.name = "Apple".to_string();
+        apple.asset_type = AssetType::Stock;
+        apple.is_otc = false;
+        let list = assets(&[
+            apple,
+            asset("EURUSD_otc", 92, true),
+            asset("EURJPY_otc", 85, false),
+        ]);
+        let symbols = |found: Vec<&Asset>| -> Vec<String> {
+            found.into_iter().map(|a| a.symbol.clone()).collect()
+        };
+
+        assert_eq!(symbols(list.search("eur")), ["EURJPY_otc", "EURUSD_otc"]);
+        assert_eq!(symbols(list.search("APPLE")), ["#AAPL"]);
+        assert!(list.search("btc").is_empty());
+        assert_eq!(symbols(list.by_type(AssetType::Stock)), ["#AAPL"]);
+        assert_eq!(symbols(list.otc()), ["EURJPY_otc", "EURUSD_otc"]);
+    }
+
+    #[tokio::test]
+    async fn test_set_assets_broadcasts_changes() {
+        let state = create_mock_state();
+        let mut changes = state.asset_changes();
+        state
+            .set_assets(assets(&[asset("EURUSD_otc", 92, true)]))
+            .await;
+        state
+            .set_assets(assets(&[asset("EURUSD_otc", 88, true)]))
+            .await;
+        assert_eq!(
+            changes.try_recv().unwrap(),
+            AssetChange::PayoutChanged {
+                symbol: "EURUSD_otc".into(),
+                old: 92,
+                new: 88
+            }
+        );
+        assert!(changes.try_recv().is_err());
+    }
 }
@@ -9,23 +9,59 @@ use binary_options_tools_core::{
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde_json::Value;
-use tracing::{debug, warn};
+use tracing::debug;
 
-use crate::pocketoption::{state::State, types::MultiPatternRule};
+use crate::pocketoption::{
+    state::State,
+    types::{AccountType, MultiPatternRule},
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BalanceMessage {
     balance: Decimal,
+    #[serde(default)]
+    currency: Option<String>,
+    #[serde(default)]
+    is_demo: Option<u8>,
+    #[serde(default)]
+    tournament_id: Option<Value>,
     #[serde(flatten)]
     _extra: HashMap<String, Value>,
 }
 
+impl BalanceMessage {
+    /// Account type the balance belongs to, `None` if the message does not say.
+    fn account_type(&self) -> Option<AccountType> {
+        if self.tournament_id.as_ref().is_some_and(|id| !id.is_null()) {
+            return Some(AccountType::Tournament);
+        }
+        self.is_demo.map(|demo| {
+            if demo == 1 {
+                AccountType::Demo
+            } else {
+                AccountType::Real
+            }
+        })
+    }
+}
+
 pub struct BalanceModule {
     state: Arc<State>,
     receiver: AsyncReceiver<Arc<Message>>,
 }
 
+impl BalanceModule {
+    async fn update(&self, msg: BalanceMessage) {
+        let account = msg
+            .account_type()
+            .unwrap_or_else(|| self.state.account_type());
+        self.state
+            .set_account_balance(account, msg.balance, msg.currency)
+            .await;
+    }
+}
+
 #[async_trait]
 impl LightweightModule<State> for BalanceModule {
     fn new(
@@ -40,18 +76,17 @@ impl LightweightModule<State> for BalanceModule {
     async fn run(&mut self) -> CoreResult<()> {
         while let Ok(msg) = self.receiver.recv().await {
             match &*msg {
-                Message::Binary(data) => {
-                    if let Ok(balance_msg) = serde_json::from_slice::<BalanceMessage>(data) {
+                Message::Binary(data) => match serde_json::from_slice::<BalanceMessage>(data) {
+                    Ok(balance_msg) => {
                         debug!("Received balance message (binary): {:?}", balance_msg);
-                        self.state.set_balance(balance_msg.balance).await;
-                    } else {
-                        warn!("Failed to parse balance message (binary): {:?}", data);
+                        self.update(balance_msg).await;
                     }
-                }
+                    Err(e) => self.state.quarantine("BalanceModule", &msg, e),
+                },
                 Message::Text(text) => {
                     if let Ok(balance_msg) = serde_json::from_str::<BalanceMessage>(text) {
                         debug!("Received balance message (text): {:?}", balance_msg);
-                        self.state.set_balance(balance_msg.balance).await;
+                        self.update(balance_msg).await;
                     } else if let Some(start) = text.find('[') {
                         // Try to parse as a 1-step Socket.IO message: 42["successupdateBalance", {...}]
                         match serde_json::from_str::<serde_json::Value>(&text[start..]) {
@@ -66,24 +101,16 @@ impl LightweightModule<State> for BalanceModule {
                                                     "Received balance message (text 1-step): {:?}",
                                                     balance_msg
                                                 );
-                                                self.state.set_balance(balance_msg.balance).await;
+                                                self.update(balance_msg).await;
                                             }
                                             Err(e) => {
-                                                warn!(
-                                                    "Failed to deserialize BalanceMessage from Socket.IO payload (arr[1]): {}. Raw text slice: {}",
-                                                    e, &text[start..]
-                                                );
+                                                self.state.quarantine("BalanceModule", &msg, e)
                                             }
                                         }
                                     }
                                 }
                             }
-                            Err(e) => {
-                                warn!(
-                                    "Failed to parse Socket.IO JSON envelope for balance: {}. Raw text slice: {}",
-                                    e, &text[start..]
-                                );
-                            }
+                            Err(e) => self.state.quarantine("BalanceModule", &msg, e),
                         }
                     }
                 }
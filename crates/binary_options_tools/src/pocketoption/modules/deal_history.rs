@@ -0,0 +1,395 @@
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use binary_options_tools_core::{
+    error::{CoreError, CoreResult},
+    reimports::{AsyncReceiver, AsyncSender, Message},
+    traits::{ApiModule, Rule, RunnerCommand},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{select, sync::oneshot};
+use tracing::debug;
+
+use crate::pocketoption::{
+    error::{PocketError, PocketResult},
+    state::State,
+    types::{Deal, MultiPatternRule},
+    utils::SocketIoFrame,
+};
+
+/// Socket.IO event used to request a page of closed deals.
+const REQUEST_EVENT: &str = "getClosedDeals";
+/// Socket.IO event carrying the requested page of closed deals.
+const RESPONSE_EVENT: &str = "successgetClosedDeals";
+/// Default number of deals requested per page.
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+/// Time to wait for a single page before giving up.
+const PAGE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A single page of closed deals returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DealHistoryPage {
+    pub deals: Vec<Deal>,
+    /// Offset used to request this page.
+    pub offset: u32,
+    /// Whether the server may have more deals after this page.
+    pub has_more: bool,
+}
+
+/// Raw server payload: the server either sends a bare list of deals or wraps it.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ServerResponse {
+    Wrapped { deals: Vec<Deal> },
+    List(Vec<Deal>),
+}
+
+impl ServerResponse {
+    fn into_deals(self) -> Vec<Deal> {
+        match self {
+            ServerResponse::Wrapped { deals } | ServerResponse::List(deals) => deals,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Command {
+    /// Request a single page of closed deals in the `[from, to]` range.
+    GetPage {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        offset: u32,
+        limit: u32,
+        responder: oneshot::Sender<PocketResult<DealHistoryPage>>,
+    },
+}
+
+/// Kept for trait compatibility, responses are delivered through oneshot channels.
+#[derive(Debug)]
+pub enum CommandResponse {}
+
+/// Handle for fetching the account's closed deals history from the server.
+#[derive(Clone)]
+pub struct DealHistoryHandle {
+    sender: AsyncSender<Command>,
+    _receiver: AsyncReceiver<CommandResponse>,
+}
+
+impl DealHistoryHandle {
+    /// Fetches a single page of closed deals between `from` and `to`.
+    pub async fn page(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        offset: u32,
+        limit: u32,
+    ) -> PocketResult<DealHistoryPage> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Command::GetPage {
+                from,
+                to,
+                offset,
+                limit,
+                responder: tx,
+            })
+            .await
+            .map_err(CoreError::from)?;
+
+        match tokio::time::timeout(PAGE_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(PocketError::General(
+                "DealHistoryApiModule responder dropped".into(),
+            )),
+            Err(_) => Err(PocketError::Timeout {
+                task: "closed_deals_history".to_string(),
+                context: format!("from: {from}, to: {to}, offset: {offset}"),
+                duration: PAGE_TIMEOUT,
+            }),
+        }
+    }
+
+    /// Fetches every closed deal between `from` and `to`, following pagination
+    /// until the server returns a short page.
+    pub async fn range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        page_size: u32,
+    ) -> PocketResult<Vec<Deal>> {
+        let page_size = page_size.max(1);
+        let mut offset = 0;
+        let mut deals = Vec::new();
+        loop {
+            let page = self.page(from, to, offset, page_size).await?;
+            deals.extend(page.deals);
+            if !page.has_more {
+                break;
+            }
+            offset += page_size;
+        }
+        Ok(deals)
+    }
+}
+
+struct PendingPage {
+    offset: u32,
+    limit: u32,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    responder: oneshot::Sender<PocketResult<DealHistoryPage>>,
+}
+
+/// API module that requests historical closed deals from the server.
+///
+/// Unlike `DealsApiModule`, which only sees deals closed during the current session,
+/// this module queries the server so that a restarted client can rebuild its history.
+/// The server does not echo a request id, so requests are answered in FIFO order, skipping
+/// the ones whose caller stopped waiting.
+pub struct DealHistoryApiModule {
+    state: Arc<State>,
+    command_receiver: AsyncReceiver<Command>,
+    _command_responder: AsyncSender<CommandResponse>,
+    message_receiver: AsyncReceiver<Arc<Message>>,
+    to_ws_sender: AsyncSender<Message>,
+    pending: VecDeque<PendingPage>,
+}
+
+impl DealHistoryApiModule {
+    fn parse_response(msg: &Message) -> Option<Vec<Deal>> {
+        let parsed = match msg {
+            Message::Binary(data) => serde_json::from_slice::<ServerResponse>(data).ok(),
+            Message::Text(text) => {
+                serde_json::from_str::<ServerResponse>(text)
+                    .ok()
+                    .or_else(|| {
+                        let (event, payload) = SocketIoFrame::parse(text)?.extract_event()?;
+                        if event != RESPONSE_EVENT {
+                            return None;
+                        }
+                        serde_json::from_value::<ServerResponse>(payload).ok()
+                    })
+            }
+            _ => None,
+        };
+        parsed.map(ServerResponse::into_deals)
+    }
+
+    /// Drops the requests whose caller gave up, their responses never arrived and would
+    /// otherwise shift every later page onto the previous request.
+    fn prune_abandoned(&mut self) {
+        self.pending
+            .retain(|pending| !pending.responder.is_closed());
+    }
+
+    fn notify_waiters_module_stopped(&mut self) {
+        for pending in self.pending.drain(..) {
+            let _ = pending.responder.send(Err(PocketError::ModuleStopped {
+                module_name: "DealHistoryApiModule".to_string(),
+                context: format!("offset: {}", pending.offset),
+            }));
+        }
+    }
+}
+
+impl Drop for DealHistoryApiModule {
+    fn drop(&mut self) {
+        self.notify_waiters_module_stopped();
+    }
+}
+
+#[async_trait]
+impl ApiModule<State> for DealHistoryApiModule {
+    type Command = Command;
+    type CommandResponse = CommandResponse;
+    type Handle = DealHistoryHandle;
+
+    fn new(
+        state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        command_responder: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self {
+            state,
+            command_receiver,
+            _command_responder: command_responder,
+            message_receiver,
+            to_ws_sender,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        receiver: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        DealHistoryHandle {
+            sender,
+            _receiver: receiver,
+        }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                cmd = self.command_receiver.recv() => {
+                    match cmd {
+                        Ok(Command::GetPage { from, to, offset, limit, responder }) => {
+                            let payload = json!([REQUEST_EVENT, {
+                                "from": from.timestamp(),
+                                "to": to.timestamp(),
+                                "offset": offset,
+                                "limit": limit,
+                            }]);
+                            let msg = Message::text(format!("42{payload}"));
+                            if let Err(e) = self.to_ws_sender.send(msg).await {
+                                let _ = responder.send(Err(CoreError::from(e).into()));
+                                continue;
+                            }
+                            self.pending.push_back(PendingPage { offset, limit, from, to, responder });
+                        }
+                        Err(_) => {
+                            self.notify_waiters_module_stopped();
+                            return Ok(());
+                        }
+                    }
+                }
+                msg = self.message_receiver.recv() => {
+                    let msg = match msg {
+                        Ok(msg) => msg,
+                        Err(_) => {
+                            self.notify_waiters_module_stopped();
+                            return Ok(());
+                        }
+                    };
+                    let Some(deals) = Self::parse_response(&msg) else {
+                        self.state.quarantine("DealHistoryApiModule", &msg, "unexpected closed deals history format");
+                        continue;
+                    };
+                    self.prune_abandoned();
+                    let Some(pending) = self.pending.pop_front() else {
+                        debug!(target: "DealHistoryApiModule", "Received closed deals history with no pending request");
+                        continue;
+                    };
+                    let has_more = deals.len() as u32 >= pending.limit;
+                    let deals: Vec<Deal> = deals
+                        .into_iter()
+                        .filter(|d| d.close_timestamp >= pending.from && d.close_timestamp <= pending.to)
+                        .collect();
+                    let page = DealHistoryPage {
+                        deals,
+                        offset: pending.offset,
+                        has_more,
+                    };
+                    let _ = pending.responder.send(Ok(page));
+                }
+            }
+        }
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MultiPatternRule::new(vec![RESPONSE_EVENT]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::{ssid::Ssid, state::StateBuilder};
+    use kanal::bounded_async;
+
+    const DEAL: &str = r#"{
+        "id": "3a5c2d1e-8b4f-4c6d-9e0a-1b2c3d4e5f60",
+        "openTime": "2026-01-01 10:00:00",
+        "closeTime": "2026-01-01 10:01:00",
+        "openTimestamp": 1767261600,
+        "closeTimestamp": 1767261660,
+        "uid": 1,
+        "amount": 1.0,
+        "profit": 0.92,
+        "percentProfit": 92,
+        "percentLoss": 100,
+        "openPrice": 1.1,
+        "closePrice": 1.2,
+        "command": 0,
+        "asset": "EURUSD_otc",
+        "isDemo": 1,
+        "copyTicket": "",
+        "openMs": 0,
+        "optionType": 100,
+        "currency": "USD"
+    }"#;
+
+    #[test]
+    fn test_parse_wrapped_and_list_responses() {
+        let wrapped = format!(r#"42["{RESPONSE_EVENT}",{{"deals":[{DEAL}]}}]"#);
+        let deals = DealHistoryApiModule::parse_response(&Message::text(wrapped)).unwrap();
+        assert_eq!(deals.len(), 1);
+
+        let list = format!("[{DEAL},{DEAL}]");
+        let deals =
+            DealHistoryApiModule::parse_response(&Message::binary(list.into_bytes())).unwrap();
+        assert_eq!(deals.len(), 2);
+        assert_eq!(deals[0].asset, "EURUSD_otc");
+    }
+
+    #[test]
+    fn test_parse_ignores_other_events() {
+        let other = format!(r#"42["updateClosedDeals",[{DEAL}]]"#);
+        assert!(DealHistoryApiModule::parse_response(&Message::text(other)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_page_does_not_shift_responses() {
+        let ssid = r#"{"session":"test","isDemo":1,"uid":123,"platform":2}"#;
+        let state = Arc::new(
+            StateBuilder::default()
+                .ssid(Ssid::parse(ssid).unwrap())
+                .build()
+                .unwrap(),
+        );
+        let (msg_tx, msg_rx) = bounded_async::<Arc<Message>>(10);
+        let (cmd_tx, cmd_rx) = bounded_async(10);
+        let (res_tx, res_rx) = bounded_async(10);
+        let (ws_tx, ws_rx) = bounded_async::<Message>(10);
+        let (runner_tx, _runner_rx) = bounded_async::<RunnerCommand>(1);
+        let mut module = DealHistoryApiModule::new(state, cmd_rx, res_tx, msg_rx, ws_tx, runner_tx);
+        let handle = DealHistoryApiModule::create_handle(cmd_tx, res_rx);
+        let module = tokio::spawn(async move { module.run().await });
+
+        let from = DateTime::from_timestamp(1767261000, 0).unwrap();
+        let to = DateTime::from_timestamp(1767262000, 0).unwrap();
+        // The server never answers the first page and its caller gives up, as `page` does
+        // after `PAGE_TIMEOUT`.
+        let first =
+            tokio::time::timeout(Duration::from_millis(50), handle.page(from, to, 0, 10)).await;
+        assert!(first.is_err());
+        ws_rx.recv().await.unwrap();
+
+        let second = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.page(from, to, 10, 10).await }
+        });
+        ws_rx.recv().await.unwrap();
+        let response = format!(r#"42["{RESPONSE_EVENT}",[{DEAL}]]"#);
+        msg_tx
+            .send(Arc::new(Message::text(response)))
+            .await
+            .unwrap();
+
+        let page = tokio::time::timeout(Duration::from_secs(1), second)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.offset, 10);
+        assert_eq!(page.deals.len(), 1);
+        assert!(!page.has_more);
+        module.abort();
+    }
+}
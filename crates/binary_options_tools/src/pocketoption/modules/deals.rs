@@ -1,5 +1,4 @@
 use std::{
-    collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -13,16 +12,18 @@ use binary_options_tools_core::{
     reimports::{AsyncReceiver, AsyncSender, Message},
     traits::{ApiModule, Rule, RunnerCommand},
 };
+use chrono::Utc;
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use tokio::sync::oneshot;
+use tokio::{sync::oneshot, time::Instant};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::pocketoption::{
+    correlation::{PendingRequests, SWEEP_INTERVAL},
     error::{PocketError, PocketResult},
     state::State,
-    types::Deal,
+    types::{Deal, EarlyClose},
     utils::SocketIoFrame,
 };
 
@@ -32,10 +33,53 @@ const SUCCESS_CLOSE_ORDER: &str = r#"451-["successcloseOrder","#;
 const UPDATE_OPENED_DEALS_42: &str = r#"42["updateOpenedDeals","#;
 const UPDATE_CLOSED_DEALS_42: &str = r#"42["updateClosedDeals","#;
 const SUCCESS_CLOSE_ORDER_42: &str = r#"42["successcloseOrder","#;
+const FAIL_CLOSE_ORDER: &str = r#"451-["failcloseOrder","#;
+const FAIL_CLOSE_ORDER_42: &str = r#"42["failcloseOrder","#;
+
+/// How long after a deal's expiry its result may take to arrive before waiters time out.
+const DEAL_RESULT_GRACE: Duration = Duration::from_secs(60);
+/// How long to wait for `successcloseOrder` / `failcloseOrder` after asking to close a deal early.
+const CLOSE_EARLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+type CloseResponder = oneshot::Sender<PocketResult<EarlyClose>>;
+
+/// How `PocketOption::result_with_options` waits for the result of a deal.
+#[derive(Debug, Clone)]
+pub struct ResultOptions {
+    /// Wait until the deal closes, otherwise return its current status at once.
+    pub wait: bool,
+    /// Look the deal up in the server's closed deals history when its result wasn't pushed
+    /// this long after its expiry, e.g. because the `updateClosedDeals` frame was missed.
+    pub history_fallback: Option<Duration>,
+}
+
+impl Default for ResultOptions {
+    fn default() -> Self {
+        Self {
+            wait: true,
+            history_fallback: None,
+        }
+    }
+}
+
+impl ResultOptions {
+    /// Returns the current status of the deal instead of waiting for it to close.
+    pub fn no_wait(mut self) -> Self {
+        self.wait = false;
+        self
+    }
+
+    /// Falls back to the server history `grace` after the deal's expiry.
+    pub fn with_history_fallback(mut self, grace: Duration) -> Self {
+        self.history_fallback = Some(grace);
+        self
+    }
+}
 
 #[derive(Debug)]
 pub enum Command {
     CheckResult(Uuid, oneshot::Sender<PocketResult<Deal>>),
+    CloseEarly(Uuid, CloseResponder),
 }
 
 #[derive(Debug)]
@@ -49,6 +93,7 @@ enum ExpectedMessage {
     UpdateClosedDeals,
     UpdateOpenedDeals,
     SuccessCloseOrder,
+    FailCloseOrder,
     None,
 }
 
@@ -103,6 +148,20 @@ impl DealsHandle {
             }),
         }
     }
+
+    /// Asks the server to close an opened deal before its expiry and waits for the confirmation.
+    pub async fn close_early(&self, trade_id: Uuid) -> PocketResult<EarlyClose> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Command::CloseEarly(trade_id, tx))
+            .await
+            .map_err(CoreError::from)?;
+
+        match rx.await {
+            Ok(result) => result,
+            Err(_) => Err(CoreError::Other("DealsApiModule responder dropped".into()).into()),
+        }
+    }
 }
 
 /// An API module responsible for listening to deal updates (opened and closed),
@@ -112,13 +171,17 @@ impl DealsHandle {
 /// - `updateOpenedDeals`: Updates the list of currently open trades.
 /// - `updateClosedDeals`: Updates the list of closed trades and moves them from the open list.
 /// - `successcloseOrder`: Confirms that a trade has been successfully closed.
+/// - `failcloseOrder`: The server refused to close a trade early.
 pub struct DealsApiModule {
     state: Arc<State>,
     ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
     command_receiver: AsyncReceiver<Command>,
     _command_responder: AsyncSender<CommandResponse>,
     // Map of Trade ID -> List of waiters expecting the result
-    waiting_requests: HashMap<Uuid, Vec<oneshot::Sender<PocketResult<Deal>>>>,
+    waiting_requests: PendingRequests<Uuid, oneshot::Sender<PocketResult<Deal>>>,
+    // Map of Trade ID -> waiters for an early close confirmation
+    closing: PendingRequests<Uuid, CloseResponder>,
 }
 
 impl DealsApiModule {
@@ -129,12 +192,15 @@ impl DealsApiModule {
                 Ok(deals) => {
                     self.state.trade_state.update_opened_deals(deals).await;
                 }
-                Err(e) => warn!("Failed to parse UpdateOpenedDeals (text): {:?}", e),
+                Err(e) => self
+                    .state
+                    .quarantine("DealsApiModule", &Message::text(text), e),
             },
             ExpectedMessage::UpdateClosedDeals => match serde_json::from_str::<Vec<Deal>>(text) {
                 Ok(deals) => {
                     for deal in &deals {
-                        if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
+                        let waiters = self.waiting_requests.take(&deal.id);
+                        if !waiters.is_empty() {
                             info!("Trade closed: {:?}", deal);
                             for tx in waiters {
                                 let _ = tx.send(Ok(deal.clone()));
@@ -145,68 +211,163 @@ impl DealsApiModule {
                     // Periodically prune closed deals to prevent memory growth (limit to 1000)
                     self.state.trade_state.prune_closed_deals(1000).await;
                 }
-                Err(e) => warn!("Failed to parse UpdateClosedDeals (text): {:?}", e),
+                Err(e) => self
+                    .state
+                    .quarantine("DealsApiModule", &Message::text(text), e),
             },
             ExpectedMessage::SuccessCloseOrder => {
                 // Try parsing as CloseOrder struct first
                 match serde_json::from_str::<CloseOrder>(text) {
-                    Ok(mut close_order) => {
-                        let deals = std::mem::take(&mut close_order.deals);
-                        for deal in &deals {
-                            if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
-                                info!("Trade closed: {:?}", deal);
-                                for tx in waiters {
-                                    let _ = tx.send(Ok(deal.clone()));
-                                }
-                            }
-                        }
-                        self.state.trade_state.update_closed_deals(deals).await;
-                        // Prune closed deals
-                        self.state.trade_state.prune_closed_deals(1000).await;
-                    }
+                    Ok(close_order) => self.process_close_order(close_order.deals).await,
                     Err(_) => {
                         // Fallback: Try parsing as Vec<Deal> (sometimes API sends just the list)
                         match serde_json::from_str::<Vec<Deal>>(text) {
-                            Ok(deals) => {
-                                for deal in &deals {
-                                    if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
-                                        info!("Trade closed (fallback): {:?}", deal);
-                                        for tx in waiters {
-                                            let _ = tx.send(Ok(deal.clone()));
-                                        }
-                                    }
-                                }
-                                self.state.trade_state.update_closed_deals(deals).await;
-                                // Prune closed deals
-                                self.state.trade_state.prune_closed_deals(1000).await;
+                            Ok(deals) => self.process_close_order(deals).await,
+                            Err(e) => {
+                                self.state
+                                    .quarantine("DealsApiModule", &Message::text(text), e)
                             }
-                            Err(e) => warn!("Failed to parse SuccessCloseOrder (text): {:?}", e),
                         }
                     }
                 }
             }
+            ExpectedMessage::FailCloseOrder => self.process_fail_close_order(text),
             ExpectedMessage::None => {}
         }
     }
 
+    /// Completes the result and early close waiters of deals confirmed closed by `successcloseOrder`.
+    async fn process_close_order(&mut self, deals: Vec<Deal>) {
+        for deal in &deals {
+            let waiters = self.waiting_requests.take(&deal.id);
+            if !waiters.is_empty() {
+                info!("Trade closed: {:?}", deal);
+                for tx in waiters {
+                    let _ = tx.send(Ok(deal.clone()));
+                }
+            }
+            for tx in self.closing.take(&deal.id) {
+                let _ = tx.send(Ok(EarlyClose::from(deal.clone())));
+            }
+        }
+        self.state.trade_state.update_closed_deals(deals).await;
+        // Prune closed deals
+        self.state.trade_state.prune_closed_deals(1000).await;
+    }
+
+    /// Fails the early close waiters refused by `failcloseOrder`.
+    ///
+    /// The payload carries the deal `ticket` when the server sends it, otherwise every
+    /// pending early close is failed since the refusal cannot be attributed.
+    fn process_fail_close_order(&mut self, text: &str) {
+        let payload = serde_json::from_str::<serde_json::Value>(text).unwrap_or_default();
+        let reason = ["error", "message", "reason"]
+            .iter()
+            .find_map(|key| payload.get(key).and_then(|v| v.as_str()))
+            .or_else(|| payload.as_str())
+            .unwrap_or("early close not available")
+            .to_string();
+        let ticket = ["ticket", "id"]
+            .iter()
+            .find_map(|key| payload.get(key).and_then(|v| v.as_str()))
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let refused: Vec<(Uuid, CloseResponder)> = match ticket {
+            Some(id) => self
+                .closing
+                .take(&id)
+                .into_iter()
+                .map(|tx| (id, tx))
+                .collect(),
+            None => self.closing.drain().collect(),
+        };
+        if refused.is_empty() {
+            warn!(target: "DealsApiModule", "Unexpected failcloseOrder: {}", text);
+        }
+        for (deal_id, tx) in refused {
+            let _ = tx.send(Err(PocketError::EarlyCloseRejected {
+                deal_id,
+                reason: reason.clone(),
+            }));
+        }
+    }
+
+    /// Sends the early close request for an opened deal, answering at once if it cannot be closed.
+    async fn close_early(&mut self, trade_id: Uuid, responder: CloseResponder) {
+        if self
+            .state
+            .trade_state
+            .get_opened_deal(trade_id)
+            .await
+            .is_none()
+        {
+            let error = if self.state.trade_state.contains_closed_deal(trade_id).await {
+                PocketError::EarlyCloseRejected {
+                    deal_id: trade_id,
+                    reason: "deal already closed".to_string(),
+                }
+            } else {
+                PocketError::DealNotFound(trade_id)
+            };
+            let _ = responder.send(Err(error));
+            return;
+        }
+        let request = format!(r#"42["closeOrder",{{"ticket":"{trade_id}"}}]"#);
+        if let Err(e) = self.ws_sender.send(Message::text(request)).await {
+            let _ = responder.send(Err(CoreError::from(e).into()));
+            return;
+        }
+        self.closing
+            .insert(trade_id, responder, CLOSE_EARLY_TIMEOUT);
+    }
+
     /// Notifies all pending waiters that the module has stopped.
     /// This prevents "responder dropped" errors by properly cleaning up pending requests.
     fn notify_waiters_module_stopped(&mut self) {
-        let waiters = std::mem::take(&mut self.waiting_requests);
-        if !waiters.is_empty() {
+        if !self.waiting_requests.is_empty() {
             tracing::info!(
                 "DealsApiModule: Notifying {} pending waiters that module has stopped",
-                waiters.len()
+                self.waiting_requests.len()
             );
         }
-        for (trade_id, responders) in waiters {
-            for responder in responders {
-                let error = PocketError::ModuleStopped {
-                    module_name: "DealsApiModule".to_string(),
-                    context: format!("Trade ID: {}", trade_id),
-                };
-                let _ = responder.send(Err(error));
-            }
+        for (trade_id, responder) in self.waiting_requests.drain() {
+            let error = PocketError::ModuleStopped {
+                module_name: "DealsApiModule".to_string(),
+                context: format!("Trade ID: {}", trade_id),
+            };
+            let _ = responder.send(Err(error));
+        }
+        for (trade_id, responder) in self.closing.drain() {
+            let error = PocketError::ModuleStopped {
+                module_name: "DealsApiModule".to_string(),
+                context: format!("Closing trade ID: {}", trade_id),
+            };
+            let _ = responder.send(Err(error));
+        }
+    }
+
+    /// Fails waiters for deals that did not close within `DEAL_RESULT_GRACE` of their
+    /// expiry and forgets the ones whose caller stopped waiting.
+    fn sweep_waiters(&mut self) {
+        let expired = self
+            .waiting_requests
+            .sweep(Instant::now(), |responder| responder.is_closed());
+        for (trade_id, responder) in expired {
+            warn!(target: "DealsApiModule", "No result for trade {} within {:?} of its expiry", trade_id, DEAL_RESULT_GRACE);
+            let _ = responder.send(Err(PocketError::Timeout {
+                task: "check_result".to_string(),
+                context: format!("Waiting for trade '{trade_id}' to close"),
+                duration: DEAL_RESULT_GRACE,
+            }));
+        }
+        let expired = self
+            .closing
+            .sweep(Instant::now(), |responder| responder.is_closed());
+        for (trade_id, responder) in expired {
+            let _ = responder.send(Err(PocketError::Timeout {
+                task: "close_deal_early".to_string(),
+                context: format!("Waiting for trade '{trade_id}' to close early"),
+                duration: CLOSE_EARLY_TIMEOUT,
+            }));
         }
     }
 }
@@ -228,15 +389,17 @@ impl ApiModule<State> for DealsApiModule {
         command_receiver: AsyncReceiver<Self::Command>,
         command_responder: AsyncSender<Self::CommandResponse>,
         ws_receiver: AsyncReceiver<Arc<Message>>,
-        _ws_sender: AsyncSender<Message>,
+        ws_sender: AsyncSender<Message>,
         _: AsyncSender<RunnerCommand>,
     ) -> Self {
         Self {
+            waiting_requests: PendingRequests::new(state.correlation_stats.clone()),
+            closing: PendingRequests::new(state.correlation_stats.clone()),
             state,
             ws_receiver,
+            ws_sender,
             command_receiver,
             _command_responder: command_responder,
-            waiting_requests: HashMap::new(),
         }
     }
 
@@ -252,6 +415,7 @@ impl ApiModule<State> for DealsApiModule {
 
     async fn run(&mut self) -> binary_options_tools_core::error::CoreResult<()> {
         let mut expected = ExpectedMessage::None;
+        let mut sweep = tokio::time::interval(SWEEP_INTERVAL);
         loop {
             tokio::select! {
                 biased;
@@ -270,6 +434,8 @@ impl ApiModule<State> for DealsApiModule {
                                                 ExpectedMessage::UpdateClosedDeals
                                             } else if event == "successcloseOrder" {
                                                 ExpectedMessage::SuccessCloseOrder
+                                            } else if event == "failcloseOrder" {
+                                                ExpectedMessage::FailCloseOrder
                                             } else {
                                                 ExpectedMessage::None
                                             };
@@ -308,14 +474,15 @@ impl ApiModule<State> for DealsApiModule {
                                                 Ok(deals) => {
                                                     self.state.trade_state.update_opened_deals(deals).await;
                                                 },
-                                                Err(e) => warn!("Failed to parse UpdateOpenedDeals (binary): {:?}", e),
+                                                Err(e) => self.state.quarantine("DealsApiModule", &msg, e),
                                             }
                                         }
                                         ExpectedMessage::UpdateClosedDeals => {
                                             match serde_json::from_slice::<Vec<Deal>>(data) {
                                                 Ok(deals) => {
                                                     for deal in &deals {
-                                                        if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
+                                                        let waiters = self.waiting_requests.take(&deal.id);
+                                                        if !waiters.is_empty() {
                                                             info!("Trade closed: {:?}", deal);
                                                             for tx in waiters {
                                                                 let _ = tx.send(Ok(deal.clone()));
@@ -326,46 +493,24 @@ impl ApiModule<State> for DealsApiModule {
                                                     // Prune closed deals
                                                     self.state.trade_state.prune_closed_deals(1000).await;
                                                 },
-                                                Err(e) => warn!("Failed to parse UpdateClosedDeals (binary): {:?}", e),
+                                                Err(e) => self.state.quarantine("DealsApiModule", &msg, e),
                                             }
                                         }
                                         ExpectedMessage::SuccessCloseOrder => {
                                             match serde_json::from_slice::<CloseOrder>(data) {
-                                                Ok(mut close_order) => {
-                                                    let deals = std::mem::take(&mut close_order.deals);
-                                                    for deal in &deals {
-                                                        if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
-                                                            info!("Trade closed: {:?}", deal);
-                                                            for tx in waiters {
-                                                                let _ = tx.send(Ok(deal.clone()));
-                                                            }
-                                                        }
-                                                    }
-                                                    self.state.trade_state.update_closed_deals(deals).await;
-                                                    // Prune closed deals
-                                                    self.state.trade_state.prune_closed_deals(1000).await;
-                                                },
+                                                Ok(close_order) => self.process_close_order(close_order.deals).await,
                                                 Err(_) => {
                                                      // Fallback: Try parsing as Vec<Deal>
                                                      match serde_json::from_slice::<Vec<Deal>>(data) {
-                                                        Ok(deals) => {
-                                                            for deal in &deals {
-                                                                if let Some(waiters) = self.waiting_requests.remove(&deal.id) {
-                                                                    info!("Trade closed (fallback): {:?}", deal);
-                                                                    for tx in waiters {
-                                                                        let _ = tx.send(Ok(deal.clone()));
-                                                                    }
-                                                                }
-                                                            }
-                                                            self.state.trade_state.update_closed_deals(deals).await;
-                                                            // Prune closed deals
-                                                            self.state.trade_state.prune_closed_deals(1000).await;
-                                                        }
-                                                        Err(e) => warn!("Failed to parse SuccessCloseOrder (binary): {:?}", e),
+                                                        Ok(deals) => self.process_close_order(deals).await,
+                                                        Err(e) => self.state.quarantine("DealsApiModule", &msg, e),
                                                     }
                                                 }
                                             }
                                         },
+                                        ExpectedMessage::FailCloseOrder => {
+                                            self.process_fail_close_order(&String::from_utf8_lossy(data));
+                                        },
                                         ExpectedMessage::None => {
                                             let payload_preview = if data.len() > 64 {
                                                 format!("Payload ({} bytes, truncated): {:?}", data.len(), &data[..64])
@@ -391,14 +536,18 @@ impl ApiModule<State> for DealsApiModule {
                         }
                     }
                 }
+                _ = sweep.tick() => {
+                    self.sweep_waiters();
+                }
                 cmd_res = self.command_receiver.recv() => {
                     match cmd_res {
                         Ok(cmd) => {
                             match cmd {
                                 Command::CheckResult(trade_id, responder) => {
-                                    if self.state.trade_state.contains_opened_deal(trade_id).await {
-                                        // If the deal is still opened, add it to the waitlist
-                                        self.waiting_requests.entry(trade_id).or_default().push(responder);
+                                    if let Some(deal) = self.state.trade_state.get_opened_deal(trade_id).await {
+                                        // If the deal is still opened, add it to the waitlist until shortly after it expires
+                                        let remaining = (deal.close_timestamp - Utc::now()).to_std().unwrap_or_default();
+                                        self.waiting_requests.insert(trade_id, responder, remaining + DEAL_RESULT_GRACE);
                                     } else if let Some(deal) = self.state.trade_state.get_closed_deal(trade_id).await {
                                         // If the deal is already closed, send the result immediately
                                         let _ = responder.send(Ok(deal));
@@ -407,6 +556,9 @@ impl ApiModule<State> for DealsApiModule {
                                         let _ = responder.send(Err(PocketError::DealNotFound(trade_id)));
                                     }
                                 }
+                                Command::CloseEarly(trade_id, responder) => {
+                                    self.close_early(trade_id, responder).await;
+                                }
                             }
                         }
                         Err(_) => {
@@ -427,14 +579,17 @@ impl ApiModule<State> for DealsApiModule {
         // 451-["updateOpenedDeals",...]
         // 451-["updateClosedDeals",...]
         // 451-["successcloseOrder",...]
+        // 451-["failcloseOrder",...]
 
         Box::new(DealsUpdateRule::new(vec![
             UPDATE_CLOSED_DEALS,
             UPDATE_OPENED_DEALS,
             SUCCESS_CLOSE_ORDER,
+            FAIL_CLOSE_ORDER,
             UPDATE_CLOSED_DEALS_42,
             UPDATE_OPENED_DEALS_42,
             SUCCESS_CLOSE_ORDER_42,
+            FAIL_CLOSE_ORDER_42,
         ]))
     }
 }
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::pocketoption::{
+        error::PocketError,
         modules::deals::{Command, CommandResponse, DealsApiModule},
         state::TradeState,
         types::Deal,
@@ -144,4 +145,127 @@ mod tests {
 
         module_handle.abort();
     }
+
+    async fn opened_deal_module(
+        deal: Deal,
+    ) -> (
+        DealsApiModule,
+        kanal::AsyncSender<Arc<Message>>,
+        kanal::AsyncSender<Command>,
+        kanal::AsyncReceiver<Message>,
+    ) {
+        let trade_state = Arc::new(TradeState::default());
+        let state = Arc::new(
+            crate::pocketoption::state::StateBuilder::default()
+                .ssid(
+                    crate::pocketoption::ssid::Ssid::parse(
+                        "{\"session\":\"test\",\"isDemo\":1,\"uid\":123,\"platform\":2}",
+                    )
+                    .unwrap(),
+                )
+                .build_with_trade_state(trade_state.clone())
+                .unwrap(),
+        );
+        trade_state.add_opened_deal(deal).await;
+
+        let (ws_tx, ws_rx) = bounded_async::<Arc<Message>>(10);
+        let (cmd_tx, cmd_rx) = bounded_async::<Command>(10);
+        let (res_tx, _res_rx) = bounded_async::<CommandResponse>(10);
+        let (ws_sender_tx, ws_sender_rx) = bounded_async::<Message>(10);
+        let (runner_tx, _runner_rx) = bounded_async::<RunnerCommand>(1);
+
+        let module = DealsApiModule::new(state, cmd_rx, res_tx, ws_rx, ws_sender_tx, runner_tx);
+        (module, ws_tx, cmd_tx, ws_sender_rx)
+    }
+
+    #[tokio::test]
+    async fn test_close_early_confirmed() {
+        let deal_id = Uuid::new_v4();
+        let mut deal = create_mock_deal(deal_id);
+        let (mut module, ws_tx, cmd_tx, ws_sender_rx) = opened_deal_module(deal.clone()).await;
+        let module_handle = tokio::spawn(async move { module.run().await });
+
+        let (tx, rx) = oneshot::channel();
+        cmd_tx.send(Command::CloseEarly(deal_id, tx)).await.unwrap();
+
+        let request = ws_sender_rx.recv().await.unwrap();
+        assert_eq!(
+            request,
+            Message::text(format!(r#"42["closeOrder",{{"ticket":"{deal_id}"}}]"#))
+        );
+
+        // Closed at a loss of 40, so 60 of the 100 stake comes back.
+        deal.profit = "-40.0".parse().unwrap();
+        let event = json!(["successcloseOrder", {"profit": -40.0, "deals": [deal]}]);
+        let msg = format!("42{}", serde_json::to_string(&event).unwrap());
+        ws_tx
+            .send(Arc::new(Message::Text(msg.into())))
+            .await
+            .unwrap();
+
+        let closed = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(closed.deal_id, deal_id);
+        assert_eq!(closed.returned, "60.0".parse().unwrap());
+
+        module_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_close_early_rejected() {
+        let deal_id = Uuid::new_v4();
+        let (mut module, ws_tx, cmd_tx, ws_sender_rx) =
+            opened_deal_module(create_mock_deal(deal_id)).await;
+        let module_handle = tokio::spawn(async move { module.run().await });
+
+        let (tx, rx) = oneshot::channel();
+        cmd_tx.send(Command::CloseEarly(deal_id, tx)).await.unwrap();
+        ws_sender_rx.recv().await.unwrap();
+
+        let event = json!(["failcloseOrder", {"ticket": deal_id, "error": "not available"}]);
+        let msg = format!("42{}", serde_json::to_string(&event).unwrap());
+        ws_tx
+            .send(Arc::new(Message::Text(msg.into())))
+            .await
+            .unwrap();
+
+        let result = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .unwrap()
+            .unwrap();
+        match result {
+            Err(PocketError::EarlyCloseRejected {
+                deal_id: id,
+                reason,
+            }) => {
+                assert_eq!(id, deal_id);
+                assert_eq!(reason, "not available");
+            }
+            other => panic!("expected EarlyCloseRejected, got {other:?}"),
+        }
+
+        module_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_close_early_unknown_deal() {
+        let (mut module, _ws_tx, cmd_tx, _ws_sender_rx) =
+            opened_deal_module(create_mock_deal(Uuid::new_v4())).await;
+        let module_handle = tokio::spawn(async move { module.run().await });
+
+        let unknown = Uuid::new_v4();
+        let (tx, rx) = oneshot::channel();
+        cmd_tx.send(Command::CloseEarly(unknown, tx)).await.unwrap();
+
+        let result = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(result, Err(PocketError::DealNotFound(id)) if id == unknown));
+
+        module_handle.abort();
+    }
 }
@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use binary_options_tools_core::{
@@ -16,6 +15,7 @@ use uuid::Uuid;
 use crate::pocketoption::{
     candle::{compile_candles_from_ticks, Candle, HistoryItem},
     error::{PocketError, PocketResult},
+    quality::{validate_candles, DataSource},
     state::State,
     types::MultiPatternRule,
     utils::{get_index, normalize_timestamp, SocketIoFrame},
@@ -25,6 +25,10 @@ const LOAD_HISTORY_PERIOD_PATTERNS: [&str; 2] = ["loadHistoryPeriodFast", "loadH
 
 /// Default number of ticks/candles to fetch per pagination page.
 const DEFAULT_PAGE_OFFSET: i64 = 1000;
+/// Safety limit on the pages fetched by `get_ticks`, to prevent infinite loops.
+const LOOKBACK_MAX_PAGES: usize = 20;
+/// Safety limit on the pages fetched by `get_ticks_range`, which may span days of history.
+const RANGE_MAX_PAGES: usize = 500;
 /// Maximum number of ticks to keep per asset in `latest_ticks`.
 const MAX_TICKS_PER_ASSET: usize = 10000;
 /// Maximum age (in seconds) for ticks in `latest_ticks`. Ticks older than this are pruned.
@@ -287,88 +291,96 @@ impl GetCandlesHandle {
         period: i64,
         lookback_seconds: i64,
     ) -> PocketResult<Vec<(i64, f64)>> {
-        let asset_str = asset.to_string();
-        let now = chrono::Utc::now().timestamp();
-        let target_time = now - lookback_seconds;
-        let page_offset: i64 = DEFAULT_PAGE_OFFSET; // Fetch ticks per page
+        self.get_ticks_until(
+            asset,
+            period,
+            lookback_seconds,
+            chrono::Utc::now().timestamp(),
+        )
+        .await
+    }
+
+    /// Same as [`GetCandlesHandle::get_ticks`], looking back from the unix timestamp `now`
+    /// instead of the system time.
+    pub async fn get_ticks_until(
+        &self,
+        asset: impl ToString,
+        period: i64,
+        lookback_seconds: i64,
+        now: i64,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        self.collect_ticks(
+            asset.to_string(),
+            period,
+            now - lookback_seconds,
+            now,
+            LOOKBACK_MAX_PAGES,
+        )
+        .await
+    }
+
+    /// Gets the tick data (timestamp, price) of an asset between two unix timestamps (seconds).
+    ///
+    /// Pages backwards through the server history from `to` until `from` is reached,
+    /// merging the pages and dropping the ticks repeated at page boundaries.
+    ///
+    /// # Arguments
+    /// * `asset` - Trading symbol (e.g., "EURUSD_otc")
+    /// * `period` - Time period in seconds (used as context for the server)
+    /// * `from` - Range start (inclusive)
+    /// * `to` - Range end (inclusive)
+    ///
+    /// # Returns
+    /// A vector of (timestamp, price) tuples sorted by timestamp
+    pub async fn get_ticks_range(
+        &self,
+        asset: impl ToString,
+        period: i64,
+        from: i64,
+        to: i64,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        if from > to {
+            return Err(PocketError::General(format!(
+                "Invalid tick range: from ({from}) is after to ({to})"
+            )));
+        }
+        self.collect_ticks(asset.to_string(), period, from, to, RANGE_MAX_PAGES)
+            .await
+    }
 
+    async fn collect_ticks(
+        &self,
+        asset: String,
+        period: i64,
+        from: i64,
+        to: i64,
+        max_pages: usize,
+    ) -> PocketResult<Vec<(i64, f64)>> {
         let mut all_ticks: Vec<(i64, f64)> = Vec::new();
-        let mut current_time = now;
-        let mut max_pages = 20; // Safety limit to prevent infinite loops
+        let mut current_time = to;
+        let mut pages = 0;
 
         loop {
-            let req_id = Uuid::new_v4();
-            // Use loadHistoryPeriodFast for small offsets if needed, but here we use the module's logic
-            info!(target: "GetCandlesHandle", "Requesting ticks for asset: {}, period: {}, time: {}, offset: {}", asset_str, period, current_time, page_offset);
-
-            self.sender
-                .send(Command::GetTicks {
-                    asset: asset_str.clone(),
-                    period,
-                    time: current_time,
-                    offset: page_offset,
-                    req_id,
-                })
-                .await
-                .map_err(CoreError::from)?;
-
-            // Wait for the response
-            let ticks = loop {
-                match self.receiver.recv().await {
-                    Ok(CommandResponse::TicksResult {
-                        req_id: response_id,
-                        ticks,
-                    }) => {
-                        if req_id == response_id {
-                            break ticks;
-                        }
-                    }
-                    Ok(CommandResponse::Error {
-                        req_id: response_id,
-                        error,
-                    }) => {
-                        if req_id == response_id {
-                            return Err(PocketError::General(error));
-                        }
-                    }
-                    Ok(CommandResponse::Shutdown {
-                        req_id: response_id,
-                    }) => {
-                        if req_id == response_id {
-                            return Err(PocketError::ModuleStopped {
-                                module_name: "GetCandlesApiModule".to_string(),
-                                context: "GetCandlesApiModule stopped during request".to_string(),
-                            });
-                        }
-                    }
-                    Ok(_) => continue,
-                    Err(e) => return Err(CoreError::from(e).into()),
-                }
-            };
-
+            let ticks = self.ticks_page(&asset, period, current_time).await?;
             if ticks.is_empty() {
                 break; // No more data
             }
 
-            let earliest_tick_time = ticks.first().map(|(t, _)| *t).unwrap_or(current_time);
+            let earliest_tick_time = ticks.iter().map(|(t, _)| *t).min().unwrap_or(current_time);
 
-            // Add ticks that are within our lookback window
-            for (ts, price) in &ticks {
-                if *ts >= target_time {
-                    all_ticks.push((*ts, *price));
-                }
-            }
+            // Add ticks that are within the requested range
+            all_ticks.extend(ticks.into_iter().filter(|(ts, _)| (from..=to).contains(ts)));
 
-            // Check if we've covered the lookback period
-            if earliest_tick_time <= target_time {
+            // Check if we've covered the range, or the server stopped going further back
+            if earliest_tick_time <= from || earliest_tick_time >= current_time {
                 break;
             }
 
             // Move to the next page
             current_time = earliest_tick_time;
-            max_pages -= 1;
-            if max_pages <= 0 {
-                warn!(target: "GetCandlesHandle", "Reached max pagination pages for {}", asset_str);
+            pages += 1;
+            if pages >= max_pages {
+                warn!(target: "GetCandlesHandle", "Reached max pagination pages for {}", asset);
                 break;
             }
         }
@@ -377,14 +389,69 @@ impl GetCandlesHandle {
         all_ticks.sort_by_key(|a| a.0);
         all_ticks.dedup_by(|a, b| a.0 == b.0);
 
-        info!(target: "GetCandlesHandle", "Collected {} ticks for {} covering {} seconds", all_ticks.len(), asset_str, lookback_seconds);
+        info!(target: "GetCandlesHandle", "Collected {} ticks for {} between {} and {}", all_ticks.len(), asset, from, to);
         Ok(all_ticks)
     }
+
+    /// Requests one page of ticks ending at `time`.
+    async fn ticks_page(
+        &self,
+        asset: &str,
+        period: i64,
+        time: i64,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        let req_id = Uuid::new_v4();
+        info!(target: "GetCandlesHandle", "Requesting ticks for asset: {}, period: {}, time: {}, offset: {}", asset, period, time, DEFAULT_PAGE_OFFSET);
+
+        self.sender
+            .send(Command::GetTicks {
+                asset: asset.to_string(),
+                period,
+                time,
+                offset: DEFAULT_PAGE_OFFSET,
+                req_id,
+            })
+            .await
+            .map_err(CoreError::from)?;
+
+        // Wait for the response
+        loop {
+            match self.receiver.recv().await {
+                Ok(CommandResponse::TicksResult {
+                    req_id: response_id,
+                    ticks,
+                }) => {
+                    if req_id == response_id {
+                        return Ok(ticks);
+                    }
+                }
+                Ok(CommandResponse::Error {
+                    req_id: response_id,
+                    error,
+                }) => {
+                    if req_id == response_id {
+                        return Err(PocketError::General(error));
+                    }
+                }
+                Ok(CommandResponse::Shutdown {
+                    req_id: response_id,
+                }) => {
+                    if req_id == response_id {
+                        return Err(PocketError::ModuleStopped {
+                            module_name: "GetCandlesApiModule".to_string(),
+                            context: "GetCandlesApiModule stopped during request".to_string(),
+                        });
+                    }
+                }
+                Ok(_) => continue,
+                Err(e) => return Err(CoreError::from(e).into()),
+            }
+        }
+    }
 }
 
 /// API module for handling candle data requests.
 pub struct GetCandlesApiModule {
-    #[allow(dead_code)]
     state: Arc<State>,
     ws_receiver: AsyncReceiver<Arc<Message>>,
     ws_sender: AsyncSender<Message>,
@@ -702,6 +769,7 @@ impl GetCandlesApiModule {
                                         None
                                     },
                                     is_closed: true,
+                                    synthetic: false,
                                 });
                             }
                             other => {
@@ -715,6 +783,11 @@ impl GetCandlesApiModule {
                         candles.extend(compiled);
                     }
 
+                    self.state.report_data_quality(validate_candles(
+                        &candles,
+                        Some(requested_period),
+                        DataSource::Candles,
+                    ));
                     if let Err(e) = self
                         .command_responder
                         .send(CommandResponse::CandlesResult { req_id, candles })
@@ -760,11 +833,7 @@ impl GetCandlesApiModule {
 
     /// Prune `latest_ticks` to enforce maximum size and maximum age limits.
     fn prune_latest_ticks(&mut self) {
-        let cutoff = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-            .saturating_sub(MAX_TICK_AGE_SECS) as i64;
+        let cutoff = self.state.clock.now().timestamp() - MAX_TICK_AGE_SECS as i64;
         self.latest_ticks.retain(|_, ticks| {
             // Remove ticks older than the cutoff
             ticks.retain(|&(ts, _)| ts >= cutoff);
@@ -777,3 +846,53 @@ impl GetCandlesApiModule {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanal::bounded_async;
+
+    #[tokio::test]
+    async fn test_get_ticks_range_paginates_backwards() {
+        let (cmd_tx, cmd_rx) = bounded_async(8);
+        let (res_tx, res_rx) = bounded_async(8);
+        let handle = GetCandlesApiModule::create_handle(cmd_tx, res_rx);
+
+        // Fake server: every page holds the 5 seconds of ticks up to (and including) `time`.
+        let server = tokio::spawn(async move {
+            let mut requested = Vec::new();
+            while let Ok(Command::GetTicks { time, req_id, .. }) = cmd_rx.recv().await {
+                requested.push(time);
+                let ticks = (time - 4..=time).map(|t| (t, t as f64)).collect();
+                res_tx
+                    .send(CommandResponse::TicksResult { req_id, ticks })
+                    .await
+                    .unwrap();
+            }
+            requested
+        });
+
+        let ticks = handle
+            .get_ticks_range("EURUSD_otc", 1, 1_000, 1_010)
+            .await
+            .unwrap();
+        drop(handle);
+
+        assert_eq!(
+            ticks.iter().map(|(t, _)| *t).collect::<Vec<_>>(),
+            (1_000..=1_010).collect::<Vec<_>>()
+        );
+        assert_eq!(server.await.unwrap(), vec![1_010, 1_006, 1_002]);
+    }
+
+    #[tokio::test]
+    async fn test_get_ticks_range_rejects_inverted_range() {
+        let (cmd_tx, _cmd_rx) = bounded_async(1);
+        let (_res_tx, res_rx) = bounded_async(1);
+        let handle = GetCandlesApiModule::create_handle(cmd_tx, res_rx);
+        assert!(handle
+            .get_ticks_range("EURUSD_otc", 1, 10, 5)
+            .await
+            .is_err());
+    }
+}
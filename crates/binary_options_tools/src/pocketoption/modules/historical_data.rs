@@ -17,6 +17,7 @@ use crate::pocketoption::candle::{
     compile_candles_from_ticks, BaseCandle, Candle, CandleItem, HistoryItem,
 };
 use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::quality::{validate_candles, DataSource};
 use crate::pocketoption::state::State;
 use crate::pocketoption::types::MultiPatternRule;
 use crate::pocketoption::utils::normalize_timestamp;
@@ -231,7 +232,7 @@ impl HistoricalDataHandle {
 }
 
 pub struct HistoricalDataApiModule {
-    _state: Arc<State>,
+    state: Arc<State>,
     command_receiver: AsyncReceiver<Command>,
     command_responder: AsyncSender<CommandResponse>,
     message_receiver: AsyncReceiver<Arc<Message>>,
@@ -255,7 +256,7 @@ impl ApiModule<State> for HistoricalDataApiModule {
         _: AsyncSender<RunnerCommand>,
     ) -> Self {
         Self {
-            _state: shared_state,
+            state: shared_state,
             command_receiver,
             command_responder,
             message_receiver,
@@ -335,7 +336,7 @@ impl ApiModule<State> for HistoricalDataApiModule {
                                 Message::Binary(data) => match serde_json::from_slice::<ServerResponse>(data) {
                                     Ok(res) => Some(res),
                                     Err(e) => {
-                                        warn!(target: "HistoricalDataApiModule", "Failed to parse binary ServerResponse: {}", e);
+                                        self.state.quarantine("HistoricalDataApiModule", &msg, e);
                                         None
                                     }
                                 },
@@ -353,7 +354,7 @@ impl ApiModule<State> for HistoricalDataApiModule {
                                                         match serde_json::from_value::<ServerResponse>(arr[1].clone()) {
                                                             Ok(res) => Some(res),
                                                             Err(e) => {
-                                                                warn!(target: "HistoricalDataApiModule", "Failed to parse updateHistory payload: {}", e);
+                                                                self.state.quarantine("HistoricalDataApiModule", &msg, e);
                                                                 None
                                                             }
                                                         }
@@ -364,7 +365,7 @@ impl ApiModule<State> for HistoricalDataApiModule {
                                             }
                                             Ok(_) => None,
                                             Err(e) => {
-                                                warn!(target: "HistoricalDataApiModule", "Failed to parse JSON array from text: {}", e);
+                                                self.state.quarantine("HistoricalDataApiModule", &msg, e);
                                                 None
                                             }
                                         }
@@ -450,6 +451,7 @@ impl ApiModule<State> for HistoricalDataApiModule {
                                                     for cc in compiled { if cc.timestamp > last_ts { candles.push(cc); } }
                                                 }
                                                 candles.sort_by_key(|c| c.timestamp);
+                                                self.state.report_data_quality(validate_candles(&candles, Some(history_response.period), DataSource::History));
                                                 let _ = self.command_responder.send(CommandResponse::Candles { req_id, candles }).await;
                                             }
                                         }
@@ -1,19 +1,55 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use binary_options_tools_core::{
     error::{CoreError, CoreResult},
     reimports::{AsyncReceiver, AsyncSender, Message},
+    status::ConnectionStatus,
     traits::{LightweightModule, Rule, RunnerCommand},
 };
 use tracing::{debug, warn};
 
+use crate::clock::SharedClock;
+use crate::config::InitProfile;
+use crate::pocketoption::ssid::Ssid;
 use crate::pocketoption::state::State;
 
 const SID_BASE: &str = r#"0{"sid":"#;
 const SID: &str = r#"40{"sid":"#;
 
+/// The `42["auth",{...}]` message authenticating `ssid`.
+fn auth_message(ssid: &Ssid) -> String {
+    let ssid = ssid.to_string();
+    // Ensure SSID is correctly formatted for Socket.IO (starts with a packet type, usually 42)
+    if ssid.starts_with('4') {
+        ssid
+    } else {
+        debug!(target: "InitModule", "SSID does not start with Socket.IO packet type; wrapping in 42[\"auth\",...]");
+        format!(r#"42["auth",{}]"#, ssid)
+    }
+}
+
+/// Requests sent right after authenticating, without waiting for the answers. The balance
+/// comes first since `PocketOption::new` waits for it with most profiles.
+fn init_messages(profile: InitProfile, default_symbol: &str) -> Vec<String> {
+    let mut messages = vec![
+        r#"42["getBalance"]"#.to_string(),
+        r#"42["assets/load"]"#.to_string(),
+    ];
+    if profile != InitProfile::Minimal {
+        messages.extend([
+            format!(r#"42["changeSymbol",{{ "asset":"{default_symbol}","period":60 }}]"#),
+            format!(r#"42["subfor","{default_symbol}"]"#),
+            r#"42["indicator/load"]"#.to_string(),
+            r#"42["favorite/load"]"#.to_string(),
+            r#"42["price-alert/load"]"#.to_string(),
+        ]);
+    }
+    messages
+}
+
 pub struct InitModule {
     ws_sender: AsyncSender<Message>,
     ws_receiver: AsyncReceiver<Arc<Message>>,
@@ -21,8 +57,12 @@ pub struct InitModule {
     runner_command_tx: AsyncSender<RunnerCommand>,
 }
 
+/// Pings the server and re-sends the auth message as set by the keep-alive settings
+/// (`Config::keep_alive`) of the server it is connected to.
 pub struct KeepAliveModule {
     ws_sender: AsyncSender<Message>,
+    state: Arc<State>,
+    clock: SharedClock,
 }
 
 #[async_trait]
@@ -90,13 +130,7 @@ impl LightweightModule<State> for InitModule {
 
                         // Socket.IO 4.x established connection SID message: 40{"sid":"..."}
                         if text.starts_with("40") {
-                            let mut ssid_str = self.state.ssid.to_string();
-
-                            // Ensure SSID is correctly formatted for Socket.IO (starts with a packet type, usually 42)
-                            if !ssid_str.starts_with('4') {
-                                debug!(target: "InitModule", "SSID does not start with Socket.IO packet type; wrapping in 42[\"auth\",...]");
-                                ssid_str = format!(r#"42["auth",{}]"#, ssid_str);
-                            }
+                            let ssid_str = auth_message(&self.state.ssid);
 
                             let redacted_ssid = if ssid_str.len() > 20 {
                                 format!("{}...", &ssid_str[..20])
@@ -114,6 +148,7 @@ impl LightweightModule<State> for InitModule {
 
                         if text == "41" {
                             tracing::error!(target: "InitModule", "Server sent Socket.IO disconnect signal (41). Authentication rejected or session expired. Message: {}", text);
+                            self.state.set_auth_rejected();
 
                             // Log public IP on rejection to help user identify IP mismatch issues
                             if let Ok(ip) = crate::pocketoption::utils::get_public_ip().await {
@@ -160,22 +195,17 @@ impl LightweightModule<State> for InitModule {
                         if trigger_auth {
                             authenticated = true;
                             tracing::debug!(target: "InitModule", "Authentication successful! Triggering data load.");
+                            if let Err(e) = self
+                                .runner_command_tx
+                                .send(RunnerCommand::SetStatus(ConnectionStatus::Authenticated))
+                                .await
+                            {
+                                warn!(target: "InitModule", "Failed to report authenticated status: {}", e);
+                            }
 
-                            // Explicitly request everything needed for a full sync
-                            let initialization_messages = vec![
-                                r#"42["assets/load"]"#.to_string(),
-                                r#"42["indicator/load"]"#.to_string(),
-                                r#"42["favorite/load"]"#.to_string(),
-                                r#"42["price-alert/load"]"#.to_string(),
-                                r#"42["getBalance"]"#.to_string(),
-                                format!(
-                                    r#"42["changeSymbol",{{ "asset":"{}","period":60 }}]"#,
-                                    self.state.default_symbol
-                                ),
-                                format!(r#"42["subfor","{}"]"#, self.state.default_symbol),
-                            ];
-
-                            for raw_msg in initialization_messages {
+                            for raw_msg in
+                                init_messages(self.state.init_profile, &self.state.default_symbol)
+                            {
                                 self.ws_sender.send(Message::text(raw_msg)).await.inspect_err(|e| {
                                     warn!(target: "InitModule", "Failed to send init message: {}", e);
                                 })?;
@@ -258,13 +288,9 @@ impl Rule for InitRule {
                 }
                 false
             }
-            Message::Binary(_) => {
-                if self.valid.load(Ordering::SeqCst) {
-                    self.valid.store(false, Ordering::SeqCst);
-                    true
-                } else {
-                    false
-                }
+            Message::Binary(_) if self.valid.load(Ordering::SeqCst) => {
+                self.valid.store(false, Ordering::SeqCst);
+                true
             }
             Message::Close(_) => true,
             _ => false,
@@ -279,19 +305,43 @@ impl Rule for InitRule {
 #[async_trait]
 impl LightweightModule<State> for KeepAliveModule {
     fn new(
-        _: Arc<State>,
+        state: Arc<State>,
         ws_sender: AsyncSender<Message>,
         _: AsyncReceiver<Arc<Message>>,
         _: AsyncSender<RunnerCommand>,
     ) -> Self {
-        Self { ws_sender }
+        Self {
+            ws_sender,
+            clock: state.clock.clone(),
+            state,
+        }
     }
 
     async fn run(&mut self) -> CoreResult<()> {
+        let mut since_auth = Duration::ZERO;
         loop {
-            // Send a keep-alive message every 20 seconds.
-            tokio::time::sleep(std::time::Duration::from_secs(20)).await;
-            self.ws_sender.send(Message::text(r#"42["ps"]"#)).await?;
+            // Read on every ping, the settings change with the server after a reconnect.
+            let config = self.state.keep_alive_config();
+            self.clock.sleep(config.interval).await;
+            if let Some(pong) = config.pong {
+                self.state
+                    .ping_stats
+                    .write()
+                    .expect("ping stats lock poisoned")
+                    .sent(Instant::now(), pong);
+            }
+            self.ws_sender.send(Message::text(config.payload)).await?;
+
+            if let Some(reauth_interval) = config.reauth_interval {
+                since_auth += config.interval;
+                if since_auth >= reauth_interval {
+                    since_auth = Duration::ZERO;
+                    debug!(target: "KeepAliveModule", "Re-sending the auth message");
+                    self.ws_sender
+                        .send(Message::text(auth_message(&self.state.ssid)))
+                        .await?;
+                }
+            }
         }
     }
 
@@ -1,5 +1,6 @@
 pub mod assets;
 pub mod balance;
+pub mod deal_history;
 pub mod deals;
 pub mod get_candles;
 pub mod historical_data;
@@ -13,11 +14,15 @@ pub mod historical_data;
 /// ## keep_alive
 /// Contains modules for maintaining the WebSocket connection alive:
 /// - `InitModule`: Handles initial authentication and setup
-/// - `KeepAliveModule`: Sends periodic ping messages to prevent disconnection
+/// - `KeepAliveModule`: Sends periodic ping messages to prevent disconnection, at the interval and with the payload of `Config::keep_alive`
 ///
 /// ## balance
 /// Manages account balance tracking and updates from the server.
 ///
+/// ## deal_history
+/// Fetches the account's closed deals from the server with date-range pagination,
+/// so history survives client restarts.
+///
 /// ## server_time
 /// Lightweight module for synchronizing local time with server time.
 /// Automatically processes incoming price data to maintain accurate time sync.
@@ -56,6 +61,9 @@ mod deals_tests;
 #[cfg(test)]
 mod pending_trades_tests;
 
+#[cfg(test)]
+mod raw_tests;
+
 #[cfg(test)]
 mod resilient_parsing_tests;
 
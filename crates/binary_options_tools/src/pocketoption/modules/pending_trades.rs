@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use binary_options_tools_core::{
     error::{CoreError, CoreResult},
     reimports::{AsyncReceiver, AsyncSender, Message},
-    traits::{ApiModule, Rule, RunnerCommand},
+    traits::{ApiModule, Dependency, Rule, RunnerCommand},
 };
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,7 @@ use uuid::Uuid;
 
 use crate::pocketoption::{
     error::{PocketError, PocketResult},
+    modules::assets::AssetsModule,
     state::State,
     types::{FailOpenOrder, MultiPatternRule, OpenPendingOrder, PendingOrder},
     utils::SocketIoFrame,
@@ -502,7 +503,7 @@ impl ApiModule<State> for PendingTradesApiModule {
                                             }
                                         }
                                         Err(e) => {
-                                            warn!(target: "PendingTradesApiModule", "Failed to parse binary ServerResponse: {}", e);
+                                            self.state.quarantine("PendingTradesApiModule", &msg, e);
                                         }
                                     }
                                 }
@@ -532,6 +533,11 @@ impl ApiModule<State> for PendingTradesApiModule {
             "failcancelPendingOrders",
         ]))
     }
+
+    fn dependencies() -> Vec<Dependency> {
+        // Pending orders are validated against the assets.
+        vec![Dependency::on::<AssetsModule>()]
+    }
 }
 
 impl PendingTradesApiModule {
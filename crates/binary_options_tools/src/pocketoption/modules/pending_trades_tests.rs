@@ -55,23 +55,47 @@ fn create_mock_state() -> Arc<State> {
         default_symbol: "EURUSD_otc".to_string(),
         balance: tokio::sync::RwLock::new(None),
         balance_updated: Arc::new(tokio::sync::Notify::new()),
+        currency: tokio::sync::RwLock::new(None),
+        amount_limit_overrides: HashMap::new(),
+        balances: tokio::sync::RwLock::new(HashMap::new()),
         server_time: ServerTimeState::default(),
+        server_time_updated: Arc::new(tokio::sync::Notify::new()),
         assets: tokio::sync::RwLock::new(None),
         assets_updated: Arc::new(tokio::sync::Notify::new()),
+        asset_changes: tokio::sync::broadcast::channel(16).0,
+        parse_failures: tokio::sync::broadcast::channel(16).0,
+        data_quality: tokio::sync::broadcast::channel(16).0,
+        keep_alive: Default::default(),
+        keep_alive_overrides: HashMap::new(),
+        init_profile: Default::default(),
+        trade_guard: Default::default(),
+        connected_url: std::sync::RwLock::new(None),
+        ping_stats: std::sync::RwLock::new(Default::default()),
         trade_state: Arc::new(TradeState::default()),
         raw_validators: std::sync::RwLock::new(HashMap::new()),
-        active_subscriptions: tokio::sync::RwLock::new(HashMap::new()),
+        raw_reassembling: std::sync::RwLock::new(Default::default()),
+        active_subscriptions: dashmap::DashMap::new(),
+        subscriptions_updated: Arc::new(tokio::sync::Notify::new()),
+        last_ticks: dashmap::DashMap::new(),
+        ticks_updated: Arc::new(tokio::sync::Notify::new()),
         histories: tokio::sync::RwLock::new(Vec::new()),
         raw_sinks: tokio::sync::RwLock::new(HashMap::new()),
         raw_keep_alive: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
         urls: Vec::new(),
         proxy: None,
+        system_proxy: false,
+        dns: Default::default(),
+        url_blacklist: Default::default(),
         user_agent: None,
         origin: None,
         sec_websocket_extensions: None,
         tls_cipher_suites: None,
         tls_alpn: None,
         raw_subscribers: tokio::sync::RwLock::new(Vec::new()),
+        draining: std::sync::atomic::AtomicBool::new(false),
+        auth_rejected: std::sync::atomic::AtomicBool::new(false),
+        correlation_stats: Default::default(),
+        clock: crate::clock::system_clock(),
     })
 }
 
@@ -132,7 +156,7 @@ async fn test_open_pending_order_success_integrated() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -213,7 +237,7 @@ async fn test_open_pending_order_failure() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -292,7 +316,7 @@ async fn test_open_pending_order_mismatch_retry() {
         let (ws_tx, mut ws_rx) = kanal::bounded_async(10);
         let (runner_tx, _) = kanal::bounded_async(10);
         let mut ws_rx_clone = ws_rx.clone();
-        tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+        tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
         let state = create_mock_state();
         let mut module = PendingTradesApiModule::new(
             state,
@@ -452,7 +476,7 @@ async fn test_open_pending_order_with_socket_io_framing() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -529,7 +553,7 @@ async fn test_run_routes_command_to_websocket() {
 
     // Drain ws_rx in background using a clone to prevent blocking
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -625,7 +649,7 @@ async fn test_run_handles_socket_io_text_success() {
 
     // Drain ws_rx in background using a clone to prevent blocking
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -696,7 +720,7 @@ async fn test_run_handles_failure_response() {
 
     // Drain ws_rx in background using a clone to prevent blocking
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -872,7 +896,7 @@ async fn test_cancel_pending_order_success() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -945,7 +969,7 @@ async fn test_cancel_pending_order_failure() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -1016,7 +1040,7 @@ async fn test_cancel_pending_orders_batch_success() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -1091,7 +1115,7 @@ async fn test_cancel_pending_orders_batch_partial_success() {
     let (runner_tx, _) = kanal::bounded_async(10);
 
     let mut ws_rx_clone = ws_rx.clone();
-    tokio::spawn(async move { while let Ok(_) = ws_rx_clone.recv().await {} });
+    tokio::spawn(async move { while ws_rx_clone.recv().await.is_ok() {} });
 
     let state = create_mock_state();
 
@@ -1,19 +1,26 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use binary_options_tools_core::error::CoreError;
 use binary_options_tools_core::reimports::{bounded_async, AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::socketio::{AttachmentAssembler, BinaryHeader};
 use binary_options_tools_core::traits::{ApiModule, Rule, RunnerCommand};
+use serde_json::Value;
 use tokio::select;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
+use tokio::time::Instant;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::pocketoption::correlation::{PendingRequests, SWEEP_INTERVAL};
 use crate::pocketoption::error::{PocketError, PocketResult};
 use crate::pocketoption::state::State;
 use crate::traits::ValidatorTrait;
-use crate::validator::Validator;
+use crate::validator::{JsonPath, Validator};
 
 pub use crate::pocketoption::types::Outgoing;
 
@@ -29,6 +36,10 @@ pub enum Command {
     Create {
         validator: Validator,
         keep_alive: Option<Outgoing>,
+        /// Path of the id used to route responses to `SendCorrelated` callers.
+        correlation: Option<JsonPath>,
+        /// Deliver Socket.IO binary packets as a single reassembled frame.
+        reassemble: bool,
         command_id: Uuid,
     },
     Remove {
@@ -36,6 +47,15 @@ pub enum Command {
         command_id: Uuid,
     },
     Send(Outgoing),
+    /// Sends a message and hands the response of `handler_id` whose correlation id equals
+    /// `correlation_id` to `responder` instead of the handler's stream.
+    SendCorrelated {
+        handler_id: Uuid,
+        correlation_id: String,
+        msg: Outgoing,
+        timeout: Duration,
+        responder: oneshot::Sender<PocketResult<Arc<Message>>>,
+    },
 }
 
 /// Responses for RawApiModule
@@ -55,6 +75,36 @@ pub enum CommandResponse {
     Shutdown { command_id: Uuid },
 }
 
+/// Options for [`RawHandle::create_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct RawHandlerOptions {
+    /// Message re-sent after every reconnection.
+    pub keep_alive: Option<Outgoing>,
+    /// Path of the id used by [`RawHandler::send_and_wait_for`] to route responses.
+    pub correlation: Option<JsonPath>,
+    /// Receive Socket.IO binary packets (`451-[...]` plus its binary frames) as a single
+    /// `42[...]` frame with the attachments decoded in place, instead of the raw frames.
+    /// The validator is then checked against the reassembled frame.
+    pub reassemble: bool,
+}
+
+impl RawHandlerOptions {
+    pub fn keep_alive(mut self, msg: Outgoing) -> Self {
+        self.keep_alive = Some(msg);
+        self
+    }
+
+    pub fn correlation(mut self, path: JsonPath) -> Self {
+        self.correlation = Some(path);
+        self
+    }
+
+    pub fn reassemble(mut self, reassemble: bool) -> Self {
+        self.reassemble = reassemble;
+        self
+    }
+}
+
 /// Handle used by clients to create per-validator RawHandlers
 #[derive(Clone)]
 pub struct RawHandle {
@@ -68,12 +118,44 @@ impl RawHandle {
         &self,
         validator: Validator,
         keep_alive: Option<Outgoing>,
+    ) -> PocketResult<RawHandler> {
+        let options = RawHandlerOptions {
+            keep_alive,
+            ..Default::default()
+        };
+        self.create_with_options(validator, options).await
+    }
+
+    /// Create a RawHandler that routes responses by the id found at `correlation`
+    /// (e.g. `$.data.requestId`), see [`RawHandler::send_and_wait_for`].
+    pub async fn create_correlated(
+        &self,
+        validator: Validator,
+        keep_alive: Option<Outgoing>,
+        correlation: JsonPath,
+    ) -> PocketResult<RawHandler> {
+        let options = RawHandlerOptions {
+            keep_alive,
+            correlation: Some(correlation),
+            ..Default::default()
+        };
+        self.create_with_options(validator, options).await
+    }
+
+    /// Create a RawHandler with every option, see [`RawHandlerOptions`].
+    pub async fn create_with_options(
+        &self,
+        validator: Validator,
+        options: RawHandlerOptions,
     ) -> PocketResult<RawHandler> {
         let command_id = Uuid::new_v4();
+        let correlated = options.correlation.is_some();
         self.sender
             .send(Command::Create {
                 validator,
-                keep_alive,
+                keep_alive: options.keep_alive,
+                correlation: options.correlation,
+                reassemble: options.reassemble,
                 command_id,
             })
             .await
@@ -89,6 +171,7 @@ impl RawHandle {
                         id,
                         sender: self.sender.clone(),
                         receiver: stream_receiver,
+                        correlated,
                     });
                 }
                 Ok(CommandResponse::Shutdown { command_id: cid }) if cid == command_id => {
@@ -135,6 +218,7 @@ pub struct RawHandler {
     id: Uuid,
     sender: AsyncSender<Command>,
     receiver: AsyncReceiver<Arc<Message>>,
+    correlated: bool,
 }
 
 impl RawHandler {
@@ -167,6 +251,46 @@ impl RawHandler {
         self.wait_next().await
     }
 
+    /// Send a message and wait for the matching response whose correlation id equals
+    /// `correlation_id`, so several requests can be in flight on the same handler.
+    ///
+    /// Only available on handlers created with [`RawHandle::create_correlated`]. Matching
+    /// responses nobody waits for still go to the handler's stream. The returned future does
+    /// not borrow the handler, so it can be awaited after releasing a lock around it.
+    pub fn send_and_wait_for(
+        &self,
+        msg: Outgoing,
+        correlation_id: impl ToString,
+        timeout: Duration,
+    ) -> impl Future<Output = PocketResult<Arc<Message>>> + Send + 'static {
+        let sender = self.sender.clone();
+        let handler_id = self.id;
+        let correlated = self.correlated;
+        let correlation_id = correlation_id.to_string();
+        async move {
+            if !correlated {
+                return Err(PocketError::General(
+                    "send_and_wait_for requires a handler created with a correlation path".into(),
+                ));
+            }
+            let (tx, rx) = oneshot::channel();
+            sender
+                .send(Command::SendCorrelated {
+                    handler_id,
+                    correlation_id,
+                    msg,
+                    timeout,
+                    responder: tx,
+                })
+                .await
+                .map_err(CoreError::from)?;
+            rx.await.map_err(|_| PocketError::ModuleStopped {
+                module_name: "RawApiModule".to_string(),
+                context: "RawApiModule stopped while waiting for a correlated response".to_string(),
+            })?
+        }
+    }
+
     /// Wait for next message that matches this handler's validator
     pub async fn wait_next(&self) -> PocketResult<Arc<Message>> {
         self.receiver
@@ -202,14 +326,75 @@ pub struct RawApiModule {
     #[allow(clippy::type_complexity)]
     sinks: Arc<RwLock<HashMap<Uuid, Arc<AsyncSender<Arc<Message>>>>>>,
     keep_alive_msgs: Arc<RwLock<HashMap<Uuid, Outgoing>>>,
+    /// Correlation path of the handlers created with one.
+    correlations: HashMap<Uuid, JsonPath>,
+    /// Merges binary packets for the handlers created with `reassemble`.
+    assembler: AttachmentAssembler,
+    /// Callers of `send_and_wait_for` keyed by (handler id, correlation id).
+    waiters: PendingRequests<(Uuid, String), RawWaiter>,
+}
+
+/// A `send_and_wait_for` caller and the timeout it asked for.
+struct RawWaiter {
+    timeout: Duration,
+    responder: oneshot::Sender<PocketResult<Arc<Message>>>,
+}
+
+/// Turns a correlation value into the key callers wait on, strings are used without quotes.
+fn correlation_key(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Text the validators are checked against, binary frames are read as (lossy) UTF-8.
+fn message_content(msg: &Message) -> String {
+    match msg {
+        Message::Binary(bin) => String::from_utf8_lossy(bin.as_ref()).into_owned(),
+        Message::Text(t) => t.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn to_message(msg: Outgoing) -> Message {
+    match msg {
+        Outgoing::Text(text) => Message::text(text),
+        Outgoing::Binary(data) => Message::binary(data),
+    }
 }
 
 pub struct RawRule {
     state: Arc<State>,
+    /// Binary frames still expected by the last binary packet header.
+    attachments_left: AtomicUsize,
 }
 
 impl Rule for RawRule {
     fn call(&self, msg: &Message) -> bool {
+        // Let whole binary packets through when a handler reassembles them
+        if self.state.has_raw_reassembling() {
+            match msg {
+                Message::Text(text) => {
+                    if let Some(header) = BinaryHeader::parse(text.as_str()) {
+                        self.attachments_left
+                            .store(header.attachments, Ordering::SeqCst);
+                        return true;
+                    }
+                }
+                Message::Binary(_) => {
+                    let expected = self.attachments_left.fetch_update(
+                        Ordering::SeqCst,
+                        Ordering::SeqCst,
+                        |left| left.checked_sub(1),
+                    );
+                    if expected.is_ok() {
+                        return true;
+                    }
+                }
+                _ => {}
+            }
+        }
         // Convert to string view for validator check
         let msg_str = match msg {
             Message::Binary(bin) => String::from_utf8_lossy(bin.as_ref()).into_owned(),
@@ -231,6 +416,7 @@ impl Rule for RawRule {
 
     fn reset(&self) {
         // Do not clear validators on reconnect; handlers remain valid
+        self.attachments_left.store(0, Ordering::SeqCst);
     }
 }
 
@@ -249,6 +435,7 @@ impl ApiModule<State> for RawApiModule {
         _: AsyncSender<RunnerCommand>,
     ) -> Self {
         Self {
+            waiters: PendingRequests::new(shared_state.correlation_stats.clone()),
             state: shared_state,
             command_receiver,
             command_responder,
@@ -256,6 +443,8 @@ impl ApiModule<State> for RawApiModule {
             to_ws_sender,
             sinks: Arc::new(RwLock::new(HashMap::new())),
             keep_alive_msgs: Arc::new(RwLock::new(HashMap::new())),
+            correlations: HashMap::new(),
+            assembler: AttachmentAssembler::new(),
         }
     }
 
@@ -267,15 +456,25 @@ impl ApiModule<State> for RawApiModule {
     }
 
     async fn run(&mut self) -> binary_options_tools_core::error::CoreResult<()> {
+        let mut sweep = tokio::time::interval(SWEEP_INTERVAL);
         loop {
             select! {
+                _ = sweep.tick() => {
+                    self.sweep_waiters();
+                },
                 cmd_res = self.command_receiver.recv() => {
                     match cmd_res {
                         Ok(cmd) => {
                             match cmd {
-                                Command::Create { validator, keep_alive, command_id } => {
+                                Command::Create { validator, keep_alive, correlation, reassemble, command_id } => {
                                     let id = Uuid::new_v4();
                                     self.state.add_raw_validator(id, validator);
+                                    if reassemble {
+                                        self.state.set_raw_reassembling(id);
+                                    }
+                                    if let Some(path) = correlation {
+                                        self.correlations.insert(id, path);
+                                    }
                                     if let Some(msg) = keep_alive.clone() {
                                         self.keep_alive_msgs.write().await.insert(id, msg);
                                     }
@@ -287,6 +486,7 @@ impl ApiModule<State> for RawApiModule {
                                     let existed_state = self.state.remove_raw_validator(&id);
                                     let existed_sink = self.sinks.write().await.remove(&id).is_some();
                                     self.keep_alive_msgs.write().await.remove(&id);
+                                    self.correlations.remove(&id);
                                     let _ = self.command_responder.send(CommandResponse::Removed { command_id, id, existed: existed_state || existed_sink }).await;
                                 }
                                 Command::Send(Outgoing::Text(text)) => {
@@ -299,6 +499,18 @@ impl ApiModule<State> for RawApiModule {
                                         warn!(target: "RawApiModule", "Failed to send raw binary: {}", e);
                                     }
                                 }
+                                Command::SendCorrelated { handler_id, correlation_id, msg, timeout, responder } => {
+                                    let key = (handler_id, correlation_id);
+                                    self.waiters.insert(key.clone(), RawWaiter { timeout, responder }, timeout);
+                                    if let Err(e) = self.to_ws_sender.send(to_message(msg)).await {
+                                        warn!(target: "RawApiModule", "Failed to send correlated raw message: {}", e);
+                                        for waiter in self.waiters.take(&key) {
+                                            let _ = waiter.responder.send(Err(PocketError::General(format!(
+                                                "Failed to send raw message: {e}"
+                                            ))));
+                                        }
+                                    }
+                                }
                             }
                         }
                         Err(_) => {
@@ -310,29 +522,43 @@ impl ApiModule<State> for RawApiModule {
                 msg_res = self.message_receiver.recv() => {
                     match msg_res {
                         Ok(msg) => {
-                            // When a message arrives, route it to all matching validators
-                            let content = match msg.as_ref() {
-                                Message::Binary(bin) => String::from_utf8_lossy(bin.as_ref()).into_owned(),
-                                Message::Text(t) => t.to_string(),
-                                _ => String::new(),
+                            // Reassembling handlers see nothing until a binary packet is complete
+                            let assembled = if self.state.has_raw_reassembling() {
+                                self.assembler.push(msg.as_ref().clone()).map(Arc::new)
+                            } else {
+                                self.assembler.reset();
+                                None
                             };
-                            if content.is_empty() { continue; }
+                            // When a message arrives, route it to all matching validators
+                            let content = message_content(&msg);
+                            let assembled_content = assembled.as_deref().map(message_content);
 
                             let mut targets = Vec::new();
                             {
                                 let validators = self.state.raw_validators.read().unwrap_or_else(|e| e.into_inner());
                                 for (id, validator) in validators.iter() {
-                                    if validator.call(content.as_str()) {
-                                        targets.push(*id);
+                                    let (candidate, content) = if self.state.is_raw_reassembling(id) {
+                                        match (&assembled, &assembled_content) {
+                                            (Some(m), Some(c)) => (m, c),
+                                            _ => continue,
+                                        }
+                                    } else {
+                                        (&msg, &content)
+                                    };
+                                    if !content.is_empty() && validator.call(content.as_str()) {
+                                        targets.push((*id, candidate.clone(), content.clone()));
                                     }
                                 }
                             }
 
+                            // Responses awaited by `send_and_wait_for` skip the handler's stream.
+                            targets.retain(|(id, msg, content)| !self.deliver_correlated(*id, content, msg));
+
                             if !targets.is_empty() {
                                 let sinks = self.sinks.read().await;
-                                for id in targets {
+                                for (id, msg, _) in targets {
                                     if let Some(tx) = sinks.get(&id) {
-                                        let _ = tx.send(msg.clone()).await; // best effort
+                                        let _ = tx.send(msg).await; // best effort
                                     }
                                 }
                             }
@@ -349,7 +575,10 @@ impl ApiModule<State> for RawApiModule {
     }
 
     fn rule(state: Arc<State>) -> Box<dyn Rule + Send + Sync> {
-        Box::new(RawRule { state })
+        Box::new(RawRule {
+            state,
+            attachments_left: AtomicUsize::new(0),
+        })
     }
 
     fn callback(
@@ -398,6 +627,46 @@ impl RawApiModule {
         // but we clear sinks to drop streams.
         let mut sinks = self.sinks.write().await;
         sinks.clear();
+        for (_, waiter) in self.waiters.drain() {
+            let _ = waiter.responder.send(Err(PocketError::ModuleStopped {
+                module_name: "RawApiModule".to_string(),
+                context: "RawApiModule stopped while waiting for a correlated response".to_string(),
+            }));
+        }
+    }
+
+    /// Hands `msg` to the callers waiting for its correlation id on handler `id`,
+    /// returns `false` if nobody was waiting for it.
+    fn deliver_correlated(&mut self, id: Uuid, content: &str, msg: &Arc<Message>) -> bool {
+        let Some(key) = self
+            .correlations
+            .get(&id)
+            .and_then(|path| path.find_in(content))
+            .map(correlation_key)
+        else {
+            return false;
+        };
+        let waiters = self.waiters.take(&(id, key));
+        let delivered = !waiters.is_empty();
+        for waiter in waiters {
+            let _ = waiter.responder.send(Ok(msg.clone()));
+        }
+        delivered
+    }
+
+    /// Fails `send_and_wait_for` callers whose response did not arrive in time.
+    fn sweep_waiters(&mut self) {
+        let expired = self
+            .waiters
+            .sweep(Instant::now(), |waiter| waiter.responder.is_closed());
+        for ((handler_id, correlation_id), waiter) in expired {
+            warn!(target: "RawApiModule", "No response with id '{}' for raw handler {}", correlation_id, handler_id);
+            let _ = waiter.responder.send(Err(PocketError::Timeout {
+                task: "raw_send_and_wait_for".to_string(),
+                context: format!("Waiting for the response with id '{correlation_id}'"),
+                duration: waiter.timeout,
+            }));
+        }
     }
 }
 
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use crate::pocketoption::modules::raw::{Outgoing, RawApiModule, RawHandlerOptions};
+    use crate::pocketoption::modules::trades_tests::common::create_mock_state;
+    use crate::validator::{JsonPath, Validator};
+    use binary_options_tools_core::{
+        reimports::Message,
+        traits::{ApiModule, RunnerCommand},
+    };
+    use kanal::bounded_async;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_send_and_wait_for_routes_by_correlation_id() {
+        let (ws_tx, ws_rx) = bounded_async::<Arc<Message>>(8);
+        let (cmd_tx, cmd_rx) = bounded_async(8);
+        let (res_tx, res_rx) = bounded_async(8);
+        let (ws_sender_tx, ws_sender_rx) = bounded_async::<Message>(8);
+        let (runner_tx, _runner_rx) = bounded_async::<RunnerCommand>(1);
+
+        let mut module = RawApiModule::new(
+            create_mock_state(),
+            cmd_rx,
+            res_tx,
+            ws_rx,
+            ws_sender_tx,
+            runner_tx,
+        );
+        let task = tokio::spawn(async move { module.run().await });
+        let handle = RawApiModule::create_handle(cmd_tx, res_rx);
+
+        let handler = handle
+            .create_correlated(
+                Validator::contains("rawResponse".to_string()),
+                None,
+                JsonPath::parse("$.data.requestId").unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let first = handler.send_and_wait_for(
+            Outgoing::Text("42[\"rawRequest\",{\"requestId\":\"a\"}]".into()),
+            "a",
+            Duration::from_secs(5),
+        );
+        let second = handler.send_and_wait_for(
+            Outgoing::Text("42[\"rawRequest\",{\"requestId\":7}]".into()),
+            7,
+            Duration::from_secs(5),
+        );
+        let responses = async {
+            // Wait for both requests to go out, then answer them in reverse order.
+            for _ in 0..2 {
+                ws_sender_rx.recv().await.unwrap();
+            }
+            for response in [
+                "42[\"rawResponse\",{\"requestId\":7,\"n\":2}]",
+                "42[\"rawResponse\",{\"requestId\":\"unknown\"}]",
+                "42[\"rawResponse\",{\"requestId\":\"a\",\"n\":1}]",
+            ] {
+                ws_tx.send(Arc::new(Message::text(response))).await.unwrap();
+            }
+        };
+        let (first, second, _) = tokio::join!(first, second, responses);
+
+        assert!(first.unwrap().to_text().unwrap().contains("\"n\":1"));
+        assert!(second.unwrap().to_text().unwrap().contains("\"n\":2"));
+        // Responses nobody waits for still reach the handler's stream.
+        let unmatched = handler.wait_next().await.unwrap();
+        assert!(unmatched.to_text().unwrap().contains("unknown"));
+
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reassembling_handler_receives_single_frame() {
+        let (ws_tx, ws_rx) = bounded_async::<Arc<Message>>(8);
+        let (cmd_tx, cmd_rx) = bounded_async(8);
+        let (res_tx, res_rx) = bounded_async(8);
+        let (ws_sender_tx, _ws_sender_rx) = bounded_async::<Message>(8);
+        let (runner_tx, _runner_rx) = bounded_async::<RunnerCommand>(1);
+
+        let state = create_mock_state();
+        let mut module = RawApiModule::new(
+            state.clone(),
+            cmd_rx,
+            res_tx,
+            ws_rx,
+            ws_sender_tx,
+            runner_tx,
+        );
+        let rule = RawApiModule::rule(state);
+        let task = tokio::spawn(async move { module.run().await });
+        let handle = RawApiModule::create_handle(cmd_tx, res_rx);
+
+        let assembled = handle
+            .create_with_options(
+                Validator::starts_with(r#"42["updateStream""#.to_string()),
+                RawHandlerOptions::default().reassemble(true),
+            )
+            .await
+            .unwrap();
+        let raw = handle
+            .create(
+                Validator::starts_with(r#"451-["updateStream""#.to_string()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let frames = [
+            Message::text(r#"451-["updateStream",{"_placeholder":true,"num":0}]"#),
+            Message::binary(br#"[["EURUSD_otc",1700000000,1.1]]"#.to_vec()),
+        ];
+        for frame in frames {
+            // Frames reach the module only through its rule, like in the router.
+            assert!(rule.call(&frame));
+            ws_tx.send(Arc::new(frame)).await.unwrap();
+        }
+
+        let merged = assembled.wait_next().await.unwrap();
+        assert_eq!(
+            merged.to_text().unwrap(),
+            r#"42["updateStream",[["EURUSD_otc",1700000000,1.1]]]"#
+        );
+        let header = raw.wait_next().await.unwrap();
+        assert!(header.to_text().unwrap().starts_with("451-"));
+
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_send_and_wait_for_requires_correlation_path() {
+        let (_ws_tx, ws_rx) = bounded_async::<Arc<Message>>(1);
+        let (cmd_tx, cmd_rx) = bounded_async(1);
+        let (res_tx, res_rx) = bounded_async(1);
+        let (ws_sender_tx, _ws_sender_rx) = bounded_async::<Message>(1);
+        let (runner_tx, _runner_rx) = bounded_async::<RunnerCommand>(1);
+
+        let mut module = RawApiModule::new(
+            create_mock_state(),
+            cmd_rx,
+            res_tx,
+            ws_rx,
+            ws_sender_tx,
+            runner_tx,
+        );
+        let task = tokio::spawn(async move { module.run().await });
+        let handle = RawApiModule::create_handle(cmd_tx, res_rx);
+
+        let handler = handle
+            .create(Validator::contains("x".to_string()), None)
+            .await
+            .unwrap();
+        assert!(handler
+            .send_and_wait_for(Outgoing::Text("x".into()), "1", Duration::from_secs(1))
+            .await
+            .is_err());
+
+        task.abort();
+    }
+}
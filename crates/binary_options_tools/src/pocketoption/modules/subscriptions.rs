@@ -12,13 +12,14 @@ use futures_util::{future::join_all, stream::unfold};
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex as TokioMutex;
+use tokio::time::Instant;
 
 use tracing::warn;
 use uuid::Uuid;
@@ -26,7 +27,13 @@ use uuid::Uuid;
 use crate::pocketoption::candle::{
     compile_candles_from_ticks, BaseCandle, HistoryItem, SubscriptionType,
 };
+use crate::pocketoption::correlation::{
+    CorrelationCounters, CorrelationStats, PendingRequests, SWEEP_INTERVAL,
+};
 use crate::pocketoption::error::PocketError;
+use crate::pocketoption::quality::{
+    validate_candles, CandleValidator, DataQualityEvent, DataQualityIssue, DataSource,
+};
 use crate::pocketoption::types::{MultiPatternRule, StreamData as RawCandle, SubscriptionEvent};
 use crate::pocketoption::utils::SocketIoFrame;
 use crate::pocketoption::{
@@ -39,30 +46,53 @@ use crate::pocketoption::{
 const DEFAULT_CACHED_MAX: usize = 4;
 
 /// Internal router to distribute command responses to multiple waiters.
+///
+/// Every waiter has a deadline, a background sweeper completes the ones that got no
+/// response in time with `CommandResponse::TimedOut` so their senders do not leak.
 pub struct ResponseRouter {
-    pending: TokioMutex<HashMap<Uuid, oneshot::Sender<CommandResponse>>>,
+    pending: TokioMutex<PendingRequests<Uuid, oneshot::Sender<CommandResponse>>>,
+    stats: Arc<CorrelationStats>,
 }
 
 impl ResponseRouter {
     pub fn new(receiver: AsyncReceiver<CommandResponse>) -> Arc<Self> {
+        let stats = Arc::new(CorrelationStats::default());
         let router = Arc::new(Self {
-            pending: TokioMutex::new(HashMap::new()),
+            pending: TokioMutex::new(PendingRequests::new(stats.clone())),
+            stats,
         });
         let router_clone = router.clone();
         tokio::spawn(async move {
-            while let Ok(resp) = receiver.recv().await {
-                if let Some(id) = get_command_id(&resp) {
-                    let mut pending = router_clone.pending.lock().await;
-                    if let Some(tx) = pending.remove(&id) {
-                        if let Err(_) = tx.send(resp) {
-                            tracing::trace!(target: "ResponseRouter", "Failed to route response: receiver dropped");
+            let mut sweep = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                select! {
+                    resp = receiver.recv() => {
+                        let Ok(resp) = resp else { break };
+                        if let Some(id) = get_command_id(&resp) {
+                            let mut pending = router_clone.pending.lock().await;
+                            if let Some(tx) = pending.remove(&id).pop() {
+                                if tx.send(resp).is_err() {
+                                    tracing::trace!(target: "ResponseRouter", "Failed to route response: receiver dropped");
+                                }
+                            }
+                        }
+                    }
+                    _ = sweep.tick() => {
+                        let expired = router_clone
+                            .pending
+                            .lock()
+                            .await
+                            .sweep(Instant::now(), |tx| tx.is_closed());
+                        for (command_id, tx) in expired {
+                            warn!(target: "ResponseRouter", "No response for command {} within {:?}", command_id, SUBSCRIBE_TIMEOUT);
+                            let _ = tx.send(CommandResponse::TimedOut { command_id });
                         }
                     }
                 }
             }
             let mut pending = router_clone.pending.lock().await;
             for (id, tx) in pending.drain() {
-                if let Err(_) = tx.send(CommandResponse::Shutdown { command_id: id }) {
+                if tx.send(CommandResponse::Shutdown { command_id: id }).is_err() {
                     tracing::trace!(target: "ResponseRouter", "Failed to send shutdown notification: receiver dropped");
                 }
             }
@@ -79,10 +109,32 @@ impl ResponseRouter {
     }
 
     pub async fn register(&self, id: Uuid) -> oneshot::Receiver<CommandResponse> {
+        self.register_with_timeout(id, SUBSCRIBE_TIMEOUT).await
+    }
+
+    /// Registers a waiter for `id` that is completed with `TimedOut` after `timeout`.
+    pub async fn register_with_timeout(
+        &self,
+        id: Uuid,
+        timeout: Duration,
+    ) -> oneshot::Receiver<CommandResponse> {
         let (tx, rx) = oneshot::channel();
-        self.pending.lock().await.insert(id, tx);
+        self.pending.lock().await.insert(id, tx, timeout);
         rx
     }
+
+    /// Timeout, abandoned waiter and orphaned response counters of this router.
+    pub fn stats(&self) -> CorrelationCounters {
+        self.stats.snapshot()
+    }
+}
+
+fn request_timed_out(task: &str) -> PocketError {
+    PocketError::Timeout {
+        task: task.to_string(),
+        context: "Waiting for SubscriptionsApiModule response".to_string(),
+        duration: SUBSCRIBE_TIMEOUT,
+    }
 }
 
 fn get_command_id(resp: &CommandResponse) -> Option<Uuid> {
@@ -95,6 +147,7 @@ fn get_command_id(resp: &CommandResponse) -> Option<Uuid> {
         CommandResponse::SubscriptionCount { command_id, .. } => Some(*command_id),
         CommandResponse::HistoryFailed { command_id, .. } => Some(*command_id),
         CommandResponse::Shutdown { command_id } => Some(*command_id),
+        CommandResponse::TimedOut { command_id } => Some(*command_id),
     }
 }
 
@@ -136,6 +189,8 @@ const MAX_CHANNEL_CAPACITY: usize = 64;
 const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(2);
 const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(30);
 const DEFAULT_RECEIVE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Longest gap of a live stream filled by [`GapFill`], in periods.
+const MAX_GAP_FILL: i64 = 1440;
 
 #[derive(Debug, thiserror::Error)]
 pub enum SubscriptionError {
@@ -180,6 +235,8 @@ pub enum CommandResponse {
         command_id: Uuid,
         subscription_id: Uuid,
         stream_receiver: AsyncReceiver<SubscriptionEvent>,
+        /// Receives the issues found in the candles of the stream.
+        data_quality: tokio::sync::broadcast::Sender<DataQualityEvent>,
     },
     /// Subscription failed
     SubscriptionFailed {
@@ -208,6 +265,31 @@ pub enum CommandResponse {
     },
     /// The module has stopped and cannot fulfill the request.
     Shutdown { command_id: Uuid },
+    /// No response arrived before the request deadline.
+    TimedOut { command_id: Uuid },
+}
+
+/// A raw price update or an aggregated candle, see [`SubscriptionStream::to_update_stream`].
+#[derive(Debug, Clone)]
+pub enum StreamUpdate {
+    /// A raw price update of the asset.
+    Tick { timestamp: i64, price: Decimal },
+    /// A candle completed according to the subscription type.
+    Candle(Candle),
+}
+
+/// How a live stream fills the candles missed while disconnected, see
+/// [`SubscriptionStream::heal_gaps`]. Only time based subscriptions have gaps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GapFill {
+    /// Leave the gap, it is only reported as a [`DataQualityEvent`].
+    #[default]
+    None,
+    /// Insert flat candles at the last close, flagged as `synthetic`.
+    Flat,
+    /// Load the missing candles from the history, falling back to flat candles for
+    /// the periods the history doesn't cover.
+    Backfill,
 }
 
 /// Represents the data sent through the subscription stream.
@@ -218,6 +300,13 @@ pub struct SubscriptionStream {
     asset: String,
     sub_type: SubscriptionType,
     subscription_id: Uuid,
+    validator: CandleValidator,
+    data_quality: tokio::sync::broadcast::Sender<DataQualityEvent>,
+    gap_fill: GapFill,
+    /// Close of the last candle returned, for the flat candles.
+    last_close: Option<Decimal>,
+    /// Candles completed but not returned yet.
+    pending: VecDeque<Candle>,
 }
 
 /// Callback for when there is a disconnection
@@ -228,7 +317,11 @@ impl ReconnectCallback<State> for SubscriptionCallback {
     async fn call(&self, state: Arc<State>, ws_sender: &AsyncSender<Message>) -> CoreResult<()> {
         tokio::time::sleep(RECONNECT_INITIAL_DELAY).await;
         // Resubscribe to all active subscriptions
-        let subscriptions = state.active_subscriptions.read().await.clone();
+        let subscriptions: Vec<_> = state
+            .active_subscriptions
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
 
         // Send subscription messages concurrently (all unique types per asset)
         let mut futures = Vec::new();
@@ -267,6 +360,11 @@ pub struct SubscriptionsHandle {
 }
 
 impl SubscriptionsHandle {
+    /// Correlation counters of the subscription command router.
+    pub fn correlation_stats(&self) -> CorrelationCounters {
+        self.router.stats()
+    }
+
     /// Subscribe to an asset's real-time data stream.
     ///
     /// # Arguments
@@ -294,35 +392,34 @@ impl SubscriptionsHandle {
             .await
             .map_err(CoreError::from)?;
         // Wait for the subscription response with timeout
-        match tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver)
-            .await
-            .map_err(|_| {
-                PocketError::General(format!(
-                    "Subscription timed out after {:?} waiting for server response for asset: {}",
-                    SUBSCRIBE_TIMEOUT, asset
-                ))
-            })?
-            .map_err(|_| PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "Response router channel closed".to_string(),
-            })? {
+        match receiver.await.map_err(|_| PocketError::ModuleStopped {
+            module_name: "SubscriptionsApiModule".to_string(),
+            context: "Response router channel closed".to_string(),
+        })? {
             CommandResponse::SubscriptionSuccess {
                 command_id: _,
                 subscription_id,
                 stream_receiver,
+                data_quality,
             } => Ok(SubscriptionStream {
                 receiver: stream_receiver,
                 sender: Some(self.sender.clone()),
                 router: self.router.clone(),
                 asset,
+                validator: CandleValidator::new(sub_type.period_secs()),
                 sub_type,
                 subscription_id,
+                data_quality,
+                gap_fill: GapFill::None,
+                last_close: None,
+                pending: VecDeque::new(),
             }),
             CommandResponse::SubscriptionFailed { error, .. } => Err(*error),
             CommandResponse::Shutdown { .. } => Err(PocketError::ModuleStopped {
                 module_name: "SubscriptionsApiModule".to_string(),
                 context: "SubscriptionsApiModule stopped during request".to_string(),
             }),
+            CommandResponse::TimedOut { .. } => Err(request_timed_out("subscribe")),
             _ => Err(PocketError::General(
                 "Unexpected response to subscribe command".into(),
             )),
@@ -348,24 +445,17 @@ impl SubscriptionsHandle {
             .await
             .map_err(CoreError::from)?;
         // Wait for the unsubscription response with timeout
-        match tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver)
-            .await
-            .map_err(|_| {
-                PocketError::General(format!(
-                    "Unsubscribe timed out after {:?} waiting for server response",
-                    SUBSCRIBE_TIMEOUT
-                ))
-            })?
-            .map_err(|_| PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "Response router channel closed".to_string(),
-            })? {
+        match receiver.await.map_err(|_| PocketError::ModuleStopped {
+            module_name: "SubscriptionsApiModule".to_string(),
+            context: "Response router channel closed".to_string(),
+        })? {
             CommandResponse::UnsubscriptionSuccess { .. } => Ok(()),
             CommandResponse::UnsubscriptionFailed { error, .. } => Err(*error),
             CommandResponse::Shutdown { .. } => Err(PocketError::ModuleStopped {
                 module_name: "SubscriptionsApiModule".to_string(),
                 context: "SubscriptionsApiModule stopped during request".to_string(),
             }),
+            CommandResponse::TimedOut { .. } => Err(request_timed_out("unsubscribe")),
             _ => Err(PocketError::General(
                 "Unexpected response to unsubscribe command".into(),
             )),
@@ -384,18 +474,10 @@ impl SubscriptionsHandle {
             .await
             .map_err(CoreError::from)?;
         // Wait for the subscription count response with timeout
-        match tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver)
-            .await
-            .map_err(|_| {
-                PocketError::General(format!(
-                    "Subscription count request timed out after {:?} waiting for server response",
-                    SUBSCRIBE_TIMEOUT
-                ))
-            })?
-            .map_err(|_| PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "Response router channel closed".to_string(),
-            })? {
+        match receiver.await.map_err(|_| PocketError::ModuleStopped {
+            module_name: "SubscriptionsApiModule".to_string(),
+            context: "Response router channel closed".to_string(),
+        })? {
             CommandResponse::SubscriptionCount { count, max, .. } => {
                 self.cached_max.store(max, Ordering::Relaxed);
                 Ok(count)
@@ -404,6 +486,7 @@ impl SubscriptionsHandle {
                 module_name: "SubscriptionsApiModule".to_string(),
                 context: "SubscriptionsApiModule stopped during request".to_string(),
             }),
+            CommandResponse::TimedOut { .. } => Err(request_timed_out("subscription_count")),
             _ => Err(PocketError::General(
                 "Unexpected response to subscription count command".into(),
             )),
@@ -423,39 +506,41 @@ impl SubscriptionsHandle {
     /// # Returns
     /// * `PocketResult<Vec<Candle>>` - Vector of candles
     pub async fn history(&self, asset: String, period: u32) -> PocketResult<Vec<Candle>> {
-        let id = Uuid::new_v4();
-        let receiver = self.router.register(id).await;
-        self.sender
-            .send(Command::History {
-                asset,
-                period,
-                command_id: id,
-            })
-            .await
-            .map_err(CoreError::from)?;
-        // Wait for the history response with timeout
-        match tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver)
-            .await
-            .map_err(|_| {
-                PocketError::General(format!(
-                    "History request timed out after {:?} waiting for server response",
-                    SUBSCRIBE_TIMEOUT
-                ))
-            })?
-            .map_err(|_| PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "Response router channel closed".to_string(),
-            })? {
-            CommandResponse::History { data, .. } => Ok(data),
-            CommandResponse::HistoryFailed { error, .. } => Err(*error),
-            CommandResponse::Shutdown { .. } => Err(PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "SubscriptionsApiModule stopped during request".to_string(),
-            }),
-            _ => Err(PocketError::General(
-                "Unexpected response to history command".into(),
-            )),
-        }
+        request_history(&self.sender, &self.router, asset, period).await
+    }
+}
+
+async fn request_history(
+    sender: &AsyncSender<Command>,
+    router: &ResponseRouter,
+    asset: String,
+    period: u32,
+) -> PocketResult<Vec<Candle>> {
+    let id = Uuid::new_v4();
+    let receiver = router.register(id).await;
+    sender
+        .send(Command::History {
+            asset,
+            period,
+            command_id: id,
+        })
+        .await
+        .map_err(CoreError::from)?;
+    // Wait for the history response with timeout
+    match receiver.await.map_err(|_| PocketError::ModuleStopped {
+        module_name: "SubscriptionsApiModule".to_string(),
+        context: "Response router channel closed".to_string(),
+    })? {
+        CommandResponse::History { data, .. } => Ok(data),
+        CommandResponse::HistoryFailed { error, .. } => Err(*error),
+        CommandResponse::Shutdown { .. } => Err(PocketError::ModuleStopped {
+            module_name: "SubscriptionsApiModule".to_string(),
+            context: "SubscriptionsApiModule stopped during request".to_string(),
+        }),
+        CommandResponse::TimedOut { .. } => Err(request_timed_out("history")),
+        _ => Err(PocketError::General(
+            "Unexpected response to history command".into(),
+        )),
     }
 }
 
@@ -550,6 +635,7 @@ impl ApiModule<State> for SubscriptionsApiModule {
                                 command_id,
                                 subscription_id,
                                 stream_receiver,
+                                data_quality: self.state.data_quality.clone(),
                             }).await {
                                 warn!(target: "SubscriptionsApiModule", "Failed to send SubscriptionSuccess response: {}", e);
                             }
@@ -581,8 +667,7 @@ impl ApiModule<State> for SubscriptionsApiModule {
                             }
                         },
                         Command::SubscriptionCount { command_id } => {
-                            let subscriptions = self.state.active_subscriptions.read().await;
-                            let count = subscriptions.values().map(|v| v.len()).sum::<usize>() as u32;
+                            let count = self.state.active_subscriptions.iter().map(|v| v.len()).sum::<usize>() as u32;
                             if let Err(e) = self.command_responder.send(CommandResponse::SubscriptionCount {
                                 command_id,
                                 count,
@@ -626,7 +711,7 @@ impl ApiModule<State> for SubscriptionsApiModule {
                         Message::Binary(data) => match serde_json::from_slice::<ServerResponse>(data) {
                             Ok(res) => Some(res),
                             Err(e) => {
-                                warn!(target: "SubscriptionsApiModule", "Failed to parse binary ServerResponse: {}", e);
+                                self.state.quarantine("SubscriptionsApiModule", &msg, e);
                                 None
                             }
                         },
@@ -641,7 +726,7 @@ impl ApiModule<State> for SubscriptionsApiModule {
                                             match serde_json::from_value::<ServerResponse>(payload) {
                                                 Ok(res) => Some(res),
                                                 Err(e) => {
-                                                    warn!(target: "SubscriptionsApiModule", "Failed to parse event {} payload: {}", event, e);
+                                                    self.state.quarantine("SubscriptionsApiModule", &msg, format!("{event}: {e}"));
                                                     None
                                                 }
                                             }
@@ -690,6 +775,7 @@ impl ApiModule<State> for SubscriptionsApiModule {
 
                                     match candles_res {
                                         Ok(candles) => {
+                                            self.state.report_data_quality(validate_candles(&candles, Some(data.period), DataSource::History));
                                             if let Err(e) = self.command_responder.send(CommandResponse::History {
                                                 command_id,
                                                 data: candles
@@ -755,10 +841,18 @@ impl SubscriptionsApiModule {
             }
         }
 
-        // Active streams should also be notified
-        let mut subscriptions_lock = self.state.active_subscriptions.write().await;
-        let active = std::mem::take(&mut *subscriptions_lock);
-        for (_, subs) in active {
+        // Active streams should also be notified, removing each asset so no guard is held
+        // while sending
+        let assets: Vec<String> = self
+            .state
+            .active_subscriptions
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        for asset in assets {
+            let Some((_, subs)) = self.state.active_subscriptions.remove(&asset) else {
+                continue;
+            };
             for (sender, _, _) in subs {
                 if let Err(e) = sender
                     .send(SubscriptionEvent::Terminated {
@@ -781,10 +875,12 @@ impl SubscriptionsApiModule {
         stream_sender: AsyncSender<SubscriptionEvent>,
         subscription_id: Uuid,
     ) -> PocketResult<()> {
-
-        let mut subscriptions = self.state.active_subscriptions.write().await;
-        let entry = subscriptions.entry(asset).or_insert_with(Vec::new);
-        entry.push((stream_sender, sub_type, subscription_id));
+        self.state
+            .active_subscriptions
+            .entry(asset)
+            .or_default()
+            .push((stream_sender, sub_type, subscription_id));
+        self.state.subscriptions_updated.notify_waiters();
         Ok(())
     }
 
@@ -794,32 +890,27 @@ impl SubscriptionsApiModule {
         asset: &str,
         subscription_id: Option<Uuid>,
     ) -> CoreResult<bool> {
-        let (removed_senders, removed_at_least_one) = {
-            let mut subscriptions = self.state.active_subscriptions.write().await;
-            let mut removed_senders = Vec::new();
-            let mut removed_at_least_one = false;
-
-            if let Some(vec) = subscriptions.get_mut(asset) {
-                if let Some(sub_id) = subscription_id {
-                    if let Some(idx) = vec.iter().position(|(_, _, id)| *id == sub_id) {
-                        let (stream_sender, _, _) = vec.remove(idx);
-                        removed_senders.push(stream_sender);
-                        removed_at_least_one = true;
-                        if vec.is_empty() {
-                            subscriptions.remove(asset);
-                        }
-                    }
-                } else {
-                    removed_senders = vec
-                        .drain(..)
-                        .map(|(stream_sender, _, _)| stream_sender)
-                        .collect();
-                    removed_at_least_one = !removed_senders.is_empty();
-                    subscriptions.remove(asset);
-                }
+        let subscriptions = &self.state.active_subscriptions;
+        let removed_senders: Vec<_> = match subscription_id {
+            Some(sub_id) => {
+                // The entry guard must be released before removing the asset, on the same shard
+                let removed = subscriptions.get_mut(asset).and_then(|mut vec| {
+                    let idx = vec.iter().position(|(_, _, id)| *id == sub_id)?;
+                    Some(vec.remove(idx).0)
+                });
+                subscriptions.remove_if(asset, |_, vec| vec.is_empty());
+                removed.into_iter().collect()
             }
-            (removed_senders, removed_at_least_one)
+            None => subscriptions
+                .remove(asset)
+                .map(|(_, vec)| {
+                    vec.into_iter()
+                        .map(|(stream_sender, _, _)| stream_sender)
+                        .collect()
+                })
+                .unwrap_or_default(),
         };
+        let removed_at_least_one = !removed_senders.is_empty();
 
         for stream_sender in removed_senders {
             if let Err(e) = stream_sender
@@ -845,14 +936,12 @@ impl SubscriptionsApiModule {
         price: Decimal,
         timestamp: i64,
     ) -> CoreResult<()> {
-        let senders: Vec<AsyncSender<SubscriptionEvent>> = {
-            let subscriptions = self.state.active_subscriptions.read().await;
-            if let Some(vec) = subscriptions.get(asset) {
-                vec.iter().map(|(sender, _, _)| sender.clone()).collect()
-            } else {
-                return Ok(());
-            }
-        };
+        self.state.record_tick(asset, timestamp, price).await;
+        let senders: Vec<AsyncSender<SubscriptionEvent>> =
+            match self.state.active_subscriptions.get(asset) {
+                Some(vec) => vec.iter().map(|(sender, _, _)| sender.clone()).collect(),
+                None => return Ok(()),
+            };
 
         let update = SubscriptionEvent::Update {
             asset: asset.to_string(),
@@ -873,6 +962,12 @@ impl SubscriptionStream {
         &self.asset
     }
 
+    /// Sets how the candles missed while disconnected are filled, [`GapFill::None`] by default.
+    pub fn heal_gaps(mut self, gap_fill: GapFill) -> Self {
+        self.gap_fill = gap_fill;
+        self
+    }
+
     /// Unsubscribe from the stream
     pub async fn unsubscribe(&self) -> PocketResult<()> {
         let command_id = Uuid::new_v4();
@@ -890,24 +985,17 @@ impl SubscriptionStream {
             return Ok(());
         }
 
-        match tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver)
-            .await
-            .map_err(|_| {
-                PocketError::General(format!(
-                    "Unsubscribe timed out after {:?} waiting for server response",
-                    SUBSCRIBE_TIMEOUT
-                ))
-            })?
-            .map_err(|_| PocketError::ModuleStopped {
-                module_name: "SubscriptionsApiModule".to_string(),
-                context: "Response router channel closed".to_string(),
-            })? {
+        match receiver.await.map_err(|_| PocketError::ModuleStopped {
+            module_name: "SubscriptionsApiModule".to_string(),
+            context: "Response router channel closed".to_string(),
+        })? {
             CommandResponse::UnsubscriptionSuccess { .. } => Ok(()),
             CommandResponse::UnsubscriptionFailed { error, .. } => Err(*error),
             CommandResponse::Shutdown { .. } => Err(PocketError::ModuleStopped {
                 module_name: "SubscriptionsApiModule".to_string(),
                 context: "SubscriptionsApiModule stopped during request".to_string(),
             }),
+            CommandResponse::TimedOut { .. } => Err(request_timed_out("unsubscribe")),
             _ => Err(PocketError::General(
                 "Unexpected response to unsubscribe command".into(),
             )),
@@ -921,6 +1009,17 @@ impl SubscriptionStream {
 
     /// Receive the next candle from the stream with a custom timeout
     pub async fn receive_with_timeout(&mut self, timeout: Duration) -> PocketResult<Candle> {
+        loop {
+            if let Some(candle) = self.pending.pop_front() {
+                return Ok(candle);
+            }
+            let (timestamp, price) = self.receive_tick(timeout).await?;
+            self.process_update(timestamp, price).await?;
+        }
+    }
+
+    /// Receive the next raw price update of the asset, as `(timestamp, price)`.
+    async fn receive_tick(&mut self, timeout: Duration) -> PocketResult<(i64, Decimal)> {
         loop {
             match tokio::time::timeout(timeout, self.receiver.recv()).await {
                 Ok(Ok(crate::pocketoption::types::SubscriptionEvent::Update {
@@ -929,10 +1028,7 @@ impl SubscriptionStream {
                     timestamp,
                 })) => {
                     if asset == self.asset {
-                        let candle = self.process_update(timestamp, price)?;
-                        if let Some(candle) = candle {
-                            return Ok(candle);
-                        }
+                        return Ok((timestamp, price));
                     }
                 }
                 Ok(Ok(crate::pocketoption::types::SubscriptionEvent::Terminated { reason })) => {
@@ -951,8 +1047,9 @@ impl SubscriptionStream {
         }
     }
 
-    /// Process an incoming price update based on subscription type
-    fn process_update(&mut self, timestamp: i64, price: Decimal) -> PocketResult<Option<Candle>> {
+    /// Process an incoming price update based on subscription type, queueing the candle it
+    /// completes after the candles filling the gap before it.
+    async fn process_update(&mut self, timestamp: i64, price: Decimal) -> PocketResult<()> {
         let asset = self.asset().to_string();
         let price_f64 = price.to_f64().ok_or_else(|| {
             PocketError::General(format!(
@@ -964,11 +1061,68 @@ impl SubscriptionStream {
             .sub_type
             .update(&BaseCandle::from((timestamp, price_f64)))?
         {
-            Ok(Some(Candle::try_from((c, asset)).map_err(|e| {
-                PocketError::General(format!("Failed to convert candle: {e}"))
-            })?))
-        } else {
-            Ok(None)
+            let candle = Candle::try_from((c, asset))
+                .map_err(|e| PocketError::General(format!("Failed to convert candle: {e}")))?;
+            let mut gap = None;
+            for event in self.validator.events(&candle, DataSource::Stream) {
+                if let DataQualityIssue::Gap { previous, .. } = event.issue {
+                    gap = Some(previous);
+                }
+                // Nobody listening is fine, the candle is delivered anyway.
+                let _ = self.data_quality.send(event);
+            }
+            if let (Some(previous), Some(period)) = (gap, self.sub_type.period_secs()) {
+                self.fill_gap(previous, candle.timestamp, period).await;
+            }
+            self.last_close = Some(candle.close);
+            self.pending.push_back(candle);
+        }
+        Ok(())
+    }
+
+    /// Queues the candles of the periods between the candles starting at `previous` and `next`.
+    async fn fill_gap(&mut self, previous: i64, next: i64, period: u32) {
+        if self.gap_fill == GapFill::None {
+            return;
+        }
+        let step = i64::from(period);
+        if (next - previous) / step > MAX_GAP_FILL {
+            warn!(target: "SubscriptionsApiModule", "Not filling the gap of {}s in the {} stream, it is too long", next - previous, self.asset);
+            return;
+        }
+        let mut history = Vec::new();
+        if self.gap_fill == GapFill::Backfill {
+            if let Some(sender) = &self.sender {
+                match request_history(sender, &self.router, self.asset.clone(), period).await {
+                    Ok(candles) => history = candles,
+                    Err(e) => {
+                        warn!(target: "SubscriptionsApiModule", "Failed to backfill the {} stream, using flat candles: {}", self.asset, e)
+                    }
+                }
+            }
+        }
+        let mut timestamp = previous + step;
+        while timestamp < next {
+            if let Some(candle) = history.iter().find(|c| c.timestamp == timestamp) {
+                self.last_close = Some(candle.close);
+                self.pending.push_back(Candle {
+                    is_closed: true,
+                    ..candle.clone()
+                });
+            } else if let Some(close) = self.last_close {
+                self.pending.push_back(Candle {
+                    symbol: self.asset.clone(),
+                    timestamp,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: None,
+                    is_closed: true,
+                    synthetic: true,
+                });
+            }
+            timestamp += step;
         }
     }
 
@@ -984,6 +1138,28 @@ impl SubscriptionStream {
         }))
     }
 
+    /// Convert to a futures Stream of every raw price update along with the candles.
+    ///
+    /// Each update is yielded as a [`StreamUpdate::Tick`], followed by a
+    /// [`StreamUpdate::Candle`] when it completes a candle. Cleanup works as in [`Self::to_stream`].
+    pub fn to_update_stream(
+        self,
+    ) -> impl futures_util::Stream<Item = PocketResult<StreamUpdate>> + 'static {
+        Box::pin(unfold(self, |mut stream| async move {
+            if let Some(candle) = stream.pending.pop_front() {
+                return Some((Ok(StreamUpdate::Candle(candle)), stream));
+            }
+            let result = match stream.receive_tick(DEFAULT_RECEIVE_TIMEOUT).await {
+                Ok((timestamp, price)) => stream
+                    .process_update(timestamp, price)
+                    .await
+                    .map(|()| StreamUpdate::Tick { timestamp, price }),
+                Err(e) => Err(e),
+            };
+            Some((result, stream))
+        }))
+    }
+
     /// Check if the subscription type uses time alignment
     pub fn is_time_aligned(&self) -> bool {
         matches!(self.sub_type, SubscriptionType::TimeAligned { .. })
@@ -1004,6 +1180,11 @@ impl Clone for SubscriptionStream {
             asset: self.asset.clone(),
             sub_type: self.sub_type.clone(),
             subscription_id: self.subscription_id,
+            validator: self.validator.clone(),
+            data_quality: self.data_quality.clone(),
+            gap_fill: self.gap_fill,
+            last_close: self.last_close,
+            pending: self.pending.clone(),
         }
     }
 }
@@ -1042,3 +1223,104 @@ impl Drop for SubscriptionStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    const ASSET: &str = "EURUSD_otc";
+
+    fn stream(gap_fill: GapFill) -> (AsyncSender<SubscriptionEvent>, SubscriptionStream) {
+        let (sender, receiver) = bounded_async(16);
+        let (_, responses) = bounded_async(1);
+        let sub_type = SubscriptionType::time_aligned(Duration::from_secs(60)).unwrap();
+        let stream = SubscriptionStream {
+            receiver,
+            sender: None,
+            router: ResponseRouter::new(responses),
+            asset: ASSET.to_string(),
+            validator: CandleValidator::new(sub_type.period_secs()),
+            sub_type,
+            subscription_id: Uuid::new_v4(),
+            data_quality: tokio::sync::broadcast::channel(16).0,
+            gap_fill: GapFill::None,
+            last_close: None,
+            pending: VecDeque::new(),
+        };
+        (sender, stream.heal_gaps(gap_fill))
+    }
+
+    /// Sends a minute of prices, a disconnection of two minutes and two more prices.
+    async fn send_ticks(sender: &AsyncSender<SubscriptionEvent>) {
+        let ticks = [
+            (0, dec!(1.10)),
+            (30, dec!(1.12)),
+            (60, dec!(1.11)),
+            (90, dec!(1.13)),
+            (250, dec!(1.20)),
+            (310, dec!(1.21)),
+        ];
+        for (timestamp, price) in ticks {
+            sender
+                .send(SubscriptionEvent::Update {
+                    asset: ASSET.to_string(),
+                    price,
+                    timestamp,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn receive(stream: &mut SubscriptionStream, count: usize) -> Vec<Candle> {
+        let mut candles = Vec::new();
+        for _ in 0..count {
+            let candle = stream
+                .receive_with_timeout(Duration::from_secs(1))
+                .await
+                .unwrap();
+            candles.push(candle);
+        }
+        candles
+    }
+
+    #[tokio::test]
+    async fn test_flat_gap_fill() {
+        let (sender, mut stream) = stream(GapFill::Flat);
+        let mut events = stream.data_quality.subscribe();
+        send_ticks(&sender).await;
+
+        let candles = receive(&mut stream, 5).await;
+        let timestamps: Vec<_> = candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 60, 120, 180, 240]);
+        let synthetic: Vec<_> = candles.iter().map(|c| c.synthetic).collect();
+        assert_eq!(synthetic, vec![false, false, true, true, false]);
+        assert_eq!(candles[2].open, dec!(1.13));
+        assert_eq!(candles[3].high, candles[3].low);
+
+        let gap = std::iter::from_fn(|| events.try_recv().ok())
+            .find(|event| matches!(event.issue, DataQualityIssue::Gap { .. }))
+            .unwrap();
+        assert_eq!(gap.timestamp, 240);
+        assert_eq!(
+            gap.issue,
+            DataQualityIssue::Gap {
+                previous: 60,
+                missing: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gaps_are_kept_by_default() {
+        let (sender, mut stream) = stream(GapFill::None);
+        send_ticks(&sender).await;
+
+        let candles = receive(&mut stream, 3).await;
+        let timestamps: Vec<_> = candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 60, 240]);
+        assert!(candles.iter().all(|c| !c.synthetic));
+    }
+}
@@ -2,27 +2,35 @@ use std::{
     collections::{HashMap, VecDeque},
     fmt::Debug,
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
 use binary_options_tools_core::{
     error::{CoreError, CoreResult},
     reimports::{AsyncReceiver, AsyncSender, Message},
-    traits::{ApiModule, Rule, RunnerCommand},
+    traits::{ApiModule, Dependency, Rule, RunnerCommand},
 };
+use futures_util::future::join_all;
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use tokio::{select, sync::oneshot};
-use tracing::{info, warn};
+use tokio::{select, sync::oneshot, time::Instant};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::pocketoption::{
+    correlation::{PendingRequests, SWEEP_INTERVAL},
     error::{PocketError, PocketResult},
+    modules::{assets::AssetsModule, server_time::ServerTimeModule},
     state::State,
-    types::{Action, Deal, FailOpenOrder, MultiPatternRule, OpenOrder, RequestId},
+    stats::{OrderOutcome, TradeStages},
+    types::{Action, Deal, FailOpenOrder, MultiPatternRule, OpenOrder, RequestId, TradeRequest},
     utils::SocketIoFrame,
 };
 
+/// How long to wait for `successopenOrder` / `failopenOrder` before failing the request.
+const OPEN_ORDER_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Command enum for the `TradesApiModule`.
 #[derive(Debug)]
 pub enum Command {
@@ -33,6 +41,8 @@ pub enum Command {
         amount: Decimal,
         time: u32,
         req_id: Uuid,
+        /// Timing of the order, started when the command is enqueued.
+        stages: TradeStages,
         responder: oneshot::Sender<PocketResult<Deal>>,
     },
 }
@@ -86,6 +96,40 @@ impl TradesHandle {
         time: u32,
         req_id: Uuid,
     ) -> PocketResult<Deal> {
+        let rx = self.send_order(asset, action, amount, time, req_id).await?;
+        Self::order_result(rx).await
+    }
+
+    /// Places several trades, given with their request ID.
+    ///
+    /// Every order is queued before waiting for any confirmation, so the `openOrder` frames
+    /// are sent back-to-back. The results are in the order of `orders`.
+    pub async fn trade_batch(&self, orders: Vec<(Uuid, TradeRequest)>) -> Vec<PocketResult<Deal>> {
+        let mut pending = Vec::with_capacity(orders.len());
+        for (req_id, order) in orders {
+            pending.push(
+                self.send_order(order.asset, order.action, order.amount, order.time, req_id)
+                    .await,
+            );
+        }
+        join_all(pending.into_iter().map(|sent| async move {
+            match sent {
+                Ok(rx) => Self::order_result(rx).await,
+                Err(e) => Err(e),
+            }
+        }))
+        .await
+    }
+
+    /// Queues an order, returning the receiver of its result.
+    async fn send_order(
+        &self,
+        asset: String,
+        action: Action,
+        amount: Decimal,
+        time: u32,
+        req_id: Uuid,
+    ) -> PocketResult<oneshot::Receiver<PocketResult<Deal>>> {
         let (tx, rx) = oneshot::channel();
 
         self.sender
@@ -95,11 +139,15 @@ impl TradesHandle {
                 amount,
                 time,
                 req_id,
+                stages: TradeStages::start(),
                 responder: tx,
             })
             .await
             .map_err(CoreError::from)?;
+        Ok(rx)
+    }
 
+    async fn order_result(rx: oneshot::Receiver<PocketResult<Deal>>) -> PocketResult<Deal> {
         match rx.await {
             Ok(result) => result,
             Err(_) => Err(PocketError::General(
@@ -123,6 +171,9 @@ impl TradesHandle {
 struct PendingOrderTracker {
     asset: String,
     amount: Decimal,
+    /// Last price of the asset when the order was sent, for the slippage.
+    quote: Option<Decimal>,
+    stages: TradeStages,
     responder: oneshot::Sender<PocketResult<Deal>>,
 }
 
@@ -133,7 +184,7 @@ pub struct TradesApiModule {
     _command_responder: AsyncSender<CommandResponse>,
     message_receiver: AsyncReceiver<Arc<Message>>,
     to_ws_sender: AsyncSender<Message>,
-    pending_orders: HashMap<Uuid, PendingOrderTracker>,
+    pending_orders: PendingRequests<Uuid, PendingOrderTracker>,
     // Secondary index for matching failures (which lack UUID)
     // Map of (Asset, Amount, RequestUUID) -> Queue of UUIDs (each entry typically has 1 element)
     /// A heuristic-based mapping for correlating server-side failures to client requests.
@@ -145,18 +196,32 @@ pub struct TradesApiModule {
     /// Each request gets its own entry keyed by its UUID as a nonce, preventing
     /// race conditions when identical trades are executed simultaneously.
     failure_matching: HashMap<(String, Decimal, Uuid), VecDeque<Uuid>>,
+    /// When the last order was sent on each asset, for the trade cooldown.
+    last_orders: HashMap<String, Instant>,
 }
 
 impl TradesApiModule {
+    /// Records the timings of an order resolved with `outcome`.
+    async fn record_timings(
+        &self,
+        req_id: Uuid,
+        asset: String,
+        stages: TradeStages,
+        outcome: OrderOutcome,
+    ) {
+        let timings = stages.finish(req_id, asset, outcome, std::time::Instant::now());
+        debug!(target: "TradesApiModule", "Order {} {:?}: queued {:?}, server {:?}, confirm {:?}, total {:?}", req_id, outcome, timings.queued, timings.server, timings.confirm, timings.total);
+        self.state.trade_state.record_timings(timings).await;
+    }
+
     fn notify_waiters_module_stopped(&mut self) {
-        let pending = std::mem::take(&mut self.pending_orders);
-        if !pending.is_empty() {
+        if !self.pending_orders.is_empty() {
             tracing::info!(
                 "TradesApiModule: Notifying {} pending waiters that module has stopped",
-                pending.len()
+                self.pending_orders.len()
             );
         }
-        for (req_id, tracker) in pending {
+        for (req_id, tracker) in self.pending_orders.drain() {
             let error = PocketError::ModuleStopped {
                 module_name: "TradesApiModule".to_string(),
                 context: format!("Request ID: {}", req_id),
@@ -164,6 +229,86 @@ impl TradesApiModule {
             let _ = tracker.responder.send(Err(error));
         }
     }
+
+    /// Fails orders that got no response within `OPEN_ORDER_TIMEOUT`.
+    ///
+    /// Orders whose caller stopped waiting are kept until the server answers, since they were
+    /// already sent and the response still has to update the trade state.
+    async fn sweep_pending_orders(&mut self) {
+        let expired = self.pending_orders.sweep(Instant::now(), |_| false);
+        for (req_id, tracker) in expired {
+            warn!(target: "TradesApiModule", "No response for order {} on {} after {:?} (frame sent: {})", req_id, tracker.asset, OPEN_ORDER_TIMEOUT, tracker.stages.was_sent());
+            self.state
+                .trade_state
+                .pending_market_orders
+                .write()
+                .await
+                .remove(&req_id);
+            let _ = tracker.responder.send(Err(PocketError::Timeout {
+                task: "open_order".to_string(),
+                context: format!("Waiting for server response to order '{req_id}'"),
+                duration: OPEN_ORDER_TIMEOUT,
+            }));
+            self.record_timings(
+                req_id,
+                tracker.asset,
+                tracker.stages,
+                OrderOutcome::TimedOut,
+            )
+            .await;
+        }
+        let pending = &self.pending_orders;
+        self.failure_matching
+            .retain(|(_, _, req_id), _| pending.contains(req_id));
+        let cooldown = self.state.trade_guard.cooldown;
+        let now = Instant::now();
+        self.last_orders
+            .retain(|_, sent| now.duration_since(*sent) < cooldown);
+    }
+
+    /// Checks the kill switch and the per-asset limits of `State::trade_guard` before sending an
+    /// order on `asset`.
+    ///
+    /// Orders still waiting for the server count as open deals, so concurrent callers can't
+    /// race past the limit.
+    async fn check_trade_guard(&self, asset: &str) -> PocketResult<()> {
+        let now = self.state.get_server_datetime().await;
+        self.state.trade_state.kill_switch.check(now)?;
+        let guard = &self.state.trade_guard;
+        if let Some(sent) = self.last_orders.get(asset) {
+            let elapsed = sent.elapsed();
+            if elapsed < guard.cooldown {
+                return Err(PocketError::TradeCooldown {
+                    asset: asset.to_string(),
+                    remaining: guard.cooldown - elapsed,
+                });
+            }
+        }
+        if let Some(max) = guard.max_open_per_asset {
+            let in_flight = self
+                .pending_orders
+                .values()
+                .filter(|tracker| tracker.asset == asset)
+                .count();
+            let opened = self
+                .state
+                .trade_state
+                .get_opened_deals()
+                .await
+                .values()
+                .filter(|deal| deal.asset == asset)
+                .count();
+            let open = in_flight + opened;
+            if open >= max {
+                return Err(PocketError::TooManyOpenDeals {
+                    asset: asset.to_string(),
+                    open,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Drop for TradesApiModule {
@@ -187,13 +332,14 @@ impl ApiModule<State> for TradesApiModule {
         _: AsyncSender<RunnerCommand>,
     ) -> Self {
         Self {
+            pending_orders: PendingRequests::new(shared_state.correlation_stats.clone()),
             state: shared_state,
             command_receiver,
             _command_responder: command_responder,
             message_receiver,
             to_ws_sender,
-            pending_orders: HashMap::new(),
             failure_matching: HashMap::new(),
+            last_orders: HashMap::new(),
         }
     }
 
@@ -208,18 +354,36 @@ impl ApiModule<State> for TradesApiModule {
     }
 
     async fn run(&mut self) -> CoreResult<()> {
+        let mut sweep = tokio::time::interval(SWEEP_INTERVAL);
         loop {
             select! {
+              _ = sweep.tick() => {
+                  self.sweep_pending_orders().await;
+              },
               cmd_res = self.command_receiver.recv() => {
                   match cmd_res {
-                      Ok(Command::OpenOrder { asset, action, amount, time, req_id, responder }) => {
+                      Ok(Command::OpenOrder { asset, action, amount, time, req_id, stages, responder }) => {
+                          // The caller stopped waiting (e.g. its task was cancelled) before the order went out.
+                          if responder.is_closed() {
+                              info!(target: "TradesApiModule", "Order {} was cancelled before being sent, skipping it", req_id);
+                              continue;
+                          }
+
+                          if let Err(e) = self.check_trade_guard(&asset).await {
+                              warn!(target: "TradesApiModule", "Order {} rejected: {}", req_id, e);
+                              let _ = responder.send(Err(e));
+                              continue;
+                          }
+
                           // Register pending order
                           let tracker = PendingOrderTracker {
                               asset: asset.clone(),
                               amount,
+                              quote: self.state.last_price(&asset).await,
+                              stages,
                               responder,
                           };
-                          self.pending_orders.insert(req_id, tracker);
+                          self.pending_orders.insert(req_id, tracker, OPEN_ORDER_TIMEOUT);
 
                           // Add to failure matching queue (keyed with req_id as nonce for disambiguation)
                           let key = (asset.clone(), amount, req_id);
@@ -228,12 +392,20 @@ impl ApiModule<State> for TradesApiModule {
                           // Create OpenOrder and send to WebSocket.
                           let asset_for_error = asset.clone();
                           let order = OpenOrder::new(amount, asset, action, time, self.state.is_demo() as u32, req_id);
+                          self.state.trade_state.pending_market_orders.write().await.insert(req_id, (order.clone(), std::time::Instant::now()));
                           if let Err(e) = self.to_ws_sender.send(Message::text(order.to_string())).await {
-                              if let Some(tracker) = self.pending_orders.remove(&req_id) {
+                              self.state.trade_state.pending_market_orders.write().await.remove(&req_id);
+                              if let Some(tracker) = self.pending_orders.take(&req_id).pop() {
                                   let _ = tracker.responder.send(Err(CoreError::from(e).into()));
+                                  self.record_timings(req_id, tracker.asset, tracker.stages, OrderOutcome::Failed).await;
                               }
                               let key = (asset_for_error, amount, req_id);
                               self.failure_matching.remove(&key);
+                          } else {
+                              self.last_orders.insert(asset_for_error, Instant::now());
+                              if let Some(tracker) = self.pending_orders.get_mut(&req_id) {
+                                  tracker.stages.sent(std::time::Instant::now());
+                              }
                           }
                       }
                       Err(_) => {
@@ -243,6 +415,7 @@ impl ApiModule<State> for TradesApiModule {
                   }
               },
               msg_res = self.message_receiver.recv() => {
+                  let received = std::time::Instant::now();
                   let msg = match msg_res {
                       Ok(msg) => msg,
                       Err(_) => {
@@ -251,13 +424,7 @@ impl ApiModule<State> for TradesApiModule {
                       }
                   };
                   let response_result = match msg.as_ref() {
-                      Message::Binary(data) => match serde_json::from_slice::<ServerResponse>(data) {
-                          Ok(res) => Ok(res),
-                          Err(e) => {
-                              warn!(target: "TradesApiModule", "Failed to parse binary ServerResponse: {}", e);
-                              Err(e)
-                          }
-                      },
+                      Message::Binary(data) => serde_json::from_slice::<ServerResponse>(data),
                       Message::Text(text) => {
                           if let Ok(res) = serde_json::from_str::<ServerResponse>(text) {
                               Ok(res)
@@ -283,25 +450,39 @@ impl ApiModule<State> for TradesApiModule {
 
                   if let Ok(response) = response_result {
                       match response {
-                          ServerResponse::Success(deal) => {
-                              self.state.trade_state.add_opened_deal(*deal.clone()).await;
-                              info!(target: "TradesApiModule", "Trade opened: {}", deal.id);
-
+                          ServerResponse::Success(mut deal) => {
                               let req_id = match deal.request_id.as_ref() {
                                   Some(RequestId::Uuid(id)) => Some(*id),
                                   Some(RequestId::Number(_)) | None => None,
                               };
 
+                              let quote = req_id
+                                  .and_then(|id| self.pending_orders.get_mut(&id))
+                                  .and_then(|tracker| tracker.quote);
+                              if let Some(quote) = quote {
+                                  let slippage = deal.slippage_from(quote);
+                                  if !slippage.is_zero() {
+                                      debug!(target: "TradesApiModule", "Deal {} on {} opened at {} for a quote of {} (slippage {})", deal.id, deal.asset, deal.open_price, quote, slippage);
+                                  }
+                                  deal.slippage = Some(slippage);
+                                  self.state.trade_state.record_slippage(&deal.asset, slippage).await;
+                              }
+
+                              self.state.trade_state.add_opened_deal(*deal.clone()).await;
+                              info!(target: "TradesApiModule", "Trade opened: {}", deal.id);
+
                               // Clean up pending_market_orders in state and notify responder
                               if let Some(id) = req_id {
                                   self.state.trade_state.pending_market_orders.write().await.remove(&id);
 
-                                  if let Some(tracker) = self.pending_orders.remove(&id) {
+                                  if let Some(mut tracker) = self.pending_orders.remove(&id).pop() {
+                                      tracker.stages.acked(received);
                                       let _ = tracker.responder.send(Ok(*deal.clone()));
 
                                       // Remove the specific failure_matching entry for this request
-                                      let key = (tracker.asset, tracker.amount, id);
+                                      let key = (tracker.asset.clone(), tracker.amount, id);
                                       self.failure_matching.remove(&key);
+                                      self.record_timings(id, tracker.asset, tracker.stages, OrderOutcome::Opened).await;
                                   } else {
                                       warn!(target: "TradesApiModule", "Received success for unknown request ID: {}", id);
                                   }
@@ -313,14 +494,20 @@ impl ApiModule<State> for TradesApiModule {
                               let asset = fail.asset.clone();
                               let amount = fail.amount;
 
-                              // Find any entry in failure_matching matching this (asset, amount)
-                              // The triple key includes req_id as nonce for disambiguation
+                              // Find the entries in failure_matching matching this (asset, amount)
+                              // The triple key includes req_id as nonce for disambiguation, the
+                              // server answers identical orders in the order they were sent
                               let found_req_id = {
                                   let matching: Vec<Uuid> = self.failure_matching.keys()
                                       .filter(|(a, am, _)| a == &asset && *am == amount)
                                       .map(|(_, _, req_id)| *req_id)
                                       .collect();
-                                  matching.first().copied()
+                                  let pending = self.state.trade_state.pending_market_orders.read().await;
+                                  matching.iter()
+                                      .filter_map(|req_id| pending.get(req_id).map(|(_, sent)| (*sent, *req_id)))
+                                      .min()
+                                      .map(|(_, req_id)| req_id)
+                                      .or_else(|| matching.first().copied())
                               };
 
                               if let Some(req_id) = found_req_id {
@@ -329,21 +516,24 @@ impl ApiModule<State> for TradesApiModule {
                                   // Clean up pending_market_orders in state
                                   self.state.trade_state.pending_market_orders.write().await.remove(&req_id);
 
-                                  if let Some(tracker) = self.pending_orders.remove(&req_id) {
+                                  if let Some(mut tracker) = self.pending_orders.remove(&req_id).pop() {
+                                      tracker.stages.acked(received);
                                       let _ = tracker.responder.send(Err(PocketError::FailOpenOrder {
                                           error: fail.error.clone(),
                                           amount: fail.amount,
                                           asset: fail.asset.clone(),
                                       }));
+                                      self.record_timings(req_id, tracker.asset, tracker.stages, OrderOutcome::Failed).await;
                                   }
                               } else {
+                                   self.state.correlation_stats.record_orphan();
                                    warn!(target: "TradesApiModule", "Received failure for unknown order: {} {}", fail.asset, fail.amount);
                               }
                           }
                       }
-                  } else {
-                          warn!(target: "TradesApiModule", "Failed to parse ServerResponse from message");
-                      }
+                  } else if let Err(e) = response_result {
+                      self.state.quarantine("TradesApiModule", &msg, e);
+                  }
                   }
             }
         }
@@ -359,4 +549,12 @@ impl ApiModule<State> for TradesApiModule {
             "failopenOrder",
         ]))
     }
+
+    fn dependencies() -> Vec<Dependency> {
+        // Orders are validated against the assets and the trade guards use the server time.
+        vec![
+            Dependency::on::<AssetsModule>(),
+            Dependency::on::<ServerTimeModule>(),
+        ]
+    }
 }
@@ -22,6 +22,11 @@ use crate::pocketoption::modules::trades::{
 
 // Helper to create a minimal mock State for testing
 pub fn create_mock_state() -> Arc<State> {
+    Arc::new(mock_state_builder().build().unwrap())
+}
+
+// Builder of the mock State, for tests needing more configuration
+pub fn mock_state_builder() -> StateBuilder {
     // Construct a real SSID (non-demo) for testing
     let real_ssid = PocketSsid::Real(Real {
         session: SessionData {
@@ -40,12 +45,9 @@ pub fn create_mock_state() -> Arc<State> {
         is_optimized: None,
         extra: std::collections::HashMap::new(),
     });
-    let state = StateBuilder::default()
+    StateBuilder::default()
         .ssid(real_ssid)
         .default_symbol("EURUSD_otc".to_string())
-        .build()
-        .unwrap();
-    Arc::new(state)
 }
 
 // Helper to create test deal
@@ -79,6 +81,7 @@ pub fn create_test_deal(req_id: Uuid, asset: &str) -> Deal {
         currency: "USD".to_string(),
         amount_usd: None,
         amount_usd2: None,
+        slippage: None,
     }
 }
 
@@ -141,8 +144,10 @@ pub struct TestSetup {
 }
 
 pub async fn create_test_setup() -> TestSetup {
-    let state = create_mock_state();
+    create_test_setup_with_state(create_mock_state()).await
+}
 
+pub async fn create_test_setup_with_state(state: Arc<State>) -> TestSetup {
     let (cmd_tx, cmd_rx) = kanal::bounded_async::<Command>(100);
     let (cmd_resp_tx, cmd_resp_rx) = kanal::bounded_async::<CommandResponse>(100);
     let (msg_tx, msg_rx) = kanal::bounded_async::<Arc<Message>>(100);
@@ -1,5 +1,6 @@
 use super::common::*;
-use crate::pocketoption::types::Action;
+use crate::pocketoption::error::PocketError;
+use crate::pocketoption::types::{Action, TradeRequest};
 use binary_options_tools_core::reimports::Message;
 use rust_decimal_macros::dec;
 use std::sync::Arc;
@@ -111,3 +112,250 @@ async fn test_concurrent_identical_trades_hammer() {
         assert!(format!("{:?}", e).contains("Insufficient balance"));
     }
 }
+
+#[tokio::test]
+async fn test_failure_matches_the_oldest_identical_order() {
+    let setup = create_test_setup().await;
+    let amount = dec!(10.0);
+
+    // Sent one after the other, so the orders are pending from the oldest to the newest
+    let mut trades = Vec::new();
+    for _ in 0..5 {
+        let h = setup.handle.clone();
+        trades.push(tokio::spawn(async move {
+            h.trade_with_id(
+                "EURUSD_otc".to_string(),
+                Action::Call,
+                amount,
+                60,
+                Uuid::new_v4(),
+            )
+            .await
+        }));
+        timeout(Duration::from_secs(1), setup.ws_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let fail = format!(
+        r#"42["failopenOrder",{}]"#,
+        serde_json::to_string(&create_test_fail("EURUSD_otc", amount)).unwrap()
+    );
+    for trade in trades {
+        setup
+            .msg_tx
+            .send(Arc::new(Message::Text(fail.clone().into())))
+            .await
+            .unwrap();
+        let result = timeout(Duration::from_secs(1), trade)
+            .await
+            .expect("the failure was not matched to the oldest order")
+            .unwrap();
+        assert!(matches!(result, Err(PocketError::FailOpenOrder { .. })));
+    }
+}
+
+#[tokio::test]
+async fn test_cancelled_trade_is_not_sent() {
+    let setup = create_test_setup().await;
+    let req_id = Uuid::new_v4();
+
+    // Queue an order whose caller already gave up waiting.
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    drop(rx);
+    setup
+        .cmd_tx
+        .send(crate::pocketoption::modules::trades::Command::OpenOrder {
+            asset: "EURUSD_otc".to_string(),
+            action: Action::Call,
+            amount: dec!(10.0),
+            time: 60,
+            req_id,
+            stages: crate::pocketoption::stats::TradeStages::start(),
+            responder: tx,
+        })
+        .await
+        .unwrap();
+
+    // A live order queued afterwards is still sent.
+    let h = setup.handle.clone();
+    let live = tokio::spawn(async move {
+        h.trade("EURUSD_otc".to_string(), Action::Put, dec!(5.0), 60)
+            .await
+    });
+
+    let Message::Text(text) = timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap()
+    else {
+        panic!("expected a text frame");
+    };
+    assert!(!text.contains(&req_id.to_string()));
+    assert!(!setup
+        .state
+        .trade_state
+        .pending_market_orders
+        .read()
+        .await
+        .contains_key(&req_id));
+    assert_eq!(
+        setup
+            .state
+            .trade_state
+            .pending_market_orders
+            .read()
+            .await
+            .len(),
+        1
+    );
+    live.abort();
+}
+
+#[tokio::test]
+async fn test_trade_records_stage_timings() {
+    let setup = create_test_setup().await;
+    let req_id = Uuid::new_v4();
+    let h = setup.handle.clone();
+    let trade = tokio::spawn(async move {
+        h.trade_with_id(
+            "EURUSD_otc".to_string(),
+            Action::Call,
+            dec!(10.0),
+            60,
+            req_id,
+        )
+        .await
+    });
+
+    timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let deal = create_test_deal(req_id, "EURUSD_otc");
+    let resp = format!(
+        r#"42["successopenOrder",{}]"#,
+        serde_json::to_string(&deal).unwrap()
+    );
+    setup
+        .msg_tx
+        .send(Arc::new(Message::Text(resp.into())))
+        .await
+        .unwrap();
+    trade.await.unwrap().unwrap();
+
+    // The timings are recorded right after the caller is answered.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let latency = setup.state.trade_state.get_latency().await;
+    assert_eq!(latency.opened, 1);
+    let last = latency.last.unwrap();
+    assert_eq!(last.request_id, req_id);
+    assert_eq!(
+        last.outcome,
+        crate::pocketoption::stats::OrderOutcome::Opened
+    );
+    assert!(last.queued.is_some() && last.server.is_some() && last.confirm.is_some());
+}
+
+#[tokio::test]
+async fn test_trade_reports_slippage() {
+    let setup = create_test_setup().await;
+    setup
+        .state
+        .record_tick("EURUSD_otc", 1_700_000_000, dec!(1.2345))
+        .await;
+    let req_id = Uuid::new_v4();
+    let h = setup.handle.clone();
+    let trade = tokio::spawn(async move {
+        h.trade_with_id(
+            "EURUSD_otc".to_string(),
+            Action::Call,
+            dec!(10.0),
+            60,
+            req_id,
+        )
+        .await
+    });
+
+    timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    // The call opened higher than quoted, against the trader.
+    let mut deal = create_test_deal(req_id, "EURUSD_otc");
+    deal.open_price = dec!(1.2347);
+    let resp = format!(
+        r#"42["successopenOrder",{}]"#,
+        serde_json::to_string(&deal).unwrap()
+    );
+    setup
+        .msg_tx
+        .send(Arc::new(Message::Text(resp.into())))
+        .await
+        .unwrap();
+
+    let deal = trade.await.unwrap().unwrap();
+    assert_eq!(deal.slippage, Some(dec!(0.0002)));
+    let slippage = setup.state.trade_state.get_slippage().await;
+    assert_eq!((slippage.total.deals, slippage.total.adverse), (1, 1));
+    assert_eq!(slippage.per_asset["EURUSD_otc"].worst, dec!(0.0002));
+}
+
+#[tokio::test]
+async fn test_trade_batch_partial_failure() {
+    let setup = create_test_setup().await;
+    let ids = [Uuid::new_v4(), Uuid::new_v4()];
+    let orders = vec![
+        (
+            ids[0],
+            TradeRequest::new("EURUSD_otc", Action::Call, 60, dec!(10.0)),
+        ),
+        (
+            ids[1],
+            TradeRequest::new("GBPUSD_otc", Action::Put, 60, dec!(5.0)),
+        ),
+    ];
+    let h = setup.handle.clone();
+    let batch = tokio::spawn(async move { h.trade_batch(orders).await });
+
+    // Both orders go out before any confirmation.
+    for id in ids {
+        let Message::Text(text) = timeout(Duration::from_secs(1), setup.ws_rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+        else {
+            panic!("expected a text frame");
+        };
+        assert!(text.contains(&id.to_string()));
+    }
+
+    let deal = create_test_deal(ids[0], "EURUSD_otc");
+    let fail = create_test_fail("GBPUSD_otc", dec!(5.0));
+    for resp in [
+        format!(
+            r#"42["successopenOrder",{}]"#,
+            serde_json::to_string(&deal).unwrap()
+        ),
+        format!(
+            r#"42["failopenOrder",{}]"#,
+            serde_json::to_string(&fail).unwrap()
+        ),
+    ] {
+        setup
+            .msg_tx
+            .send(Arc::new(Message::Text(resp.into())))
+            .await
+            .unwrap();
+    }
+
+    let results = timeout(Duration::from_secs(1), batch)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().id, deal.id);
+    assert!(results[1].is_err());
+}
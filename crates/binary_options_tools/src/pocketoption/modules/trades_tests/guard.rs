@@ -0,0 +1,123 @@
+use super::common::*;
+use crate::config::{KillSwitchConfig, TradeGuardConfig};
+use crate::pocketoption::error::PocketError;
+use crate::pocketoption::types::Action;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use tokio::time::{timeout, Duration};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_max_open_per_asset_counts_in_flight_orders() {
+    let state = mock_state_builder()
+        .trade_guard(TradeGuardConfig {
+            max_open_per_asset: Some(1),
+            cooldown: Duration::ZERO,
+        })
+        .build()
+        .unwrap();
+    let setup = create_test_setup_with_state(Arc::new(state)).await;
+
+    let h = setup.handle.clone();
+    let first = tokio::spawn(async move {
+        h.trade("EURUSD_otc".to_string(), Action::Call, dec!(10.0), 60)
+            .await
+    });
+    timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The first order is still waiting for the server, it counts against the limit.
+    let err = setup
+        .handle
+        .trade("EURUSD_otc".to_string(), Action::Put, dec!(10.0), 60)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PocketError::TooManyOpenDeals {
+            open: 1,
+            max: 1,
+            ..
+        }
+    ));
+
+    // Other assets are not limited.
+    let h = setup.handle.clone();
+    let other = tokio::spawn(async move {
+        h.trade("AUDUSD_otc".to_string(), Action::Call, dec!(10.0), 60)
+            .await
+    });
+    timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    first.abort();
+    other.abort();
+}
+
+#[tokio::test]
+async fn test_trade_cooldown_per_asset() {
+    let state = mock_state_builder()
+        .trade_guard(TradeGuardConfig {
+            max_open_per_asset: None,
+            cooldown: Duration::from_secs(60),
+        })
+        .build()
+        .unwrap();
+    let setup = create_test_setup_with_state(Arc::new(state)).await;
+
+    let h = setup.handle.clone();
+    let first = tokio::spawn(async move {
+        h.trade("EURUSD_otc".to_string(), Action::Call, dec!(10.0), 60)
+            .await
+    });
+    timeout(Duration::from_secs(1), setup.ws_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let err = setup
+        .handle
+        .trade("EURUSD_otc".to_string(), Action::Call, dec!(10.0), 60)
+        .await
+        .unwrap_err();
+    match err {
+        PocketError::TradeCooldown { asset, remaining } => {
+            assert_eq!(asset, "EURUSD_otc");
+            assert!(remaining > Duration::from_secs(50));
+        }
+        other => panic!("expected a cooldown error, got {other:?}"),
+    }
+    assert!(setup.ws_rx.is_empty());
+    first.abort();
+}
+
+#[tokio::test]
+async fn test_kill_switch_rejects_orders() {
+    let state = mock_state_builder()
+        .kill_switch(KillSwitchConfig {
+            daily_profit_target: None,
+            max_drawdown: Some(dec!(10)),
+        })
+        .build()
+        .unwrap();
+    let setup = create_test_setup_with_state(Arc::new(state)).await;
+
+    let mut deal = create_test_deal(Uuid::new_v4(), "EURUSD_otc");
+    deal.profit = dec!(-10);
+    setup
+        .state
+        .trade_state
+        .update_closed_deals(vec![deal])
+        .await;
+
+    let err = setup
+        .handle
+        .trade("AUDUSD_otc".to_string(), Action::Call, dec!(10.0), 60)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PocketError::KillSwitchTripped { .. }));
+    assert!(setup.ws_rx.is_empty());
+}
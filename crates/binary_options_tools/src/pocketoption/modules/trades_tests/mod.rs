@@ -1,2 +1,3 @@
 pub mod common;
 pub mod concurrency;
+pub mod guard;
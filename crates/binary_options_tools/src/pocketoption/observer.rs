@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use binary_options_tools_core::{reconnect::ReconnectEvent, status::ConnectionStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::pocketoption::{
+    candle::{Candle, SubscriptionType},
+    collector::{CollectorConfig, CollectorJob, CollectorStore, DataCollector},
+    error::PocketResult,
+    modules::{deal_history::DealHistoryPage, subscriptions::SubscriptionStream},
+    pocket_client::PocketOption,
+    schedule::AssetSchedule,
+    types::{AssetChange, Assets, Deal},
+};
+
+/// A PocketOption client that can only read market and account data.
+///
+/// Built by [`PocketOption::observer`] without the trades, pending orders and raw modules, and
+/// exposing no method that sends an order, so a data-collection deployment cannot trade by
+/// mistake. Skipping those modules also saves their message routing.
+#[derive(Clone)]
+pub struct PocketObserver {
+    client: PocketOption,
+}
+
+impl PocketObserver {
+    pub(crate) fn new(client: PocketOption) -> Self {
+        Self { client }
+    }
+
+    /// Gets the current account balance, see [`PocketOption::balance`].
+    pub async fn balance(&self) -> Decimal {
+        self.client.balance().await
+    }
+
+    /// Gets the account currency once the server sent it.
+    pub async fn currency(&self) -> Option<String> {
+        self.client.currency().await
+    }
+
+    /// Returns `true` for a demo account.
+    pub fn is_demo(&self) -> bool {
+        self.client.is_demo()
+    }
+
+    /// Gets the current server time.
+    pub async fn server_time(&self) -> DateTime<Utc> {
+        self.client.server_time().await
+    }
+
+    /// Gets the assets, `None` until the server sent them.
+    pub async fn assets(&self) -> Option<Assets> {
+        self.client.assets().await
+    }
+
+    /// Gets the assets currently open for trading.
+    pub async fn active_assets(&self) -> Option<Assets> {
+        self.client.active_assets().await
+    }
+
+    /// Asks the server for the asset list again, see [`PocketOption::refresh_assets`].
+    pub async fn refresh_assets(&self, timeout: Duration) -> PocketResult<Assets> {
+        self.client.refresh_assets(timeout).await
+    }
+
+    /// Subscribes to asset additions, removals, openings, closings and payout changes.
+    pub fn assets_changed(&self) -> broadcast::Receiver<AssetChange> {
+        self.client.assets_changed()
+    }
+
+    /// Waits until the assets are loaded.
+    pub async fn wait_for_assets(&self, timeout: Duration) -> PocketResult<()> {
+        self.client.wait_for_assets(timeout).await
+    }
+
+    /// Gets the inferred trading hours of an asset.
+    pub async fn asset_schedule(&self, asset: impl ToString) -> PocketResult<AssetSchedule> {
+        self.client.asset_schedule(asset).await
+    }
+
+    /// Returns `true` if the asset is active and inside its trading hours.
+    pub async fn is_tradeable_now(&self, asset: impl ToString) -> PocketResult<bool> {
+        self.client.is_tradeable_now(asset).await
+    }
+
+    /// Subscribes to an asset's price stream.
+    pub async fn subscribe(
+        &self,
+        asset: impl ToString,
+        sub_type: SubscriptionType,
+    ) -> PocketResult<SubscriptionStream> {
+        self.client.subscribe(asset, sub_type).await
+    }
+
+    /// Subscribes to several assets, merging their candles into one stream of
+    /// `(asset, candle)` pairs.
+    pub async fn subscribe_many(
+        &self,
+        subscriptions: impl IntoIterator<Item = (impl ToString, SubscriptionType)>,
+    ) -> PocketResult<
+        impl futures_util::Stream<Item = (String, PocketResult<Candle>)> + Send + 'static,
+    > {
+        self.client.subscribe_many(subscriptions).await
+    }
+
+    /// Subscribes to an asset's stream, starting with its history.
+    pub async fn subscribe_with_history(
+        &self,
+        asset: impl Into<String>,
+        sub_type: SubscriptionType,
+    ) -> PocketResult<impl futures_util::Stream<Item = PocketResult<Candle>> + 'static> {
+        self.client.subscribe_with_history(asset, sub_type).await
+    }
+
+    /// Unsubscribes from an asset's price stream.
+    pub async fn unsubscribe(&self, asset: impl ToString) -> PocketResult<()> {
+        self.client.unsubscribe(asset).await
+    }
+
+    /// Gets historical candles ending at `time`.
+    pub async fn get_candles_advanced(
+        &self,
+        asset: impl ToString,
+        period: i64,
+        time: i64,
+        offset: i64,
+    ) -> PocketResult<Vec<Candle>> {
+        self.client
+            .get_candles_advanced(asset, period, time, offset)
+            .await
+    }
+
+    /// Gets the latest historical candles.
+    pub async fn get_candles(
+        &self,
+        asset: impl ToString,
+        period: i64,
+        offset: i64,
+    ) -> PocketResult<Vec<Candle>> {
+        self.client.get_candles(asset, period, offset).await
+    }
+
+    /// Gets the ticks of the last `lookback_seconds`.
+    pub async fn ticks(
+        &self,
+        asset: impl ToString,
+        lookback_seconds: u32,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        self.client.ticks(asset, lookback_seconds).await
+    }
+
+    /// Gets the ticks between `from` and `to`.
+    pub async fn ticks_range(
+        &self,
+        asset: impl ToString,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        self.client.ticks_range(asset, from, to).await
+    }
+
+    /// Gets candles compiled from the last 1000 periods of ticks, aligned to UTC boundaries.
+    pub async fn candles(&self, asset: impl ToString, period: u32) -> PocketResult<Vec<Candle>> {
+        self.client.candles(asset, period).await
+    }
+
+    /// Compiles candles of a custom period from the ticks of the lookback period.
+    pub async fn compile_candles(
+        &self,
+        asset: impl ToString,
+        custom_period: u32,
+        lookback_period: u32,
+    ) -> PocketResult<Vec<Candle>> {
+        self.client
+            .compile_candles(asset, custom_period, lookback_period)
+            .await
+    }
+
+    /// Downloads the history of `jobs` into `store` in the background, see
+    /// [`PocketOption::collect_data`].
+    pub async fn collect_data(
+        &self,
+        jobs: impl IntoIterator<Item = CollectorJob>,
+        store: Arc<dyn CollectorStore>,
+        config: CollectorConfig,
+    ) -> PocketResult<DataCollector> {
+        self.client.collect_data(jobs, store, config).await
+    }
+
+    /// Gets the deals currently opened on the account, e.g. from the web terminal.
+    pub async fn get_opened_deals(&self) -> HashMap<Uuid, Deal> {
+        self.client.get_opened_deals().await
+    }
+
+    /// Gets the deals closed since the client started.
+    pub async fn get_closed_deals(&self) -> HashMap<Uuid, Deal> {
+        self.client.get_closed_deals().await
+    }
+
+    /// Subscribes to deals as they open on the account.
+    pub fn opened_deal_events(&self) -> broadcast::Receiver<Deal> {
+        self.client.opened_deal_events()
+    }
+
+    /// Subscribes to deals as they close on the account.
+    pub fn closed_deal_events(&self) -> broadcast::Receiver<Deal> {
+        self.client.closed_deal_events()
+    }
+
+    /// Gets the deals closed between `from` and `to` from the server history.
+    pub async fn closed_deals_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> PocketResult<Vec<Deal>> {
+        self.client.closed_deals_history(from, to).await
+    }
+
+    /// Gets one page of the server's closed deals history.
+    pub async fn closed_deals_history_page(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        offset: u32,
+        limit: u32,
+    ) -> PocketResult<DealHistoryPage> {
+        self.client
+            .closed_deals_history_page(from, to, offset, limit)
+            .await
+    }
+
+    /// Subscribes to reconnection events.
+    pub fn reconnect_events(&self) -> broadcast::Receiver<ReconnectEvent> {
+        self.client.reconnect_events()
+    }
+
+    /// Returns `true` while the websocket is connected.
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+
+    /// Gets the connection status.
+    pub fn status(&self) -> ConnectionStatus {
+        self.client.status()
+    }
+
+    /// Streams the connection status changes.
+    pub fn status_stream(
+        &self,
+    ) -> impl futures_util::Stream<Item = ConnectionStatus> + Send + 'static {
+        self.client.status_stream()
+    }
+
+    /// Waits until the connection reaches `status`.
+    pub async fn wait_for_status(
+        &self,
+        status: ConnectionStatus,
+        timeout: Duration,
+    ) -> PocketResult<()> {
+        self.client.wait_for_status(status, timeout).await
+    }
+
+    /// Disconnects, the client stays usable with [`PocketObserver::connect`].
+    pub async fn disconnect(&self) -> PocketResult<()> {
+        self.client.disconnect().await
+    }
+
+    /// Connects again after [`PocketObserver::disconnect`].
+    pub async fn connect(&self) -> PocketResult<()> {
+        self.client.connect().await
+    }
+
+    /// Drops the connection and connects again.
+    pub async fn reconnect(&self) -> PocketResult<()> {
+        self.client.reconnect().await
+    }
+
+    /// Shuts down the client and stops the runner.
+    pub async fn shutdown(&self) -> PocketResult<()> {
+        self.client.shutdown().await
+    }
+}
@@ -1,51 +1,108 @@
 #![allow(deprecated)]
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use binary_options_tools_core::{
+    audit::{AuditLog, AuditRecord},
     builder::ClientBuilder,
-    client::Client,
-    error::CoreResult,
+    client::{Client, ClientRunner},
+    connector::Connector,
+    error::{CoreError, CoreResult},
+    frame_dump::{FrameDumpFlusher, FrameDumpMiddleware},
+    middleware::WebSocketMiddleware,
+    reconnect::ReconnectEvent,
     reimports::AsyncSender,
+    statistics::ClientStats,
+    status::ConnectionStatus,
     testing::TestingWrapper,
     testing::TestingWrapperBuilder,
+    testing::{DisconnectInjection, StressScenario, Workload},
     traits::{ApiModule, ReconnectCallback},
+    utils::tracing::log_redaction,
 };
 use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use tokio::sync::{broadcast, broadcast::error::TryRecvError, watch};
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
-use crate::config::Config;
+use crate::config::{Config, InitProfile, ModuleFlags};
 use crate::pocketoption::types::Outgoing;
 use crate::{
     error::BinaryOptionsError,
     pocketoption::{
+        alerts::{Alert, AlertEvent, AlertMode, Condition as AlertCondition, PriceAlerts},
+        blacklist::BlacklistedUrl,
         candle::{compile_candles_from_tuples, Candle, SubscriptionType},
+        collector::{CollectorConfig, CollectorJob, CollectorStore, DataCollector, Series},
         connect::PocketConnect,
-        error::{PocketError, PocketResult},
+        correlation::CorrelationCounters,
+        debug::DebugSnapshot,
+        error::{FatalError, PocketError, PocketResult},
+        kill_switch::{KillSwitchEvent, KillSwitchStatus},
         modules::{
             assets::AssetsModule,
             balance::BalanceModule,
-            deals::DealsApiModule,
+            deal_history::{DealHistoryApiModule, DealHistoryPage, DEFAULT_PAGE_SIZE},
+            deals::{DealsApiModule, ResultOptions},
             get_candles::GetCandlesApiModule,
             historical_data::HistoricalDataApiModule,
             keep_alive::{InitModule, KeepAliveModule},
             pending_trades::PendingTradesApiModule,
-            raw::{RawApiModule, RawHandle as InnerRawHandle, RawHandler as InnerRawHandler},
+            raw::{
+                RawApiModule, RawHandle as InnerRawHandle, RawHandler as InnerRawHandler,
+                RawHandlerOptions,
+            },
             server_time::ServerTimeModule,
             subscriptions::{SubscriptionStream, SubscriptionsApiModule},
             trades::TradesApiModule,
         },
+        observer::PocketObserver,
+        quality::DataQualityEvent,
+        scanner::{ScannerConfig, ScannerSubscription},
+        schedule::AssetSchedule,
+        session::SessionSnapshot,
         ssid::Ssid,
         state::{State, StateBuilder},
-        types::{Action, Assets, Deal, OpenPendingOrder, PendingOrder},
+        stats::{Heartbeat, PingStats, SlippageStats, TradeLatency, TradeStats, TradeTimings},
+        trailing::{TrailingConfig, TrailingOrder},
+        types::{
+            AccountBalance, AccountType, Action, AmountLimits, AssetChange, Assets, Condition,
+            Deal, DealStatus, EarlyClose, OpenPendingOrder, ParseFailure, PendingOrder,
+            ServerTimeQuality, TradeOptions, TradeRequest,
+        },
+        warmup::HistorySplice,
     },
-    utils::print_handler,
+    utils::{f64_to_decimal, print_handler},
 };
 
-const MINIMUM_TRADE_AMOUNT: Decimal = dec!(1.0);
-const MAXIMUM_TRADE_AMOUNT: Decimal = dec!(20000.0);
+/// Converts an `f64` amount into a `Decimal`, rejecting NaN and infinite values.
+fn amount_from_f64(amount: f64) -> PocketResult<Decimal> {
+    f64_to_decimal(amount).ok_or_else(|| PocketError::General(format!("Invalid amount: {amount}")))
+}
+
+/// What `PocketOption::new` waits for after authenticating with `profile`.
+fn init_conditions(profile: InitProfile) -> Vec<Condition> {
+    match profile {
+        InitProfile::Minimal => vec![Condition::BalanceKnown],
+        InitProfile::Standard => Vec::new(),
+        InitProfile::Full => vec![
+            Condition::BalanceKnown,
+            Condition::AssetsLoaded,
+            Condition::ServerTimeSynced,
+        ],
+    }
+}
 
 /// Reconnection callback to verify potential lost trades
 struct TradeReconciliationCallback;
@@ -97,33 +154,159 @@ impl Market for PocketOption {
     }
 }
 
+/// The runner of a client, spawned on construction or by `PocketOption::connect` for clients
+/// built offline.
+///
+/// A watcher task reports the runner stopping without a shutdown on `fatal`.
+struct RunnerTask {
+    runner: std::sync::Mutex<Option<ClientRunner<State>>>,
+    handle: std::sync::Mutex<Option<AbortHandle>>,
+    stopping: Arc<AtomicBool>,
+    fatal: Arc<watch::Sender<Option<FatalError>>>,
+}
+
+impl RunnerTask {
+    fn new(runner: ClientRunner<State>) -> Self {
+        Self {
+            runner: std::sync::Mutex::new(Some(runner)),
+            handle: std::sync::Mutex::new(None),
+            stopping: Arc::new(AtomicBool::new(false)),
+            fatal: Arc::new(watch::channel(None).0),
+        }
+    }
+
+    /// Spawns the runner of `client`, `false` if it was already started.
+    fn start(&self, client: &Client<State>) -> bool {
+        let Some(mut runner) = self.runner.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+            return false;
+        };
+        // Subscribed before the runner starts so its last events are not missed.
+        let mut events = client.signal.subscribe_reconnect_events();
+        let handle = tokio::spawn(async move { runner.run().await });
+        *self.handle.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle.abort_handle());
+
+        let state = client.state.clone();
+        let stopping = self.stopping.clone();
+        let fatal = self.fatal.clone();
+        tokio::spawn(async move {
+            let error = match handle.await {
+                Err(e) if e.is_cancelled() => return,
+                Err(e) => FatalError::Panicked {
+                    message: panic_message(e.into_panic()),
+                },
+                Ok(()) if stopping.load(Ordering::SeqCst) => return,
+                Ok(()) if state.is_auth_rejected() => FatalError::NotAuthorized,
+                Ok(()) => {
+                    let mut error = FatalError::Stopped;
+                    let mut last_error = None;
+                    loop {
+                        match events.try_recv() {
+                            Ok(ReconnectEvent::LimitReached(diagnostics)) => {
+                                last_error = diagnostics.last_error;
+                            }
+                            Ok(ReconnectEvent::Exhausted { attempts }) => {
+                                error = FatalError::ReconnectExhausted {
+                                    attempts,
+                                    last_error: last_error.take(),
+                                };
+                            }
+                            Ok(_) | Err(TryRecvError::Lagged(_)) => {}
+                            Err(_) => break,
+                        }
+                    }
+                    error
+                }
+            };
+            tracing::error!(target: "PocketOption", "Runner stopped unexpectedly: {error}");
+            fatal.send_replace(Some(error));
+        });
+        true
+    }
+
+    /// Marks the coming stop of the runner as requested, so it is not reported as fatal.
+    fn stopping(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+    }
+
+    fn abort(&self) {
+        self.stopping();
+        let handle = self.handle.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(handle) = handle.as_ref() {
+            handle.abort();
+        }
+    }
+}
+
+/// Message of a panic payload, for [`FatalError::Panicked`].
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Waits for the first fatal error on `fatal`, `None` once the client is dropped.
+async fn next_fatal(mut fatal: watch::Receiver<Option<FatalError>>) -> Option<FatalError> {
+    let error = fatal.wait_for(Option::is_some).await.ok()?;
+    error.clone()
+}
+
 /// A high-level client for interacting with PocketOption.
 /// It provides methods for executing trades, retrieving balance, subscribing to
 /// asset updates, and managing the connection to the PocketOption platform.
 #[derive(Clone)]
 pub struct PocketOption {
     client: Client<State>,
-    _runner: Arc<tokio::task::JoinHandle<()>>,
+    runner: Arc<RunnerTask>,
     pub config: Config,
     pending_trades_lock: Arc<tokio::sync::Mutex<()>>,
+    alerts: PriceAlerts,
+    /// Flushes the dump of `Config::frame_dump` before `shutdown_graceful` disconnects.
+    frame_dump: Option<FrameDumpFlusher>,
 }
 
 impl PocketOption {
-    fn configure_common_modules(builder: ClientBuilder<State>) -> ClientBuilder<State> {
+    fn configure_common_modules(
+        builder: ClientBuilder<State>,
+        modules: ModuleFlags,
+    ) -> ClientBuilder<State> {
+        let mut builder =
+            Self::configure_data_modules(builder, modules).with_module::<TradesApiModule>();
+        if modules.pending_trades {
+            builder = builder.with_module::<PendingTradesApiModule>();
+        }
+        if modules.raw {
+            builder = builder.with_module::<RawApiModule>();
+        }
+        builder
+    }
+
+    /// Registers the modules that never send orders, the only ones of an observer client.
+    fn configure_data_modules(
+        builder: ClientBuilder<State>,
+        modules: ModuleFlags,
+    ) -> ClientBuilder<State> {
+        let builder = if modules.historical_data {
+            builder.with_module::<HistoricalDataApiModule>()
+        } else {
+            builder
+        };
         builder
             .with_lightweight_module::<KeepAliveModule>()
             .with_lightweight_module::<InitModule>()
             .with_lightweight_module::<BalanceModule>()
             .with_lightweight_module::<ServerTimeModule>()
             .with_lightweight_module::<AssetsModule>()
-            .with_module::<TradesApiModule>()
             .with_module::<DealsApiModule>()
+            .with_module::<DealHistoryApiModule>()
             .with_module::<SubscriptionsApiModule>()
             .with_module::<GetCandlesApiModule>()
-            .with_module::<PendingTradesApiModule>()
-            .with_module::<HistoricalDataApiModule>()
-            .with_module::<RawApiModule>()
             .with_lightweight_handler(|msg, _, _| Box::pin(print_handler(msg)))
+            .with_lightweight_handler(|msg, state, _| {
+                state.record_pong(&msg);
+                Box::pin(async { Ok(()) })
+            })
             .with_lightweight_handler(|msg, state, _| Box::pin(async move {
                 let subs = state.raw_subscribers.read().await;
                 for sub in subs.iter() {
@@ -143,12 +326,22 @@ impl PocketOption {
             .ok_or_else(|| PocketError::ModuleNotFound(module_name.to_string()))
     }
 
-    fn builder(ssid: impl ToString) -> PocketResult<ClientBuilder<State>> {
+    fn testing_builder(ssid: impl ToString) -> PocketResult<ClientBuilder<State>> {
         let state = StateBuilder::default().ssid(Ssid::parse(ssid)?).build()?;
-        Ok(Self::configure_common_modules(ClientBuilder::new(
-            PocketConnect,
-            state,
-        )))
+        Ok(Self::configure_common_modules(
+            ClientBuilder::new(PocketConnect, state),
+            ModuleFlags::default(),
+        ))
+    }
+
+    /// Returns a builder for a client, e.g. to create it without connecting with
+    /// [`PocketOptionBuilder::build_offline`].
+    pub fn builder() -> PocketOptionBuilder {
+        PocketOptionBuilder {
+            ssid: None,
+            config: Config::default(),
+            connector: PocketConnect,
+        }
     }
 
     /// Creates a new PocketOption client with the provided session ID.
@@ -189,10 +382,14 @@ impl PocketOption {
             .ssid(parsed_ssid)
             .default_connection_url(url)
             .build()?;
-        let builder = Self::configure_common_modules(ClientBuilder::new(PocketConnect, state));
-        let (client, mut runner) = builder.build().await?;
+        let builder = Self::configure_common_modules(
+            ClientBuilder::new(PocketConnect, state),
+            ModuleFlags::default(),
+        );
+        let (client, runner) = builder.build().await?;
 
-        let _runner = tokio::spawn(async move { runner.run().await });
+        let runner = RunnerTask::new(runner);
+        runner.start(&client);
 
         match tokio::time::timeout(Duration::from_secs(30), client.wait_connected()).await {
             Ok(_) => {}
@@ -205,16 +402,169 @@ impl PocketOption {
 
         Ok(Self {
             client,
-            _runner: Arc::new(_runner),
+            runner: Arc::new(runner),
             config: Config::default(),
             pending_trades_lock: Arc::new(tokio::sync::Mutex::new(())),
+            alerts: PriceAlerts::default(),
+            frame_dump: None,
         })
     }
 
     /// Creates a new PocketOption client with the provided configuration.
     pub async fn new_with_config(ssid: impl ToString, config: Config) -> PocketResult<Self> {
+        Self::connect_with_modules(
+            ssid,
+            config,
+            PocketConnect,
+            Self::configure_common_modules,
+            None,
+        )
+        .await
+    }
+
+    /// Creates a client that connects through `connector` instead of the PocketOption
+    /// servers, e.g. a `MockConnector` scripted with [`fixtures`](crate::pocketoption::fixtures)
+    /// to test against canned server frames.
+    pub async fn new_with_connector(
+        ssid: impl ToString,
+        connector: impl Connector<State> + 'static,
+        config: Config,
+    ) -> PocketResult<Self> {
+        Self::connect_with_modules(
+            ssid,
+            config,
+            connector,
+            Self::configure_common_modules,
+            None,
+        )
+        .await
+    }
+
+    /// Creates a client seeded with the session saved at `path`, see [`PocketOption::resume_with_config`].
+    pub async fn resume(path: impl AsRef<Path>, ssid: impl ToString) -> PocketResult<Self> {
+        Self::resume_with_config(path, ssid, Config::default()).await
+    }
+
+    /// Creates a client seeded with the session saved at `path` by a previous shutdown.
+    ///
+    /// The assets, server time offset and still-open deals of the snapshot are available right
+    /// away, so results of deals opened before the restart can be awaited without waiting for
+    /// the server to push them again. The server's data replaces them once it arrives. A
+    /// missing, unreadable or other-account snapshot is ignored and the client starts fresh.
+    ///
+    /// The session is saved back to `path` on shutdown.
+    pub async fn resume_with_config(
+        path: impl AsRef<Path>,
+        ssid: impl ToString,
+        mut config: Config,
+    ) -> PocketResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let ssid = ssid.to_string();
+        let parsed_ssid = Ssid::parse(&ssid)?;
+        let session = match SessionSnapshot::load(&path).await {
+            Ok(session) if session.matches(&parsed_ssid) => Some(session),
+            Ok(_) => {
+                tracing::warn!(target: "PocketOption", "Ignoring session {}, it belongs to another account", path.display());
+                None
+            }
+            Err(e) if path.exists() => {
+                tracing::warn!(target: "PocketOption", "Ignoring session: {e}");
+                None
+            }
+            Err(_) => None,
+        };
+        config.session_path = Some(path);
+        Self::connect_with_modules(
+            ssid,
+            config,
+            PocketConnect,
+            Self::configure_common_modules,
+            session,
+        )
+        .await
+    }
+
+    /// Saves the assets, server time offset, subscribed assets and opened deals to `path`,
+    /// for [`PocketOption::resume`].
+    pub async fn save_session(&self, path: impl AsRef<Path>) -> PocketResult<()> {
+        SessionSnapshot::capture(&self.client.state)
+            .await
+            .save(path)
+            .await
+    }
+
+    /// Saves the session to `Config::session_path`, if set.
+    async fn save_configured_session(&self) {
+        if let Some(path) = &self.config.session_path {
+            if let Err(e) = self.save_session(path).await {
+                tracing::warn!(target: "PocketOption", "Failed to save the session: {e}");
+            }
+        }
+    }
+
+    /// Creates a client that can only read market and account data, see [`PocketObserver`].
+    pub async fn observer(ssid: impl ToString) -> PocketResult<PocketObserver> {
+        Self::observer_with_config(ssid, Config::default()).await
+    }
+
+    /// Creates an observer client with the provided configuration, see [`PocketObserver`].
+    pub async fn observer_with_config(
+        ssid: impl ToString,
+        config: Config,
+    ) -> PocketResult<PocketObserver> {
+        Self::connect_with_modules(
+            ssid,
+            config,
+            PocketConnect,
+            Self::configure_data_modules,
+            None,
+        )
+        .await
+        .map(PocketObserver::new)
+    }
+
+    async fn connect_with_modules(
+        ssid: impl ToString,
+        config: Config,
+        connector: impl Connector<State> + 'static,
+        configure: fn(ClientBuilder<State>, ModuleFlags) -> ClientBuilder<State>,
+        session: Option<SessionSnapshot>,
+    ) -> PocketResult<Self> {
+        let client = Self::build_with_modules(ssid, config, connector, configure, session).await?;
+        client.runner.start(&client.client);
+        client.wait_ready().await?;
+        Ok(client)
+    }
+
+    /// Builds the client without starting its runner, see [`RunnerTask::start`].
+    async fn build_with_modules(
+        ssid: impl ToString,
+        config: Config,
+        connector: impl Connector<State> + 'static,
+        configure: fn(ClientBuilder<State>, ModuleFlags) -> ClientBuilder<State>,
+        session: Option<SessionSnapshot>,
+    ) -> PocketResult<Self> {
         let parsed_ssid = Ssid::parse(ssid)?;
-        let mut builder = StateBuilder::default().ssid(parsed_ssid.clone());
+        let mut builder = StateBuilder::default()
+            .ssid(parsed_ssid.clone())
+            .clock(config.clock.clone())
+            .keep_alive(
+                config.keep_alive.clone(),
+                config.keep_alive_overrides.clone(),
+            )
+            .server_time_drift_threshold(config.server_time_drift_threshold)
+            .init_profile(config.init_profile)
+            .trade_guard(config.trade_guard.clone())
+            .kill_switch(config.kill_switch.clone())
+            .proxy(config.proxy.clone())
+            .system_proxy(config.system_proxy)
+            .dns(config.dns.clone())
+            .url_blacklist(config.url_blacklist.clone())
+            .user_agent(config.user_agent.clone())
+            .origin(config.origin.clone())
+            .sec_websocket_extensions(config.sec_websocket_extensions.clone())
+            .tls_cipher_suites(config.tls_cipher_suites.clone())
+            .tls_alpn(config.tls_alpn.clone());
 
         // Priority 1: Use SSID's current_url if available (the server the session is tied to)
         if let Some(url) = parsed_ssid.current_url() {
@@ -227,27 +577,48 @@ impl PocketOption {
 
         // Pass all URLs as fallbacks
         builder = builder
-            .urls(config.urls.iter().map(|u| u.to_string()).collect());
+            .urls(config.urls.iter().map(|u| u.to_string()).collect())
+            .amount_limits(config.amount_limits.clone());
 
         let state = builder.build()?;
-        let client_builder =
-            Self::configure_common_modules(ClientBuilder::new(PocketConnect, state))
-                .with_max_allowed_loops(config.max_allowed_loops)
-                .with_reconnect_delay(config.reconnect_time);
+        if let Some(session) = &session {
+            session.restore(&state).await;
+        }
+        let mut client_builder = configure(ClientBuilder::new(connector, state), config.modules)
+            .with_reconnect_policy(config.reconnect_policy())
+            .with_restart_policy(config.module_restart_policy.clone());
+        let mut frame_dump = None;
+        if let Some(path) = &config.frame_dump {
+            let dump = FrameDumpMiddleware::create(path).map_err(|e| {
+                PocketError::General(format!(
+                    "Failed to create the frame dump {}: {e}",
+                    path.display()
+                ))
+            })?;
+            frame_dump = Some(dump.flusher());
+            client_builder = client_builder.with_middleware(Box::new(dump));
+        }
+        if config.audit_log > 0 {
+            client_builder = client_builder.with_audit_log(AuditLog::new(config.audit_log));
+        }
 
-        let (client, mut runner): (
-            Client<State>,
-            binary_options_tools_core::client::ClientRunner<State>,
-        ) = client_builder.build().await?;
+        let (client, runner) = client_builder.build().await?;
 
-        let _runner = tokio::spawn(async move { runner.run().await });
+        Ok(Self {
+            client,
+            runner: Arc::new(RunnerTask::new(runner)),
+            config,
+            pending_trades_lock: Arc::new(tokio::sync::Mutex::new(())),
+            alerts: PriceAlerts::default(),
+            frame_dump,
+        })
+    }
 
-        match tokio::time::timeout(
-            config.connection_initialization_timeout,
-            client.wait_connected(),
-        )
-        .await
-        {
+    /// Waits until the client is connected and the conditions of `Config::init_profile` hold,
+    /// for at most `Config::connection_initialization_timeout`.
+    async fn wait_ready(&self) -> PocketResult<()> {
+        let deadline = tokio::time::Instant::now() + self.config.connection_initialization_timeout;
+        match tokio::time::timeout_at(deadline, self.client.wait_connected()).await {
             Ok(_) => {}
             Err(_) => {
                 return Err(PocketError::General(
@@ -255,13 +626,22 @@ impl PocketOption {
                 ));
             }
         }
-
-        Ok(Self {
-            client,
-            _runner: Arc::new(_runner),
-            config,
-            pending_trades_lock: Arc::new(tokio::sync::Mutex::new(())),
-        })
+        let state = &self.client.state;
+        let conditions = init_conditions(self.config.init_profile);
+        let ready = join_all(conditions.iter().map(|c| state.wait_for(c)));
+        if tokio::time::timeout_at(deadline, ready).await.is_err() {
+            let mut pending = Vec::new();
+            for condition in &conditions {
+                if !state.is_satisfied(condition).await {
+                    pending.push(condition.to_string());
+                }
+            }
+            return Err(PocketError::General(format!(
+                "Connection initialization timed out waiting for {}",
+                pending.join(", ")
+            )));
+        }
+        Ok(())
     }
 
     /// Get a handle to the Raw module for ad-hoc validators and custom message processing.
@@ -279,6 +659,31 @@ impl PocketOption {
         handle.create(validator, keep_alive).await
     }
 
+    /// Convenience: create a RawHandler whose responses are routed to `send_and_wait_for`
+    /// callers by the id found at `correlation` (e.g. `$.data.requestId`).
+    pub async fn create_correlated_raw_handler(
+        &self,
+        validator: crate::validator::Validator,
+        keep_alive: Option<Outgoing>,
+        correlation: crate::validator::JsonPath,
+    ) -> PocketResult<InnerRawHandler> {
+        let handle = self.require_handle::<RawApiModule>("RawApiModule").await?;
+        handle
+            .create_correlated(validator, keep_alive, correlation)
+            .await
+    }
+
+    /// Convenience: create a RawHandler with every option (keep-alive, correlation path,
+    /// binary packet reassembly), see [`RawHandlerOptions`].
+    pub async fn create_raw_handler_with_options(
+        &self,
+        validator: crate::validator::Validator,
+        options: RawHandlerOptions,
+    ) -> PocketResult<InnerRawHandler> {
+        let handle = self.require_handle::<RawApiModule>("RawApiModule").await?;
+        handle.create_with_options(validator, options).await
+    }
+
     /// Gets the current account balance.
     ///
     /// This method waits up to 10 seconds for the balance to be populated from the server.
@@ -307,6 +712,31 @@ impl PocketOption {
         dec!(-1.0)
     }
 
+    /// Notified every time the server reports the balance.
+    pub(crate) fn balance_updated(&self) -> Arc<tokio::sync::Notify> {
+        self.client.state.balance_updated.clone()
+    }
+
+    /// Last balance reported for each account type (demo, real, tournament).
+    ///
+    /// Unlike [`balance`](Self::balance) this does not wait, account types the server has not
+    /// reported a balance for yet are missing.
+    pub async fn balances(&self) -> HashMap<AccountType, AccountBalance> {
+        self.client.state.balances().await
+    }
+
+    /// Gets the account currency code (e.g. `"USD"`), if the server has reported it.
+    pub async fn currency(&self) -> Option<String> {
+        self.client.state.get_currency().await
+    }
+
+    /// Gets the trade amount limits for the account currency.
+    ///
+    /// Until the currency is known the USD limits are returned.
+    pub async fn amount_limits(&self) -> AmountLimits {
+        self.client.state.amount_limits().await
+    }
+
     /// Checks if the account is a demo account.
     ///
     /// # Returns
@@ -316,6 +746,30 @@ impl PocketOption {
         state.ssid.demo()
     }
 
+    /// Subscribes to reconnection events emitted by the connection runner.
+    ///
+    /// Useful to log or alert on scheduled reconnect attempts and on the runner giving up.
+    pub fn reconnect_events(&self) -> broadcast::Receiver<ReconnectEvent> {
+        self.client.reconnect_events()
+    }
+
+    /// Subscribes to deals as they open, whether placed by this client or elsewhere on the
+    /// account (e.g. the web terminal).
+    ///
+    /// Slow receivers lose the oldest deals and get `RecvError::Lagged`,
+    /// [`PocketOption::get_opened_deals`] still holds them.
+    pub fn opened_deal_events(&self) -> broadcast::Receiver<Deal> {
+        self.client.state.trade_state.opened_deal_events()
+    }
+
+    /// Subscribes to deals as they close, each closed deal is sent once.
+    ///
+    /// Slow receivers lose the oldest deals and get `RecvError::Lagged`,
+    /// [`PocketOption::get_closed_deals`] still holds them.
+    pub fn closed_deal_events(&self) -> broadcast::Receiver<Deal> {
+        self.client.state.trade_state.closed_deal_events()
+    }
+
     /// Checks if the client is currently connected to the WebSocket server.
     ///
     /// Use this before performing operations to avoid "channel closed" errors
@@ -327,39 +781,74 @@ impl PocketOption {
         self.client.is_connected()
     }
 
+    /// Current lifecycle status of the connection.
+    pub fn status(&self) -> ConnectionStatus {
+        self.client.status()
+    }
+
+    /// Stream yielding the current connection status followed by every change.
+    pub fn status_stream(
+        &self,
+    ) -> impl futures_util::Stream<Item = ConnectionStatus> + Send + 'static {
+        self.client.status_stream()
+    }
+
+    /// Waits until the connection reaches `status`, or fails after `timeout`.
+    pub async fn wait_for_status(
+        &self,
+        status: ConnectionStatus,
+        timeout: Duration,
+    ) -> PocketResult<()> {
+        let result = tokio::time::timeout(timeout, self.client.wait_for_status(status)).await;
+        if result.is_err() && self.client.state.is_auth_rejected() {
+            return Err(PocketError::NotAuthorized(
+                "the server rejected the session, the SSID may be invalid or expired".into(),
+            ));
+        }
+        result.map_err(|_| PocketError::Timeout {
+            task: "wait_for_status".to_string(),
+            context: format!("waiting for {status}, current: {}", self.status()),
+            duration: timeout,
+        })
+    }
+
 
     /// Subscribes to an asset's stream and prepends historical data.
     ///
     /// This is a QoL helper for bot developers who need to "warm up" their indicators.
+    ///
+    /// The subscription is opened before the history is fetched and both are spliced at the
+    /// server time of the subscription, see [`HistorySplice`]: no candle is missed or delivered
+    /// twice in between. With a time aligned subscription the candle of that moment is built
+    /// from the history and the live updates.
     pub async fn subscribe_with_history(
         &self,
         asset: impl Into<String>,
         sub_type: SubscriptionType,
     ) -> PocketResult<impl futures_util::Stream<Item = PocketResult<Candle>> + 'static> {
-        let asset_str = asset.into();
-
-        // Determine the period for history based on subscription type
-        let period = match &sub_type {
-            SubscriptionType::Time { duration, .. } => duration.as_secs() as u32,
-            SubscriptionType::TimeAligned { duration, .. } => duration.as_secs() as u32,
-            _ => 60, // Default to 1 minute if not specified
-        };
+        use futures_util::stream::{iter, StreamExt};
 
-        // 1. Fetch history
-        let history = self
-            .history(asset_str.clone(), period)
-            .await
-            .unwrap_or_default();
+        let asset_str = asset.into();
+        // History candles of the subscription period, 1 minute for other subscriptions
+        let period = sub_type.period_secs().unwrap_or(60);
+        let aligned = matches!(sub_type, SubscriptionType::TimeAligned { .. });
 
-        // 2. Subscribe to live stream
-        let subscription = self.subscribe(asset_str, sub_type).await?;
-        let live_stream = subscription.to_stream();
+        // 1. Subscribe first, updates are buffered while the history loads
+        let subscription = self.subscribe(asset_str.clone(), sub_type).await?;
+        let start = self.server_time().await.timestamp();
 
-        // 3. Chain history and live stream
-        use futures_util::stream::{iter, StreamExt};
-        let history_stream = iter(history.into_iter().map(Ok));
+        // 2. Fetch history, it overlaps with the subscription from `start` on
+        let history = self.history(asset_str, period).await.unwrap_or_default();
+        let (history, mut splice) = HistorySplice::new(history, start, period, aligned);
 
-        Ok(history_stream.chain(live_stream))
+        // 3. Splice history and live stream
+        let live_stream = subscription
+            .to_stream()
+            .flat_map(move |candle| match candle {
+                Ok(candle) => iter(splice.push(candle).into_iter().map(Ok).collect::<Vec<_>>()),
+                Err(e) => iter(vec![Err(e)]),
+            });
+        Ok(iter(history.into_iter().map(Ok)).chain(live_stream))
     }
 
     /// Validates if an asset is active and supports the given timeframe without cloning the entire assets map.
@@ -373,41 +862,70 @@ impl PocketOption {
         }
     }
 
-    async fn register_pending_trade(
-        &self,
-        asset: &str,
-        action: Action,
-        time: u32,
-        amount: Decimal,
-    ) -> Uuid {
-        use crate::pocketoption::types::OpenOrder;
-        let request_id = Uuid::new_v4();
-        let order = OpenOrder::new(
-            amount,
-            asset.to_string(),
-            action,
-            time,
-            self.is_demo() as u32,
-            request_id,
-        );
-        self.client
-            .state
-            .trade_state
-            .pending_market_orders
-            .write()
-            .await
-            .insert(request_id, (order, std::time::Instant::now()));
-        request_id
+    /// Returns the trading hours of `asset`, inferred from its type and OTC flag.
+    pub async fn asset_schedule(&self, asset: impl ToString) -> PocketResult<AssetSchedule> {
+        let asset = asset.to_string();
+        let assets = self.client.state.assets.read().await;
+        let assets = assets
+            .as_ref()
+            .ok_or_else(|| PocketError::General("Assets not loaded".to_string()))?;
+        assets
+            .get(&asset)
+            .map(AssetSchedule::for_asset)
+            .ok_or(PocketError::InvalidAsset(asset))
+    }
+
+    /// Returns `true` if `asset` is reported active by the server and its schedule is open
+    /// at the current server time.
+    pub async fn is_tradeable_now(&self, asset: impl ToString) -> PocketResult<bool> {
+        let asset = asset.to_string();
+        let now = self.server_time().await;
+        let assets = self.client.state.assets.read().await;
+        let assets = assets
+            .as_ref()
+            .ok_or_else(|| PocketError::General("Assets not loaded".to_string()))?;
+        let asset = assets.get(&asset).ok_or(PocketError::InvalidAsset(asset))?;
+        Ok(asset.is_active && AssetSchedule::for_asset(asset).is_open_at(now))
+    }
+
+    /// Waits up to `wait` for the payout of `asset` to reach `min` percent.
+    async fn ensure_payout(&self, asset: &str, min: i32, wait: Duration) -> PocketResult<()> {
+        let state = &self.client.state;
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let updated = state.assets_updated.notified();
+            tokio::pin!(updated);
+            updated.as_mut().enable();
+            let payout = state
+                .assets
+                .read()
+                .await
+                .as_ref()
+                .and_then(|assets| assets.get(asset))
+                .map_or(0, |a| a.payout);
+            if payout >= min {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, updated).await.is_err() {
+                return Err(PocketError::PayoutTooLow {
+                    asset: asset.to_string(),
+                    payout,
+                    min,
+                });
+            }
+        }
     }
 
     async fn cleanup_trade(&self, fingerprint: &(String, Action, u32, Decimal), request_id: Uuid) {
-        self.client
-            .state
-            .trade_state
-            .recent_trades
-            .write()
-            .await
-            .remove(fingerprint);
+        let mut recent = self.client.state.trade_state.recent_trades.write().await;
+        // Only release the reservation made by this order, not one of a concurrent call.
+        if recent
+            .get(fingerprint)
+            .is_some_and(|(id, _)| *id == request_id)
+        {
+            recent.remove(fingerprint);
+        }
+        drop(recent);
         self.client
             .state
             .trade_state
@@ -417,6 +935,13 @@ impl PocketOption {
             .remove(&request_id);
     }
 
+    /// Places a trade and waits for the server to open it.
+    ///
+    /// Dropping the returned future is a cooperative cancellation: if the order was not sent
+    /// yet it is skipped, otherwise the trade is still tracked in the opened/closed deals.
+    ///
+    /// An identical trade placed within `Config::duplicate_trade_window` is rejected, use
+    /// [`PocketOption::trade_with_options`] to place it anyway.
     pub async fn trade(
         &self,
         asset: impl ToString,
@@ -424,28 +949,22 @@ impl PocketOption {
         time: u32,
         amount: Decimal,
     ) -> PocketResult<(Uuid, Deal)> {
-        let asset_str = asset.to_string();
-
-        if amount <= dec!(0.0) {
-            return Err(PocketError::General("Amount must be positive".into()));
-        }
-
-        self.validate_asset(&asset_str, time).await?;
+        self.trade_with_options(asset, action, time, amount, TradeOptions::default())
+            .await
+    }
 
-        if amount < MINIMUM_TRADE_AMOUNT {
-            return Err(PocketError::General(format!(
-                "Amount must be at least {MINIMUM_TRADE_AMOUNT}"
-            )));
-        }
-        if amount > MAXIMUM_TRADE_AMOUNT {
-            return Err(PocketError::General(format!(
-                "Amount must be at most {MAXIMUM_TRADE_AMOUNT}"
-            )));
-        }
-        let fingerprint = (asset_str.clone(), action, time, amount);
-        let request_id = self
-            .register_pending_trade(&asset_str, action, time, amount)
-            .await;
+    /// Places a trade like [`PocketOption::trade`] with per-order options,
+    /// e.g. `TradeOptions::default().allow_duplicate(true)` for rapid-fire strategies.
+    pub async fn trade_with_options(
+        &self,
+        asset: impl ToString,
+        action: Action,
+        time: u32,
+        amount: Decimal,
+        options: TradeOptions,
+    ) -> PocketResult<(Uuid, Deal)> {
+        let request = TradeRequest::new(asset, action, time, amount).with_options(options);
+        let request_id = self.prepare_trade(&request).await?;
 
         let handle = match self
             .require_handle::<TradesApiModule>("TradesApiModule")
@@ -453,27 +972,119 @@ impl PocketOption {
         {
             Ok(h) => h,
             Err(e) => {
-                self.cleanup_trade(&fingerprint, request_id).await;
+                self.cleanup_trade(&request.fingerprint(), request_id).await;
                 return Err(e);
             }
         };
 
-        match handle
-            .trade_with_id(asset_str, action, amount, time, request_id)
+        let result = handle
+            .trade_with_id(request.asset.clone(), action, amount, time, request_id)
+            .await;
+        self.finish_trade(&request, request_id, result).await
+    }
+
+    /// Places several trades with minimal latency between them, e.g. to hedge or to act
+    /// on a signal for multiple assets.
+    ///
+    /// Every request is checked like [`PocketOption::trade_with_options`] first, then the
+    /// orders passing the checks are sent back-to-back. Returns the result of each request,
+    /// in order, so a failed order doesn't affect the others.
+    pub async fn trade_batch(
+        &self,
+        requests: Vec<TradeRequest>,
+    ) -> Vec<PocketResult<(Uuid, Deal)>> {
+        let mut results = Vec::with_capacity(requests.len());
+        let mut orders = Vec::new();
+        for (index, request) in requests.iter().enumerate() {
+            match self.prepare_trade(request).await {
+                Ok(request_id) => {
+                    orders.push((index, request_id));
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        let sent = match self.client.get_handle::<TradesApiModule>().await {
+            Some(handle) => {
+                let batch = orders
+                    .iter()
+                    .map(|&(index, request_id)| (request_id, requests[index].clone()))
+                    .collect();
+                handle.trade_batch(batch).await
+            }
+            None => orders
+                .iter()
+                .map(|_| Err(PocketError::ModuleNotFound("TradesApiModule".into())))
+                .collect(),
+        };
+        for ((index, request_id), result) in orders.into_iter().zip(sent) {
+            let result = self
+                .finish_trade(&requests[index], request_id, result)
+                .await;
+            results[index] = Some(result);
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Checks a trade request and reserves it against duplicates, returning its request id.
+    async fn prepare_trade(&self, request: &TradeRequest) -> PocketResult<Uuid> {
+        if self.client.state.is_draining() {
+            return Err(PocketError::ShuttingDown("trade".into()));
+        }
+        let account = self.client.state.account_type();
+        if self.client.state.account_balance(account).await.is_none() {
+            return Err(PocketError::BalanceUnknown(account));
+        }
+        self.client
+            .state
+            .amount_limits()
             .await
-        {
+            .validate(request.amount)?;
+        self.validate_asset(&request.asset, request.time).await?;
+        if let Some(min) = request.options.min_payout {
+            self.ensure_payout(&request.asset, min, request.options.payout_wait)
+                .await?;
+        }
+        // The trades module records the order as pending once it is actually sent, so
+        // dropping this future (e.g. a cancelled Python task) before that never places it.
+        let request_id = Uuid::new_v4();
+        if !self.config.duplicate_trade_window.is_zero() && !request.options.allow_duplicate {
+            self.client
+                .state
+                .trade_state
+                .reserve_trade(
+                    &request.fingerprint(),
+                    request_id,
+                    self.config.duplicate_trade_window,
+                )
+                .await?;
+        }
+        Ok(request_id)
+    }
+
+    /// Records the outcome of an order sent for `request`.
+    async fn finish_trade(
+        &self,
+        request: &TradeRequest,
+        request_id: Uuid,
+        result: PocketResult<Deal>,
+    ) -> PocketResult<(Uuid, Deal)> {
+        match result {
             Ok(deal) => {
-                self.client
-                    .state
-                    .trade_state
-                    .recent_trades
-                    .write()
-                    .await
-                    .insert(fingerprint, (deal.id, std::time::Instant::now()));
+                if !self.config.duplicate_trade_window.is_zero() {
+                    self.client
+                        .state
+                        .trade_state
+                        .recent_trades
+                        .write()
+                        .await
+                        .insert(request.fingerprint(), (deal.id, std::time::Instant::now()));
+                }
                 Ok((deal.id, deal))
             }
             Err(e) => {
-                self.cleanup_trade(&fingerprint, request_id).await;
+                self.cleanup_trade(&request.fingerprint(), request_id).await;
                 Err(e)
             }
         }
@@ -513,12 +1124,68 @@ impl PocketOption {
         self.trade(asset, Action::Put, time, amount).await
     }
 
+    /// Places a trade using an `f64` amount.
+    /// This is a convenience wrapper around `trade` that converts the amount to `Decimal`.
+    pub async fn trade_f64(
+        &self,
+        asset: impl ToString,
+        action: Action,
+        time: u32,
+        amount: f64,
+    ) -> PocketResult<(Uuid, Deal)> {
+        self.trade(asset, action, time, amount_from_f64(amount)?)
+            .await
+    }
+
+    /// Places a new buy trade using an `f64` amount.
+    pub async fn buy_f64(
+        &self,
+        asset: impl ToString,
+        time: u32,
+        amount: f64,
+    ) -> PocketResult<(Uuid, Deal)> {
+        self.trade_f64(asset, Action::Call, time, amount).await
+    }
+
+    /// Places a new sell trade using an `f64` amount.
+    pub async fn sell_f64(
+        &self,
+        asset: impl ToString,
+        time: u32,
+        amount: f64,
+    ) -> PocketResult<(Uuid, Deal)> {
+        self.trade_f64(asset, Action::Put, time, amount).await
+    }
+
     /// Gets the current server time.
     /// If the server time is not set, it returns None.
     pub async fn server_time(&self) -> DateTime<Utc> {
         self.client.state.get_server_datetime().await
     }
 
+    /// Gets the server time minus the local time.
+    ///
+    /// It is the median over the latest server timestamps, which re-syncs when the server time
+    /// drifts further than `Config::server_time_drift_threshold`.
+    pub async fn server_time_offset(&self) -> chrono::Duration {
+        self.client.state.server_time_offset().await
+    }
+
+    /// Gets the staleness and jitter of the server time, to guard expiry-sensitive trades
+    /// against clock skew.
+    pub async fn server_time_quality(&self) -> ServerTimeQuality {
+        self.client.state.server_time_quality().await
+    }
+
+    /// Captures the balances, server time, subscriptions, deals and pending orders of the
+    /// client, for bug reports and dashboards.
+    ///
+    /// The session token is never included, the uid and balances are left out while log
+    /// redaction is on, see [`set_log_redaction`](crate::stream::set_log_redaction).
+    pub async fn debug_snapshot(&self) -> DebugSnapshot {
+        DebugSnapshot::capture(&self.client.state, log_redaction()).await
+    }
+
     /// Gets the current assets.
     pub async fn assets(&self) -> Option<Assets> {
         let state = &self.client.state;
@@ -529,6 +1196,60 @@ impl PocketOption {
         None
     }
 
+    /// Asks the server for the asset list again and waits until it is received.
+    ///
+    /// Changes compared to the previous list are also sent to [`PocketOption::assets_changed`]
+    /// receivers.
+    pub async fn refresh_assets(&self, timeout: Duration) -> PocketResult<Assets> {
+        if !self.is_connected() {
+            return Err(PocketError::ConnectionLost(
+                "The connection may have dropped; wait for reconnection or create a new client."
+                    .into(),
+            ));
+        }
+        let state = &self.client.state;
+        let updated = state.assets_updated.notified();
+        tokio::pin!(updated);
+        updated.as_mut().enable();
+        self.send_raw(r#"42["assets/load"]"#.to_string()).await?;
+        tokio::time::timeout(timeout, updated)
+            .await
+            .map_err(|_| PocketError::Timeout {
+                task: "refresh_assets".to_string(),
+                context: "assets/load".to_string(),
+                duration: timeout,
+            })?;
+        self.assets()
+            .await
+            .ok_or_else(|| PocketError::General("Assets were not stored after a refresh".into()))
+    }
+
+    /// Subscribes to payout and availability changes between consecutive asset lists,
+    /// e.g. an OTC pair opening or closing.
+    ///
+    /// The first list received after connecting only sets the baseline. Slow receivers
+    /// lose the oldest changes and get `RecvError::Lagged`.
+    pub fn assets_changed(&self) -> broadcast::Receiver<AssetChange> {
+        self.client.state.asset_changes()
+    }
+
+    /// Subscribes to the frames a module received but could not parse, with the raw payload
+    /// and the error, to detect changes of the server's message formats.
+    ///
+    /// Slow receivers lose the oldest failures and get `RecvError::Lagged`.
+    pub fn parse_failures(&self) -> broadcast::Receiver<ParseFailure> {
+        self.client.state.parse_failures()
+    }
+
+    /// Subscribes to the suspicious candles found in history, `get_candles` results and
+    /// live subscriptions: timestamps going back in time, zero-range candles and gaps longer
+    /// than the period. The candles are still delivered, discard them as needed.
+    ///
+    /// Slow receivers lose the oldest events and get `RecvError::Lagged`.
+    pub fn data_quality_events(&self) -> broadcast::Receiver<DataQualityEvent> {
+        self.client.state.data_quality()
+    }
+
     /// Gets the current active assets only.
     /// This filters out inactive assets from the available assets.
     ///
@@ -543,6 +1264,21 @@ impl PocketOption {
         None
     }
 
+    /// Waits until `condition` holds, e.g. `Condition::BalanceKnown` or
+    /// `Condition::FirstTick("EURUSD_otc".into())`.
+    ///
+    /// Returns immediately if the condition already holds, otherwise wakes up on the
+    /// matching state notification instead of polling.
+    pub async fn wait_for(&self, condition: Condition, timeout: Duration) -> PocketResult<()> {
+        tokio::time::timeout(timeout, self.client.state.wait_for(&condition))
+            .await
+            .map_err(|_| PocketError::Timeout {
+                task: "wait_for".to_string(),
+                context: condition.to_string(),
+                duration: timeout,
+            })
+    }
+
     /// Waits for the assets to be loaded from the server.
     /// # Arguments
     /// * `timeout` - The maximum time to wait for assets to be loaded.
@@ -551,15 +1287,9 @@ impl PocketOption {
     pub async fn wait_for_assets(&self, timeout: Duration) -> PocketResult<()> {
         let state = &self.client.state;
 
-        // Fast path
-        if state.assets.read().await.is_some() {
-            return Ok(());
-        }
-
-        if tokio::time::timeout(timeout, state.assets_updated.notified())
+        if tokio::time::timeout(timeout, state.wait_for(&Condition::AssetsLoaded))
             .await
             .is_ok()
-            && state.assets.read().await.is_some()
         {
             return Ok(());
         }
@@ -600,6 +1330,118 @@ impl PocketOption {
             .await
     }
 
+    /// Returns where a deal is in its lifecycle, without waiting.
+    pub async fn deal_status(&self, id: Uuid) -> PocketResult<DealStatus> {
+        let trade_state = &self.client.state.trade_state;
+        if let Some(deal) = trade_state.get_closed_deal(id).await {
+            return Ok(DealStatus::Closed(deal));
+        }
+        trade_state
+            .get_opened_deal(id)
+            .await
+            .map(|deal| DealStatus::opened(deal, Utc::now()))
+            .ok_or(PocketError::DealNotFound(id))
+    }
+
+    /// Checks the result of a trade by its ID, see [`ResultOptions`].
+    /// # Returns
+    /// The `DealStatus::Closed` deal when waiting, otherwise the current status of the deal.
+    pub async fn result_with_options(
+        &self,
+        id: Uuid,
+        options: &ResultOptions,
+    ) -> PocketResult<DealStatus> {
+        let status = self.deal_status(id).await?;
+        if status.is_closed() || !options.wait {
+            return Ok(status);
+        }
+        let Some(grace) = options.history_fallback else {
+            return self.result(id).await.map(DealStatus::Closed);
+        };
+        let deal = status.deal();
+        let remaining = (deal.close_timestamp - Utc::now())
+            .to_std()
+            .unwrap_or_default();
+        match self.result_with_timeout(id, remaining + grace).await {
+            Err(PocketError::Timeout { .. }) => {
+                tracing::warn!(target: "PocketOption", "No result pushed for deal {}, checking the server history", id);
+                self.result_from_history(deal).await.map(DealStatus::Closed)
+            }
+            result => result.map(DealStatus::Closed),
+        }
+    }
+
+    /// Finds the result of `deal` in the server's closed deals history.
+    async fn result_from_history(&self, deal: &Deal) -> PocketResult<Deal> {
+        let from = deal.open_timestamp - chrono::Duration::minutes(1);
+        let closed = self
+            .closed_deals_history(from, Utc::now())
+            .await?
+            .into_iter()
+            .find(|closed| closed.id == deal.id)
+            .ok_or(PocketError::DealNotFound(deal.id))?;
+        self.client
+            .state
+            .trade_state
+            .update_closed_deals(vec![closed.clone()])
+            .await;
+        Ok(closed)
+    }
+
+    /// Streams the status of a deal as it goes from open to closing to closed.
+    ///
+    /// Each status is sent once, starting with the current one, and the stream ends after
+    /// the closed deal or an error. The result is awaited as in [`Self::result_with_options`].
+    pub fn deal_status_stream(
+        &self,
+        id: Uuid,
+        options: ResultOptions,
+    ) -> impl futures_util::Stream<Item = PocketResult<DealStatus>> + Send + 'static {
+        let options = ResultOptions {
+            wait: true,
+            ..options
+        };
+        futures_util::stream::unfold(Some((self.clone(), None::<DealStatus>)), move |updates| {
+            let options = options.clone();
+            async move {
+                let (client, last) = updates?;
+                let status = match &last {
+                    None => client.deal_status(id).await,
+                    Some(DealStatus::Open(deal)) => {
+                        let remaining = (deal.close_timestamp - Utc::now())
+                            .to_std()
+                            .unwrap_or_default();
+                        // The deal may also be closed early.
+                        tokio::select! {
+                            result = client.result(id) => result.map(DealStatus::Closed),
+                            _ = tokio::time::sleep(remaining) => client.deal_status(id).await,
+                        }
+                    }
+                    Some(DealStatus::Closing(_)) => client.result_with_options(id, &options).await,
+                    Some(DealStatus::Closed(_)) => return None,
+                };
+                match status {
+                    Ok(status) => Some((Ok(status.clone()), Some((client, Some(status))))),
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })
+    }
+
+    /// Closes an opened deal before its expiry ("sell early").
+    /// # Arguments
+    /// * `id` - The ID of the deal to close.
+    /// # Returns
+    /// The `EarlyClose` with the amount credited back to the balance. Fails with
+    /// `PocketError::EarlyCloseRejected` when the server or account type does not allow it,
+    /// and `PocketError::DealNotFound` when the deal is unknown.
+    pub async fn close_deal_early(&self, id: Uuid) -> PocketResult<EarlyClose> {
+        self.require_handle::<DealsApiModule>("DealsApiModule")
+            .await?
+            .close_early(id)
+            .await
+    }
+
     /// Gets the currently opened deals.
     pub async fn get_opened_deals(&self) -> HashMap<Uuid, Deal> {
         self.client.state.trade_state.get_opened_deals().await
@@ -614,6 +1456,183 @@ impl PocketOption {
         self.client.state.trade_state.clear_closed_deals().await
     }
 
+    /// Gets win/loss statistics over the deals closed since the client started.
+    ///
+    /// Includes win rate, rolling win rate, profit factor, streaks and a per-asset breakdown.
+    pub async fn trade_stats(&self) -> TradeStats {
+        self.client.state.trade_state.get_stats().await
+    }
+
+    /// Resets the statistics returned by [`PocketOption::trade_stats`],
+    /// [`PocketOption::trade_latency`] and [`PocketOption::slippage_stats`].
+    pub async fn reset_trade_stats(&self) {
+        self.client.state.trade_state.reset_stats().await
+    }
+
+    /// Net profit of the current server day and whether `Config::kill_switch` stopped the
+    /// trading, in which case every order fails with `PocketError::KillSwitchTripped`.
+    pub async fn kill_switch_status(&self) -> KillSwitchStatus {
+        let now = self.client.state.get_server_datetime().await;
+        self.client.state.trade_state.kill_switch.status(now)
+    }
+
+    /// Subscribes to the kill switch tripping, at most once per server day.
+    pub fn kill_switch_events(&self) -> broadcast::Receiver<KillSwitchEvent> {
+        self.client.state.trade_state.kill_switch.events()
+    }
+
+    /// Snapshot of the connection's traffic, reconnect and error counters, with the messages
+    /// routed to each module.
+    pub fn stats(&self) -> ClientStats {
+        self.client.stats()
+    }
+
+    /// Messages sent after the one numbered `since` (`0` for all the kept ones), each with its
+    /// sequence number and the module that sent it, e.g. to find which module sent a
+    /// `changeSymbol`.
+    ///
+    /// Empty unless `Config::audit_log` is set, which is the number of messages kept.
+    pub fn audit_log(&self, since: u64) -> Vec<AuditRecord> {
+        self.client.audit_log(since)
+    }
+
+    /// Sets the counters returned by [`PocketOption::stats`] back to zero.
+    pub fn reset_stats(&self) {
+        self.client.reset_stats()
+    }
+
+    /// Stage timings of the most recently resolved order (opened, failed or timed out).
+    ///
+    /// Splits the time spent waiting in the command queue, waiting for the server's answer
+    /// and handing the deal back, which tells a slow server apart from a congested client
+    /// when diagnosing `Trade task timeout` errors.
+    pub async fn last_trade_timings(&self) -> Option<TradeTimings> {
+        self.client.state.trade_state.get_latency().await.last
+    }
+
+    /// Latency histograms for each stage of every order placed since the client started.
+    pub async fn trade_latency(&self) -> TradeLatency {
+        self.client.state.trade_state.get_latency().await
+    }
+
+    /// Slippage of every deal opened since the client started against the last price of its
+    /// asset when the order was sent, see [`Deal::slippage`].
+    ///
+    /// Only the deals of subscribed assets have a quote to compare with.
+    pub async fn slippage_stats(&self) -> SlippageStats {
+        self.client.state.trade_state.get_slippage().await
+    }
+
+    /// Current [`Heartbeat`]: connection status, last message age, subscriptions and queues.
+    pub async fn heartbeat_now(&self) -> Heartbeat {
+        Self::collect_heartbeat(&self.client).await
+    }
+
+    /// Stream emitting a [`Heartbeat`] right away and then every `interval`.
+    ///
+    /// The stream only holds the client's shared state, it does not keep the connection
+    /// open once every `PocketOption` handle is dropped.
+    ///
+    /// # Panics
+    /// Polling the stream panics if `interval` is zero.
+    pub fn heartbeat(
+        &self,
+        interval: Duration,
+    ) -> impl futures_util::Stream<Item = Heartbeat> + Send + 'static {
+        let client = self.client.clone();
+        futures_util::stream::unfold(
+            (client, None::<tokio::time::Interval>),
+            move |(client, ticker)| async move {
+                let mut ticker = ticker.unwrap_or_else(|| {
+                    let mut ticker = tokio::time::interval(interval);
+                    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    ticker
+                });
+                ticker.tick().await;
+                let heartbeat = Self::collect_heartbeat(&client).await;
+                Some((heartbeat, (client, Some(ticker))))
+            },
+        )
+    }
+
+    async fn collect_heartbeat(client: &Client<State>) -> Heartbeat {
+        let state = &client.state;
+        let mut subscriptions: Vec<String> = state
+            .active_subscriptions
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| entry.key().clone())
+            .collect();
+        subscriptions.sort();
+        Heartbeat {
+            at: Utc::now(),
+            status: client.status(),
+            last_message_age: client.last_message_age(),
+            subscriptions,
+            outgoing_queue: client.outgoing_queue_len(),
+            pending_orders: state.trade_state.pending_market_orders.read().await.len(),
+            open_deals: state.trade_state.opened_deals_count().await,
+        }
+    }
+
+    /// Round trip times of the keep-alive pings, only measured when `KeepAliveConfig::pong`
+    /// is set for the server.
+    pub fn ping_stats(&self) -> PingStats {
+        self.client
+            .state
+            .ping_stats
+            .read()
+            .expect("ping stats lock poisoned")
+            .clone()
+    }
+
+    /// Server URLs skipped on reconnects after failing repeatedly, see `Config::url_blacklist`.
+    pub fn blacklisted_urls(&self) -> Vec<BlacklistedUrl> {
+        self.client.state.url_blacklist.blacklisted()
+    }
+
+    /// Request/response correlation counters of the trades, deals and subscriptions modules.
+    ///
+    /// `timed_out` counts requests completed with a timeout error because no response
+    /// arrived, `orphaned` counts responses that arrived for requests nobody waited for.
+    pub async fn correlation_stats(&self) -> PocketResult<CorrelationCounters> {
+        let subscriptions = self
+            .require_handle::<SubscriptionsApiModule>("SubscriptionsApiModule")
+            .await?
+            .correlation_stats();
+        Ok(self.client.state.correlation_stats.snapshot() + subscriptions)
+    }
+
+    /// Fetches the account's closed deals between `from` and `to` from the server.
+    ///
+    /// Unlike [`PocketOption::get_closed_deals`], which only contains deals closed during
+    /// this session, this queries the server and follows pagination until all deals
+    /// in the range have been received.
+    pub async fn closed_deals_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> PocketResult<Vec<Deal>> {
+        self.require_handle::<DealHistoryApiModule>("DealHistoryApiModule")
+            .await?
+            .range(from, to, DEFAULT_PAGE_SIZE)
+            .await
+    }
+
+    /// Fetches a single page of the account's closed deals between `from` and `to`.
+    pub async fn closed_deals_history_page(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        offset: u32,
+        limit: u32,
+    ) -> PocketResult<DealHistoryPage> {
+        self.require_handle::<DealHistoryApiModule>("DealHistoryApiModule")
+            .await?
+            .page(from, to, offset, limit)
+            .await
+    }
+
     /// Gets a specific opened deal by its ID.
     pub async fn get_opened_deal(&self, deal_id: Uuid) -> Option<Deal> {
         self.client.state.trade_state.get_opened_deal(deal_id).await
@@ -624,6 +1643,41 @@ impl PocketOption {
         self.client.state.trade_state.get_closed_deal(deal_id).await
     }
 
+    /// Returns the time left before a deal expires, computed with server time.
+    ///
+    /// Returns zero once the deal closed and `DealNotFound` if the deal is unknown.
+    pub async fn time_to_expiry(&self, deal_id: Uuid) -> PocketResult<Duration> {
+        self.client
+            .state
+            .time_to_expiry(deal_id)
+            .await
+            .ok_or(PocketError::DealNotFound(deal_id))
+    }
+
+    /// Streams the time left before a deal expires, once per second.
+    ///
+    /// The first value is sent immediately and the stream ends after sending zero, i.e. when
+    /// the deal reached its expiry or closed.
+    pub async fn deal_countdown_stream(
+        &self,
+        deal_id: Uuid,
+    ) -> PocketResult<impl futures_util::Stream<Item = Duration> + Send + 'static> {
+        self.time_to_expiry(deal_id).await?;
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let state = self.client.state.clone();
+        Ok(futures_util::stream::unfold(
+            Some((state, interval)),
+            move |countdown| async move {
+                let (state, mut interval) = countdown?;
+                interval.tick().await;
+                let left = state.time_to_expiry(deal_id).await.unwrap_or_default();
+                let next = (!left.is_zero()).then_some((state, interval));
+                Some((left, next))
+            },
+        ))
+    }
+
     /// Non-blocking check of a closed deal by its ID.
     pub fn try_get_settled_deal(&self, deal_id: &Uuid) -> Option<Deal> {
         self.client.state.trade_state.try_get_closed_deal(deal_id)
@@ -653,6 +1707,12 @@ impl PocketOption {
         min_payout: u32,
         command: u32,
     ) -> PocketResult<PendingOrder> {
+        if self.client.state.is_draining() {
+            return Err(PocketError::ShuttingDown("pending order".into()));
+        }
+        let now = self.client.state.get_server_datetime().await;
+        self.client.state.trade_state.kill_switch.check(now)?;
+        self.client.state.amount_limits().await.validate(amount)?;
         self.require_handle::<PendingTradesApiModule>("PendingTradesApiModule")
             .await?
             .with_lock(self.pending_trades_lock.clone())
@@ -669,6 +1729,20 @@ impl PocketOption {
             .await
     }
 
+    /// Makes a pending order follow the market by `config.distance` until it is filled or
+    /// cancelled, see [`TrailingOrder`].
+    pub async fn trail_pending_order(
+        &self,
+        ticket: Uuid,
+        config: TrailingConfig,
+    ) -> PocketResult<TrailingOrder> {
+        let order = self
+            .get_pending_deal(ticket)
+            .await
+            .ok_or(PocketError::DealNotFound(ticket))?;
+        Ok(TrailingOrder::start(self.clone(), order, config))
+    }
+
     /// Gets the currently pending deals.
     /// # Returns
     /// A `HashMap` containing the pending deals, keyed by their UUID.
@@ -726,8 +1800,9 @@ impl PocketOption {
         sub_type: SubscriptionType,
     ) -> PocketResult<SubscriptionStream> {
         if !self.is_connected() {
-            return Err(PocketError::General(
-                "Not connected to server. The connection may have dropped; wait for reconnection or create a new client.".into(),
+            return Err(PocketError::ConnectionLost(
+                "The connection may have dropped; wait for reconnection or create a new client."
+                    .into(),
             ));
         }
         let handle = self
@@ -745,6 +1820,121 @@ impl PocketOption {
         }
     }
 
+    /// Subscribes to several assets at once and merges their candles into one stream of
+    /// `(asset, candle)` pairs, in the order they arrive.
+    ///
+    /// Fails if any subscription fails, the subscriptions already opened are then closed.
+    /// Dropping the stream unsubscribes from every asset.
+    pub async fn subscribe_many(
+        &self,
+        subscriptions: impl IntoIterator<Item = (impl ToString, SubscriptionType)>,
+    ) -> PocketResult<
+        impl futures_util::Stream<Item = (String, PocketResult<Candle>)> + Send + 'static,
+    > {
+        use futures_util::stream::{select_all, StreamExt};
+
+        let mut streams = Vec::new();
+        for (asset, sub_type) in subscriptions {
+            let asset = asset.to_string();
+            let stream = self.subscribe(&asset, sub_type).await?;
+            streams.push(
+                stream
+                    .to_stream()
+                    .map(move |candle| (asset.clone(), candle))
+                    .boxed(),
+            );
+        }
+        Ok(select_all(streams))
+    }
+
+    /// Scans more assets than there are subscription slots by rotating the slots through
+    /// `assets`, see [`ScannerSubscription`].
+    pub async fn scanner(
+        &self,
+        assets: impl IntoIterator<Item = impl ToString>,
+        config: ScannerConfig,
+    ) -> PocketResult<ScannerSubscription> {
+        self.require_handle::<SubscriptionsApiModule>("SubscriptionsApiModule")
+            .await?;
+        let known = self
+            .assets()
+            .await
+            .ok_or_else(|| BinaryOptionsError::General("Assets not found".into()))?;
+        let assets = assets
+            .into_iter()
+            .map(|asset| {
+                let asset = asset.to_string();
+                match known.get(&asset) {
+                    Some(_) => Ok(asset),
+                    None => Err(PocketError::InvalidAsset(asset)),
+                }
+            })
+            .collect::<PocketResult<Vec<_>>>()?;
+        Ok(ScannerSubscription::start(self.clone(), assets, config))
+    }
+
+    /// Starts downloading the history of `jobs` into `store` in the background, resuming
+    /// from the last record stored for each job, see [`DataCollector`].
+    pub async fn collect_data(
+        &self,
+        jobs: impl IntoIterator<Item = CollectorJob>,
+        store: Arc<dyn CollectorStore>,
+        config: CollectorConfig,
+    ) -> PocketResult<DataCollector> {
+        self.require_handle::<GetCandlesApiModule>("GetCandlesApiModule")
+            .await?;
+        let jobs: Vec<CollectorJob> = jobs.into_iter().collect();
+        if let Some(assets) = self.assets().await {
+            if let Some(job) = jobs.iter().find(|job| assets.get(&job.asset).is_none()) {
+                return Err(PocketError::InvalidAsset(job.asset.clone()));
+            }
+        }
+        if jobs
+            .iter()
+            .any(|job| job.series == Series::Candles { period: 0 })
+        {
+            return Err(PocketError::InvalidPeriod(0));
+        }
+        Ok(DataCollector::start(self.clone(), jobs, store, config))
+    }
+
+    /// Adds a price alert on `asset` and returns its id, e.g.
+    /// `add_alert("EURUSD_otc", AlertCondition::CrossesAbove(dec!(1.1)), AlertMode::Once)`.
+    ///
+    /// The condition is checked in Rust on every tick of the asset, only the alerts that fire
+    /// are sent to [`PocketOption::alert_events`]. The first alert of an asset subscribes to it,
+    /// the subscription is released once the asset has no alerts left.
+    pub async fn add_alert(
+        &self,
+        asset: impl ToString,
+        condition: AlertCondition,
+        mode: AlertMode,
+    ) -> PocketResult<Uuid> {
+        let asset = asset.to_string();
+        self.alerts
+            .add(asset.clone(), condition, mode, || {
+                self.subscribe(asset, SubscriptionType::none())
+            })
+            .await
+    }
+
+    /// Removes an alert, returns `false` if it already fired or never existed.
+    pub async fn remove_alert(&self, id: Uuid) -> bool {
+        self.alerts.remove(id).await
+    }
+
+    /// Alerts that have not fired yet, and the repeating ones.
+    pub async fn active_alerts(&self) -> Vec<Alert> {
+        self.alerts.list().await
+    }
+
+    /// Subscribes to the events of the alerts as they fire.
+    ///
+    /// Slow receivers lose the oldest events and get `RecvError::Lagged`.
+    pub fn alert_events(&self) -> broadcast::Receiver<AlertEvent> {
+        self.alerts.events()
+    }
+
     /// Unsubscribes from a specific asset's real-time updates.
     ///
     /// # Arguments
@@ -835,7 +2025,10 @@ impl PocketOption {
             }
         }
         // If assets are not loaded yet, still try to get candles
-        handle.get_candles(asset, period, offset).await
+        let now = self.client.state.clock.now().timestamp();
+        handle
+            .get_candles_advanced(asset, period, now, offset)
+            .await
     }
 
     /// Gets historical tick data (timestamp, price) for a specific asset and period.
@@ -857,8 +2050,9 @@ impl PocketOption {
         let asset_str = asset.to_string();
 
         if !self.is_connected() {
-            return Err(PocketError::General(
-                "Not connected to server. The connection may have dropped; wait for reconnection or create a new client.".into(),
+            return Err(PocketError::ConnectionLost(
+                "The connection may have dropped; wait for reconnection or create a new client."
+                    .into(),
             ));
         }
 
@@ -874,8 +2068,41 @@ impl PocketOption {
             .await?;
 
         // Use a 1-second period context for the server
+        let now = self.client.state.clock.now().timestamp();
         handle
-            .get_ticks(asset_str, 1, lookback_seconds as i64)
+            .get_ticks_until(asset_str, 1, lookback_seconds as i64, now)
+            .await
+    }
+
+    /// Gets the tick data (timestamp, price) of an asset between `from` and `to`.
+    ///
+    /// Unlike [`PocketOption::ticks`], which only looks back from now, this pages backwards
+    /// through the server history from `to` until `from` is covered, merging the pages and
+    /// dropping duplicated ticks, which makes it suitable for building datasets.
+    pub async fn ticks_range(
+        &self,
+        asset: impl ToString,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> PocketResult<Vec<(i64, f64)>> {
+        let asset_str = asset.to_string();
+
+        if !self.is_connected() {
+            return Err(PocketError::ConnectionLost(
+                "The connection may have dropped; wait for reconnection or create a new client."
+                    .into(),
+            ));
+        }
+
+        if let Some(assets) = self.assets().await {
+            if assets.get(&asset_str).is_none() {
+                return Err(PocketError::InvalidAsset(asset_str.clone()));
+            }
+        }
+
+        self.require_handle::<GetCandlesApiModule>("GetCandlesApiModule")
+            .await?
+            .get_ticks_range(asset_str, 1, from.timestamp(), to.timestamp())
             .await
     }
 
@@ -967,6 +2194,16 @@ impl PocketOption {
         self.client.get_handle::<M>().await
     }
 
+    /// Adds a middleware layer to the running client, see [`Client::add_middleware`].
+    pub async fn add_middleware(&self, middleware: Box<dyn WebSocketMiddleware<State>>) {
+        self.client.add_middleware(middleware).await
+    }
+
+    /// Attaches a custom api module to the running client, see [`Client::register_module`].
+    pub async fn register_module<M: ApiModule<State>>(&self) -> PocketResult<M::Handle> {
+        Ok(self.client.register_module::<M>().await?)
+    }
+
     /// Disconnects the client while keeping the configuration intact.
     /// The connection can be re-established later using `connect()`.
     /// This is useful for temporarily closing the connection without losing credentials or settings.
@@ -974,30 +2211,111 @@ impl PocketOption {
         self.client.disconnect().await.map_err(PocketError::from)
     }
 
-    /// Establishes a connection after a manual disconnect.
+    /// Connects a client built with [`PocketOptionBuilder::build_offline`] and waits until it
+    /// is ready, or establishes a connection after a manual disconnect.
     /// This will reconnect using the same configuration and credentials.
     pub async fn connect(&self) -> PocketResult<()> {
+        if self.runner.start(&self.client) {
+            return self.wait_ready().await;
+        }
         self.client.reconnect().await.map_err(PocketError::from)
     }
 
+    /// Waits until the runner stops without a shutdown, e.g. because the session was rejected
+    /// or the reconnect policy gave up. Never resolves for a client that keeps running.
+    pub async fn fatal_error(&self) -> FatalError {
+        match next_fatal(self.runner.fatal.subscribe()).await {
+            Some(error) => error,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Calls `callback` once if the runner stops without a shutdown, see
+    /// [`fatal_error`](Self::fatal_error). The callback is dropped with the client.
+    pub fn on_fatal(&self, callback: impl FnOnce(FatalError) + Send + 'static) {
+        let fatal = self.runner.fatal.subscribe();
+        tokio::spawn(async move {
+            if let Some(error) = next_fatal(fatal).await {
+                callback(error);
+            }
+        });
+    }
+
     /// Disconnects and reconnects the client.
     pub async fn reconnect(&self) -> PocketResult<()> {
         self.client.reconnect().await.map_err(PocketError::from)
     }
 
     /// Commands the runner to shutdown without consuming the client.
+    ///
+    /// The session is saved first when `Config::session_path` is set.
     pub async fn shutdown(&self) -> PocketResult<()> {
+        self.save_configured_session().await;
+        self.runner.stopping();
         self.client.shutdown_ref().await.map_err(PocketError::from)
     }
 
+    /// Gracefully shuts down the client.
+    ///
+    /// New trades and pending orders are rejected with `PocketError::ShuttingDown` right away,
+    /// then this waits up to `timeout` for in-flight pending-order confirmations, trades that
+    /// were sent but not yet confirmed, and opened deals awaiting their result. Once everything
+    /// settled or the timeout expired, the frame dump of `Config::frame_dump` is flushed, the
+    /// session saved to `Config::session_path` and the socket closed.
+    ///
+    /// Returns the number of trades that were still unresolved when the socket was closed.
+    pub async fn shutdown_graceful(&self, timeout: Duration) -> PocketResult<usize> {
+        let state = &self.client.state;
+        state.start_draining();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        // Any in-flight `open_pending_order` holds this lock until the server confirms it.
+        let _pending_guard = match tokio::time::timeout_at(
+            deadline,
+            self.pending_trades_lock.lock(),
+        )
+        .await
+        {
+            Ok(guard) => Some(guard),
+            Err(_) => {
+                tracing::warn!(target: "PocketOption", "Timed out waiting for pending order confirmations");
+                None
+            }
+        };
+
+        let unresolved = state.trade_state.wait_settled(deadline).await;
+        if unresolved > 0 {
+            tracing::warn!(
+                target: "PocketOption",
+                "Graceful shutdown timed out with {unresolved} unresolved trade(s)"
+            );
+        } else {
+            tracing::info!(target: "PocketOption", "All in-flight trades settled, shutting down");
+        }
+
+        if let Some(dump) = &self.frame_dump {
+            if !dump.flush().await {
+                tracing::warn!(target: "PocketOption", "Failed to flush the frame dump");
+            }
+        }
+        self.shutdown().await?;
+        Ok(unresolved)
+    }
+
+    /// Returns `true` once `shutdown_graceful` has been called.
+    pub fn is_shutting_down(&self) -> bool {
+        self.client.state.is_draining()
+    }
+
     /// Shuts down the client and stops the runner.
     pub async fn shutdown_owned(self) -> PocketResult<()> {
-        self._runner.abort();
+        self.save_configured_session().await;
+        self.runner.abort();
         self.client.clone().shutdown().await.map_err(PocketError::from)
     }
 
     pub async fn new_testing_wrapper(ssid: impl ToString) -> PocketResult<TestingWrapper<State>> {
-        let pocket_builder = Self::builder(ssid)?;
+        let pocket_builder = Self::testing_builder(ssid)?;
         let builder = TestingWrapperBuilder::new()
             .with_stats_interval(Duration::from_secs(10))
             .with_log_stats(true)
@@ -1012,6 +2330,73 @@ impl PocketOption {
         Ok(builder)
     }
 
+    /// Creates a load scenario for this client, refused on real accounts.
+    ///
+    /// The scenario places `trades_per_minute` calls of `amount` on `asset`, keeps
+    /// `subscriptions` streams of `asset` open and reconnects the client every one to five
+    /// minutes. Add assertions on the `trades` and `subscriptions` workloads before running it.
+    pub fn demo_stress_scenario(
+        &self,
+        asset: impl ToString,
+        amount: Decimal,
+        time: u32,
+        trades_per_minute: u32,
+        subscriptions: usize,
+        duration: Duration,
+    ) -> PocketResult<StressScenario> {
+        use futures_util::stream::{once, StreamExt};
+
+        if !self.is_demo() {
+            return Err(PocketError::General(
+                "Stress scenarios can only run on demo accounts".into(),
+            ));
+        }
+        let asset = asset.to_string();
+        let (trader, subscriber) = (self.clone(), self.clone());
+        let reconnector = self.client.clone();
+        let trade_asset = asset.clone();
+        let trades = Workload::rate("trades", trades_per_minute, move || {
+            let (client, asset) = (trader.clone(), trade_asset.clone());
+            async move {
+                client
+                    .buy(asset, time, amount)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| CoreError::Other(e.to_string()))
+            }
+        });
+        let streams = Workload::streams("subscriptions", subscriptions, move || {
+            let (client, asset) = (subscriber.clone(), asset.clone());
+            once(async move { client.subscribe(asset, SubscriptionType::None).await }).flat_map(
+                |subscription| match subscription {
+                    Ok(subscription) => subscription
+                        .to_stream()
+                        .map(|candle| {
+                            candle
+                                .map(|_| ())
+                                .map_err(|e| CoreError::Other(e.to_string()))
+                        })
+                        .boxed(),
+                    Err(e) => once(async move { Err(CoreError::Other(e.to_string())) }).boxed(),
+                },
+            )
+        });
+        let disconnects = DisconnectInjection {
+            min_interval: Duration::from_secs(60),
+            max_interval: Duration::from_secs(300),
+        };
+        Ok(StressScenario::new("pocketoption-demo", duration)
+            .with_workload(trades)
+            .with_workload(streams)
+            .with_disconnects(
+                disconnects,
+                Some(move || {
+                    let client = reconnector.clone();
+                    async move { client.reconnect().await }
+                }),
+            ))
+    }
+
     /// Sends a raw message directly over the WebSocket connection.
     pub async fn send_raw(&self, message: String) -> PocketResult<()> {
         let msg = binary_options_tools_core::reimports::Message::Text(message.into());
@@ -1039,10 +2424,77 @@ impl PocketOption {
 
 impl Drop for PocketOption {
     fn drop(&mut self) {
-        if Arc::strong_count(&self._runner) == 1 {
-            self._runner.abort();
+        if Arc::strong_count(&self.runner) == 1 {
+            self.runner.abort();
+        }
+    }
+}
+
+/// Builder of a [`PocketOption`] client, see [`PocketOption::builder`].
+pub struct PocketOptionBuilder<C = PocketConnect> {
+    ssid: Option<String>,
+    config: Config,
+    connector: C,
+}
+
+impl<C: Connector<State> + 'static> PocketOptionBuilder<C> {
+    /// Sets the session ID, required.
+    pub fn ssid(mut self, ssid: impl ToString) -> Self {
+        self.ssid = Some(ssid.to_string());
+        self
+    }
+
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Connects through `connector` instead of the PocketOption servers, see
+    /// [`PocketOption::new_with_connector`].
+    pub fn connector<D: Connector<State> + 'static>(self, connector: D) -> PocketOptionBuilder<D> {
+        PocketOptionBuilder {
+            ssid: self.ssid,
+            config: self.config,
+            connector,
         }
     }
+
+    /// Builds the client and connects it, like [`PocketOption::new_with_config`].
+    pub async fn build(self) -> PocketResult<PocketOption> {
+        let (ssid, config, connector) = self.parts()?;
+        PocketOption::connect_with_modules(
+            ssid,
+            config,
+            connector,
+            PocketOption::configure_common_modules,
+            None,
+        )
+        .await
+    }
+
+    /// Builds the client without connecting it, so it can be created at startup without
+    /// network access. Its status is `Disconnected` until [`PocketOption::connect`] is called,
+    /// requests made before that fail or time out.
+    pub async fn build_offline(self) -> PocketResult<PocketOption> {
+        let (ssid, config, connector) = self.parts()?;
+        let client = PocketOption::build_with_modules(
+            ssid,
+            config,
+            connector,
+            PocketOption::configure_common_modules,
+            None,
+        )
+        .await?;
+        client.client.signal.set_disconnected();
+        Ok(client)
+    }
+
+    fn parts(self) -> PocketResult<(String, Config, C)> {
+        let ssid = self
+            .ssid
+            .ok_or_else(|| PocketError::Configuration("missing ssid".into()))?;
+        Ok((ssid, self.config, self.connector))
+    }
 }
 
 #[cfg(test)]
@@ -1431,4 +2883,63 @@ mod additional_tests {
             "One call should be blocked as duplicate"
         );
     }
+
+    #[tokio::test]
+    async fn test_observer_cannot_trade() {
+        use crate::pocketoption::fixtures::{self, MOCK_SSID};
+
+        let server = fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)]);
+        let config = Config {
+            connection_initialization_timeout: Duration::from_secs(10),
+            ..Config::default()
+        };
+        // What `PocketOption::observer` wraps, with the order sending methods still reachable
+        let client = PocketOption::connect_with_modules(
+            MOCK_SSID,
+            config,
+            server.clone(),
+            PocketOption::configure_data_modules,
+            None,
+        )
+        .await
+        .unwrap();
+        client
+            .wait_for_assets(Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        assert!(client
+            .client
+            .get_handle::<TradesApiModule>()
+            .await
+            .is_none());
+        assert!(client
+            .client
+            .get_handle::<PendingTradesApiModule>()
+            .await
+            .is_none());
+        assert!(client.client.get_handle::<RawApiModule>().await.is_none());
+
+        let err = client.buy("EURUSD_otc", 60, dec!(10)).await.unwrap_err();
+        assert!(matches!(err, PocketError::ModuleNotFound(m) if m == "TradesApiModule"));
+        let err = client
+            .open_pending_order(
+                0,
+                dec!(10),
+                "EURUSD_otc".into(),
+                String::new(),
+                dec!(1),
+                60,
+                0,
+                0,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PocketError::ModuleNotFound(m) if m == "PendingTradesApiModule"));
+        assert!(!server
+            .received_text()
+            .iter()
+            .any(|frame| frame.contains("openOrder") || frame.contains("openPendingOrder")));
+        client.shutdown().await.unwrap();
+    }
 }
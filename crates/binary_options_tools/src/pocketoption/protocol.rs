@@ -0,0 +1,530 @@
+//! Versioned wire models of the PocketOption messages the client decodes.
+//!
+//! PocketOption changes its payloads without notice (the `isOptimized` ssid field is one
+//! example). Each model here describes the layout last seen on the wire and decodes it
+//! leniently: unknown fields are ignored, fields the client can live without may be missing and
+//! tuples may grow. Every deviation from the known layout is logged once and kept in
+//! [`compatibility_report`], so a protocol change shows up in the logs before it breaks anything.
+//!
+//! The public types ([`Deal`], [`Asset`], [`StreamData`]) deserialize through these models.
+
+use core::fmt;
+use std::collections::BTreeSet;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::pocketoption::types::{Asset, AssetType, CandleLength, Deal, RequestId, StreamData};
+use crate::pocketoption::utils::normalize_timestamp;
+
+/// A message model known to the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Model {
+    Deal,
+    Asset,
+    StreamData,
+}
+
+impl Model {
+    /// The latest layout version of the model the client knows.
+    pub fn version(self) -> u32 {
+        match self {
+            Model::Deal | Model::Asset | Model::StreamData => 1,
+        }
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Model::Deal => write!(f, "deal"),
+            Model::Asset => write!(f, "asset"),
+            Model::StreamData => write!(f, "stream data"),
+        }
+    }
+}
+
+/// How a decoded message differs from the known layout.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Deviation {
+    /// A field the model does not know, it was ignored.
+    UnknownField { name: String },
+    /// A field the model knows that was absent, it was set to its default.
+    MissingField { name: String },
+    /// A tuple with another number of elements than the model, extra elements were ignored.
+    Length { expected: usize, found: usize },
+}
+
+impl fmt::Display for Deviation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Deviation::UnknownField { name } => write!(f, "unknown field `{name}`"),
+            Deviation::MissingField { name } => write!(f, "missing field `{name}`"),
+            Deviation::Length { expected, found } => {
+                write!(f, "{found} elements instead of {expected}")
+            }
+        }
+    }
+}
+
+/// A deviation seen while decoding a model.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Incompatibility {
+    pub model: Model,
+    pub version: u32,
+    pub deviation: Deviation,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} v{}: {}", self.model, self.version, self.deviation)
+    }
+}
+
+fn seen() -> &'static Mutex<BTreeSet<Incompatibility>> {
+    static SEEN: OnceLock<Mutex<BTreeSet<Incompatibility>>> = OnceLock::new();
+    SEEN.get_or_init(Default::default)
+}
+
+/// Records a deviation of `model`, logging it the first time it is seen.
+pub fn report(model: Model, deviation: Deviation) {
+    let entry = Incompatibility {
+        model,
+        version: model.version(),
+        deviation,
+    };
+    let mut seen = seen().lock().expect("compatibility report lock poisoned");
+    if !seen.contains(&entry) {
+        warn!(
+            target: "Protocol",
+            "PocketOption protocol changed, {}; decoded with the compatibility shim", entry
+        );
+        seen.insert(entry);
+    }
+}
+
+/// Every deviation seen since the process started, sorted by model.
+pub fn compatibility_report() -> Vec<Incompatibility> {
+    seen()
+        .lock()
+        .expect("compatibility report lock poisoned")
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Wire names of every [`DealV1`] field.
+const DEAL_V1_FIELDS: &[&str] = &[
+    "id",
+    "openTime",
+    "closeTime",
+    "openTimestamp",
+    "closeTimestamp",
+    "refundTime",
+    "refundTimestamp",
+    "uid",
+    "requestId",
+    "amount",
+    "profit",
+    "percentProfit",
+    "percentLoss",
+    "openPrice",
+    "closePrice",
+    "command",
+    "asset",
+    "isDemo",
+    "copyTicket",
+    "openMs",
+    "closeMs",
+    "optionType",
+    "isRollover",
+    "isCopySignal",
+    "isAI",
+    "currency",
+    "amountUsd",
+    "amountUSD",
+];
+
+/// Fields the server always sent in v1 but a deal can be tracked without, reported when absent.
+/// The optional fields are absent on some deals anyway and are not reported.
+const DEAL_V1_DEFAULTED: &[&str] = &[
+    "openTime",
+    "closeTime",
+    "uid",
+    "profit",
+    "percentProfit",
+    "percentLoss",
+    "closePrice",
+    "isDemo",
+    "copyTicket",
+    "openMs",
+    "optionType",
+    "currency",
+];
+
+/// Deal layout of `updateOpenedDeals`, `updateClosedDeals`, `successopenOrder` and
+/// `successcloseOrder`. Only the fields identifying the deal and its bet are required.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DealV1 {
+    pub id: Uuid,
+    #[serde(default)]
+    pub open_time: String,
+    #[serde(default)]
+    pub close_time: String,
+    #[serde(with = "crate::pocketoption::utils::unix_timestamp")]
+    pub open_timestamp: DateTime<Utc>,
+    #[serde(with = "crate::pocketoption::utils::unix_timestamp")]
+    pub close_timestamp: DateTime<Utc>,
+    pub refund_time: Option<Value>,
+    pub refund_timestamp: Option<Value>,
+    #[serde(default)]
+    pub uid: u64,
+    #[serde(default)]
+    pub request_id: Option<RequestId>,
+    pub amount: Decimal,
+    #[serde(default)]
+    pub profit: Decimal,
+    #[serde(default)]
+    pub percent_profit: i32,
+    #[serde(default)]
+    pub percent_loss: i32,
+    pub open_price: Decimal,
+    #[serde(default)]
+    pub close_price: Decimal,
+    pub command: i32,
+    pub asset: String,
+    #[serde(default)]
+    pub is_demo: u32,
+    #[serde(default)]
+    pub copy_ticket: String,
+    #[serde(default)]
+    pub open_ms: i32,
+    pub close_ms: Option<i32>,
+    #[serde(default)]
+    pub option_type: i32,
+    pub is_rollover: Option<bool>,
+    pub is_copy_signal: Option<bool>,
+    #[serde(rename = "isAI")]
+    pub is_ai: Option<bool>,
+    #[serde(default)]
+    pub currency: String,
+    pub amount_usd: Option<Decimal>,
+    #[serde(rename = "amountUSD")]
+    pub amount_usd2: Option<Decimal>,
+}
+
+impl DealV1 {
+    /// Decodes a deal object, reporting the fields that differ from v1.
+    pub fn decode(fields: Map<String, Value>) -> serde_json::Result<Self> {
+        for name in fields.keys() {
+            if !DEAL_V1_FIELDS.contains(&name.as_str()) {
+                report(Model::Deal, Deviation::UnknownField { name: name.clone() });
+            }
+        }
+        for name in DEAL_V1_DEFAULTED {
+            if !fields.contains_key(*name) {
+                report(
+                    Model::Deal,
+                    Deviation::MissingField {
+                        name: name.to_string(),
+                    },
+                );
+            }
+        }
+        Self::deserialize(Value::Object(fields))
+    }
+}
+
+impl From<DealV1> for Deal {
+    fn from(deal: DealV1) -> Self {
+        Deal {
+            id: deal.id,
+            open_time: deal.open_time,
+            close_time: deal.close_time,
+            open_timestamp: deal.open_timestamp,
+            close_timestamp: deal.close_timestamp,
+            refund_time: deal.refund_time,
+            refund_timestamp: deal.refund_timestamp,
+            uid: deal.uid,
+            request_id: deal.request_id,
+            amount: deal.amount,
+            profit: deal.profit,
+            percent_profit: deal.percent_profit,
+            percent_loss: deal.percent_loss,
+            open_price: deal.open_price,
+            close_price: deal.close_price,
+            command: deal.command,
+            asset: deal.asset,
+            is_demo: deal.is_demo,
+            copy_ticket: deal.copy_ticket,
+            open_ms: deal.open_ms,
+            close_ms: deal.close_ms,
+            option_type: deal.option_type,
+            is_rollover: deal.is_rollover,
+            is_copy_signal: deal.is_copy_signal,
+            is_ai: deal.is_ai,
+            currency: deal.currency,
+            amount_usd: deal.amount_usd,
+            amount_usd2: deal.amount_usd2,
+            slippage: None,
+        }
+    }
+}
+
+/// Number of elements of a v1 asset tuple.
+const ASSET_V1_LEN: usize = 19;
+
+/// Elements up to the last one the client reads, shorter tuples cannot be decoded.
+const ASSET_V1_MIN_LEN: usize = 16;
+
+/// Asset layout of `updateAssets`, a tuple of which the client reads
+/// `[id, symbol, name, type, _, payout, _, _, _, is_otc, _, _, _, _, is_active, allowed_candles, ..]`.
+#[derive(Debug)]
+pub struct AssetV1 {
+    pub id: i32,
+    pub symbol: String,
+    pub name: String,
+    pub asset_type: AssetType,
+    pub payout: i32,
+    /// `1` for OTC assets.
+    pub is_otc: i32,
+    pub is_active: bool,
+    pub allowed_candles: Vec<CandleLength>,
+}
+
+fn element<T: DeserializeOwned>(tuple: &mut [Value], index: usize) -> serde_json::Result<T> {
+    serde_json::from_value(tuple[index].take())
+        .map_err(|e| serde_json::Error::custom(format!("asset element {index}: {e}")))
+}
+
+impl AssetV1 {
+    /// Decodes an asset tuple, reporting a length that differs from v1.
+    pub fn decode(mut tuple: Vec<Value>) -> serde_json::Result<Self> {
+        if tuple.len() < ASSET_V1_MIN_LEN {
+            return Err(serde_json::Error::custom(format!(
+                "asset tuple has {} elements, at least {ASSET_V1_MIN_LEN} are needed",
+                tuple.len()
+            )));
+        }
+        if tuple.len() != ASSET_V1_LEN {
+            report(
+                Model::Asset,
+                Deviation::Length {
+                    expected: ASSET_V1_LEN,
+                    found: tuple.len(),
+                },
+            );
+        }
+        Ok(AssetV1 {
+            id: element(&mut tuple, 0)?,
+            symbol: element(&mut tuple, 1)?,
+            name: element(&mut tuple, 2)?,
+            asset_type: element(&mut tuple, 3)?,
+            payout: element(&mut tuple, 5)?,
+            is_otc: element(&mut tuple, 9)?,
+            is_active: element(&mut tuple, 14)?,
+            allowed_candles: element(&mut tuple, 15)?,
+        })
+    }
+}
+
+impl From<AssetV1> for Asset {
+    fn from(asset: AssetV1) -> Self {
+        Asset {
+            id: asset.id,
+            symbol: asset.symbol,
+            name: asset.name,
+            asset_type: asset.asset_type,
+            payout: asset.payout,
+            is_otc: asset.is_otc == 1,
+            is_active: asset.is_active,
+            allowed_candles: asset.allowed_candles,
+        }
+    }
+}
+
+/// Number of elements of a v1 tick, `[symbol, timestamp, price]`.
+const STREAM_DATA_V1_LEN: usize = 3;
+
+/// Tick layout of `updateStream`, `[[symbol, timestamp, price]]`.
+#[derive(Debug)]
+pub struct StreamDataV1 {
+    pub symbol: String,
+    /// Unix timestamp in seconds or milliseconds, with fractions.
+    pub timestamp: f64,
+    pub price: f64,
+}
+
+impl StreamDataV1 {
+    /// Decodes a tick batch holding a single tick, reporting a tick length that differs from v1.
+    pub fn decode(batch: Vec<Vec<Value>>) -> serde_json::Result<Self> {
+        let [tick] = <[Vec<Value>; 1]>::try_from(batch)
+            .map_err(|_| serde_json::Error::custom("Invalid StreamData format"))?;
+        if tick.len() < STREAM_DATA_V1_LEN {
+            return Err(serde_json::Error::custom("Invalid StreamData format"));
+        }
+        if tick.len() != STREAM_DATA_V1_LEN {
+            report(
+                Model::StreamData,
+                Deviation::Length {
+                    expected: STREAM_DATA_V1_LEN,
+                    found: tick.len(),
+                },
+            );
+        }
+        Ok(StreamDataV1 {
+            symbol: tick[0].as_str().unwrap_or_default().to_string(),
+            timestamp: tick[1].as_f64().unwrap_or(0.0),
+            price: tick[2].as_f64().unwrap_or(0.0),
+        })
+    }
+}
+
+impl From<StreamDataV1> for StreamData {
+    fn from(tick: StreamDataV1) -> Self {
+        StreamData {
+            symbol: tick.symbol,
+            timestamp: normalize_timestamp(tick.timestamp),
+            price: Decimal::from_f64_retain(tick.price).unwrap_or_default(),
+        }
+    }
+}
+
+/// Deserializes `T` through the JSON value `V` and the model decoder `decode`.
+pub(crate) fn deserialize_with<'de, D, V, M, T>(
+    deserializer: D,
+    decode: fn(V) -> serde_json::Result<M>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+    T: From<M>,
+{
+    let value = V::deserialize(deserializer)?;
+    decode(value).map(T::from).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::pocketoption::types::Assets;
+
+    fn deal_json() -> Value {
+        json!({
+            "id": "b5b7e1a6-3f0c-4c0e-9a9b-1d2c3e4f5a6b",
+            "openTime": "2024-01-01 00:00:00",
+            "closeTime": "2024-01-01 00:01:00",
+            "openTimestamp": 1704067200,
+            "closeTimestamp": 1704067260,
+            "refundTime": null,
+            "refundTimestamp": null,
+            "uid": 42,
+            "amount": 10,
+            "profit": 9.2,
+            "percentProfit": 92,
+            "percentLoss": 100,
+            "openPrice": 1.1,
+            "closePrice": 1.2,
+            "command": 0,
+            "asset": "EURUSD_otc",
+            "isDemo": 1,
+            "copyTicket": "",
+            "openMs": 0,
+            "optionType": 100,
+            "currency": "USD"
+        })
+    }
+
+    #[test]
+    fn test_deal_tolerates_added_and_removed_fields() {
+        let mut deal = deal_json();
+        let fields = deal.as_object_mut().unwrap();
+        fields.insert("isOptimizedTest".into(), json!(true));
+        fields.remove("copyTicket");
+        fields.remove("percentLoss");
+
+        let deal: Deal = serde_json::from_value(deal).unwrap();
+        assert_eq!(deal.asset, "EURUSD_otc");
+        assert_eq!(deal.percent_loss, 0);
+        assert!(deal.copy_ticket.is_empty());
+
+        let report = compatibility_report();
+        for deviation in [
+            Deviation::UnknownField {
+                name: "isOptimizedTest".into(),
+            },
+            Deviation::MissingField {
+                name: "copyTicket".into(),
+            },
+            Deviation::MissingField {
+                name: "percentLoss".into(),
+            },
+        ] {
+            assert!(report.contains(&Incompatibility {
+                model: Model::Deal,
+                version: 1,
+                deviation,
+            }));
+        }
+    }
+
+    #[test]
+    fn test_deal_without_required_field_fails() {
+        let mut deal = deal_json();
+        deal.as_object_mut().unwrap().remove("openPrice");
+        assert!(serde_json::from_value::<Deal>(deal).is_err());
+    }
+
+    #[test]
+    fn test_deal_round_trip() {
+        let deal: Deal = serde_json::from_value(deal_json()).unwrap();
+        let again: Deal = serde_json::to_string(&deal)
+            .and_then(|json| serde_json::from_str(&json))
+            .unwrap();
+        assert_eq!(deal, again);
+    }
+
+    #[test]
+    fn test_asset_tuple_may_grow() {
+        let mut tuple = json!([
+            7, "AUDCAD_otc", "AUD/CAD OTC", "currency", 2, 85, 60, 30, 3, 1, 0, 0, [], 0, true,
+            [{"time": 60}], [], 0, 0
+        ]);
+        tuple.as_array_mut().unwrap().push(json!("new"));
+        let assets: Assets = serde_json::from_value(json!([tuple])).unwrap();
+        let asset = assets.get("AUDCAD_otc").unwrap();
+        assert_eq!(asset.payout, 85);
+        assert!(asset.is_otc && asset.is_active);
+        assert!(compatibility_report().contains(&Incompatibility {
+            model: Model::Asset,
+            version: 1,
+            deviation: Deviation::Length {
+                expected: 19,
+                found: 20
+            },
+        }));
+
+        let truncated = json!([[7, "AUDCAD_otc", "AUD/CAD OTC", "currency", 2, 85]]);
+        assert!(serde_json::from_value::<Assets>(truncated).is_err());
+    }
+
+    #[test]
+    fn test_stream_data_tolerates_extra_elements() {
+        let tick: StreamData =
+            serde_json::from_value(json!([["EURUSD_otc", 1700000000.5, 1.0842, 7]])).unwrap();
+        assert_eq!(tick.symbol, "EURUSD_otc");
+        assert_eq!(tick.timestamp, 1_700_000_000);
+        assert!(serde_json::from_value::<StreamData>(json!([["EURUSD_otc", 1.0]])).is_err());
+    }
+}
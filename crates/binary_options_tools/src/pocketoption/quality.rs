@@ -0,0 +1,176 @@
+use serde::Serialize;
+
+use crate::pocketoption::candle::Candle;
+
+/// Problem found in a candle series by [`CandleValidator`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataQualityIssue {
+    /// The candle doesn't start after the previous one.
+    NonMonotonic { previous: i64 },
+    /// High equals low, the price never moved during the candle.
+    ZeroRange,
+    /// More than one period passed since the previous candle, `missing` candles are absent.
+    Gap { previous: i64, missing: i64 },
+}
+
+/// Where the flagged candles came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataSource {
+    /// History loaded when subscribing or through `history`.
+    History,
+    /// Candles requested through `get_candles`.
+    Candles,
+    /// Candles completed by a live subscription.
+    Stream,
+}
+
+/// A suspicious candle, see
+/// [`PocketOption::data_quality_events`](crate::pocketoption::PocketOption::data_quality_events).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DataQualityEvent {
+    pub asset: String,
+    /// Period of the candles in seconds, `None` for tick and chunk based candles.
+    pub period: Option<u32>,
+    /// Start of the flagged candle.
+    pub timestamp: i64,
+    pub source: DataSource,
+    pub issue: DataQualityIssue,
+}
+
+/// Checks the candles of a series one by one against the previously accepted candle.
+///
+/// A candle that goes back in time is flagged and otherwise ignored, so a single stray
+/// candle doesn't make every following one look out of order.
+#[derive(Clone, Debug, Default)]
+pub struct CandleValidator {
+    period: Option<u32>,
+    last: Option<i64>,
+}
+
+impl CandleValidator {
+    /// Gaps are only detected when `period` is known.
+    pub fn new(period: Option<u32>) -> Self {
+        Self {
+            period: period.filter(|period| *period > 0),
+            last: None,
+        }
+    }
+
+    pub fn check(&mut self, candle: &Candle) -> Vec<DataQualityIssue> {
+        let mut issues = Vec::new();
+        if candle.high == candle.low {
+            issues.push(DataQualityIssue::ZeroRange);
+        }
+        if let Some(previous) = self.last {
+            if candle.timestamp <= previous {
+                issues.push(DataQualityIssue::NonMonotonic { previous });
+                return issues;
+            }
+            if let Some(period) = self.period.map(i64::from) {
+                let elapsed = candle.timestamp - previous;
+                if elapsed > period {
+                    issues.push(DataQualityIssue::Gap {
+                        previous,
+                        missing: (elapsed + period - 1) / period - 1,
+                    });
+                }
+            }
+        }
+        self.last = Some(candle.timestamp);
+        issues
+    }
+
+    /// Checks `candle` and wraps the issues found into events.
+    pub fn events(&mut self, candle: &Candle, source: DataSource) -> Vec<DataQualityEvent> {
+        let period = self.period;
+        self.check(candle)
+            .into_iter()
+            .map(|issue| DataQualityEvent {
+                asset: candle.symbol.clone(),
+                period,
+                timestamp: candle.timestamp,
+                source,
+                issue,
+            })
+            .collect()
+    }
+}
+
+/// Validates a whole series in its current order, useful to clean data before a backtest.
+pub fn validate_candles(
+    candles: &[Candle],
+    period: Option<u32>,
+    source: DataSource,
+) -> Vec<DataQualityEvent> {
+    let mut validator = CandleValidator::new(period);
+    candles
+        .iter()
+        .flat_map(|candle| validator.events(candle, source))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn candle(timestamp: i64, low: Decimal, high: Decimal) -> Candle {
+        Candle {
+            symbol: "EURUSD_otc".to_string(),
+            timestamp,
+            open: low,
+            high,
+            low,
+            close: high,
+            volume: None,
+            is_closed: true,
+            synthetic: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_candles_flags_anomalies() {
+        let candles = vec![
+            candle(60, dec!(1.0), dec!(1.1)),
+            candle(120, dec!(1.1), dec!(1.1)),
+            candle(300, dec!(1.0), dec!(1.2)),
+            candle(240, dec!(1.0), dec!(1.2)),
+            candle(360, dec!(1.0), dec!(1.2)),
+        ];
+        let issues: Vec<_> = validate_candles(&candles, Some(60), DataSource::History)
+            .into_iter()
+            .map(|event| (event.timestamp, event.issue))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (120, DataQualityIssue::ZeroRange),
+                (
+                    300,
+                    DataQualityIssue::Gap {
+                        previous: 120,
+                        missing: 2
+                    }
+                ),
+                (240, DataQualityIssue::NonMonotonic { previous: 300 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validator_without_period_skips_gaps() {
+        let mut validator = CandleValidator::new(None);
+        assert!(validator.check(&candle(0, dec!(1.0), dec!(1.1))).is_empty());
+        assert!(validator
+            .check(&candle(3600, dec!(1.0), dec!(1.1)))
+            .is_empty());
+        assert_eq!(
+            serde_json::to_value(DataQualityIssue::NonMonotonic { previous: 3600 }).unwrap(),
+            serde_json::json!({"kind": "non_monotonic", "previous": 3600})
+        );
+    }
+}
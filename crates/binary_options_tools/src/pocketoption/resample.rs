@@ -0,0 +1,242 @@
+//! Resampling of historical candles and ticks into longer candles.
+//!
+//! Buckets are aligned on multiples of the period since the Unix epoch, in UTC, so they never
+//! shift with daylight saving time. Each bucket is built by the same
+//! [`SubscriptionType::time_aligned`] aggregation as the live streams: its timestamp is the
+//! start of the bucket, its open the first price in it and its close the last one. A bucket is
+//! complete once data at or after its end is seen, so the first bucket is kept even when the
+//! data starts inside it, as it is live. The last bucket is kept when the source candles cover
+//! it to its end, otherwise [`PartialBucket`] decides. Backtests on resampled history and bots
+//! on live candles therefore see the same bars.
+
+use std::time::Duration;
+
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::pocketoption::candle::{BaseCandle, Candle, SubscriptionType};
+use crate::pocketoption::error::{PocketError, PocketResult};
+
+/// What to do with a last bucket the data ends inside of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartialBucket {
+    /// Left out, the live stream would only emit it once the next bucket starts.
+    #[default]
+    Drop,
+    /// Kept with `is_closed` unset, like the forming candle of
+    /// [`compile_candles_from_ticks`](crate::pocketoption::candle::compile_candles_from_ticks).
+    Keep,
+}
+
+/// Aggregates candles of `from_period` seconds into candles of `to_period` seconds, dropping a
+/// partial last bucket, see the [module documentation](self).
+pub fn resample(candles: &[Candle], from_period: u32, to_period: u32) -> PocketResult<Vec<Candle>> {
+    resample_with(candles, from_period, to_period, PartialBucket::Drop)
+}
+
+/// Same as [`resample`], with the handling of a partial last bucket.
+///
+/// Fails if `to_period` is not a multiple of `from_period`, does not divide a day, or the
+/// candles are of several assets.
+pub fn resample_with(
+    candles: &[Candle],
+    from_period: u32,
+    to_period: u32,
+    partial: PartialBucket,
+) -> PocketResult<Vec<Candle>> {
+    if from_period == 0 || !to_period.is_multiple_of(from_period) {
+        return Err(PocketError::InvalidPeriod(to_period));
+    }
+    let mut aligned = aligned(to_period)?;
+    let Some(first) = candles.first() else {
+        return Ok(Vec::new());
+    };
+    if let Some(other) = candles.iter().find(|c| c.symbol != first.symbol) {
+        return Err(PocketError::General(format!(
+            "Cannot resample candles of {} and {} together",
+            first.symbol, other.symbol
+        )));
+    }
+    let mut sorted: Vec<&Candle> = candles.iter().collect();
+    sorted.sort_by_key(|candle| candle.timestamp);
+
+    let mut resampled = Vec::new();
+    for candle in &sorted {
+        let base = BaseCandle::new(
+            candle.timestamp,
+            to_f64(candle, candle.open)?,
+            to_f64(candle, candle.high)?,
+            to_f64(candle, candle.low)?,
+            to_f64(candle, candle.close)?,
+            candle.volume.and_then(|volume| volume.to_f64()),
+        );
+        if let Some(bar) = aligned.update(&base)? {
+            resampled.push(bar_to_candle(bar, &first.symbol, true)?);
+        }
+    }
+    let last_end = sorted[sorted.len() - 1].timestamp + i64::from(from_period);
+    finish(aligned, last_end, partial, &first.symbol, &mut resampled)?;
+    Ok(resampled)
+}
+
+/// Builds candles of `period` seconds from `(timestamp, price)` ticks, such as the output of
+/// `ticks()`, like a live time aligned subscription of `symbol` receiving them.
+///
+/// The last bucket is never known to be complete, [`PartialBucket`] decides whether it is kept.
+pub fn resample_ticks(
+    ticks: &[(i64, f64)],
+    period: u32,
+    symbol: &str,
+    partial: PartialBucket,
+) -> PocketResult<Vec<Candle>> {
+    if period == 0 {
+        return Err(PocketError::InvalidPeriod(period));
+    }
+    let mut aligned = aligned(period)?;
+    let mut sorted = ticks.to_vec();
+    sorted.sort_by_key(|tick| tick.0);
+
+    let mut resampled = Vec::new();
+    for tick in sorted {
+        if let Some(bar) = aligned.update(&BaseCandle::from(tick))? {
+            resampled.push(bar_to_candle(bar, symbol, true)?);
+        }
+    }
+    finish(aligned, i64::MIN, partial, symbol, &mut resampled)?;
+    Ok(resampled)
+}
+
+fn aligned(period: u32) -> PocketResult<SubscriptionType> {
+    SubscriptionType::time_aligned(Duration::from_secs(u64::from(period)))
+}
+
+fn to_f64(candle: &Candle, value: rust_decimal::Decimal) -> PocketResult<f64> {
+    value.to_f64().ok_or_else(|| {
+        PocketError::General(format!(
+            "Failed to convert price {} of {} at {} to f64",
+            value, candle.symbol, candle.timestamp
+        ))
+    })
+}
+
+fn bar_to_candle(bar: BaseCandle, symbol: &str, closed: bool) -> PocketResult<Candle> {
+    let mut candle = Candle::try_from((bar, symbol.to_string()))
+        .map_err(|e| PocketError::General(format!("Failed to convert candle: {e}")))?;
+    candle.is_closed = closed;
+    Ok(candle)
+}
+
+/// Pushes the bucket still forming in `aligned`, closed if the data reaches its end at
+/// `data_end`, or forming if `partial` keeps it.
+fn finish(
+    aligned: SubscriptionType,
+    data_end: i64,
+    partial: PartialBucket,
+    symbol: &str,
+    resampled: &mut Vec<Candle>,
+) -> PocketResult<()> {
+    let SubscriptionType::TimeAligned {
+        duration,
+        mut candle,
+        next_boundary: Some(boundary),
+    } = aligned
+    else {
+        return Ok(());
+    };
+    let complete = data_end >= boundary;
+    if complete || partial == PartialBucket::Keep {
+        // Stamped with the start of the bucket, as when the next bucket closes it.
+        candle.timestamp = boundary - duration.as_secs() as i64;
+        resampled.push(bar_to_candle(candle, symbol, complete)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    const SYMBOL: &str = "EURUSD_otc";
+
+    /// One tick every 7 seconds from 12:01:05 UTC, ending inside the 12:15 bucket.
+    fn ticks() -> Vec<(i64, f64)> {
+        (0..120)
+            .map(|i| {
+                let price = 1.1 + ((i * 37) % 23) as f64 / 1000.0;
+                (43_265 + i * 7, price)
+            })
+            .collect()
+    }
+
+    fn ohlc(candles: &[Candle]) -> Vec<(i64, Decimal, Decimal, Decimal, Decimal)> {
+        candles
+            .iter()
+            .map(|c| (c.timestamp, c.open, c.high, c.low, c.close))
+            .collect()
+    }
+
+    #[test]
+    fn test_resampled_candles_match_the_live_aggregation() {
+        let ticks = ticks();
+        let forming = resample_ticks(&ticks, 60, SYMBOL, PartialBucket::Keep).unwrap();
+        let forming = forming.last().unwrap();
+        assert_eq!((forming.timestamp, forming.is_closed), (44_040, false));
+        let minutes = resample_ticks(&ticks, 60, SYMBOL, PartialBucket::Drop).unwrap();
+        let last = minutes.last().unwrap();
+        assert_eq!((last.timestamp, last.is_closed), (43_980, true));
+
+        // Live five minute candles from the same ticks.
+        let mut live = aligned(300).unwrap();
+        let expected: Vec<Candle> = ticks
+            .iter()
+            .filter_map(|tick| live.update(&BaseCandle::from(*tick)).unwrap())
+            .map(|bar| Candle::try_from((bar, SYMBOL.to_string())).unwrap())
+            .collect();
+        assert_eq!(expected.len(), 2);
+        // The first bucket starts at 12:00 although the ticks start at 12:01:05.
+        assert_eq!(expected[0].timestamp, 43_200);
+
+        let from_ticks = resample_ticks(&ticks, 300, SYMBOL, PartialBucket::Drop).unwrap();
+        assert_eq!(ohlc(&from_ticks), ohlc(&expected));
+        let from_minutes = resample(&minutes, 60, 300).unwrap();
+        assert_eq!(ohlc(&from_minutes), ohlc(&expected));
+        assert!(from_minutes.iter().all(|c| c.is_closed));
+
+        let kept = resample_with(&minutes, 60, 300, PartialBucket::Keep).unwrap();
+        assert_eq!(kept.len(), 3);
+        assert_eq!((kept[2].timestamp, kept[2].is_closed), (43_800, false));
+        assert_eq!(kept[2].close, last.close);
+    }
+
+    #[test]
+    fn test_last_bucket_kept_when_covered() {
+        let minutes: Vec<Candle> = (0..10)
+            .map(|i| Candle {
+                symbol: SYMBOL.into(),
+                timestamp: 600 + i * 60,
+                open: Decimal::from(i),
+                high: Decimal::from(i + 2),
+                low: Decimal::from(i),
+                close: Decimal::from(i + 1),
+                ..Candle::default()
+            })
+            .rev()
+            .collect();
+        let bars = resample(&minutes, 60, 300).unwrap();
+        assert_eq!(
+            ohlc(&bars)[1],
+            (900, 5.into(), 11.into(), 5.into(), 10.into())
+        );
+        assert!(bars[1].is_closed);
+
+        assert!(matches!(
+            resample(&minutes, 60, 90),
+            Err(PocketError::InvalidPeriod(90))
+        ));
+        assert!(resample(&minutes, 60, 420).is_err());
+        let mut mixed = minutes.clone();
+        mixed[0].symbol = "AUDUSD_otc".into();
+        assert!(resample(&mixed, 60, 300).is_err());
+        assert!(resample(&[], 60, 300).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::pocketoption::{
+    candle::{Candle, SubscriptionType},
+    pocket_client::PocketOption,
+};
+
+/// Settings of a [`ScannerSubscription`].
+#[derive(Clone, Debug)]
+pub struct ScannerConfig {
+    /// Assets subscribed at the same time, keep it within the server's subscription limit.
+    pub slots: usize,
+    /// How long each group of assets keeps the slots before the next group takes them.
+    pub dwell: Duration,
+    /// How the ticks of an asset are aggregated while it is subscribed.
+    pub sub_type: SubscriptionType,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            slots: 4,
+            dwell: Duration::from_secs(5),
+            sub_type: SubscriptionType::none(),
+        }
+    }
+}
+
+impl ScannerConfig {
+    pub fn with_slots(mut self, slots: usize) -> Self {
+        self.slots = slots;
+        self
+    }
+
+    pub fn with_dwell(mut self, dwell: Duration) -> Self {
+        self.dwell = dwell;
+        self
+    }
+
+    pub fn with_subscription_type(mut self, sub_type: SubscriptionType) -> Self {
+        self.sub_type = sub_type;
+        self
+    }
+}
+
+/// Last candle received for an asset during its window.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScanQuote {
+    pub asset: String,
+    pub candle: Candle,
+    pub received_at: DateTime<Utc>,
+    /// Pass over the asset list this quote comes from, starting at 1.
+    pub rotation: u64,
+}
+
+/// Cycles a few subscription slots through a long asset list.
+///
+/// The assets are subscribed `slots` at a time for `dwell` each, then unsubscribed to make room
+/// for the next group. When its window ends, the last candle of every asset is emitted, so one
+/// stream gives a regularly refreshed snapshot of more assets than the server lets a client
+/// subscribe to at once. Dropping the scanner stops it.
+pub struct ScannerSubscription {
+    receiver: mpsc::Receiver<ScanQuote>,
+    latest: Arc<RwLock<HashMap<String, ScanQuote>>>,
+    task: JoinHandle<()>,
+}
+
+impl ScannerSubscription {
+    pub(crate) fn start(client: PocketOption, assets: Vec<String>, config: ScannerConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(assets.len().max(1));
+        let latest = Arc::new(RwLock::new(HashMap::new()));
+        let task = tokio::spawn(rotate(client, assets, config, sender, latest.clone()));
+        Self {
+            receiver,
+            latest,
+            task,
+        }
+    }
+
+    /// Waits for the next quote, `None` once the scanner stopped.
+    pub async fn next(&mut self) -> Option<ScanQuote> {
+        self.receiver.recv().await
+    }
+
+    /// The last quote of every asset scanned so far.
+    pub async fn snapshot(&self) -> HashMap<String, ScanQuote> {
+        self.latest.read().await.clone()
+    }
+
+    /// Converts the scanner into a stream of quotes.
+    pub fn to_stream(self) -> impl futures_util::Stream<Item = ScanQuote> + Send + 'static {
+        futures_util::stream::unfold(self, |mut scanner| async move {
+            scanner.next().await.map(|quote| (quote, scanner))
+        })
+    }
+}
+
+impl Drop for ScannerSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn rotate(
+    client: PocketOption,
+    assets: Vec<String>,
+    config: ScannerConfig,
+    sender: mpsc::Sender<ScanQuote>,
+    latest: Arc<RwLock<HashMap<String, ScanQuote>>>,
+) {
+    if assets.is_empty() {
+        return;
+    }
+    let mut rotation = 0;
+    loop {
+        rotation += 1;
+        for group in assets.chunks(config.slots.max(1)) {
+            let deadline = Instant::now() + config.dwell;
+            let mut windows = JoinSet::new();
+            for asset in group {
+                windows.spawn(watch(
+                    client.clone(),
+                    asset.clone(),
+                    config.sub_type.clone(),
+                    deadline,
+                ));
+            }
+            while let Some(result) = windows.join_next().await {
+                let Ok(Some((asset, candle))) = result else {
+                    continue;
+                };
+                let quote = ScanQuote {
+                    asset: asset.clone(),
+                    candle,
+                    received_at: Utc::now(),
+                    rotation,
+                };
+                latest.write().await.insert(asset, quote.clone());
+                if sender.send(quote).await.is_err() {
+                    return;
+                }
+            }
+        }
+        debug!(target: "Scanner", "Finished rotation {} over {} assets", rotation, assets.len());
+    }
+}
+
+/// Keeps `asset` subscribed until `deadline` and returns its last candle.
+async fn watch(
+    client: PocketOption,
+    asset: String,
+    sub_type: SubscriptionType,
+    deadline: Instant,
+) -> Option<(String, Candle)> {
+    let mut stream = match client.subscribe(&asset, sub_type).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            warn!(target: "Scanner", "Skipping {} this rotation: {}", asset, e);
+            return None;
+        }
+    };
+    let mut last = None;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match stream.receive_with_timeout(remaining).await {
+            Ok(candle) => last = Some(candle),
+            Err(_) => break,
+        }
+    }
+    // Dropping the stream unsubscribes, freeing the slot for the next group.
+    last.map(|candle| (asset, candle))
+}
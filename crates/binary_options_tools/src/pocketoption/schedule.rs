@@ -0,0 +1,191 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc, Weekday};
+use serde::Serialize;
+
+use crate::pocketoption::types::{Asset, AssetType};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// A weekly period during which an asset can be traded, in UTC.
+///
+/// A window may wrap around the end of the week, e.g. from Sunday evening to Friday evening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TradingWindow {
+    pub open_day: Weekday,
+    pub open_time: NaiveTime,
+    pub close_day: Weekday,
+    pub close_time: NaiveTime,
+}
+
+impl TradingWindow {
+    pub fn new(
+        open_day: Weekday,
+        open_time: NaiveTime,
+        close_day: Weekday,
+        close_time: NaiveTime,
+    ) -> Self {
+        Self {
+            open_day,
+            open_time,
+            close_day,
+            close_time,
+        }
+    }
+
+    fn open_minute(&self) -> u32 {
+        minute_of_week(self.open_day, self.open_time)
+    }
+
+    fn close_minute(&self) -> u32 {
+        minute_of_week(self.close_day, self.close_time)
+    }
+
+    /// Returns `true` if `at` falls inside the window, the close time is excluded.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let (open, close) = (self.open_minute(), self.close_minute());
+        let now = minute_of_week(at.weekday(), at.time());
+        if open <= close {
+            (open..close).contains(&now)
+        } else {
+            now >= open || now < close
+        }
+    }
+}
+
+/// Trading hours of an asset, inferred from its type and OTC flag since the server does
+/// not publish them.
+///
+/// OTC pairs and cryptocurrencies trade around the clock. Currencies, commodities and
+/// indices follow the forex week and stocks the US regular session; those windows are
+/// widened to cover both the summer and winter offsets and ignore market holidays, so
+/// the `is_active` flag reported by the server stays the final word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssetSchedule {
+    pub symbol: String,
+    /// The asset trades at any time, `windows` is empty.
+    pub always_open: bool,
+    pub windows: Vec<TradingWindow>,
+}
+
+impl AssetSchedule {
+    pub fn for_asset(asset: &Asset) -> Self {
+        let windows = if asset.is_otc {
+            Vec::new()
+        } else {
+            match asset.asset_type {
+                AssetType::Cryptocurrency => Vec::new(),
+                AssetType::Currency | AssetType::Commodity | AssetType::Index => {
+                    vec![TradingWindow::new(
+                        Weekday::Sun,
+                        time(21, 0),
+                        Weekday::Fri,
+                        time(22, 0),
+                    )]
+                }
+                AssetType::Stock => [
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ]
+                .into_iter()
+                .map(|day| TradingWindow::new(day, time(13, 30), day, time(21, 0)))
+                .collect(),
+            }
+        };
+        Self {
+            symbol: asset.symbol.clone(),
+            always_open: windows.is_empty(),
+            windows,
+        }
+    }
+
+    /// Returns `true` if the schedule allows trading at `at`.
+    pub fn is_open_at(&self, at: DateTime<Utc>) -> bool {
+        self.always_open || self.windows.iter().any(|w| w.contains(at))
+    }
+
+    /// Returns `at` if the schedule is open then, otherwise the start of the next window.
+    pub fn next_open(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        if self.is_open_at(at) {
+            return at;
+        }
+        let now = minute_of_week(at.weekday(), at.time());
+        let wait = self
+            .windows
+            .iter()
+            .map(|w| (w.open_minute() + MINUTES_PER_WEEK - now) % MINUTES_PER_WEEK)
+            .min()
+            .unwrap_or_default();
+        let minute_start = at
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(at);
+        minute_start + Duration::minutes(wait as i64)
+    }
+}
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default()
+}
+
+fn minute_of_week(day: Weekday, time: NaiveTime) -> u32 {
+    day.num_days_from_monday() * MINUTES_PER_DAY + time.hour() * 60 + time.minute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::types::CandleLength;
+    use chrono::TimeZone;
+
+    fn asset(asset_type: AssetType, is_otc: bool) -> Asset {
+        Asset {
+            id: 1,
+            symbol: "TEST".to_string(),
+            name: "Test".to_string(),
+            asset_type,
+            payout: 80,
+            is_otc,
+            is_active: true,
+            allowed_candles: vec![CandleLength::new(60)],
+        }
+    }
+
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // 2025-06-02 is a Monday.
+        Utc.with_ymd_and_hms(2025, 6, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_otc_and_crypto_are_always_open() {
+        for schedule in [
+            AssetSchedule::for_asset(&asset(AssetType::Currency, true)),
+            AssetSchedule::for_asset(&asset(AssetType::Cryptocurrency, false)),
+        ] {
+            assert!(schedule.always_open);
+            assert!(schedule.is_open_at(utc(7, 12, 0)));
+        }
+    }
+
+    #[test]
+    fn test_forex_week_wraps_around_the_weekend() {
+        let schedule = AssetSchedule::for_asset(&asset(AssetType::Currency, false));
+        assert!(schedule.is_open_at(utc(4, 3, 0)));
+        assert!(schedule.is_open_at(utc(8, 21, 0)));
+        assert!(!schedule.is_open_at(utc(7, 12, 0)));
+        assert!(!schedule.is_open_at(utc(6, 22, 0)));
+        assert_eq!(schedule.next_open(utc(7, 12, 0)), utc(8, 21, 0));
+    }
+
+    #[test]
+    fn test_stock_sessions() {
+        let schedule = AssetSchedule::for_asset(&asset(AssetType::Stock, false));
+        assert_eq!(schedule.windows.len(), 5);
+        assert!(schedule.is_open_at(utc(3, 14, 0)));
+        assert!(!schedule.is_open_at(utc(3, 21, 0)));
+        assert_eq!(schedule.next_open(utc(3, 21, 0)), utc(4, 13, 30));
+        assert_eq!(schedule.next_open(utc(6, 22, 15)), utc(9, 13, 30));
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::pocketoption::{
+    error::{PocketError, PocketResult},
+    ssid::Ssid,
+    state::State,
+    types::{Asset, AssetType, Assets, CandleLength, Deal, ServerTime},
+};
+
+/// Version of the snapshot file format, snapshots of another version are not restored.
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+/// An asset as stored in a session snapshot.
+///
+/// [`Asset`] deserializes from the server's array format, so the snapshot keeps its own copy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedAsset {
+    pub id: i32,
+    pub name: String,
+    pub symbol: String,
+    pub is_otc: bool,
+    pub is_active: bool,
+    pub payout: i32,
+    pub allowed_candles: Vec<CandleLength>,
+    pub asset_type: AssetType,
+}
+
+impl From<&Asset> for SavedAsset {
+    fn from(asset: &Asset) -> Self {
+        Self {
+            id: asset.id,
+            name: asset.name.clone(),
+            symbol: asset.symbol.clone(),
+            is_otc: asset.is_otc,
+            is_active: asset.is_active,
+            payout: asset.payout,
+            allowed_candles: asset.allowed_candles.clone(),
+            asset_type: asset.asset_type,
+        }
+    }
+}
+
+impl From<SavedAsset> for Asset {
+    fn from(asset: SavedAsset) -> Self {
+        Self {
+            id: asset.id,
+            name: asset.name,
+            symbol: asset.symbol,
+            is_otc: asset.is_otc,
+            is_active: asset.is_active,
+            payout: asset.payout,
+            allowed_candles: asset.allowed_candles,
+            asset_type: asset.asset_type,
+        }
+    }
+}
+
+/// The state a client needs to be useful right after a restart, written on shutdown and read
+/// back by [`PocketOption::resume`](crate::pocketoption::PocketOption::resume).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub saved_at: DateTime<Utc>,
+    /// Account the session belongs to, a snapshot is only restored for the same account.
+    pub uid: u32,
+    pub demo: bool,
+    pub assets: Vec<SavedAsset>,
+    /// Server time minus local time, in seconds.
+    pub server_time_offset: i64,
+    /// Assets that had an active subscription. Subscriptions are not reopened on resume since
+    /// they need a consumer, resubscribe to these if needed.
+    pub subscriptions: Vec<String>,
+    /// Deals that were still open, restored so their results can be awaited after the restart.
+    pub opened_deals: Vec<Deal>,
+}
+
+impl SessionSnapshot {
+    pub(crate) async fn capture(state: &State) -> Self {
+        let assets = state
+            .assets
+            .read()
+            .await
+            .as_ref()
+            .map(|assets| assets.0.values().map(SavedAsset::from).collect())
+            .unwrap_or_default();
+        let mut subscriptions: Vec<String> = state
+            .active_subscriptions
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| entry.key().clone())
+            .collect();
+        subscriptions.sort();
+        let mut opened_deals: Vec<Deal> = state
+            .trade_state
+            .get_opened_deals()
+            .await
+            .into_values()
+            .collect();
+        opened_deals.sort_by_key(|deal| deal.open_timestamp);
+        Self {
+            version: SESSION_FORMAT_VERSION,
+            saved_at: state.clock.now(),
+            uid: state.ssid.uid(),
+            demo: state.ssid.demo(),
+            assets,
+            server_time_offset: state.server_time.read().await.offset.num_seconds(),
+            subscriptions,
+            opened_deals,
+        }
+    }
+
+    /// Reads a snapshot written by [`SessionSnapshot::save`].
+    pub async fn load(path: impl AsRef<Path>) -> PocketResult<Self> {
+        let path = path.as_ref();
+        let data = tokio::fs::read(path).await.map_err(|e| {
+            PocketError::General(format!("Failed to read session {}: {e}", path.display()))
+        })?;
+        let snapshot: Self = serde_json::from_slice(&data).map_err(|e| {
+            PocketError::General(format!("Invalid session file {}: {e}", path.display()))
+        })?;
+        if snapshot.version != SESSION_FORMAT_VERSION {
+            return Err(PocketError::General(format!(
+                "Session file {} has version {}, expected {}",
+                path.display(),
+                snapshot.version,
+                SESSION_FORMAT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot as JSON, replacing `path` only once the new file is complete.
+    pub async fn save(&self, path: impl AsRef<Path>) -> PocketResult<()> {
+        let path = path.as_ref();
+        let data = serde_json::to_vec_pretty(self)
+            .map_err(|e| PocketError::General(format!("Failed to encode session: {e}")))?;
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, data).await.map_err(|e| {
+            PocketError::General(format!("Failed to write session {}: {e}", tmp.display()))
+        })?;
+        tokio::fs::rename(&tmp, path).await.map_err(|e| {
+            PocketError::General(format!("Failed to write session {}: {e}", path.display()))
+        })
+    }
+
+    /// Whether the snapshot was taken with the same account as `ssid`.
+    pub fn matches(&self, ssid: &Ssid) -> bool {
+        self.uid == ssid.uid() && self.demo == ssid.demo()
+    }
+
+    /// Seeds a state that was not connected yet with the snapshot. Anything the server sends
+    /// after connecting replaces the restored values.
+    pub(crate) async fn restore(&self, state: &State) {
+        if !self.assets.is_empty() {
+            let assets = self
+                .assets
+                .iter()
+                .cloned()
+                .map(|asset| (asset.symbol.clone(), Asset::from(asset)))
+                .collect();
+            state.set_assets(Assets(assets)).await;
+        }
+        // Dated at save time, so the restored clock counts as stale until the server syncs it.
+        *state.server_time.write().await = ServerTime {
+            last_server_time: self.saved_at.timestamp() + self.server_time_offset,
+            last_updated: self.saved_at,
+            offset: Duration::seconds(self.server_time_offset),
+            ..ServerTime::default()
+        };
+        if !self.opened_deals.is_empty() {
+            state
+                .trade_state
+                .update_opened_deals(self.opened_deals.clone())
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::pocketoption::state::StateBuilder;
+
+    fn state(uid: u32) -> State {
+        let ssid = Ssid::parse(format!(
+            r#"42["auth",{{"session":"dummy_session_id","isDemo":1,"uid":{uid},"platform":2}}]"#
+        ))
+        .unwrap();
+        StateBuilder::default().ssid(ssid).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_round_trip() {
+        let source = state(42);
+        let asset = Asset {
+            id: 1,
+            name: "EUR/USD OTC".into(),
+            symbol: "EURUSD_otc".into(),
+            is_otc: true,
+            is_active: true,
+            payout: 92,
+            allowed_candles: vec![CandleLength::new(60)],
+            asset_type: AssetType::Currency,
+        };
+        source
+            .set_assets(Assets([(asset.symbol.clone(), asset)].into()))
+            .await;
+        source
+            .update_server_time(Utc::now().timestamp() + 120)
+            .await;
+        let deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 42, "amount": 1.0, "profit": 0.92,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 1.1,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        source.trade_state.add_opened_deal(deal.clone()).await;
+
+        let path = std::env::temp_dir().join(format!("session-{}.json", Uuid::new_v4()));
+        let snapshot = SessionSnapshot::capture(&source).await;
+        snapshot.save(&path).await.unwrap();
+        let loaded = SessionSnapshot::load(&path).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.assets, snapshot.assets);
+        assert_eq!(loaded.opened_deals.len(), 1);
+        assert!(loaded.matches(&source.ssid));
+        assert!(!loaded.matches(&state(7).ssid));
+
+        let target = state(42);
+        loaded.restore(&target).await;
+        assert_eq!(
+            target
+                .assets
+                .read()
+                .await
+                .as_ref()
+                .unwrap()
+                .get("EURUSD_otc")
+                .unwrap()
+                .payout,
+            92
+        );
+        assert_eq!(target.server_time.read().await.offset.num_seconds(), 120);
+        assert!(target.trade_state.contains_opened_deal(deal.id).await);
+    }
+}
@@ -137,6 +137,43 @@ impl fmt::Debug for Ssid {
 use regex::Regex;
 use std::sync::OnceLock;
 
+/// Cookie holding the PHP session of a logged in browser.
+const SESSION_COOKIE: &str = "ci_session";
+
+/// `platform` value sent by the web client.
+const WEB_PLATFORM: u32 = 2;
+
+/// Decodes the `%XX` escapes and `+` of a cookie value.
+fn percent_decode(value: &str) -> CoreResult<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let byte = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| {
+                        CoreError::SsidParsing(format!("Invalid escape in cookie at {i}"))
+                    })?;
+                decoded.push(byte);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|e| CoreError::SsidParsing(format!("Cookie is not valid UTF-8: {e}")))
+}
+
 static KEY_REGEX: OnceLock<Regex> = OnceLock::new();
 static VAL_REGEX: OnceLock<Regex> = OnceLock::new();
 
@@ -307,6 +344,46 @@ impl Ssid {
         }
     }
 
+    /// Builds the ssid from the fields of the `auth` message, e.g. copied from the WebSocket
+    /// frames in the browser devtools, instead of hand-crafting the `42["auth",{...}]` string.
+    ///
+    /// For real accounts `session` is the PHP serialized session (the decoded `ci_session`
+    /// cookie), for demo accounts it is the session token.
+    pub fn from_parts(
+        session: impl Into<String>,
+        uid: u32,
+        platform: u32,
+        is_demo: bool,
+        is_fast_history: bool,
+        is_optimized: bool,
+    ) -> CoreResult<Self> {
+        let auth = serde_json::json!({
+            "session": session.into(),
+            "isDemo": is_demo as u32,
+            "uid": uid,
+            "platform": platform,
+            "isFastHistory": is_fast_history,
+            "isOptimized": is_optimized,
+        });
+        Self::parse(format!("42[\"auth\",{auth}]"))
+    }
+
+    /// Builds the ssid from the `Cookie` header the browser sends to PocketOption, with or
+    /// without the `Cookie:` name. The session is read from the `ci_session` cookie, the account
+    /// id is not part of the cookies and is shown in the profile page.
+    pub fn from_cookie_header(header: &str, uid: u32, is_demo: bool) -> CoreResult<Self> {
+        let header = header.trim();
+        let cookies = header.strip_prefix("Cookie:").unwrap_or(header).split(';');
+        let session = cookies
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == SESSION_COOKIE)
+            .map(|(_, value)| percent_decode(value.trim_matches('"')))
+            .ok_or_else(|| {
+                CoreError::SsidParsing(format!("No `{SESSION_COOKIE}` cookie in the header"))
+            })??;
+        Self::from_parts(session, uid, WEB_PLATFORM, is_demo, true, true)
+    }
+
     pub async fn server(&self) -> CoreResult<String> {
         match self {
             Self::Demo(_) => Ok(Regions::DEMO.0.to_string()),
@@ -356,6 +433,14 @@ impl Ssid {
         }
     }
 
+    /// Returns the id of the account the session belongs to.
+    pub fn uid(&self) -> u32 {
+        match self {
+            Self::Demo(demo) => demo.uid,
+            Self::Real(real) => real.uid,
+        }
+    }
+
     /// Get the current_url from the SSID if available.
     /// For Demo accounts, this is stored directly.
     /// For Real accounts, this may be in the extra field.
@@ -597,6 +682,36 @@ mod tests {
         assert!(err.to_string().contains("double-encoding"));
     }
 
+    #[test]
+    fn test_ssid_from_parts() -> Result<(), Box<dyn Error>> {
+        let demo = Ssid::from_parts("dummy_session_id", 87654321, 2, true, true, true)?;
+        assert!(demo.demo());
+        assert_eq!(demo.uid(), 87654321);
+        assert_eq!(demo.session_id(), "dummy_session_id");
+        let reparsed = Ssid::parse(demo.to_string())?;
+        assert_eq!(format!("{demo:?}"), format!("{reparsed:?}"));
+
+        let session = "a:4:{s:10:\"session_id\";s:32:\"00000000000000000000000000000000\";s:10:\"ip_address\";s:7:\"0.0.0.0\";s:10:\"user_agent\";s:10:\"Mozilla/5.\";s:13:\"last_activity\";i:1732926685;}00000000000000000000000000000000";
+        let real = Ssid::from_parts(session, 12345678, 2, false, true, false)?;
+        assert!(!real.demo());
+        assert_eq!(real.ip_address(), Some("0.0.0.0"));
+        assert!(real.to_string().contains("\"isOptimized\":false"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ssid_from_cookie_header() -> Result<(), Box<dyn Error>> {
+        let header = "Cookie: lang=en; ci_session=a%3A4%3A%7Bs%3A10%3A%22session_id%22%3Bs%3A32%3A%2200000000000000000000000000000000%22%3Bs%3A10%3A%22ip_address%22%3Bs%3A7%3A%220.0.0.0%22%3Bs%3A10%3A%22user_agent%22%3Bs%3A10%3A%22Mozilla%2F5.%22%3Bs%3A13%3A%22last_activity%22%3Bi%3A1732926685%3B%7D00000000000000000000000000000000; _ga=GA1.1";
+        let ssid = Ssid::from_cookie_header(header, 12345678, false)?;
+        assert_eq!(ssid.uid(), 12345678);
+        assert_eq!(ssid.ip_address(), Some("0.0.0.0"));
+        assert_eq!(ssid.user_agent(), "Mozilla/5.");
+
+        let missing = Ssid::from_cookie_header("lang=en; _ga=GA1.1", 1, false);
+        assert!(missing.unwrap_err().to_string().contains("ci_session"));
+        Ok(())
+    }
+
     #[test]
     fn test_ssid_rejects_single_quoted() {
         let malicious = r#"'some_string_ssid'"#;
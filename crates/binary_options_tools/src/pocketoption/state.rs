@@ -1,9 +1,13 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use rust_decimal::Decimal;
 use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock as SyncRwLock},
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock as SyncRwLock,
+    },
     time::Instant,
 };
 use tokio::sync::RwLock;
@@ -14,14 +18,23 @@ use binary_options_tools_core::{
     traits::AppState,
 };
 
-use crate::pocketoption::types::ServerTimeState;
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{InitProfile, KeepAliveConfig, KillSwitchConfig, TradeGuardConfig};
 use crate::pocketoption::types::{
-    Action, Assets, Deal, OpenOrder, Outgoing, PendingOrder, SubscriptionEvent,
+    AccountBalance, AccountType, Action, AmountLimits, AssetChange, Assets, Condition, Deal,
+    OpenOrder, Outgoing, ParseFailure, PendingOrder, ServerTime, ServerTimeQuality,
+    ServerTimeState, SubscriptionEvent,
 };
 use crate::pocketoption::{
+    blacklist::{BlacklistConfig, UrlBlacklist},
     candle::SubscriptionType,
+    correlation::CorrelationStats,
+    dns::DnsConfig,
     error::{PocketError, PocketResult},
+    kill_switch::KillSwitch,
+    quality::DataQualityEvent,
     ssid::Ssid,
+    stats::{PingStats, SeenDeals, SlippageStats, TradeLatency, TradeStats, TradeTimings},
 };
 use crate::validator::Validator;
 
@@ -50,18 +63,44 @@ pub struct State {
     pub balance: RwLock<Option<Decimal>>,
     /// Notification for when balance is updated
     pub balance_updated: Arc<tokio::sync::Notify>,
+    /// Account currency code (e.g. "USD"), if reported by the server.
+    pub currency: RwLock<Option<String>>,
+    /// Trade amount limits keyed by currency code, see [`AmountLimits::for_currency`].
+    pub amount_limit_overrides: HashMap<String, AmountLimits>,
+    /// Last balance reported for each account type, kept across reconnects.
+    pub balances: RwLock<HashMap<AccountType, AccountBalance>>,
     /// Server time synchronization state
     pub server_time: ServerTimeState,
+    /// Notification for when server time is updated
+    pub server_time_updated: Arc<tokio::sync::Notify>,
     /// Assets information
     pub assets: RwLock<Option<Assets>>,
     /// Notification for when assets are updated
     pub assets_updated: Arc<tokio::sync::Notify>,
+    /// Broadcasts payout and availability changes between consecutive asset lists
+    pub asset_changes: tokio::sync::broadcast::Sender<AssetChange>,
+    /// Broadcasts the frames modules failed to parse
+    pub parse_failures: tokio::sync::broadcast::Sender<ParseFailure>,
+    /// Broadcasts the suspicious candles found when receiving or compiling candles
+    pub data_quality: tokio::sync::broadcast::Sender<DataQualityEvent>,
     /// Holds the state for all trading-related data.
     pub trade_state: Arc<TradeState>,
     /// Holds the current validators for the raw module keyed by ID
     pub raw_validators: SyncRwLock<HashMap<Uuid, Arc<Validator>>>,
-    /// Active subscriptions mapped by subscription symbol
-    pub active_subscriptions: RwLock<HashMap<String, Vec<SubscriptionEntry>>>,
+    /// Raw handlers that receive Socket.IO binary packets reassembled into a single frame
+    pub raw_reassembling: SyncRwLock<HashSet<Uuid>>,
+    /// Active subscriptions mapped by subscription symbol, read on every tick.
+    ///
+    /// Sharded so readers of different assets don't wait for each other, entries must not be
+    /// held across an `.await`.
+    pub active_subscriptions: DashMap<String, Vec<SubscriptionEntry>>,
+    /// Notification for when a subscription is added
+    pub subscriptions_updated: Arc<tokio::sync::Notify>,
+    /// Timestamp and price of the last price update received per asset, written on every
+    /// tick and sharded like `active_subscriptions`
+    pub last_ticks: DashMap<String, (i64, Decimal)>,
+    /// Notification for when a price update is received
+    pub ticks_updated: Arc<tokio::sync::Notify>,
     /// Active history requests
     pub histories: RwLock<Vec<(String, u32, Uuid)>>,
     /// Sinks for raw module
@@ -71,12 +110,37 @@ pub struct State {
     /// List of fallback WebSocket URLs
     pub urls: Vec<String>,
     pub proxy: Option<String>,
+    /// Connect through the system proxy when `proxy` is not set.
+    pub system_proxy: bool,
+    /// Resolver of the server hosts for direct connections.
+    pub dns: DnsConfig,
+    /// Failures of the server URLs, the ones failing repeatedly are tried last.
+    pub url_blacklist: UrlBlacklist,
     pub user_agent: Option<String>,
     pub origin: Option<String>,
     pub sec_websocket_extensions: Option<String>,
     pub tls_cipher_suites: Option<Vec<String>>,
     pub tls_alpn: Option<Vec<String>>,
     pub raw_subscribers: RwLock<Vec<AsyncSender<Arc<Message>>>>,
+    /// Set once a graceful shutdown started, new trades and pending orders are rejected.
+    pub draining: AtomicBool,
+    /// Set when the server rejected the session during authentication.
+    pub auth_rejected: AtomicBool,
+    /// Request/response correlation counters shared by the trades and deals modules.
+    pub correlation_stats: Arc<CorrelationStats>,
+    /// Source of the current time, a `SimClock` in tests and backtests.
+    pub clock: SharedClock,
+    /// Keep-alive settings, replaced by the matching entry of `keep_alive_overrides`.
+    pub keep_alive: KeepAliveConfig,
+    pub keep_alive_overrides: HashMap<String, KeepAliveConfig>,
+    /// Requests sent after authenticating.
+    pub init_profile: InitProfile,
+    /// Per-asset limits enforced by the trades module.
+    pub trade_guard: TradeGuardConfig,
+    /// URL of the current connection, `None` with a custom connector.
+    pub connected_url: SyncRwLock<Option<String>>,
+    /// Round trip times of the keep-alive pings.
+    pub ping_stats: SyncRwLock<PingStats>,
 }
 /// Builder pattern for creating State instances
 ///
@@ -87,13 +151,24 @@ pub struct StateBuilder {
     ssid: Option<Ssid>,
     default_connection_url: Option<String>,
     default_symbol: Option<String>,
+    amount_limits: HashMap<String, AmountLimits>,
     urls: Vec<String>,
     proxy: Option<String>,
+    system_proxy: bool,
+    dns: DnsConfig,
+    url_blacklist: BlacklistConfig,
     user_agent: Option<String>,
     origin: Option<String>,
     sec_websocket_extensions: Option<String>,
     tls_cipher_suites: Option<Vec<String>>,
     tls_alpn: Option<Vec<String>>,
+    clock: Option<SharedClock>,
+    keep_alive: KeepAliveConfig,
+    keep_alive_overrides: HashMap<String, KeepAliveConfig>,
+    server_time_drift_threshold: Option<std::time::Duration>,
+    init_profile: InitProfile,
+    trade_guard: TradeGuardConfig,
+    kill_switch: KillSwitchConfig,
 }
 
 impl StateBuilder {
@@ -124,6 +199,12 @@ impl StateBuilder {
         self
     }
 
+    /// Set the trade amount limits of currencies the client has no limits for
+    pub fn amount_limits(mut self, limits: HashMap<String, AmountLimits>) -> Self {
+        self.amount_limits = limits;
+        self
+    }
+
     /// Set the fallback WebSocket URLs
     pub fn urls(mut self, urls: Vec<String>) -> Self {
         self.urls = urls;
@@ -135,6 +216,24 @@ impl StateBuilder {
         self
     }
 
+    /// Detect the system proxy when no proxy is set
+    pub fn system_proxy(mut self, enabled: bool) -> Self {
+        self.system_proxy = enabled;
+        self
+    }
+
+    /// Set the host overrides and DoH endpoint used to resolve the servers
+    pub fn dns(mut self, dns: DnsConfig) -> Self {
+        self.dns = dns;
+        self
+    }
+
+    /// Set when server URLs that keep failing are skipped
+    pub fn url_blacklist(mut self, config: BlacklistConfig) -> Self {
+        self.url_blacklist = config;
+        self
+    }
+
     pub fn user_agent(mut self, user_agent: Option<String>) -> Self {
         self.user_agent = user_agent;
         self
@@ -159,6 +258,53 @@ impl StateBuilder {
         self.tls_alpn = alpn;
         self
     }
+
+    /// Set the clock, the system clock by default
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+    /// Set the keep-alive settings and their per-server overrides
+    pub fn keep_alive(
+        mut self,
+        keep_alive: KeepAliveConfig,
+        overrides: HashMap<String, KeepAliveConfig>,
+    ) -> Self {
+        self.keep_alive = keep_alive;
+        self.keep_alive_overrides = overrides;
+        self
+    }
+    /// Set the offset change that makes the server time re-sync
+    pub fn server_time_drift_threshold(mut self, threshold: std::time::Duration) -> Self {
+        self.server_time_drift_threshold = Some(threshold);
+        self
+    }
+    /// Set the requests sent after authenticating
+    pub fn init_profile(mut self, profile: InitProfile) -> Self {
+        self.init_profile = profile;
+        self
+    }
+    /// Set the per-asset limits on open deals and time between orders
+    pub fn trade_guard(mut self, guard: TradeGuardConfig) -> Self {
+        self.trade_guard = guard;
+        self
+    }
+    /// Set the daily profit target and drawdown limit
+    pub fn kill_switch(mut self, config: KillSwitchConfig) -> Self {
+        self.kill_switch = config;
+        self
+    }
+    fn server_time(&self) -> ServerTime {
+        let mut server_time = ServerTime::default();
+        if let Some(threshold) = self
+            .server_time_drift_threshold
+            .and_then(|t| chrono::Duration::from_std(t).ok())
+        {
+            server_time.drift_threshold = threshold;
+        }
+        server_time
+    }
+
     /// Build the final State instance
     pub fn build(self) -> PocketResult<State> {
         self.build_with_trade_state(Arc::new(TradeState::default()))
@@ -166,6 +312,8 @@ impl StateBuilder {
 
     /// Build the final State instance with a custom TradeState
     pub fn build_with_trade_state(self, trade_state: Arc<TradeState>) -> PocketResult<State> {
+        trade_state.kill_switch.configure(self.kill_switch.clone());
+        let server_time = self.server_time();
         Ok(State {
             ssid: self
                 .ssid
@@ -176,23 +324,47 @@ impl StateBuilder {
                 .unwrap_or_else(|| "EURUSD_otc".to_string()),
             balance: RwLock::new(None),
             balance_updated: Arc::new(tokio::sync::Notify::new()),
-            server_time: ServerTimeState::default(),
+            currency: RwLock::new(None),
+            amount_limit_overrides: self.amount_limits,
+            balances: RwLock::new(HashMap::new()),
+            server_time: ServerTimeState::new(server_time),
+            server_time_updated: Arc::new(tokio::sync::Notify::new()),
             assets: RwLock::new(None),
             assets_updated: Arc::new(tokio::sync::Notify::new()),
+            asset_changes: tokio::sync::broadcast::channel(ASSET_CHANGES_CAPACITY).0,
+            parse_failures: tokio::sync::broadcast::channel(PARSE_FAILURES_CAPACITY).0,
+            data_quality: tokio::sync::broadcast::channel(DATA_QUALITY_CAPACITY).0,
             trade_state,
             raw_validators: SyncRwLock::new(HashMap::new()),
-            active_subscriptions: RwLock::new(HashMap::new()),
+            raw_reassembling: SyncRwLock::new(HashSet::new()),
+            active_subscriptions: DashMap::new(),
+            subscriptions_updated: Arc::new(tokio::sync::Notify::new()),
+            last_ticks: DashMap::new(),
+            ticks_updated: Arc::new(tokio::sync::Notify::new()),
             histories: RwLock::new(Vec::new()),
             raw_sinks: RwLock::new(HashMap::new()),
             raw_keep_alive: Arc::new(RwLock::new(HashMap::new())),
             urls: self.urls,
             proxy: self.proxy,
+            system_proxy: self.system_proxy,
+            dns: self.dns,
+            url_blacklist: UrlBlacklist::new(self.url_blacklist),
             user_agent: self.user_agent,
             origin: self.origin,
             sec_websocket_extensions: self.sec_websocket_extensions,
             tls_cipher_suites: self.tls_cipher_suites,
             tls_alpn: self.tls_alpn,
             raw_subscribers: RwLock::new(Vec::new()),
+            draining: AtomicBool::new(false),
+            auth_rejected: AtomicBool::new(false),
+            correlation_stats: Arc::new(CorrelationStats::default()),
+            clock: self.clock.unwrap_or_else(system_clock),
+            keep_alive: self.keep_alive,
+            keep_alive_overrides: self.keep_alive_overrides,
+            init_profile: self.init_profile,
+            trade_guard: self.trade_guard,
+            connected_url: SyncRwLock::new(None),
+            ping_stats: SyncRwLock::new(PingStats::default()),
         })
     }
 }
@@ -211,7 +383,8 @@ impl AppState for State {
         self.trade_state.pending_deals.write().await.clear();
 
         // Mark subscriptions as requiring re-subscription
-        self.active_subscriptions.write().await.clear();
+        self.active_subscriptions.clear();
+        self.last_ticks.clear();
 
         // Clear raw validators
         self.clear_raw_validators();
@@ -236,6 +409,41 @@ impl State {
         self.balance_updated.notify_waiters();
     }
 
+    /// Records a balance reported for `account`.
+    ///
+    /// The current balance is only updated when `account` is the account type of the session.
+    pub async fn set_account_balance(
+        &self,
+        account: AccountType,
+        amount: Decimal,
+        currency: Option<String>,
+    ) {
+        self.balances.write().await.insert(
+            account,
+            AccountBalance {
+                amount,
+                currency: currency.clone(),
+                updated_at: Utc::now(),
+            },
+        );
+        if account == self.account_type() {
+            if let Some(currency) = currency {
+                self.set_currency(currency).await;
+            }
+            self.set_balance(amount).await;
+        }
+    }
+
+    /// Last balance reported for `account`, if any.
+    pub async fn account_balance(&self, account: AccountType) -> Option<AccountBalance> {
+        self.balances.read().await.get(&account).cloned()
+    }
+
+    /// Last balance reported for each account type.
+    pub async fn balances(&self) -> HashMap<AccountType, AccountBalance> {
+        self.balances.read().await.clone()
+    }
+
     /// Get the current balance
     ///
     /// # Returns
@@ -245,6 +453,48 @@ impl State {
         *state
     }
 
+    /// Sets the account currency reported by the server.
+    pub async fn set_currency(&self, currency: impl Into<String>) {
+        *self.currency.write().await = Some(currency.into());
+    }
+
+    /// Get the account currency, if known
+    pub async fn get_currency(&self) -> Option<String> {
+        self.currency.read().await.clone()
+    }
+
+    /// Marks the client as draining so no new trades or pending orders are accepted.
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` once a graceful shutdown has started.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Records that the server rejected the session.
+    pub fn set_auth_rejected(&self) {
+        self.auth_rejected.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the server rejected the session.
+    pub fn is_auth_rejected(&self) -> bool {
+        self.auth_rejected.load(Ordering::SeqCst)
+    }
+
+    /// Trade amount limits for the current account currency.
+    ///
+    /// Falls back to the USD limits while the currency is still unknown.
+    pub async fn amount_limits(&self) -> AmountLimits {
+        self.currency
+            .read()
+            .await
+            .as_deref()
+            .map(|currency| AmountLimits::for_currency(currency, &self.amount_limit_overrides))
+            .unwrap_or_default()
+    }
+
     /// Check if the current account is a demo account
     ///
     /// # Returns
@@ -253,12 +503,24 @@ impl State {
         self.ssid.demo()
     }
 
+    /// Account type of the session, demo or real.
+    pub fn account_type(&self) -> AccountType {
+        if self.is_demo() {
+            AccountType::Demo
+        } else {
+            AccountType::Real
+        }
+    }
+
     /// Get current server time
     ///
     /// # Returns
     /// Current estimated server time as Unix timestamp
     pub async fn get_server_time(&self) -> i64 {
-        self.server_time.read().await.get_server_time()
+        self.server_time
+            .read()
+            .await
+            .get_server_time_at(self.clock.now())
     }
 
     /// Update server time with new timestamp
@@ -266,7 +528,21 @@ impl State {
     /// # Arguments
     /// * `timestamp` - New server timestamp to synchronize with
     pub async fn update_server_time(&self, timestamp: i64) {
-        self.server_time.write().await.update(timestamp);
+        self.server_time
+            .write()
+            .await
+            .update_at(timestamp, self.clock.now());
+        self.server_time_updated.notify_waiters();
+    }
+
+    /// Server time minus local time, smoothed over the latest server timestamps
+    pub async fn server_time_offset(&self) -> chrono::Duration {
+        self.server_time.read().await.offset
+    }
+
+    /// Offset, staleness and jitter of the server time
+    pub async fn server_time_quality(&self) -> ServerTimeQuality {
+        self.server_time.read().await.quality_at(self.clock.now())
     }
 
     /// Check if server time data is stale
@@ -274,7 +550,7 @@ impl State {
     /// # Returns
     /// True if server time hasn't been updated recently
     pub async fn is_server_time_stale(&self) -> bool {
-        self.server_time.read().await.is_stale()
+        self.server_time.read().await.is_stale_at(self.clock.now())
     }
 
     /// Get server time as `DateTime<Utc>`
@@ -283,7 +559,20 @@ impl State {
     /// Current server time as `DateTime<Utc>`
     pub async fn get_server_datetime(&self) -> DateTime<Utc> {
         let timestamp = self.get_server_time().await;
-        DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now)
+        DateTime::from_timestamp(timestamp, 0).unwrap_or_else(|| self.clock.now())
+    }
+
+    /// Time left before a deal expires according to server time, zero once it closed and
+    /// `None` if the deal is unknown.
+    pub async fn time_to_expiry(&self, deal_id: Uuid) -> Option<std::time::Duration> {
+        if let Some(deal) = self.trade_state.get_opened_deal(deal_id).await {
+            let left = deal.close_timestamp - self.get_server_datetime().await;
+            return Some(left.to_std().unwrap_or_default());
+        }
+        self.trade_state
+            .get_closed_deal(deal_id)
+            .await
+            .map(|_| std::time::Duration::ZERO)
     }
 
     /// Convert local time to server time
@@ -317,12 +606,147 @@ impl State {
     /// * `assets` - New assets information
     /// # Returns
     /// Result indicating success or failure
+    /// Stores a new asset list and broadcasts what changed since the previous one,
+    /// the first list received only wakes up the waiters.
     pub async fn set_assets(&self, assets: Assets) {
         let mut state = self.assets.write().await;
+        if let Some(previous) = state.as_ref() {
+            for change in previous.diff(&assets) {
+                let _ = self.asset_changes.send(change);
+            }
+        }
         *state = Some(assets);
         self.assets_updated.notify_waiters();
     }
 
+    /// Subscribes to the changes between consecutive asset lists.
+    pub fn asset_changes(&self) -> tokio::sync::broadcast::Receiver<AssetChange> {
+        self.asset_changes.subscribe()
+    }
+
+    /// Logs a frame `module` could not parse and forwards it to the
+    /// [`State::parse_failures`] receivers instead of dropping it silently.
+    pub fn quarantine(&self, module: &str, msg: &Message, error: impl std::fmt::Display) {
+        let failure = ParseFailure::new(module, msg, error, self.clock.now());
+        tracing::warn!(target: "PocketState", "{} failed to parse a frame: {}", module, failure.error);
+        let _ = self.parse_failures.send(failure);
+    }
+
+    /// Subscribes to the frames modules failed to parse.
+    pub fn parse_failures(&self) -> tokio::sync::broadcast::Receiver<ParseFailure> {
+        self.parse_failures.subscribe()
+    }
+
+    /// Forwards the issues found in a candle series to the [`State::data_quality`] receivers.
+    pub fn report_data_quality(&self, events: Vec<DataQualityEvent>) {
+        for event in events {
+            tracing::debug!(target: "PocketState", "Suspicious candle for {} at {}: {:?}", event.asset, event.timestamp, event.issue);
+            let _ = self.data_quality.send(event);
+        }
+    }
+
+    /// Subscribes to the suspicious candles.
+    pub fn data_quality(&self) -> tokio::sync::broadcast::Receiver<DataQualityEvent> {
+        self.data_quality.subscribe()
+    }
+
+    /// Remembers the URL of the connection, selecting its keep-alive settings.
+    pub fn set_connected_url(&self, url: impl Into<String>) {
+        *self
+            .connected_url
+            .write()
+            .expect("connected url lock poisoned") = Some(url.into());
+    }
+
+    /// Keep-alive settings for the server the client is connected to.
+    pub fn keep_alive_config(&self) -> KeepAliveConfig {
+        let url = self
+            .connected_url
+            .read()
+            .expect("connected url lock poisoned")
+            .clone();
+        url.and_then(|url| KeepAliveConfig::override_for(&self.keep_alive_overrides, &url))
+            .unwrap_or(&self.keep_alive)
+            .clone()
+    }
+
+    /// Checks whether `msg` answers the pending keep-alive ping and records its round trip time.
+    pub fn record_pong(&self, msg: &Message) {
+        let Message::Text(text) = msg else { return };
+        let pending = self
+            .ping_stats
+            .read()
+            .expect("ping stats lock poisoned")
+            .is_pending();
+        if pending {
+            self.ping_stats
+                .write()
+                .expect("ping stats lock poisoned")
+                .received(text, Instant::now());
+        }
+    }
+
+    /// Records a price update for `asset`.
+    pub async fn record_tick(&self, asset: &str, timestamp: i64, price: Decimal) {
+        // Only the first tick of an asset allocates its key
+        match self.last_ticks.get_mut(asset) {
+            Some(mut tick) => *tick = (timestamp, price),
+            None => {
+                self.last_ticks
+                    .insert(asset.to_string(), (timestamp, price));
+            }
+        }
+        self.ticks_updated.notify_waiters();
+    }
+
+    /// Price of the last update received for `asset`, only known for subscribed assets.
+    pub async fn last_price(&self, asset: &str) -> Option<Decimal> {
+        self.last_ticks.get(asset).map(|tick| tick.1)
+    }
+
+    /// Checks whether `condition` currently holds.
+    pub async fn is_satisfied(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::AssetsLoaded => self.assets.read().await.is_some(),
+            Condition::BalanceKnown => self.balance.read().await.is_some(),
+            Condition::ServerTimeSynced => {
+                self.server_time.read().await.is_synced_at(self.clock.now())
+            }
+            Condition::SubscriptionActive(asset) => self
+                .active_subscriptions
+                .get(asset)
+                .is_some_and(|subs| !subs.is_empty()),
+            Condition::FirstTick(asset) => self.last_ticks.contains_key(asset),
+        }
+    }
+
+    /// The notification fired whenever `condition` may have changed.
+    fn notifier(&self, condition: &Condition) -> &tokio::sync::Notify {
+        match condition {
+            Condition::AssetsLoaded => &self.assets_updated,
+            Condition::BalanceKnown => &self.balance_updated,
+            Condition::ServerTimeSynced => &self.server_time_updated,
+            Condition::SubscriptionActive(_) => &self.subscriptions_updated,
+            Condition::FirstTick(_) => &self.ticks_updated,
+        }
+    }
+
+    /// Waits until `condition` holds, without polling.
+    pub async fn wait_for(&self, condition: &Condition) {
+        let notify = self.notifier(condition);
+        loop {
+            // Register interest before checking so an update between the check
+            // and the await is not missed.
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_satisfied(condition).await {
+                return;
+            }
+            notified.await;
+        }
+    }
+
     /// Adds or replaces a validator in the list of raw validators.
     pub fn add_raw_validator(&self, id: Uuid, validator: Validator) {
         self.raw_validators
@@ -331,8 +755,37 @@ impl State {
             .insert(id, Arc::new(validator));
     }
 
+    /// Marks the raw handler `id` as receiving reassembled binary packets.
+    pub fn set_raw_reassembling(&self, id: Uuid) {
+        self.raw_reassembling
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id);
+    }
+
+    /// Returns whether the raw handler `id` receives reassembled binary packets.
+    pub fn is_raw_reassembling(&self, id: &Uuid) -> bool {
+        self.raw_reassembling
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(id)
+    }
+
+    /// Returns whether any raw handler receives reassembled binary packets.
+    pub fn has_raw_reassembling(&self) -> bool {
+        !self
+            .raw_reassembling
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
     /// Removes a validator by ID. Returns whether it existed.
     pub fn remove_raw_validator(&self, id: &Uuid) -> bool {
+        self.raw_reassembling
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id);
         self.raw_validators
             .write()
             .unwrap_or_else(|e| e.into_inner())
@@ -342,6 +795,10 @@ impl State {
 
     /// Removes all the validators
     pub fn clear_raw_validators(&self) {
+        self.raw_reassembling
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
         self.raw_validators
             .write()
             .unwrap_or_else(|e| e.into_inner())
@@ -349,6 +806,32 @@ impl State {
     }
 }
 
+/// How often `TradeState::wait_settled` checks whether in-flight trades settled.
+const DRAIN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Number of asset changes buffered for slow [`State::asset_changes`] receivers.
+const ASSET_CHANGES_CAPACITY: usize = 256;
+
+/// Number of parse failures buffered for slow [`State::parse_failures`] receivers.
+const PARSE_FAILURES_CAPACITY: usize = 64;
+
+/// Number of candle issues buffered for slow [`State::data_quality`] receivers.
+const DATA_QUALITY_CAPACITY: usize = 256;
+
+/// Number of deals buffered for slow [`TradeState::opened_deal_events`] and
+/// [`TradeState::closed_deal_events`] receivers.
+const DEAL_EVENTS_CAPACITY: usize = 256;
+
+/// Broadcasts every deal the first time it is seen opened, or closed.
+#[derive(Debug)]
+struct DealEvents(tokio::sync::broadcast::Sender<Deal>);
+
+impl Default for DealEvents {
+    fn default() -> Self {
+        Self(tokio::sync::broadcast::channel(DEAL_EVENTS_CAPACITY).0)
+    }
+}
+
 /// Holds all state related to trades and deals.
 type RecentTradeKey = (String, Action, u32, Decimal);
 
@@ -366,12 +849,24 @@ pub struct TradeState {
     /// Cache of recent trades
     /// Key: (Asset, Action, Time, Amount). Value: (Trade ID, Timestamp)
     pub recent_trades: RwLock<HashMap<RecentTradeKey, (Uuid, Instant)>>,
+    /// Win/loss statistics over every deal closed since the client started.
+    stats: RwLock<TradeStats>,
+    /// Latency histograms of the orders placed since the client started.
+    latency: RwLock<TradeLatency>,
+    /// Slippage of the deals opened since the client started.
+    slippage: RwLock<SlippageStats>,
+    /// Daily profit target and drawdown limit over the closed deals.
+    pub kill_switch: KillSwitch,
+    opened_events: DealEvents,
+    closed_events: DealEvents,
+    /// Closed deals already handled, kept across `reset_stats` unlike the statistics.
+    closed_seen: RwLock<SeenDeals>,
 }
 
 impl TradeState {
     /// Adds a new opened deal.
     pub async fn add_opened_deal(&self, deal: Deal) {
-        self.opened_deals.write().await.insert(deal.id, deal);
+        self.update_opened_deals(vec![deal]).await;
     }
 
     /// Adds a new pending deal.
@@ -381,23 +876,74 @@ impl TradeState {
 
     /// Adds or updates deals in the opened_deals map.
     pub async fn update_opened_deals(&self, deals: Vec<Deal>) {
-        self.opened_deals
-            .write()
-            .await
-            .extend(deals.into_iter().map(|deal| (deal.id, deal)));
+        let mut opened = self.opened_deals.write().await;
+        for deal in deals {
+            if opened.insert(deal.id, deal.clone()).is_none() {
+                let _ = self.opened_events.0.send(deal);
+            }
+        }
     }
 
     /// Moves deals from opened to closed and adds new closed deals.
     pub async fn update_closed_deals(&self, deals: Vec<Deal>) {
         let mut opened = self.opened_deals.write().await;
         let mut closed = self.closed_deals.write().await;
+        let mut stats = self.stats.write().await;
+        let mut seen = self.closed_seen.write().await;
 
         for deal in deals {
             opened.remove(&deal.id);
+            if seen.insert(deal.id) {
+                stats.record(&deal);
+                self.kill_switch.record(&deal);
+                let _ = self.closed_events.0.send(deal.clone());
+            }
             closed.insert(deal.id, deal);
         }
     }
 
+    /// Subscribes to deals as they open, each deal is sent once while it stays opened.
+    pub fn opened_deal_events(&self) -> tokio::sync::broadcast::Receiver<Deal> {
+        self.opened_events.0.subscribe()
+    }
+
+    /// Subscribes to deals as they close, each deal is sent once.
+    pub fn closed_deal_events(&self) -> tokio::sync::broadcast::Receiver<Deal> {
+        self.closed_events.0.subscribe()
+    }
+
+    /// Returns a snapshot of the win/loss statistics.
+    pub async fn get_stats(&self) -> TradeStats {
+        self.stats.read().await.clone()
+    }
+
+    /// Resets the win/loss statistics, the latency histograms and the slippage statistics.
+    pub async fn reset_stats(&self) {
+        self.stats.write().await.reset();
+        *self.latency.write().await = TradeLatency::default();
+        *self.slippage.write().await = SlippageStats::default();
+    }
+
+    /// Records the stage timings of a resolved order.
+    pub async fn record_timings(&self, timings: TradeTimings) {
+        self.latency.write().await.record(timings);
+    }
+
+    /// Returns a snapshot of the order latency histograms.
+    pub async fn get_latency(&self) -> TradeLatency {
+        self.latency.read().await.clone()
+    }
+
+    /// Records the slippage of a deal opened on `asset`.
+    pub async fn record_slippage(&self, asset: &str, slippage: Decimal) {
+        self.slippage.write().await.record(asset, slippage);
+    }
+
+    /// Returns a snapshot of the slippage statistics.
+    pub async fn get_slippage(&self) -> SlippageStats {
+        self.slippage.read().await.clone()
+    }
+
     /// Removes all deals from the closed_deals map.
     pub async fn clear_closed_deals(&self) {
         self.closed_deals.write().await.clear();
@@ -409,7 +955,7 @@ impl TradeState {
         if closed.len() > max_deals {
             let mut deals: Vec<_> = closed.values().collect();
             // Sort by close timestamp (descending)
-            deals.sort_by(|a, b| b.close_timestamp.cmp(&a.close_timestamp));
+            deals.sort_by_key(|d| std::cmp::Reverse(d.close_timestamp));
 
             let to_keep: std::collections::HashSet<_> =
                 deals.iter().take(max_deals).map(|d| d.id).collect();
@@ -427,6 +973,11 @@ impl TradeState {
         self.opened_deals.read().await.clone()
     }
 
+    /// Number of opened deals.
+    pub async fn opened_deals_count(&self) -> usize {
+        self.opened_deals.read().await.len()
+    }
+
     /// Retrieves all closed deals.
     pub async fn get_closed_deals(&self) -> HashMap<Uuid, Deal> {
         self.closed_deals.read().await.clone()
@@ -471,6 +1022,41 @@ impl TradeState {
         self.pending_deals.read().await.clone()
     }
 
+    /// Rejects an order identical to one placed less than `window` ago, otherwise reserves
+    /// its fingerprint under `request_id` so concurrent identical orders are rejected too.
+    pub async fn reserve_trade(
+        &self,
+        fingerprint: &RecentTradeKey,
+        request_id: Uuid,
+        window: std::time::Duration,
+    ) -> PocketResult<()> {
+        let now = Instant::now();
+        let mut recent = self.recent_trades.write().await;
+        recent.retain(|_, (_, placed)| now.duration_since(*placed) < window);
+        if let Some((id, _)) = recent.get(fingerprint) {
+            let (asset, action, time, amount) = fingerprint;
+            return Err(PocketError::General(format!(
+                "Duplicate trade blocked: {action:?} {amount} on {asset} for {time}s matches order {id} placed less than {window:?} ago, use allow_duplicate to place it anyway"
+            )));
+        }
+        recent.insert(fingerprint.clone(), (request_id, now));
+        Ok(())
+    }
+
+    /// Waits until no market order awaits its confirmation and no opened deal its result,
+    /// or until `deadline`. Returns the number of trades still in flight.
+    pub async fn wait_settled(&self, deadline: tokio::time::Instant) -> usize {
+        loop {
+            let in_flight = self.pending_market_orders.read().await.len()
+                + self.opened_deals.read().await.len();
+            if in_flight == 0 || tokio::time::Instant::now() >= deadline {
+                return in_flight;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL.min(deadline - tokio::time::Instant::now()))
+                .await;
+        }
+    }
+
     /// Removes a pending deal by its ID.
     pub async fn remove_pending_deal(&self, deal_id: &Uuid) -> Option<PendingOrder> {
         self.pending_deals.write().await.remove(deal_id)
@@ -522,4 +1108,303 @@ mod tests {
             assert!(pending.is_empty());
         });
     }
+
+    fn opened_deal() -> Deal {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 1, "amount": 1.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 0.0,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_wait_settled_waits_for_results() {
+        let ts = Arc::new(TradeState::default());
+        let deal = opened_deal();
+        ts.add_opened_deal(deal.clone()).await;
+        let waiting = tokio::spawn({
+            let ts = ts.clone();
+            async move {
+                ts.wait_settled(tokio::time::Instant::now() + std::time::Duration::from_secs(5))
+                    .await
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert!(!waiting.is_finished(), "the opened deal has no result yet");
+
+        ts.update_closed_deals(vec![deal]).await;
+        let unresolved = tokio::time::timeout(std::time::Duration::from_secs(1), waiting)
+            .await
+            .expect("still waiting once the deal closed")
+            .unwrap();
+        assert_eq!(unresolved, 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_settled_respects_deadline() {
+        let ts = TradeState::default();
+        ts.add_opened_deal(opened_deal()).await;
+        let order = OpenOrder::new(
+            Decimal::ONE,
+            "EURUSD_otc".into(),
+            Action::Call,
+            60,
+            1,
+            Uuid::new_v4(),
+        );
+        ts.pending_market_orders
+            .write()
+            .await
+            .insert(order.request_id, (order, Instant::now()));
+
+        let timeout = std::time::Duration::from_millis(300);
+        let started = Instant::now();
+        let unresolved = ts.wait_settled(tokio::time::Instant::now() + timeout).await;
+        assert_eq!(unresolved, 2, "neither trade settled");
+        assert!(started.elapsed() >= timeout);
+        assert!(started.elapsed() < timeout * 3);
+    }
+
+    #[tokio::test]
+    async fn test_deal_events_sent_once() {
+        let ts = TradeState::default();
+        let mut opened = ts.opened_deal_events();
+        let mut events = ts.closed_deal_events();
+        let deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 1, "amount": 1.0, "profit": 0.92,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 1.1,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        ts.add_opened_deal(deal.clone()).await;
+        ts.update_opened_deals(vec![deal.clone()]).await;
+        assert_eq!(opened.recv().await.unwrap().id, deal.id);
+        assert!(opened.try_recv().is_err());
+        ts.update_closed_deals(vec![deal.clone()]).await;
+        ts.update_closed_deals(vec![deal.clone()]).await;
+        assert_eq!(events.recv().await.unwrap().id, deal.id);
+        assert!(events.try_recv().is_err());
+
+        // The server re-sends the latest closed deals after the statistics were reset
+        ts.reset_stats().await;
+        ts.update_closed_deals(vec![deal.clone()]).await;
+        assert!(events.try_recv().is_err());
+        assert_eq!(ts.get_stats().await.total_trades, 0);
+    }
+
+    fn is_duplicate(result: PocketResult<()>) -> bool {
+        matches!(result, Err(PocketError::General(msg)) if msg.starts_with("Duplicate trade blocked"))
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_trade_window() {
+        let ts = TradeState::default();
+        let window = std::time::Duration::from_millis(300);
+        let call = ("EURUSD_otc".to_string(), Action::Call, 60, Decimal::TEN);
+        ts.reserve_trade(&call, Uuid::new_v4(), window)
+            .await
+            .unwrap();
+        assert!(is_duplicate(
+            ts.reserve_trade(&call, Uuid::new_v4(), window).await
+        ));
+        // Another action is not the same trade.
+        let put = ("EURUSD_otc".to_string(), Action::Put, 60, Decimal::TEN);
+        ts.reserve_trade(&put, Uuid::new_v4(), window)
+            .await
+            .unwrap();
+
+        // Once the window expired the identical trade is placed again.
+        tokio::time::sleep(window + std::time::Duration::from_millis(100)).await;
+        ts.reserve_trade(&call, Uuid::new_v4(), window)
+            .await
+            .unwrap();
+        assert!(is_duplicate(
+            ts.reserve_trade(&call, Uuid::new_v4(), window).await
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_zero_duplicate_window_allows_identical_trades() {
+        let ts = TradeState::default();
+        let call = ("EURUSD_otc".to_string(), Action::Call, 60, Decimal::TEN);
+        for _ in 0..3 {
+            ts.reserve_trade(&call, Uuid::new_v4(), std::time::Duration::ZERO)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kill_switch_counts_deals_once_across_stats_resets() {
+        let ts = TradeState::default();
+        ts.kill_switch.configure(KillSwitchConfig {
+            daily_profit_target: None,
+            max_drawdown: Some(Decimal::new(20, 0)),
+        });
+        let mut deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 1_700_000_000, "closeTimestamp": 1_700_000_060,
+            "uid": 1, "amount": 15.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 0.9,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        deal.profit = Decimal::new(-15, 0);
+        let now = deal.close_timestamp;
+
+        ts.update_closed_deals(vec![deal.clone()]).await;
+        ts.reset_stats().await;
+        ts.update_closed_deals(vec![deal.clone()]).await;
+        let status = ts.kill_switch.status(now);
+        assert_eq!(status.pnl, Decimal::new(-15, 0));
+        assert!(status.tripped.is_none());
+        assert!(ts.kill_switch.check(now).is_ok());
+    }
+
+    #[test]
+    fn test_quarantine_forwards_the_raw_frame() {
+        let ssid =
+            Ssid::parse(r#"42["auth",{"session":"s","isDemo":1,"uid":1,"platform":2}]"#).unwrap();
+        let state = StateBuilder::default().ssid(ssid).build().unwrap();
+        let mut failures = state.parse_failures();
+        let frame = Message::binary(br#"{"balance":}"#.to_vec());
+        state.quarantine("BalanceModule", &frame, "bad json");
+        let failure = failures.try_recv().unwrap();
+        assert_eq!(failure.module, "BalanceModule");
+        assert_eq!(failure.raw, r#"{"balance":}"#);
+        assert!(failure.binary);
+        assert_eq!(failure.error, "bad json");
+    }
+
+    #[tokio::test]
+    async fn test_balances_per_account_type() {
+        let ssid =
+            Ssid::parse(r#"42["auth",{"session":"s","isDemo":1,"uid":1,"platform":2}]"#).unwrap();
+        let state = StateBuilder::default().ssid(ssid).build().unwrap();
+        assert_eq!(state.account_type(), AccountType::Demo);
+
+        state
+            .set_account_balance(AccountType::Real, Decimal::new(250, 0), Some("EUR".into()))
+            .await;
+        assert_eq!(state.get_balance().await, None, "not the session account");
+        assert_eq!(state.get_currency().await, None);
+
+        state
+            .set_account_balance(
+                AccountType::Demo,
+                Decimal::new(50000, 0),
+                Some("USD".into()),
+            )
+            .await;
+        assert_eq!(state.get_balance().await, Some(Decimal::new(50000, 0)));
+        assert_eq!(state.get_currency().await.as_deref(), Some("USD"));
+
+        let balances = state.balances().await;
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[&AccountType::Real].amount, Decimal::new(250, 0));
+        assert_eq!(
+            balances[&AccountType::Real].currency.as_deref(),
+            Some("EUR")
+        );
+        assert!(!balances.contains_key(&AccountType::Tournament));
+
+        state.clear_temporal_data().await;
+        assert_eq!(state.get_balance().await, None);
+        assert_eq!(state.balances().await.len(), 2, "kept across reconnects");
+    }
+
+    #[tokio::test]
+    async fn test_time_to_expiry_uses_server_time() {
+        let ssid = Ssid::parse(
+            r#"42["auth",{"sessionToken":"test","uid":0,"platform":2,"currentUrl":"demo","isFastHistory":false,"isOptimized":true}]"#
+        ).unwrap();
+        let state = StateBuilder::default().ssid(ssid).build().unwrap();
+        let deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 1_700_000_000, "closeTimestamp": 1_700_000_060,
+            "uid": 1, "amount": 1.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 0.0,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        assert_eq!(state.time_to_expiry(deal.id).await, None);
+
+        state.update_server_time(1_700_000_015).await;
+        state.trade_state.add_opened_deal(deal.clone()).await;
+        let left = state.time_to_expiry(deal.id).await.unwrap();
+        assert!(left <= std::time::Duration::from_secs(45));
+        assert!(left >= std::time::Duration::from_secs(44));
+
+        state.update_server_time(1_700_000_090).await;
+        assert_eq!(
+            state.time_to_expiry(deal.id).await,
+            Some(std::time::Duration::ZERO)
+        );
+        state
+            .trade_state
+            .update_closed_deals(vec![deal.clone()])
+            .await;
+        assert_eq!(
+            state.time_to_expiry(deal.id).await,
+            Some(std::time::Duration::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_condition_wakes_on_update() {
+        let ssid = Ssid::parse(
+            r#"42["auth",{"sessionToken":"test","uid":0,"platform":2,"currentUrl":"demo","isFastHistory":false,"isOptimized":true}]"#
+        ).unwrap();
+        let state = Arc::new(StateBuilder::default().ssid(ssid).build().unwrap());
+        let tick = Condition::FirstTick("EURUSD_otc".to_string());
+        assert!(!state.is_satisfied(&tick).await);
+
+        let waiter = tokio::spawn({
+            let state = state.clone();
+            async move {
+                state
+                    .wait_for(&Condition::FirstTick("EURUSD_otc".to_string()))
+                    .await
+            }
+        });
+        tokio::task::yield_now().await;
+        state
+            .record_tick("EURUSD_otc", 1_700_000_000, Decimal::ONE)
+            .await;
+        assert_eq!(state.last_price("EURUSD_otc").await, Some(Decimal::ONE));
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should be woken by the tick")
+            .unwrap();
+
+        state.set_balance(Decimal::ONE).await;
+        tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            state.wait_for(&Condition::BalanceKnown),
+        )
+        .await
+        .expect("already satisfied conditions return immediately");
+        assert!(!state.is_satisfied(&Condition::ServerTimeSynced).await);
+    }
 }
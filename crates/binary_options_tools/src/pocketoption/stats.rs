@@ -0,0 +1,704 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use binary_options_tools_core::status::ConnectionStatus;
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::pocketoption::types::Deal;
+
+/// Number of most recent deals used for the rolling win rate.
+pub const DEFAULT_ROLLING_WINDOW: usize = 50;
+
+/// Number of most recent deal ids remembered to skip deals the server sends again.
+///
+/// `updateClosedDeals` only re-sends the latest deals, so older ids are forgotten to keep a
+/// long running client from growing without bound.
+pub const SEEN_DEALS_CAPACITY: usize = 10_000;
+
+/// Upper bounds (in milliseconds) of the trade latency histogram buckets,
+/// slower samples land in an extra overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 9] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000];
+
+/// Outcome of a closed deal, classified by its profit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl Outcome {
+    /// Classifies a deal the same way `check_win` does: positive profit is a win,
+    /// negative profit a loss and zero a draw.
+    pub fn of(deal: &Deal) -> Self {
+        if deal.profit > Decimal::ZERO {
+            Outcome::Win
+        } else if deal.profit < Decimal::ZERO {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+}
+
+/// Win/loss breakdown for a single asset.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AssetStats {
+    pub trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_profit: Decimal,
+    pub win_rate: f64,
+}
+
+/// Aggregated statistics over closed deals.
+///
+/// Deals are fed in as they close (see `TradeState::update_closed_deals`). Each deal is
+/// only counted once, even if the server re-sends it in a later `updateClosedDeals`, as long
+/// as it is among the last [`SEEN_DEALS_CAPACITY`] deals recorded.
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeStats {
+    pub total_trades: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub gross_profit: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub gross_loss: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_profit: Decimal,
+    /// Wins over decided trades (draws excluded), between 0 and 1.
+    pub win_rate: f64,
+    /// Win rate over the last `rolling_window` decided trades.
+    pub rolling_win_rate: f64,
+    pub rolling_window: usize,
+    /// Gross profit divided by gross loss, `None` while there are no losses.
+    pub profit_factor: Option<f64>,
+    /// Positive for a running win streak, negative for a losing one.
+    pub current_streak: i64,
+    pub max_win_streak: u64,
+    pub max_loss_streak: u64,
+    pub per_asset: HashMap<String, AssetStats>,
+    #[serde(skip)]
+    recent: VecDeque<Outcome>,
+    #[serde(skip)]
+    seen: SeenDeals,
+}
+
+impl Default for TradeStats {
+    fn default() -> Self {
+        Self::with_rolling_window(DEFAULT_ROLLING_WINDOW)
+    }
+}
+
+impl TradeStats {
+    /// Creates an empty aggregator using the given rolling window size.
+    pub fn with_rolling_window(rolling_window: usize) -> Self {
+        Self {
+            total_trades: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            gross_profit: Decimal::ZERO,
+            gross_loss: Decimal::ZERO,
+            net_profit: Decimal::ZERO,
+            win_rate: 0.0,
+            rolling_win_rate: 0.0,
+            rolling_window: rolling_window.max(1),
+            profit_factor: None,
+            current_streak: 0,
+            max_win_streak: 0,
+            max_loss_streak: 0,
+            per_asset: HashMap::new(),
+            recent: VecDeque::new(),
+            seen: SeenDeals::default(),
+        }
+    }
+
+    /// Records a closed deal. Returns `false` if the deal was already counted.
+    pub fn record(&mut self, deal: &Deal) -> bool {
+        if !self.seen.insert(deal.id) {
+            return false;
+        }
+        let outcome = Outcome::of(deal);
+
+        self.total_trades += 1;
+        self.net_profit += deal.profit;
+        let asset = self.per_asset.entry(deal.asset.clone()).or_default();
+        asset.trades += 1;
+        asset.net_profit += deal.profit;
+
+        match outcome {
+            Outcome::Win => {
+                self.wins += 1;
+                asset.wins += 1;
+                self.gross_profit += deal.profit;
+                self.current_streak = self.current_streak.max(0) + 1;
+                self.max_win_streak = self.max_win_streak.max(self.current_streak as u64);
+            }
+            Outcome::Loss => {
+                self.losses += 1;
+                asset.losses += 1;
+                self.gross_loss += -deal.profit;
+                self.current_streak = self.current_streak.min(0) - 1;
+                self.max_loss_streak = self.max_loss_streak.max(self.current_streak.unsigned_abs());
+            }
+            // Draws neither extend nor break a streak.
+            Outcome::Draw => {
+                self.draws += 1;
+                asset.draws += 1;
+            }
+        }
+        asset.win_rate = ratio(asset.wins, asset.wins + asset.losses);
+
+        if outcome != Outcome::Draw {
+            self.recent.push_back(outcome);
+            while self.recent.len() > self.rolling_window {
+                self.recent.pop_front();
+            }
+        }
+        self.refresh_ratios();
+        true
+    }
+
+    /// Clears all counters while keeping the rolling window size.
+    pub fn reset(&mut self) {
+        *self = Self::with_rolling_window(self.rolling_window);
+    }
+
+    fn refresh_ratios(&mut self) {
+        self.win_rate = ratio(self.wins, self.wins + self.losses);
+        let recent_wins = self.recent.iter().filter(|o| **o == Outcome::Win).count();
+        self.rolling_win_rate = ratio(recent_wins as u64, self.recent.len() as u64);
+        self.profit_factor = if self.gross_loss.is_zero() {
+            None
+        } else {
+            (self.gross_profit / self.gross_loss).to_f64()
+        };
+    }
+}
+
+/// Ids of the last [`SEEN_DEALS_CAPACITY`] deals, to skip deals the server sends again.
+#[derive(Debug, Clone, Default)]
+pub struct SeenDeals {
+    ids: HashSet<Uuid>,
+    /// `ids` in insertion order, the oldest is forgotten first.
+    order: VecDeque<Uuid>,
+}
+
+impl SeenDeals {
+    /// Remembers `id`, returns `false` if it was already remembered.
+    pub fn insert(&mut self, id: Uuid) -> bool {
+        if !self.ids.insert(id) {
+            return false;
+        }
+        self.order.push_back(id);
+        while self.order.len() > SEEN_DEALS_CAPACITY {
+            if let Some(id) = self.order.pop_front() {
+                self.ids.remove(&id);
+            }
+        }
+        true
+    }
+}
+
+/// How an order placed with `trade` ended, as seen by the trades module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderOutcome {
+    /// The server answered with `successopenOrder`.
+    Opened,
+    /// The server answered with `failopenOrder` or the frame could not be sent.
+    Failed,
+    /// No answer arrived before the open order timeout.
+    TimedOut,
+}
+
+/// Instants at which an order went through each stage, filled in by the trades module.
+#[derive(Debug, Clone, Copy)]
+pub struct TradeStages {
+    enqueued: Instant,
+    enqueued_at: i64,
+    sent: Option<Instant>,
+    acked: Option<Instant>,
+}
+
+impl TradeStages {
+    /// Starts timing an order at the moment its command is enqueued.
+    pub fn start() -> Self {
+        Self {
+            enqueued: Instant::now(),
+            enqueued_at: Utc::now().timestamp_millis(),
+            sent: None,
+            acked: None,
+        }
+    }
+
+    /// The order frame was handed to the websocket writer.
+    pub fn sent(&mut self, at: Instant) {
+        self.sent = Some(at);
+    }
+
+    /// Whether the order frame was handed to the websocket writer.
+    pub fn was_sent(&self) -> bool {
+        self.sent.is_some()
+    }
+
+    /// The server answered the order.
+    pub fn acked(&mut self, at: Instant) {
+        self.acked = Some(at);
+    }
+
+    /// Closes the timing once the result was handed back to the caller at `confirmed`.
+    pub fn finish(
+        self,
+        request_id: Uuid,
+        asset: String,
+        outcome: OrderOutcome,
+        confirmed: Instant,
+    ) -> TradeTimings {
+        let since = |from: Option<Instant>, to: Option<Instant>| match (from, to) {
+            (Some(from), Some(to)) => Some(to.saturating_duration_since(from)),
+            _ => None,
+        };
+        let confirmed = (outcome != OrderOutcome::TimedOut).then_some(confirmed);
+        TradeTimings {
+            request_id,
+            asset,
+            outcome,
+            enqueued_at: self.enqueued_at,
+            queued: since(Some(self.enqueued), self.sent),
+            server: since(self.sent, self.acked),
+            confirm: since(self.acked, confirmed),
+            total: confirmed
+                .unwrap_or_else(Instant::now)
+                .saturating_duration_since(self.enqueued),
+        }
+    }
+}
+
+/// Time spent by one order in each stage, durations are serialized in milliseconds.
+///
+/// A stage the order never reached is `None`: `server` is missing when the server did not
+/// answer (the usual cause of a `Trade task timeout`), `queued` when the frame was never sent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeTimings {
+    pub request_id: Uuid,
+    pub asset: String,
+    pub outcome: OrderOutcome,
+    /// Unix timestamp in milliseconds at which the order was enqueued.
+    pub enqueued_at: i64,
+    /// From the command being enqueued to the frame being handed to the websocket writer.
+    #[serde(serialize_with = "opt_millis")]
+    pub queued: Option<Duration>,
+    /// From the frame being sent to the server's `successopenOrder` / `failopenOrder`.
+    #[serde(serialize_with = "opt_millis")]
+    pub server: Option<Duration>,
+    /// From the server's answer to the deal being stored and returned to the caller.
+    #[serde(serialize_with = "opt_millis")]
+    pub confirm: Option<Duration>,
+    /// From the command being enqueued to the order being resolved.
+    #[serde(serialize_with = "millis")]
+    pub total: Duration,
+}
+
+/// Fixed bucket histogram of latencies, see [`LATENCY_BUCKETS_MS`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyHistogram {
+    /// Inclusive upper bound of each bucket in milliseconds.
+    pub bounds_ms: Vec<u64>,
+    /// Samples per bucket, the last entry counts samples above the highest bound.
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    #[serde(skip)]
+    sum_ms: f64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            bounds_ms: LATENCY_BUCKETS_MS.to_vec(),
+            counts: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+            count: 0,
+            mean_ms: 0.0,
+            max_ms: 0.0,
+            sum_ms: 0.0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let ms = latency.as_secs_f64() * 1000.0;
+        let bucket = self
+            .bounds_ms
+            .iter()
+            .position(|bound| ms <= *bound as f64)
+            .unwrap_or(self.bounds_ms.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+        self.mean_ms = self.sum_ms / self.count as f64;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    /// Upper bound of the bucket holding the `quantile` (0 to 1) sample, `None` while empty
+    /// or when it falls in the overflow bucket.
+    pub fn quantile_ms(&self, quantile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return self.bounds_ms.get(i).copied();
+            }
+        }
+        None
+    }
+}
+
+/// Latency histograms over every order placed since the client started.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TradeLatency {
+    pub opened: u64,
+    pub failed: u64,
+    pub timed_out: u64,
+    pub queued: LatencyHistogram,
+    pub server: LatencyHistogram,
+    pub confirm: LatencyHistogram,
+    pub total: LatencyHistogram,
+    /// Timings of the most recently resolved order.
+    pub last: Option<TradeTimings>,
+}
+
+impl TradeLatency {
+    pub fn record(&mut self, timings: TradeTimings) {
+        match timings.outcome {
+            OrderOutcome::Opened => self.opened += 1,
+            OrderOutcome::Failed => self.failed += 1,
+            OrderOutcome::TimedOut => self.timed_out += 1,
+        }
+        for (histogram, stage) in [
+            (&mut self.queued, timings.queued),
+            (&mut self.server, timings.server),
+            (&mut self.confirm, timings.confirm),
+        ] {
+            if let Some(stage) = stage {
+                histogram.record(stage);
+            }
+        }
+        self.total.record(timings.total);
+        self.last = Some(timings);
+    }
+}
+
+/// Slippage of a set of deals, see [`Deal::slippage`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlippageSummary {
+    pub deals: u64,
+    /// Deals opened at another price than the quote.
+    pub requotes: u64,
+    /// Deals opened at a worse price than the quote.
+    pub adverse: u64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mean: Decimal,
+    /// Largest slippage, the worst fill.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub worst: Decimal,
+    #[serde(skip)]
+    total: Decimal,
+}
+
+impl SlippageSummary {
+    fn record(&mut self, slippage: Decimal) {
+        if self.deals == 0 || slippage > self.worst {
+            self.worst = slippage;
+        }
+        self.deals += 1;
+        if !slippage.is_zero() {
+            self.requotes += 1;
+        }
+        if slippage > Decimal::ZERO {
+            self.adverse += 1;
+        }
+        self.total += slippage;
+        self.mean = self.total / Decimal::from(self.deals);
+    }
+}
+
+/// Slippage of the deals opened since the client started, overall and per asset since it is
+/// in price units.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlippageStats {
+    #[serde(flatten)]
+    pub total: SlippageSummary,
+    pub per_asset: HashMap<String, SlippageSummary>,
+}
+
+impl SlippageStats {
+    pub fn record(&mut self, asset: &str, slippage: Decimal) {
+        self.total.record(slippage);
+        self.per_asset
+            .entry(asset.to_string())
+            .or_default()
+            .record(slippage);
+    }
+}
+
+/// Round trip times of the keep-alive pings, measured when `KeepAliveConfig::pong` is set.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PingStats {
+    /// Pings sent expecting an answer.
+    pub sent: u64,
+    /// Pings answered before the next one was sent.
+    pub answered: u64,
+    pub rtt: LatencyHistogram,
+    #[serde(serialize_with = "opt_millis")]
+    pub last_rtt: Option<Duration>,
+    /// Time the unanswered ping was sent and the prefix of its answer.
+    #[serde(skip)]
+    pending: Option<(Instant, String)>,
+}
+
+impl PingStats {
+    /// Records a ping answered by a frame starting with `pong`.
+    pub fn sent(&mut self, at: Instant, pong: String) {
+        self.sent += 1;
+        self.pending = Some((at, pong));
+    }
+
+    /// Whether a ping waits for an answer.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Records `text` received at `at`, returns `true` if it answered the pending ping.
+    pub fn received(&mut self, text: &str, at: Instant) -> bool {
+        let Some((sent, pong)) = &self.pending else {
+            return false;
+        };
+        if !text.starts_with(pong.as_str()) {
+            return false;
+        }
+        let rtt = at.saturating_duration_since(*sent);
+        self.pending = None;
+        self.answered += 1;
+        self.rtt.record(rtt);
+        self.last_rtt = Some(rtt);
+        true
+    }
+}
+
+/// Liveness snapshot emitted by [`PocketOption::heartbeat`](crate::pocketoption::PocketOption::heartbeat),
+/// meant for health dashboards.
+#[derive(Debug, Clone, Serialize)]
+pub struct Heartbeat {
+    pub at: DateTime<Utc>,
+    pub status: ConnectionStatus,
+    /// Time since the last message from the server, `None` before the first one.
+    #[serde(serialize_with = "opt_millis")]
+    pub last_message_age: Option<Duration>,
+    /// Assets with at least one active subscription, sorted.
+    pub subscriptions: Vec<String>,
+    /// Messages waiting to be written to the socket.
+    pub outgoing_queue: usize,
+    /// Orders sent to the server and not confirmed yet.
+    pub pending_orders: usize,
+    pub open_deals: usize,
+}
+
+fn millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+pub(crate) fn opt_millis<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => millis(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn ratio(num: u64, den: u64) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn deal(asset: &str, profit: Decimal) -> Deal {
+        let mut deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 1, "amount": 1.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 1.0,
+            "command": 0, "asset": asset, "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        deal.profit = profit;
+        deal
+    }
+
+    #[test]
+    fn test_trade_stats_aggregation() {
+        let mut stats = TradeStats::default();
+        let win = deal("EURUSD_otc", dec!(0.92));
+        assert!(stats.record(&win));
+        assert!(!stats.record(&win), "duplicate deals must not be counted");
+        stats.record(&deal("EURUSD_otc", dec!(0.92)));
+        stats.record(&deal("AUDCAD_otc", dec!(-1)));
+        stats.record(&deal("AUDCAD_otc", dec!(0)));
+        stats.record(&deal("AUDCAD_otc", dec!(-1)));
+
+        assert_eq!(stats.total_trades, 5);
+        assert_eq!((stats.wins, stats.losses, stats.draws), (2, 2, 1));
+        assert_eq!(stats.net_profit, dec!(-0.16));
+        assert_eq!(stats.win_rate, 0.5);
+        assert_eq!(stats.profit_factor, Some(0.92));
+        assert_eq!(stats.current_streak, -2);
+        assert_eq!(stats.max_win_streak, 2);
+        assert_eq!(stats.max_loss_streak, 2);
+        assert_eq!(stats.per_asset["AUDCAD_otc"].losses, 2);
+        assert_eq!(stats.per_asset["EURUSD_otc"].win_rate, 1.0);
+
+        stats.reset();
+        assert_eq!(stats.total_trades, 0);
+        assert!(stats.per_asset.is_empty());
+    }
+
+    #[test]
+    fn test_trade_stats_rolling_window() {
+        let mut stats = TradeStats::with_rolling_window(2);
+        stats.record(&deal("EURUSD_otc", dec!(-1)));
+        stats.record(&deal("EURUSD_otc", dec!(1)));
+        stats.record(&deal("EURUSD_otc", dec!(1)));
+        assert_eq!(stats.rolling_win_rate, 1.0);
+        assert!((stats.win_rate - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_trade_stats_forgets_oldest_deals() {
+        let mut stats = TradeStats::default();
+        let first = deal("EURUSD_otc", dec!(0.92));
+        stats.record(&first);
+        let mut last = first.clone();
+        for _ in 0..SEEN_DEALS_CAPACITY {
+            last.id = Uuid::new_v4();
+            assert!(stats.record(&last));
+        }
+        assert_eq!(stats.seen.ids.len(), SEEN_DEALS_CAPACITY);
+        assert_eq!(stats.seen.order.len(), SEEN_DEALS_CAPACITY);
+        assert!(!stats.record(&last));
+        assert!(stats.record(&first), "the oldest id is forgotten");
+    }
+
+    #[test]
+    fn test_trade_latency_histograms() {
+        let start = Instant::now();
+        let mut stages = TradeStages::start();
+        stages.enqueued = start;
+        stages.sent(start + Duration::from_millis(20));
+        stages.acked(start + Duration::from_millis(320));
+        let opened = stages.finish(
+            Uuid::new_v4(),
+            "EURUSD_otc".into(),
+            OrderOutcome::Opened,
+            start + Duration::from_millis(330),
+        );
+        assert_eq!(opened.queued, Some(Duration::from_millis(20)));
+        assert_eq!(opened.server, Some(Duration::from_millis(300)));
+        assert_eq!(opened.confirm, Some(Duration::from_millis(10)));
+        assert_eq!(opened.total, Duration::from_millis(330));
+
+        let mut stages = TradeStages::start();
+        stages.sent(Instant::now());
+        let timed_out = stages.finish(
+            Uuid::new_v4(),
+            "EURUSD_otc".into(),
+            OrderOutcome::TimedOut,
+            Instant::now(),
+        );
+        assert!(timed_out.queued.is_some());
+        assert_eq!((timed_out.server, timed_out.confirm), (None, None));
+
+        let mut latency = TradeLatency::default();
+        latency.record(opened);
+        latency.record(timed_out.clone());
+        assert_eq!((latency.opened, latency.timed_out), (1, 1));
+        assert_eq!(latency.server.count, 1);
+        assert_eq!(latency.server.counts[2], 0);
+        assert_eq!(latency.server.counts[3], 1);
+        assert_eq!(latency.server.quantile_ms(0.5), Some(500));
+        assert_eq!(latency.total.count, 2);
+        assert_eq!(latency.last, Some(timed_out));
+
+        let json = serde_json::to_value(&latency.last).unwrap();
+        assert_eq!(json["outcome"], "timed_out");
+        assert!(json["server"].is_null());
+    }
+
+    #[test]
+    fn test_slippage_stats() {
+        let mut stats = SlippageStats::default();
+        stats.record("EURUSD_otc", dec!(0.0002));
+        stats.record("EURUSD_otc", dec!(-0.0001));
+        stats.record("AUDCAD_otc", Decimal::ZERO);
+
+        assert_eq!(
+            (stats.total.deals, stats.total.requotes, stats.total.adverse),
+            (3, 2, 1)
+        );
+        assert_eq!(stats.total.worst, dec!(0.0002));
+        let eurusd = &stats.per_asset["EURUSD_otc"];
+        assert_eq!(eurusd.mean, dec!(0.00005));
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["deals"], 3);
+        assert_eq!(json["per_asset"]["AUDCAD_otc"]["requotes"], 0);
+    }
+
+    #[test]
+    fn test_ping_stats_round_trip() {
+        let mut stats = PingStats::default();
+        let start = Instant::now();
+        assert!(!stats.received("pong", start));
+
+        stats.sent(start, "42[\"pong\"".into());
+        assert!(stats.is_pending());
+        assert!(!stats.received("42[\"other\"]", start));
+        assert!(stats.received("42[\"pong\"]", start + Duration::from_millis(80)));
+        assert!(!stats.is_pending());
+        assert_eq!((stats.sent, stats.answered), (1, 1));
+        assert_eq!(stats.last_rtt, Some(Duration::from_millis(80)));
+        assert_eq!(stats.rtt.counts[1], 1);
+
+        stats.sent(start, "3".into());
+        assert_eq!((stats.sent, stats.answered), (2, 1));
+        assert_eq!(serde_json::to_value(&stats).unwrap()["last_rtt"], 80.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! Detection of the proxy configured for the machine, used by the connector when
+//! `Config::system_proxy` is enabled and no explicit `Config::proxy` is set.
+//!
+//! Sources, the first one defining a proxy wins:
+//! - the `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY` environment variables
+//!   (upper or lower case),
+//! - on Windows the WinHTTP settings, as printed by `netsh winhttp show proxy`,
+//! - on macOS the SystemConfiguration settings, as printed by `scutil --proxy`.
+
+use std::sync::OnceLock;
+
+use url::Url;
+
+/// Proxies configured for the machine, see [`SystemProxy::detect`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemProxy {
+    /// Proxy for `ws://` targets.
+    pub http: Option<String>,
+    /// Proxy for `wss://` targets.
+    pub https: Option<String>,
+    /// Proxy for both schemes when the specific one is missing, may be a `socks5://` URL.
+    pub all: Option<String>,
+    /// Hosts reached directly: `*`, exact hosts, domain suffixes (`.corp`, `*.corp`) and
+    /// `<local>` for hosts without a dot.
+    pub bypass: Vec<String>,
+}
+
+impl SystemProxy {
+    /// Proxy settings of the environment, or of the operating system when the environment
+    /// defines none.
+    ///
+    /// The operating system settings are read once per process, as it runs an external command.
+    pub fn detect() -> Self {
+        let env = Self::from_env();
+        if !env.is_empty() {
+            return env;
+        }
+        static OS: OnceLock<SystemProxy> = OnceLock::new();
+        OS.get_or_init(Self::from_os).clone()
+    }
+
+    /// Proxy settings of the environment variables.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Proxy settings read through `var`, upper case names take precedence.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let lookup = |name: &str| {
+            var(name)
+                .or_else(|| var(&name.to_ascii_lowercase()))
+                .filter(|value| !value.trim().is_empty())
+        };
+        Self {
+            http: lookup("HTTP_PROXY").map(|p| with_scheme(&p, "http")),
+            https: lookup("HTTPS_PROXY").map(|p| with_scheme(&p, "http")),
+            all: lookup("ALL_PROXY").map(|p| with_scheme(&p, "http")),
+            bypass: lookup("NO_PROXY")
+                .map(|l| split_list(&l))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[cfg(windows)]
+    fn from_os() -> Self {
+        std::process::Command::new("netsh")
+            .args(["winhttp", "show", "proxy"])
+            .output()
+            .ok()
+            .map(|out| Self::parse_netsh(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_os = "macos")]
+    fn from_os() -> Self {
+        std::process::Command::new("scutil")
+            .arg("--proxy")
+            .output()
+            .ok()
+            .map(|out| Self::parse_scutil(&String::from_utf8_lossy(&out.stdout)))
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn from_os() -> Self {
+        Self::default()
+    }
+
+    /// Parses the output of `netsh winhttp show proxy`.
+    ///
+    /// The server is either `host:port`, used for every scheme, or a list such as
+    /// `http=host:80;https=host:443;socks=host:1080`.
+    pub fn parse_netsh(output: &str) -> Self {
+        let mut proxy = Self::default();
+        for line in output.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+            if key.starts_with("proxy server") {
+                for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+                    match entry.split_once('=') {
+                        Some(("http", server)) => proxy.http = Some(with_scheme(server, "http")),
+                        Some(("https", server)) => proxy.https = Some(with_scheme(server, "http")),
+                        Some(("socks", server)) => proxy.all = Some(with_scheme(server, "socks5")),
+                        Some(_) => {}
+                        None => proxy.all = Some(with_scheme(entry, "http")),
+                    }
+                }
+            } else if key.starts_with("bypass list") {
+                proxy.bypass = split_list(value);
+            }
+        }
+        proxy
+    }
+
+    /// Parses the output of `scutil --proxy`, only enabled proxies are kept.
+    pub fn parse_scutil(output: &str) -> Self {
+        let mut values = std::collections::HashMap::new();
+        let mut bypass = Vec::new();
+        let mut in_exceptions = false;
+        for line in output.lines() {
+            let line = line.trim();
+            if in_exceptions {
+                match line.split_once(" : ") {
+                    Some((_, host)) => bypass.push(host.trim().to_string()),
+                    None => in_exceptions = false,
+                }
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(" : ") {
+                if key == "ExceptionsList" {
+                    in_exceptions = true;
+                } else {
+                    values.insert(key.trim(), value.trim());
+                }
+            }
+        }
+        let server = |prefix: &str, scheme: &str| {
+            if values.get(format!("{prefix}Enable").as_str()) != Some(&"1") {
+                return None;
+            }
+            let host = values.get(format!("{prefix}Proxy").as_str())?;
+            Some(match values.get(format!("{prefix}Port").as_str()) {
+                Some(port) => format!("{scheme}://{host}:{port}"),
+                None => format!("{scheme}://{host}"),
+            })
+        };
+        Self {
+            http: server("HTTP", "http"),
+            https: server("HTTPS", "http"),
+            all: server("SOCKS", "socks5"),
+            bypass,
+        }
+    }
+
+    /// Whether no proxy is defined.
+    pub fn is_empty(&self) -> bool {
+        self.http.is_none() && self.https.is_none() && self.all.is_none()
+    }
+
+    /// Proxy URL to reach `url` through, `None` to connect directly.
+    pub fn proxy_for(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?;
+        if self.bypasses(host) {
+            return None;
+        }
+        let specific = match url.scheme() {
+            "wss" | "https" => &self.https,
+            _ => &self.http,
+        };
+        specific.clone().or_else(|| self.all.clone())
+    }
+
+    /// Whether `host` matches an entry of the bypass list.
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.bypass.iter().any(|entry| {
+            let entry = entry.trim().to_ascii_lowercase();
+            match entry.as_str() {
+                "*" => true,
+                "<local>" => !host.contains('.'),
+                _ => {
+                    let domain = entry.trim_start_matches('*').trim_start_matches('.');
+                    !domain.is_empty() && (host == domain || host.ends_with(&format!(".{domain}")))
+                }
+            }
+        })
+    }
+}
+
+/// Prefixes `proxy` with `scheme://` when it has no scheme.
+fn with_scheme(proxy: &str, scheme: &str) -> String {
+    let proxy = proxy.trim();
+    if proxy.contains("://") {
+        proxy.to_string()
+    } else {
+        format!("{scheme}://{proxy}")
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split([',', ';'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let vars = HashMap::from([
+            ("HTTPS_PROXY", "proxy.corp:3128"),
+            ("https_proxy", "ignored:1"),
+            ("all_proxy", "socks5://socks.corp:1080"),
+            ("NO_PROXY", "localhost, .internal"),
+        ]);
+        let proxy = SystemProxy::from_vars(|name| vars.get(name).map(|v| v.to_string()));
+        assert_eq!(proxy.https.as_deref(), Some("http://proxy.corp:3128"));
+        assert_eq!(proxy.http, None);
+        assert_eq!(
+            proxy.proxy_for(&url("wss://api-eu.po.market/socket.io/")),
+            Some("http://proxy.corp:3128".to_string())
+        );
+        assert_eq!(
+            proxy.proxy_for(&url("ws://api-eu.po.market/")),
+            Some("socks5://socks.corp:1080".to_string())
+        );
+        assert_eq!(proxy.proxy_for(&url("wss://ws.internal/")), None);
+        assert_eq!(proxy.proxy_for(&url("wss://localhost:8080/")), None);
+        assert!(SystemProxy::from_vars(|_| Some(" ".into())).is_empty());
+    }
+
+    #[test]
+    fn test_parse_netsh() {
+        let output = "\r\nCurrent WinHTTP proxy settings:\r\n\r\n    \
+                      Proxy Server(s) :  proxy.corp:8080\r\n    \
+                      Bypass List     :  *.local;<local>\r\n";
+        let proxy = SystemProxy::parse_netsh(output);
+        assert_eq!(proxy.all.as_deref(), Some("http://proxy.corp:8080"));
+        assert!(proxy.bypasses("intranet"));
+        assert!(proxy.bypasses("printer.local"));
+        assert!(!proxy.bypasses("po.market"));
+
+        let output = "    Proxy Server(s) :  http=web:80;https=secure:443;socks=s:1080\n";
+        let proxy = SystemProxy::parse_netsh(output);
+        assert_eq!(proxy.http.as_deref(), Some("http://web:80"));
+        assert_eq!(proxy.https.as_deref(), Some("http://secure:443"));
+        assert_eq!(proxy.all.as_deref(), Some("socks5://s:1080"));
+
+        let direct = "Current WinHTTP proxy settings:\n\n    Direct access (no proxy server).\n";
+        assert!(SystemProxy::parse_netsh(direct).is_empty());
+    }
+
+    #[test]
+    fn test_parse_scutil() {
+        let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  FTPPassive : 1
+  HTTPEnable : 0
+  HTTPPort : 80
+  HTTPProxy : web.corp
+  HTTPSEnable : 1
+  HTTPSPort : 8443
+  HTTPSProxy : secure.corp
+  SOCKSEnable : 0
+}";
+        let proxy = SystemProxy::parse_scutil(output);
+        assert_eq!(proxy.http, None);
+        assert_eq!(proxy.https.as_deref(), Some("http://secure.corp:8443"));
+        assert_eq!(proxy.all, None);
+        assert_eq!(proxy.bypass, vec!["*.local", "169.254/16"]);
+        assert!(proxy.bypasses("mac.local"));
+    }
+}
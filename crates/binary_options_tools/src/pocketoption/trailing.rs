@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::pocketoption::{
+    candle::SubscriptionType,
+    error::PocketError,
+    modules::subscriptions::StreamUpdate,
+    pocket_client::PocketOption,
+    types::{Deal, PendingOrder},
+};
+
+/// `command` of a pending order placed as a Call, see `PocketOption::open_pending_order`.
+const CALL: u32 = 0;
+
+/// Settings of a [`TrailingOrder`].
+#[derive(Clone, Debug)]
+pub struct TrailingConfig {
+    /// Distance kept between the market price and the open price of the order.
+    pub distance: Decimal,
+    /// Smallest move of the open price worth re-submitting the order for.
+    pub min_step: Decimal,
+}
+
+impl TrailingConfig {
+    pub fn new(distance: Decimal) -> Self {
+        Self {
+            distance,
+            min_step: Decimal::ZERO,
+        }
+    }
+
+    pub fn with_min_step(mut self, min_step: Decimal) -> Self {
+        self.min_step = min_step;
+        self
+    }
+}
+
+/// What happened to a [`TrailingOrder`].
+#[derive(Debug)]
+pub enum TrailingEvent {
+    /// The order was re-submitted at a new open price.
+    Moved(PendingOrder),
+    /// The order was filled, trailing stopped.
+    Filled(Box<Deal>),
+    /// The order was cancelled with [`TrailingOrder::cancel`], trailing stopped.
+    Cancelled,
+    /// Re-submitting or cancelling the order failed, trailing stopped. The order may be
+    /// cancelled without a replacement, check `PocketOption::get_pending_deals`.
+    Failed(PocketError),
+}
+
+/// A pending order that follows the market until it is filled or cancelled.
+///
+/// The open price of a Call is kept `distance` above the market and only moves down, the one
+/// of a Put is kept `distance` below and only moves up, so the order fills once the market
+/// turns by `distance`. Each move cancels the order and opens it again at the new price.
+///
+/// A fill is detected from the opened deals: the first deal on the same asset with the same
+/// amount and direction. Dropping the trailing order stops it and leaves the current order as is.
+pub struct TrailingOrder {
+    receiver: mpsc::Receiver<TrailingEvent>,
+    current: Arc<RwLock<PendingOrder>>,
+    cancel: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl TrailingOrder {
+    pub(crate) fn start(client: PocketOption, order: PendingOrder, config: TrailingConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(16);
+        let (cancel, cancelled) = oneshot::channel();
+        let current = Arc::new(RwLock::new(order));
+        let task = tokio::spawn(trail(client, config, current.clone(), sender, cancelled));
+        Self {
+            receiver,
+            current,
+            cancel: Some(cancel),
+            task,
+        }
+    }
+
+    /// Waits for the next event, `None` once trailing stopped.
+    pub async fn next(&mut self) -> Option<TrailingEvent> {
+        self.receiver.recv().await
+    }
+
+    /// The order as currently placed.
+    pub async fn order(&self) -> PendingOrder {
+        self.current.read().await.clone()
+    }
+
+    /// Cancels the order on the server and stops trailing, followed by a
+    /// [`TrailingEvent::Cancelled`] or [`TrailingEvent::Failed`] event.
+    pub fn cancel(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
+impl Drop for TrailingOrder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Open price the order should move to for the `market` price, if it should move at all.
+fn trailed_price(
+    order: &PendingOrder,
+    market: Decimal,
+    config: &TrailingConfig,
+) -> Option<Decimal> {
+    if order.command == CALL {
+        let target = market + config.distance;
+        (target < order.open_price - config.min_step).then_some(target)
+    } else {
+        let target = market - config.distance;
+        (target > order.open_price + config.min_step).then_some(target)
+    }
+}
+
+/// Whether `deal` is the fill of `order`, trailed since `since`.
+fn is_fill(order: &PendingOrder, deal: &Deal, since: DateTime<Utc>) -> bool {
+    deal.asset == order.symbol
+        && deal.amount == order.amount
+        && deal.command == order.command as i32
+        && deal.open_timestamp >= since
+}
+
+async fn trail(
+    client: PocketOption,
+    config: TrailingConfig,
+    current: Arc<RwLock<PendingOrder>>,
+    events: mpsc::Sender<TrailingEvent>,
+    mut cancelled: oneshot::Receiver<()>,
+) {
+    let since = Utc::now() - chrono::Duration::seconds(1);
+    let mut opened = client.opened_deal_events();
+    let mut order = current.read().await.clone();
+    let stream = match client
+        .subscribe(order.symbol.clone(), SubscriptionType::none())
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            let _ = events.send(TrailingEvent::Failed(e)).await;
+            return;
+        }
+    };
+    let mut ticks = stream.to_update_stream();
+
+    loop {
+        tokio::select! {
+            biased;
+            cancel = &mut cancelled => {
+                if cancel.is_err() {
+                    // The trailing order was dropped.
+                    return;
+                }
+                let event = match client.cancel_pending_order(order.ticket.to_string()).await {
+                    Ok(_) => TrailingEvent::Cancelled,
+                    Err(e) => TrailingEvent::Failed(e),
+                };
+                let _ = events.send(event).await;
+                return;
+            }
+            deal = opened.recv() => match deal {
+                Ok(deal) if is_fill(&order, &deal, since) => {
+                    let _ = events.send(TrailingEvent::Filled(Box::new(deal))).await;
+                    return;
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(target: "TrailingOrder", "Missed {} deal events while trailing {}", missed, order.ticket);
+                }
+                Err(RecvError::Closed) => return,
+            },
+            update = ticks.next() => {
+                let price = match update {
+                    Some(Ok(StreamUpdate::Tick { price, .. })) => price,
+                    Some(Ok(StreamUpdate::Candle(_))) => continue,
+                    Some(Err(e)) => {
+                        debug!(target: "TrailingOrder", "Tick stream error for {}: {}", order.symbol, e);
+                        continue;
+                    }
+                    None => return,
+                };
+                let Some(target) = trailed_price(&order, price, &config) else {
+                    continue;
+                };
+                match resubmit(&client, &order, target).await {
+                    Ok(moved) => {
+                        order = moved;
+                        *current.write().await = order.clone();
+                        let _ = events.send(TrailingEvent::Moved(order.clone())).await;
+                    }
+                    Err(e) => {
+                        let _ = events.send(TrailingEvent::Failed(e)).await;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Cancels `order` and opens it again at `open_price`.
+async fn resubmit(
+    client: &PocketOption,
+    order: &PendingOrder,
+    open_price: Decimal,
+) -> Result<PendingOrder, PocketError> {
+    client
+        .cancel_pending_order(order.ticket.to_string())
+        .await?;
+    client
+        .open_pending_order(
+            order.open_type,
+            order.amount,
+            order.symbol.clone(),
+            order.open_time.clone(),
+            open_price,
+            order.timeframe,
+            order.min_payout,
+            order.command,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn order(command: u32, open_price: Decimal) -> PendingOrder {
+        PendingOrder {
+            ticket: Uuid::new_v4(),
+            open_type: 1,
+            amount: dec!(10),
+            symbol: "EURUSD_otc".to_string(),
+            open_time: String::new(),
+            open_price,
+            timeframe: 60,
+            min_payout: 80,
+            command,
+            date_created: String::new(),
+            id: 1,
+        }
+    }
+
+    #[test]
+    fn test_trailed_price() {
+        let config = TrailingConfig::new(dec!(0.001)).with_min_step(dec!(0.0002));
+
+        // A Call follows the market down only.
+        let call = order(CALL, dec!(1.1010));
+        assert_eq!(
+            trailed_price(&call, dec!(1.0990), &config),
+            Some(dec!(1.1000))
+        );
+        assert_eq!(
+            trailed_price(&call, dec!(1.0999), &config),
+            None,
+            "below min_step"
+        );
+        assert_eq!(trailed_price(&call, dec!(1.1005), &config), None);
+
+        // A Put follows the market up only.
+        let put = order(1, dec!(1.0990));
+        assert_eq!(
+            trailed_price(&put, dec!(1.1010), &config),
+            Some(dec!(1.1000))
+        );
+        assert_eq!(trailed_price(&put, dec!(1.0995), &config), None);
+    }
+}
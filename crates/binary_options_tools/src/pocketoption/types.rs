@@ -1,19 +1,20 @@
 use core::fmt;
 use std::hash::Hash;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::atomic::{AtomicBool, Ordering},
 };
 
 use binary_options_tools_core::{reimports::Message, traits::Rule};
 use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 use uuid::Uuid;
 
 use crate::pocketoption::error::{PocketError, PocketResult};
-use crate::pocketoption::utils::normalize_timestamp;
+use crate::pocketoption::protocol::{self, AssetV1, DealV1, StreamDataV1};
 
 // Audit Note: Financial values (amount, price, profit) have been migrated to
 // `rust_decimal::Decimal` to prevent precision errors in financial calculations.
@@ -31,18 +32,49 @@ pub struct ServerTime {
     pub last_updated: DateTime<Utc>,
     /// Calculated offset between server time and local time
     pub offset: Duration,
+    /// Offsets (in seconds) of the latest server timestamps, `offset` is their median.
+    pub(crate) samples: VecDeque<i64>,
+    /// A sample further than this from `offset` discards the others and re-syncs the clock.
+    pub drift_threshold: Duration,
+    /// Number of re-syncs caused by drift.
+    pub(crate) resyncs: u64,
 }
 
+/// Number of offset samples kept to smooth out network delays.
+const OFFSET_SAMPLES: usize = 30;
+
+/// Default [`ServerTime::drift_threshold`].
+pub const DEFAULT_DRIFT_THRESHOLD: Duration = Duration::seconds(5);
+
 impl Default for ServerTime {
     fn default() -> Self {
         Self {
             last_server_time: 0,
             last_updated: Utc::now(),
             offset: Duration::zero(),
+            samples: VecDeque::with_capacity(OFFSET_SAMPLES),
+            drift_threshold: DEFAULT_DRIFT_THRESHOLD,
+            resyncs: 0,
         }
     }
 }
 
+/// How much the synced server time can be trusted, see [`ServerTime::quality_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ServerTimeQuality {
+    /// Server time minus local time, in seconds.
+    pub offset: i64,
+    /// Time since the last server timestamp, `None` before the first one.
+    #[serde(serialize_with = "crate::pocketoption::stats::opt_millis")]
+    pub staleness: Option<std::time::Duration>,
+    /// Standard deviation of the offset samples, in seconds.
+    pub jitter: f64,
+    /// Number of offset samples since the last re-sync.
+    pub samples: usize,
+    /// Number of re-syncs caused by the offset drifting past the threshold.
+    pub resyncs: u64,
+}
+
 impl ServerTime {
     /// Update server time with a new timestamp from the server
     ///
@@ -52,7 +84,11 @@ impl ServerTime {
     /// # Arguments
     /// * `server_timestamp` - Unix timestamp from the server as i64
     pub fn update(&mut self, server_timestamp: i64) {
-        let now = Utc::now();
+        self.update_at(server_timestamp, Utc::now())
+    }
+
+    /// Same as [`ServerTime::update`], with the local time `now` read from a clock.
+    pub fn update_at(&mut self, server_timestamp: i64, now: DateTime<Utc>) {
         let local_timestamp = now.timestamp();
 
         self.last_server_time = server_timestamp;
@@ -60,7 +96,59 @@ impl ServerTime {
 
         // Calculate offset: server time - local time
         let offset_seconds = server_timestamp - local_timestamp;
-        self.offset = Duration::seconds(offset_seconds);
+        if !self.samples.is_empty()
+            && (offset_seconds - self.offset.num_seconds()).abs()
+                > self.drift_threshold.num_seconds()
+        {
+            tracing::warn!(
+                target: "ServerTime",
+                "Server time drifted from {}s to {}s, re-syncing",
+                self.offset.num_seconds(),
+                offset_seconds
+            );
+            self.samples.clear();
+            self.resyncs += 1;
+        }
+        if self.samples.len() == OFFSET_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(offset_seconds);
+
+        let mut sorted: Vec<i64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        self.offset = Duration::seconds(sorted[sorted.len() / 2]);
+    }
+
+    /// Standard deviation of the offset samples, in seconds.
+    pub fn jitter(&self) -> f64 {
+        if self.samples.len() < 2 {
+            return 0.0;
+        }
+        let n = self.samples.len() as f64;
+        let mean = self.samples.iter().sum::<i64>() as f64 / n;
+        let variance = self
+            .samples
+            .iter()
+            .map(|&s| (s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        variance.sqrt()
+    }
+
+    /// Offset, staleness and jitter of the synced time at the local time `now`.
+    pub fn quality_at(&self, now: DateTime<Utc>) -> ServerTimeQuality {
+        let staleness = if self.last_server_time > 0 {
+            now.signed_duration_since(self.last_updated).to_std().ok()
+        } else {
+            None
+        };
+        ServerTimeQuality {
+            offset: self.offset.num_seconds(),
+            staleness,
+            jitter: self.jitter(),
+            samples: self.samples.len(),
+            resyncs: self.resyncs,
+        }
     }
 
     /// Convert local time to estimated server time
@@ -92,7 +180,11 @@ impl ServerTime {
     /// # Returns
     /// Current estimated server timestamp as i64
     pub fn get_server_time(&self) -> i64 {
-        let now = Utc::now();
+        self.get_server_time_at(Utc::now())
+    }
+
+    /// Estimated server time at the local time `now`.
+    pub fn get_server_time_at(&self, now: DateTime<Utc>) -> i64 {
         let elapsed = now.signed_duration_since(self.last_updated);
         self.last_server_time + elapsed.num_seconds()
     }
@@ -102,9 +194,23 @@ impl ServerTime {
     /// # Returns
     /// True if the server time data is considered stale
     pub fn is_stale(&self) -> bool {
-        let now = Utc::now();
+        self.is_stale_at(Utc::now())
+    }
+
+    /// Whether the data is stale at the local time `now`.
+    pub fn is_stale_at(&self, now: DateTime<Utc>) -> bool {
         now.signed_duration_since(self.last_updated) > Duration::seconds(30)
     }
+
+    /// Check if at least one server timestamp was received and it is not stale
+    pub fn is_synced(&self) -> bool {
+        self.is_synced_at(Utc::now())
+    }
+
+    /// Whether the server time is synced at the local time `now`.
+    pub fn is_synced_at(&self, now: DateTime<Utc>) -> bool {
+        self.last_server_time > 0 && !self.is_stale_at(now)
+    }
 }
 
 impl fmt::Display for ServerTime {
@@ -138,22 +244,7 @@ impl<'de> Deserialize<'de> for StreamData {
     where
         D: serde::Deserializer<'de>,
     {
-        let vec: Vec<Vec<serde_json::Value>> = Vec::deserialize(deserializer)?;
-        if vec.len() != 1 {
-            return Err(serde::de::Error::custom("Invalid StreamData format"));
-        }
-        if vec[0].len() != 3 {
-            return Err(serde::de::Error::custom("Invalid StreamData format"));
-        }
-
-        let price_f64 = vec[0][2].as_f64().unwrap_or(0.0);
-        let price = Decimal::from_f64_retain(price_f64).unwrap_or_default();
-
-        Ok(StreamData {
-            symbol: vec[0][0].as_str().unwrap_or_default().to_string(),
-            timestamp: normalize_timestamp(vec[0][1].as_f64().unwrap_or(0.0)),
-            price,
-        })
+        protocol::deserialize_with(deserializer, StreamDataV1::decode)
     }
 }
 
@@ -187,6 +278,33 @@ impl StreamData {
 /// using a read-write lock for concurrent access.
 pub type ServerTimeState = tokio::sync::RwLock<ServerTime>;
 
+/// A state condition that can be awaited with `PocketOption::wait_for`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    /// Asset information has been received.
+    AssetsLoaded,
+    /// The account balance has been received.
+    BalanceKnown,
+    /// A server timestamp was received recently (see `ServerTime::is_synced`).
+    ServerTimeSynced,
+    /// There is at least one active subscription for the asset.
+    SubscriptionActive(String),
+    /// At least one price update was received for the asset since connecting.
+    FirstTick(String),
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::AssetsLoaded => write!(f, "assets loaded"),
+            Condition::BalanceKnown => write!(f, "balance known"),
+            Condition::ServerTimeSynced => write!(f, "server time synced"),
+            Condition::SubscriptionActive(asset) => write!(f, "subscription active for {asset}"),
+            Condition::FirstTick(asset) => write!(f, "first tick for {asset}"),
+        }
+    }
+}
+
 /// Simple rule implementation for when the websocket data is sent using 2 messages
 /// The first one telling which message type it is, and the second one containing the actual data.
 pub struct TwoStepRule {
@@ -383,7 +501,7 @@ pub struct Asset {
     pub asset_type: AssetType,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum AssetType {
     Stock,
@@ -413,7 +531,7 @@ impl Asset {
     /// Note: Time validation has been removed to allow trading at any expiration time.
     pub fn validate(&self, time: u32) -> PocketResult<()> {
         if !self.is_active {
-            return Err(PocketError::InvalidAsset("Asset is not active".into()));
+            return Err(PocketError::AssetInactive(self.symbol.clone()));
         }
         if time == 0 {
             return Err(PocketError::InvalidAsset("Time must be a positive value".into()));
@@ -427,41 +545,7 @@ impl<'de> Deserialize<'de> for Asset {
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        #[allow(dead_code)] // Allow dead code because many fields are unused but kept for wire compatibility
-        struct AssetRawTuple(
-            i32,                   // 0: id (used)
-            String,                // 1: symbol (used)
-            String,                // 2: name (used)
-            AssetType,             // 3: asset_type (used)
-            serde::de::IgnoredAny, // 4: unused
-            i32,                   // 5: payout (used)
-            serde::de::IgnoredAny, // 6: unused
-            serde::de::IgnoredAny, // 7: unused
-            serde::de::IgnoredAny, // 8: unused
-            i32,                   // 9: is_otc (used, 1 for true, 0 for false)
-            serde::de::IgnoredAny, // 10: unused
-            serde::de::IgnoredAny, // 11: unused
-            serde::de::IgnoredAny, // 12: unused
-            serde::de::IgnoredAny, // 13: unused
-            bool,                  // 14: is_active (used)
-            Vec<CandleLength>,     // 15: allowed_candles (used)
-            serde::de::IgnoredAny, // 16: unused
-            serde::de::IgnoredAny, // 17: unused
-            serde::de::IgnoredAny, // 18: unused
-        );
-
-        let raw: AssetRawTuple = AssetRawTuple::deserialize(deserializer)?;
-        Ok(Asset {
-            id: raw.0,
-            symbol: raw.1,
-            name: raw.2,
-            asset_type: raw.3,
-            payout: raw.5,
-            is_otc: raw.9 == 1,
-            is_active: raw.14,
-            allowed_candles: raw.15,
-        })
+        protocol::deserialize_with(deserializer, AssetV1::decode)
     }
 }
 
@@ -496,6 +580,30 @@ impl Assets {
         self.0.values().filter(|a| a.is_active)
     }
 
+    /// Assets whose symbol or name contains `query`, ignoring case, sorted by symbol.
+    pub fn search(&self, query: &str) -> Vec<&Asset> {
+        let query = query.to_lowercase();
+        self.sorted(|a| {
+            a.symbol.to_lowercase().contains(&query) || a.name.to_lowercase().contains(&query)
+        })
+    }
+
+    /// Assets of the given type, sorted by symbol.
+    pub fn by_type(&self, asset_type: AssetType) -> Vec<&Asset> {
+        self.sorted(|a| a.asset_type == asset_type)
+    }
+
+    /// OTC assets, sorted by symbol.
+    pub fn otc(&self) -> Vec<&Asset> {
+        self.sorted(|a| a.is_otc)
+    }
+
+    fn sorted(&self, filter: impl Fn(&Asset) -> bool) -> Vec<&Asset> {
+        let mut assets: Vec<&Asset> = self.0.values().filter(|a| filter(a)).collect();
+        assets.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        assets
+    }
+
     pub fn active(&self) -> Self {
         let active = self
             .0
@@ -505,6 +613,118 @@ impl Assets {
             .collect();
         Assets(active)
     }
+
+    /// Lists what changed between `self` and a newer asset list, sorted by symbol.
+    pub fn diff(&self, newer: &Assets) -> Vec<AssetChange> {
+        let mut changes = Vec::new();
+        for (symbol, asset) in &newer.0 {
+            let Some(old) = self.0.get(symbol) else {
+                changes.push(AssetChange::Added {
+                    symbol: symbol.clone(),
+                    payout: asset.payout,
+                    is_active: asset.is_active,
+                });
+                continue;
+            };
+            match (old.is_active, asset.is_active) {
+                (false, true) => changes.push(AssetChange::Opened {
+                    symbol: symbol.clone(),
+                }),
+                (true, false) => changes.push(AssetChange::Closed {
+                    symbol: symbol.clone(),
+                }),
+                _ => {}
+            }
+            if old.payout != asset.payout {
+                changes.push(AssetChange::PayoutChanged {
+                    symbol: symbol.clone(),
+                    old: old.payout,
+                    new: asset.payout,
+                });
+            }
+        }
+        changes.extend(
+            self.0
+                .keys()
+                .filter(|symbol| !newer.0.contains_key(*symbol))
+                .map(|symbol| AssetChange::Removed {
+                    symbol: symbol.clone(),
+                }),
+        );
+        changes.sort_by(|a, b| a.symbol().cmp(b.symbol()));
+        changes
+    }
+}
+
+/// A change between two consecutive asset lists received from the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AssetChange {
+    /// The asset was not part of the previous list.
+    Added {
+        symbol: String,
+        payout: i32,
+        is_active: bool,
+    },
+    /// The asset is no longer listed.
+    Removed {
+        symbol: String,
+    },
+    /// The asset became tradable, e.g. an OTC pair opening.
+    Opened {
+        symbol: String,
+    },
+    /// The asset stopped being tradable.
+    Closed {
+        symbol: String,
+    },
+    PayoutChanged {
+        symbol: String,
+        old: i32,
+        new: i32,
+    },
+}
+
+/// A frame routed to a module that could not be parsed, usually a sign the server changed
+/// the format of a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseFailure {
+    /// Module the frame was routed to.
+    pub module: String,
+    /// The frame as received, binary frames are decoded as lossy UTF-8.
+    pub raw: String,
+    pub binary: bool,
+    pub error: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ParseFailure {
+    pub fn new(module: &str, msg: &Message, error: impl fmt::Display, now: DateTime<Utc>) -> Self {
+        let (raw, binary) = match msg {
+            Message::Text(text) => (text.to_string(), false),
+            Message::Binary(data) => (String::from_utf8_lossy(data).into_owned(), true),
+            other => (other.to_string(), false),
+        };
+        Self {
+            module: module.to_string(),
+            raw,
+            binary,
+            error: error.to_string(),
+            timestamp: now,
+        }
+    }
+}
+
+impl AssetChange {
+    pub fn symbol(&self) -> &str {
+        match self {
+            AssetChange::Added { symbol, .. }
+            | AssetChange::Removed { symbol }
+            | AssetChange::Opened { symbol }
+            | AssetChange::Closed { symbol }
+            | AssetChange::PayoutChanged { symbol, .. } => symbol,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for Assets {
@@ -525,6 +745,130 @@ pub enum Action {
     Put,  // Sell
 }
 
+/// Per-order options for `PocketOption::trade_with_options`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeOptions {
+    /// Places the order even if an identical one was placed within the duplicate trade window.
+    pub allow_duplicate: bool,
+    /// Refuses the order if the asset payout (in percent) is below this value.
+    pub min_payout: Option<i32>,
+    /// How long to wait for the payout to reach `min_payout` before refusing the order.
+    pub payout_wait: std::time::Duration,
+}
+
+impl TradeOptions {
+    pub fn allow_duplicate(mut self, allow: bool) -> Self {
+        self.allow_duplicate = allow;
+        self
+    }
+
+    pub fn min_payout(mut self, payout: i32) -> Self {
+        self.min_payout = Some(payout);
+        self
+    }
+
+    /// Waits up to `wait` for the payout to recover instead of refusing the order at once.
+    pub fn payout_wait(mut self, wait: std::time::Duration) -> Self {
+        self.payout_wait = wait;
+        self
+    }
+}
+
+/// One order of `PocketOption::trade_batch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeRequest {
+    pub asset: String,
+    pub action: Action,
+    /// Duration of the trade in seconds.
+    pub time: u32,
+    pub amount: Decimal,
+    pub options: TradeOptions,
+}
+
+impl TradeRequest {
+    pub fn new(asset: impl ToString, action: Action, time: u32, amount: Decimal) -> Self {
+        Self {
+            asset: asset.to_string(),
+            action,
+            time,
+            amount,
+            options: TradeOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: TradeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Key under which identical orders are detected as duplicates.
+    pub(crate) fn fingerprint(&self) -> (String, Action, u32, Decimal) {
+        (self.asset.clone(), self.action, self.time, self.amount)
+    }
+}
+
+/// Minimum and maximum trade amount accepted for an account currency.
+///
+/// PocketOption enforces its limits in the account currency. The client only knows the USD
+/// limits it always enforced, other currencies are checked against `Config::amount_limits`
+/// and otherwise left to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmountLimits {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+impl AmountLimits {
+    /// The 1 to 20 000 range enforced on USD accounts, also used while the currency is unknown.
+    pub const USD: AmountLimits = AmountLimits {
+        min: dec!(1.0),
+        max: dec!(20000.0),
+    };
+
+    /// No client side bounds, only amounts that are not positive are rejected.
+    pub const UNBOUNDED: AmountLimits = AmountLimits {
+        min: Decimal::ZERO,
+        max: Decimal::MAX,
+    };
+
+    /// Returns the limits for the given ISO currency code (case insensitive).
+    ///
+    /// The matching entry of `overrides` wins, USD gets [`AmountLimits::USD`] and any other
+    /// currency [`AmountLimits::UNBOUNDED`].
+    pub fn for_currency(currency: &str, overrides: &HashMap<String, AmountLimits>) -> Self {
+        if let Some(limits) = overrides
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+            .map(|(_, limits)| *limits)
+        {
+            return limits;
+        }
+        if currency.eq_ignore_ascii_case("USD") {
+            Self::USD
+        } else {
+            Self::UNBOUNDED
+        }
+    }
+
+    /// Checks that `amount` is within the limits, returning a descriptive error otherwise.
+    pub fn validate(&self, amount: Decimal) -> PocketResult<()> {
+        if amount <= Decimal::ZERO || amount < self.min || amount > self.max {
+            return Err(PocketError::AmountOutOfRange {
+                amount,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for AmountLimits {
+    fn default() -> Self {
+        Self::USD
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FailOpenOrder {
     pub error: String,
@@ -561,7 +905,8 @@ pub struct OpenOrder {
     pub time: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A deal as reported by the server, decoded through [`DealV1`].
+#[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Deal {
     pub id: Uuid,
@@ -597,6 +942,20 @@ pub struct Deal {
     pub amount_usd: Option<Decimal>,
     #[serde(rename = "amountUSD")]
     pub amount_usd2: Option<Decimal>,
+    /// Open price minus the price quoted when the order was sent (the other way round for a
+    /// put), positive when the deal opened at a worse price. Only set on the deals returned
+    /// by `trade` when a quote of the asset was known, it is not sent by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slippage: Option<Decimal>,
+}
+
+impl<'de> Deserialize<'de> for Deal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        protocol::deserialize_with(deserializer, DealV1::decode)
+    }
 }
 
 impl Hash for Deal {
@@ -608,6 +967,16 @@ impl Hash for Deal {
 
 impl Eq for Deal {}
 
+impl Deal {
+    /// Slippage of the deal against `quote`, see [`Deal::slippage`].
+    pub fn slippage_from(&self, quote: Decimal) -> Decimal {
+        match self.command {
+            0 => self.open_price - quote,
+            _ => quote - self.open_price,
+        }
+    }
+}
+
 impl OpenOrder {
     pub fn new(
         amount: Decimal,
@@ -635,6 +1004,85 @@ impl std::cmp::PartialEq<Uuid> for Deal {
     }
 }
 
+/// Outcome of closing a deal before its expiry, built from the `successcloseOrder` confirmation.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EarlyClose {
+    pub deal_id: Uuid,
+    /// Amount credited back to the balance: the stake plus the (possibly negative) profit.
+    pub returned: Decimal,
+    pub deal: Deal,
+}
+
+impl From<Deal> for EarlyClose {
+    fn from(deal: Deal) -> Self {
+        Self {
+            deal_id: deal.id,
+            returned: (deal.amount + deal.profit).max(Decimal::ZERO),
+            deal,
+        }
+    }
+}
+
+/// Where a deal is in its lifecycle, see `PocketOption::deal_status`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "status", content = "deal", rename_all = "snake_case")]
+pub enum DealStatus {
+    /// The deal is running.
+    Open(Deal),
+    /// The deal reached its expiry but its result hasn't been received yet.
+    Closing(Deal),
+    /// The deal is closed, with its result.
+    Closed(Deal),
+}
+
+impl DealStatus {
+    /// Status of a deal that is still in the opened deals at `now`.
+    pub fn opened(deal: Deal, now: DateTime<Utc>) -> Self {
+        if deal.close_timestamp <= now {
+            Self::Closing(deal)
+        } else {
+            Self::Open(deal)
+        }
+    }
+
+    pub fn deal(&self) -> &Deal {
+        match self {
+            Self::Open(deal) | Self::Closing(deal) | Self::Closed(deal) => deal,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed(_))
+    }
+}
+
+/// Kind of account a balance belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    Demo,
+    Real,
+    Tournament,
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Demo => write!(f, "demo"),
+            Self::Real => write!(f, "real"),
+            Self::Tournament => write!(f, "tournament"),
+        }
+    }
+}
+
+/// Last balance reported by the server for an account type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountBalance {
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
 pub fn serialize_action<S>(action: &Action, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -811,4 +1259,97 @@ mod tests {
         assert!(formatted.contains("\"time\":60"));
         dbg!(formatted);
     }
+
+    #[test]
+    fn test_amount_limits_for_currency() {
+        let none = HashMap::new();
+        assert_eq!(AmountLimits::for_currency("usd", &none), AmountLimits::USD);
+        let brl = AmountLimits::for_currency("BRL", &none);
+        assert_eq!(brl, AmountLimits::UNBOUNDED);
+        assert!(brl.validate(Decimal::from(1_000_000)).is_ok());
+        assert!(brl.validate(Decimal::ZERO).is_err());
+        assert!(brl.validate(Decimal::from(-5)).is_err());
+
+        let overrides = HashMap::from([(
+            "brl".to_string(),
+            AmountLimits {
+                min: Decimal::from(5),
+                max: Decimal::from(100_000),
+            },
+        )]);
+        let brl = AmountLimits::for_currency("BRL", &overrides);
+        assert_eq!(brl.min, Decimal::from(5));
+        assert!(brl.validate(Decimal::from(4)).is_err());
+        assert!(brl.validate(Decimal::from(5)).is_ok());
+        assert_eq!(
+            AmountLimits::for_currency("USD", &overrides),
+            AmountLimits::USD
+        );
+
+        let usd = AmountLimits::default();
+        assert!(usd
+            .validate(Decimal::from_f64_retain(0.5).unwrap())
+            .is_err());
+        assert!(usd.validate(Decimal::from(20001)).is_err());
+        assert!(usd.validate(Decimal::from(20000)).is_ok());
+    }
+
+    #[test]
+    fn test_deal_status() {
+        let deal: Deal = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "openTime": "", "closeTime": "",
+            "openTimestamp": 0, "closeTimestamp": 60,
+            "uid": 1, "amount": 1.0, "profit": 0.0,
+            "percentProfit": 92, "percentLoss": 100,
+            "openPrice": 1.0, "closePrice": 0.0,
+            "command": 0, "asset": "EURUSD_otc", "isDemo": 1,
+            "copyTicket": "", "openMs": 0, "optionType": 100,
+            "currency": "USD"
+        }))
+        .unwrap();
+        let before = DateTime::from_timestamp(30, 0).unwrap();
+        let after = DateTime::from_timestamp(61, 0).unwrap();
+        assert_eq!(
+            DealStatus::opened(deal.clone(), before),
+            DealStatus::Open(deal.clone())
+        );
+        let closing = DealStatus::opened(deal.clone(), after);
+        assert_eq!(closing, DealStatus::Closing(deal.clone()));
+        assert!(!closing.is_closed());
+        assert_eq!(closing.deal().id, deal.id);
+        let json = serde_json::to_value(DealStatus::Closed(deal)).unwrap();
+        assert_eq!(json["status"], "closed");
+    }
+
+    #[test]
+    fn test_server_time_quality_and_resync() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut time = ServerTime::default();
+        let quality = time.quality_at(start);
+        assert_eq!(quality.staleness, None);
+        assert_eq!(quality.samples, 0);
+
+        // Offsets of 10, 11, 10 and 12 seconds: the median is kept, the spread is the jitter.
+        for (i, offset) in [10, 11, 10, 12].into_iter().enumerate() {
+            let now = start + Duration::seconds(i as i64);
+            time.update_at(now.timestamp() + offset, now);
+        }
+        assert_eq!(time.offset, Duration::seconds(11));
+        let quality = time.quality_at(start + Duration::seconds(8));
+        assert_eq!(quality.offset, 11);
+        assert_eq!(quality.staleness, Some(std::time::Duration::from_secs(5)));
+        assert_eq!(quality.samples, 4);
+        assert!((quality.jitter - 0.829).abs() < 0.001);
+        assert_eq!(quality.resyncs, 0);
+
+        // A jump past the drift threshold drops the old samples.
+        let now = start + Duration::seconds(10);
+        time.update_at(now.timestamp() - 20, now);
+        let quality = time.quality_at(now);
+        assert_eq!(quality.offset, -20);
+        assert_eq!(quality.samples, 1);
+        assert_eq!(quality.jitter, 0.0);
+        assert_eq!(quality.resyncs, 1);
+    }
 }
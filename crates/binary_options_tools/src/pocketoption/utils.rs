@@ -12,6 +12,7 @@ use std::time::Duration as StdDuration;
 use crate::pocketoption::{
     error::{PocketError, PocketResult},
     state::State,
+    system_proxy::SystemProxy,
 };
 use crate::utils::init_crypto_provider;
 use serde_json::Value;
@@ -151,7 +152,7 @@ pub async fn get_public_ip() -> PocketResult<String> {
 
 fn base64_encode(input: &[u8]) -> String {
     const CHARSET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut result = String::with_capacity((input.len() + 2) / 3 * 4);
+    let mut result = String::with_capacity(input.len().div_ceil(3) * 4);
     for chunk in input.chunks(3) {
         match chunk.len() {
             3 => {
@@ -391,7 +392,17 @@ pub async fn try_connect(
         _ => return Err(ConnectorError::Custom(format!("Unsupported scheme: {}", t_url.scheme()))),
     });
 
-    let socket = if let Some(proxy_str) = &state.proxy {
+    let proxy = match &state.proxy {
+        Some(proxy) => Some(proxy.clone()),
+        None if state.system_proxy => {
+            let detected = tokio::task::spawn_blocking(SystemProxy::detect)
+                .await
+                .unwrap_or_default();
+            detected.proxy_for(&t_url)
+        }
+        None => None,
+    };
+    let socket = if let Some(proxy_str) = &proxy {
         let proxy_url = Url::parse(proxy_str)
             .map_err(|e| ConnectorError::Custom(format!("Invalid proxy URL: {e}")))?;
         let proxy_host = proxy_url
@@ -440,13 +451,14 @@ pub async fn try_connect(
             return Err(ConnectorError::Custom(format!("Unsupported proxy scheme: {}", proxy_url.scheme())));
         }
     } else {
-        let tcp = tokio::time::timeout(
-            per_url_connect_timeout(),
-            TcpStream::connect((target_host, target_port)),
-        )
+        let tcp = tokio::time::timeout(per_url_connect_timeout(), async {
+            let addrs = state.dns.resolve(target_host, target_port).await?;
+            TcpStream::connect(&addrs[..])
+                .await
+                .map_err(|e| ConnectorError::Custom(format!("Failed to connect to {target_host}:{target_port}: {e}")))
+        })
         .await
-        .map_err(|_| ConnectorError::Timeout)?
-        .map_err(|e| ConnectorError::Custom(format!("Failed to connect to {target_host}:{target_port}: {e}")))?;
+        .map_err(|_| ConnectorError::Timeout)??;
         MaybeTlsStream::Plain(tcp)
     };
 
@@ -509,6 +521,7 @@ pub async fn try_connect(
     .map_err(|_| ConnectorError::Timeout)?
     .map_err(|e| ConnectorError::Custom(e.to_string()))?;
 
+    state.set_connected_url(url);
     Ok(ws)
 }
 
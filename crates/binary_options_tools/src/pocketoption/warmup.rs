@@ -0,0 +1,214 @@
+//! Joins the candle history of an asset with its live subscription, used by
+//! [`PocketOption::subscribe_with_history`](super::PocketOption::subscribe_with_history).
+//!
+//! The subscription is opened first and the server time at that moment (the start) is
+//! snapshotted, then the history is fetched. The candle period containing the start is the
+//! splice point: history covers what came before it, the subscription what comes after it,
+//! and the candle of the splice point itself is built from both.
+
+use rust_decimal::Decimal;
+
+use super::candle::Candle;
+
+/// Splices history candles and the live candles of a subscription opened at `start`.
+#[derive(Debug, Clone)]
+pub struct HistorySplice {
+    /// Start of the candle period containing the subscription start.
+    bucket: i64,
+    /// History candle of `bucket`, waiting for the first live candle.
+    partial: Option<Candle>,
+    /// Whether live candles are aligned to the history periods and the candle of `bucket` is
+    /// merged, otherwise live candles before `bucket` are only dropped.
+    aligned: bool,
+}
+
+impl HistorySplice {
+    /// Splits `history` around the period of `start`.
+    ///
+    /// Returns the history candles to emit before the live ones, oldest first without
+    /// duplicates, and the splice to pass every live candle through.
+    pub fn new(
+        history: Vec<Candle>,
+        start: i64,
+        period: u32,
+        aligned: bool,
+    ) -> (Vec<Candle>, Self) {
+        let period = i64::from(period.max(1));
+        let bucket = start - start.rem_euclid(period);
+        let mut history = history;
+        history.sort_by_key(|candle| candle.timestamp);
+        // Later answers of the server are more complete, keep the last candle of a timestamp.
+        history.reverse();
+        history.dedup_by_key(|candle| candle.timestamp);
+        history.reverse();
+        history.retain(|candle| candle.timestamp <= bucket);
+
+        let partial = match history.last() {
+            Some(last) if aligned && last.timestamp == bucket => history.pop(),
+            _ => None,
+        };
+        for candle in history
+            .iter_mut()
+            .filter(|candle| candle.timestamp < bucket)
+        {
+            candle.is_closed = true;
+        }
+        let splice = Self {
+            bucket,
+            partial,
+            aligned,
+        };
+        (history, splice)
+    }
+
+    /// Start of the candle period in which the subscription started.
+    pub fn bucket(&self) -> i64 {
+        self.bucket
+    }
+
+    /// The candles to emit for the live candle `candle`, none for candles history already
+    /// covered.
+    pub fn push(&mut self, candle: Candle) -> Vec<Candle> {
+        if candle.timestamp < self.bucket {
+            return Vec::new();
+        }
+        if !self.aligned {
+            return vec![candle];
+        }
+        match self.partial.take() {
+            Some(partial) if partial.timestamp == candle.timestamp => {
+                vec![merge(partial, candle)]
+            }
+            Some(mut partial) => {
+                // No update came through the subscription for the rest of that period.
+                partial.is_closed = true;
+                vec![partial, candle]
+            }
+            None => vec![candle],
+        }
+    }
+}
+
+/// The candle of a period started by `history` and finished by `live`.
+fn merge(history: Candle, live: Candle) -> Candle {
+    Candle {
+        open: history.open,
+        high: Decimal::max(history.high, live.high),
+        low: Decimal::min(history.low, live.low),
+        ..live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::candle::{compile_candles_from_tuples, BaseCandle, SubscriptionType};
+
+    const PERIOD: u32 = 60;
+    const SYMBOL: &str = "EURUSD_otc";
+
+    /// One tick every 7 seconds over 10 minutes, with a changing price.
+    fn ticks() -> Vec<(i64, f64)> {
+        (0..86)
+            .map(|i| {
+                let price = 1.1 + ((i * 37) % 23) as f64 / 1000.0;
+                (600 + i * 7, price)
+            })
+            .collect()
+    }
+
+    /// Live candles of a time aligned subscription receiving the ticks from `start`.
+    fn live(ticks: &[(i64, f64)], start: i64) -> Vec<Candle> {
+        let mut sub_type = SubscriptionType::time_aligned(std::time::Duration::from_secs(60))
+            .expect("valid duration");
+        ticks
+            .iter()
+            .filter(|(timestamp, _)| *timestamp >= start)
+            .filter_map(|tick| sub_type.update(&(*tick).into()).unwrap())
+            .map(|candle| Candle::try_from((candle, SYMBOL.to_string())).unwrap())
+            .collect()
+    }
+
+    fn ohlc(candles: &[Candle]) -> Vec<(i64, Decimal, Decimal, Decimal, Decimal)> {
+        candles
+            .iter()
+            .map(|c| (c.timestamp, c.open, c.high, c.low, c.close))
+            .collect()
+    }
+
+    fn spliced(start: i64, fetched_at: i64) -> Vec<Candle> {
+        let ticks = ticks();
+        // History is fetched after the subscription started, so it overlaps with it.
+        let seen: Vec<_> = ticks
+            .iter()
+            .copied()
+            .filter(|(timestamp, _)| *timestamp <= fetched_at)
+            .collect();
+        let history = compile_candles_from_tuples(&seen, PERIOD, SYMBOL);
+        let (mut candles, mut splice) = HistorySplice::new(history, start, PERIOD, true);
+        for candle in live(&ticks, start) {
+            candles.extend(splice.push(candle));
+        }
+        candles
+    }
+
+    #[test]
+    fn test_splice_is_continuous() {
+        let expected = compile_candles_from_tuples(&ticks(), PERIOD, SYMBOL);
+        for start in [900, 913, 959, 960, 1001] {
+            for delay in [0, 3, 20, 75] {
+                let candles = spliced(start, start + delay);
+                // Every closed candle is delivered once, only the forming one is missing.
+                assert_eq!(
+                    ohlc(&candles),
+                    ohlc(&expected[..expected.len() - 1]),
+                    "start {start}, history fetched {delay}s later"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_duplicated_history_and_late_live_candles() {
+        let candles = compile_candles_from_tuples(
+            &[(60, 1.0), (120, 2.0), (130, 2.5), (180, 3.0)],
+            PERIOD,
+            SYMBOL,
+        );
+        let mut history = candles.clone();
+        history.insert(0, candles[1].clone());
+        let (history, mut splice) = HistorySplice::new(history, 150, PERIOD, true);
+        assert_eq!(splice.bucket(), 120);
+        assert_eq!(
+            history.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![60]
+        );
+
+        // A candle already covered by history is dropped.
+        assert!(splice.push(candles[0].clone()).is_empty());
+        // The history candle of the splice period is emitted when no live candle covers it.
+        let out = splice.push(candles[2].clone());
+        assert_eq!(
+            out.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![120, 180]
+        );
+        assert!(out[0].is_closed);
+        assert_eq!(out[0].close, candles[1].close);
+    }
+
+    #[test]
+    fn test_unaligned_subscriptions_keep_the_forming_candle() {
+        let history =
+            compile_candles_from_tuples(&[(60, 1.0), (120, 2.0), (130, 2.5)], PERIOD, SYMBOL);
+        let (history, mut splice) = HistorySplice::new(history, 135, PERIOD, false);
+        assert_eq!(history.len(), 2);
+        assert!(history[0].is_closed);
+        assert!(!history[1].is_closed);
+
+        let tick = |timestamp: i64| {
+            Candle::try_from((BaseCandle::from((timestamp, 2.6)), SYMBOL.to_string()))
+        };
+        assert!(splice.push(tick(110).unwrap()).is_empty());
+        assert_eq!(splice.push(tick(136).unwrap()).len(), 1);
+    }
+}
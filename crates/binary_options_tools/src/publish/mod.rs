@@ -0,0 +1,136 @@
+//! Mirrors market data and account events of one client to a message broker, so several
+//! processes can consume them while sharing a single PocketOption connection.
+//!
+//! Messages are JSON encoded and published under `<prefix>/...` (`pocketoption` by default):
+//! - `<prefix>/candles/<asset>`: every candle of the mirrored subscriptions
+//! - `<prefix>/balance`: `{"balance": ..}` on every balance change
+//! - `<prefix>/deals/closed`: every closed deal
+//!
+//! Brokers are enabled with the `mqtt` ([`MqttPublisher`]) and `redis` ([`RedisPublisher`])
+//! features, any other transport can implement [`Publisher`].
+
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "redis")]
+mod redis;
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisPublisher;
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttPublisher;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::future::{try_join_all, BoxFuture};
+use futures_util::FutureExt;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::pocketoption::candle::SubscriptionType;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::PocketOption;
+
+/// A destination for mirrored messages.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    async fn publish(&self, topic: &str, payload: Vec<u8>) -> PocketResult<()>;
+}
+
+/// Mirrors a client's candles, balance and closed deals to a [`Publisher`].
+pub struct Mirror {
+    client: PocketOption,
+    publisher: Arc<dyn Publisher>,
+    prefix: String,
+    candles: Vec<(String, SubscriptionType)>,
+}
+
+impl Mirror {
+    pub fn new(client: PocketOption, publisher: Arc<dyn Publisher>) -> Self {
+        Self {
+            client,
+            publisher,
+            prefix: "pocketoption".to_string(),
+            candles: Vec::new(),
+        }
+    }
+
+    /// Sets the topic prefix, `pocketoption` by default.
+    pub fn with_prefix(mut self, prefix: impl ToString) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Mirrors the candles of `asset`.
+    pub fn with_candles(mut self, asset: impl ToString, sub_type: SubscriptionType) -> Self {
+        self.candles.push((asset.to_string(), sub_type));
+        self
+    }
+
+    fn topic(&self, path: &str) -> String {
+        format!("{}/{path}", self.prefix)
+    }
+
+    /// Publishes a message, failures are logged so a broker hiccup does not stop the mirror.
+    async fn send(&self, topic: &str, message: &impl Serialize) {
+        let payload = match serde_json::to_vec(message) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!(target: "Publish", "Failed to encode message for {}: {}", topic, e);
+                return;
+            }
+        };
+        if let Err(e) = self.publisher.publish(topic, payload).await {
+            warn!(target: "Publish", "Failed to publish to {}: {}", topic, e);
+        }
+    }
+
+    /// Runs the mirror, it only returns when a candle subscription fails.
+    pub async fn run(self) -> PocketResult<()> {
+        let mut tasks: Vec<BoxFuture<'_, PocketResult<()>>> =
+            vec![self.mirror_balance().boxed(), self.mirror_deals().boxed()];
+        for (asset, sub_type) in &self.candles {
+            tasks.push(self.mirror_candles(asset, sub_type.clone()).boxed());
+        }
+        info!(target: "Publish", "Mirroring {} candle streams to {}/", self.candles.len(), self.prefix);
+        try_join_all(tasks).await.map(|_| ())
+    }
+
+    async fn mirror_candles(&self, asset: &str, sub_type: SubscriptionType) -> PocketResult<()> {
+        let topic = self.topic(&format!("candles/{asset}"));
+        let mut stream = self.client.subscribe(asset, sub_type).await?;
+        loop {
+            let candle = stream.receive().await?;
+            self.send(&topic, &candle).await;
+        }
+    }
+
+    async fn mirror_balance(&self) -> PocketResult<()> {
+        let topic = self.topic("balance");
+        let notify = self.client.balance_updated();
+        loop {
+            let updated = notify.notified();
+            let balance = self.client.balance().await;
+            self.send(&topic, &json!({ "balance": balance })).await;
+            updated.await;
+        }
+    }
+
+    async fn mirror_deals(&self) -> PocketResult<()> {
+        let topic = self.topic("deals/closed");
+        let mut deals = self.client.closed_deal_events();
+        loop {
+            match deals.recv().await {
+                Ok(deal) => self.send(&topic, &deal).await,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(target: "Publish", "Mirror is lagging, {} closed deals were not published", missed);
+                }
+                Err(RecvError::Closed) => {
+                    return Err(PocketError::General("Closed deals channel closed".into()))
+                }
+            }
+        }
+    }
+}
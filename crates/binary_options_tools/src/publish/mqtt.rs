@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use super::Publisher;
+use crate::pocketoption::error::{PocketError, PocketResult};
+
+/// Publishes to an MQTT broker with QoS 1 (at least once).
+pub struct MqttPublisher {
+    client: AsyncClient,
+    event_loop: JoinHandle<()>,
+}
+
+impl MqttPublisher {
+    /// Connects to the broker, `options` holds the broker address, client id and credentials.
+    pub fn new(options: MqttOptions) -> Self {
+        let (client, mut event_loop) = AsyncClient::new(options, 64);
+        // rumqttc only makes progress while its event loop is polled, it also reconnects on errors.
+        let event_loop = tokio::spawn(async move {
+            loop {
+                if let Err(e) = event_loop.poll().await {
+                    warn!(target: "Publish", "MQTT connection error: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            }
+        });
+        Self { client, event_loop }
+    }
+}
+
+impl Drop for MqttPublisher {
+    fn drop(&mut self) {
+        self.event_loop.abort();
+    }
+}
+
+#[async_trait]
+impl Publisher for MqttPublisher {
+    async fn publish(&self, topic: &str, payload: Vec<u8>) -> PocketResult<()> {
+        self.client
+            .publish(topic, QoS::AtLeastOnce, false, payload)
+            .await
+            .map_err(|e| PocketError::General(format!("MQTT publish failed: {e}")))
+    }
+}
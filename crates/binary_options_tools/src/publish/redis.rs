@@ -0,0 +1,36 @@
+use ::redis::aio::MultiplexedConnection;
+use ::redis::AsyncCommands;
+use async_trait::async_trait;
+
+use super::Publisher;
+use crate::pocketoption::error::{PocketError, PocketResult};
+
+/// Publishes to Redis pub/sub channels named after the topics.
+#[derive(Clone)]
+pub struct RedisPublisher {
+    connection: MultiplexedConnection,
+}
+
+impl RedisPublisher {
+    /// Connects to `url`, e.g. `redis://127.0.0.1/`.
+    pub async fn connect(url: &str) -> PocketResult<Self> {
+        let client = ::redis::Client::open(url)
+            .map_err(|e| PocketError::Configuration(format!("Invalid Redis url: {e}")))?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| PocketError::General(format!("Failed to connect to Redis: {e}")))?;
+        Ok(Self { connection })
+    }
+}
+
+#[async_trait]
+impl Publisher for RedisPublisher {
+    async fn publish(&self, topic: &str, payload: Vec<u8>) -> PocketResult<()> {
+        let mut connection = self.connection.clone();
+        connection
+            .publish::<_, _, ()>(topic, payload)
+            .await
+            .map_err(|e| PocketError::General(format!("Redis publish failed: {e}")))
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{builder::ClientBuilder, client::Client, error::CoreError};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::framework::market::Market;
+use crate::pocketoption::candle::Candle;
+use crate::pocketoption::error::PocketResult;
+use crate::pocketoption::types::{Action, Deal};
+use crate::quotex::{
+    connect::QuotexConnect,
+    error::{QuotexError, QuotexResult},
+    modules::{
+        assets::AssetsModule,
+        balance::BalanceModule,
+        candles::CandlesModule,
+        keep_alive::{InitModule, KeepAliveModule},
+        trades::TradesModule,
+    },
+    state::{Config, State},
+    types::{Instrument, OpenOrder, OPTION_TYPE},
+};
+
+/// How long to wait for the server to answer an order or a history request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client of the Quotex platform.
+///
+/// Authenticated with the `session` token of a logged in web session, it trades on the
+/// demo or the live account and loads historical candles. It implements
+/// [`Market`], so the strategies of the [framework](crate::framework) can trade on it.
+#[derive(Clone)]
+pub struct Quotex {
+    client: Client<State>,
+    _runner: Arc<JoinHandle<()>>,
+}
+
+impl Quotex {
+    fn builder(state: State) -> ClientBuilder<State> {
+        ClientBuilder::new(QuotexConnect, state)
+            .with_lightweight_module::<InitModule>()
+            .with_lightweight_module::<KeepAliveModule>()
+            .with_lightweight_module::<BalanceModule>()
+            .with_lightweight_module::<AssetsModule>()
+            .with_module::<TradesModule>()
+            .with_module::<CandlesModule>()
+    }
+
+    pub async fn new(session: impl ToString, demo: bool) -> QuotexResult<Self> {
+        Self::new_with_config(session, demo, Config::default()).await
+    }
+
+    pub async fn new_with_config(
+        session: impl ToString,
+        demo: bool,
+        config: Config,
+    ) -> QuotexResult<Self> {
+        let state = State::new(session.to_string(), demo, config);
+        let (client, mut runner) = Self::builder(state).build().await?;
+
+        let _runner = tokio::spawn(async move { runner.run().await });
+        client.wait_connected().await;
+
+        Ok(Self {
+            client,
+            _runner: Arc::new(_runner),
+        })
+    }
+
+    /// Checks if the client trades on the demo account.
+    pub fn is_demo(&self) -> bool {
+        self.client.state.demo
+    }
+
+    /// Balance of the account traded on, -1 until the server sent it.
+    pub async fn balance(&self) -> Decimal {
+        self.client.state.get_balance().await
+    }
+
+    /// Tradable assets by symbol.
+    pub async fn assets(&self) -> HashMap<String, Instrument> {
+        self.client.state.instruments.read().await.clone()
+    }
+
+    /// Payout of `asset` in percent.
+    pub async fn payout(&self, asset: &str) -> QuotexResult<i32> {
+        self.client
+            .state
+            .instruments
+            .read()
+            .await
+            .get(asset)
+            .map(|instrument| instrument.payout)
+            .ok_or_else(|| QuotexError::InvalidAsset(asset.to_string()))
+    }
+
+    /// Opens a trade of `amount` on `asset` for `time` seconds.
+    pub async fn trade(
+        &self,
+        asset: impl ToString,
+        action: Action,
+        time: u32,
+        amount: Decimal,
+    ) -> QuotexResult<(Uuid, Deal)> {
+        let asset = asset.to_string();
+        if amount <= Decimal::ZERO {
+            return Err(QuotexError::InvalidAmount(amount));
+        }
+        // Not checked until the server has sent its instruments
+        if let Some(instrument) = self.client.state.instruments.read().await.get(&asset) {
+            if !instrument.is_open {
+                return Err(QuotexError::AssetClosed(asset));
+            }
+        }
+        let handle = self
+            .client
+            .get_handle::<TradesModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("TradesModule".into()))?;
+        let order = OpenOrder {
+            asset,
+            amount,
+            time,
+            action,
+            is_demo: u8::from(self.is_demo()),
+            tournament_id: 0,
+            request_id: 0,
+            option_type: OPTION_TYPE,
+        };
+        let deal = tokio::time::timeout(REQUEST_TIMEOUT, handle.open(order))
+            .await
+            .map_err(|_| CoreError::TimeoutError {
+                task: "orders/open".into(),
+                duration: REQUEST_TIMEOUT,
+            })??;
+        Ok((deal.id, deal))
+    }
+
+    pub async fn buy(
+        &self,
+        asset: impl ToString,
+        time: u32,
+        amount: Decimal,
+    ) -> QuotexResult<(Uuid, Deal)> {
+        self.trade(asset, Action::Call, time, amount).await
+    }
+
+    pub async fn sell(
+        &self,
+        asset: impl ToString,
+        time: u32,
+        amount: Decimal,
+    ) -> QuotexResult<(Uuid, Deal)> {
+        self.trade(asset, Action::Put, time, amount).await
+    }
+
+    /// Waits until the deal `id` closes and returns it.
+    pub async fn result(&self, id: Uuid) -> QuotexResult<Deal> {
+        if self.client.state.deal(id).await.is_none() {
+            return Err(QuotexError::DealNotFound(id));
+        }
+        let handle = self
+            .client
+            .get_handle::<TradesModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("TradesModule".into()))?;
+        handle.result(id).await
+    }
+
+    /// Open deals by id.
+    pub async fn opened_deals(&self) -> HashMap<Uuid, Deal> {
+        self.client.state.opened_deals.read().await.clone()
+    }
+
+    /// Deals closed since the client connected, by id.
+    pub async fn closed_deals(&self) -> HashMap<Uuid, Deal> {
+        self.client.state.closed_deals.read().await.clone()
+    }
+
+    /// Gets the candles of `period` seconds of `asset` over the last `offset` seconds, oldest
+    /// first. The last candle is still forming if it ends after now.
+    pub async fn get_candles(
+        &self,
+        asset: impl ToString,
+        period: u32,
+        offset: i64,
+    ) -> QuotexResult<Vec<Candle>> {
+        let asset = asset.to_string();
+        let instruments = self.client.state.instruments.read().await;
+        if !instruments.is_empty() && !instruments.contains_key(&asset) {
+            return Err(QuotexError::InvalidAsset(asset));
+        }
+        drop(instruments);
+        let handle = self
+            .client
+            .get_handle::<CandlesModule>()
+            .await
+            .ok_or_else(|| CoreError::ModuleNotFound("CandlesModule".into()))?;
+        let now = Utc::now().timestamp();
+        let history = tokio::time::timeout(
+            REQUEST_TIMEOUT,
+            handle.history(asset.clone(), period, now, offset),
+        )
+        .await
+        .map_err(|_| CoreError::TimeoutError {
+            task: format!("candles of {asset}"),
+            duration: REQUEST_TIMEOUT,
+        })??;
+        history
+            .into_iter()
+            .map(|bar| {
+                let closed = bar.timestamp + i64::from(period) <= now;
+                let mut candle = Candle::try_from((bar, asset.clone()))
+                    .map_err(|e| QuotexError::General(e.to_string()))?;
+                candle.is_closed = closed;
+                Ok(candle)
+            })
+            .collect()
+    }
+
+    /// Disconnects and reconnects the client.
+    pub async fn reconnect(&self) -> QuotexResult<()> {
+        self.client.reconnect().await.map_err(QuotexError::from)
+    }
+
+    /// Shuts down the client and stops the runner.
+    pub async fn shutdown(self) -> QuotexResult<()> {
+        self.client.shutdown().await.map_err(QuotexError::from)
+    }
+}
+
+#[async_trait]
+impl Market for Quotex {
+    async fn buy(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        Ok(self.buy(asset, time, amount).await?)
+    }
+
+    async fn sell(&self, asset: &str, amount: Decimal, time: u32) -> PocketResult<(Uuid, Deal)> {
+        Ok(self.sell(asset, time, amount).await?)
+    }
+
+    async fn balance(&self) -> Decimal {
+        self.balance().await
+    }
+
+    async fn result(&self, trade_id: Uuid) -> PocketResult<Deal> {
+        Ok(self.result(trade_id).await?)
+    }
+}
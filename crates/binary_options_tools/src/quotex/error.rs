@@ -0,0 +1,50 @@
+use binary_options_tools_core::error::CoreError;
+use rust_decimal::Decimal;
+
+use crate::pocketoption::error::PocketError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum QuotexError {
+    #[error("Serde JSON deserialization error: {0}")]
+    Deserializing(#[from] serde_json::Error),
+
+    #[error("Core error: {0}")]
+    Core(#[from] Box<CoreError>),
+
+    #[error("Invalid asset: {0}")]
+    InvalidAsset(String),
+
+    #[error("Asset {0} is closed")]
+    AssetClosed(String),
+
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(Decimal),
+
+    #[error("Failed to open order: {0}")]
+    Order(String),
+
+    #[error("Deal {0} not found")]
+    DealNotFound(uuid::Uuid),
+
+    #[error("General error: {0}")]
+    General(String),
+}
+
+pub type QuotexResult<T> = Result<T, QuotexError>;
+
+impl From<CoreError> for QuotexError {
+    fn from(err: CoreError) -> Self {
+        QuotexError::Core(Box::new(err))
+    }
+}
+
+/// For the [`Market`](crate::framework::market::Market) implementation.
+impl From<QuotexError> for PocketError {
+    fn from(err: QuotexError) -> Self {
+        match err {
+            QuotexError::InvalidAsset(asset) => PocketError::InvalidAsset(asset),
+            QuotexError::Core(err) => PocketError::Core(*err),
+            err => PocketError::General(err.to_string()),
+        }
+    }
+}
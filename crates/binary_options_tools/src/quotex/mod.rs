@@ -0,0 +1,13 @@
+//! Quotex client over the Socket.IO v2 protocol of its web platform.
+//!
+//! The session is authorized with the `session` token of a logged in browser. Events are
+//! `42["name",data]` text frames, or a `451-["name",{"_placeholder":true,"num":0}]` text
+//! frame followed by a binary attachment holding the data.
+pub mod client;
+pub mod connect;
+pub mod error;
+pub mod modules;
+pub mod state;
+pub mod types;
+
+pub use client::Quotex;
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{
+    error::{CoreError, CoreResult},
+    reimports::{AsyncReceiver, AsyncSender, Message},
+    traits::{LightweightModule, Rule, RunnerCommand},
+};
+use tracing::{debug, warn};
+
+use crate::pocketoption::types::MultiPatternRule;
+use crate::quotex::state::State;
+use crate::quotex::types::{payload, Instrument};
+
+/// Stores the assets of `instruments/list`, sent after authorizing.
+pub struct AssetsModule {
+    state: Arc<State>,
+    receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<State> for AssetsModule {
+    fn new(
+        state: Arc<State>,
+        _: AsyncSender<Message>,
+        receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { state, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.receiver.recv().await {
+            let instruments = payload(&msg)
+                .map(|list| Instrument::list(&list))
+                .unwrap_or_default();
+            if instruments.is_empty() {
+                warn!(target: "AssetsModule", "Received an instrument list without instruments");
+                continue;
+            }
+            debug!(target: "AssetsModule", "Loaded {} instruments", instruments.len());
+            self.state.set_instruments(instruments).await;
+        }
+        Err(CoreError::LightweightModuleLoop("AssetsModule".into()))
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(MultiPatternRule::new(vec!["instruments/list"]))
+    }
+}
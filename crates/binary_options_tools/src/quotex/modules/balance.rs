@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{
+    error::{CoreError, CoreResult},
+    reimports::{AsyncReceiver, AsyncSender, Message},
+    traits::{LightweightModule, Rule, RunnerCommand},
+};
+use tracing::debug;
+
+use crate::quotex::state::State;
+use crate::quotex::types::{payload, Balance};
+
+const BALANCE_KEYS: [&str; 2] = ["\"liveBalance\"", "\"demoBalance\""];
+
+/// Keeps the balances of the state up to date, from any message carrying them.
+pub struct BalanceModule {
+    state: Arc<State>,
+    receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<State> for BalanceModule {
+    fn new(
+        state: Arc<State>,
+        _: AsyncSender<Message>,
+        receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { state, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.receiver.recv().await {
+            if let Some(balance) = payload(&msg).as_ref().and_then(Balance::from_value) {
+                debug!(target: "BalanceModule", "Received balance: {:?}", balance);
+                self.state.set_balance(balance).await;
+            }
+        }
+        Err(CoreError::LightweightModuleLoop("BalanceModule".into()))
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|msg: &Message| {
+            let data: &[u8] = match msg {
+                Message::Text(text) => text.as_bytes(),
+                Message::Binary(data) => data,
+                _ => return false,
+            };
+            BALANCE_KEYS.iter().any(|key| {
+                data.windows(key.len())
+                    .any(|window| window == key.as_bytes())
+            })
+        })
+    }
+}
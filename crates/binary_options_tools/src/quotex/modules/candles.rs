@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, Rule, RunnerCommand};
+use serde_json::Value;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::pocketoption::candle::BaseCandle;
+use crate::pocketoption::types::MultiPatternRule;
+use crate::quotex::state::State;
+use crate::quotex::types::{event, payload, HistoryRequest};
+
+#[derive(Debug)]
+pub enum Request {
+    /// Loads the candles of `request`, its index is set by the module.
+    History {
+        request: HistoryRequest,
+        responder: oneshot::Sender<Vec<BaseCandle>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct CandlesHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl CandlesHandle {
+    /// Candles of `period` seconds of `asset` over the `offset` seconds before `time`,
+    /// oldest first.
+    pub async fn history(
+        &self,
+        asset: String,
+        period: u32,
+        time: i64,
+        offset: i64,
+    ) -> CoreResult<Vec<BaseCandle>> {
+        let (responder, response) = oneshot::channel();
+        let request = HistoryRequest {
+            asset,
+            index: 0,
+            time,
+            offset,
+            period,
+        };
+        self.sender
+            .send(Request::History { request, responder })
+            .await?;
+        response.await.map_err(|_| {
+            CoreError::Other("CandlesModule stopped before answering the request".into())
+        })
+    }
+}
+
+/// Candles `[time, open, close, high, low, volume]` of a `history/load` answer.
+pub fn parse_history(data: &Value) -> Vec<BaseCandle> {
+    let mut candles: Vec<BaseCandle> = data
+        .get("candles")
+        .and_then(Value::as_array)
+        .map(|candles| {
+            candles
+                .iter()
+                .filter_map(|candle| serde_json::from_value(candle.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    candles.sort_by_key(|candle| candle.timestamp);
+    candles
+}
+
+/// Candles module, answers `history/load` requests.
+///
+/// Answers are matched to requests by the `index` they echo, or by asset and period.
+pub struct CandlesModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    pending: VecDeque<(HistoryRequest, oneshot::Sender<Vec<BaseCandle>>)>,
+    next_index: u64,
+}
+
+impl CandlesModule {
+    async fn request(
+        &mut self,
+        mut request: HistoryRequest,
+        responder: oneshot::Sender<Vec<BaseCandle>>,
+    ) -> CoreResult<()> {
+        request.index = self.next_index;
+        self.next_index += 1;
+        self.ws_sender.send(event("history/load", &request)).await?;
+        self.pending.push_back((request, responder));
+        Ok(())
+    }
+
+    fn answer(&mut self, data: Value) {
+        // Requests whose caller gave up
+        self.pending.retain(|(_, responder)| !responder.is_closed());
+        let index = data.get("index").and_then(Value::as_u64);
+        let asset = data.get("asset").and_then(Value::as_str);
+        let period = data.get("period").and_then(Value::as_u64);
+        let position = self.pending.iter().position(|(request, _)| match index {
+            Some(index) => request.index == index,
+            None => {
+                asset.is_none_or(|asset| asset == request.asset)
+                    && period.is_none_or(|period| period == u64::from(request.period))
+            }
+        });
+        let Some((_, responder)) = position.and_then(|position| self.pending.remove(position))
+        else {
+            debug!(target: "CandlesModule", "History candles without a pending request");
+            return;
+        };
+        let _ = responder.send(parse_history(&data));
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for CandlesModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = CandlesHandle;
+
+    fn new(
+        _: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            pending: VecDeque::new(),
+            // The web platform also uses timestamps
+            next_index: chrono::Utc::now().timestamp() as u64 * 100,
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        CandlesHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Some(data) = payload(&msg) {
+                                self.answer(data);
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::History { request, responder }) => {
+                            self.request(request, responder).await?;
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MultiPatternRule::new(vec![
+            "history/load",
+            "history/load/line",
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_history() {
+        let data = json!({
+            "asset": "EURUSD_otc",
+            "index": 170000000000u64,
+            "candles": [[120, 1.2, 1.25, 1.3, 1.1, 14], [60, 1.0, 1.2, 1.2, 0.9], "bad"]
+        });
+        let candles = parse_history(&data);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, 60);
+        let last = &candles[1];
+        assert_eq!(
+            (last.open, last.close, last.high, last.low),
+            (1.2, 1.25, 1.3, 1.1)
+        );
+        assert_eq!(last.volume, Some(14.0));
+        assert!(parse_history(&json!({"error": "unknown asset"})).is_empty());
+    }
+}
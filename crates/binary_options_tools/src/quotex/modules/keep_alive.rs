@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use binary_options_tools_core::{
+    error::{CoreError, CoreResult},
+    reimports::{AsyncReceiver, AsyncSender, Message},
+    status::ConnectionStatus,
+    traits::{LightweightModule, Rule, RunnerCommand},
+};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use crate::quotex::state::State;
+use crate::quotex::types::event;
+
+/// Engine.IO v3 handshake, the client pings every `pingInterval` milliseconds.
+const HANDSHAKE: &str = "0{";
+/// Socket.IO connection to the default namespace.
+const CONNECTED: &str = "40";
+const AUTHORIZED: &str = r#"["s_authorization""#;
+const REJECTED: &str = r#"["authorization/reject""#;
+/// Interval of the pings when the handshake doesn't say.
+const PING_INTERVAL: Duration = Duration::from_secs(25);
+
+/// The `42["authorization",{...}]` message authenticating the session.
+fn auth_message(state: &State) -> Message {
+    event(
+        "authorization",
+        &json!({
+            "session": state.session,
+            "isDemo": u8::from(state.demo),
+            "tournamentId": 0,
+        }),
+    )
+}
+
+/// Authorizes the session once the Socket.IO connection is open.
+pub struct InitModule {
+    ws_sender: AsyncSender<Message>,
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    state: Arc<State>,
+    runner_command_tx: AsyncSender<RunnerCommand>,
+}
+
+#[async_trait]
+impl LightweightModule<State> for InitModule {
+    fn new(
+        state: Arc<State>,
+        ws_sender: AsyncSender<Message>,
+        ws_receiver: AsyncReceiver<Arc<Message>>,
+        runner_command_tx: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_sender,
+            ws_receiver,
+            state,
+            runner_command_tx,
+        }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.ws_receiver.recv().await {
+            let Message::Text(text) = &*msg else {
+                continue;
+            };
+            if text.as_str() == CONNECTED {
+                debug!(target: "InitModule", "Socket.IO connected, sending the authorization");
+                self.ws_sender.send(auth_message(&self.state)).await?;
+            } else if text.as_str() == "2" {
+                self.ws_sender.send(Message::text("3")).await?;
+            } else if text.contains(AUTHORIZED) {
+                debug!(target: "InitModule", "Authorization successful");
+                self.runner_command_tx
+                    .send(RunnerCommand::SetStatus(ConnectionStatus::Authenticated))
+                    .await?;
+            } else if text.contains(REJECTED) {
+                tracing::error!(target: "InitModule", "Quotex rejected the session, it may have expired");
+                if let Err(e) = self.runner_command_tx.send(RunnerCommand::Shutdown).await {
+                    warn!(target: "InitModule", "Failed to send shutdown command to runner: {}", e);
+                }
+                return Err(CoreError::SsidParsing(
+                    "Quotex rejected the session token".into(),
+                ));
+            }
+        }
+        Err(CoreError::LightweightModuleLoop("InitModule".into()))
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|msg: &Message| match msg {
+            Message::Text(text) => {
+                text.as_str() == CONNECTED
+                    || text.as_str() == "2"
+                    || text.contains(AUTHORIZED)
+                    || text.contains(REJECTED)
+            }
+            _ => false,
+        })
+    }
+}
+
+/// Sends the Engine.IO v3 pings, at the interval of the handshake.
+pub struct KeepAliveModule {
+    ws_sender: AsyncSender<Message>,
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<State> for KeepAliveModule {
+    fn new(
+        _: Arc<State>,
+        ws_sender: AsyncSender<Message>,
+        ws_receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self {
+            ws_sender,
+            ws_receiver,
+        }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        let mut interval = PING_INTERVAL;
+        loop {
+            tokio::select! {
+                msg = self.ws_receiver.recv() => {
+                    let Ok(msg) = msg else { break };
+                    if let Message::Text(text) = &*msg {
+                        interval = ping_interval(text).unwrap_or(interval);
+                    }
+                }
+                _ = tokio::time::sleep(interval) => {
+                    self.ws_sender.send(Message::text("2")).await?;
+                }
+            }
+        }
+        Err(CoreError::LightweightModuleLoop("KeepAliveModule".into()))
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|msg: &Message| matches!(msg, Message::Text(text) if text.starts_with(HANDSHAKE)))
+    }
+}
+
+/// `pingInterval` of a `0{...}` handshake.
+fn ping_interval(handshake: &str) -> Option<Duration> {
+    let handshake: serde_json::Value = serde_json::from_str(handshake.strip_prefix('0')?).ok()?;
+    handshake
+        .get("pingInterval")?
+        .as_u64()
+        .map(Duration::from_millis)
+}
@@ -0,0 +1,5 @@
+pub mod assets;
+pub mod balance;
+pub mod candles;
+pub mod keep_alive;
+pub mod trades;
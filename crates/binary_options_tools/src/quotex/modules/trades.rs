@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::reimports::{AsyncReceiver, AsyncSender, Message};
+use binary_options_tools_core::traits::{ApiModule, Rule, RunnerCommand};
+use serde_json::Value;
+use tokio::select;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::pocketoption::types::{Deal, MultiPatternRule};
+use crate::quotex::error::{QuotexError, QuotexResult};
+use crate::quotex::state::State;
+use crate::quotex::types::{event, payload, OpenOrder, QuotexDeal};
+
+#[derive(Debug)]
+pub enum Request {
+    /// Opens `order`, its request id is set by the module.
+    Open {
+        order: OpenOrder,
+        responder: oneshot::Sender<QuotexResult<Deal>>,
+    },
+    /// Waits until the deal `id` closes.
+    Result {
+        id: Uuid,
+        responder: oneshot::Sender<Deal>,
+    },
+}
+
+#[derive(Clone)]
+pub struct TradesHandle {
+    sender: AsyncSender<Request>,
+}
+
+impl TradesHandle {
+    pub async fn open(&self, order: OpenOrder) -> QuotexResult<Deal> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::Open { order, responder })
+            .await
+            .map_err(CoreError::from)?;
+        response.await.map_err(|_| stopped())?
+    }
+
+    /// The deal `id` once closed.
+    pub async fn result(&self, id: Uuid) -> QuotexResult<Deal> {
+        let (responder, response) = oneshot::channel();
+        self.sender
+            .send(Request::Result { id, responder })
+            .await
+            .map_err(CoreError::from)?;
+        response.await.map_err(|_| stopped())
+    }
+}
+
+fn stopped() -> QuotexError {
+    QuotexError::General("TradesModule stopped before answering the request".into())
+}
+
+/// Trades module, opens orders and reports the deals they close with.
+///
+/// `s_orders/open` answers an order with its deal, or with an `error`, and
+/// `s_orders/close` reports the closed `deals`. Answers are matched to orders by their
+/// `requestId`, or to the oldest order when the server leaves it out.
+pub struct TradesModule {
+    ws_receiver: AsyncReceiver<Arc<Message>>,
+    ws_sender: AsyncSender<Message>,
+    command_receiver: AsyncReceiver<Request>,
+    state: Arc<State>,
+    pending_orders: BTreeMap<u64, oneshot::Sender<QuotexResult<Deal>>>,
+    waiting_results: HashMap<Uuid, Vec<oneshot::Sender<Deal>>>,
+    next_request_id: u64,
+}
+
+impl TradesModule {
+    async fn open(
+        &mut self,
+        mut order: OpenOrder,
+        responder: oneshot::Sender<QuotexResult<Deal>>,
+    ) -> CoreResult<()> {
+        order.request_id = self.next_request_id;
+        self.next_request_id += 1;
+        self.ws_sender.send(event("orders/open", &order)).await?;
+        self.pending_orders.insert(order.request_id, responder);
+        Ok(())
+    }
+
+    async fn wait_result(&mut self, id: Uuid, responder: oneshot::Sender<Deal>) {
+        if let Some(deal) = self.state.closed_deals.read().await.get(&id) {
+            let _ = responder.send(deal.clone());
+            return;
+        }
+        self.waiting_results.entry(id).or_default().push(responder);
+    }
+
+    /// Responder of the order answered by `data`.
+    fn take_order(&mut self, data: &Value) -> Option<oneshot::Sender<QuotexResult<Deal>>> {
+        match data.get("requestId").and_then(Value::as_u64) {
+            Some(request_id) => self.pending_orders.remove(&request_id),
+            None => self
+                .pending_orders
+                .pop_first()
+                .map(|(_, responder)| responder),
+        }
+    }
+
+    async fn handle(&mut self, data: Value) {
+        if let Some(deals) = data.get("deals").and_then(Value::as_array) {
+            for deal in deals {
+                match serde_json::from_value::<QuotexDeal>(deal.clone()) {
+                    Ok(deal) => self.closed(deal.into()).await,
+                    Err(e) => warn!(target: "TradesModule", "Failed to parse closed deal: {e}"),
+                }
+            }
+            return;
+        }
+        if let Some(error) = data.get("error") {
+            let error = error
+                .as_str()
+                .map_or_else(|| error.to_string(), String::from);
+            match self.take_order(&data) {
+                Some(responder) => {
+                    let _ = responder.send(Err(QuotexError::Order(error)));
+                }
+                None => {
+                    warn!(target: "TradesModule", "Order error without a pending order: {error}")
+                }
+            }
+            return;
+        }
+        match serde_json::from_value::<QuotexDeal>(data.clone()) {
+            Ok(deal) => {
+                let deal = Deal::from(deal);
+                self.state.add_opened_deal(deal.clone()).await;
+                match self.take_order(&data) {
+                    Some(responder) => {
+                        let _ = responder.send(Ok(deal));
+                    }
+                    None => debug!(target: "TradesModule", "Deal {} opened elsewhere", deal.id),
+                }
+            }
+            Err(e) => warn!(target: "TradesModule", "Failed to parse opened deal: {e}"),
+        }
+    }
+
+    async fn closed(&mut self, deal: Deal) {
+        for responder in self.waiting_results.remove(&deal.id).unwrap_or_default() {
+            let _ = responder.send(deal.clone());
+        }
+        self.state.close_deal(deal).await;
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiModule<State> for TradesModule {
+    type Command = Request;
+    type CommandResponse = ();
+    type Handle = TradesHandle;
+
+    fn new(
+        shared_state: Arc<State>,
+        command_receiver: AsyncReceiver<Self::Command>,
+        _: AsyncSender<Self::CommandResponse>,
+        message_receiver: AsyncReceiver<Arc<Message>>,
+        to_ws_sender: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            ws_receiver: message_receiver,
+            ws_sender: to_ws_sender,
+            command_receiver,
+            state: shared_state,
+            pending_orders: BTreeMap::new(),
+            waiting_results: HashMap::new(),
+            // Kept unique across restarts of the client
+            next_request_id: chrono::Utc::now().timestamp() as u64,
+        }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        _: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        TradesHandle { sender }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        loop {
+            select! {
+                msg_res = self.ws_receiver.recv() => {
+                    match msg_res {
+                        Ok(msg) => {
+                            if let Some(data) = payload(&msg) {
+                                self.handle(data).await;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                },
+                cmd_res = self.command_receiver.recv() => {
+                    match cmd_res {
+                        Ok(Request::Open { order, responder }) => self.open(order, responder).await?,
+                        Ok(Request::Result { id, responder }) => self.wait_result(id, responder).await,
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<State>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(MultiPatternRule::new(vec![
+            "s_orders/open",
+            "s_orders/close",
+        ]))
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use binary_options_tools_core::traits::AppState;
+use rust_decimal::Decimal;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::pocketoption::types::Deal;
+use crate::quotex::types::{Balance, Instrument};
+
+/// Socket.IO endpoints, tried in order.
+pub const DEFAULT_URLS: &[&str] = &["wss://ws2.qxbroker.com/socket.io/?EIO=3&transport=websocket"];
+
+pub struct Config {
+    pub user_agent: String,
+    pub urls: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/135.0.0.0 Safari/537.36".to_string(),
+            urls: DEFAULT_URLS.iter().map(|url| url.to_string()).collect(),
+        }
+    }
+}
+
+pub struct State {
+    /// Session token of the account, the `session` cookie of the web platform
+    pub session: String,
+    /// Trades on the demo account if true
+    pub demo: bool,
+    /// Configuration for the Quotex client
+    pub config: Config,
+    /// Balances of the account
+    pub balance: RwLock<Option<Balance>>,
+    /// Assets by symbol
+    pub instruments: RwLock<HashMap<String, Instrument>>,
+    /// Deals not closed yet
+    pub opened_deals: RwLock<HashMap<Uuid, Deal>>,
+    /// Closed deals
+    pub closed_deals: RwLock<HashMap<Uuid, Deal>>,
+}
+
+#[async_trait::async_trait]
+impl AppState for State {
+    async fn clear_temporal_data(&self) {
+        // Sent again after authorizing
+        self.instruments.write().await.clear();
+    }
+}
+
+impl State {
+    pub fn new(session: String, demo: bool, config: Config) -> Self {
+        State {
+            session,
+            demo,
+            config,
+            balance: RwLock::new(None),
+            instruments: RwLock::new(HashMap::new()),
+            opened_deals: RwLock::new(HashMap::new()),
+            closed_deals: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn set_balance(&self, balance: Balance) {
+        *self.balance.write().await = Some(balance);
+    }
+
+    /// Balance of the account traded on, -1 until the server sent it.
+    pub async fn get_balance(&self) -> Decimal {
+        match &*self.balance.read().await {
+            Some(balance) if self.demo => balance.demo,
+            Some(balance) => balance.live,
+            None => Decimal::NEGATIVE_ONE,
+        }
+    }
+
+    pub async fn set_instruments(&self, instruments: HashMap<String, Instrument>) {
+        *self.instruments.write().await = instruments;
+    }
+
+    pub async fn add_opened_deal(&self, deal: Deal) {
+        self.opened_deals.write().await.insert(deal.id, deal);
+    }
+
+    pub async fn close_deal(&self, deal: Deal) {
+        self.opened_deals.write().await.remove(&deal.id);
+        self.closed_deals.write().await.insert(deal.id, deal);
+    }
+
+    /// The deal `id`, closed or still open.
+    pub async fn deal(&self, id: Uuid) -> Option<Deal> {
+        if let Some(deal) = self.closed_deals.read().await.get(&id) {
+            return Some(deal.clone());
+        }
+        self.opened_deals.read().await.get(&id).cloned()
+    }
+}
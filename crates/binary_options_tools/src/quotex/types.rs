@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use binary_options_tools_core::reimports::Message;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::pocketoption::types::{Action, Deal, RequestId};
+
+/// Option type of the fixed time trades.
+pub const OPTION_TYPE: u32 = 100;
+
+/// Socket.IO v2 prefixes the binary attachments with this byte.
+const BINARY_ATTACHMENT: u8 = 0x04;
+
+/// Payload of a Socket.IO event, either the data of a one step `42["event",{...}]` text or a
+/// binary attachment. `None` for placeholders and frames without JSON.
+pub fn payload(msg: &Message) -> Option<Value> {
+    match msg {
+        Message::Text(text) => {
+            let start = text.find('[')?;
+            let value: Value = serde_json::from_str(&text[start..]).ok()?;
+            let data = value.as_array()?.get(1)?;
+            if data.get("_placeholder").is_some() {
+                return None;
+            }
+            Some(data.clone())
+        }
+        Message::Binary(data) => {
+            let data = data.strip_prefix(&[BINARY_ATTACHMENT]).unwrap_or(data);
+            serde_json::from_slice(data).ok()
+        }
+        _ => None,
+    }
+}
+
+/// `42["event",data]`.
+pub fn event(name: &str, data: &impl Serialize) -> Message {
+    let data = serde_json::to_string(data).unwrap_or_else(|_| "{}".to_string());
+    Message::text(format!(r#"42["{name}",{data}]"#))
+}
+
+/// Balances of the account, sent after authorizing and after each trade.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Balance {
+    pub live: Decimal,
+    pub demo: Decimal,
+    pub currency: Option<String>,
+}
+
+impl Balance {
+    /// Reads the `liveBalance`/`demoBalance` of a message, `None` if it has neither.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let amount = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| serde_json::from_value::<Decimal>(v.clone()).ok())
+        };
+        let (live, demo) = (amount("liveBalance"), amount("demoBalance"));
+        if live.is_none() && demo.is_none() {
+            return None;
+        }
+        Some(Self {
+            live: live.unwrap_or_default(),
+            demo: demo.unwrap_or_default(),
+            currency: ["currencyCode", "currency"]
+                .iter()
+                .find_map(|key| value.get(key)?.as_str())
+                .map(String::from),
+        })
+    }
+}
+
+/// A tradable asset of `instruments/list`.
+///
+/// The list is sent as arrays, the fields are read by position: the symbol second, the name
+/// third, whether the market is open fifteenth and the payout in percent nineteenth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instrument {
+    pub symbol: String,
+    pub name: String,
+    pub is_open: bool,
+    pub payout: i32,
+}
+
+impl Instrument {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let fields = value.as_array()?;
+        Some(Self {
+            symbol: fields.get(1)?.as_str()?.to_string(),
+            name: fields.get(2)?.as_str()?.to_string(),
+            is_open: fields.get(14).and_then(Value::as_bool).unwrap_or(false),
+            payout: fields
+                .get(18)
+                .and_then(Value::as_f64)
+                .map_or(0, |payout| payout as i32),
+        })
+    }
+
+    pub fn list(value: &Value) -> HashMap<String, Self> {
+        value
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Self::from_value)
+                    .map(|instrument| (instrument.symbol.clone(), instrument))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// `orders/open` request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrder {
+    pub asset: String,
+    pub amount: Decimal,
+    /// Duration in seconds.
+    pub time: u32,
+    pub action: Action,
+    pub is_demo: u8,
+    pub tournament_id: u32,
+    pub request_id: u64,
+    pub option_type: u32,
+}
+
+/// `history/load` request, answered with the candles of `period` seconds of the `offset`
+/// seconds before `time`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryRequest {
+    pub asset: String,
+    /// Echoed in the answer.
+    pub index: u64,
+    pub time: i64,
+    pub offset: i64,
+    pub period: u32,
+}
+
+/// A deal of `s_orders/open` and `s_orders/close`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotexDeal {
+    pub id: Uuid,
+    #[serde(default)]
+    pub open_time: String,
+    #[serde(default)]
+    pub close_time: String,
+    #[serde(with = "crate::pocketoption::utils::unix_timestamp")]
+    pub open_timestamp: DateTime<Utc>,
+    #[serde(with = "crate::pocketoption::utils::unix_timestamp")]
+    pub close_timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub uid: u64,
+    #[serde(default)]
+    pub request_id: Option<u64>,
+    pub amount: Decimal,
+    #[serde(default)]
+    pub profit: Decimal,
+    #[serde(default)]
+    pub percent_profit: i32,
+    #[serde(default)]
+    pub percent_loss: i32,
+    pub open_price: Decimal,
+    #[serde(default)]
+    pub close_price: Decimal,
+    /// 0 for a call, 1 for a put.
+    pub command: i32,
+    pub asset: String,
+    #[serde(default)]
+    pub is_demo: u32,
+    #[serde(default)]
+    pub open_ms: i32,
+    #[serde(default)]
+    pub currency: String,
+}
+
+impl From<QuotexDeal> for Deal {
+    fn from(deal: QuotexDeal) -> Self {
+        Deal {
+            id: deal.id,
+            open_time: deal.open_time,
+            close_time: deal.close_time,
+            open_timestamp: deal.open_timestamp,
+            close_timestamp: deal.close_timestamp,
+            refund_time: None,
+            refund_timestamp: None,
+            uid: deal.uid,
+            request_id: deal.request_id.map(RequestId::Number),
+            amount: deal.amount,
+            profit: deal.profit,
+            percent_profit: deal.percent_profit,
+            percent_loss: deal.percent_loss,
+            open_price: deal.open_price,
+            close_price: deal.close_price,
+            command: deal.command,
+            asset: deal.asset,
+            is_demo: deal.is_demo,
+            copy_ticket: String::new(),
+            open_ms: deal.open_ms,
+            close_ms: None,
+            option_type: OPTION_TYPE as i32,
+            is_rollover: None,
+            is_copy_signal: None,
+            is_ai: None,
+            currency: deal.currency,
+            amount_usd: None,
+            amount_usd2: None,
+            slippage: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payloads() {
+        let text = Message::text(r#"42["s_orders/open",{"error":"Not enough money"}]"#);
+        assert_eq!(payload(&text).unwrap()["error"], "Not enough money");
+        let placeholder = Message::text(r#"451-["s_orders/open",{"_placeholder":true,"num":0}]"#);
+        assert!(payload(&placeholder).is_none());
+        let mut binary = vec![BINARY_ATTACHMENT];
+        binary.extend_from_slice(br#"{"liveBalance":0,"demoBalance":10000.5}"#);
+        let balance = Balance::from_value(&payload(&Message::binary(binary)).unwrap()).unwrap();
+        assert_eq!(balance.demo, Decimal::new(100005, 1));
+        assert!(Balance::from_value(&json!({"result": true})).is_none());
+
+        let deal: QuotexDeal = serde_json::from_value(json!({
+            "id": "67f1c0a2-1b2c-4d5e-8f90-123456789abc",
+            "openTimestamp": 1700000000,
+            "closeTimestamp": 1700000060,
+            "requestId": 17,
+            "amount": 10,
+            "percentProfit": 85,
+            "openPrice": 1.0845,
+            "command": 1,
+            "asset": "EURUSD_otc",
+            "isDemo": 1,
+            "tournamentId": 0
+        }))
+        .unwrap();
+        let deal = Deal::from(deal);
+        assert_eq!(deal.request_id, Some(RequestId::Number(17)));
+        assert_eq!((deal.command, deal.percent_profit), (1, 85));
+        assert!(deal.close_price.is_zero());
+
+        let mut fields = vec![json!(null); 19];
+        fields[1] = json!("EURUSD_otc");
+        fields[2] = json!("EUR/USD (OTC)");
+        fields[14] = json!(true);
+        fields[18] = json!(92);
+        let list = Instrument::list(&json!([fields, [1]]));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list["EURUSD_otc"].payout, 92);
+        assert!(list["EURUSD_otc"].is_open);
+    }
+}
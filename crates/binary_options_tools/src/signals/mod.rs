@@ -0,0 +1,156 @@
+//! Trade signals received from external sources.
+//!
+//! - `tradingview`: webhook receiver for TradingView alerts (`tradingview` feature)
+//! - `telegram`: trade notifications and signal channels through a Telegram bot
+//!
+//! Every source produces [`Signal`]s, which are executed against any [`Market`].
+
+pub mod telegram;
+#[cfg(feature = "tradingview")]
+pub mod tradingview;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::framework::market::Market;
+use crate::pocketoption::error::PocketResult;
+use crate::pocketoption::types::{Action, Deal};
+
+/// A trade instruction parsed from an external source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Signal {
+    pub asset: String,
+    pub action: Action,
+    /// Trade amount, the source's default amount is used when missing.
+    pub amount: Option<Decimal>,
+    /// Expiration in seconds.
+    pub expiry: u32,
+}
+
+impl Signal {
+    /// Places the trade, using `default_amount` if the signal has no amount.
+    pub async fn execute(
+        &self,
+        market: &dyn Market,
+        default_amount: Decimal,
+    ) -> PocketResult<(Uuid, Deal)> {
+        let amount = self.amount.unwrap_or(default_amount);
+        match self.action {
+            Action::Call => market.buy(&self.asset, amount, self.expiry).await,
+            Action::Put => market.sell(&self.asset, amount, self.expiry).await,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SignalError {
+    #[error("Missing field {0}")]
+    MissingField(String),
+    #[error("Invalid value for {field}: {value}")]
+    InvalidField { field: String, value: String },
+    #[error("Duplicate signal ignored")]
+    Duplicate,
+}
+
+/// Parses the usual ways of writing a direction: `call`/`buy`/`long`/`up` and
+/// `put`/`sell`/`short`/`down`, case insensitive.
+pub fn parse_action(value: &str) -> Option<Action> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "call" | "buy" | "long" | "up" | "higher" => Some(Action::Call),
+        "put" | "sell" | "short" | "down" | "lower" => Some(Action::Put),
+        _ => None,
+    }
+}
+
+/// Parses an expiry written in seconds (`60`, `60s`), minutes (`5m`, `M5`) or hours (`1h`).
+pub fn parse_expiry(value: &str) -> Option<u32> {
+    let value = value.trim().to_ascii_lowercase();
+    if let Some(minutes) = value.strip_prefix('m') {
+        return minutes.parse::<u32>().ok().map(|m| m * 60);
+    }
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value.as_str(), "s"),
+    };
+    let number: u32 = number.parse().ok()?;
+    match unit.trim() {
+        "s" | "sec" | "secs" => Some(number),
+        "m" | "min" | "mins" => Some(number * 60),
+        "h" | "hour" | "hours" => Some(number * 3600),
+        _ => None,
+    }
+}
+
+/// Rejects signals identical to one accepted less than `window` ago,
+/// so webhook retries or reposted messages do not open the same trade twice.
+#[derive(Debug)]
+pub struct SignalDedup {
+    window: Duration,
+    seen: HashMap<Signal, Instant>,
+}
+
+impl SignalDedup {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Returns `Err(SignalError::Duplicate)` if `signal` was already accepted inside the window.
+    pub fn check(&mut self, signal: &Signal) -> Result<(), SignalError> {
+        let now = Instant::now();
+        let window = self.window;
+        self.seen
+            .retain(|_, seen| now.duration_since(*seen) < window);
+        if self.seen.contains_key(signal) {
+            return Err(SignalError::Duplicate);
+        }
+        self.seen.insert(signal.clone(), now);
+        Ok(())
+    }
+
+    /// Forgets an accepted `signal`, so it is accepted again, e.g. after trading it failed.
+    pub fn forget(&mut self, signal: &Signal) {
+        self.seen.remove(signal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_action_and_expiry() {
+        assert_eq!(parse_action("BUY"), Some(Action::Call));
+        assert_eq!(parse_action(" short "), Some(Action::Put));
+        assert_eq!(parse_action("hold"), None);
+
+        assert_eq!(parse_expiry("60"), Some(60));
+        assert_eq!(parse_expiry("30s"), Some(30));
+        assert_eq!(parse_expiry("5m"), Some(300));
+        assert_eq!(parse_expiry("M5"), Some(300));
+        assert_eq!(parse_expiry("1h"), Some(3600));
+        assert_eq!(parse_expiry("5 days"), None);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_window() {
+        let mut dedup = SignalDedup::new(Duration::from_millis(50));
+        let signal = Signal {
+            asset: "EURUSD_otc".into(),
+            action: Action::Call,
+            amount: None,
+            expiry: 60,
+        };
+        assert!(dedup.check(&signal).is_ok());
+        assert_eq!(dedup.check(&signal), Err(SignalError::Duplicate));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(dedup.check(&signal).is_ok());
+    }
+}
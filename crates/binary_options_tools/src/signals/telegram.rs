@@ -0,0 +1,357 @@
+//! Telegram integration: trade notifications and signal channels.
+//!
+//! [`TelegramBot`] sends messages through the Bot API with a bot token from `@BotFather`.
+//! [`TelegramSignals`] long-polls the bot's updates and turns messages from the configured
+//! chats into [`Signal`]s with a [`SignalParser`], e.g. `EUR/USD OTC CALL 5m`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
+
+use super::{parse_action, parse_expiry, Signal, SignalDedup};
+use crate::framework::market::Market;
+use crate::pocketoption::error::{PocketError, PocketResult};
+use crate::pocketoption::types::Deal;
+use crate::utils::init_crypto_provider;
+
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+/// Regex matching `<asset> <direction> <expiry>`, e.g. `EURUSD CALL 5m` or `EUR/USD OTC put 60`.
+pub const DEFAULT_SIGNAL_PATTERN: &str = r"(?i)(?P<asset>[A-Z]{3}/?[A-Z]{3}(?:[ _-]?OTC)?)\W+(?P<action>call|put|buy|sell|up|down)\W+(?P<expiry>M?\d+\s*[smh]?)";
+
+/// Minimal Telegram Bot API client.
+#[derive(Debug, Clone)]
+pub struct TelegramBot {
+    http: reqwest::Client,
+    base_url: String,
+    chat_id: String,
+}
+
+impl TelegramBot {
+    /// Creates a bot sending its notifications to `chat_id`, either a numeric id or `@channel`.
+    pub fn new(token: impl AsRef<str>, chat_id: impl ToString) -> PocketResult<Self> {
+        Self::with_api_url(TELEGRAM_API, token, chat_id)
+    }
+
+    /// Same as [`TelegramBot::new`] with a custom Bot API server.
+    pub fn with_api_url(
+        api_url: &str,
+        token: impl AsRef<str>,
+        chat_id: impl ToString,
+    ) -> PocketResult<Self> {
+        init_crypto_provider();
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| PocketError::General(format!("Failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            http,
+            base_url: format!("{}/bot{}", api_url.trim_end_matches('/'), token.as_ref()),
+            chat_id: chat_id.to_string(),
+        })
+    }
+
+    async fn call(&self, method: &str, body: Value) -> PocketResult<Value> {
+        let response: Value = self
+            .http
+            .post(format!("{}/{method}", self.base_url))
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if response["ok"].as_bool() != Some(true) {
+            return Err(PocketError::ServerRejected {
+                reason: format!(
+                    "Telegram {method} failed: {}",
+                    response["description"].as_str().unwrap_or("unknown error")
+                ),
+            });
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Sends a plain text message to the notification chat.
+    pub async fn send_message(&self, text: impl AsRef<str>) -> PocketResult<()> {
+        self.call(
+            "sendMessage",
+            json!({ "chat_id": self.chat_id, "text": text.as_ref() }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Announces an opened trade, meant for `Strategy::on_deal_opened`.
+    pub async fn notify_trade(&self, deal: &Deal) -> PocketResult<()> {
+        let direction = if deal.command == 0 { "CALL" } else { "PUT" };
+        self.send_message(format!(
+            "Opened {direction} {} {} {} ({}s)\nId: {}",
+            deal.asset,
+            deal.amount,
+            deal.currency,
+            (deal.close_timestamp - deal.open_timestamp).num_seconds(),
+            deal.id
+        ))
+        .await
+    }
+
+    /// Announces a closed trade, meant for `Strategy::on_deal_closed`.
+    pub async fn notify_result(&self, deal: &Deal) -> PocketResult<()> {
+        let outcome = match deal.profit.cmp(&Decimal::ZERO) {
+            std::cmp::Ordering::Greater => "WIN",
+            std::cmp::Ordering::Less => "LOSS",
+            std::cmp::Ordering::Equal => "DRAW",
+        };
+        self.send_message(format!(
+            "{outcome} {} profit {} {}\nId: {}",
+            deal.asset, deal.profit, deal.currency, deal.id
+        ))
+        .await
+    }
+
+    async fn get_updates(&self, offset: i64, timeout: Duration) -> PocketResult<Vec<Value>> {
+        let result = self
+            .call(
+                "getUpdates",
+                json!({
+                    "offset": offset,
+                    "timeout": timeout.as_secs(),
+                    "allowed_updates": ["message", "channel_post"],
+                }),
+            )
+            .await?;
+        Ok(result.as_array().cloned().unwrap_or_default())
+    }
+}
+
+/// Extracts signals from message text with regex templates.
+///
+/// Templates use the named groups `asset`, `action` and `expiry`, plus an optional `amount`.
+/// They are tried in order and every match in a message becomes a signal.
+#[derive(Debug, Clone)]
+pub struct SignalParser {
+    templates: Vec<Regex>,
+    asset_suffix: String,
+}
+
+impl Default for SignalParser {
+    fn default() -> Self {
+        Self {
+            templates: vec![Regex::new(DEFAULT_SIGNAL_PATTERN).expect("valid default pattern")],
+            asset_suffix: String::new(),
+        }
+    }
+}
+
+impl SignalParser {
+    /// Creates a parser from regex templates, in order of priority.
+    pub fn new<I, S>(templates: I) -> PocketResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let templates = templates
+            .into_iter()
+            .map(|t| {
+                Regex::new(t.as_ref()).map_err(|e| {
+                    PocketError::Configuration(format!("Invalid signal template: {e}"))
+                })
+            })
+            .collect::<PocketResult<Vec<_>>>()?;
+        Ok(Self {
+            templates,
+            asset_suffix: String::new(),
+        })
+    }
+
+    /// Appended to assets that are not already OTC, e.g. `_otc` to always trade OTC pairs.
+    pub fn with_asset_suffix(mut self, suffix: impl ToString) -> Self {
+        self.asset_suffix = suffix.to_string();
+        self
+    }
+
+    /// Turns `EUR/USD OTC` into `EURUSD_otc` and `eurusd` into `EURUSD` (plus the suffix).
+    fn normalize_asset(&self, raw: &str) -> String {
+        let cleaned: String = raw
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        match cleaned.strip_suffix("OTC") {
+            Some(pair) => format!("{pair}_otc"),
+            None => format!("{cleaned}{}", self.asset_suffix),
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Vec<Signal> {
+        for template in &self.templates {
+            let signals: Vec<Signal> = template
+                .captures_iter(text)
+                .filter_map(|caps| {
+                    Some(Signal {
+                        asset: self.normalize_asset(caps.name("asset")?.as_str()),
+                        action: parse_action(caps.name("action")?.as_str())?,
+                        amount: match caps.name("amount") {
+                            Some(amount) => Some(amount.as_str().parse().ok()?),
+                            None => None,
+                        },
+                        expiry: parse_expiry(caps.name("expiry")?.as_str())?,
+                    })
+                })
+                .collect();
+            if !signals.is_empty() {
+                return signals;
+            }
+        }
+        Vec::new()
+    }
+}
+
+/// Reads signals from Telegram chats the bot is a member (or admin, for channels) of.
+pub struct TelegramSignals {
+    bot: TelegramBot,
+    parser: SignalParser,
+    chats: Vec<i64>,
+    offset: i64,
+    poll_timeout: Duration,
+    dedup: SignalDedup,
+}
+
+impl TelegramSignals {
+    /// Listens to `chats` (numeric chat ids), messages from any other chat are ignored.
+    /// An empty list accepts every chat.
+    pub fn new(bot: TelegramBot, parser: SignalParser, chats: Vec<i64>) -> Self {
+        Self {
+            bot,
+            parser,
+            chats,
+            offset: 0,
+            poll_timeout: Duration::from_secs(30),
+            dedup: SignalDedup::new(Duration::from_secs(60)),
+        }
+    }
+
+    /// Identical signals within `window` are only reported once, 60 seconds by default.
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.dedup = SignalDedup::new(window);
+        self
+    }
+
+    /// Long-polls for new messages and returns the signals they contain.
+    pub async fn poll(&mut self) -> PocketResult<Vec<Signal>> {
+        let updates = self.bot.get_updates(self.offset, self.poll_timeout).await?;
+        let mut signals = Vec::new();
+        for update in &updates {
+            if let Some(id) = update["update_id"].as_i64() {
+                self.offset = self.offset.max(id + 1);
+            }
+            for signal in self.parse_update(update) {
+                match self.dedup.check(&signal) {
+                    Ok(()) => signals.push(signal),
+                    Err(_) => debug!(target: "Telegram", "Ignoring duplicate signal {:?}", signal),
+                }
+            }
+        }
+        Ok(signals)
+    }
+
+    fn parse_update(&self, update: &Value) -> Vec<Signal> {
+        let Some(message) = update.get("message").or_else(|| update.get("channel_post")) else {
+            return Vec::new();
+        };
+        let chat = message["chat"]["id"].as_i64();
+        if !self.chats.is_empty() && !chat.is_some_and(|id| self.chats.contains(&id)) {
+            return Vec::new();
+        }
+        message["text"]
+            .as_str()
+            .or_else(|| message["caption"].as_str())
+            .map(|text| self.parser.parse(text))
+            .unwrap_or_default()
+    }
+
+    /// Trades every received signal on `market` until an API error occurs,
+    /// notifying the bot's chat of each opened trade.
+    pub async fn run(
+        mut self,
+        market: Arc<dyn Market>,
+        default_amount: Decimal,
+    ) -> PocketResult<()> {
+        loop {
+            for signal in self.poll().await? {
+                info!(target: "Telegram", "Received signal {:?}", signal);
+                match signal.execute(market.as_ref(), default_amount).await {
+                    Ok((_, deal)) => {
+                        if let Err(e) = self.bot.notify_trade(&deal).await {
+                            warn!(target: "Telegram", "Failed to send notification: {}", e);
+                        }
+                    }
+                    Err(e) => warn!(target: "Telegram", "Failed to trade {:?}: {}", signal, e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pocketoption::types::Action;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_default_parser() {
+        let parser = SignalParser::default();
+        let signals = parser.parse("🔥 SIGNAL\nEUR/USD OTC CALL 5m\ngbpjpy put 60s");
+        assert_eq!(
+            signals,
+            vec![
+                Signal {
+                    asset: "EURUSD_otc".into(),
+                    action: Action::Call,
+                    amount: None,
+                    expiry: 300,
+                },
+                Signal {
+                    asset: "GBPJPY".into(),
+                    action: Action::Put,
+                    amount: None,
+                    expiry: 60,
+                },
+            ]
+        );
+        assert!(parser.parse("Good morning traders").is_empty());
+    }
+
+    #[test]
+    fn test_custom_template_with_amount() {
+        let parser = SignalParser::new([
+            r"(?P<action>BUY|SELL) (?P<asset>\w+) \$(?P<amount>[\d.]+) (?P<expiry>\d+m)",
+        ])
+        .unwrap()
+        .with_asset_suffix("_otc");
+        assert_eq!(
+            parser.parse("SELL AUDCAD $2.5 3m"),
+            vec![Signal {
+                asset: "AUDCAD_otc".into(),
+                action: Action::Put,
+                amount: Some(dec!(2.5)),
+                expiry: 180,
+            }]
+        );
+        assert!(SignalParser::new(["("]).is_err());
+    }
+
+    #[test]
+    fn test_updates_filtered_by_chat() {
+        let bot = TelegramBot::new("token", "1").unwrap();
+        let signals = TelegramSignals::new(bot, SignalParser::default(), vec![-100]);
+        let update = |chat: i64| json!({ "update_id": 1, "channel_post": { "chat": { "id": chat }, "text": "EURUSD CALL 1m" } });
+        assert_eq!(signals.parse_update(&update(-100)).len(), 1);
+        assert!(signals.parse_update(&update(-200)).is_empty());
+    }
+}
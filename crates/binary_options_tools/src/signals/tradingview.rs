@@ -0,0 +1,337 @@
+//! Receiver for TradingView webhook alerts.
+//!
+//! TradingView posts the alert message as the request body, usually with a `text/plain`
+//! content type even when the message is JSON. The message is mapped to a [`Signal`] with an
+//! [`AlertTemplate`], e.g. with the default template an alert message of
+//!
+//! ```json
+//! {"ticker": "{{ticker}}", "action": "{{strategy.order.action}}", "amount": 1, "expiry": "5m", "passphrase": "..."}
+//! ```
+//!
+//! opens a 5 minute trade of 1 on the alert's ticker.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::{parse_action, parse_expiry, Signal, SignalDedup, SignalError};
+use crate::framework::market::Market;
+use crate::pocketoption::error::PocketError;
+
+/// Where to find each trade parameter in the alert JSON, as JSON pointers (`/ticker`, `/data/side`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertTemplate {
+    pub asset: String,
+    pub action: String,
+    /// Optional field holding the amount, the receiver's default amount is used when missing.
+    pub amount: Option<String>,
+    /// Optional field holding the expiry, in seconds or as `5m` / `1h`.
+    pub expiry: Option<String>,
+    /// Expiry used when the alert has none, in seconds.
+    pub default_expiry: u32,
+    /// Appended to every asset, e.g. `_otc`.
+    pub asset_suffix: String,
+    /// Renames assets, applied before the suffix, e.g. `XAUUSD` -> `#XAUUSD`.
+    pub asset_map: HashMap<String, String>,
+}
+
+impl Default for AlertTemplate {
+    fn default() -> Self {
+        Self {
+            asset: "/ticker".into(),
+            action: "/action".into(),
+            amount: Some("/amount".into()),
+            expiry: Some("/expiry".into()),
+            default_expiry: 60,
+            asset_suffix: String::new(),
+            asset_map: HashMap::new(),
+        }
+    }
+}
+
+/// Reads a string or number field as text.
+fn field(body: &Value, pointer: &str) -> Option<String> {
+    match body.pointer(pointer)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Reads and parses an optional field, a present but unparsable value is an error.
+fn optional_field<T>(
+    body: &Value,
+    pointer: &Option<String>,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Option<T>, SignalError> {
+    let Some((pointer, raw)) = pointer.as_ref().and_then(|p| Some((p, field(body, p)?))) else {
+        return Ok(None);
+    };
+    parse(&raw)
+        .map(Some)
+        .ok_or_else(|| SignalError::InvalidField {
+            field: pointer.clone(),
+            value: raw,
+        })
+}
+
+impl AlertTemplate {
+    /// Maps an alert message to a signal.
+    pub fn parse(&self, body: &Value) -> Result<Signal, SignalError> {
+        let ticker = field(body, &self.asset)
+            .ok_or_else(|| SignalError::MissingField(self.asset.clone()))?;
+        // TradingView tickers may carry the exchange, e.g. `FX:EURUSD`.
+        let ticker = ticker.rsplit(':').next().unwrap_or(&ticker);
+        let asset = self
+            .asset_map
+            .get(ticker)
+            .map(String::as_str)
+            .unwrap_or(ticker);
+        let asset = format!("{asset}{}", self.asset_suffix);
+
+        let raw_action = field(body, &self.action)
+            .ok_or_else(|| SignalError::MissingField(self.action.clone()))?;
+        let action = parse_action(&raw_action).ok_or_else(|| SignalError::InvalidField {
+            field: self.action.clone(),
+            value: raw_action,
+        })?;
+
+        let amount = optional_field(body, &self.amount, |raw| raw.parse::<Decimal>().ok())?;
+        let expiry =
+            optional_field(body, &self.expiry, parse_expiry)?.unwrap_or(self.default_expiry);
+
+        Ok(Signal {
+            asset,
+            action,
+            amount,
+            expiry,
+        })
+    }
+}
+
+/// Settings of the TradingView receiver.
+#[derive(Debug, Clone)]
+pub struct TradingViewConfig {
+    /// Address to listen on, `127.0.0.1:8090` by default.
+    pub addr: SocketAddr,
+    /// Path the alerts are posted to, `/tradingview` by default.
+    pub path: String,
+    /// When set, alerts must contain a matching `passphrase` field, as TradingView cannot
+    /// send custom headers.
+    pub passphrase: Option<String>,
+    pub template: AlertTemplate,
+    /// Amount used for alerts without one.
+    pub default_amount: Decimal,
+    /// Identical alerts received within this window are only traded once.
+    pub dedup_window: Duration,
+}
+
+impl TradingViewConfig {
+    pub fn new(default_amount: Decimal) -> Self {
+        Self {
+            addr: SocketAddr::from(([127, 0, 0, 1], 8090)),
+            path: "/tradingview".into(),
+            passphrase: None,
+            template: AlertTemplate::default(),
+            default_amount,
+            dedup_window: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    pub fn with_path(mut self, path: impl ToString) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
+    pub fn with_passphrase(mut self, passphrase: impl ToString) -> Self {
+        self.passphrase = Some(passphrase.to_string());
+        self
+    }
+
+    pub fn with_template(mut self, template: AlertTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.dedup_window = window;
+        self
+    }
+}
+
+/// Listens for TradingView alerts and trades them on a [`Market`].
+#[derive(Clone)]
+pub struct TradingViewReceiver {
+    market: Arc<dyn Market>,
+    config: Arc<TradingViewConfig>,
+    dedup: Arc<Mutex<SignalDedup>>,
+}
+
+impl TradingViewReceiver {
+    pub fn new(market: Arc<dyn Market>, config: TradingViewConfig) -> Self {
+        Self {
+            market,
+            dedup: Arc::new(Mutex::new(SignalDedup::new(config.dedup_window))),
+            config: Arc::new(config),
+        }
+    }
+
+    /// Builds the webhook route, for embedding it into an existing axum application.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(&self.config.path, post(receive))
+            .with_state(self.clone())
+    }
+
+    /// Serves the webhook on the configured address.
+    pub async fn serve(self) -> Result<(), PocketError> {
+        let addr = self.config.addr;
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| PocketError::Configuration(format!("Failed to bind {addr}: {e}")))?;
+        info!(target: "TradingView", "Listening for alerts on {}{}", addr, self.config.path);
+        axum::serve(listener, self.router())
+            .await
+            .map_err(|e| PocketError::General(format!("TradingView receiver failed: {e}")))
+    }
+
+    /// Validates an alert and maps it to a signal, without trading it.
+    async fn accept(&self, body: &str) -> Result<Signal, Response> {
+        let body: Value = serde_json::from_str(body)
+            .map_err(|e| reject(StatusCode::BAD_REQUEST, format!("Alert is not JSON: {e}")))?;
+        if let Some(passphrase) = &self.config.passphrase {
+            if body.get("passphrase").and_then(Value::as_str) != Some(passphrase.as_str()) {
+                return Err(reject(StatusCode::UNAUTHORIZED, "Invalid passphrase"));
+            }
+        }
+        let signal = self
+            .config
+            .template
+            .parse(&body)
+            .map_err(|e| reject(StatusCode::BAD_REQUEST, e))?;
+        self.dedup
+            .lock()
+            .await
+            .check(&signal)
+            .map_err(|e| reject(StatusCode::CONFLICT, e))?;
+        Ok(signal)
+    }
+}
+
+fn reject(status: StatusCode, error: impl ToString) -> Response {
+    let error = error.to_string();
+    warn!(target: "TradingView", "Rejected alert: {}", error);
+    (status, Json(json!({ "error": error }))).into_response()
+}
+
+async fn receive(State(receiver): State<TradingViewReceiver>, body: String) -> Response {
+    let signal = match receiver.accept(&body).await {
+        Ok(signal) => signal,
+        Err(response) => return response,
+    };
+    info!(target: "TradingView", "Received signal {:?}", signal);
+    match signal
+        .execute(receiver.market.as_ref(), receiver.config.default_amount)
+        .await
+    {
+        Ok((id, deal)) => {
+            info!(target: "TradingView", "Opened trade {} for {:?}", id, signal);
+            Json(json!({ "id": id, "deal": deal })).into_response()
+        }
+        Err(e) => {
+            // Nothing was traded, a retry of the alert must not be rejected as a duplicate.
+            receiver.dedup.lock().await.forget(&signal);
+            reject(StatusCode::BAD_GATEWAY, e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::virtual_market::VirtualMarket;
+    use crate::pocketoption::types::Action;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_default_template() {
+        let template = AlertTemplate {
+            asset_suffix: "_otc".into(),
+            ..Default::default()
+        };
+        let body =
+            json!({ "ticker": "FX:EURUSD", "action": "sell", "amount": 2.5, "expiry": "5m" });
+        assert_eq!(
+            template.parse(&body).unwrap(),
+            Signal {
+                asset: "EURUSD_otc".into(),
+                action: Action::Put,
+                amount: Some(dec!(2.5)),
+                expiry: 300,
+            }
+        );
+
+        let body = json!({ "ticker": "EURUSD", "action": "buy" });
+        let signal = template.parse(&body).unwrap();
+        assert_eq!(signal.amount, None);
+        assert_eq!(signal.expiry, 60);
+
+        let body = json!({ "ticker": "EURUSD", "action": "flat" });
+        assert!(matches!(
+            template.parse(&body),
+            Err(SignalError::InvalidField { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_receiver_checks_passphrase_and_duplicates() {
+        let market = Arc::new(VirtualMarket::new(dec!(100)));
+        let config = TradingViewConfig::new(dec!(1)).with_passphrase("secret");
+        let receiver = TradingViewReceiver::new(market, config);
+
+        let wrong = r#"{"ticker": "EURUSD", "action": "buy", "passphrase": "nope"}"#;
+        let response = receiver.accept(wrong).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let alert = r#"{"ticker": "EURUSD", "action": "buy", "passphrase": "secret"}"#;
+        assert!(receiver.accept(alert).await.is_ok());
+        let response = receiver.accept(alert).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_failed_trade_is_not_a_duplicate() {
+        // The virtual balance cannot cover the trade, so the market rejects it.
+        let market = Arc::new(VirtualMarket::new(dec!(0)));
+        let receiver = TradingViewReceiver::new(market, TradingViewConfig::new(dec!(1)));
+
+        let alert = r#"{"ticker": "EURUSD", "action": "buy"}"#;
+        for _ in 0..2 {
+            let response = receive(State(receiver.clone()), alert.to_string()).await;
+            assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        }
+        assert!(receiver.accept(alert).await.is_ok());
+        let response = receiver.accept(alert).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+}
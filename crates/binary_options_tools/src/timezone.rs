@@ -0,0 +1,234 @@
+//! Time zone of the times users write and read.
+//!
+//! The client and the server work in UTC. A [`TimeZoneConfig`] reads the times users type
+//! without an offset, like the open time of a pending order or the bounds of a history range,
+//! as wall clock times of their zone, and shows UTC times in that zone. A daylight saving
+//! change skips or repeats an hour of wall clock time, the [`DstPolicy`] decides what such
+//! times mean.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::offset::LocalResult;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::pocketoption::error::{PocketError, PocketResult};
+
+/// Formats of the wall clock times [`TimeZoneConfig::parse`] accepts.
+const NAIVE_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// Meaning of a wall clock time a daylight saving change skips or repeats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DstPolicy {
+    /// The first of two repeated times. A skipped time is moved forward by the length of
+    /// the gap, e.g. 02:30 becomes 03:30 when clocks go from 02:00 to 03:00.
+    #[default]
+    Earliest,
+    /// The second of two repeated times, skipped times are moved forward like `Earliest`.
+    Latest,
+    /// Both fail, for schedules that must not run at a guessed time.
+    Reject,
+}
+
+impl DstPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Earliest => "earliest",
+            Self::Latest => "latest",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+impl fmt::Display for DstPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DstPolicy {
+    type Err = PocketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "earliest" => Ok(Self::Earliest),
+            "latest" => Ok(Self::Latest),
+            "reject" => Ok(Self::Reject),
+            _ => Err(PocketError::Configuration(format!(
+                "Unknown DST policy '{s}', expected 'earliest', 'latest' or 'reject'"
+            ))),
+        }
+    }
+}
+
+/// Time zone of the user, UTC by default, see the [module documentation](self).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeZoneConfig {
+    pub zone: Tz,
+    pub dst: DstPolicy,
+}
+
+impl TimeZoneConfig {
+    pub fn new(zone: Tz) -> Self {
+        Self {
+            zone,
+            dst: DstPolicy::default(),
+        }
+    }
+
+    pub fn with_dst_policy(mut self, dst: DstPolicy) -> Self {
+        self.dst = dst;
+        self
+    }
+
+    /// Zone named `name`: an IANA name like `"Europe/Madrid"`, `"UTC"`, or `"local"` for the
+    /// zone of the operating system.
+    pub fn zone_from_name(name: &str) -> PocketResult<Tz> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            let system = iana_time_zone::get_timezone().map_err(|e| {
+                PocketError::Configuration(format!("Failed to read the system time zone: {e}"))
+            })?;
+            return Self::zone_from_name(&system);
+        }
+        if name.eq_ignore_ascii_case("utc") {
+            return Ok(Tz::UTC);
+        }
+        name.parse::<Tz>()
+            .map_err(|e| PocketError::Configuration(format!("Unknown time zone '{name}': {e}")))
+    }
+
+    /// The instant the wall clock of the zone shows `local`.
+    pub fn to_utc(&self, local: NaiveDateTime) -> PocketResult<DateTime<Utc>> {
+        let time = match (self.zone.from_local_datetime(&local), self.dst) {
+            (LocalResult::Single(time), _) => time,
+            (LocalResult::Ambiguous(earliest, _), DstPolicy::Earliest) => earliest,
+            (LocalResult::Ambiguous(_, latest), DstPolicy::Latest) => latest,
+            (LocalResult::None, DstPolicy::Earliest | DstPolicy::Latest) => {
+                // Read with the offset in force before the gap, which lands past it.
+                let before = self
+                    .zone
+                    .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+                    .fix();
+                let utc = local - TimeDelta::seconds(before.local_minus_utc().into());
+                return Ok(utc.and_utc());
+            }
+            (LocalResult::Ambiguous(..), DstPolicy::Reject) => {
+                return Err(PocketError::General(format!(
+                    "{local} happens twice in {}, the clocks go back then",
+                    self.zone
+                )))
+            }
+            (LocalResult::None, DstPolicy::Reject) => {
+                return Err(PocketError::General(format!(
+                    "{local} doesn't exist in {}, the clocks go forward then",
+                    self.zone
+                )))
+            }
+        };
+        Ok(time.with_timezone(&Utc))
+    }
+
+    /// Parses a time written by the user.
+    ///
+    /// Times with an offset (RFC 3339) and Unix timestamps in seconds are taken as they are.
+    /// Wall clock times (`2025-03-30 02:30:00`, `2025-03-30T02:30`, ...) and dates, read as
+    /// their midnight, are in the zone.
+    pub fn parse(&self, text: &str) -> PocketResult<DateTime<Utc>> {
+        let text = text.trim();
+        if let Ok(timestamp) = text.parse::<i64>() {
+            return DateTime::from_timestamp(timestamp, 0).ok_or_else(|| invalid_time(text));
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Ok(time.with_timezone(&Utc));
+        }
+        let local = NAIVE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| date.and_time(Default::default()))
+            })
+            .ok_or_else(|| invalid_time(text))?;
+        self.to_utc(local)
+    }
+
+    /// `time` on the wall clock of the zone.
+    pub fn localize(&self, time: DateTime<Utc>) -> DateTime<Tz> {
+        time.with_timezone(&self.zone)
+    }
+}
+
+fn invalid_time(text: &str) -> PocketError {
+    PocketError::General(format!(
+        "Invalid time '{text}', expected a Unix timestamp, an RFC 3339 time or 'YYYY-MM-DD HH:MM:SS'"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_wall_clock_times_across_dst_changes() {
+        let madrid = TimeZoneConfig::new(TimeZoneConfig::zone_from_name("Europe/Madrid").unwrap());
+        // Winter and summer offsets.
+        assert_eq!(
+            madrid.parse("2025-01-15 10:00").unwrap(),
+            utc("2025-01-15T09:00:00Z")
+        );
+        assert_eq!(
+            madrid.parse("2025-07-15T10:00:00").unwrap(),
+            utc("2025-07-15T08:00:00Z")
+        );
+        // 02:30 is skipped on the 30th of March, 02:30 happens twice on the 26th of October.
+        assert_eq!(
+            madrid.parse("2025-03-30 02:30:00").unwrap(),
+            utc("2025-03-30T01:30:00Z")
+        );
+        assert_eq!(
+            madrid.parse("2025-10-26 02:30:00").unwrap(),
+            utc("2025-10-26T00:30:00Z")
+        );
+        let latest = madrid.with_dst_policy(DstPolicy::Latest);
+        assert_eq!(
+            latest.parse("2025-10-26 02:30:00").unwrap(),
+            utc("2025-10-26T01:30:00Z")
+        );
+        let strict = madrid.with_dst_policy(DstPolicy::Reject);
+        assert!(strict.parse("2025-10-26 02:30:00").is_err());
+        assert!(strict.parse("2025-03-30 02:30:00").is_err());
+        assert!(strict.parse("2025-03-30 03:30:00").is_ok());
+
+        let local = madrid.localize(utc("2025-07-15T08:00:00Z"));
+        assert_eq!(local.to_rfc3339(), "2025-07-15T10:00:00+02:00");
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        let tz = TimeZoneConfig::default();
+        assert_eq!(tz.zone, Tz::UTC);
+        let noon = utc("2025-06-02T12:00:00Z");
+        assert_eq!(tz.parse("2025-06-02 12:00:00").unwrap(), noon);
+        assert_eq!(tz.parse(" 1748865600 ").unwrap(), noon);
+        assert_eq!(tz.parse("2025-06-02T14:00:00+02:00").unwrap(), noon);
+        assert_eq!(tz.parse("2025-06-02").unwrap(), utc("2025-06-02T00:00:00Z"));
+        assert!(tz.parse("next monday").is_err());
+
+        assert_eq!(TimeZoneConfig::zone_from_name("utc").unwrap(), Tz::UTC);
+        assert!(TimeZoneConfig::zone_from_name("Mars/Olympus").is_err());
+        assert_eq!("Latest".parse::<DstPolicy>().unwrap(), DstPolicy::Latest);
+        assert!("nearest".parse::<DstPolicy>().is_err());
+    }
+}
@@ -22,6 +22,16 @@ pub fn init_crypto_provider() {
     });
 }
 
+/// Compares secrets (auth tokens) in constant time so they cannot be guessed byte by byte.
+pub fn constant_time_eq(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
 /// Lightweight message printer for debugging purposes
 ///
 /// This handler logs all incoming WebSocket messages for debugging
@@ -148,4 +158,12 @@ mod tests {
         // but it must not crash.
         assert!(result.is_none() || result.unwrap().is_zero());
     }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("secret", "secret"));
+        assert!(!constant_time_eq("secret", "secreT"));
+        assert!(!constant_time_eq("secret", "secret2"));
+        assert!(!constant_time_eq("secret", ""));
+    }
 }
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::{Validator, ValidatorError};
+use crate::traits::ValidatorTrait;
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// A compiled JSONPath expression, supporting the subset needed to match websocket messages:
+/// `$` for the root, `.key` and `['key']` for object fields, `[n]` for array items and
+/// `.*` / `[*]` for every child.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, ValidatorError> {
+        let invalid = |reason: &str| ValidatorError::InvalidJsonPath {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        };
+        let mut chars = path.chars().peekable();
+        if chars.next() != Some('$') {
+            return Err(invalid("must start with '$'"));
+        }
+        let mut segments = Vec::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    segments.push(Segment::Wildcard);
+                }
+                '.' => {
+                    let mut key = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if matches!(c, '.' | '[' | ']') {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    if key.is_empty() {
+                        return Err(invalid("empty field name"));
+                    }
+                    segments.push(Segment::Key(key));
+                }
+                '[' => {
+                    let mut inner = String::new();
+                    let mut quote = None;
+                    loop {
+                        match chars.next() {
+                            None => return Err(invalid("unclosed '['")),
+                            Some(c)
+                                if quote.is_none()
+                                    && inner.is_empty()
+                                    && matches!(c, '\'' | '"') =>
+                            {
+                                quote = Some(c)
+                            }
+                            Some(c) if Some(c) == quote => {
+                                if chars.next() != Some(']') {
+                                    return Err(invalid("expected ']' after quoted field name"));
+                                }
+                                break;
+                            }
+                            Some(']') if quote.is_none() => break,
+                            Some(c) => inner.push(c),
+                        }
+                    }
+                    let segment = match (quote, inner.as_str()) {
+                        (Some(_), _) => Segment::Key(inner),
+                        (None, "*") => Segment::Wildcard,
+                        (None, index) => {
+                            Segment::Index(index.trim().parse().map_err(|_| {
+                                invalid("array index must be a non-negative integer")
+                            })?)
+                        }
+                    };
+                    segments.push(segment);
+                }
+                _ => return Err(invalid("expected '.' or '['")),
+            }
+        }
+        Ok(Self {
+            raw: path.to_string(),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the first value the path selects in a websocket message, parsed the same way
+    /// as for [`Validator::json_path`].
+    pub fn find_in(&self, message: &str) -> Option<Value> {
+        let doc = document(message)?;
+        self.select(&doc).first().map(|value| (*value).clone())
+    }
+
+    /// Returns every value the path selects in `root`.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            current = current
+                .into_iter()
+                .flat_map(|value| -> Vec<&'a Value> {
+                    match (segment, value) {
+                        (Segment::Key(key), Value::Object(map)) => {
+                            map.get(key).into_iter().collect()
+                        }
+                        (Segment::Index(i), Value::Array(items)) => {
+                            items.get(*i).into_iter().collect()
+                        }
+                        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                        (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+            if current.is_empty() {
+                break;
+            }
+        }
+        current
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = ValidatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Condition applied to the values selected by a [`JsonPath`], it holds if at least one
+/// selected value satisfies it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonMatch {
+    /// The path selects at least one value.
+    Exists,
+    Eq(Value),
+    Ne(Value),
+    Gt(f64),
+    Lt(f64),
+    /// The value as text (strings without quotes) is accepted by the validator.
+    Matches(Box<Validator>),
+}
+
+impl JsonMatch {
+    pub(crate) fn depth(&self) -> usize {
+        match self {
+            JsonMatch::Matches(validator) => validator.depth(),
+            _ => 0,
+        }
+    }
+
+    fn holds(&self, value: &Value) -> bool {
+        match self {
+            JsonMatch::Exists => true,
+            JsonMatch::Eq(expected) => json_eq(value, expected),
+            JsonMatch::Ne(expected) => !json_eq(value, expected),
+            JsonMatch::Gt(bound) => value.as_f64().is_some_and(|v| v > *bound),
+            JsonMatch::Lt(bound) => value.as_f64().is_some_and(|v| v < *bound),
+            JsonMatch::Matches(validator) => match value {
+                Value::String(s) => validator.call(s),
+                other => validator.call(&other.to_string()),
+            },
+        }
+    }
+
+    pub(crate) fn matches(&self, selected: &[&Value]) -> bool {
+        selected.iter().any(|value| self.holds(value))
+    }
+}
+
+/// The path selects at least one value.
+pub fn exists() -> JsonMatch {
+    JsonMatch::Exists
+}
+
+/// A selected value equals `value`, numbers are compared by value (`1 == 1.0`).
+pub fn eq(value: impl Into<Value>) -> JsonMatch {
+    JsonMatch::Eq(value.into())
+}
+
+/// A selected value differs from `value`.
+pub fn ne(value: impl Into<Value>) -> JsonMatch {
+    JsonMatch::Ne(value.into())
+}
+
+/// A selected value is a number greater than `bound`.
+pub fn gt(bound: f64) -> JsonMatch {
+    JsonMatch::Gt(bound)
+}
+
+/// A selected value is a number lower than `bound`.
+pub fn lt(bound: f64) -> JsonMatch {
+    JsonMatch::Lt(bound)
+}
+
+/// A selected value, as text, is accepted by `validator`.
+pub fn matches(validator: Validator) -> JsonMatch {
+    JsonMatch::Matches(Box::new(validator))
+}
+
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => a == b,
+    }
+}
+
+/// Parses the JSON document a path is evaluated against.
+///
+/// Socket.IO event frames such as `42["successopenOrder",{...}]` (or `451-[...]`) are exposed
+/// as `{"event": "successopenOrder", "data": {...}}`, anything else must be plain JSON.
+pub(crate) fn document(data: &str) -> Option<Value> {
+    let start = data.find(['[', '{'])?;
+    let (prefix, body) = data.split_at(start);
+    if !prefix.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return None;
+    }
+    let value: Value = serde_json::from_str(body).ok()?;
+    match value {
+        Value::Array(mut items)
+            if !prefix.is_empty() && matches!(items.first(), Some(Value::String(_))) =>
+        {
+            let data = if items.len() > 1 {
+                items.swap_remove(1)
+            } else {
+                Value::Null
+            };
+            let event = items.swap_remove(0);
+            Some(serde_json::json!({ "event": event, "data": data }))
+        }
+        value => Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path_parse_and_select() {
+        let doc =
+            json!({"data": {"requestId": 7, "items": [{"id": "a"}, {"id": "b"}], "x.y": true}});
+        let select = |path: &str| {
+            JsonPath::parse(path)
+                .unwrap()
+                .select(&doc)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(select("$.data.requestId"), vec![json!(7)]);
+        assert_eq!(select("$['data'].items[1].id"), vec![json!("b")]);
+        assert_eq!(select("$.data.items[*].id"), vec![json!("a"), json!("b")]);
+        assert_eq!(select("$.data[\"x.y\"]"), vec![json!(true)]);
+        assert!(select("$.data.missing.deeper").is_empty());
+        assert_eq!(select("$"), vec![doc.clone()]);
+
+        for invalid in ["data", "$.", "$[1", "$[-1]", "$.a]"] {
+            assert!(
+                JsonPath::parse(invalid).is_err(),
+                "{invalid} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_document_unwraps_socket_io_events() {
+        assert_eq!(
+            document(r#"42["successopenOrder",{"requestId":"abc"}]"#),
+            Some(json!({"event": "successopenOrder", "data": {"requestId": "abc"}}))
+        );
+        assert_eq!(document(r#"{"a":1}"#), Some(json!({"a": 1})));
+        assert_eq!(document(r#"["a",1]"#), Some(json!(["a", 1])));
+        assert_eq!(document("2"), None);
+        assert_eq!(document(r#"abc{"a":1}"#), None);
+    }
+}
@@ -1,11 +1,48 @@
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use regex::Regex;
 use serde_json::Value;
 
 use crate::traits::ValidatorTrait;
 
+mod json_path;
+
+pub use json_path::{eq, exists, gt, lt, matches, ne, JsonMatch, JsonPath};
+
+/// Maximum nesting of `All`/`Any`/`Not` combinators accepted by [`Validator::check_depth`].
+pub const MAX_VALIDATOR_DEPTH: usize = 32;
+
+/// Number of compiled patterns kept by [`cached_regex`] before the cache is cleared.
+const REGEX_CACHE_CAPACITY: usize = 256;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ValidatorError {
+    #[error("Invalid JSONPath '{path}': {reason}")]
+    InvalidJsonPath { path: String, reason: String },
+    #[error("Validator is nested {depth} levels deep, the limit is {max}")]
+    TooDeep { depth: usize, max: usize },
+    #[error("Invalid regex: {0}")]
+    Regex(#[from] regex::Error),
+}
+
+/// Compiles `pattern` once and returns the cached copy on later calls.
+pub fn cached_regex(pattern: &str) -> Result<Regex, ValidatorError> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(regex) = cache.lock().unwrap().get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = Regex::new(pattern)?;
+    let mut cache = cache.lock().unwrap();
+    if cache.len() >= REGEX_CACHE_CAPACITY {
+        cache.clear();
+    }
+    cache.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
 #[derive(Clone, Default)]
 pub enum Validator {
     #[default]
@@ -17,6 +54,8 @@ pub enum Validator {
     Not(Box<Validator>),
     All(Box<Vec<Validator>>),
     Any(Box<Vec<Validator>>),
+    /// Parses the message as JSON and checks the values selected by the path.
+    JsonPath(JsonPath, JsonMatch),
     Custom(Arc<dyn ValidatorTrait + Send + Sync>),
 }
 
@@ -31,6 +70,11 @@ impl fmt::Debug for Validator {
             Validator::Not(v) => f.debug_tuple("Validator::Not").field(v).finish(),
             Validator::All(v) => f.debug_tuple("Validator::All").field(v).finish(),
             Validator::Any(v) => f.debug_tuple("Validator::Any").field(v).finish(),
+            Validator::JsonPath(p, m) => f
+                .debug_tuple("Validator::JsonPath")
+                .field(&p.as_str())
+                .field(m)
+                .finish(),
             Validator::Custom(_) => write!(f, "Validator::Custom(<opaque>)"),
         }
     }
@@ -53,6 +97,19 @@ impl Validator {
         Validator::Regex(regex)
     }
 
+    /// Builds a regex validator, reusing the compiled pattern if it was built before.
+    pub fn regex_cached(pattern: &str) -> Result<Self, ValidatorError> {
+        cached_regex(pattern).map(Validator::Regex)
+    }
+
+    /// Matches messages whose JSON values at `path` satisfy `matcher`, e.g.
+    /// `Validator::json_path("$.data.requestId", eq(id))`.
+    ///
+    /// Socket.IO event frames are matched as `{"event": <name>, "data": <payload>}`.
+    pub fn json_path(path: &str, matcher: JsonMatch) -> Result<Self, ValidatorError> {
+        Ok(Validator::JsonPath(JsonPath::parse(path)?, matcher))
+    }
+
     pub fn negate(validator: Validator) -> Self {
         Validator::Not(Box::new(validator))
     }
@@ -69,6 +126,29 @@ impl Validator {
         Validator::Custom(validator)
     }
 
+    /// Number of nested validators, a leaf validator has a depth of 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            Validator::Not(v) => 1 + v.depth(),
+            Validator::All(v) | Validator::Any(v) => {
+                1 + v.iter().map(Validator::depth).max().unwrap_or(0)
+            }
+            Validator::JsonPath(_, m) => 1 + m.depth(),
+            _ => 1,
+        }
+    }
+
+    /// Rejects validators nested deeper than [`MAX_VALIDATOR_DEPTH`].
+    pub fn check_depth(&self) -> Result<(), ValidatorError> {
+        match self.depth() {
+            depth if depth > MAX_VALIDATOR_DEPTH => Err(ValidatorError::TooDeep {
+                depth,
+                max: MAX_VALIDATOR_DEPTH,
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Adds a new validator to the current validator.
     /// If the current validator is `All` or `Any`, it appends to the existing list.
     /// If the current validator is a single validator, it wraps it in an `All` validator with the new one.
@@ -94,6 +174,7 @@ impl PartialEq for Validator {
             (Validator::Not(a), Validator::Not(b)) => a == b,
             (Validator::All(a), Validator::All(b)) => a == b,
             (Validator::Any(a), Validator::Any(b)) => a == b,
+            (Validator::JsonPath(pa, ma), Validator::JsonPath(pb, mb)) => pa == pb && ma == mb,
             (Validator::Custom(a), Validator::Custom(b)) => Arc::ptr_eq(a, b),
             _ => false,
         }
@@ -111,6 +192,9 @@ impl ValidatorTrait for Validator {
             Validator::Not(validator) => !validator.call(data),
             Validator::All(validators) => validators.iter().all(|v| v.call(data)),
             Validator::Any(validators) => validators.iter().any(|v| v.call(data)),
+            Validator::JsonPath(path, matcher) => {
+                json_path::document(data).is_some_and(|doc| matcher.matches(&path.select(&doc)))
+            }
             Validator::Custom(validator) => validator.call(data),
         }
     }
@@ -253,4 +337,49 @@ mod tests {
         let re2 = Regex::new("a").unwrap();
         assert_eq!(Validator::regex(re1), Validator::regex(re2));
     }
+
+    #[test]
+    fn test_validator_json_path() {
+        let id = "5f8e0d2a-0000-4000-8000-000000000001";
+        let v = Validator::json_path("$.data.requestId", eq(id)).unwrap();
+        assert!(v.call(&format!(r#"42["successopenOrder",{{"requestId":"{id}"}}]"#)));
+        assert!(v.call(&format!(r#"{{"data":{{"requestId":"{id}"}}}}"#)));
+        assert!(!v.call(r#"42["successopenOrder",{"requestId":"other"}]"#));
+        assert!(!v.call("not json"));
+
+        let amount = Validator::json_path("$.data.amount", gt(5.0)).unwrap();
+        assert!(amount.call(r#"{"data":{"amount":10}}"#));
+        assert!(Validator::json_path("$.data.amount", eq(10.0))
+            .unwrap()
+            .call(r#"{"data":{"amount":10}}"#));
+        let event =
+            Validator::json_path("$.event", matches(Validator::starts_with("success".into())))
+                .unwrap();
+        assert!(event.call(r#"42["successopenOrder",{}]"#));
+        assert!(Validator::json_path("data", exists()).is_err());
+    }
+
+    #[test]
+    fn test_validator_depth_limit() {
+        let mut v = Validator::contains("a".into());
+        for _ in 0..MAX_VALIDATOR_DEPTH - 1 {
+            v = Validator::negate(v);
+        }
+        assert_eq!(v.depth(), MAX_VALIDATOR_DEPTH);
+        assert!(v.check_depth().is_ok());
+        let v = Validator::any(vec![Validator::None, v]);
+        assert!(matches!(
+            v.check_depth(),
+            Err(ValidatorError::TooDeep { depth, .. }) if depth == MAX_VALIDATOR_DEPTH + 1
+        ));
+    }
+
+    #[test]
+    fn test_regex_cache() {
+        let a = cached_regex(r"^\d+$").unwrap();
+        let b = cached_regex(r"^\d+$").unwrap();
+        assert_eq!(a.as_str(), b.as_str());
+        assert!(Validator::regex_cached(r"^\d+$").unwrap().call("123"));
+        assert!(Validator::regex_cached("(").is_err());
+    }
 }
@@ -0,0 +1,743 @@
+//! End to end tests of the PocketOption client against the scripted server of
+//! `pocketoption::fixtures`, no ssid or network access needed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use binary_options_tools::config::{Config, InitProfile, KeepAliveConfig};
+use binary_options_tools::pocketoption::candle::SubscriptionType;
+use binary_options_tools::pocketoption::error::{FatalError, PocketError};
+use binary_options_tools::pocketoption::fixtures::{self, MOCK_SSID};
+use binary_options_tools::pocketoption::state::State;
+use binary_options_tools::pocketoption::types::{Action, TradeOptions};
+use binary_options_tools::pocketoption::PocketOption;
+use binary_options_tools::reconnect::{LimitAction, ReconnectEvent};
+use binary_options_tools_core::connector::{Connector, ConnectorError, ConnectorResult, WsStream};
+use binary_options_tools_core::frame_dump::{read_frame_dump, FrameDirection};
+use binary_options_tools_core::reimports::Message;
+use binary_options_tools_core::status::ConnectionStatus;
+use binary_options_tools_core::testing::{MockConnector, ScriptStep};
+use futures_util::StreamExt;
+use rust_decimal_macros::dec;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn connect(server: MockConnector) -> PocketOption {
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        ..Config::default()
+    };
+    PocketOption::new_with_connector(MOCK_SSID, server, config)
+        .await
+        .expect("mock server connection failed")
+}
+
+fn server() -> MockConnector {
+    fixtures::mock_server(dec!(1000), &[fixtures::asset(1, "EURUSD_otc", 92)])
+}
+
+#[tokio::test]
+async fn test_login_loads_balance_and_assets() {
+    let server = server();
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+
+    assert_eq!(client.balance().await, dec!(1000));
+    assert!(client.is_demo());
+    let assets = client.assets().await.unwrap();
+    assert_eq!(assets.get("EURUSD_otc").unwrap().payout, 92);
+
+    // The client asks for its data once the server accepted the session.
+    let requested = server
+        .wait_for(TIMEOUT, |msg| {
+            msg.to_text().is_ok_and(|t| t == r#"42["getBalance"]"#)
+        })
+        .await;
+    assert!(requested.is_some());
+    assert_eq!(server.connections(), 1);
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_build_offline_connects_later() {
+    let server = server();
+    let client = PocketOption::builder()
+        .ssid(MOCK_SSID)
+        .connector(server.clone())
+        .config(Config {
+            connection_initialization_timeout: TIMEOUT,
+            ..Config::default()
+        })
+        .build_offline()
+        .await
+        .unwrap();
+    assert_eq!(client.status(), ConnectionStatus::Disconnected);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(server.connections(), 0);
+
+    client.connect().await.unwrap();
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    assert_eq!(server.connections(), 1);
+    assert_eq!(client.balance().await, dec!(1000));
+    client.shutdown().await.unwrap();
+}
+
+/// A connector to a server that is never reachable.
+struct Unreachable;
+
+#[async_trait::async_trait]
+impl Connector<State> for Unreachable {
+    async fn connect(&self, _: Arc<State>) -> ConnectorResult<WsStream> {
+        Err(ConnectorError::Custom("unreachable".into()))
+    }
+
+    async fn disconnect(&self) -> ConnectorResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_fatal_error_when_reconnects_are_exhausted() {
+    let config = Config {
+        connection_initialization_timeout: Duration::from_secs(1),
+        max_allowed_loops: 2,
+        reconnect_time: Duration::from_millis(10),
+        reconnect_jitter: 0.0,
+        ..Config::default()
+    };
+    let client = PocketOption::builder()
+        .ssid(MOCK_SSID)
+        .connector(Unreachable)
+        .config(config)
+        .build_offline()
+        .await
+        .unwrap();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    client.on_fatal(move |error| {
+        let _ = sender.send(error);
+    });
+
+    assert!(client.connect().await.is_err());
+    let error = tokio::time::timeout(TIMEOUT, client.fatal_error())
+        .await
+        .expect("the runner did not stop");
+    assert!(matches!(error, FatalError::ReconnectExhausted { .. }));
+    assert_eq!(receiver.await.unwrap(), error);
+}
+
+fn flapping_config(limit_action: LimitAction) -> Config {
+    Config {
+        connection_initialization_timeout: Duration::from_secs(1),
+        max_allowed_loops: 2,
+        reconnect_time: Duration::from_millis(10),
+        reconnect_jitter: 0.0,
+        loop_limit_action: limit_action,
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn test_connections_dropped_right_away_count_as_failures() {
+    let client = PocketOption::builder()
+        .ssid(MOCK_SSID)
+        .connector(server().close())
+        .config(flapping_config(LimitAction::Abort))
+        .build_offline()
+        .await
+        .unwrap();
+    let _ = client.connect().await;
+
+    let error = tokio::time::timeout(TIMEOUT, client.fatal_error())
+        .await
+        .expect("the runner kept reconnecting");
+    let FatalError::ReconnectExhausted {
+        attempts,
+        last_error,
+    } = error
+    else {
+        panic!("unexpected fatal error {error:?}");
+    };
+    assert_eq!(attempts, 2);
+    assert!(last_error.unwrap().starts_with("connection lost after"));
+}
+
+#[tokio::test]
+async fn test_reconnect_limit_action_keeps_running() {
+    let server = server().close();
+    let client = PocketOption::builder()
+        .ssid(MOCK_SSID)
+        .connector(server.clone())
+        .config(flapping_config(LimitAction::Reconnect))
+        .build_offline()
+        .await
+        .unwrap();
+    let mut events = client.reconnect_events();
+    let _ = client.connect().await;
+
+    let diagnostics = tokio::time::timeout(TIMEOUT, async {
+        loop {
+            if let ReconnectEvent::LimitReached(diagnostics) = events.recv().await.unwrap() {
+                return diagnostics;
+            }
+        }
+    })
+    .await
+    .expect("the limit was never reached");
+    assert_eq!(diagnostics.attempts, 2);
+    assert_eq!(diagnostics.action, LimitAction::Reconnect);
+    assert!(diagnostics.cycles_last_minute >= 2);
+
+    // The runner starts over instead of stopping.
+    let before = server.connections();
+    tokio::time::timeout(TIMEOUT, async {
+        while server.connections() == before {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the runner stopped reconnecting");
+    let fatal = tokio::time::timeout(Duration::from_millis(200), client.fatal_error()).await;
+    assert!(fatal.is_err());
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_is_not_fatal() {
+    let client = connect(server()).await;
+    client.shutdown().await.unwrap();
+    let fatal = tokio::time::timeout(Duration::from_millis(500), client.fatal_error()).await;
+    assert!(fatal.is_err());
+}
+
+#[tokio::test]
+async fn test_minimal_init_profile() {
+    let server = server();
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        init_profile: InitProfile::Minimal,
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server.clone(), config)
+        .await
+        .unwrap();
+
+    // Returned once the balance is known, without waiting for it.
+    assert_eq!(client.balances().await.len(), 1);
+    server
+        .wait_for(TIMEOUT, |msg| {
+            msg.to_text().is_ok_and(|t| t == r#"42["assets/load"]"#)
+        })
+        .await
+        .expect("assets not requested");
+    let sent = server.received_text();
+    assert!(!sent.iter().any(|t| t.contains("changeSymbol")));
+    assert!(!sent.iter().any(|t| t.contains("indicator/load")));
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_full_init_profile_waits_for_warmup() {
+    let now = chrono::Utc::now().timestamp() as f64;
+    let server = server().steps(
+        fixtures::update_stream("EURUSD_otc", now, 1.1)
+            .into_iter()
+            .map(ScriptStep::Send),
+    );
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        init_profile: InitProfile::Full,
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server, config)
+        .await
+        .unwrap();
+
+    assert!(client.assets().await.is_some());
+    assert_eq!(client.balances().await.len(), 1);
+    assert!(client.server_time_quality().await.staleness.is_some());
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_subscription_receives_pushed_ticks() {
+    let server = server();
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+
+    let mut stream = client
+        .subscribe("EURUSD_otc", SubscriptionType::none())
+        .await
+        .unwrap();
+    for frame in fixtures::update_stream("EURUSD_otc", 1_700_000_000.5, 1.0842) {
+        assert!(server.push(frame));
+    }
+    let tick = tokio::time::timeout(TIMEOUT, stream.receive())
+        .await
+        .expect("no tick received")
+        .unwrap();
+    assert_eq!(tick.symbol, "EURUSD_otc");
+    assert_eq!(tick.timestamp, 1_700_000_000);
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_concurrent_subscriptions_while_ticks_arrive() {
+    const ASSETS: [&str; 4] = ["EURUSD_otc", "GBPUSD_otc", "USDJPY_otc", "AUDCAD_otc"];
+    let assets: Vec<_> = (1..)
+        .zip(ASSETS)
+        .map(|(id, asset)| fixtures::asset(id, asset, 92))
+        .collect();
+    let server = fixtures::mock_server(dec!(1000), &assets);
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+
+    // Ticks of every asset keep arriving and the subscriptions are read while the streams
+    // subscribe
+    let feed = tokio::spawn({
+        let server = server.clone();
+        async move {
+            for second in 0.. {
+                for asset in ASSETS {
+                    let timestamp = 1_700_000_000.0 + second as f64;
+                    for frame in fixtures::update_stream(asset, timestamp, 1.5) {
+                        server.push(frame);
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(2)).await;
+            }
+        }
+    });
+    let reader = tokio::spawn({
+        let client = client.clone();
+        async move {
+            loop {
+                client.debug_snapshot().await;
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+    let subscribers = ASSETS.map(|asset| {
+        let client = client.clone();
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+            for sub_type in [SubscriptionType::none(), SubscriptionType::chunk(2)] {
+                streams.push(client.subscribe(asset, sub_type).await.unwrap());
+            }
+            for stream in &mut streams {
+                for _ in 0..5 {
+                    let candle = tokio::time::timeout(TIMEOUT, stream.receive())
+                        .await
+                        .expect("no tick received")
+                        .unwrap();
+                    assert_eq!(candle.symbol, asset);
+                }
+            }
+            streams
+        })
+    });
+    let mut streams = Vec::new();
+    for subscriber in subscribers {
+        streams.push(
+            tokio::time::timeout(TIMEOUT, subscriber)
+                .await
+                .expect("subscriber stuck")
+                .unwrap(),
+        );
+    }
+    feed.abort();
+    reader.abort();
+
+    let snapshot = client.debug_snapshot().await;
+    for asset in ASSETS {
+        assert_eq!(snapshot.subscriptions[asset].len(), 2);
+        assert_eq!(snapshot.last_ticks[asset].1, dec!(1.5));
+    }
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_audit_log_attributes_messages_to_modules() {
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        audit_log: 256,
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server(), config)
+        .await
+        .expect("mock server connection failed");
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    let since = client.audit_log(0).last().map_or(0, |record| record.seq);
+
+    let _stream = client
+        .subscribe("EURUSD_otc", SubscriptionType::none())
+        .await
+        .unwrap();
+    let is_change_symbol = |message: &Message| {
+        message
+            .to_text()
+            .is_ok_and(|text| text.starts_with("42[\"changeSymbol\""))
+    };
+    let deadline = tokio::time::Instant::now() + TIMEOUT;
+    let subscribed = loop {
+        let subscribed = client
+            .audit_log(since)
+            .into_iter()
+            .find(|record| is_change_symbol(&record.message));
+        match subscribed {
+            Some(record) => break record,
+            None if tokio::time::Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(20)).await
+            }
+            None => panic!("changeSymbol was not sent"),
+        }
+    };
+    assert_eq!(&*subscribed.source, "SubscriptionsApiModule");
+    assert!(subscribed.seq > since);
+
+    let records = client.audit_log(0);
+    let auth = records
+        .iter()
+        .find(|record| {
+            record
+                .message
+                .to_text()
+                .is_ok_and(|text| text.starts_with("42[\"auth\""))
+        })
+        .expect("auth was not audited");
+    assert_eq!(&*auth.source, "InitModule");
+    assert!(records.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_subscribe_many_tags_candles_with_their_asset() {
+    let server = fixtures::mock_server(
+        dec!(1000),
+        &[
+            fixtures::asset(1, "EURUSD_otc", 92),
+            fixtures::asset(2, "GBPUSD_otc", 88),
+        ],
+    );
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+
+    let mut stream = Box::pin(
+        client
+            .subscribe_many([
+                ("EURUSD_otc", SubscriptionType::none()),
+                ("GBPUSD_otc", SubscriptionType::none()),
+            ])
+            .await
+            .unwrap(),
+    );
+    for frame in fixtures::update_stream("GBPUSD_otc", 1_700_000_001.0, 1.2631)
+        .into_iter()
+        .chain(fixtures::update_stream(
+            "EURUSD_otc",
+            1_700_000_002.0,
+            1.0842,
+        ))
+    {
+        assert!(server.push(frame));
+    }
+    let mut received = Vec::new();
+    for _ in 0..2 {
+        let (asset, candle) = tokio::time::timeout(TIMEOUT, stream.next())
+            .await
+            .expect("no candle received")
+            .unwrap();
+        let candle = candle.unwrap();
+        assert_eq!(candle.symbol, asset);
+        received.push((asset, candle.timestamp));
+    }
+    received.sort();
+    assert_eq!(
+        received,
+        vec![
+            ("EURUSD_otc".to_string(), 1_700_000_002),
+            ("GBPUSD_otc".to_string(), 1_700_000_001),
+        ]
+    );
+
+    assert!(client
+        .subscribe_many([("UNKNOWN_otc", SubscriptionType::none())])
+        .await
+        .is_err());
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_heartbeat_reports_liveness() {
+    let server = server();
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    let _stream = client
+        .subscribe("EURUSD_otc", SubscriptionType::none())
+        .await
+        .unwrap();
+
+    let mut heartbeats = Box::pin(client.heartbeat(Duration::from_millis(50)));
+    let first = tokio::time::timeout(TIMEOUT, heartbeats.next())
+        .await
+        .expect("no heartbeat emitted")
+        .unwrap();
+    assert_eq!(first.status, client.status());
+    assert_eq!(first.subscriptions, vec!["EURUSD_otc".to_string()]);
+    assert!(first.last_message_age.is_some());
+    assert_eq!(first.pending_orders, 0);
+    let second = tokio::time::timeout(TIMEOUT, heartbeats.next())
+        .await
+        .expect("no second heartbeat")
+        .unwrap();
+    assert!(second.at >= first.at);
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_trade_lifecycle() {
+    let server = server();
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+
+    let (id, deal) = tokio::time::timeout(
+        TIMEOUT,
+        client.trade("EURUSD_otc", Action::Call, 60, dec!(10)),
+    )
+    .await
+    .expect("order not confirmed")
+    .unwrap();
+    assert_eq!(deal.id, id);
+    assert_eq!(deal.amount, dec!(10));
+    assert_eq!(deal.percent_profit, 92);
+
+    let closed = fixtures::closed(&deal, dec!(1.1));
+    for frame in fixtures::update_closed_deals(&[closed]) {
+        assert!(server.push(frame));
+    }
+    let result = tokio::time::timeout(TIMEOUT, client.result(id))
+        .await
+        .expect("no result received")
+        .unwrap();
+    assert_eq!(result.profit, dec!(9.2));
+    client.shutdown().await.unwrap();
+}
+
+async fn connect_with_duplicate_window(window: Duration) -> PocketOption {
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        duplicate_trade_window: window,
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server(), config)
+        .await
+        .unwrap();
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    client
+}
+
+fn is_duplicate(result: &Result<(uuid::Uuid, impl std::fmt::Debug), PocketError>) -> bool {
+    matches!(result, Err(PocketError::General(msg)) if msg.starts_with("Duplicate trade blocked"))
+}
+
+#[tokio::test]
+async fn test_duplicate_trade_window() {
+    let client = connect_with_duplicate_window(Duration::from_millis(500)).await;
+    let trade = || client.trade("EURUSD_otc", Action::Call, 60, dec!(10));
+
+    tokio::time::timeout(TIMEOUT, trade())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(is_duplicate(&trade().await));
+    // Another action is not the same trade.
+    tokio::time::timeout(
+        TIMEOUT,
+        client.trade("EURUSD_otc", Action::Put, 60, dec!(10)),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    // Once the window expired the identical trade is placed again.
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    tokio::time::timeout(TIMEOUT, trade())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(is_duplicate(&trade().await));
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_zero_duplicate_window_allows_identical_trades() {
+    let client = connect_with_duplicate_window(Duration::ZERO).await;
+    for _ in 0..3 {
+        tokio::time::timeout(
+            TIMEOUT,
+            client.trade("EURUSD_otc", Action::Call, 60, dec!(10)),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_allow_duplicate_bypasses_the_window() {
+    let client = connect_with_duplicate_window(Duration::from_secs(60)).await;
+    let allowed = TradeOptions::default().allow_duplicate(true);
+    let trade =
+        |options| client.trade_with_options("EURUSD_otc", Action::Call, 60, dec!(10), options);
+
+    tokio::time::timeout(TIMEOUT, trade(TradeOptions::default()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(is_duplicate(&trade(TradeOptions::default()).await));
+    for _ in 0..2 {
+        tokio::time::timeout(TIMEOUT, trade(allowed))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    // A trade placed with allow_duplicate still blocks the identical ones after it.
+    assert!(is_duplicate(&trade(TradeOptions::default()).await));
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_malformed_frames_are_quarantined() {
+    let server = server();
+    let client = connect(server.clone()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    let mut failures = client.parse_failures();
+
+    let [header, _] = fixtures::balance(dec!(1000), true);
+    assert!(server.push(header));
+    assert!(server.push(Message::binary(br#"{"balance":"lots"}"#.to_vec())));
+    let failure = tokio::time::timeout(TIMEOUT, failures.recv())
+        .await
+        .expect("no parse failure reported")
+        .unwrap();
+    assert_eq!(failure.module, "BalanceModule");
+    assert!(failure.binary);
+    assert_eq!(failure.raw, r#"{"balance":"lots"}"#);
+    assert_eq!(client.balance().await, dec!(1000));
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_keep_alive_settings_and_ping_rtt() {
+    let server = server().respond(|msg| match msg.to_text() {
+        Ok(r#"42["ping"]"#) => Some(vec![Message::text(r#"42["pong"]"#)]),
+        _ => None,
+    });
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        keep_alive: KeepAliveConfig {
+            interval: Duration::from_millis(100),
+            payload: r#"42["ping"]"#.into(),
+            pong: Some(r#"42["pong"]"#.into()),
+            reauth_interval: Some(Duration::from_millis(200)),
+        },
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server.clone(), config)
+        .await
+        .unwrap();
+
+    let auth_messages = || {
+        server
+            .received_text()
+            .iter()
+            .filter(|text| text.starts_with(r#"42["auth""#))
+            .count()
+    };
+    tokio::time::timeout(TIMEOUT, async {
+        while auth_messages() < 2 || client.ping_stats().answered == 0 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("no re-authentication or ping answer");
+    let stats = client.ping_stats();
+    assert!(stats.sent >= stats.answered);
+    assert!(stats.last_rtt.is_some());
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown_waits_for_results_and_flushes_the_dump() {
+    let path = std::env::temp_dir().join(format!("graceful-{}.jsonl.gz", uuid::Uuid::new_v4()));
+    let server = server();
+    let config = Config {
+        connection_initialization_timeout: TIMEOUT,
+        frame_dump: Some(path.clone()),
+        ..Config::default()
+    };
+    let client = PocketOption::new_with_connector(MOCK_SSID, server.clone(), config)
+        .await
+        .unwrap();
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    let (_, deal) = tokio::time::timeout(
+        TIMEOUT,
+        client.trade("EURUSD_otc", Action::Call, 60, dec!(10)),
+    )
+    .await
+    .expect("order not confirmed")
+    .unwrap();
+
+    let shutdown = tokio::spawn({
+        let client = client.clone();
+        async move { client.shutdown_graceful(TIMEOUT).await }
+    });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!shutdown.is_finished(), "the opened deal has no result yet");
+    assert!(client.is_shutting_down());
+    let rejected = client.trade("EURUSD_otc", Action::Put, 60, dec!(10)).await;
+    assert!(matches!(rejected, Err(PocketError::ShuttingDown(_))));
+
+    for frame in fixtures::update_closed_deals(&[fixtures::closed(&deal, dec!(1.1))]) {
+        assert!(server.push(frame));
+    }
+    let unresolved = tokio::time::timeout(TIMEOUT, shutdown)
+        .await
+        .expect("shutdown did not finish once the deal closed")
+        .unwrap()
+        .unwrap();
+    assert_eq!(unresolved, 0);
+
+    // The frames up to the closed deal reached the dump before the socket was closed.
+    let records = read_frame_dump(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert!(records
+        .iter()
+        .any(|r| r.dir == FrameDirection::Out && r.data.starts_with(r#"42["openOrder""#)));
+    assert!(records
+        .iter()
+        .any(|r| r.dir == FrameDirection::In && r.data.contains("updateClosedDeals")));
+}
+
+#[tokio::test]
+async fn test_graceful_shutdown_respects_timeout() {
+    let client = connect(server()).await;
+    client.wait_for_assets(TIMEOUT).await.unwrap();
+    tokio::time::timeout(
+        TIMEOUT,
+        client.trade("EURUSD_otc", Action::Call, 60, dec!(10)),
+    )
+    .await
+    .expect("order not confirmed")
+    .unwrap();
+
+    let started = std::time::Instant::now();
+    let unresolved = client
+        .shutdown_graceful(Duration::from_millis(300))
+        .await
+        .unwrap();
+    let elapsed = started.elapsed();
+    assert_eq!(unresolved, 1, "the opened deal never got a result");
+    assert!(elapsed >= Duration::from_millis(300));
+    assert!(elapsed < TIMEOUT, "shutdown took {elapsed:?}");
+}
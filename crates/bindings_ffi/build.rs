@@ -0,0 +1,9 @@
+fn main() {
+    #[cfg(feature = "header")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        cbindgen::generate(&crate_dir)
+            .expect("Failed to generate C header")
+            .write_to_file(std::path::Path::new(&crate_dir).join("include/binary_options_tools.h"));
+    }
+}
@@ -0,0 +1,196 @@
+#ifndef BINARY_OPTIONS_TOOLS_H
+#define BINARY_OPTIONS_TOOLS_H
+
+/* Generated with cbindgen, run `cargo build -p binary_options_tools_ffi --features header` to regenerate. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Size of a buffer able to hold a trade id (a UUID) with its NUL terminator.
+ */
+#define BO_ID_LEN 37
+
+/**
+ * Maximum number of candles buffered for polling, older candles are dropped first.
+ */
+#define BO_SUBSCRIPTION_QUEUE_LEN 1024
+
+enum BoAction {
+  BO_ACTION_CALL = 0,
+  BO_ACTION_PUT = 1,
+};
+typedef int32_t BoAction;
+
+/**
+ * Result of every fallible call.
+ */
+enum BoStatus {
+  BO_STATUS_OK = 0,
+  /**
+   * The requested value is not available yet (open trade, empty subscription queue).
+   */
+  BO_STATUS_PENDING = 1,
+  /**
+   * The call failed, see `bo_last_error_code` / `bo_last_error_message`.
+   */
+  BO_STATUS_ERROR = -1,
+  /**
+   * A pointer was null or a string was not valid UTF-8.
+   */
+  BO_STATUS_INVALID_ARGUMENT = -2,
+  /**
+   * An output buffer was too small, the message holds the required size.
+   */
+  BO_STATUS_BUFFER_TOO_SMALL = -3,
+};
+typedef int32_t BoStatus;
+
+/**
+ * Opaque client handle, created with [`bo_client_new`] and released with [`bo_client_free`].
+ *
+ * Each handle owns the tokio runtime driving its connection, every call blocks the
+ * calling thread until the operation completes.
+ */
+typedef struct BoClient BoClient;
+
+/**
+ * Opaque subscription handle, created with [`bo_subscribe`] and released with
+ * [`bo_subscription_free`].
+ */
+typedef struct BoSubscription BoSubscription;
+
+/**
+ * A trade, money and prices are converted to `double`, timestamps are Unix seconds.
+ */
+typedef struct BoDeal {
+  int64_t open_timestamp;
+  int64_t close_timestamp;
+  double amount;
+  double profit;
+  double open_price;
+  double close_price;
+  /**
+   * 0 for a call, 1 for a put.
+   */
+  int32_t command;
+  int32_t percent_profit;
+} BoDeal;
+
+/**
+ * An OHLC candle, `volume` is NaN when the server does not provide it.
+ */
+typedef struct BoCandle {
+  int64_t timestamp;
+  double open;
+  double high;
+  double low;
+  double close;
+  double volume;
+} BoCandle;
+
+/**
+ * Called for every candle of a subscription, from one of the client's runtime threads.
+ * `candle` is only valid for the duration of the call.
+ */
+typedef void (*BoCandleCallback)(const struct BoCandle *candle, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Connects to PocketOption with the given session id, returns `null` on failure.
+ */
+struct BoClient *bo_client_new(const char *ssid);
+
+/**
+ * Shuts the client down and releases it. Subscriptions must be freed before their client.
+ */
+void bo_client_free(struct BoClient *client);
+
+/**
+ * Writes the current account balance to `out`.
+ */
+BoStatus bo_balance(const struct BoClient *client, double *out);
+
+/**
+ * Places a trade of `amount` on `asset` expiring after `time` seconds.
+ *
+ * `action` is one of the `BoAction` values. The trade id is written to `id_buf`, which must
+ * hold at least `BO_ID_LEN` bytes, and the opened deal to `out_deal` (may be null). Both are
+ * checked before the order is placed.
+ */
+BoStatus bo_trade(const struct BoClient *client,
+                  const char *asset,
+                  int32_t action,
+                  double amount,
+                  uint32_t time,
+                  char *id_buf,
+                  uintptr_t id_len,
+                  struct BoDeal *out_deal);
+
+/**
+ * Checks whether the trade `id` has closed without blocking.
+ *
+ * Returns `BO_STATUS_OK` and fills `out_deal` once it closed, `BO_STATUS_PENDING` while it is
+ * still open. Meant for callers that cannot block, like MetaTrader's `OnTick`.
+ */
+BoStatus bo_poll_result(const struct BoClient *client, const char *id, struct BoDeal *out_deal);
+
+/**
+ * Blocks until the trade `id` closes, or `timeout_secs` elapse when it is not 0.
+ */
+BoStatus bo_result(const struct BoClient *client,
+                   const char *id,
+                   uint32_t timeout_secs,
+                   struct BoDeal *out_deal);
+
+/**
+ * Code of the last error on the calling thread, `0` if it did not come from the client
+ * or no error happened yet. Client codes are the ones documented on `PocketError::code`.
+ */
+uint32_t bo_last_error_code(void);
+
+/**
+ * Copies the message of the last error on the calling thread into `buf`.
+ *
+ * Returns the size of the message including the NUL terminator, if it is larger than `len`
+ * nothing is written, call again with a larger buffer. Returns `0` if there was no error.
+ */
+uintptr_t bo_last_error_message(char *buf, uintptr_t len);
+
+/**
+ * Subscribes to real-time candles for `asset`, aggregated into time aligned candles of
+ * `duration_secs` seconds, or one candle per price update when it is 0.
+ *
+ * With a `callback` every candle is pushed to it. Without one (for MetaTrader, which cannot
+ * pass function pointers) candles are queued and read with [`bo_subscription_poll`].
+ * Returns `null` on failure.
+ */
+struct BoSubscription *bo_subscribe(const struct BoClient *client,
+                                    const char *asset,
+                                    uint32_t duration_secs,
+                                    BoCandleCallback callback,
+                                    void *user_data);
+
+/**
+ * Pops the oldest queued candle into `out` without blocking.
+ *
+ * Returns `BO_STATUS_PENDING` when no candle is queued, and the stream's error once it ended.
+ */
+BoStatus bo_subscription_poll(const struct BoSubscription *subscription, struct BoCandle *out);
+
+/**
+ * Stops the subscription, unsubscribes from the asset and releases the handle.
+ * No callback is invoked once this returns.
+ */
+void bo_subscription_free(struct BoSubscription *subscription);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BINARY_OPTIONS_TOOLS_H */
@@ -0,0 +1,265 @@
+use std::ffi::c_char;
+use std::time::Duration;
+
+use binary_options_tools::pocketoption::{types::Action, PocketOption};
+use binary_options_tools::utils::f64_to_decimal;
+use rust_decimal::prelude::ToPrimitive;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use crate::error::{guard, guard_ptr, str_arg, write_str, FfiError};
+use crate::types::{BoAction, BoDeal, BoStatus, BO_ID_LEN};
+
+/// Opaque client handle, created with [`bo_client_new`] and released with [`bo_client_free`].
+///
+/// Each handle owns the tokio runtime driving its connection, every call blocks the
+/// calling thread until the operation completes.
+pub struct BoClient {
+    pub(crate) runtime: Runtime,
+    pub(crate) inner: PocketOption,
+}
+
+impl BoClient {
+    /// # Safety
+    /// `client` must be null or a pointer returned by [`bo_client_new`] that was not freed.
+    pub(crate) unsafe fn from_ptr<'a>(client: *const BoClient) -> Result<&'a BoClient, FfiError> {
+        client
+            .as_ref()
+            .ok_or_else(|| FfiError::invalid_argument("`client` is null"))
+    }
+}
+
+unsafe fn write_deal(out: *mut BoDeal, deal: BoDeal) {
+    if !out.is_null() {
+        *out = deal;
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, FfiError> {
+    Uuid::parse_str(id).map_err(|e| FfiError::invalid_argument(format!("Invalid trade id: {e}")))
+}
+
+/// Connects to PocketOption with the given session id, returns `null` on failure.
+///
+/// # Safety
+/// `ssid` must be a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn bo_client_new(ssid: *const c_char) -> *mut BoClient {
+    guard_ptr(|| {
+        let ssid = str_arg(ssid, "ssid")?;
+        let runtime =
+            Runtime::new().map_err(|e| FfiError::other(format!("Failed to start runtime: {e}")))?;
+        let inner = runtime.block_on(PocketOption::new(ssid))?;
+        Ok(Box::into_raw(Box::new(BoClient { runtime, inner })))
+    })
+}
+
+/// Shuts the client down and releases it. Subscriptions must be freed before their client.
+///
+/// # Safety
+/// `client` must be null or a pointer returned by [`bo_client_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn bo_client_free(client: *mut BoClient) {
+    if client.is_null() {
+        return;
+    }
+    guard(|| {
+        let client = Box::from_raw(client);
+        let _ = client.runtime.block_on(client.inner.shutdown());
+        Ok(BoStatus::Ok)
+    });
+}
+
+/// Writes the current account balance to `out`.
+///
+/// # Safety
+/// `client` must be a live client handle and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bo_balance(client: *const BoClient, out: *mut f64) -> BoStatus {
+    guard(|| {
+        let client = BoClient::from_ptr(client)?;
+        if out.is_null() {
+            return Err(FfiError::invalid_argument("`out` is null"));
+        }
+        let balance = client.runtime.block_on(client.inner.balance());
+        *out = balance.to_f64().unwrap_or_default();
+        Ok(BoStatus::Ok)
+    })
+}
+
+/// Places a trade of `amount` on `asset` expiring after `time` seconds.
+///
+/// `action` is one of the `BoAction` values. The trade id is written to `id_buf`, which must
+/// hold at least `BO_ID_LEN` bytes, and the opened deal to `out_deal` (may be null). Both are
+/// checked before the order is placed.
+///
+/// # Safety
+/// `client` must be a live client handle, `asset` a NUL terminated string, `id_buf` valid for
+/// writes of `id_len` bytes and `out_deal` null or valid for writes.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn bo_trade(
+    client: *const BoClient,
+    asset: *const c_char,
+    action: i32,
+    amount: f64,
+    time: u32,
+    id_buf: *mut c_char,
+    id_len: usize,
+    out_deal: *mut BoDeal,
+) -> BoStatus {
+    guard(|| {
+        let action = BoAction::try_from(action).map_err(|value| {
+            FfiError::invalid_argument(format!(
+                "Invalid action: {value}, expected BO_ACTION_CALL or BO_ACTION_PUT"
+            ))
+        })?;
+        let action = match action {
+            BoAction::Call => Action::Call,
+            BoAction::Put => Action::Put,
+        };
+        if id_buf.is_null() {
+            return Err(FfiError::invalid_argument("`id_buf` is null"));
+        }
+        if id_len < BO_ID_LEN {
+            return Err(FfiError::buffer_too_small(BO_ID_LEN));
+        }
+        let client = BoClient::from_ptr(client)?;
+        let asset = str_arg(asset, "asset")?;
+        let amount = f64_to_decimal(amount)
+            .ok_or_else(|| FfiError::invalid_argument(format!("Invalid amount: {amount}")))?;
+        let (id, deal) = client
+            .runtime
+            .block_on(client.inner.trade(asset, action, time, amount))?;
+        write_str(id_buf, id_len, &id.to_string())?;
+        write_deal(out_deal, BoDeal::from(&deal));
+        Ok(BoStatus::Ok)
+    })
+}
+
+/// Checks whether the trade `id` has closed without blocking.
+///
+/// Returns `BO_STATUS_OK` and fills `out_deal` once it closed, `BO_STATUS_PENDING` while it is
+/// still open. Meant for callers that cannot block, like MetaTrader's `OnTick`.
+///
+/// # Safety
+/// `client` must be a live client handle, `id` a NUL terminated string and `out_deal` null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bo_poll_result(
+    client: *const BoClient,
+    id: *const c_char,
+    out_deal: *mut BoDeal,
+) -> BoStatus {
+    guard(|| {
+        let client = BoClient::from_ptr(client)?;
+        let id = parse_id(str_arg(id, "id")?)?;
+        let closed = client.runtime.block_on(client.inner.get_closed_deals());
+        match closed.get(&id) {
+            Some(deal) => {
+                write_deal(out_deal, BoDeal::from(deal));
+                Ok(BoStatus::Ok)
+            }
+            None => Ok(BoStatus::Pending),
+        }
+    })
+}
+
+/// Blocks until the trade `id` closes, or `timeout_secs` elapse when it is not 0.
+///
+/// # Safety
+/// `client` must be a live client handle, `id` a NUL terminated string and `out_deal` null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bo_result(
+    client: *const BoClient,
+    id: *const c_char,
+    timeout_secs: u32,
+    out_deal: *mut BoDeal,
+) -> BoStatus {
+    guard(|| {
+        let client = BoClient::from_ptr(client)?;
+        let id = parse_id(str_arg(id, "id")?)?;
+        let deal = if timeout_secs == 0 {
+            client.runtime.block_on(client.inner.result(id))?
+        } else {
+            client.runtime.block_on(
+                client
+                    .inner
+                    .result_with_timeout(id, Duration::from_secs(timeout_secs.into())),
+            )?
+        };
+        write_deal(out_deal, BoDeal::from(&deal));
+        Ok(BoStatus::Ok)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+    use crate::error::bo_last_error_message;
+
+    fn last_error() -> String {
+        let mut buf = [0 as c_char; 128];
+        unsafe { bo_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        let message = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        message.to_str().unwrap().to_string()
+    }
+
+    fn trade(action: i32, id_buf: *mut c_char, id_len: usize) -> BoStatus {
+        let asset = c"EURUSD_otc".as_ptr();
+        let deal = std::ptr::null_mut();
+        // A null client is only reported once the arguments checked before trading are valid.
+        unsafe {
+            bo_trade(
+                std::ptr::null(),
+                asset,
+                action,
+                1.0,
+                60,
+                id_buf,
+                id_len,
+                deal,
+            )
+        }
+    }
+
+    #[test]
+    fn test_trade_rejects_invalid_action() {
+        let mut id = [0 as c_char; BO_ID_LEN];
+        assert_eq!(
+            trade(2, id.as_mut_ptr(), id.len()),
+            BoStatus::InvalidArgument
+        );
+        assert!(last_error().starts_with("Invalid action: 2"));
+        assert_eq!(
+            trade(-1, id.as_mut_ptr(), id.len()),
+            BoStatus::InvalidArgument
+        );
+        assert!(last_error().starts_with("Invalid action: -1"));
+
+        assert_eq!(
+            trade(1, id.as_mut_ptr(), id.len()),
+            BoStatus::InvalidArgument
+        );
+        assert_eq!(last_error(), "`client` is null");
+    }
+
+    #[test]
+    fn test_trade_checks_id_buffer_first() {
+        let mut small = [0 as c_char; BO_ID_LEN - 1];
+        assert_eq!(
+            trade(0, small.as_mut_ptr(), small.len()),
+            BoStatus::BufferTooSmall
+        );
+        assert!(last_error().contains(&format!("{BO_ID_LEN} bytes")));
+
+        assert_eq!(
+            trade(0, std::ptr::null_mut(), BO_ID_LEN),
+            BoStatus::InvalidArgument
+        );
+        assert_eq!(last_error(), "`id_buf` is null");
+    }
+}
@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use binary_options_tools::pocketoption::error::PocketError;
+
+use crate::types::BoStatus;
+
+/// Error code used for failures that do not come from the PocketOption client.
+pub(crate) const FFI_ERROR_CODE: u32 = 0;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<FfiError>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FfiError {
+    pub(crate) status: BoStatus,
+    pub(crate) code: u32,
+    pub(crate) message: String,
+}
+
+impl FfiError {
+    pub(crate) fn invalid_argument(message: impl ToString) -> Self {
+        Self {
+            status: BoStatus::InvalidArgument,
+            code: FFI_ERROR_CODE,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn other(message: impl ToString) -> Self {
+        Self {
+            status: BoStatus::Error,
+            code: FFI_ERROR_CODE,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn buffer_too_small(needed: usize) -> Self {
+        Self {
+            status: BoStatus::BufferTooSmall,
+            code: FFI_ERROR_CODE,
+            message: format!("Buffer too small, {needed} bytes are needed"),
+        }
+    }
+}
+
+impl From<PocketError> for FfiError {
+    fn from(e: PocketError) -> Self {
+        Self {
+            status: BoStatus::Error,
+            code: e.code(),
+            message: e.to_string(),
+        }
+    }
+}
+
+fn set_last_error(error: FfiError) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+/// Runs `f`, storing its error (or panic) as the last error of the calling thread.
+///
+/// Catching the panic needs the library built with `panic = "unwind"`, the workspace release
+/// profile sets it.
+pub(crate) fn guard(f: impl FnOnce() -> Result<BoStatus, FfiError>) -> BoStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(status)) => status,
+        Ok(Err(e)) => {
+            let status = e.status;
+            set_last_error(e);
+            status
+        }
+        Err(_) => {
+            set_last_error(FfiError::other("Internal panic"));
+            BoStatus::Error
+        }
+    }
+}
+
+/// Same as [`guard`] for functions returning a handle, `null` signals an error.
+pub(crate) fn guard_ptr<T>(f: impl FnOnce() -> Result<*mut T, FfiError>) -> *mut T {
+    let mut ptr = std::ptr::null_mut();
+    guard(|| {
+        ptr = f()?;
+        Ok(BoStatus::Ok)
+    });
+    ptr
+}
+
+/// Borrows a NUL terminated UTF-8 string argument.
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL terminated string valid for `'a`.
+pub(crate) unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid_argument(format!("`{name}` is null")));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| FfiError::invalid_argument(format!("`{name}` is not valid UTF-8")))
+}
+
+/// Copies `value` into `buf` as a NUL terminated string.
+///
+/// # Safety
+/// `buf` must be null or valid for writes of `len` bytes.
+pub(crate) unsafe fn write_str(buf: *mut c_char, len: usize, value: &str) -> Result<(), FfiError> {
+    let needed = value.len() + 1;
+    if buf.is_null() || len < needed {
+        return Err(FfiError::buffer_too_small(needed));
+    }
+    std::ptr::copy_nonoverlapping(value.as_ptr(), buf as *mut u8, value.len());
+    *buf.add(value.len()) = 0;
+    Ok(())
+}
+
+/// Code of the last error on the calling thread, `0` if it did not come from the client
+/// or no error happened yet. Client codes are the ones documented on `PocketError::code`.
+#[no_mangle]
+pub extern "C" fn bo_last_error_code() -> u32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(FFI_ERROR_CODE, |e| e.code))
+}
+
+/// Copies the message of the last error on the calling thread into `buf`.
+///
+/// Returns the size of the message including the NUL terminator, if it is larger than `len`
+/// nothing is written, call again with a larger buffer. Returns `0` if there was no error.
+///
+/// # Safety
+/// `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bo_last_error_message(buf: *mut c_char, len: usize) -> usize {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(e) => {
+            let _ = write_str(buf, len, &e.message);
+            e.message.len() + 1
+        }
+        None => 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_records_last_error() {
+        let status = guard(|| Err(FfiError::invalid_argument("bad asset")));
+        assert_eq!(status, BoStatus::InvalidArgument);
+        assert_eq!(bo_last_error_code(), FFI_ERROR_CODE);
+
+        let mut small = [0 as c_char; 4];
+        let needed = unsafe { bo_last_error_message(small.as_mut_ptr(), small.len()) };
+        assert_eq!(needed, "bad asset".len() + 1);
+        assert_eq!(small, [0; 4]);
+
+        let mut buf = [0 as c_char; 32];
+        unsafe { bo_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        let message = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(message.to_str().unwrap(), "bad asset");
+
+        let status = guard(|| panic!("boom"));
+        assert_eq!(status, BoStatus::Error);
+    }
+
+    #[test]
+    fn test_str_arg_rejects_null() {
+        let err = unsafe { str_arg(std::ptr::null(), "asset") }.unwrap_err();
+        assert_eq!(err.status, BoStatus::InvalidArgument);
+        let ok = unsafe { str_arg(c"EURUSD_otc".as_ptr(), "asset") }.unwrap();
+        assert_eq!(ok, "EURUSD_otc");
+    }
+
+    #[test]
+    fn test_release_profile_unwinds() {
+        // `guard` only catches panics when they unwind, `panic = 'abort'` would end the host.
+        let manifest = include_str!("../../../Cargo.toml");
+        let release = manifest
+            .split("[profile.release]")
+            .nth(1)
+            .and_then(|profile| profile.split("\n[").next())
+            .unwrap();
+        assert!(release.lines().any(|line| line.trim() == "panic = 'unwind'"));
+    }
+}
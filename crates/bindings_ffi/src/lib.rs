@@ -0,0 +1,20 @@
+//! C ABI for `binary_options_tools`.
+//!
+//! Every function is `extern "C"`, takes and returns plain C types and never unwinds into the
+//! caller. Functions that can fail return a [`BoStatus`]; details of the last failure on the
+//! calling thread are available through [`bo_last_error_code`] and [`bo_last_error_message`].
+//! The matching header lives in `include/binary_options_tools.h`.
+//!
+//! Strings passed in must be NUL terminated UTF-8. Strings returned to the caller are written
+//! into caller provided buffers, so nothing has to be freed except the handles created by
+//! `bo_client_new` and `bo_subscribe`.
+
+mod client;
+mod error;
+mod subscription;
+mod types;
+
+pub use client::*;
+pub use error::{bo_last_error_code, bo_last_error_message};
+pub use subscription::*;
+pub use types::*;
@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::ffi::{c_char, c_void};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use binary_options_tools::pocketoption::{candle::SubscriptionType, PocketOption};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::client::BoClient;
+use crate::error::{guard, guard_ptr, str_arg, FfiError};
+use crate::types::{BoCandle, BoStatus};
+
+/// Maximum number of candles buffered for polling, older candles are dropped first.
+pub const BO_SUBSCRIPTION_QUEUE_LEN: usize = 1024;
+
+/// Called for every candle of a subscription, from one of the client's runtime threads.
+/// `candle` is only valid for the duration of the call.
+pub type BoCandleCallback = extern "C" fn(candle: *const BoCandle, user_data: *mut c_void);
+
+/// Opaque subscription handle, created with [`bo_subscribe`] and released with
+/// [`bo_subscription_free`].
+pub struct BoSubscription {
+    asset: String,
+    client: PocketOption,
+    runtime: Handle,
+    queue: Arc<Mutex<Queue>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct Queue {
+    candles: VecDeque<BoCandle>,
+    error: Option<FfiError>,
+}
+
+/// Pointer supplied by the caller, handed back untouched to the callback.
+struct UserData(*mut c_void);
+
+// SAFETY: the pointer is never dereferenced on our side, the caller is responsible for making
+// whatever it points to usable from the runtime threads.
+unsafe impl Send for UserData {}
+
+/// Subscribes to real-time candles for `asset`, aggregated into time aligned candles of
+/// `duration_secs` seconds, or one candle per price update when it is 0.
+///
+/// With a `callback` every candle is pushed to it. Without one (for MetaTrader, which cannot
+/// pass function pointers) candles are queued and read with [`bo_subscription_poll`].
+/// Returns `null` on failure.
+///
+/// # Safety
+/// `client` must be a live client handle and `asset` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn bo_subscribe(
+    client: *const BoClient,
+    asset: *const c_char,
+    duration_secs: u32,
+    callback: Option<BoCandleCallback>,
+    user_data: *mut c_void,
+) -> *mut BoSubscription {
+    guard_ptr(|| {
+        let client = BoClient::from_ptr(client)?;
+        let asset = str_arg(asset, "asset")?.to_string();
+        let sub_type = match duration_secs {
+            0 => SubscriptionType::none(),
+            secs => SubscriptionType::time_aligned(Duration::from_secs(secs.into()))?,
+        };
+        let mut stream = client
+            .runtime
+            .block_on(client.inner.subscribe(asset.clone(), sub_type))?;
+
+        let queue = Arc::new(Mutex::new(Queue::default()));
+        let task_queue = queue.clone();
+        let user_data = UserData(user_data);
+        let task = client.runtime.spawn(async move {
+            let user_data = user_data;
+            loop {
+                match stream.receive().await {
+                    Ok(candle) => {
+                        let candle = BoCandle::from(&candle);
+                        match callback {
+                            Some(callback) => callback(&candle, user_data.0),
+                            None => {
+                                let mut queue = task_queue.lock().unwrap();
+                                if queue.candles.len() == BO_SUBSCRIPTION_QUEUE_LEN {
+                                    queue.candles.pop_front();
+                                }
+                                queue.candles.push_back(candle);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        task_queue.lock().unwrap().error = Some(e.into());
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::into_raw(Box::new(BoSubscription {
+            asset,
+            client: client.inner.clone(),
+            runtime: client.runtime.handle().clone(),
+            queue,
+            task,
+        })))
+    })
+}
+
+/// Pops the oldest queued candle into `out` without blocking.
+///
+/// Returns `BO_STATUS_PENDING` when no candle is queued, and the stream's error once it ended.
+///
+/// # Safety
+/// `subscription` must be a live subscription handle and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bo_subscription_poll(
+    subscription: *const BoSubscription,
+    out: *mut BoCandle,
+) -> BoStatus {
+    guard(|| {
+        let subscription = subscription
+            .as_ref()
+            .ok_or_else(|| FfiError::invalid_argument("`subscription` is null"))?;
+        if out.is_null() {
+            return Err(FfiError::invalid_argument("`out` is null"));
+        }
+        let mut queue = subscription.queue.lock().unwrap();
+        match queue.candles.pop_front() {
+            Some(candle) => {
+                *out = candle;
+                Ok(BoStatus::Ok)
+            }
+            None => match queue.error.clone() {
+                Some(e) => Err(e),
+                None => Ok(BoStatus::Pending),
+            },
+        }
+    })
+}
+
+/// Stops the subscription, unsubscribes from the asset and releases the handle.
+/// No callback is invoked once this returns.
+///
+/// # Safety
+/// `subscription` must be null or a pointer returned by [`bo_subscribe`] that was not freed,
+/// and its client must still be alive.
+#[no_mangle]
+pub unsafe extern "C" fn bo_subscription_free(subscription: *mut BoSubscription) {
+    if subscription.is_null() {
+        return;
+    }
+    guard(|| {
+        let subscription = Box::from_raw(subscription);
+        subscription.task.abort();
+        let BoSubscription {
+            asset,
+            client,
+            runtime,
+            task,
+            ..
+        } = *subscription;
+        runtime.block_on(async move {
+            let _ = task.await;
+            let _ = client.unsubscribe(asset).await;
+        });
+        Ok(BoStatus::Ok)
+    });
+}
@@ -0,0 +1,96 @@
+use binary_options_tools::pocketoption::candle::Candle;
+use binary_options_tools::pocketoption::types::Deal;
+use rust_decimal::prelude::ToPrimitive;
+
+/// Result of every fallible call.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoStatus {
+    Ok = 0,
+    /// The requested value is not available yet (open trade, empty subscription queue).
+    Pending = 1,
+    /// The call failed, see `bo_last_error_code` / `bo_last_error_message`.
+    Error = -1,
+    /// A pointer was null or a string was not valid UTF-8.
+    InvalidArgument = -2,
+    /// An output buffer was too small, the message holds the required size.
+    BufferTooSmall = -3,
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoAction {
+    Call = 0,
+    Put = 1,
+}
+
+/// Fails with the value itself when it is not a `BoAction`.
+impl TryFrom<i32> for BoAction {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(BoAction::Call),
+            1 => Ok(BoAction::Put),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Size of a buffer able to hold a trade id (a UUID) with its NUL terminator.
+pub const BO_ID_LEN: usize = 37;
+
+/// A trade, money and prices are converted to `double`, timestamps are Unix seconds.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoDeal {
+    pub open_timestamp: i64,
+    pub close_timestamp: i64,
+    pub amount: f64,
+    pub profit: f64,
+    pub open_price: f64,
+    pub close_price: f64,
+    /// 0 for a call, 1 for a put.
+    pub command: i32,
+    pub percent_profit: i32,
+}
+
+impl From<&Deal> for BoDeal {
+    fn from(deal: &Deal) -> Self {
+        Self {
+            open_timestamp: deal.open_timestamp.timestamp(),
+            close_timestamp: deal.close_timestamp.timestamp(),
+            amount: deal.amount.to_f64().unwrap_or_default(),
+            profit: deal.profit.to_f64().unwrap_or_default(),
+            open_price: deal.open_price.to_f64().unwrap_or_default(),
+            close_price: deal.close_price.to_f64().unwrap_or_default(),
+            command: deal.command,
+            percent_profit: deal.percent_profit,
+        }
+    }
+}
+
+/// An OHLC candle, `volume` is NaN when the server does not provide it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoCandle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl From<&Candle> for BoCandle {
+    fn from(candle: &Candle) -> Self {
+        Self {
+            timestamp: candle.timestamp,
+            open: candle.open.to_f64().unwrap_or_default(),
+            high: candle.high.to_f64().unwrap_or_default(),
+            low: candle.low.to_f64().unwrap_or_default(),
+            close: candle.close.to_f64().unwrap_or_default(),
+            volume: candle.volume.and_then(|v| v.to_f64()).unwrap_or(f64::NAN),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// Entry point of the package: re-exports the native binding generated by `napi build`
+// and makes subscription streams usable with `for await`.
+const binding = require("./binding.js");
+
+binding.SubscriptionStream.prototype[Symbol.asyncIterator] = async function* () {
+  try {
+    while (true) {
+      const candle = await this.next();
+      if (candle === null) {
+        return;
+      }
+      yield candle;
+    }
+  } finally {
+    // Breaking out of the loop unsubscribes from the asset.
+    await this.close();
+  }
+};
+
+module.exports = binding;
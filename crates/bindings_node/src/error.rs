@@ -0,0 +1,22 @@
+use binary_options_tools::error::BinaryOptionsError;
+use binary_options_tools::pocketoption::error::PocketError;
+use napi::{Error, Status};
+
+/// Converts library errors into JavaScript exceptions.
+///
+/// The message is prefixed with the error code when the library provides one, so scripts can
+/// match on it (`err.message.startsWith("[1003]")`) without parsing the whole text.
+pub(crate) fn to_napi(e: impl Into<BinaryOptionsError>) -> Error {
+    match e.into() {
+        BinaryOptionsError::PocketOptions(pocket) => pocket_error(pocket),
+        other => Error::new(Status::GenericFailure, other.to_string()),
+    }
+}
+
+fn pocket_error(e: PocketError) -> Error {
+    Error::new(Status::GenericFailure, format!("[{}] {}", e.code(), e))
+}
+
+pub(crate) fn invalid_arg(msg: impl ToString) -> Error {
+    Error::new(Status::InvalidArg, msg.to_string())
+}
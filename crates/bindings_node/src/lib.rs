@@ -0,0 +1,10 @@
+#![deny(clippy::all)]
+
+pub mod error;
+pub mod pocketoption;
+pub mod stream;
+pub mod types;
+
+pub use pocketoption::PocketOption;
+pub use stream::SubscriptionStream;
+pub use types::{Candle, Deal};
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use binary_options_tools::pocketoption::{
+    candle::SubscriptionType, types::Action, PocketOption as OriginalPocketOption,
+};
+use binary_options_tools::utils::f64_to_decimal;
+use napi::Result;
+use napi_derive::napi;
+use rust_decimal::prelude::ToPrimitive;
+use uuid::Uuid;
+
+use crate::error::{invalid_arg, to_napi};
+use crate::stream::SubscriptionStream;
+use crate::types::{Candle, Deal};
+
+/// PocketOption client for Node.js, the equivalent of `PocketOptionAsync` in Python.
+///
+/// ```js
+/// const { PocketOption } = require("binary-options-tools");
+/// const client = await PocketOption.connect(ssid);
+/// const deal = await client.buy("EURUSD_otc", 1.0, 60);
+/// const result = await client.result(deal.id);
+/// ```
+#[napi]
+pub struct PocketOption {
+    inner: OriginalPocketOption,
+}
+
+#[napi]
+impl PocketOption {
+    /// Connects to PocketOption with the given session id.
+    #[napi]
+    pub async fn connect(ssid: String) -> Result<PocketOption> {
+        let inner = OriginalPocketOption::new(ssid).await.map_err(to_napi)?;
+        Ok(Self { inner })
+    }
+
+    /// Connects using a specific websocket url instead of picking one automatically.
+    #[napi]
+    pub async fn connect_with_url(ssid: String, url: String) -> Result<PocketOption> {
+        let inner = OriginalPocketOption::new_with_url(ssid, url)
+            .await
+            .map_err(to_napi)?;
+        Ok(Self { inner })
+    }
+
+    /// Current account balance.
+    #[napi]
+    pub async fn balance(&self) -> f64 {
+        self.inner.balance().await.to_f64().unwrap_or_default()
+    }
+
+    #[napi]
+    pub fn is_demo(&self) -> bool {
+        self.inner.is_demo()
+    }
+
+    /// Places a Call trade of `amount` on `asset` expiring after `time` seconds.
+    #[napi]
+    pub async fn buy(&self, asset: String, amount: f64, time: u32) -> Result<Deal> {
+        self.trade(asset, Action::Call, amount, time).await
+    }
+
+    /// Places a Put trade of `amount` on `asset` expiring after `time` seconds.
+    #[napi]
+    pub async fn sell(&self, asset: String, amount: f64, time: u32) -> Result<Deal> {
+        self.trade(asset, Action::Put, amount, time).await
+    }
+
+    /// Waits for the trade with the given id to close and returns the closed deal.
+    ///
+    /// Without `timeoutSecs` it waits until the trade's expiry plus the library's grace period.
+    #[napi]
+    pub async fn result(&self, id: String, timeout_secs: Option<u32>) -> Result<Deal> {
+        let id = Uuid::parse_str(&id).map_err(|e| invalid_arg(format!("Invalid UUID: {e}")))?;
+        let deal = match timeout_secs {
+            Some(secs) => {
+                self.inner
+                    .result_with_timeout(id, Duration::from_secs(secs.into()))
+                    .await
+            }
+            None => self.inner.result(id).await,
+        };
+        deal.map(Deal::from).map_err(to_napi)
+    }
+
+    /// Fetches historical candles of `period` seconds, going back `offset` seconds.
+    #[napi]
+    pub async fn get_candles(
+        &self,
+        asset: String,
+        period: i64,
+        offset: i64,
+    ) -> Result<Vec<Candle>> {
+        self.inner
+            .get_candles(asset, period, offset)
+            .await
+            .map(|candles| candles.into_iter().map(Candle::from).collect())
+            .map_err(to_napi)
+    }
+
+    /// Subscribes to real-time candles for `asset`.
+    ///
+    /// With `durationSecs` the updates are aggregated into time aligned candles of that length,
+    /// otherwise every price update is yielded.
+    #[napi]
+    pub async fn subscribe(
+        &self,
+        asset: String,
+        duration_secs: Option<u32>,
+    ) -> Result<SubscriptionStream> {
+        let sub_type = match duration_secs {
+            Some(secs) => {
+                SubscriptionType::time_aligned(Duration::from_secs(secs.into())).map_err(to_napi)?
+            }
+            None => SubscriptionType::none(),
+        };
+        let stream = self
+            .inner
+            .subscribe(asset, sub_type)
+            .await
+            .map_err(to_napi)?;
+        Ok(SubscriptionStream::from_original(stream))
+    }
+
+    #[napi]
+    pub async fn unsubscribe(&self, asset: String) -> Result<()> {
+        self.inner.unsubscribe(asset).await.map_err(to_napi)
+    }
+
+    /// Server time as a Unix timestamp in seconds.
+    #[napi]
+    pub async fn server_time(&self) -> i64 {
+        self.inner.server_time().await.timestamp()
+    }
+
+    /// Closes the connection and stops every background task.
+    #[napi]
+    pub async fn shutdown(&self) -> Result<()> {
+        self.inner.shutdown().await.map_err(to_napi)
+    }
+}
+
+impl PocketOption {
+    async fn trade(&self, asset: String, action: Action, amount: f64, time: u32) -> Result<Deal> {
+        let amount = f64_to_decimal(amount)
+            .ok_or_else(|| invalid_arg(format!("Invalid amount: {amount}")))?;
+        let (_, deal) = self
+            .inner
+            .trade(asset, action, time, amount)
+            .await
+            .map_err(to_napi)?;
+        Ok(deal.into())
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use binary_options_tools::pocketoption::modules::subscriptions::SubscriptionStream as OriginalSubscriptionStream;
+use napi::Result;
+use napi_derive::napi;
+use tokio::sync::{watch, Mutex};
+
+use crate::error::to_napi;
+use crate::types::Candle;
+
+/// Real-time candles for one asset.
+///
+/// `index.js` makes this an async iterable, so it can be consumed with
+/// `for await (const candle of stream)`. Iteration ends once `close()` is called.
+#[napi]
+pub struct SubscriptionStream {
+    asset: String,
+    inner: Arc<Mutex<Option<OriginalSubscriptionStream>>>,
+    /// Set by `close()`, ends a pending `next()` that holds the lock of `inner`.
+    closed: watch::Sender<bool>,
+}
+
+impl SubscriptionStream {
+    pub(crate) fn from_original(stream: OriginalSubscriptionStream) -> Self {
+        Self {
+            asset: stream.asset().to_string(),
+            inner: Arc::new(Mutex::new(Some(stream))),
+            closed: watch::Sender::new(false),
+        }
+    }
+}
+
+#[napi]
+impl SubscriptionStream {
+    /// The asset this stream is subscribed to.
+    #[napi(getter)]
+    pub fn asset(&self) -> String {
+        self.asset.clone()
+    }
+
+    /// Waits for the next candle, resolves to `null` once the stream is closed.
+    #[napi]
+    pub async fn next(&self) -> Result<Option<Candle>> {
+        let mut closed = self.closed.subscribe();
+        let mut stream = tokio::select! {
+            stream = self.inner.lock() => stream,
+            _ = closed.wait_for(|closed| *closed) => return Ok(None),
+        };
+        let Some(stream) = stream.as_mut() else {
+            return Ok(None);
+        };
+        tokio::select! {
+            candle = stream.receive() => candle.map(|candle| Some(candle.into())).map_err(to_napi),
+            _ = closed.wait_for(|closed| *closed) => Ok(None),
+        }
+    }
+
+    /// Unsubscribes from the asset and ends the stream.
+    #[napi]
+    pub async fn close(&self) -> Result<()> {
+        self.closed.send_replace(true);
+        if let Some(stream) = self.inner.lock().await.take() {
+            stream.unsubscribe().await.map_err(to_napi)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use binary_options_tools::pocketoption::candle::Candle as OriginalCandle;
+use binary_options_tools::pocketoption::types::Deal as OriginalDeal;
+use napi_derive::napi;
+use rust_decimal::prelude::ToPrimitive;
+
+/// A trade, as returned by `buy`, `sell` and `result`.
+///
+/// Money and price fields are converted to JavaScript numbers, timestamps are Unix seconds.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Deal {
+    pub id: String,
+    pub request_id: Option<String>,
+    pub asset: String,
+    pub open_timestamp: i64,
+    pub close_timestamp: i64,
+    pub amount: f64,
+    pub profit: f64,
+    pub percent_profit: i32,
+    pub percent_loss: i32,
+    pub open_price: f64,
+    pub close_price: f64,
+    pub command: i32,
+    pub is_demo: u32,
+    pub currency: String,
+}
+
+impl From<OriginalDeal> for Deal {
+    fn from(deal: OriginalDeal) -> Self {
+        Self {
+            id: deal.id.to_string(),
+            request_id: deal.request_id.map(|id| id.to_string()),
+            asset: deal.asset,
+            open_timestamp: deal.open_timestamp.timestamp(),
+            close_timestamp: deal.close_timestamp.timestamp(),
+            amount: deal.amount.to_f64().unwrap_or_default(),
+            profit: deal.profit.to_f64().unwrap_or_default(),
+            percent_profit: deal.percent_profit,
+            percent_loss: deal.percent_loss,
+            open_price: deal.open_price.to_f64().unwrap_or_default(),
+            close_price: deal.close_price.to_f64().unwrap_or_default(),
+            command: deal.command,
+            is_demo: deal.is_demo,
+            currency: deal.currency,
+        }
+    }
+}
+
+/// An OHLC candle, timestamps are Unix seconds.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Candle {
+    pub symbol: String,
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<f64>,
+}
+
+impl From<OriginalCandle> for Candle {
+    fn from(candle: OriginalCandle) -> Self {
+        Self {
+            symbol: candle.symbol,
+            timestamp: candle.timestamp,
+            open: candle.open.to_f64().unwrap_or_default(),
+            high: candle.high.to_f64().unwrap_or_default(),
+            low: candle.low.to_f64().unwrap_or_default(),
+            close: candle.close.to_f64().unwrap_or_default(),
+            volume: candle.volume.and_then(|v| v.to_f64()),
+        }
+    }
+}
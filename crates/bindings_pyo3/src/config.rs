@@ -1,6 +1,12 @@
-use binary_options_tools::config::Config;
+use binary_options_tools::config::{Config, InitProfile};
+use binary_options_tools::reconnect::LimitAction;
+use binary_options_tools::timezone::{DstPolicy, TimeZoneConfig};
+use binary_options_tools::utils::f64_to_decimal;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::time::Duration;
 use url::Url;
 
@@ -50,6 +56,59 @@ impl PyConfig {
         self.inner.reconnect_time = Duration::from_secs(value);
     }
 
+    #[getter]
+    fn reconnect_multiplier(&self) -> f64 {
+        self.inner.reconnect_multiplier
+    }
+
+    #[setter]
+    fn set_reconnect_multiplier(&mut self, value: f64) -> PyResult<()> {
+        if !value.is_finite() || value < 1.0 {
+            return Err(PyValueError::new_err("reconnect_multiplier must be >= 1.0"));
+        }
+        self.inner.reconnect_multiplier = value;
+        Ok(())
+    }
+
+    #[getter]
+    fn reconnect_max_delay(&self) -> u64 {
+        self.inner.reconnect_max_delay.as_secs()
+    }
+
+    #[setter]
+    fn set_reconnect_max_delay(&mut self, value: u64) {
+        self.inner.reconnect_max_delay = Duration::from_secs(value);
+    }
+
+    #[getter]
+    fn reconnect_jitter(&self) -> f64 {
+        self.inner.reconnect_jitter
+    }
+
+    #[setter]
+    fn set_reconnect_jitter(&mut self, value: f64) -> PyResult<()> {
+        if !(0.0..1.0).contains(&value) {
+            return Err(PyValueError::new_err(
+                "reconnect_jitter must be in the range [0.0, 1.0)",
+            ));
+        }
+        self.inner.reconnect_jitter = value;
+        Ok(())
+    }
+
+    #[getter]
+    fn loop_limit_action(&self) -> &'static str {
+        self.inner.loop_limit_action.as_str()
+    }
+
+    #[setter]
+    fn set_loop_limit_action(&mut self, value: &str) -> PyResult<()> {
+        self.inner.loop_limit_action = value
+            .parse::<LimitAction>()
+            .map_err(PyValueError::new_err)?;
+        Ok(())
+    }
+
     #[getter]
     fn connection_initialization_timeout_secs(&self) -> u64 {
         self.inner.connection_initialization_timeout.as_secs()
@@ -110,6 +169,57 @@ impl PyConfig {
         self.inner.proxy = value;
     }
 
+    #[getter]
+    fn system_proxy(&self) -> bool {
+        self.inner.system_proxy
+    }
+
+    #[setter]
+    fn set_system_proxy(&mut self, value: bool) {
+        self.inner.system_proxy = value;
+    }
+
+    #[getter]
+    fn dns_overrides(&self) -> HashMap<String, Vec<String>> {
+        self.inner
+            .dns
+            .overrides
+            .iter()
+            .map(|(host, ips)| (host.clone(), ips.iter().map(|ip| ip.to_string()).collect()))
+            .collect()
+    }
+
+    #[setter]
+    fn set_dns_overrides(&mut self, value: Option<HashMap<String, Vec<String>>>) -> PyResult<()> {
+        let mut overrides = HashMap::new();
+        for (host, ips) in value.unwrap_or_default() {
+            let ips = ips
+                .iter()
+                .map(|ip| ip.parse::<IpAddr>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    PyValueError::new_err(format!("Invalid IP address for {host}: {e}"))
+                })?;
+            overrides.insert(host.to_ascii_lowercase(), ips);
+        }
+        self.inner.dns.overrides = overrides;
+        Ok(())
+    }
+
+    #[getter]
+    fn doh_url(&self) -> Option<String> {
+        self.inner.dns.doh.as_ref().map(|url| url.to_string())
+    }
+
+    #[setter]
+    fn set_doh_url(&mut self, value: Option<String>) -> PyResult<()> {
+        self.inner.dns.doh = value
+            .map(|url| Url::parse(&url))
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Invalid doh_url: {e}")))?;
+        Ok(())
+    }
+
     #[getter]
     fn user_agent(&self) -> Option<String> {
         self.inner.user_agent.clone()
@@ -159,4 +269,253 @@ impl PyConfig {
     fn set_tls_alpn(&mut self, value: Option<Vec<String>>) {
         self.inner.tls_alpn = value;
     }
+
+    #[getter]
+    fn duplicate_trade_window_ms(&self) -> u64 {
+        self.inner.duplicate_trade_window.as_millis() as u64
+    }
+
+    #[setter]
+    fn set_duplicate_trade_window_ms(&mut self, value: u64) {
+        self.inner.duplicate_trade_window = Duration::from_millis(value);
+    }
+
+    #[getter]
+    fn max_open_per_asset(&self) -> Option<usize> {
+        self.inner.trade_guard.max_open_per_asset
+    }
+
+    #[setter]
+    fn set_max_open_per_asset(&mut self, value: Option<usize>) {
+        self.inner.trade_guard.max_open_per_asset = value;
+    }
+
+    #[getter]
+    fn trade_cooldown_ms(&self) -> u64 {
+        self.inner.trade_guard.cooldown.as_millis() as u64
+    }
+
+    #[setter]
+    fn set_trade_cooldown_ms(&mut self, value: u64) {
+        self.inner.trade_guard.cooldown = Duration::from_millis(value);
+    }
+
+    #[getter]
+    fn daily_profit_target(&self) -> Option<f64> {
+        self.inner
+            .kill_switch
+            .daily_profit_target
+            .and_then(|target| target.to_f64())
+    }
+
+    #[setter]
+    fn set_daily_profit_target(&mut self, value: Option<f64>) -> PyResult<()> {
+        self.inner.kill_switch.daily_profit_target =
+            kill_switch_limit("daily_profit_target", value)?;
+        Ok(())
+    }
+
+    #[getter]
+    fn max_drawdown(&self) -> Option<f64> {
+        self.inner
+            .kill_switch
+            .max_drawdown
+            .and_then(|max| max.to_f64())
+    }
+
+    #[setter]
+    fn set_max_drawdown(&mut self, value: Option<f64>) -> PyResult<()> {
+        self.inner.kill_switch.max_drawdown = kill_switch_limit("max_drawdown", value)?;
+        Ok(())
+    }
+
+    #[getter]
+    fn enable_pending_trades(&self) -> bool {
+        self.inner.modules.pending_trades
+    }
+
+    #[setter]
+    fn set_enable_pending_trades(&mut self, value: bool) {
+        self.inner.modules.pending_trades = value;
+    }
+
+    #[getter]
+    fn enable_historical_data(&self) -> bool {
+        self.inner.modules.historical_data
+    }
+
+    #[setter]
+    fn set_enable_historical_data(&mut self, value: bool) {
+        self.inner.modules.historical_data = value;
+    }
+
+    #[getter]
+    fn enable_raw(&self) -> bool {
+        self.inner.modules.raw
+    }
+
+    #[setter]
+    fn set_enable_raw(&mut self, value: bool) {
+        self.inner.modules.raw = value;
+    }
+
+    #[getter]
+    fn keep_alive_interval_ms(&self) -> u64 {
+        self.inner.keep_alive.interval.as_millis() as u64
+    }
+
+    #[setter]
+    fn set_keep_alive_interval_ms(&mut self, value: u64) -> PyResult<()> {
+        if value == 0 {
+            return Err(PyValueError::new_err(
+                "keep_alive_interval_ms must be positive",
+            ));
+        }
+        self.inner.keep_alive.interval = Duration::from_millis(value);
+        Ok(())
+    }
+
+    #[getter]
+    fn keep_alive_payload(&self) -> String {
+        self.inner.keep_alive.payload.clone()
+    }
+
+    #[setter]
+    fn set_keep_alive_payload(&mut self, value: String) {
+        self.inner.keep_alive.payload = value;
+    }
+
+    /// Seconds between two re-sends of the auth message, `0` disables them.
+    #[getter]
+    fn keep_alive_reauth_interval_secs(&self) -> u64 {
+        self.inner
+            .keep_alive
+            .reauth_interval
+            .map_or(0, |interval| interval.as_secs())
+    }
+
+    #[setter]
+    fn set_keep_alive_reauth_interval_secs(&mut self, value: u64) {
+        self.inner.keep_alive.reauth_interval = if value > 0 {
+            Some(Duration::from_secs(value))
+        } else {
+            None
+        };
+    }
+
+    /// Offset change, in seconds, that makes the server time re-sync.
+    #[getter]
+    fn server_time_drift_threshold_secs(&self) -> u64 {
+        self.inner.server_time_drift_threshold.as_secs()
+    }
+
+    #[setter]
+    fn set_server_time_drift_threshold_secs(&mut self, value: u64) -> PyResult<()> {
+        if value == 0 {
+            return Err(PyValueError::new_err(
+                "server_time_drift_threshold_secs must be positive",
+            ));
+        }
+        self.inner.server_time_drift_threshold = Duration::from_secs(value);
+        Ok(())
+    }
+
+    /// What is loaded on connect: "minimal", "standard" or "full".
+    #[getter]
+    fn init_profile(&self) -> &'static str {
+        match self.inner.init_profile {
+            InitProfile::Minimal => "minimal",
+            InitProfile::Standard => "standard",
+            InitProfile::Full => "full",
+        }
+    }
+
+    #[setter]
+    fn set_init_profile(&mut self, value: &str) -> PyResult<()> {
+        self.inner.init_profile = match value {
+            "minimal" => InitProfile::Minimal,
+            "standard" => InitProfile::Standard,
+            "full" => InitProfile::Full,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown init_profile '{value}', expected 'minimal', 'standard' or 'full'"
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Time zone of the times given without an offset, an IANA name, "UTC" or "local".
+    #[getter]
+    fn timezone(&self) -> &'static str {
+        self.inner.timezone.zone.name()
+    }
+
+    #[setter]
+    fn set_timezone(&mut self, value: &str) -> PyResult<()> {
+        self.inner.timezone.zone = TimeZoneConfig::zone_from_name(value)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Meaning of the times a daylight saving change skips or repeats: "earliest", "latest"
+    /// or "reject".
+    #[getter]
+    fn dst_policy(&self) -> &'static str {
+        self.inner.timezone.dst.as_str()
+    }
+
+    #[setter]
+    fn set_dst_policy(&mut self, value: &str) -> PyResult<()> {
+        self.inner.timezone.dst = value
+            .parse::<DstPolicy>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(())
+    }
+
+    #[getter]
+    fn session_path(&self) -> Option<String> {
+        self.inner
+            .session_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    #[setter]
+    fn set_session_path(&mut self, value: Option<String>) {
+        self.inner.session_path = value.map(Into::into);
+    }
+
+    #[getter]
+    fn frame_dump(&self) -> Option<String> {
+        self.inner
+            .frame_dump
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    #[setter]
+    fn set_frame_dump(&mut self, value: Option<String>) {
+        self.inner.frame_dump = value.map(Into::into);
+    }
+
+    #[getter]
+    fn audit_log(&self) -> usize {
+        self.inner.audit_log
+    }
+
+    #[setter]
+    fn set_audit_log(&mut self, value: usize) {
+        self.inner.audit_log = value;
+    }
+}
+
+/// Converts a kill switch limit passed from Python.
+fn kill_switch_limit(name: &str, value: Option<f64>) -> PyResult<Option<Decimal>> {
+    value
+        .map(|limit| {
+            f64_to_decimal(limit)
+                .ok_or_else(|| PyValueError::new_err(format!("Invalid {name}: {limit}")))
+        })
+        .transpose()
 }
@@ -1,5 +1,8 @@
-use binary_options_tools::{error::BinaryOptionsError, pocketoption::error::PocketError};
-use pyo3::{exceptions::PyValueError, PyErr};
+use binary_options_tools::{
+    error::BinaryOptionsError, pocketoption::error::PocketError, validator::ValidatorError,
+};
+use pyo3::types::PyAnyMethods;
+use pyo3::{exceptions::PyValueError, PyErr, Python};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -31,6 +34,29 @@ pyo3::create_exception!(
     PocketOptionError,
     pyo3::exceptions::PyException
 );
+// Specific PocketOption errors subclass `PocketOptionError` so existing handlers keep working.
+pyo3::create_exception!(
+    BinaryOptionsToolsV2,
+    OperationTimeoutError,
+    PocketOptionError
+);
+pyo3::create_exception!(BinaryOptionsToolsV2, NotAuthorizedError, PocketOptionError);
+pyo3::create_exception!(BinaryOptionsToolsV2, AssetInactiveError, PocketOptionError);
+pyo3::create_exception!(
+    BinaryOptionsToolsV2,
+    AmountOutOfRangeError,
+    PocketOptionError
+);
+pyo3::create_exception!(BinaryOptionsToolsV2, PayoutTooLowError, PocketOptionError);
+pyo3::create_exception!(
+    BinaryOptionsToolsV2,
+    EarlyCloseRejectedError,
+    PocketOptionError
+);
+pyo3::create_exception!(BinaryOptionsToolsV2, ServerRejectedError, PocketOptionError);
+pyo3::create_exception!(BinaryOptionsToolsV2, ConnectionLostError, PocketOptionError);
+pyo3::create_exception!(BinaryOptionsToolsV2, TradeGuardError, PocketOptionError);
+pyo3::create_exception!(BinaryOptionsToolsV2, KillSwitchError, PocketOptionError);
 pyo3::create_exception!(
     BinaryOptionsToolsV2,
     TradeNotFoundError,
@@ -52,10 +78,37 @@ pyo3::create_exception!(
     pyo3::exceptions::PyException
 );
 
+/// Maps a `PocketError` to the most specific Python exception and sets its `code` attribute.
+fn pocket_error_to_py(error: &PocketError, message: String) -> PyErr {
+    let err = match error {
+        PocketError::Timeout { .. } => OperationTimeoutError::new_err(message),
+        PocketError::NotAuthorized(_) => NotAuthorizedError::new_err(message),
+        PocketError::AssetInactive(_) => AssetInactiveError::new_err(message),
+        PocketError::AmountOutOfRange { .. } => AmountOutOfRangeError::new_err(message),
+        PocketError::PayoutTooLow { .. } => PayoutTooLowError::new_err(message),
+        PocketError::EarlyCloseRejected { .. } => EarlyCloseRejectedError::new_err(message),
+        PocketError::ServerRejected { .. } | PocketError::FailOpenOrder { .. } => {
+            ServerRejectedError::new_err(message)
+        }
+        PocketError::ConnectionLost(_) => ConnectionLostError::new_err(message),
+        PocketError::TooManyOpenDeals { .. } | PocketError::TradeCooldown { .. } => {
+            TradeGuardError::new_err(message)
+        }
+        PocketError::KillSwitchTripped { .. } => KillSwitchError::new_err(message),
+        _ => PocketOptionError::new_err(message),
+    };
+    Python::attach(|py| {
+        let _ = err.value(py).setattr("code", error.code());
+    });
+    err
+}
+
 impl From<BinaryErrorPy> for PyErr {
     fn from(value: BinaryErrorPy) -> Self {
         match value {
-            BinaryErrorPy::PocketOptionError(..) => PocketOptionError::new_err(value.to_string()),
+            BinaryErrorPy::PocketOptionError(ref error) => {
+                pocket_error_to_py(error, value.to_string())
+            }
             BinaryErrorPy::TradeNotFound(..) => TradeNotFoundError::new_err(value.to_string()),
             BinaryErrorPy::Uninitialized(..) => UninitializedError::new_err(value.to_string()),
             BinaryErrorPy::NotAllowed(..) => NotAllowedError::new_err(value.to_string()),
@@ -80,3 +133,12 @@ impl From<PocketError> for BinaryErrorPy {
         BinaryErrorPy::PocketOptionError(Box::new(value))
     }
 }
+
+impl From<ValidatorError> for BinaryErrorPy {
+    fn from(value: ValidatorError) -> Self {
+        match value {
+            ValidatorError::Regex(e) => BinaryErrorPy::InvalidRegexError(e),
+            other => BinaryErrorPy::InvalidParameter(other.to_string()),
+        }
+    }
+}
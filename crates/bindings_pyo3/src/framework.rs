@@ -2,11 +2,15 @@ use crate::error::BinaryErrorPy;
 use crate::pocketoption::RawPocketOption;
 use crate::runtime::get_runtime;
 
+use binary_options_tools::framework::backtest::Backtest;
+use binary_options_tools::framework::journal::TradeJournal;
 use binary_options_tools::framework::market::Market;
+use binary_options_tools::framework::optimizer::{Objective, Optimizer, ParamSpace, Search};
 use binary_options_tools::framework::virtual_market::VirtualMarket;
-use binary_options_tools::framework::{Bot, Context, Strategy};
+use binary_options_tools::framework::{Bot, BotHandle, BotStatus, Context, Params, Strategy};
 use binary_options_tools::pocketoption::candle::Candle;
 use binary_options_tools::pocketoption::error::{PocketError, PocketResult};
+use binary_options_tools::pocketoption::types::Deal;
 use binary_options_tools::utils::f64_to_decimal;
 
 use pyo3::prelude::*;
@@ -56,6 +60,26 @@ impl PyStrategy {
         Ok(())
     }
 
+    /// Called on every price update of the assets added with `ticks=True`.
+    pub fn on_tick(&self, _ctx: PyContext, _asset: String, _price: f64) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Called with the deal (a JSON object) once it is opened.
+    pub fn on_deal_opened(&self, _ctx: PyContext, _deal_json: String) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Called with the deal (a JSON object) once it is closed, before the next `on_candle`.
+    pub fn on_deal_closed(&self, _ctx: PyContext, _deal_json: String) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Called with all the parameters (a JSON object) when one is set with `PyBot.set_param`.
+    pub fn on_params_update(&self, _ctx: PyContext, _params_json: String) -> PyResult<()> {
+        Ok(())
+    }
+
     pub fn trade<'py>(
         &self,
         py: Python<'py>,
@@ -179,18 +203,54 @@ pub struct StrategyWrapper {
     pub inner: Py<PyStrategy>,
 }
 
+impl StrategyWrapper {
+    /// Calls the `method` hook of the Python strategy with the deal as JSON.
+    async fn call_deal_hook(
+        &self,
+        ctx: &Context,
+        method: &'static str,
+        deal: &Deal,
+    ) -> PocketResult<()> {
+        let deal_json =
+            serde_json::to_string(deal).map_err(|e| PocketError::General(e.to_string()))?;
+        let inner = Python::attach(|py| self.inner.clone_ref(py));
+        let client = ctx.client.clone();
+        let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
+        tokio::task::spawn_blocking(move || -> PocketResult<()> {
+            Python::attach(|py| {
+                let py_ctx = PyContext {
+                    client: Some(client),
+                    market,
+                    journal,
+                };
+                inner
+                    .call_method1(py, method, (py_ctx, deal_json))
+                    .map_err(|e| PocketError::General(format!("Python {} error: {}", method, e)))
+            })
+            .map(|_| ())
+        })
+        .await
+        .map_err(|e| PocketError::General(format!("Spawn blocking error: {}", e)))??;
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Strategy for StrategyWrapper {
     async fn on_start(&self, ctx: &Context) -> PocketResult<()> {
         let inner = Python::attach(|py| self.inner.clone_ref(py));
         let client = ctx.client.clone();
         let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
 
         tokio::task::spawn_blocking(move || -> PocketResult<()> {
             Python::attach(|py| {
                 let py_ctx = PyContext {
                     client: Some(client),
                     market,
+                    journal,
                 };
                 inner
                     .call_method1(py, "on_start", (py_ctx,))
@@ -210,6 +270,7 @@ impl Strategy for StrategyWrapper {
         let inner = Python::attach(|py| self.inner.clone_ref(py));
         let client = ctx.client.clone();
         let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
         let period = Python::attach(|py| {
             inner
                 .call_method0(py, "period")
@@ -243,6 +304,7 @@ impl Strategy for StrategyWrapper {
                 let py_ctx = PyContext {
                     client: Some(client),
                     market,
+                    journal,
                 };
                 inner
                     .call_method1(py, "on_candle", (py_ctx, asset, candle_json))
@@ -256,16 +318,44 @@ impl Strategy for StrategyWrapper {
         Ok(())
     }
 
+    async fn on_tick(&self, ctx: &Context, asset: &str, price: Decimal) -> PocketResult<()> {
+        let price = price.to_f64().unwrap_or(-1.0);
+        let asset = asset.to_string();
+        let inner = Python::attach(|py| self.inner.clone_ref(py));
+        let client = ctx.client.clone();
+        let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
+        tokio::task::spawn_blocking(move || -> PocketResult<()> {
+            Python::attach(|py| {
+                let py_ctx = PyContext {
+                    client: Some(client),
+                    market,
+                    journal,
+                };
+                inner
+                    .call_method1(py, "on_tick", (py_ctx, asset, price))
+                    .map_err(|e| PocketError::General(format!("Python on_tick error: {}", e)))
+            })
+            .map(|_| ())
+        })
+        .await
+        .map_err(|e| PocketError::General(format!("Spawn blocking error: {}", e)))??;
+
+        Ok(())
+    }
+
     async fn on_balance_update(&self, ctx: &Context, balance: Decimal) -> PocketResult<()> {
         let balance = balance.to_f64().unwrap_or(-1.0); // -1.0 indicates a conversion error, though it shouldnt happen often- awaiting this should be fine and if anything u can just asyncio wait like 5 seconds
         let inner = Python::attach(|py| self.inner.clone_ref(py));
         let client = ctx.client.clone();
         let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
         tokio::task::spawn_blocking(move || -> PocketResult<()> {
             Python::attach(|py| {
                 let py_ctx = PyContext {
                     client: Some(client),
                     market,
+                    journal,
                 };
                 inner
                     .call_method1(py, "on_balance", (py_ctx, balance))
@@ -278,6 +368,52 @@ impl Strategy for StrategyWrapper {
 
         Ok(())
     }
+
+    async fn on_deal_opened(&self, ctx: &Context, deal: &Deal) -> PocketResult<()> {
+        self.call_deal_hook(ctx, "on_deal_opened", deal).await
+    }
+
+    async fn on_deal_closed(&self, ctx: &Context, deal: &Deal) -> PocketResult<()> {
+        self.call_deal_hook(ctx, "on_deal_closed", deal).await
+    }
+
+    async fn on_params_update(&self, ctx: &Context, params: &Params) -> PocketResult<()> {
+        let params_json =
+            serde_json::to_string(params).map_err(|e| PocketError::General(e.to_string()))?;
+        let inner = Python::attach(|py| self.inner.clone_ref(py));
+        let client = ctx.client.clone();
+        let market = ctx.market.clone();
+        let journal = ctx.journal.clone();
+        tokio::task::spawn_blocking(move || -> PocketResult<()> {
+            Python::attach(|py| {
+                let py_ctx = PyContext {
+                    client: Some(client),
+                    market,
+                    journal,
+                };
+                inner
+                    .call_method1(py, "on_params_update", (py_ctx, params_json))
+                    .map_err(|e| {
+                        PocketError::General(format!("Python on_params_update error: {}", e))
+                    })
+            })
+            .map(|_| ())
+        })
+        .await
+        .map_err(|e| PocketError::General(format!("Spawn blocking error: {}", e)))??;
+
+        Ok(())
+    }
+}
+
+/// Converts a Python value to JSON through the `json` module.
+fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    let json: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    Ok(serde_json::from_str(&json).map_err(BinaryErrorPy::from)?)
 }
 
 #[pyclass(from_py_object)]
@@ -285,10 +421,25 @@ impl Strategy for StrategyWrapper {
 pub struct PyContext {
     pub client: Option<Arc<binary_options_tools::pocketoption::pocket_client::PocketOption>>,
     pub market: Arc<dyn Market>,
+    pub journal: Option<TradeJournal>,
 }
 
 #[pymethods]
 impl PyContext {
+    /// Tags a trade with the signal that triggered it, for the per-signal breakdown of the
+    /// bot's journal. Does nothing if the bot has no journal.
+    ///
+    /// :param trade_id: The id returned by `buy` or `PyStrategy.trade`.
+    /// :param signal: Name of the signal, e.g. "rsi_oversold".
+    pub fn tag_signal(&self, trade_id: String, signal: String) -> PyResult<()> {
+        let id = Uuid::parse_str(&trade_id)
+            .map_err(|e| BinaryErrorPy::NotAllowed(format!("Invalid UUID: {}", e)))?;
+        if let Some(journal) = &self.journal {
+            journal.tag_signal(id, signal);
+        }
+        Ok(())
+    }
+
     /// Places a buy (Call) order asynchronously.
     ///
     /// :param asset: The asset to trade (e.g. "EURUSD_otc").
@@ -369,9 +520,265 @@ impl PyVirtualMarket {
     }
 }
 
+/// Records the trades of bots with their strategy and signal, see `PyBot.with_journal`.
+#[pyclass(from_py_object)]
+#[derive(Clone, Default)]
+pub struct PyTradeJournal {
+    inner: TradeJournal,
+}
+
+#[pymethods]
+impl PyTradeJournal {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// :return: The recorded trades as a JSON array, oldest first.
+    pub fn entries(&self) -> PyResult<String> {
+        Ok(serde_json::to_string(&self.inner.entries()).map_err(BinaryErrorPy::from)?)
+    }
+
+    /// :return: The performance (trades, wins, losses, draws, net_profit, win_rate and
+    ///     average_payout) per strategy and per signal, as a JSON object.
+    pub fn report_json(&self) -> PyResult<String> {
+        Ok(self.inner.report().to_json().map_err(BinaryErrorPy::from)?)
+    }
+
+    /// :return: The same performance as `report_json`, as CSV with one row per strategy and
+    ///     per signal.
+    pub fn report_csv(&self) -> String {
+        self.inner.report().to_csv()
+    }
+}
+
+/// Parameters to search with `PyOptimizer` and their values.
+#[pyclass(from_py_object)]
+#[derive(Clone, Default)]
+pub struct PyParamSpace {
+    inner: ParamSpace,
+}
+
+#[pymethods]
+impl PyParamSpace {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tries each of `values` for the parameter `name`.
+    ///
+    /// :param values: A list of JSON serializable values, e.g. ["ema", "sma"].
+    pub fn add_values(&mut self, name: String, values: Bound<'_, PyAny>) -> PyResult<()> {
+        let serde_json::Value::Array(values) = py_to_json(&values)? else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "values must be a list",
+            ));
+        };
+        self.inner = std::mem::take(&mut self.inner).with_values(name, values);
+        Ok(())
+    }
+
+    /// Tries the parameter `name` from `start` to `end` included, by `step`. The values are
+    /// integers when `start` and `step` are.
+    pub fn add_range(&mut self, name: String, start: f64, end: f64, step: f64) -> PyResult<()> {
+        if !(step > 0.0 && start <= end) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid range from {} to {} by {}",
+                start, end, step
+            )));
+        }
+        self.inner = std::mem::take(&mut self.inner).with_range(name, start, end, step);
+        Ok(())
+    }
+
+    /// :return: The number of parameter sets of a grid search.
+    pub fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    /// :return: Every parameter set, as a JSON array of objects.
+    pub fn grid(&self) -> PyResult<String> {
+        Ok(serde_json::to_string(&self.inner.grid()).map_err(BinaryErrorPy::from)?)
+    }
+}
+
+/// Backtests a strategy over a `PyParamSpace` with walk-forward validation.
+#[pyclass]
+pub struct PyOptimizer {
+    inner: Option<Optimizer>,
+}
+
+impl PyOptimizer {
+    fn update(&mut self, f: impl FnOnce(Optimizer) -> Optimizer) -> PyResult<()> {
+        let optimizer = self.inner.take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Optimizer already consumed or run() called",
+            )
+        })?;
+        self.inner = Some(f(optimizer));
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyOptimizer {
+    /// :param client: Given to the strategies, it is not traded on.
+    /// :param strategy: Called without arguments to create the `PyStrategy` of each
+    ///     backtest, usually the strategy class.
+    /// :param candles: The candles to replay as a JSON array, e.g. from `get_candles`.
+    /// :param space: The parameters to search, given to `on_params_update`.
+    /// :param initial_balance: Balance of the virtual account of each backtest.
+    /// :param payout: Payout of every asset, in percent.
+    #[new]
+    #[pyo3(signature = (client, strategy, candles, space, initial_balance = 1000.0, payout = 80))]
+    pub fn new(
+        client: RawPocketOption,
+        strategy: Py<PyAny>,
+        candles: String,
+        space: PyParamSpace,
+        initial_balance: f64,
+        payout: i32,
+    ) -> PyResult<Self> {
+        let candles: Vec<Candle> = serde_json::from_str(&candles).map_err(BinaryErrorPy::from)?;
+        let initial_balance = f64_to_decimal(initial_balance).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid initial balance: {}",
+                initial_balance
+            ))
+        })?;
+        let backtest = Backtest::new(Arc::new(client.client.clone()), candles)
+            .with_initial_balance(initial_balance)
+            .with_payout(payout);
+        let factory = move || -> PocketResult<Box<dyn Strategy>> {
+            Python::attach(|py| {
+                let inner = strategy
+                    .call0(py)
+                    .and_then(|instance| Ok(instance.extract::<Py<PyStrategy>>(py)?))
+                    .map_err(|e| {
+                        PocketError::General(format!("Python strategy factory error: {}", e))
+                    })?;
+                Ok(Box::new(StrategyWrapper { inner }) as Box<dyn Strategy>)
+            })
+        };
+        Ok(Self {
+            inner: Some(Optimizer::new(backtest, space.inner, factory)),
+        })
+    }
+
+    /// Splits the candles into `folds` consecutive folds, the first `train_ratio` of each
+    /// being in-sample. Defaults to one fold with a ratio of 0.7.
+    pub fn with_walk_forward(&mut self, folds: usize, train_ratio: f64) -> PyResult<()> {
+        self.update(|optimizer| optimizer.with_walk_forward(folds, train_ratio))
+    }
+
+    /// Tries `samples` random parameter sets instead of all of them.
+    #[pyo3(signature = (samples, seed = 0))]
+    pub fn with_random_search(&mut self, samples: usize, seed: u64) -> PyResult<()> {
+        self.update(|optimizer| optimizer.with_search(Search::Random { samples, seed }))
+    }
+
+    /// Sets what the best parameters maximize: "net_profit" (default), "win_rate" or
+    /// "profit_factor".
+    pub fn with_objective(&mut self, objective: String) -> PyResult<()> {
+        let objective: Objective = objective.parse().map_err(BinaryErrorPy::from)?;
+        self.update(|optimizer| optimizer.with_objective(objective))
+    }
+
+    /// Maximum number of backtests running at once, the number of CPUs by default.
+    pub fn with_concurrency(&mut self, concurrency: usize) -> PyResult<()> {
+        self.update(|optimizer| optimizer.with_concurrency(concurrency))
+    }
+
+    /// Number of parameter sets in the `best` list of the report, 5 by default.
+    pub fn with_top(&mut self, top: usize) -> PyResult<()> {
+        self.update(|optimizer| optimizer.with_top(top))
+    }
+
+    /// Runs the backtests.
+    /// This is an asynchronous method.
+    ///
+    /// :return: The report as a JSON object, with the best parameters of each fold and their
+    ///     out-of-sample metrics in `folds`, and the best parameter sets overall in `best`.
+    pub fn run<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let optimizer = self.inner.take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Optimizer already running or consumed",
+            )
+        })?;
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let report = optimizer.run().await.map_err(BinaryErrorPy::from)?;
+            Ok(report.to_json().map_err(BinaryErrorPy::from)?)
+        })
+    }
+}
+
+/// Pauses, resumes or stops a running bot, e.g. from a dashboard.
+#[pyclass(from_py_object)]
+#[derive(Clone)]
+pub struct PyBotHandle {
+    inner: BotHandle,
+}
+
+#[pymethods]
+impl PyBotHandle {
+    /// Stops sending candles and balance updates to the strategy, subscriptions are kept.
+    ///
+    /// :return: False if the bot is not running.
+    pub fn pause(&self) -> bool {
+        self.inner.pause()
+    }
+
+    /// Resumes a paused bot.
+    ///
+    /// :return: False if the bot is not paused.
+    pub fn resume(&self) -> bool {
+        self.inner.resume()
+    }
+
+    /// Stops the bot: it unsubscribes from its assets and `run()` returns.
+    pub fn stop(&self) {
+        self.inner.stop()
+    }
+
+    /// :return: One of "idle", "running", "paused", "stopping" or "stopped".
+    pub fn status(&self) -> &'static str {
+        match self.inner.status() {
+            BotStatus::Idle => "idle",
+            BotStatus::Running => "running",
+            BotStatus::Paused => "paused",
+            BotStatus::Stopping => "stopping",
+            BotStatus::Stopped => "stopped",
+        }
+    }
+
+    /// Sets a strategy parameter (any JSON-serializable value), delivered to
+    /// `PyStrategy.on_params_update` without restarting the bot.
+    pub fn set_param(&self, key: String, value: Bound<'_, PyAny>) -> PyResult<()> {
+        self.inner.set_param(key, py_to_json(&value)?);
+        Ok(())
+    }
+
+    /// :return: The parameters as a JSON object.
+    pub fn params(&self) -> PyResult<String> {
+        Ok(serde_json::to_string(&self.inner.params()).map_err(BinaryErrorPy::from)?)
+    }
+
+    /// Waits until the bot has stopped.
+    /// This is an asynchronous method.
+    pub fn stopped<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let inner = self.inner.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            inner.stopped().await;
+            Ok(())
+        })
+    }
+}
+
 #[pyclass]
 pub struct PyBot {
     inner: Option<Bot>,
+    handle: BotHandle,
 }
 
 #[pymethods]
@@ -388,7 +795,34 @@ impl PyBot {
         if let Some(vm) = virtual_market {
             bot = bot.with_market(vm.borrow().inner.clone());
         }
-        Self { inner: Some(bot) }
+        Self {
+            handle: bot.handle(),
+            inner: Some(bot),
+        }
+    }
+
+    /// Sets a strategy parameter, see `PyBotHandle.set_param`.
+    pub fn set_param(&self, key: String, value: Bound<'_, PyAny>) -> PyResult<()> {
+        self.handle.set_param(key, py_to_json(&value)?);
+        Ok(())
+    }
+
+    /// Returns the handle controlling the bot, usable while `run()` is awaited.
+    pub fn handle(&self) -> PyBotHandle {
+        PyBotHandle {
+            inner: self.handle.clone(),
+        }
+    }
+
+    /// Records the trades of the strategy in `journal` under the name `strategy`.
+    pub fn with_journal(&mut self, journal: PyTradeJournal, strategy: String) -> PyResult<()> {
+        let bot = self.inner.take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Bot already consumed or run() called",
+            )
+        })?;
+        self.inner = Some(bot.with_journal(journal.inner, strategy));
+        Ok(())
     }
 
     pub fn with_update_interval(&mut self, millis: u64) -> PyResult<()> {
@@ -402,7 +836,10 @@ impl PyBot {
         }
     }
 
-    pub fn add_asset(&mut self, asset: String, period: u32) -> PyResult<()> {
+    /// Adds an asset whose candles of `period` seconds go to `on_candle`, and every price
+    /// update to `on_tick` when `ticks` is set.
+    #[pyo3(signature = (asset, period, ticks = false))]
+    pub fn add_asset(&mut self, asset: String, period: u32, ticks: bool) -> PyResult<()> {
         if let Some(bot) = &mut self.inner {
             let subscription =
                 binary_options_tools::pocketoption::candle::SubscriptionType::time_aligned(
@@ -410,6 +847,9 @@ impl PyBot {
                 )
                 .map_err(BinaryErrorPy::from)?;
 
+            if ticks {
+                bot.enable_ticks(asset.clone());
+            }
             bot.add_asset(asset, subscription);
             Ok(())
         } else {
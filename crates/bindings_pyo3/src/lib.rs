@@ -11,12 +11,22 @@ mod validator;
 
 use config::PyConfig;
 use error::{
-    InvalidParameterError, NotAllowedError, PocketOptionError, TradeNotFoundError,
-    UninitializedError,
+    AmountOutOfRangeError, AssetInactiveError, ConnectionLostError, EarlyCloseRejectedError,
+    InvalidParameterError, KillSwitchError, NotAllowedError, NotAuthorizedError,
+    OperationTimeoutError, PayoutTooLowError, PocketOptionError, ServerRejectedError,
+    TradeGuardError, TradeNotFoundError, UninitializedError,
+};
+use framework::{
+    PyBot, PyBotHandle, PyContext, PyOptimizer, PyParamSpace, PyStrategy, PyTradeJournal,
+    PyVirtualMarket,
+};
+use logs::{
+    set_log_redaction, start_tracing, LogBuilder, Logger, StreamLogsIterator, StreamLogsLayer,
+};
+use pocketoption::{
+    resample_candles, resample_ticks, CallbackHandle, CandleBuffer, RawHandle, RawHandler,
+    RawPocketOption, RawStreamIterator, StreamIterator,
 };
-use framework::{PyBot, PyContext, PyStrategy, PyVirtualMarket};
-use logs::{start_tracing, LogBuilder, Logger, StreamLogsIterator, StreamLogsLayer};
-use pocketoption::{RawHandle, RawHandler, RawPocketOption, RawStreamIterator, StreamIterator};
 use pyo3::prelude::*;
 use validator::RawValidator;
 
@@ -35,13 +45,22 @@ fn BinaryOptionsTools(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RawValidator>()?;
     m.add_class::<RawHandle>()?;
     m.add_class::<RawHandler>()?;
+    m.add_class::<CallbackHandle>()?;
+    m.add_class::<CandleBuffer>()?;
     m.add_class::<PyBot>()?;
+    m.add_class::<PyBotHandle>()?;
     m.add_class::<PyStrategy>()?;
     m.add_class::<PyContext>()?;
     m.add_class::<Action>()?;
     m.add_class::<PyVirtualMarket>()?;
+    m.add_class::<PyTradeJournal>()?;
+    m.add_class::<PyParamSpace>()?;
+    m.add_class::<PyOptimizer>()?;
 
     m.add_function(wrap_pyfunction!(start_tracing, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_redaction, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_candles, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_ticks, m)?)?;
 
     // Register custom exceptions
     m.add("PocketOptionError", m.py().get_type::<PocketOptionError>())?;
@@ -58,6 +77,37 @@ fn BinaryOptionsTools(m: &Bound<'_, PyModule>) -> PyResult<()> {
         "InvalidParameterError",
         m.py().get_type::<InvalidParameterError>(),
     )?;
+    m.add(
+        "OperationTimeoutError",
+        m.py().get_type::<OperationTimeoutError>(),
+    )?;
+    m.add(
+        "NotAuthorizedError",
+        m.py().get_type::<NotAuthorizedError>(),
+    )?;
+    m.add(
+        "AssetInactiveError",
+        m.py().get_type::<AssetInactiveError>(),
+    )?;
+    m.add(
+        "AmountOutOfRangeError",
+        m.py().get_type::<AmountOutOfRangeError>(),
+    )?;
+    m.add("PayoutTooLowError", m.py().get_type::<PayoutTooLowError>())?;
+    m.add(
+        "EarlyCloseRejectedError",
+        m.py().get_type::<EarlyCloseRejectedError>(),
+    )?;
+    m.add(
+        "ServerRejectedError",
+        m.py().get_type::<ServerRejectedError>(),
+    )?;
+    m.add(
+        "ConnectionLostError",
+        m.py().get_type::<ConnectionLostError>(),
+    )?;
+    m.add("TradeGuardError", m.py().get_type::<TradeGuardError>())?;
+    m.add("KillSwitchError", m.py().get_type::<KillSwitchError>())?;
 
     Ok(())
 }
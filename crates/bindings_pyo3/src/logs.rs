@@ -1,6 +1,8 @@
-use std::{fs::OpenOptions, io::Write, sync::Arc, time::Duration};
+use std::{collections::HashMap, fs::OpenOptions, io::Write, sync::Arc, time::Duration};
 
-use binary_options_tools::stream::{stream_logs_layer, Message, RecieverStream};
+use binary_options_tools::stream::{
+    redacted, stream_logs_layer, target_filter, Message, RecieverStream, RotatingFile, Rotation,
+};
 use futures_util::{
     stream::{BoxStream, Fuse},
     StreamExt,
@@ -13,7 +15,7 @@ use tracing_subscriber::{
     fmt::{self, MakeWriter},
     layer::SubscriberExt,
     util::SubscriberInitExt,
-    Layer, Registry,
+    EnvFilter, Layer, Registry,
 };
 
 use crate::{error::BinaryErrorPy, runtime::get_runtime, stream::next_stream};
@@ -50,20 +52,24 @@ pub fn start_tracing(
             // log-error file, to log the errors that arise
             fmt::layer()
                 .with_ansi(false)
-                .with_writer(error_logs)
+                .with_writer(redacted(error_logs))
                 .with_filter(LevelFilter::WARN),
         )
         .with(
             // log-debug file, to log the debug
             fmt::layer()
                 .with_ansi(false)
-                .with_writer(logs)
+                .with_writer(redacted(logs))
                 .with_filter(level),
         );
 
     if terminal {
         let _ = subscriber
-            .with(fmt::Layer::default().with_filter(level))
+            .with(
+                fmt::Layer::default()
+                    .with_writer(redacted(std::io::stdout))
+                    .with_filter(level),
+            )
             .try_init();
     } else {
         let _ = subscriber.try_init();
@@ -72,6 +78,13 @@ pub fn start_tracing(
     Ok(())
 }
 
+/// Masks session tokens, uids and balances in the logs (the default), turn it off only for
+/// local debugging.
+#[pyfunction]
+pub fn set_log_redaction(enabled: bool) {
+    binary_options_tools::stream::set_log_redaction(enabled);
+}
+
 #[pyclass(from_py_object)]
 #[derive(Clone)]
 pub struct StreamLogsLayer {
@@ -138,6 +151,44 @@ impl StreamLogsIterator {
     }
 }
 
+/// Builds a filter from a default level and optional per-target levels,
+/// an invalid default level falls back to `DEBUG`.
+fn log_filter(level: &str, filters: Option<HashMap<String, String>>) -> PyResult<EnvFilter> {
+    let level = level.parse().unwrap_or(LevelFilter::DEBUG).to_string();
+    let filters = filters.unwrap_or_default();
+    target_filter(
+        &level,
+        filters.iter().map(|(t, l)| (t.as_str(), l.as_str())),
+    )
+    .map_err(|e| BinaryErrorPy::InvalidParameter(e.to_string()).into())
+}
+
+fn fmt_layer<W>(
+    writer: W,
+    json: bool,
+    ansi: bool,
+    filter: EnvFilter,
+) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let writer = redacted(writer);
+    if json {
+        fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .with_filter(filter)
+            .boxed()
+    } else {
+        fmt::layer()
+            .with_ansi(ansi)
+            .with_writer(writer)
+            .with_filter(filter)
+            .boxed()
+    }
+}
+
 #[pyclass]
 #[derive(Default)]
 pub struct LogBuilder {
@@ -171,24 +222,54 @@ impl LogBuilder {
         iter
     }
 
-    #[pyo3(signature = (path = "logs.log".to_string(), level = "DEBUG".to_string()))]
-    pub fn log_file(&mut self, path: String, level: String) -> PyResult<()> {
-        let logs = OpenOptions::new().append(true).create(true).open(path)?;
-        let layer = fmt::layer()
-            .with_ansi(false)
-            .with_writer(logs)
-            .with_filter(level.parse().unwrap_or(LevelFilter::DEBUG))
-            .boxed();
+    /// Writes logs to `path`.
+    ///
+    /// `json` writes one JSON object per line, `filters` maps targets to levels
+    /// (`{"Supervisor": "warn"}`) overriding `level`. The file is rotated when it would grow
+    /// past `max_size` bytes and/or every `rotation` period (`"minutely"`, `"hourly"`,
+    /// `"daily"`), keeping `max_files` rotated files.
+    #[pyo3(signature = (path = "logs.log".to_string(), level = "DEBUG".to_string(), json = false, filters = None, rotation = None, max_size = None, max_files = 5))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_file(
+        &mut self,
+        path: String,
+        level: String,
+        json: bool,
+        filters: Option<HashMap<String, String>>,
+        rotation: Option<String>,
+        max_size: Option<u64>,
+        max_files: usize,
+    ) -> PyResult<()> {
+        let filter = log_filter(&level, filters)?;
+        let layer = if rotation.is_some() || max_size.is_some() {
+            let rotation = match rotation {
+                Some(rotation) => rotation
+                    .parse::<Rotation>()
+                    .map_err(|e| BinaryErrorPy::InvalidParameter(e.to_string()))?,
+                None => Rotation::Never,
+            };
+            let file = RotatingFile::new(path, rotation, max_size, max_files)?;
+            fmt_layer(file, json, false, filter)
+        } else {
+            let file = OpenOptions::new().append(true).create(true).open(path)?;
+            fmt_layer(file, json, false, filter)
+        };
         self.layers.push(layer);
         Ok(())
     }
 
-    #[pyo3(signature = (level = "DEBUG".to_string()))]
-    pub fn terminal(&mut self, level: String) {
-        let layer = fmt::Layer::default()
-            .with_filter(level.parse().unwrap_or(LevelFilter::DEBUG))
-            .boxed();
-        self.layers.push(layer);
+    /// Writes logs to stdout, `json` and `filters` work like in `log_file`.
+    #[pyo3(signature = (level = "DEBUG".to_string(), json = false, filters = None))]
+    pub fn terminal(
+        &mut self,
+        level: String,
+        json: bool,
+        filters: Option<HashMap<String, String>>,
+    ) -> PyResult<()> {
+        let filter = log_filter(&level, filters)?;
+        self.layers
+            .push(fmt_layer(std::io::stdout, json, !json, filter));
+        Ok(())
     }
 
     pub fn build(&mut self) -> PyResult<()> {
@@ -3,25 +3,50 @@ use std::str;
 use std::sync::Arc;
 use std::time::Duration;
 
-use binary_options_tools::pocketoption::candle::{Candle, SubscriptionType};
-use binary_options_tools::pocketoption::error::PocketResult;
+use async_trait::async_trait;
+use binary_options_tools::analytics;
+use binary_options_tools::config::Config;
+use binary_options_tools::middleware::{CoreResult, MiddlewareContext, WebSocketMiddleware};
+use binary_options_tools::pocketoption::alerts::{AlertMode, Condition as AlertCondition};
+use binary_options_tools::pocketoption::candle::{
+    Candle, CandleColumns, CandleWindow, SubscriptionType,
+};
+use binary_options_tools::pocketoption::error::{PocketError, PocketResult};
+use binary_options_tools::pocketoption::modules::deals::ResultOptions;
+use binary_options_tools::pocketoption::modules::raw::RawHandlerOptions;
+use binary_options_tools::pocketoption::modules::subscriptions::GapFill;
 use binary_options_tools::pocketoption::pocket_client::PocketOption;
+use binary_options_tools::pocketoption::resample::{self, resample_with, PartialBucket};
+use binary_options_tools::pocketoption::scanner::ScannerConfig;
+use binary_options_tools::pocketoption::state::State;
+use binary_options_tools::pocketoption::types::{Action, Condition, TradeOptions};
+use binary_options_tools::reconnect::ReconnectEvent;
+use binary_options_tools::status::ConnectionStatus;
+use binary_options_tools::stream::redact_logs;
+use binary_options_tools::timezone::TimeZoneConfig;
 use binary_options_tools::utils::f64_to_decimal;
+use binary_options_tools::validator::JsonPath;
 use binary_options_tools::validator::Validator as CrateValidator;
 use binary_options_tools::validator::Validator;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
 use futures_util::stream::{BoxStream, Fuse};
 use futures_util::StreamExt;
-use pyo3::{pyclass, pymethods, Bound, IntoPyObjectExt, Py, PyAny, PyResult, Python};
+use pyo3::types::{PyAnyMethods, PyBool, PyDict, PyDictMethods, PyFloat};
+use pyo3::{pyclass, pyfunction, pymethods, Bound, IntoPyObjectExt, Py, PyAny, PyResult, Python};
 use pyo3_async_runtimes::tokio::future_into_py;
 use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 use crate::config::PyConfig;
 use crate::error::BinaryErrorPy;
 use crate::runtime::get_runtime;
-use crate::stream::next_stream;
+use crate::stream::{collect_stream, next_stream, next_stream_timeout};
 use crate::validator::RawValidator;
-use tokio::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::AbortHandle;
+use tracing::warn;
 
 const CONNECTION_TIMEOUT_SECS: u64 = 20;
 
@@ -39,6 +64,52 @@ fn arc_message_to_string(msg: &std::sync::Arc<tungstenite::Message>) -> String {
     message_to_string(msg.as_ref())
 }
 
+/// Queues the text of every frame for a Python callback, see `RawPocketOption::add_middleware`.
+///
+/// Frames are dropped while the queue is full so a slow callback never delays the connection.
+struct PyMiddleware {
+    queue: mpsc::Sender<(String, &'static str)>,
+}
+
+impl PyMiddleware {
+    fn forward(&self, message: &tungstenite::Message, direction: &'static str) {
+        if !matches!(
+            message,
+            tungstenite::Message::Text(_) | tungstenite::Message::Binary(_)
+        ) {
+            return;
+        }
+        if self
+            .queue
+            .try_send((message_to_string(message), direction))
+            .is_err()
+        {
+            warn!(target: "PyMiddleware", "Callback queue is full, dropping a {} frame", direction);
+        }
+    }
+}
+
+#[async_trait]
+impl WebSocketMiddleware<State> for PyMiddleware {
+    async fn on_send(
+        &self,
+        message: &tungstenite::Message,
+        _context: &MiddlewareContext<State>,
+    ) -> CoreResult<()> {
+        self.forward(message, "send");
+        Ok(())
+    }
+
+    async fn on_receive(
+        &self,
+        message: &tungstenite::Message,
+        _context: &MiddlewareContext<State>,
+    ) -> CoreResult<()> {
+        self.forward(message, "receive");
+        Ok(())
+    }
+}
+
 /// Send a raw message and wait for the response
 async fn send_raw_message_and_wait(
     client: &PocketOption,
@@ -64,6 +135,166 @@ async fn send_raw_message_and_wait(
     Ok(arc_message_to_string(&response))
 }
 
+/// Connects with `config`, resuming the session at `config.session_path` when it is set
+async fn connect_with_config(ssid: String, config: Config) -> PocketResult<PocketOption> {
+    match config.session_path.clone() {
+        Some(path) => PocketOption::resume_with_config(path, ssid, config).await,
+        None => PocketOption::new_with_config(ssid, config).await,
+    }
+}
+
+/// Parse a condition name (and optional asset) passed from Python
+fn parse_condition(condition: &str, asset: Option<String>) -> Result<Condition, BinaryErrorPy> {
+    let require_asset = || {
+        asset.clone().ok_or_else(|| {
+            BinaryErrorPy::InvalidParameter(format!("Condition '{condition}' requires an asset"))
+        })
+    };
+    match condition {
+        "assets_loaded" => Ok(Condition::AssetsLoaded),
+        "balance_known" => Ok(Condition::BalanceKnown),
+        "server_time_synced" => Ok(Condition::ServerTimeSynced),
+        "subscription_active" => Ok(Condition::SubscriptionActive(require_asset()?)),
+        "first_tick" => Ok(Condition::FirstTick(require_asset()?)),
+        other => Err(BinaryErrorPy::InvalidParameter(format!(
+            "Unknown condition: {other}"
+        ))),
+    }
+}
+
+/// Converts an amount or price passed from Python to a `Decimal`.
+///
+/// `decimal.Decimal`, `str` and `int` values are parsed from their text, so `"2.33"` stays
+/// exactly `2.33`; floats go through their shortest representation.
+fn decimal_arg(value: &Bound<'_, PyAny>, name: &str) -> Result<Decimal, BinaryErrorPy> {
+    let invalid = || BinaryErrorPy::NotAllowed(format!("Invalid {name}: {value}"));
+    if value.is_instance_of::<PyBool>() {
+        return Err(invalid());
+    }
+    if value.is_instance_of::<PyFloat>() {
+        let float = value.extract::<f64>().map_err(|_| invalid())?;
+        return f64_to_decimal(float).ok_or_else(invalid);
+    }
+    let text = value.str().map_err(|_| invalid())?.to_string();
+    let text = text.trim();
+    Decimal::from_str_exact(text)
+        .or_else(|_| Decimal::from_scientific(text))
+        .map_err(|_| invalid())
+}
+
+/// Converts a time passed from Python to a Unix timestamp in seconds.
+///
+/// Numbers are Unix timestamps and aware datetimes are taken as they are, naive datetimes and
+/// strings without an offset are read in the configured time zone.
+fn timestamp_arg(
+    value: &Bound<'_, PyAny>,
+    timezone: &TimeZoneConfig,
+) -> Result<i64, BinaryErrorPy> {
+    let invalid = || BinaryErrorPy::InvalidParameter(format!("Invalid time: {value}"));
+    if value.is_instance_of::<PyBool>() {
+        return Err(invalid());
+    }
+    if let Ok(timestamp) = value.extract::<i64>() {
+        return Ok(timestamp);
+    }
+    if value.is_instance_of::<PyFloat>() {
+        return Ok(value.extract::<f64>().map_err(|_| invalid())?.floor() as i64);
+    }
+    if let Ok(text) = value.extract::<String>() {
+        return Ok(timezone.parse(&text)?.timestamp());
+    }
+    if let Ok(local) = value.extract::<NaiveDateTime>() {
+        return Ok(timezone.to_utc(local)?.timestamp());
+    }
+    // Aware datetimes of any `tzinfo`, `zoneinfo` included.
+    let timestamp = value
+        .call_method0("timestamp")
+        .and_then(|timestamp| timestamp.extract::<f64>())
+        .map_err(|_| invalid())?;
+    Ok(timestamp.floor() as i64)
+}
+
+/// Parse an alert condition name and its price level passed from Python
+fn parse_alert_condition(condition: &str, price: f64) -> Result<AlertCondition, BinaryErrorPy> {
+    let level = f64_to_decimal(price)
+        .ok_or_else(|| BinaryErrorPy::InvalidParameter(format!("Invalid price: {price}")))?;
+    match condition {
+        "crosses_above" => Ok(AlertCondition::CrossesAbove(level)),
+        "crosses_below" => Ok(AlertCondition::CrossesBelow(level)),
+        "above" => Ok(AlertCondition::Above(level)),
+        "below" => Ok(AlertCondition::Below(level)),
+        other => Err(BinaryErrorPy::InvalidParameter(format!(
+            "Unknown alert condition: {other}"
+        ))),
+    }
+}
+
+/// Convert a reconnect event into the JSON representation exposed to Python
+/// Builds the per-order options shared by `buy` and `sell`.
+fn trade_options(
+    allow_duplicate: bool,
+    min_payout: Option<i32>,
+    payout_wait_secs: f64,
+) -> PyResult<TradeOptions> {
+    if !payout_wait_secs.is_finite() || payout_wait_secs < 0.0 {
+        return Err(BinaryErrorPy::InvalidParameter(
+            "payout_wait_secs must be a non-negative number".into(),
+        )
+        .into());
+    }
+    let options = TradeOptions::default()
+        .allow_duplicate(allow_duplicate)
+        .payout_wait(Duration::from_secs_f64(payout_wait_secs));
+    Ok(match min_payout {
+        Some(payout) => options.min_payout(payout),
+        None => options,
+    })
+}
+
+fn reconnect_event_to_json(event: &ReconnectEvent) -> String {
+    let value = match event {
+        ReconnectEvent::Scheduled {
+            attempt,
+            max_attempts,
+            delay,
+            error,
+        } => serde_json::json!({
+            "type": "scheduled",
+            "attempt": attempt,
+            "max_attempts": max_attempts,
+            "delay": delay.as_secs_f64(),
+            "error": error,
+        }),
+        ReconnectEvent::Connected { attempts } => {
+            serde_json::json!({ "type": "connected", "attempts": attempts })
+        }
+        ReconnectEvent::LimitReached(diagnostics) => serde_json::json!({
+            "type": "limit_reached",
+            "attempts": diagnostics.attempts,
+            "cycles_last_minute": diagnostics.cycles_last_minute,
+            "last_error": diagnostics.last_error,
+            "modules": diagnostics.modules,
+            "queued_outgoing": diagnostics.queued_outgoing,
+            "action": diagnostics.action.as_str(),
+        }),
+        ReconnectEvent::Exhausted { attempts } => {
+            serde_json::json!({ "type": "exhausted", "attempts": attempts })
+        }
+    };
+    value.to_string()
+}
+
+fn candle_columns_into_py(py: Python<'_>, columns: CandleColumns) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("timestamp", columns.timestamp)?;
+    dict.set_item("open", columns.open)?;
+    dict.set_item("high", columns.high)?;
+    dict.set_item("low", columns.low)?;
+    dict.set_item("close", columns.close)?;
+    dict.set_item("volume", columns.volume)?;
+    dict.into_py_any(py)
+}
+
 #[pyclass(from_py_object)]
 #[derive(Clone)]
 pub struct RawPocketOption {
@@ -90,6 +321,218 @@ pub struct RawHandler {
     handler: Arc<Mutex<binary_options_tools::pocketoption::modules::raw::RawHandler>>,
 }
 
+/// Registration of an `on_reconnect` or `on_disconnect` callback.
+#[pyclass]
+pub struct CallbackHandle {
+    task: AbortHandle,
+}
+
+#[pymethods]
+impl CallbackHandle {
+    /// Stops calling the callback.
+    pub fn cancel(&self) {
+        self.task.abort();
+    }
+
+    /// :return: False once cancelled or after the client was dropped.
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+/// The subscription type named `kind`, see `RawPocketOption::subscribe_many`.
+fn subscription_type(kind: &str, value: Option<f64>) -> PyResult<SubscriptionType> {
+    let seconds = || match value {
+        Some(secs) if secs > 0.0 => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(BinaryErrorPy::NotAllowed(format!(
+            "subscription '{kind}' needs a period in seconds"
+        ))),
+    };
+    let sub_type = match kind {
+        "none" => SubscriptionType::none(),
+        "chunk" => match value {
+            Some(size) if size >= 1.0 => SubscriptionType::chunk(size as usize),
+            _ => {
+                return Err(BinaryErrorPy::NotAllowed(
+                    "subscription 'chunk' needs a size of at least 1".into(),
+                )
+                .into())
+            }
+        },
+        "time" => SubscriptionType::time(seconds()?),
+        "time_aligned" => {
+            SubscriptionType::time_aligned(seconds()?).map_err(BinaryErrorPy::from)?
+        }
+        _ => {
+            return Err(BinaryErrorPy::NotAllowed(format!(
+                "unknown subscription type '{kind}', expected none, chunk, time or time_aligned"
+            ))
+            .into())
+        }
+    };
+    Ok(sub_type)
+}
+
+/// The gap filling named `mode`, `None` leaves the gaps.
+fn gap_fill_mode(mode: Option<&str>) -> PyResult<GapFill> {
+    match mode {
+        None | Some("none") => Ok(GapFill::None),
+        Some("flat") => Ok(GapFill::Flat),
+        Some("backfill") => Ok(GapFill::Backfill),
+        Some(other) => Err(BinaryErrorPy::NotAllowed(format!(
+            "unknown gap fill '{other}', expected none, flat or backfill"
+        ))
+        .into()),
+    }
+}
+
+type CandleWindows = Arc<std::sync::Mutex<HashMap<String, CandleWindow>>>;
+
+/// Rolling window of the last candles of every asset, kept up to date by its subscriptions.
+#[pyclass]
+pub struct CandleBuffer {
+    windows: CandleWindows,
+    tasks: Vec<AbortHandle>,
+}
+
+impl CandleBuffer {
+    fn windows(&self) -> std::sync::MutexGuard<'_, HashMap<String, CandleWindow>> {
+        self.windows.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for CandleBuffer {
+    fn drop(&mut self) {
+        self.tasks.iter().for_each(AbortHandle::abort);
+    }
+}
+
+fn candle_record<'py>(py: Python<'py>, candle: &Candle) -> PyResult<Bound<'py, PyDict>> {
+    let record = PyDict::new(py);
+    record.set_item("symbol", &candle.symbol)?;
+    record.set_item("timestamp", candle.timestamp)?;
+    record.set_item("open", candle.open.to_f64())?;
+    record.set_item("high", candle.high.to_f64())?;
+    record.set_item("low", candle.low.to_f64())?;
+    record.set_item("close", candle.close.to_f64())?;
+    record.set_item("volume", candle.volume.and_then(|volume| volume.to_f64()))?;
+    record.set_item("is_closed", candle.is_closed)?;
+    Ok(record)
+}
+
+#[pymethods]
+impl CandleBuffer {
+    /// :return: The buffered assets, sorted.
+    pub fn assets(&self) -> Vec<String> {
+        let mut assets: Vec<String> = self.windows().keys().cloned().collect();
+        assets.sort();
+        assets
+    }
+
+    /// :return: Number of candles buffered for `asset`.
+    pub fn len(&self, asset: &str) -> usize {
+        self.windows().get(asset).map_or(0, CandleWindow::len)
+    }
+
+    /// :return: The newest candle of `asset` as a JSON string, None before the first one.
+    pub fn latest(&self, asset: &str) -> Option<String> {
+        let windows = self.windows();
+        let candle = windows.get(asset)?.latest()?;
+        Some(serde_json::to_string(candle).unwrap_or_default())
+    }
+
+    /// :return: One dict per candle, oldest first and grouped by asset, ready for
+    ///     `pandas.DataFrame.from_records`. Only the candles of `asset` when given.
+    #[pyo3(signature = (asset=None))]
+    pub fn to_records<'py>(
+        &self,
+        py: Python<'py>,
+        asset: Option<String>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let windows = self.windows();
+        let mut assets: Vec<&String> = match &asset {
+            Some(asset) => windows.keys().filter(|key| *key == asset).collect(),
+            None => windows.keys().collect(),
+        };
+        assets.sort();
+        assets
+            .into_iter()
+            .flat_map(|asset| windows[asset].iter())
+            .map(|candle| candle_record(py, candle))
+            .collect()
+    }
+
+    /// :return: The candles of `asset` as one list per column, like `get_candles_columns`.
+    pub fn to_columns(&self, py: Python<'_>, asset: &str) -> PyResult<Py<PyAny>> {
+        let columns = self
+            .windows()
+            .get(asset)
+            .map(CandleWindow::columns)
+            .unwrap_or_default();
+        candle_columns_into_py(py, columns)
+    }
+
+    /// Stops updating the buffer, the buffered candles stay readable.
+    pub fn close(&self) {
+        self.tasks.iter().for_each(AbortHandle::abort);
+    }
+
+    /// :return: False once closed or when every subscription ended.
+    pub fn is_active(&self) -> bool {
+        self.tasks.iter().any(|task| !task.is_finished())
+    }
+}
+
+/// Runs `callback(arg)` on `event_loop` from any thread, coroutine functions are scheduled
+/// as tasks of the loop.
+fn schedule_callback(
+    py: Python<'_>,
+    event_loop: &Py<PyAny>,
+    callback: &Py<PyAny>,
+    arg: String,
+) -> PyResult<()> {
+    let is_coroutine = py
+        .import("inspect")?
+        .call_method1("iscoroutinefunction", (callback,))?
+        .is_truthy()?;
+    if is_coroutine {
+        let coroutine = callback.call1(py, (arg,))?;
+        py.import("asyncio")?
+            .call_method1("run_coroutine_threadsafe", (coroutine, event_loop))?;
+    } else {
+        event_loop.call_method1(py, "call_soon_threadsafe", (callback, arg))?;
+    }
+    Ok(())
+}
+
+/// Spawns the task calling `callback` on the running event loop with every payload of
+/// `payloads`, returning its handle.
+fn spawn_callback<'py>(
+    py: Python<'py>,
+    callback: Py<PyAny>,
+    payloads: impl futures_util::Stream<Item = String> + Send + 'static,
+) -> PyResult<Bound<'py, PyAny>> {
+    let event_loop = pyo3_async_runtimes::get_running_loop(py)?.unbind();
+    future_into_py(py, async move {
+        let task = tokio::spawn(async move {
+            let mut payloads = std::pin::pin!(payloads);
+            while let Some(payload) = payloads.next().await {
+                let scheduled =
+                    Python::attach(|py| schedule_callback(py, &event_loop, &callback, payload));
+                if let Err(e) = scheduled {
+                    warn!(target: "PyCallback", "Failed to schedule the callback: {}", e);
+                }
+            }
+        });
+        Python::attach(|py| {
+            CallbackHandle {
+                task: task.abort_handle(),
+            }
+            .into_py_any(py)
+        })
+    })
+}
+
 #[pymethods]
 impl RawPocketOption {
     #[new]
@@ -161,7 +604,7 @@ impl RawPocketOption {
     pub fn new_with_config(py: Python<'_>, ssid: String, config: PyConfig) -> PyResult<Self> {
         let runtime = get_runtime(py)?;
         runtime.block_on(async move {
-            PocketOption::new_with_config(ssid, config.inner)
+            connect_with_config(ssid, config.inner)
                 .await
                 .map(|client| Self { client })
                 .map_err(|e| BinaryErrorPy::from(e).into())
@@ -175,13 +618,37 @@ impl RawPocketOption {
         py: Python<'py>,
     ) -> PyResult<Bound<'py, PyAny>> {
         future_into_py(py, async move {
-            PocketOption::new_with_config(ssid, config.inner)
+            connect_with_config(ssid, config.inner)
                 .await
                 .map(|client| RawPocketOption { client })
                 .map_err(|e| BinaryErrorPy::from(e).into())
         })
     }
 
+    /// Waits until a state condition holds.
+    ///
+    /// `condition` is one of `"assets_loaded"`, `"balance_known"`, `"server_time_synced"`,
+    /// `"subscription_active"` or `"first_tick"`; the last two require `asset`.
+    #[pyo3(signature = (condition, timeout_secs, asset=None))]
+    pub fn wait_for<'py>(
+        &self,
+        py: Python<'py>,
+        condition: String,
+        timeout_secs: f64,
+        asset: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let condition = parse_condition(&condition, asset)?;
+        let client = self.client.clone();
+        let duration = Duration::from_secs_f64(timeout_secs.max(0.0));
+        future_into_py(py, async move {
+            client
+                .wait_for(condition, duration)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
     pub fn wait_for_assets<'py>(
         &self,
         py: Python<'py>,
@@ -202,25 +669,122 @@ impl RawPocketOption {
         self.client.is_demo()
     }
 
+    /// Saves the assets, server time offset, subscribed assets and opened deals to `path`,
+    /// a client configured with this `session_path` starts from them.
+    pub fn save_session<'py>(&self, py: Python<'py>, path: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client
+                .save_session(path)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
+    /// Returns the connection's traffic, reconnect and error counters as a JSON string.
+    pub fn stats(&self) -> PyResult<String> {
+        Ok(serde_json::to_string(&self.client.stats()).map_err(BinaryErrorPy::from)?)
+    }
+
+    /// Sets the counters returned by `stats` back to zero.
+    pub fn reset_stats(&self) {
+        self.client.reset_stats()
+    }
+
+    /// Returns the messages sent after the one numbered `since` as a JSON list of
+    /// `{"seq", "time", "source", "message"}` objects, sessions masked like in the logs.
+    #[pyo3(signature = (since = 0))]
+    pub fn audit_log(&self, since: u64) -> PyResult<String> {
+        let records = self
+            .client
+            .audit_log(since)
+            .into_iter()
+            .map(|record| {
+                let time = record
+                    .at
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                let message = match record.message.to_text() {
+                    Ok(text) => redact_logs(text).into_owned(),
+                    Err(_) => format!("{:?}", record.message),
+                };
+                serde_json::json!({
+                    "seq": record.seq,
+                    "time": time,
+                    "source": &*record.source,
+                    "message": message,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_string(&records).map_err(BinaryErrorPy::from)?)
+    }
+
     /// Returns true if the client is currently connected to the WebSocket server.
     pub fn is_connected(&self) -> bool {
         self.client.is_connected()
     }
 
+    /// Returns the connection status, one of `"connecting"`, `"connected"`, `"authenticated"`,
+    /// `"degraded"`, `"reconnecting"` or `"disconnected"`.
+    pub fn status(&self) -> String {
+        self.client.status().to_string()
+    }
+
+    /// Waits until the connection reaches `status` (e.g. `"authenticated"`).
+    pub fn wait_for_status<'py>(
+        &self,
+        py: Python<'py>,
+        status: String,
+        timeout_secs: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let status: ConnectionStatus = status.parse().map_err(BinaryErrorPy::InvalidParameter)?;
+        if !timeout_secs.is_finite() || timeout_secs < 0.0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "timeout_secs must be a non-negative number".into(),
+            )
+            .into());
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client
+                .wait_for_status(status, Duration::from_secs_f64(timeout_secs))
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
+    /// Returns an async iterator yielding the current status and every status change.
+    pub fn status_stream<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let statuses = self.client.status_stream();
+        future_into_py(py, async move {
+            let boxed_stream = statuses.map(|status| Ok(status.to_string())).boxed().fuse();
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
 
+    #[pyo3(signature = (asset, amount, time, allow_duplicate=false, min_payout=None, payout_wait_secs=0.0))]
+    #[allow(clippy::too_many_arguments)]
     pub fn buy<'py>(
         &self,
         py: Python<'py>,
         asset: String,
-        amount: f64,
+        amount: &Bound<'py, PyAny>,
         time: u32,
+        allow_duplicate: bool,
+        min_payout: Option<i32>,
+        payout_wait_secs: f64,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
-        let decimal_amount = f64_to_decimal(amount)
-            .ok_or_else(|| BinaryErrorPy::NotAllowed(format!("Invalid amount: {}", amount)))?;
+        let decimal_amount = decimal_arg(amount, "amount")?;
+        let options = trade_options(allow_duplicate, min_payout, payout_wait_secs)?;
         future_into_py(py, async move {
             let res = client
-                .buy(asset, time, decimal_amount)
+                .trade_with_options(asset, Action::Call, time, decimal_amount, options)
                 .await
                 .map_err(BinaryErrorPy::from)?;
             let deal = serde_json::to_string(&res.1).map_err(BinaryErrorPy::from)?;
@@ -229,19 +793,24 @@ impl RawPocketOption {
         })
     }
 
+    #[pyo3(signature = (asset, amount, time, allow_duplicate=false, min_payout=None, payout_wait_secs=0.0))]
+    #[allow(clippy::too_many_arguments)]
     pub fn sell<'py>(
         &self,
         py: Python<'py>,
         asset: String,
-        amount: f64,
+        amount: &Bound<'py, PyAny>,
         time: u32,
+        allow_duplicate: bool,
+        min_payout: Option<i32>,
+        payout_wait_secs: f64,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
-        let decimal_amount = f64_to_decimal(amount)
-            .ok_or_else(|| BinaryErrorPy::NotAllowed(format!("Invalid amount: {}", amount)))?;
+        let decimal_amount = decimal_arg(amount, "amount")?;
+        let options = trade_options(allow_duplicate, min_payout, payout_wait_secs)?;
         future_into_py(py, async move {
             let res = client
-                .sell(asset, time, decimal_amount)
+                .trade_with_options(asset, Action::Put, time, decimal_amount, options)
                 .await
                 .map_err(BinaryErrorPy::from)?;
             let deal = serde_json::to_string(&res.1).map_err(BinaryErrorPy::from)?;
@@ -265,6 +834,40 @@ impl RawPocketOption {
         })
     }
 
+    /// Returns the status of a deal as JSON `{"status": "open" | "closing" | "closed", "deal": {...}}`.
+    #[pyo3(signature = (trade_id, wait = false, history_fallback_secs = None))]
+    pub fn deal_status<'py>(
+        &self,
+        py: Python<'py>,
+        trade_id: String,
+        wait: bool,
+        history_fallback_secs: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        let mut options = ResultOptions {
+            wait,
+            ..ResultOptions::default()
+        };
+        if let Some(secs) = history_fallback_secs {
+            let grace = Duration::try_from_secs_f64(secs).map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!("history_fallback_secs: {e}"))
+            })?;
+            options = options.with_history_fallback(grace);
+        }
+        future_into_py(py, async move {
+            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
+            let status = client
+                .result_with_options(uuid, &options)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&status)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
     pub fn get_deal_end_time<'py>(
         &self,
         py: Python<'py>,
@@ -283,6 +886,67 @@ impl RawPocketOption {
         })
     }
 
+    /// Closes an opened deal before its expiry, returning the confirmation as JSON.
+    pub fn close_deal_early<'py>(
+        &self,
+        py: Python<'py>,
+        trade_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
+            let closed = client
+                .close_deal_early(uuid)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&closed)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Returns the seconds left before a deal expires, computed with server time.
+    pub fn time_to_expiry<'py>(
+        &self,
+        py: Python<'py>,
+        trade_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
+            let left = client
+                .time_to_expiry(uuid)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Ok(left.as_secs_f64())
+        })
+    }
+
+    /// Returns an async iterator yielding the seconds left before a deal expires, once per
+    /// second, until it reaches zero.
+    pub fn deal_countdown_stream<'py>(
+        &self,
+        py: Python<'py>,
+        trade_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
+            let countdown = client
+                .deal_countdown_stream(uuid)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            let boxed_stream = countdown
+                .map(|left| Ok(left.as_secs_f64().to_string()))
+                .boxed()
+                .fuse();
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
     /// Gets historical candle data for a specific asset and period.
     pub fn candles<'py>(
         &self,
@@ -325,6 +989,28 @@ impl RawPocketOption {
         })
     }
 
+    /// Same as `get_candles`, with the candles as one list per column.
+    ///
+    /// Returns:
+    ///     dict: `timestamp`, `open`, `high`, `low`, `close` and `volume` lists
+    pub fn get_candles_columns<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+        period: i64,
+        offset: i64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let res = client
+                .get_candles(asset, period, offset)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            let columns = CandleColumns::from(res.as_slice());
+            Python::attach(|py| candle_columns_into_py(py, columns))
+        })
+    }
+
     pub fn get_candles_advanced<'py>(
         &self,
         py: Python<'py>,
@@ -355,25 +1041,38 @@ impl RawPocketOption {
         })
     }
 
+    /// Returns the last balance reported for each account type as a JSON object keyed by
+    /// "demo", "real" or "tournament".
+    pub fn balances<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let balances = client.balances().await;
+            Ok(serde_json::to_string(&balances).map_err(BinaryErrorPy::from)?)
+        })
+    }
+
+    /// Returns the account currency code (e.g. "USD"), or None if not yet reported.
+    pub fn currency<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move { Ok(client.currency().await) })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn open_pending_order<'py>(
         &self,
         py: Python<'py>,
         open_type: u32,
-        amount: f64,
+        amount: &Bound<'py, PyAny>,
         asset: String,
         open_time: String,
-        open_price: f64,
+        open_price: &Bound<'py, PyAny>,
         timeframe: u32,
         min_payout: u32,
         command: u32,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
-        let decimal_amount = f64_to_decimal(amount)
-            .ok_or_else(|| BinaryErrorPy::NotAllowed(format!("Invalid amount: {}", amount)))?;
-        let decimal_open_price = f64_to_decimal(open_price).ok_or_else(|| {
-            BinaryErrorPy::NotAllowed(format!("Invalid open price: {}", open_price))
-        })?;
+        let decimal_amount = decimal_arg(amount, "amount")?;
+        let decimal_open_price = decimal_arg(open_price, "open price")?;
         future_into_py(py, async move {
             let res = client
                 .open_pending_order(
@@ -450,41 +1149,234 @@ impl RawPocketOption {
         })
     }
 
-    pub fn get_closed_deal<'py>(
-        &self,
-        py: Python<'py>,
-        trade_id: String,
-    ) -> PyResult<Bound<'py, PyAny>> {
+    /// Returns win/loss statistics over the deals closed since the client started.
+    ///
+    /// Returns:
+    ///     str: JSON object with win rate, profit factor, streaks and per-asset breakdown
+    pub fn trade_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
-            if let Some(deal) = client.get_closed_deal(uuid).await {
-                let res = serde_json::to_string(&deal).map_err(BinaryErrorPy::from)?;
-                Ok(Some(res))
-            } else {
-                Ok(None)
-            }
+            let stats = client.trade_stats().await;
+            Python::attach(|py| {
+                serde_json::to_string(&stats)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
         })
     }
 
-    pub fn clear_closed_deals<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    pub fn reset_trade_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            client.clear_closed_deals().await;
-            Python::attach(|py| py.None().into_py_any(py))
+            client.reset_trade_stats().await;
+            Ok(())
         })
     }
 
-    pub fn opened_deals<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    /// Returns the net profit of the current server day and whether the kill switch stopped
+    /// the trading, as a JSON object.
+    pub fn kill_switch_status<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            let deals = client.get_opened_deals().await;
-            let res = serde_json::to_string(&deals).map_err(BinaryErrorPy::from)?;
-            Ok(res)
+            let status = client.kill_switch_status().await;
+            Python::attach(|py| {
+                serde_json::to_string(&status)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
         })
     }
 
-    pub fn get_opened_deal<'py>(
+    /// Returns an async iterator over the kill switch trips as JSON strings.
+    pub fn kill_switch_events<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut events = self.client.kill_switch_events();
+        future_into_py(py, async move {
+            let boxed_stream = async_stream::stream! {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => yield serde_json::to_string(&event)
+                            .map_err(|e| PocketError::General(e.to_string())),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
+    /// Returns the stage timings of the most recently resolved order.
+    ///
+    /// Returns:
+    ///     str | None: JSON object with queued, server, confirm and total latencies in milliseconds
+    pub fn last_trade_timings<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            match client.last_trade_timings().await {
+                Some(timings) => Ok(Some(
+                    serde_json::to_string(&timings).map_err(BinaryErrorPy::from)?,
+                )),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// Returns latency histograms for each stage of the orders placed since the client started.
+    ///
+    /// Returns:
+    ///     str: JSON object with one histogram per stage and outcome counters
+    pub fn trade_latency<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let latency = client.trade_latency().await;
+            Python::attach(|py| {
+                serde_json::to_string(&latency)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Returns the slippage of the deals opened since the client started against the quote of
+    /// their asset when the order was sent.
+    ///
+    /// Returns:
+    ///     str: JSON object with `deals`, `requotes`, `adverse`, `mean`, `worst` and `per_asset`
+    pub fn slippage_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let slippage = client.slippage_stats().await;
+            Python::attach(|py| {
+                serde_json::to_string(&slippage)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Fetches the account's closed deals between two unix timestamps from the server.
+    ///
+    /// Args:
+    ///     start (int): Range start as a unix timestamp (seconds)
+    ///     end (int): Range end as a unix timestamp (seconds)
+    ///
+    /// Returns:
+    ///     str: JSON list of closed deals
+    pub fn closed_deals_history<'py>(
+        &self,
+        py: Python<'py>,
+        start: i64,
+        end: i64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (Some(from), Some(to)) = (
+            DateTime::<Utc>::from_timestamp(start, 0),
+            DateTime::<Utc>::from_timestamp(end, 0),
+        ) else {
+            return Err(
+                BinaryErrorPy::InvalidParameter("Invalid timestamp range".to_string()).into(),
+            );
+        };
+        if from > to {
+            return Err(
+                BinaryErrorPy::InvalidParameter("start must not be after end".to_string()).into(),
+            );
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let deals = client
+                .closed_deals_history(from, to)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&deals)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Fetches the tick data of an asset between two unix timestamps from the server.
+    ///
+    /// Args:
+    ///     asset (str): Trading symbol
+    ///     start (int): Range start as a unix timestamp (seconds)
+    ///     end (int): Range end as a unix timestamp (seconds)
+    ///
+    /// Returns:
+    ///     str: JSON list of `[timestamp, price]` pairs sorted by timestamp
+    pub fn ticks_range<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+        start: i64,
+        end: i64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (Some(from), Some(to)) = (
+            DateTime::<Utc>::from_timestamp(start, 0),
+            DateTime::<Utc>::from_timestamp(end, 0),
+        ) else {
+            return Err(
+                BinaryErrorPy::InvalidParameter("Invalid timestamp range".to_string()).into(),
+            );
+        };
+        if from > to {
+            return Err(
+                BinaryErrorPy::InvalidParameter("start must not be after end".to_string()).into(),
+            );
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let ticks = client
+                .ticks_range(asset, from, to)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&ticks)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    pub fn get_closed_deal<'py>(
+        &self,
+        py: Python<'py>,
+        trade_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let uuid = Uuid::parse_str(&trade_id).map_err(BinaryErrorPy::from)?;
+            if let Some(deal) = client.get_closed_deal(uuid).await {
+                let res = serde_json::to_string(&deal).map_err(BinaryErrorPy::from)?;
+                Ok(Some(res))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    pub fn clear_closed_deals<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client.clear_closed_deals().await;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
+    pub fn opened_deals<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let deals = client.get_opened_deals().await;
+            let res = serde_json::to_string(&deals).map_err(BinaryErrorPy::from)?;
+            Ok(res)
+        })
+    }
+
+    pub fn get_opened_deal<'py>(
         &self,
         py: Python<'py>,
         trade_id: String,
@@ -501,23 +1393,14 @@ impl RawPocketOption {
         })
     }
 
-    pub fn payout<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    /// Returns every asset, active or not, as a JSON list sorted by symbol.
+    pub fn get_assets<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
             match client.assets().await {
                 Some(assets) => {
-                    let payouts: HashMap<&String, i32> = assets
-                        .0
-                        .iter()
-                        .filter_map(|(asset, symbol)| {
-                            if symbol.is_active {
-                                Some((asset, symbol.payout))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    let res = serde_json::to_string(&payouts).map_err(BinaryErrorPy::from)?;
+                    let res =
+                        serde_json::to_string(&assets.search("")).map_err(BinaryErrorPy::from)?;
                     Ok(res)
                 }
                 None => {
@@ -530,107 +1413,535 @@ impl RawPocketOption {
     pub fn active_assets<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            match client.active_assets().await {
-                Some(assets) => {
-                    let res = serde_json::to_string(&assets).map_err(BinaryErrorPy::from)?;
-                    Ok(res)
-                }
-                None => {
-                    Err(BinaryErrorPy::Uninitialized("Assets not initialized yet.".into()).into())
+            match client.active_assets().await {
+                Some(assets) => {
+                    let res = serde_json::to_string(&assets).map_err(BinaryErrorPy::from)?;
+                    Ok(res)
+                }
+                None => {
+                    Err(BinaryErrorPy::Uninitialized("Assets not initialized yet.".into()).into())
+                }
+            }
+        })
+    }
+
+    /// Returns the trading hours of `asset` as a JSON string with `always_open` and a list
+    /// of weekly UTC `windows`.
+    pub fn asset_schedule<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let schedule = client
+                .asset_schedule(asset)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&schedule)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Returns true if `asset` is active and its schedule is open at the current server time.
+    pub fn is_tradeable_now<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let tradeable = client
+                .is_tradeable_now(asset)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| tradeable.into_py_any(py))
+        })
+    }
+
+    /// Requests the asset list again and returns it as a JSON string once received.
+    #[pyo3(signature = (timeout_secs=30.0))]
+    pub fn refresh_assets<'py>(
+        &self,
+        py: Python<'py>,
+        timeout_secs: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if !timeout_secs.is_finite() || timeout_secs < 0.0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "timeout_secs must be a non-negative number".into(),
+            )
+            .into());
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let assets = client
+                .refresh_assets(Duration::from_secs_f64(timeout_secs))
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&assets)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Returns an async iterator over asset changes as JSON strings.
+    ///
+    /// Each change has a `kind` of `"added"`, `"removed"`, `"opened"`, `"closed"` or
+    /// `"payout_changed"` and the `symbol` it applies to.
+    pub fn assets_changed<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut changes = self.client.assets_changed();
+        future_into_py(py, async move {
+            let boxed_stream = async_stream::stream! {
+                loop {
+                    match changes.recv().await {
+                        Ok(change) => match serde_json::to_string(&change) {
+                            Ok(json) => yield Ok(json),
+                            Err(e) => yield Err(PocketError::General(e.to_string())),
+                        },
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
+    pub fn history<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+        period: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let res = client
+                .candles(asset, period)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&res)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Compiles custom candlesticks from raw tick history.
+    ///
+    /// This method fetches raw tick data for the asset over the specified
+    /// `lookback_period` and then aggregates those ticks into custom-sized
+    /// candlesticks of `custom_period` seconds.
+    ///
+    /// Args:
+    ///     asset (str): Trading symbol
+    ///     custom_period (int): Desired candle duration in seconds
+    ///     lookback_period (int): Number of seconds of tick history to fetch
+    ///
+    /// Returns:
+    ///     List[Dict]: List of candle dictionaries with OHLC data
+    pub fn compile_candles<'py>(
+        &self,
+        py: Python<'py>,
+        asset: String,
+        custom_period: u32,
+        lookback_period: u32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if custom_period == 0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "custom_period must be non-zero".to_string(),
+            )
+            .into());
+        }
+        if lookback_period == 0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "lookback_period must be non-zero".to_string(),
+            )
+            .into());
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let res = client
+                .compile_candles(asset, custom_period, lookback_period)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| {
+                serde_json::to_string(&res)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    pub fn send_raw<'py>(&self, py: Python<'py>, message: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client
+                .send_raw(message)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
+    pub fn subscribe_raw<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let raw_stream = client
+                .subscribe_raw()
+                .await
+                .map_err(BinaryErrorPy::from)?;
+
+            let boxed_stream = async_stream::stream! {
+                tokio::pin!(raw_stream);
+                while let Some(msg) = raw_stream.next().await {
+                    yield Ok(arc_message_to_string(&msg));
+                }
+            }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
+    /// Calls `callback(message, direction)` for every text or binary frame, with a `direction`
+    /// of `"send"` or `"receive"`.
+    ///
+    /// The callback runs on its own thread, fed by a queue of `queue_size` frames, so it never
+    /// delays the connection; frames arriving while the queue is full are dropped.
+    #[pyo3(signature = (callback, queue_size=1024))]
+    pub fn add_middleware<'py>(
+        &self,
+        py: Python<'py>,
+        callback: Py<PyAny>,
+        queue_size: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (queue, mut frames) = mpsc::channel::<(String, &'static str)>(queue_size.max(1));
+        std::thread::Builder::new()
+            .name("python-middleware".to_string())
+            .spawn(move || {
+                while let Some((message, direction)) = frames.blocking_recv() {
+                    Python::attach(|py| {
+                        if let Err(e) = callback.call1(py, (message, direction)) {
+                            warn!(target: "PyMiddleware", "Middleware callback failed: {}", e);
+                        }
+                    });
+                }
+            })?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            client
+                .add_middleware(Box::new(PyMiddleware { queue }))
+                .await;
+            Python::attach(|py| py.None().into_py_any(py))
+        })
+    }
+
+    /// Returns an async iterator over the quotes of a scanner rotating `slots` subscriptions
+    /// through `assets`, each group staying subscribed for `dwell_secs`.
+    ///
+    /// Every quote is a JSON object with the `asset`, its last `candle`, `received_at` and
+    /// the `rotation` it comes from.
+    #[pyo3(signature = (assets, slots=4, dwell_secs=5.0))]
+    pub fn scanner<'py>(
+        &self,
+        py: Python<'py>,
+        assets: Vec<String>,
+        slots: usize,
+        dwell_secs: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let config = ScannerConfig::default()
+            .with_slots(slots)
+            .with_dwell(Duration::from_secs_f64(dwell_secs.max(0.0)));
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let scanner = client
+                .scanner(assets, config)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            let boxed_stream = scanner
+                .to_stream()
+                .map(|quote| {
+                    serde_json::to_string(&quote).map_err(|e| PocketError::General(e.to_string()))
+                })
+                .boxed()
+                .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
+    /// Calls `callback(event)` on the running event loop every time the connection is
+    /// established after this call, so normally on reconnects. `event` is the JSON string of
+    /// a `"connected"` reconnection event.
+    ///
+    /// Coroutine functions are scheduled as tasks of the loop. Resolves to a `CallbackHandle`
+    /// whose `cancel()` removes the callback.
+    pub fn on_reconnect<'py>(
+        &self,
+        py: Python<'py>,
+        callback: Py<PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut events = self.client.reconnect_events();
+        let connected = async_stream::stream! {
+            loop {
+                match events.recv().await {
+                    Ok(event @ ReconnectEvent::Connected { .. }) => {
+                        yield reconnect_event_to_json(&event)
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
+        spawn_callback(py, callback, connected)
+    }
+
+    /// Calls `callback(status)` on the running event loop every time an open connection is
+    /// lost, with the new status (`"reconnecting"` or `"disconnected"`).
+    ///
+    /// Coroutine functions are scheduled as tasks of the loop. Resolves to a `CallbackHandle`
+    /// whose `cancel()` removes the callback.
+    pub fn on_disconnect<'py>(
+        &self,
+        py: Python<'py>,
+        callback: Py<PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut was_connected = None;
+        let lost = self.client.status_stream().filter_map(move |status| {
+            let previous = was_connected.replace(status.is_connected());
+            let lost = previous == Some(true) && !status.is_connected();
+            std::future::ready(if lost { Some(status.to_string()) } else { None })
+        });
+        spawn_callback(py, callback, lost)
+    }
+
+    /// Subscribes to `assets` and resolves to a `CandleBuffer` keeping the last `size` candles
+    /// of each one, time aligned candles of `time` when given, raw price updates otherwise.
+    #[pyo3(signature = (assets, size, time=None))]
+    pub fn candle_buffer<'py>(
+        &self,
+        py: Python<'py>,
+        assets: Vec<String>,
+        size: usize,
+        time: Option<Duration>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let windows: CandleWindows = Arc::default();
+            let mut tasks = Vec::with_capacity(assets.len());
+            for asset in assets {
+                let sub_type = match time {
+                    Some(time) => {
+                        SubscriptionType::time_aligned(time).map_err(BinaryErrorPy::from)?
+                    }
+                    None => SubscriptionType::none(),
+                };
+                let subscription = client
+                    .subscribe(asset.clone(), sub_type)
+                    .await
+                    .map_err(BinaryErrorPy::from)?;
+                windows
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(asset.clone(), CandleWindow::new(size));
+                let windows = windows.clone();
+                let task = tokio::spawn(async move {
+                    let mut candles = std::pin::pin!(subscription.to_stream());
+                    while let Some(candle) = candles.next().await {
+                        match candle {
+                            Ok(candle) => {
+                                let mut windows = windows.lock().unwrap_or_else(|e| e.into_inner());
+                                if let Some(window) = windows.get_mut(&asset) {
+                                    window.push(candle);
+                                }
+                            }
+                            Err(e) => {
+                                warn!(target: "CandleBuffer", "Subscription to {} ended: {}", asset, e);
+                                break;
+                            }
+                        }
+                    }
+                });
+                tasks.push(task.abort_handle());
+            }
+            Python::attach(|py| CandleBuffer { windows, tasks }.into_py_any(py))
+        })
+    }
+
+    /// Returns an async iterator over reconnection events as JSON strings.
+    ///
+    /// Each event has a `type` of `"scheduled"`, `"connected"`, `"limit_reached"` or
+    /// `"exhausted"`.
+    pub fn reconnect_events<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut events = self.client.reconnect_events();
+        future_into_py(py, async move {
+            let boxed_stream = async_stream::stream! {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => yield Ok(reconnect_event_to_json(&event)),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
                 }
             }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
         })
     }
 
-    pub fn history<'py>(
+    /// Returns an async iterator over the volatility of the ticks of `asset` over `period`
+    /// returns, as JSON objects with `stddev`, `atr` and the estimated `spread`.
+    #[pyo3(signature = (asset, period=14))]
+    pub fn volatility<'py>(
         &self,
         py: Python<'py>,
         asset: String,
-        period: u32,
+        period: usize,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            let res = client
-                .candles(asset, period)
+            let samples = analytics::volatility(&client, asset, period)
                 .await
                 .map_err(BinaryErrorPy::from)?;
-            Python::attach(|py| {
-                serde_json::to_string(&res)
-                    .map_err(BinaryErrorPy::from)?
-                    .into_py_any(py)
-            })
+            let boxed_stream = samples
+                .to_stream()
+                .map(|sample| {
+                    sample.and_then(|sample| {
+                        serde_json::to_string(&sample)
+                            .map_err(|e| PocketError::General(e.to_string()))
+                    })
+                })
+                .boxed()
+                .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
         })
     }
 
-    /// Compiles custom candlesticks from raw tick history.
-    ///
-    /// This method fetches raw tick data for the asset over the specified
-    /// `lookback_period` and then aggregates those ticks into custom-sized
-    /// candlesticks of `custom_period` seconds.
+    /// Adds a price alert on `asset` and returns its id.
     ///
-    /// Args:
-    ///     asset (str): Trading symbol
-    ///     custom_period (int): Desired candle duration in seconds
-    ///     lookback_period (int): Number of seconds of tick history to fetch
-    ///
-    /// Returns:
-    ///     List[Dict]: List of candle dictionaries with OHLC data
-    pub fn compile_candles<'py>(
+    /// `condition` is `"crosses_above"`, `"crosses_below"`, `"above"` or `"below"`, checked
+    /// against `price` on every tick. A repeating alert fires again each time the condition
+    /// becomes true, otherwise it is removed after firing.
+    #[pyo3(signature = (asset, condition, price, repeat=false))]
+    pub fn add_alert<'py>(
         &self,
         py: Python<'py>,
         asset: String,
-        custom_period: u32,
-        lookback_period: u32,
+        condition: String,
+        price: f64,
+        repeat: bool,
     ) -> PyResult<Bound<'py, PyAny>> {
-        if custom_period == 0 {
-            return Err(BinaryErrorPy::InvalidParameter(
-                "custom_period must be non-zero".to_string(),
-            )
-            .into());
-        }
-        if lookback_period == 0 {
-            return Err(BinaryErrorPy::InvalidParameter(
-                "lookback_period must be non-zero".to_string(),
-            )
-            .into());
-        }
+        let condition = parse_alert_condition(&condition, price)?;
+        let mode = if repeat {
+            AlertMode::Repeat
+        } else {
+            AlertMode::Once
+        };
         let client = self.client.clone();
         future_into_py(py, async move {
-            let res = client
-                .compile_candles(asset, custom_period, lookback_period)
+            let id = client
+                .add_alert(asset, condition, mode)
                 .await
                 .map_err(BinaryErrorPy::from)?;
-            Python::attach(|py| {
-                serde_json::to_string(&res)
-                    .map_err(BinaryErrorPy::from)?
-                    .into_py_any(py)
-            })
+            Ok(id.to_string())
         })
     }
 
-    pub fn send_raw<'py>(&self, py: Python<'py>, message: String) -> PyResult<Bound<'py, PyAny>> {
+    /// Removes an alert, resolves to `False` if it already fired or never existed.
+    pub fn remove_alert<'py>(
+        &self,
+        py: Python<'py>,
+        alert_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let id = Uuid::parse_str(&alert_id).map_err(BinaryErrorPy::from)?;
+        let client = self.client.clone();
+        future_into_py(py, async move { Ok(client.remove_alert(id).await) })
+    }
+
+    /// Returns the alerts still registered as a JSON array.
+    pub fn active_alerts<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
-            client
-                .send_raw(message)
-                .await
-                .map_err(BinaryErrorPy::from)?;
-            Python::attach(|py| py.None().into_py_any(py))
+            let alerts = client.active_alerts().await;
+            Ok(serde_json::to_string(&alerts).map_err(BinaryErrorPy::from)?)
         })
     }
 
-    pub fn subscribe_raw<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        let client = self.client.clone();
+    /// Returns an async iterator over the fired alerts as JSON strings.
+    pub fn alert_events<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut events = self.client.alert_events();
         future_into_py(py, async move {
-            let raw_stream = client
-                .subscribe_raw()
-                .await
-                .map_err(BinaryErrorPy::from)?;
+            let boxed_stream = async_stream::stream! {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => yield serde_json::to_string(&event)
+                            .map_err(|e| PocketError::General(e.to_string())),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
 
+    /// Returns an async iterator over the frames the client could not parse, as JSON strings.
+    pub fn parse_failures<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut failures = self.client.parse_failures();
+        future_into_py(py, async move {
             let boxed_stream = async_stream::stream! {
-                tokio::pin!(raw_stream);
-                while let Some(msg) = raw_stream.next().await {
-                    yield Ok(arc_message_to_string(&msg));
+                loop {
+                    match failures.recv().await {
+                        Ok(failure) => yield serde_json::to_string(&failure)
+                            .map_err(|e| PocketError::General(e.to_string())),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+            .boxed()
+            .fuse();
+
+            let stream = Arc::new(Mutex::new(boxed_stream));
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
+    /// Returns an async iterator over the suspicious candles found in the received data,
+    /// as JSON strings.
+    pub fn data_quality_events<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut events = self.client.data_quality_events();
+        future_into_py(py, async move {
+            let boxed_stream = async_stream::stream! {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => yield serde_json::to_string(&event)
+                            .map_err(|e| PocketError::General(e.to_string())),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
                 }
             }
             .boxed()
@@ -680,12 +1991,15 @@ impl RawPocketOption {
         })
     }
 
+    #[pyo3(signature = (symbol, time, gap_fill=None))]
     pub fn subscribe_symbol_timed<'py>(
         &self,
         py: Python<'py>,
         symbol: String,
         time: Duration,
+        gap_fill: Option<String>,
     ) -> PyResult<Bound<'py, PyAny>> {
+        let gap_fill = gap_fill_mode(gap_fill.as_deref())?;
         let client = self.client.clone();
         future_into_py(py, async move {
             let subscription = client
@@ -693,19 +2007,22 @@ impl RawPocketOption {
                 .await
                 .map_err(BinaryErrorPy::from)?;
 
-            let boxed_stream = subscription.to_stream().boxed().fuse();
+            let boxed_stream = subscription.heal_gaps(gap_fill).to_stream().boxed().fuse();
             let stream = Arc::new(Mutex::new(boxed_stream));
 
             Python::attach(|py| StreamIterator { stream }.into_py_any(py))
         })
     }
 
+    #[pyo3(signature = (symbol, time, gap_fill=None))]
     pub fn subscribe_symbol_time_aligned<'py>(
         &self,
         py: Python<'py>,
         symbol: String,
         time: Duration,
+        gap_fill: Option<String>,
     ) -> PyResult<Bound<'py, PyAny>> {
+        let gap_fill = gap_fill_mode(gap_fill.as_deref())?;
         let client = self.client.clone();
         future_into_py(py, async move {
             let subscription = client
@@ -716,13 +2033,47 @@ impl RawPocketOption {
                 .await
                 .map_err(BinaryErrorPy::from)?;
 
-            let boxed_stream = subscription.to_stream().boxed().fuse();
+            let boxed_stream = subscription.heal_gaps(gap_fill).to_stream().boxed().fuse();
             let stream = Arc::new(Mutex::new(boxed_stream));
 
             Python::attach(|py| StreamIterator { stream }.into_py_any(py))
         })
     }
 
+    /// Subscribes to several assets and returns one async iterator over JSON `[asset, candle]`
+    /// pairs.
+    ///
+    /// Each subscription is `(asset, kind, value)`: kind `"none"` for every price update,
+    /// `"chunk"` with the number of updates per candle, `"time"` or `"time_aligned"` with the
+    /// candle period in seconds.
+    pub fn subscribe_many<'py>(
+        &self,
+        py: Python<'py>,
+        subscriptions: Vec<(String, String, Option<f64>)>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let subscriptions = subscriptions
+            .into_iter()
+            .map(|(asset, kind, value)| Ok((asset, subscription_type(&kind, value)?)))
+            .collect::<PyResult<Vec<_>>>()?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let pairs = client
+                .subscribe_many(subscriptions)
+                .await
+                .map_err(BinaryErrorPy::from)?;
+
+            let boxed_stream = pairs
+                .map(|(asset, candle)| {
+                    candle.map(|candle| serde_json::json!([asset, candle]).to_string())
+                })
+                .boxed()
+                .fuse();
+            let stream = Arc::new(Mutex::new(boxed_stream));
+
+            Python::attach(|py| RawStreamIterator { stream }.into_py_any(py))
+        })
+    }
+
     pub fn send_raw_message<'py>(
         &self,
         py: Python<'py>,
@@ -895,6 +2246,28 @@ impl RawPocketOption {
         })
     }
 
+    /// Name of the time zone naive times are read in and `to_datetime` converts to.
+    pub fn timezone(&self) -> &'static str {
+        self.client.config.timezone.zone.name()
+    }
+
+    /// Converts a unix timestamp, a datetime or a time string to a unix timestamp in
+    /// seconds, reading naive datetimes and strings without an offset in the configured
+    /// time zone.
+    pub fn to_timestamp(&self, value: &Bound<'_, PyAny>) -> PyResult<i64> {
+        Ok(timestamp_arg(value, &self.client.config.timezone)?)
+    }
+
+    /// Converts a unix timestamp to an aware datetime in the configured time zone.
+    pub fn to_datetime(&self, timestamp: f64) -> PyResult<DateTime<FixedOffset>> {
+        let seconds = timestamp.floor();
+        let nanos = ((timestamp - seconds) * 1e9).round().min(999_999_999.0) as u32;
+        let time = DateTime::from_timestamp(seconds as i64, nanos).ok_or_else(|| {
+            BinaryErrorPy::InvalidParameter(format!("Invalid timestamp: {timestamp}"))
+        })?;
+        Ok(self.client.config.timezone.localize(time).fixed_offset())
+    }
+
     pub fn get_server_time<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(
@@ -903,6 +2276,50 @@ impl RawPocketOption {
         )
     }
 
+    /// Returns the server time minus the local time, in seconds.
+    pub fn server_time_offset<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            Ok(client.server_time_offset().await.num_seconds())
+        })
+    }
+
+    /// Returns the offset, staleness and jitter of the server time.
+    ///
+    /// Returns:
+    ///     str: JSON object with `offset`, `staleness`, `jitter`, `samples` and `resyncs`
+    pub fn server_time_quality<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let quality = client.server_time_quality().await;
+            Python::attach(|py| {
+                serde_json::to_string(&quality)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
+    /// Captures the state of the client for bug reports and dashboards.
+    ///
+    /// The session token is never included, the uid and balances are left out while log
+    /// redaction is on.
+    ///
+    /// Returns:
+    ///     str: JSON object with the balances, server time, subscriptions, last ticks, deals
+    ///     and pending orders with their ages
+    pub fn debug_snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let snapshot = client.debug_snapshot().await;
+            Python::attach(|py| {
+                serde_json::to_string(&snapshot)
+                    .map_err(BinaryErrorPy::from)?
+                    .into_py_any(py)
+            })
+        })
+    }
+
     /// Commands the runner to shutdown.
     pub fn shutdown<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
@@ -912,6 +2329,29 @@ impl RawPocketOption {
         })
     }
 
+    /// Stops accepting new trades, waits up to `timeout_secs` for in-flight trades to settle,
+    /// then shuts the runner down. Resolves to the number of trades still unresolved.
+    pub fn shutdown_graceful<'py>(
+        &self,
+        py: Python<'py>,
+        timeout_secs: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if !timeout_secs.is_finite() || timeout_secs < 0.0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "timeout_secs must be a non-negative number".into(),
+            )
+            .into());
+        }
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let unresolved = client
+                .shutdown_graceful(Duration::from_secs_f64(timeout_secs))
+                .await
+                .map_err(BinaryErrorPy::from)?;
+            Python::attach(|py| unresolved.into_py_any(py))
+        })
+    }
+
     /// Disconnects the client while keeping the configuration intact.
     pub fn disconnect<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
@@ -931,6 +2371,15 @@ impl RawPocketOption {
     }
 
     /// Disconnects and reconnects the client.
+    /// Resolves with a JSON object once the runner stops without a shutdown.
+    pub fn fatal_error<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let error = client.fatal_error().await;
+            Ok(serde_json::to_string(&error).map_err(BinaryErrorPy::from)?)
+        })
+    }
+
     pub fn reconnect<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         future_into_py(py, async move {
@@ -951,21 +2400,35 @@ impl RawPocketOption {
         })
     }
 
-    /// Creates a raw handler with validator and optional keep-alive message.
+    /// Creates a raw handler with validator, optional keep-alive message, optional
+    /// JSONPath of the id used to correlate responses (see `RawHandler.send_and_wait_for`)
+    /// and whether Socket.IO binary packets are delivered reassembled.
+    #[pyo3(signature = (validator, keep_alive=None, correlation_path=None, reassemble=false))]
     pub fn create_raw_handler<'py>(
         &self,
         py: Python<'py>,
         validator: Bound<'py, RawValidator>,
         keep_alive: Option<String>,
+        correlation_path: Option<String>,
+        reassemble: bool,
     ) -> PyResult<Bound<'py, PyAny>> {
         let client = self.client.clone();
         let validator = validator.get().clone();
+        let correlation = correlation_path
+            .map(|path| JsonPath::parse(&path))
+            .transpose()
+            .map_err(BinaryErrorPy::from)?;
         future_into_py(py, async move {
             let crate_validator: CrateValidator = validator.into();
             let keep_alive_msg =
                 keep_alive.map(binary_options_tools::pocketoption::modules::raw::Outgoing::Text);
+            let options = RawHandlerOptions {
+                keep_alive: keep_alive_msg,
+                correlation,
+                reassemble,
+            };
             let handler = client
-                .create_raw_handler(crate_validator, keep_alive_msg)
+                .create_raw_handler_with_options(crate_validator, options)
                 .await
                 .map_err(BinaryErrorPy::from)?;
             Python::attach(|py| {
@@ -1004,6 +2467,64 @@ impl StreamIterator {
             res.map(|res| serde_json::to_string(&res).unwrap_or_default())
         })
     }
+
+    /// Awaits the next candle for at most `seconds`, resolving to `None` when none arrived.
+    pub fn next_timeout<'py>(&self, py: Python<'py>, seconds: f64) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.stream.clone();
+        let timeout = Duration::from_secs_f64(seconds.max(0.0));
+        future_into_py(py, async move {
+            let candle = next_stream_timeout(stream, timeout, false).await?;
+            Ok(candle.map(|candle| serde_json::to_string(&candle).unwrap_or_default()))
+        })
+    }
+
+    /// Blocking version of `next_timeout`.
+    pub fn next_timeout_blocking(&self, py: Python<'_>, seconds: f64) -> PyResult<Option<String>> {
+        let runtime = get_runtime(py)?;
+        let stream = self.stream.clone();
+        let timeout = Duration::from_secs_f64(seconds.max(0.0));
+        let candle = runtime.block_on(next_stream_timeout(stream, timeout, true))?;
+        Ok(candle.map(|candle| serde_json::to_string(&candle).unwrap_or_default()))
+    }
+
+    /// Awaits up to `n` candles, resolving early with the candles received once `timeout`
+    /// seconds elapsed. The end of the stream is only raised when no candle was received.
+    #[pyo3(signature = (n, timeout=None))]
+    pub fn collect<'py>(
+        &self,
+        py: Python<'py>,
+        n: usize,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.stream.clone();
+        let timeout = timeout.map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        future_into_py(py, async move {
+            let candles = collect_stream(stream, n, timeout, false).await?;
+            Ok(candles_to_json(&candles))
+        })
+    }
+
+    /// Blocking version of `collect`.
+    #[pyo3(signature = (n, timeout=None))]
+    pub fn collect_blocking(
+        &self,
+        py: Python<'_>,
+        n: usize,
+        timeout: Option<f64>,
+    ) -> PyResult<Vec<String>> {
+        let runtime = get_runtime(py)?;
+        let stream = self.stream.clone();
+        let timeout = timeout.map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        let candles = runtime.block_on(collect_stream(stream, n, timeout, true))?;
+        Ok(candles_to_json(&candles))
+    }
+}
+
+fn candles_to_json(candles: &[Candle]) -> Vec<String> {
+    candles
+        .iter()
+        .map(|candle| serde_json::to_string(candle).unwrap_or_default())
+        .collect()
 }
 
 #[pymethods]
@@ -1032,6 +2553,47 @@ impl RawStreamIterator {
             res
         })
     }
+
+    /// Awaits the next message for at most `seconds`, resolving to `None` when none arrived.
+    pub fn next_timeout<'py>(&self, py: Python<'py>, seconds: f64) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.stream.clone();
+        let timeout = Duration::from_secs_f64(seconds.max(0.0));
+        future_into_py(py, next_stream_timeout(stream, timeout, false))
+    }
+
+    /// Blocking version of `next_timeout`.
+    pub fn next_timeout_blocking(&self, py: Python<'_>, seconds: f64) -> PyResult<Option<String>> {
+        let runtime = get_runtime(py)?;
+        let timeout = Duration::from_secs_f64(seconds.max(0.0));
+        runtime.block_on(next_stream_timeout(self.stream.clone(), timeout, true))
+    }
+
+    /// Awaits up to `n` messages, resolving early with the messages received once `timeout`
+    /// seconds elapsed. The end of the stream is only raised when no message was received.
+    #[pyo3(signature = (n, timeout=None))]
+    pub fn collect<'py>(
+        &self,
+        py: Python<'py>,
+        n: usize,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.stream.clone();
+        let timeout = timeout.map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        future_into_py(py, collect_stream(stream, n, timeout, false))
+    }
+
+    /// Blocking version of `collect`.
+    #[pyo3(signature = (n, timeout=None))]
+    pub fn collect_blocking(
+        &self,
+        py: Python<'_>,
+        n: usize,
+        timeout: Option<f64>,
+    ) -> PyResult<Vec<String>> {
+        let runtime = get_runtime(py)?;
+        let timeout = timeout.map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        runtime.block_on(collect_stream(self.stream.clone(), n, timeout, true))
+    }
 }
 
 #[pymethods]
@@ -1122,6 +2684,36 @@ impl RawHandler {
         })
     }
 
+    /// Send a message and wait for the response carrying `correlation_id` at the
+    /// handler's correlation path
+    #[pyo3(signature = (message, correlation_id, timeout_secs = 30.0))]
+    pub fn send_and_wait_for<'py>(
+        &self,
+        py: Python<'py>,
+        message: String,
+        correlation_id: String,
+        timeout_secs: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let handler = self.handler.clone();
+        if !timeout_secs.is_finite() || timeout_secs < 0.0 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "timeout_secs must be a non-negative number".into(),
+            )
+            .into());
+        }
+        let timeout = Duration::from_secs_f64(timeout_secs);
+        future_into_py(py, async move {
+            let msg = binary_options_tools::pocketoption::modules::raw::Outgoing::Text(message);
+            // The lock is only held to enqueue the request, so several can be in flight.
+            let response = handler
+                .lock()
+                .await
+                .send_and_wait_for(msg, correlation_id, timeout);
+            let response = response.await.map_err(BinaryErrorPy::from)?;
+            Ok(arc_message_to_string(&response))
+        })
+    }
+
     /// Wait for the next message that matches this handler's validator
     pub fn wait_next<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let handler = self.handler.clone();
@@ -1152,3 +2744,110 @@ impl RawHandler {
         RawStreamIterator { stream }.into_bound_py_any(py)
     }
 }
+
+fn partial_bucket(keep_partial: bool) -> PartialBucket {
+    if keep_partial {
+        PartialBucket::Keep
+    } else {
+        PartialBucket::Drop
+    }
+}
+
+/// Aggregates candles of `from_period` seconds into candles of `to_period` seconds, aligned
+/// on UTC like the live time aligned subscriptions.
+///
+/// :param candles: The candles of one asset as a JSON array, e.g. from `get_candles`.
+/// :param keep_partial: Keeps a last bucket the candles end inside of, with `is_closed` unset.
+/// :return: The resampled candles as a JSON array.
+#[pyfunction]
+#[pyo3(signature = (candles, from_period, to_period, keep_partial = false))]
+pub fn resample_candles(
+    candles: String,
+    from_period: u32,
+    to_period: u32,
+    keep_partial: bool,
+) -> PyResult<String> {
+    let candles: Vec<Candle> = serde_json::from_str(&candles).map_err(BinaryErrorPy::from)?;
+    let resampled = resample_with(
+        &candles,
+        from_period,
+        to_period,
+        partial_bucket(keep_partial),
+    )
+    .map_err(BinaryErrorPy::from)?;
+    Ok(serde_json::to_string(&resampled).map_err(BinaryErrorPy::from)?)
+}
+
+/// Builds candles of `period` seconds from `(timestamp, price)` ticks, the way a live time
+/// aligned subscription of `symbol` does.
+///
+/// :param keep_partial: Keeps the last bucket, which is still forming, with `is_closed` unset.
+/// :return: The candles as a JSON array.
+#[pyfunction]
+#[pyo3(signature = (ticks, period, symbol, keep_partial = false))]
+pub fn resample_ticks(
+    ticks: Vec<(i64, f64)>,
+    period: u32,
+    symbol: String,
+    keep_partial: bool,
+) -> PyResult<String> {
+    let candles = resample::resample_ticks(&ticks, period, &symbol, partial_bucket(keep_partial))
+        .map_err(BinaryErrorPy::from)?;
+    Ok(serde_json::to_string(&candles).map_err(BinaryErrorPy::from)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal_of(expression: &std::ffi::CStr) -> Result<Decimal, BinaryErrorPy> {
+        Python::initialize();
+        Python::attach(|py| {
+            let value = py
+                .eval(expression, None, None)
+                .expect("invalid test expression");
+            decimal_arg(&value, "amount")
+        })
+    }
+
+    #[test]
+    fn test_decimal_arg() {
+        let exact = Decimal::from_str_exact("2.33").unwrap();
+        assert_eq!(decimal_of(c"2.33").unwrap(), exact);
+        assert_eq!(decimal_of(c"'2.33'").unwrap(), exact);
+        assert_eq!(decimal_of(c"' 2.33 '").unwrap(), exact);
+        assert_eq!(
+            decimal_of(c"__import__('decimal').Decimal('2.33')").unwrap(),
+            exact
+        );
+        assert_eq!(decimal_of(c"5").unwrap(), Decimal::from(5));
+        assert_eq!(
+            decimal_of(c"0.1").unwrap(),
+            Decimal::from_str_exact("0.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decimal_arg_scientific_notation() {
+        assert_eq!(decimal_of(c"'1e2'").unwrap(), Decimal::from(100));
+        assert_eq!(
+            decimal_of(c"'2.5E-1'").unwrap(),
+            Decimal::from_str_exact("0.25").unwrap()
+        );
+        assert_eq!(
+            decimal_of(c"__import__('decimal').Decimal('1.5e1')").unwrap(),
+            Decimal::from(15)
+        );
+        assert_eq!(decimal_of(c"1e2").unwrap(), Decimal::from(100));
+    }
+
+    #[test]
+    fn test_decimal_arg_rejects_bool_and_garbage() {
+        assert!(decimal_of(c"True").is_err());
+        assert!(decimal_of(c"False").is_err());
+        assert!(decimal_of(c"'abc'").is_err());
+        assert!(decimal_of(c"''").is_err());
+        assert!(decimal_of(c"float('nan')").is_err());
+        assert!(decimal_of(c"None").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures_util::{
     stream::{BoxStream, Fuse},
@@ -34,3 +35,51 @@ where
         },
     }
 }
+
+/// Waits at most `timeout` for the next item of `stream`, `None` when it elapsed first.
+pub async fn next_stream_timeout<T, E>(
+    stream: Arc<Mutex<PyStream<T, E>>>,
+    timeout: Duration,
+    sync: bool,
+) -> PyResult<Option<T>>
+where
+    E: std::error::Error,
+{
+    match tokio::time::timeout(timeout, next_stream(stream, sync)).await {
+        Ok(item) => item.map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Collects up to `n` items of `stream`, returning early once `timeout` elapsed.
+///
+/// The end of the stream (or its error) is only raised when no item was collected, otherwise
+/// the items received before it are returned.
+pub async fn collect_stream<T, E>(
+    stream: Arc<Mutex<PyStream<T, E>>>,
+    n: usize,
+    timeout: Option<Duration>,
+    sync: bool,
+) -> PyResult<Vec<T>>
+where
+    E: std::error::Error,
+{
+    let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+    let mut items = Vec::new();
+    while items.len() < n {
+        let next = next_stream(stream.clone(), sync);
+        let item = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, next).await {
+                Ok(item) => item,
+                Err(_) => break,
+            },
+            None => next.await,
+        };
+        match item {
+            Ok(item) => items.push(item),
+            Err(e) if items.is_empty() => return Err(e),
+            Err(_) => break,
+        }
+    }
+    Ok(items)
+}
@@ -6,10 +6,13 @@ use pyo3::{
     Bound, Py, PyAny, PyResult,
 };
 use regex::Regex;
+use serde_json::Value;
 
-use crate::error::BinaryResultPy;
+use crate::error::{BinaryErrorPy, BinaryResultPy};
 use binary_options_tools::traits::ValidatorTrait;
-use binary_options_tools::validator::Validator as CrateValidator;
+use binary_options_tools::validator::{
+    cached_regex, JsonMatch, JsonPath, Validator as CrateValidator,
+};
 use pyo3::Python;
 
 #[pyclass(from_py_object)]
@@ -26,6 +29,13 @@ pub struct RegexValidator {
     regex: Regex,
 }
 
+#[pyclass(from_py_object)]
+#[derive(Clone)]
+pub struct JsonPathValidator {
+    path: JsonPath,
+    matcher: JsonMatch,
+}
+
 #[pyclass(from_py_object)]
 #[derive(Clone)]
 pub struct PyCustom {
@@ -60,12 +70,13 @@ pub enum RawValidator {
     All(ArrayValidator),
     Any(ArrayValidator),
     Not(BoxedValidator),
+    JsonPath(JsonPathValidator),
     Custom(PyCustom),
 }
 
 impl RawValidator {
     pub fn new_regex(regex: String) -> BinaryResultPy<Self> {
-        let regex = Regex::new(&regex)?;
+        let regex = cached_regex(&regex)?;
         Ok(Self::Regex(RegexValidator { regex }))
     }
 
@@ -92,6 +103,29 @@ impl RawValidator {
     pub fn new_ends_with(pattern: String) -> Self {
         Self::EndsWith(pattern)
     }
+
+    /// Rejects validators nested deeper than the crate's limit.
+    fn checked(self) -> BinaryResultPy<Self> {
+        CrateValidator::from(self.clone()).check_depth()?;
+        Ok(self)
+    }
+}
+
+/// Converts a Python scalar into the JSON value it is compared with.
+fn json_value(value: &Bound<'_, PyAny>) -> BinaryResultPy<Value> {
+    if let Ok(b) = value.extract::<bool>() {
+        Ok(Value::from(b))
+    } else if let Ok(i) = value.extract::<i64>() {
+        Ok(Value::from(i))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(Value::from(f))
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(Value::from(s))
+    } else {
+        Err(BinaryErrorPy::InvalidParameter(
+            "json_path values must be a bool, int, float or str".into(),
+        ))
+    }
 }
 
 impl Default for RawValidator {
@@ -130,21 +164,81 @@ impl RawValidator {
     }
 
     #[staticmethod]
-    pub fn ne(validator: Bound<'_, RawValidator>) -> Self {
+    pub fn ne(validator: Bound<'_, RawValidator>) -> PyResult<Self> {
         let val = validator.get();
-        Self::new_not(val.clone())
+        Ok(Self::new_not(val.clone()).checked()?)
     }
 
     #[staticmethod]
     pub fn all(validator: Bound<'_, PyList>) -> PyResult<Self> {
         let val = validator.extract::<Vec<RawValidator>>()?;
-        Ok(Self::new_all(val))
+        Ok(Self::new_all(val).checked()?)
     }
 
     #[staticmethod]
     pub fn any(validator: Bound<'_, PyList>) -> PyResult<Self> {
         let val = validator.extract::<Vec<RawValidator>>()?;
-        Ok(Self::new_any(val))
+        Ok(Self::new_any(val).checked()?)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, eq=None, ne=None, gt=None, lt=None, matches=None))]
+    /// Matches messages whose JSON value at `path` satisfies the given condition.
+    ///
+    /// `path` is a JSONPath such as `$.data.requestId`, Socket.IO event frames are matched
+    /// as `{"event": <name>, "data": <payload>}`. At most one condition can be given, without
+    /// any the path only has to exist.
+    ///
+    /// # Arguments
+    /// * `eq` / `ne` - A bool, int, float or str the value must (not) be equal to.
+    /// * `gt` / `lt` - A number the value must be greater / lower than.
+    /// * `matches` - A `RawValidator` applied to the value as text.
+    pub fn json_path(
+        path: String,
+        eq: Option<Bound<'_, PyAny>>,
+        ne: Option<Bound<'_, PyAny>>,
+        gt: Option<f64>,
+        lt: Option<f64>,
+        matches: Option<RawValidator>,
+    ) -> PyResult<Self> {
+        let given = [
+            eq.is_some(),
+            ne.is_some(),
+            gt.is_some(),
+            lt.is_some(),
+            matches.is_some(),
+        ];
+        if given.iter().filter(|g| **g).count() > 1 {
+            return Err(BinaryErrorPy::InvalidParameter(
+                "json_path accepts at most one of eq, ne, gt, lt and matches".into(),
+            )
+            .into());
+        }
+        let matcher = if let Some(value) = eq {
+            JsonMatch::Eq(json_value(&value)?)
+        } else if let Some(value) = ne {
+            JsonMatch::Ne(json_value(&value)?)
+        } else if let Some(bound) = gt {
+            JsonMatch::Gt(bound)
+        } else if let Some(bound) = lt {
+            JsonMatch::Lt(bound)
+        } else if let Some(validator) = matches {
+            JsonMatch::Matches(Box::new(validator.into()))
+        } else {
+            JsonMatch::Exists
+        };
+        let path = JsonPath::parse(&path).map_err(BinaryErrorPy::from)?;
+        Ok(Self::JsonPath(JsonPathValidator { path, matcher }).checked()?)
+    }
+
+    /// Returns a validator matching messages accepted by both validators.
+    pub fn and_(&self, other: RawValidator) -> PyResult<Self> {
+        Ok(Self::new_all(vec![self.clone(), other]).checked()?)
+    }
+
+    /// Returns a validator matching messages accepted by either validator.
+    pub fn or_(&self, other: RawValidator) -> PyResult<Self> {
+        Ok(Self::new_any(vec![self.clone(), other]).checked()?)
     }
 
     #[staticmethod]
@@ -195,6 +289,9 @@ impl From<RawValidator> for CrateValidator {
                 let validator: CrateValidator = (*boxed_validator.0).into();
                 CrateValidator::Not(Box::new(validator))
             }
+            RawValidator::JsonPath(json_path) => {
+                CrateValidator::JsonPath(json_path.path, json_path.matcher)
+            }
             RawValidator::Custom(py_custom) => {
                 // Create a custom validator that calls the Python function
                 let custom_validator = Arc::new(PyCustomValidator {
@@ -128,6 +128,7 @@ pub struct Deal {
     pub currency: String,
     pub amount_usd: Option<f64>,
     pub amount_usd2: Option<f64>,
+    pub slippage: Option<f64>,
 }
 
 impl From<OriginalDeal> for Deal {
@@ -159,6 +160,7 @@ impl From<OriginalDeal> for Deal {
             currency: deal.currency,
             amount_usd: deal.amount_usd.and_then(|v| v.to_f64()),
             amount_usd2: deal.amount_usd2.and_then(|v| v.to_f64()),
+            slippage: deal.slippage.and_then(|v| v.to_f64()),
         }
     }
 }
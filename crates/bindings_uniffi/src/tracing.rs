@@ -1,9 +1,11 @@
 /// Initialize logging for the UniFFI bindings.
 ///
-/// Uses the `tracing` crate's default subscriber.
+/// Uses the `tracing` crate's default subscriber, with session tokens, uids and balances
+/// masked.
 /// Call this once at application startup.
 pub fn init_tracing() {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(binary_options_tools::stream::redacted(std::io::stdout))
         .try_init();
 }
@@ -0,0 +1,69 @@
+//! Cost of routing an incoming frame through the rules of many modules.
+//!
+//! Run with `cargo bench -p binary-options-tools-core --bench routing`.
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+use binary_options_tools_core::client::Router;
+use binary_options_tools_core::rules::RuleBuilder;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kanal::{AsyncReceiver, AsyncSender};
+use tokio_tungstenite::tungstenite::Message;
+
+/// A router with `count` modules, module `i` matching the frames starting with `event{i}:`.
+fn router(count: usize) -> (Router<()>, Vec<AsyncReceiver<Arc<Message>>>) {
+    let mut router = Router::new(Arc::new(()));
+    let receivers = (0..count)
+        .map(|i| {
+            let (sender, receiver) = kanal::unbounded_async();
+            let rule = RuleBuilder::text_starts_with(format!("event{i}:")).build();
+            router.add_module_rule(Box::new(rule), sender);
+            receiver
+        })
+        .collect();
+    (router, receivers)
+}
+
+fn bench_route(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("failed to build the runtime");
+    let (sender, _outgoing): (AsyncSender<Message>, _) = kanal::unbounded_async();
+
+    let mut group = c.benchmark_group("route");
+    group.throughput(Throughput::Elements(1));
+    for count in [8, 64, 256] {
+        let (router, receivers) = router(count);
+        // Checked against every rule before matching the last one
+        let last = Arc::new(Message::text(format!(
+            "event{}:[\"EURUSD_otc\",1754529180.123,1.08123]",
+            count - 1
+        )));
+        let unmatched = Arc::new(Message::text("unknown:[]"));
+        let receiver = receivers.last().expect("no module");
+
+        group.bench_with_input(BenchmarkId::new("last_rule", count), &count, |b, _| {
+            b.to_async(&runtime).iter(|| async {
+                router
+                    .route(black_box(last.clone()), &sender)
+                    .await
+                    .unwrap();
+                // Keeps the channel from growing
+                receiver.try_recv().unwrap();
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("no_match", count), &count, |b, _| {
+            b.to_async(&runtime).iter(|| async {
+                router
+                    .route(black_box(unmatched.clone()), &sender)
+                    .await
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_route);
+criterion_main!(benches);
@@ -0,0 +1,227 @@
+//! Audit trail of the messages sent by a client.
+//!
+//! An [`AuditLog`] given to [`ClientBuilder::with_audit_log`] records every outgoing message
+//! with a monotonic sequence number and the module that sent it, answering questions like
+//! "who sent this `changeSymbol`" in bots running many modules.
+//!
+//! Every module gets its own sender which tags its messages before forwarding them to the
+//! socket writer; the log, registered as a middleware, attributes each sent message to the
+//! oldest tagged message equal to it. Messages sent through [`Client::send_message`],
+//! lightweight handlers, connection callbacks or middleware are attributed to
+//! [`CLIENT_SOURCE`].
+//!
+//! [`ClientBuilder::with_audit_log`]: crate::builder::ClientBuilder::with_audit_log
+//! [`Client::send_message`]: crate::client::Client::send_message
+
+use std::any::type_name;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use kanal::{bounded_async, AsyncSender};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::CoreResult;
+use crate::middleware::{MiddlewareContext, WebSocketMiddleware};
+use crate::traits::AppState;
+
+/// Source of the messages not sent by a module.
+pub const CLIENT_SOURCE: &str = "client";
+
+/// Tagged messages waiting for the writer, older ones are dropped past this (e.g. while the
+/// client is disconnected and the queue is cleared).
+const MAX_PENDING: usize = 4096;
+
+/// One message sent by the client.
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    /// Position of the message among all the messages sent, starting at 1.
+    pub seq: u64,
+    /// When the message was handed to the socket.
+    pub at: SystemTime,
+    /// Name of the module that sent the message, or [`CLIENT_SOURCE`].
+    pub source: Arc<str>,
+    pub message: Message,
+}
+
+#[derive(Debug)]
+struct AuditInner {
+    capacity: usize,
+    seq: AtomicU64,
+    records: Mutex<VecDeque<AuditRecord>>,
+    pending: Mutex<VecDeque<(Arc<str>, Message)>>,
+}
+
+/// Ring buffer of the last messages sent by a client, see the [module documentation](self).
+///
+/// Clones share the same log.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    inner: Arc<AuditInner>,
+}
+
+impl AuditLog {
+    /// Keeps the last `capacity` messages.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(AuditInner {
+                capacity: capacity.max(1),
+                seq: AtomicU64::new(0),
+                records: Mutex::new(VecDeque::new()),
+                pending: Mutex::new(VecDeque::new()),
+            }),
+        }
+    }
+
+    /// Number of messages kept.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Sequence number of the last message sent, `0` before the first one.
+    pub fn last_seq(&self) -> u64 {
+        self.inner.seq.load(Ordering::Relaxed)
+    }
+
+    /// The kept messages with a sequence number greater than `since`, oldest first.
+    pub fn since(&self, since: u64) -> Vec<AuditRecord> {
+        let records = self.inner.records.lock().unwrap_or_else(|e| e.into_inner());
+        records
+            .iter()
+            .filter(|record| record.seq > since)
+            .cloned()
+            .collect()
+    }
+
+    /// Forgets the kept messages, sequence numbers keep growing.
+    pub fn clear(&self) {
+        self.inner
+            .records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Sender tagging the messages of `source` before forwarding them to `sender`.
+    ///
+    /// Must be called from a Tokio runtime, the forwarding task stops once every clone of the
+    /// returned sender is dropped.
+    pub fn tagged_sender(
+        &self,
+        source: &str,
+        sender: AsyncSender<Message>,
+    ) -> AsyncSender<Message> {
+        let (tx, rx) = bounded_async::<Message>(256);
+        let source: Arc<str> = Arc::from(source);
+        let log = self.clone();
+        tokio::spawn(async move {
+            while let Ok(message) = rx.recv().await {
+                log.tag(source.clone(), message.clone());
+                if sender.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        tx
+    }
+
+    fn tag(&self, source: Arc<str>, message: Message) {
+        let mut pending = self.inner.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.len() >= MAX_PENDING {
+            pending.pop_front();
+        }
+        pending.push_back((source, message));
+    }
+
+    fn source_of(&self, message: &Message) -> Arc<str> {
+        let mut pending = self.inner.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending
+            .iter()
+            .position(|(_, tagged)| tagged == message)
+            .and_then(|index| pending.remove(index))
+            .map_or_else(|| Arc::from(CLIENT_SOURCE), |(source, _)| source)
+    }
+
+    fn record(&self, message: &Message) {
+        let record = AuditRecord {
+            seq: self.inner.seq.fetch_add(1, Ordering::Relaxed) + 1,
+            at: SystemTime::now(),
+            source: self.source_of(message),
+            message: message.clone(),
+        };
+        let mut records = self.inner.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() >= self.inner.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+#[async_trait]
+impl<S: AppState> WebSocketMiddleware<S> for AuditLog {
+    async fn on_send(&self, message: &Message, _context: &MiddlewareContext<S>) -> CoreResult<()> {
+        self.record(message);
+        Ok(())
+    }
+}
+
+/// Name of the module `M` in audit records, its type name without the path.
+pub(crate) fn module_source<M>() -> &'static str {
+    let name = type_name::<M>();
+    let path = name.split('<').next().unwrap_or(name);
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanal::AsyncReceiver;
+    use std::time::Duration;
+
+    struct SubscriptionsApiModule;
+
+    /// Sends `text` through `sender` and hands it to the log like the socket writer.
+    async fn send(
+        log: &AuditLog,
+        sender: &AsyncSender<Message>,
+        writer: &AsyncReceiver<Message>,
+        text: &str,
+    ) {
+        sender.send(Message::text(text)).await.unwrap();
+        let message = tokio::time::timeout(Duration::from_secs(1), writer.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let context = MiddlewareContext::new(Arc::new(()), sender.clone());
+        WebSocketMiddleware::<()>::on_send(log, &message, &context)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_audit_attributes_messages() {
+        let log = AuditLog::new(2);
+        let (sender, writer) = bounded_async(8);
+        let module = log.tagged_sender(module_source::<SubscriptionsApiModule>(), sender.clone());
+
+        send(&log, &module, &writer, "42[\"changeSymbol\"]").await;
+        send(&log, &sender, &writer, "42[\"ps\"]").await;
+        send(&log, &module, &writer, "42[\"ps\"]").await;
+
+        assert_eq!(log.last_seq(), 3);
+        // Only the last `capacity` messages are kept.
+        assert_eq!(
+            log.since(0)
+                .iter()
+                .map(|record| (record.seq, &*record.source))
+                .collect::<Vec<_>>(),
+            vec![(2, CLIENT_SOURCE), (3, "SubscriptionsApiModule")]
+        );
+        assert_eq!(log.since(2).len(), 1);
+        log.clear();
+        assert!(log.since(0).is_empty());
+        assert_eq!(log.last_seq(), 3);
+    }
+}
@@ -3,21 +3,29 @@
 use kanal::{bounded_async, AsyncSender};
 use std::any::type_name;
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::task::JoinSet;
 use tokio_tungstenite::tungstenite::Message;
-use tracing::{error, info, warn};
+use tracing::error;
 
+use crate::audit::AuditLog;
 use crate::callback::{ConnectionCallback, ReconnectCallbackStack};
-use crate::client::{Client, ClientRunner, LightweightHandler, Router};
+use crate::channels::ChannelCapacity;
+use crate::client::{Client, ClientRunner, LateModules, LightweightHandler, Router};
 use crate::connector::Connector;
 use crate::error::{CoreError, CoreResult};
 use crate::middleware::{MiddlewareStack, WebSocketMiddleware};
+use crate::reconnect::{LimitAction, ReconnectPolicy};
+use crate::routing::Routing;
 use crate::signals::Signals;
-use crate::traits::{ApiModule, AppState, LightweightModule, ReconnectCallback, RunnerCommand};
+use crate::socketio::FrameMode;
+use crate::supervisor::{supervise, RestartPolicy, SharedRule};
+use crate::traits::{
+    ApiModule, AppState, Dependency, LightweightModule, ReconnectCallback, RunnerCommand,
+};
 
 type HandlerMap = Arc<RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>;
 type HandlersFn<S> = Box<
@@ -32,8 +40,8 @@ type HandlersFn<S> = Box<
         + Sync,
 >;
 
-type LightweightHandlersFn<S> =
-    Box<dyn FnOnce(&mut Router<S>, AsyncSender<Message>, AsyncSender<RunnerCommand>) + Send + Sync>;
+/// A registered module: itself, the modules it depends on and its factory.
+type ModuleEntry<S> = (Dependency, Vec<Dependency>, HandlersFn<S>);
 
 pub struct ClientBuilder<S: AppState> {
     state: Arc<S>,
@@ -41,13 +49,19 @@ pub struct ClientBuilder<S: AppState> {
     connection_callback: ConnectionCallback<S>,
     lightweight_handlers: Vec<LightweightHandler<S>>,
     // Stores functions that know how to create and register each module.
-    module_factories: Vec<HandlersFn<S>>,
-    lightweight_factories: Vec<LightweightHandlersFn<S>>,
+    module_factories: Vec<ModuleEntry<S>>,
     // Middleware stack for WebSocket message processing
     middleware_stack: MiddlewareStack<S>,
 
-    max_allowed_loops: u32,
-    reconnect_delay: Duration,
+    reconnect_policy: ReconnectPolicy,
+    restart_policy: RestartPolicy,
+    module_restart_policies: HashMap<TypeId, RestartPolicy>,
+    channel_capacity: ChannelCapacity,
+    module_channel_capacities: HashMap<TypeId, ChannelCapacity>,
+    module_routing: HashMap<TypeId, Routing>,
+    outgoing_capacity: usize,
+    frame_mode: FrameMode,
+    audit: Option<AuditLog>,
 }
 
 impl<S: AppState> ClientBuilder<S> {
@@ -63,10 +77,16 @@ impl<S: AppState> ClientBuilder<S> {
             },
             lightweight_handlers: Vec::new(),
             module_factories: Vec::new(),
-            lightweight_factories: Vec::new(),
             middleware_stack: MiddlewareStack::new(),
-            max_allowed_loops: 0,
-            reconnect_delay: Duration::from_secs(5),
+            reconnect_policy: ReconnectPolicy::default(),
+            restart_policy: RestartPolicy::default(),
+            module_restart_policies: HashMap::new(),
+            channel_capacity: ChannelCapacity::default(),
+            module_channel_capacities: HashMap::new(),
+            module_routing: HashMap::new(),
+            outgoing_capacity: 256,
+            frame_mode: FrameMode::default(),
+            audit: None,
         }
     }
 
@@ -113,61 +133,47 @@ impl<S: AppState> ClientBuilder<S> {
     /// Registers a lightweight module
     pub fn with_lightweight_module<M: LightweightModule<S>>(mut self) -> Self {
         let factory = |router: &mut Router<S>,
+                       _: &mut JoinSet<()>,
+                       _: HandlerMap,
                        to_ws_tx: AsyncSender<Message>,
-                       runner_tx: AsyncSender<RunnerCommand>| {
-            let (msg_tx, msg_rx) = bounded_async(256);
+                       runner_tx: AsyncSender<RunnerCommand>,
+                       _: &mut ReconnectCallbackStack<S>| {
+            let to_ws_tx = router.module_sender::<M>(to_ws_tx);
+            let capacity = router.channel_capacity_for::<M>();
+            let (msg_tx, msg_rx) = bounded_async(capacity.messages);
 
             let state = router.state.clone();
+            let policy = router.restart_policy_for::<M>();
+            let rule = router
+                .stats
+                .counting_rule(type_name::<M>(), Arc::from(M::rule()));
+            let supervised_rule = rule.clone();
             // Spawn the lightweight module task.
             router.spawn_lightweight_module(async move {
-                    let mut failures = 0;
-                    // make the first timestamp far enough in the past
-                    let mut last_fail = Instant::now()
-                        .checked_sub(Duration::from_secs(3600))
-                        .unwrap_or(Instant::now());
-
-                    loop {
-                        // create the module once
+                supervise(
+                    "LightweightModule",
+                    type_name::<M>(),
+                    policy,
+                    supervised_rule,
+                    || {
                         let mut module = M::new(
                             state.clone(),
                             to_ws_tx.clone(),
                             msg_rx.clone(),
                             runner_tx.clone(),
                         );
-                        match module.run().await {
-                            Ok(()) => {
-                                info!(target: "LightweightModule", "[Lightweight {}] exited cleanly", type_name::<M>());
-                                break;
-                            }
-                            Err(e) => {
-                                let now = Instant::now();
-                                if now.duration_since(last_fail) < Duration::from_secs(30) {
-                                    failures += 1;
-                                } else {
-                                    failures = 1;
-                                }
-                                last_fail = now;
-
-                                if failures >= 5 {
-                                    error!(target: "LightweightModule",
-                                    "[Lightweight {}] failing {}× rapidly: {:?}, backing off 60s",
-                                    type_name::<M>(),
-                                    failures,
-                                    e
-                                );
-                                    tokio::time::sleep(Duration::from_secs(60)).await;
-                                } else {
-                                    warn!(target: "LightweightModule", "[Lightweight {}] error: {:?}", type_name::<M>(), e);
-                                    tokio::time::sleep(Duration::from_secs(1)).await;
-                                }
-                            }
-                        }
-                    }
-                });
-            router.add_lightweight_rule(M::rule(), msg_tx);
+                        async move { module.run().await }
+                    },
+                )
+                .await;
+            });
+            let channel = router.module_channel::<M>(msg_tx, capacity);
+            let routing = router.routing_for::<M>();
+            router.add_route(Box::new(SharedRule(rule)), channel, routing);
         };
 
-        self.lightweight_factories.push(Box::new(factory));
+        self.module_factories
+            .push((Dependency::on::<M>(), Vec::new(), Box::new(factory)));
         self
     }
 
@@ -180,9 +186,11 @@ impl<S: AppState> ClientBuilder<S> {
              to_ws_tx: AsyncSender<Message>,
              runner_tx: AsyncSender<RunnerCommand>,
              reconnect_callback_stack: &mut ReconnectCallbackStack<S>| {
-                let (cmd_tx, cmd_rx) = bounded_async(32);
-                let (cmd_ret_tx, cmd_ret_rx) = bounded_async(32);
-                let (msg_tx, msg_rx) = bounded_async(256);
+                let to_ws_tx = router.module_sender::<M>(to_ws_tx);
+                let capacity = router.channel_capacity_for::<M>();
+                let (cmd_tx, cmd_rx) = bounded_async(capacity.commands);
+                let (cmd_ret_tx, cmd_ret_rx) = bounded_async(capacity.commands);
+                let (msg_tx, msg_rx) = bounded_async(capacity.messages);
 
                 let state = router.state.clone();
                 let handle = M::create_handle(cmd_tx, cmd_ret_rx);
@@ -212,52 +220,39 @@ impl<S: AppState> ClientBuilder<S> {
                         error!(target: "ApiModule", "Failed to get callback for module {}: {:?}", type_name::<M>(), e);
                     }
                 }
-                let state_clone = state.clone();
+                let policy = router.restart_policy_for::<M>();
+                let rule = router
+                    .stats
+                    .counting_rule(type_name::<M>(), Arc::from(M::rule(state.clone())));
+                let supervised_rule = rule.clone();
                 router.spawn_module(async move {
-                let mut failures = 0;
-                let mut last_fail = Instant::now()
-                    .checked_sub(Duration::from_secs(3600))
-                    .unwrap_or(Instant::now());
-                loop {
-                    let mut module = M::new(
-                        state.clone(),
-                        cmd_rx.clone(),
-                        cmd_ret_tx.clone(),
-                        msg_rx.clone(),
-                        to_ws_tx.clone(),
-                        runner_tx.clone(),
-                    );
-                    match module.run().await {
-                        Ok(_) => {
-                            info!(target: "ApiModule", "[Module {}] exited cleanly", type_name::<M>());
-                            break;
-                        }
-                        Err(e) => {
-                            let now = Instant::now();
-                            if now.duration_since(last_fail) < Duration::from_secs(30) {
-                                failures += 1;
-                            } else {
-                                failures = 1;
-                            }
-                            last_fail = now;
-
-                            let wait = if failures >= 5 {
-                                error!(target: "ApiModule", "Module [{}] failed too many times, check module integrity: {:?}", type_name::<M>(), e);
-                                60
-                            } else {
-                                warn!(target: "ApiModule", "[{}] err={:?}", type_name::<M>(), e);
-                                1
-                            };
-                            tokio::time::sleep(Duration::from_secs(wait)).await;
-                        }
-                    }
-                }
-            });
+                    supervise(
+                        "ApiModule",
+                        type_name::<M>(),
+                        policy,
+                        supervised_rule,
+                        || {
+                            let mut module = M::new(
+                                state.clone(),
+                                cmd_rx.clone(),
+                                cmd_ret_tx.clone(),
+                                msg_rx.clone(),
+                                to_ws_tx.clone(),
+                                runner_tx.clone(),
+                            );
+                            async move { module.run().await }
+                        },
+                    )
+                    .await;
+                });
 
-                router.add_module_rule(M::rule(state_clone), msg_tx);
+                let channel = router.module_channel::<M>(msg_tx, capacity);
+                let routing = router.routing_for::<M>();
+                router.add_route(Box::new(SharedRule(rule)), channel, routing);
             };
 
-        self.module_factories.push(Box::new(factory));
+        self.module_factories
+            .push((Dependency::on::<M>(), M::dependencies(), Box::new(factory)));
         self
     }
 
@@ -392,49 +387,167 @@ impl<S: AppState> ClientBuilder<S> {
 
     /// Sets the maximum number of reconnection attempts.
     /// 0 means infinite attempts.
+    ///
+    /// Shorthand for updating `max_attempts` on the current [`ReconnectPolicy`].
     pub fn with_max_allowed_loops(mut self, max_allowed_loops: u32) -> Self {
-        self.max_allowed_loops = max_allowed_loops;
+        self.reconnect_policy.max_attempts = max_allowed_loops;
+        self
+    }
+
+    /// Sets what the runner does once the reconnection attempts are exhausted.
+    ///
+    /// Shorthand for updating `limit_action` on the current [`ReconnectPolicy`].
+    pub fn with_limit_action(mut self, action: LimitAction) -> Self {
+        self.reconnect_policy.limit_action = action;
         self
     }
 
     /// Sets the base delay for reconnection attempts.
+    ///
+    /// Shorthand for updating `initial_delay` on the current [`ReconnectPolicy`].
     pub fn with_reconnect_delay(mut self, reconnect_delay: Duration) -> Self {
-        self.reconnect_delay = reconnect_delay;
+        self.reconnect_policy.initial_delay = reconnect_delay;
+        self
+    }
+
+    /// Replaces the reconnection policy (backoff, jitter, attempt limit and exhaustion callback).
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Sets the restart policy used for modules that return an error or panic.
+    ///
+    /// Applies to every module without a policy of its own, see [`Self::with_module_restart_policy`].
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Overrides the restart policy for a single module (api or lightweight).
+    pub fn with_module_restart_policy<M: 'static>(mut self, policy: RestartPolicy) -> Self {
+        self.module_restart_policies
+            .insert(TypeId::of::<M>(), policy);
+        self
+    }
+
+    /// Sets the channel capacities used for modules (api or lightweight).
+    ///
+    /// Applies to every module without capacities of its own, see
+    /// [`Self::with_module_channel_capacity`].
+    pub fn with_channel_capacity(mut self, capacity: ChannelCapacity) -> Self {
+        self.channel_capacity = capacity;
+        self
+    }
+
+    /// Overrides the channel capacities of a single module (api or lightweight).
+    pub fn with_module_channel_capacity<M: 'static>(mut self, capacity: ChannelCapacity) -> Self {
+        self.module_channel_capacities
+            .insert(TypeId::of::<M>(), capacity);
+        self
+    }
+
+    /// Sets the priority of the rule of a module (api or lightweight) and whether a match
+    /// stops the routing, see [`Routing`].
+    pub fn with_module_routing<M: 'static>(mut self, routing: Routing) -> Self {
+        self.module_routing.insert(TypeId::of::<M>(), routing);
+        self
+    }
+
+    /// Sets how many outgoing messages can wait for the socket writer, 256 by default.
+    ///
+    /// Senders wait while the queue is full, see [`Client::outgoing_queue_len`].
+    pub fn with_outgoing_capacity(mut self, capacity: usize) -> Self {
+        self.outgoing_capacity = capacity;
+        self
+    }
+
+    /// Sets how Socket.IO packets with binary attachments reach middleware, handlers and modules.
+    ///
+    /// Defaults to [`FrameMode::Raw`]; with [`FrameMode::Reassembled`] every `45<n>-` header is
+    /// delivered once, as a `42[...]` frame with its attachments decoded in place.
+    pub fn with_frame_mode(mut self, mode: FrameMode) -> Self {
+        self.frame_mode = mode;
+        self
+    }
+
+    /// Records every sent message and the module sending it in `log`, see [`crate::audit`].
+    ///
+    /// The log is added after the other middleware when the client is built and is queried
+    /// with [`Client::audit_log`].
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.audit = Some(log);
         self
     }
 
     /// Assembles and returns the final `Client` handle and its `ClientRunner`.
+    ///
+    /// Fails if a module is registered without the modules it depends on, see
+    /// [`ApiModule::dependencies`].
     pub async fn build(self) -> CoreResult<(Client<S>, ClientRunner<S>)> {
+        let modules: Vec<_> = self
+            .module_factories
+            .iter()
+            .map(|(module, dependencies, _)| (*module, dependencies.clone()))
+            .collect();
+        let order = start_order(&modules)?;
+
         let (runner_cmd_tx, runner_cmd_rx) = bounded_async(8);
-        let (to_ws_tx, to_ws_rx) = bounded_async(256);
+        let (to_ws_tx, to_ws_rx) = bounded_async(self.outgoing_capacity);
         let signals = Signals::default();
-        let client = Client::new(
+        let mut client = Client::new(
             signals.clone(),
             runner_cmd_tx.clone(),
             self.state.clone(),
             to_ws_tx.clone(),
         );
+        let mut late_modules = LateModules::new(
+            self.restart_policy.clone(),
+            self.module_restart_policies.clone(),
+        );
+        late_modules.channel_capacity = self.channel_capacity;
+        late_modules.module_channel_capacities = self.module_channel_capacities.clone();
+        late_modules.module_routing = self.module_routing.clone();
+        late_modules.registered = modules.iter().map(|(module, _)| module.id).collect();
+        let late_modules = Arc::new(late_modules);
+        client.late_modules = late_modules.clone();
+        client.audit = self.audit.clone();
 
         let mut router = Router::new(self.state.clone());
+        router.late_modules = late_modules;
+        router.stats = client.stats.clone();
         router.lightweight_handlers = self.lightweight_handlers;
         router.middleware_stack = self.middleware_stack;
+        router.restart_policy = self.restart_policy;
+        router.module_restart_policies = self.module_restart_policies;
+        router.channel_capacity = self.channel_capacity;
+        router.module_channel_capacities = self.module_channel_capacities;
+        router.module_routing = self.module_routing;
+        router.frame_mode = self.frame_mode;
+        if let Some(audit) = &self.audit {
+            router.middleware_stack.add_layer(Box::new(audit.clone()));
+        }
+        router.audit = self.audit;
 
         let mut join_set = JoinSet::new();
-        // Execute all the deferred module setup functions.
+        // Execute all the deferred module setup functions, dependencies first.
         let mut connection_callback = self.connection_callback;
-        for factory in self.module_factories {
-            factory(
-                &mut router,
-                &mut join_set,
-                client.module_handles.clone(),
-                to_ws_tx.clone(),
-                runner_cmd_tx.clone(),
-                &mut connection_callback.on_reconnect,
-            );
-        }
-
-        for factory in self.lightweight_factories {
-            factory(&mut router, to_ws_tx.clone(), runner_cmd_tx.clone());
+        let mut factories: Vec<_> = self
+            .module_factories
+            .into_iter()
+            .map(|(_, _, factory)| Some(factory))
+            .collect();
+        for index in order {
+            if let Some(factory) = factories[index].take() {
+                factory(
+                    &mut router,
+                    &mut join_set,
+                    client.module_handles.clone(),
+                    to_ws_tx.clone(),
+                    runner_cmd_tx.clone(),
+                    &mut connection_callback.on_reconnect,
+                );
+            }
         }
 
         // Wait for all the handles to be added to the handles hashmap.
@@ -461,14 +574,69 @@ impl<S: AppState> ClientBuilder<S> {
             runner_command_rx: runner_cmd_rx,
             connection_callback,
             reconnect_attempts: 0,
-            max_allowed_loops: self.max_allowed_loops,
-            reconnect_delay: self.reconnect_delay,
+            has_connected: false,
+            reconnect_policy: self.reconnect_policy,
+            session_modules: BTreeMap::new(),
         };
 
         Ok((client, runner))
     }
 }
 
+/// Indexes of `modules` in the order they must be started, each after its dependencies and
+/// otherwise in registration order.
+fn start_order(modules: &[(Dependency, Vec<Dependency>)]) -> CoreResult<Vec<usize>> {
+    let registered: HashSet<TypeId> = modules.iter().map(|(module, _)| module.id).collect();
+    for (module, dependencies) in modules {
+        if let Some(missing) = dependencies.iter().find(|d| !registered.contains(&d.id)) {
+            return Err(CoreError::MissingDependency {
+                module: module.name.to_string(),
+                dependency: missing.name.to_string(),
+            });
+        }
+    }
+
+    let mut started = HashSet::new();
+    let mut order = Vec::with_capacity(modules.len());
+    while order.len() < modules.len() {
+        let next = (0..modules.len()).find(|index| {
+            let (_, dependencies) = &modules[*index];
+            !order.contains(index) && dependencies.iter().all(|d| started.contains(&d.id))
+        });
+        match next {
+            Some(index) => {
+                started.insert(modules[index].0.id);
+                order.push(index);
+            }
+            None => return Err(CoreError::DependencyCycle(find_cycle(modules, &started))),
+        }
+    }
+    Ok(order)
+}
+
+/// Names of the modules of a dependency cycle among the modules not started yet.
+fn find_cycle(modules: &[(Dependency, Vec<Dependency>)], started: &HashSet<TypeId>) -> Vec<String> {
+    let waiting = |id: TypeId| {
+        modules
+            .iter()
+            .find(|(module, _)| module.id == id && !started.contains(&id))
+    };
+    let mut path: Vec<Dependency> = Vec::new();
+    let mut current = modules
+        .iter()
+        .find(|(module, _)| !started.contains(&module.id));
+    while let Some((module, dependencies)) = current {
+        if let Some(start) = path.iter().position(|seen| seen.id == module.id) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|d| d.name.to_string()).collect();
+            cycle.push(module.name.to_string());
+            return cycle;
+        }
+        path.push(*module);
+        current = dependencies.iter().find_map(|d| waiting(d.id));
+    }
+    path.iter().map(|d| d.name.to_string()).collect()
+}
+
 // Add this test at the bottom of the file
 #[cfg(test)]
 mod tests {
@@ -481,4 +649,41 @@ mod tests {
         // This will fail to compile if ClientBuilder is not Send + Sync
         assert_send_sync::<ClientBuilder<()>>();
     }
+
+    struct Assets;
+    struct Time;
+    struct Trades;
+
+    #[test]
+    fn test_start_order_puts_dependencies_first() {
+        let modules = vec![
+            (
+                Dependency::on::<Trades>(),
+                vec![Dependency::on::<Assets>(), Dependency::on::<Time>()],
+            ),
+            (Dependency::on::<Time>(), vec![]),
+            (Dependency::on::<Assets>(), vec![Dependency::on::<Time>()]),
+        ];
+        assert_eq!(start_order(&modules).unwrap(), [1, 2, 0]);
+
+        let err = start_order(&modules[..2]).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::MissingDependency { ref dependency, .. } if dependency.ends_with("Assets")
+        ));
+    }
+
+    #[test]
+    fn test_start_order_detects_cycles() {
+        let modules = vec![
+            (Dependency::on::<Time>(), vec![]),
+            (Dependency::on::<Assets>(), vec![Dependency::on::<Trades>()]),
+            (Dependency::on::<Trades>(), vec![Dependency::on::<Assets>()]),
+        ];
+        let CoreError::DependencyCycle(cycle) = start_order(&modules).unwrap_err() else {
+            panic!("expected a dependency cycle");
+        };
+        assert_eq!(cycle.len(), 3);
+        assert!(cycle[0].ends_with("Assets") && cycle[2].ends_with("Assets"));
+    }
 }
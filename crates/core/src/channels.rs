@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use kanal::{AsyncSender, SendError};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::statistics::ChannelCounters;
+
+/// What the router does with a message for a module whose channel is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait until the module makes room, slowing down the routing of every other message.
+    #[default]
+    Wait,
+    /// Drop the message, the module misses it but the other modules are not delayed.
+    Drop,
+}
+
+/// Sizes of the channels between the client and a module.
+///
+/// Larger channels absorb bursts of stream traffic at the cost of memory, smaller ones keep
+/// the data a slow module sees fresher. See [`ClientStats::channels`](crate::statistics::ClientStats::channels)
+/// for how full they get.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCapacity {
+    /// Incoming messages routed to the module.
+    pub messages: usize,
+    /// Commands sent by the module handle, and their responses. Unused by lightweight modules.
+    pub commands: usize,
+    /// What happens to messages routed to the module while its channel is full.
+    pub overflow: Overflow,
+}
+
+impl Default for ChannelCapacity {
+    fn default() -> Self {
+        Self {
+            messages: 256,
+            commands: 32,
+            overflow: Overflow::Wait,
+        }
+    }
+}
+
+impl ChannelCapacity {
+    pub fn with_messages(mut self, messages: usize) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    pub fn with_commands(mut self, commands: usize) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// Message channel of a module as seen by the router, with its fill level counters.
+pub(crate) struct ModuleChannel {
+    sender: AsyncSender<Arc<Message>>,
+    overflow: Overflow,
+    counters: Arc<ChannelCounters>,
+}
+
+impl ModuleChannel {
+    pub(crate) fn new(
+        sender: AsyncSender<Arc<Message>>,
+        overflow: Overflow,
+        counters: Arc<ChannelCounters>,
+    ) -> Self {
+        Self {
+            sender,
+            overflow,
+            counters,
+        }
+    }
+
+    /// Sends `message` to the module, fails only if the module's channel is closed.
+    pub(crate) async fn deliver(&self, message: Arc<Message>) -> Result<(), SendError> {
+        match self.sender.try_send(message.clone())? {
+            true => {}
+            false => {
+                self.counters.record_overflow();
+                match self.overflow {
+                    Overflow::Wait => self.sender.send(message).await?,
+                    Overflow::Drop => {
+                        self.counters.record_dropped();
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        self.counters.observe_len(self.sender.len());
+        Ok(())
+    }
+}
+
+impl From<AsyncSender<Arc<Message>>> for ModuleChannel {
+    fn from(sender: AsyncSender<Arc<Message>>) -> Self {
+        Self::new(sender, Overflow::Wait, Arc::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statistics::ClientStatsTracker;
+    use kanal::bounded_async;
+
+    #[tokio::test]
+    async fn test_full_channel_overflow() {
+        let tracker = ClientStatsTracker::new();
+        let (sender, receiver) = bounded_async(2);
+        let channel = ModuleChannel::new(
+            sender,
+            Overflow::Drop,
+            tracker.channel("crate::modules::TicksModule", 2),
+        );
+        let message = Arc::new(Message::text("42[\"updateStream\"]"));
+        for _ in 0..3 {
+            channel.deliver(message.clone()).await.unwrap();
+        }
+        assert_eq!(receiver.len(), 2);
+        let stats = tracker.snapshot().channels["TicksModule"].clone();
+        assert_eq!(stats.capacity, 2);
+        assert_eq!(stats.high_water_mark, 2);
+        assert_eq!(stats.overflows, 1);
+        assert_eq!(stats.dropped, 1);
+
+        // Waiting delivers once the module makes room.
+        let (sender, receiver) = bounded_async(1);
+        let channel = ModuleChannel::new(sender, Overflow::Wait, tracker.channel("Slow", 1));
+        channel.deliver(message.clone()).await.unwrap();
+        let reader = tokio::spawn(async move {
+            receiver.recv().await.unwrap();
+            receiver.recv().await.unwrap();
+        });
+        channel.deliver(message).await.unwrap();
+        reader.await.unwrap();
+        let stats = tracker.snapshot().channels["Slow"].clone();
+        assert_eq!((stats.overflows, stats.dropped), (1, 0));
+
+        tracker.reset();
+        assert_eq!(tracker.snapshot().channels["Slow"].overflows, 0);
+    }
+}
@@ -1,17 +1,25 @@
-use crate::callback::ConnectionCallback;
+use crate::audit::{module_source, AuditLog, AuditRecord};
+use crate::callback::{ConnectionCallback, ReconnectCallbackStack};
+use crate::channels::{ChannelCapacity, ModuleChannel};
 use crate::connector::Connector;
-use crate::error::CoreResult;
-use crate::middleware::{MiddlewareContext, MiddlewareStack};
+use crate::error::{CoreError, CoreResult};
+use crate::middleware::{Intercepted, MiddlewareContext, MiddlewareStack, WebSocketMiddleware};
+use crate::reconnect::{LimitAction, LoopDiagnostics, ReconnectEvent, ReconnectPolicy};
+use crate::routing::{insert_by_priority, Routing};
 use crate::signals::Signals;
+use crate::socketio::{AttachmentAssembler, FrameMode};
+use crate::statistics::{ClientStats, ClientStatsTracker};
+use crate::status::ConnectionStatus;
+use crate::supervisor::{supervise, RestartPolicy, SharedRule};
 use crate::traits::{ApiModule, AppState, ReconnectCallback, Rule, RunnerCommand};
-use futures_util::{stream::StreamExt, SinkExt};
-use kanal::{AsyncReceiver, AsyncSender};
-use rand::RngExt;
-use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use futures_util::{stream::StreamExt, SinkExt, Stream};
+use kanal::{bounded_async, AsyncReceiver, AsyncSender};
+use std::any::{type_name, Any, TypeId};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
 use tokio::task::JoinSet;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, error, info, warn};
@@ -33,32 +41,141 @@ pub type LightweightHandler<S> = Box<
         + Sync,
 >;
 
-type RuleTp = (Box<dyn Rule + Send + Sync>, AsyncSender<Arc<Message>>);
+type RuleTp = (Box<dyn Rule + Send + Sync>, ModuleChannel, Routing);
+
+/// Api modules and middleware attached to a running client with [`Client::register_module`]
+/// and [`Client::add_middleware`].
+///
+/// Shared by the client and its router, the runner aborts the modules when it shuts down.
+pub(crate) struct LateModules<S: AppState> {
+    rules: RwLock<Vec<RuleTp>>,
+    pub(crate) middleware: RwLock<MiddlewareStack<S>>,
+    tasks: std::sync::Mutex<JoinSet<()>>,
+    on_reconnect: RwLock<ReconnectCallbackStack<S>>,
+    restart_policy: RestartPolicy,
+    module_restart_policies: HashMap<TypeId, RestartPolicy>,
+    pub(crate) channel_capacity: ChannelCapacity,
+    pub(crate) module_channel_capacities: HashMap<TypeId, ChannelCapacity>,
+    pub(crate) module_routing: HashMap<TypeId, Routing>,
+    /// Modules registered on the builder.
+    pub(crate) registered: HashSet<TypeId>,
+}
+
+impl<S: AppState> LateModules<S> {
+    pub(crate) fn new(
+        restart_policy: RestartPolicy,
+        module_restart_policies: HashMap<TypeId, RestartPolicy>,
+    ) -> Self {
+        Self {
+            rules: RwLock::new(Vec::new()),
+            middleware: RwLock::new(MiddlewareStack::new()),
+            tasks: std::sync::Mutex::new(JoinSet::new()),
+            on_reconnect: RwLock::new(ReconnectCallbackStack::default()),
+            restart_policy,
+            module_restart_policies,
+            channel_capacity: ChannelCapacity::default(),
+            module_channel_capacities: HashMap::new(),
+            module_routing: HashMap::new(),
+            registered: HashSet::new(),
+        }
+    }
+
+    fn abort_all(&self) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.abort_all();
+        }
+    }
+}
+
+impl<S: AppState> std::fmt::Debug for LateModules<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LateModules").finish_non_exhaustive()
+    }
+}
 
 // --- Internal Router ---
 pub struct Router<S: AppState> {
     pub(crate) state: Arc<S>,
-    pub(crate) module_rules: Vec<RuleTp>,
+    /// Rules of the api and lightweight modules, by descending priority.
+    pub(crate) rules: Vec<RuleTp>,
     pub(crate) module_set: JoinSet<()>,
-    pub(crate) lightweight_rules: Vec<RuleTp>,
     pub(crate) lightweight_handlers: Vec<LightweightHandler<S>>,
     pub(crate) lightweight_set: JoinSet<()>,
     pub(crate) middleware_stack: MiddlewareStack<S>,
+    pub(crate) restart_policy: RestartPolicy,
+    pub(crate) module_restart_policies: HashMap<TypeId, RestartPolicy>,
+    pub(crate) channel_capacity: ChannelCapacity,
+    pub(crate) module_channel_capacities: HashMap<TypeId, ChannelCapacity>,
+    pub(crate) module_routing: HashMap<TypeId, Routing>,
+    pub(crate) frame_mode: FrameMode,
+    pub(crate) late_modules: Arc<LateModules<S>>,
+    pub(crate) stats: Arc<ClientStatsTracker>,
+    pub(crate) audit: Option<AuditLog>,
 }
 
 impl<S: AppState> Router<S> {
     pub fn new(state: Arc<S>) -> Self {
         Self {
             state,
-            module_rules: Vec::new(),
+            rules: Vec::new(),
             module_set: JoinSet::new(),
-            lightweight_rules: Vec::new(),
             lightweight_handlers: Vec::new(),
             lightweight_set: JoinSet::new(),
             middleware_stack: MiddlewareStack::new(),
+            restart_policy: RestartPolicy::default(),
+            module_restart_policies: HashMap::new(),
+            channel_capacity: ChannelCapacity::default(),
+            module_channel_capacities: HashMap::new(),
+            module_routing: HashMap::new(),
+            frame_mode: FrameMode::default(),
+            late_modules: Arc::new(LateModules::new(RestartPolicy::default(), HashMap::new())),
+            stats: Arc::new(ClientStatsTracker::new()),
+            audit: None,
+        }
+    }
+
+    /// Sender for the messages of module `M`, tagged with its name when auditing.
+    pub(crate) fn module_sender<M>(&self, sender: AsyncSender<Message>) -> AsyncSender<Message> {
+        match &self.audit {
+            Some(audit) => audit.tagged_sender(module_source::<M>(), sender),
+            None => sender,
         }
     }
 
+    /// Restart policy for module `M`, falling back to the client wide default.
+    pub(crate) fn restart_policy_for<M: 'static>(&self) -> RestartPolicy {
+        self.module_restart_policies
+            .get(&TypeId::of::<M>())
+            .unwrap_or(&self.restart_policy)
+            .clone()
+    }
+
+    /// Channel capacities of module `M`, falling back to the client wide default.
+    pub(crate) fn channel_capacity_for<M: 'static>(&self) -> ChannelCapacity {
+        self.module_channel_capacities
+            .get(&TypeId::of::<M>())
+            .copied()
+            .unwrap_or(self.channel_capacity)
+    }
+
+    /// Routing of the rule of module `M`, the default one unless overridden.
+    pub(crate) fn routing_for<M: 'static>(&self) -> Routing {
+        self.module_routing
+            .get(&TypeId::of::<M>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Message channel of module `M`, its fill level is reported in the client stats.
+    pub(crate) fn module_channel<M>(
+        &self,
+        sender: AsyncSender<Arc<Message>>,
+        capacity: ChannelCapacity,
+    ) -> ModuleChannel {
+        let counters = self.stats.channel(type_name::<M>(), capacity.messages);
+        ModuleChannel::new(sender, capacity.overflow, counters)
+    }
+
     pub fn spawn_module<F: Future<Output = ()> + Send + 'static>(&mut self, task: F) {
         self.module_set.spawn(task);
     }
@@ -68,7 +185,7 @@ impl<S: AppState> Router<S> {
         rule: Box<dyn Rule + Send + Sync>,
         sender: AsyncSender<Arc<Message>>,
     ) {
-        self.module_rules.push((rule, sender));
+        self.add_route(rule, sender.into(), Routing::default());
     }
 
     pub fn add_lightweight_rule(
@@ -76,7 +193,21 @@ impl<S: AppState> Router<S> {
         rule: Box<dyn Rule + Send + Sync>,
         sender: AsyncSender<Arc<Message>>,
     ) {
-        self.lightweight_rules.push((rule, sender));
+        self.add_route(rule, sender.into(), Routing::default());
+    }
+
+    pub(crate) fn add_route(
+        &mut self,
+        rule: Box<dyn Rule + Send + Sync>,
+        channel: ModuleChannel,
+        routing: Routing,
+    ) {
+        insert_by_priority(
+            &mut self.rules,
+            (rule, channel, routing),
+            routing.priority,
+            |(_, _, routing)| routing.priority,
+        );
     }
 
     pub fn add_lightweight_handler(&mut self, handler: LightweightHandler<S>) {
@@ -92,8 +223,9 @@ impl<S: AppState> Router<S> {
     /// This method implements the core message routing logic with middleware integration:
     /// 1. **Middleware on_receive**: Called first for all incoming messages
     /// 2. **Lightweight handlers**: Processed for quick operations
-    /// 3. **Lightweight modules**: Routed based on routing rules
-    /// 4. **API modules**: Routed to matching modules
+    /// 3. **Modules**: Lightweight and API modules whose rule matches, by descending
+    ///    [`Routing`] priority, until an exclusive rule matches
+    /// 4. **Late modules**: Modules added with [`Client::register_module`], same as above
     ///
     /// # Middleware Integration
     /// The `on_receive` middleware hook is called at the beginning of message processing,
@@ -103,9 +235,13 @@ impl<S: AppState> Router<S> {
     /// # Arguments
     /// - `message`: The incoming WebSocket message wrapped in Arc for sharing
     /// - `sender`: Channel for sending outgoing messages
-    async fn route(&self, message: Arc<Message>, sender: &AsyncSender<Message>) -> CoreResult<()> {
+    ///
+    /// The runner calls it with every frame left by the interception hooks. It is public so
+    /// the `routing` benchmark can measure it.
+    pub async fn route(&self, message: Arc<Message>, sender: &AsyncSender<Message>) -> CoreResult<()> {
         // Route to all lightweight handlers first
         debug!(target: "Router", "Routing message: {message:?}");
+        self.stats.record_received(&message);
 
         // Create middleware context
         let middleware_context = MiddlewareContext::new(Arc::clone(&self.state), sender.clone());
@@ -115,30 +251,82 @@ impl<S: AppState> Router<S> {
         self.middleware_stack
             .on_receive(&message, &middleware_context)
             .await;
+        self.late_modules
+            .middleware
+            .read()
+            .await
+            .on_receive(&message, &middleware_context)
+            .await;
 
         for handler in &self.lightweight_handlers {
             if let Err(err) = handler(Arc::clone(&message), Arc::clone(&self.state), sender).await {
+                self.stats.record_handler_error();
                 error!(target: "Router",
                      "Lightweight handler error: {err:#?}"
                 );
             }
         }
-        for (rule, sender) in &self.lightweight_rules {
-            // If the rule matches, send the message to the lightweight handler
-            if rule.call(&message) && sender.send(message.clone()).await.is_err() {
-                error!(target: "Router", "A lightweight module has shut down and its channel is closed.");
-            }
+        // Route to all matching modules, an exclusive match ends the routing
+        if self.route_to(&self.rules, &message).await {
+            return Ok(());
         }
+        self.route_to(&self.late_modules.rules.read().await, &message)
+            .await;
+        Ok(())
+    }
 
-        // Route to all matching API modules
-        for (rule, sender) in &self.module_rules {
-            if rule.call(&message) {
-                if sender.send(message.clone()).await.is_err() {
-                    error!(target: "Router", "A module has shut down and its channel is closed.");
-                }
+    /// Runs the `intercept_send` hooks of the builder then the late middleware.
+    async fn intercept_send(
+        &self,
+        message: Message,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        let intercepted = self
+            .middleware_stack
+            .intercept_send(Intercepted::Pass(message), context)
+            .await;
+        self.late_modules
+            .middleware
+            .read()
+            .await
+            .intercept_send(intercepted, context)
+            .await
+    }
+
+    /// Runs the `intercept_receive` hooks of the builder then the late middleware.
+    async fn intercept_receive(
+        &self,
+        message: Message,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        let intercepted = self
+            .middleware_stack
+            .intercept_receive(Intercepted::Pass(message), context)
+            .await;
+        self.late_modules
+            .middleware
+            .read()
+            .await
+            .intercept_receive(intercepted, context)
+            .await
+    }
+
+    /// Sends `message` to the modules of `rules` matching it, returns `true` once an
+    /// exclusive rule matched.
+    async fn route_to(&self, rules: &[RuleTp], message: &Arc<Message>) -> bool {
+        for (rule, channel, routing) in rules {
+            if !rule.call(message) {
+                continue;
+            }
+            if channel.deliver(message.clone()).await.is_err() {
+                self.stats.record_routing_error();
+                error!(target: "Router", "A module has shut down and its channel is closed.");
+            }
+            if routing.exclusive {
+                return true;
             }
         }
-        Ok(())
+        false
     }
 }
 
@@ -152,6 +340,9 @@ pub struct Client<S: AppState> {
     pub to_ws_sender: AsyncSender<Message>,
 
     runner_command_tx: AsyncSender<RunnerCommand>,
+    pub(crate) late_modules: Arc<LateModules<S>>,
+    pub(crate) stats: Arc<ClientStatsTracker>,
+    pub(crate) audit: Option<AuditLog>,
 }
 
 impl<S: AppState> Clone for Client<S> {
@@ -162,6 +353,9 @@ impl<S: AppState> Clone for Client<S> {
             module_handles: Arc::clone(&self.module_handles),
             runner_command_tx: self.runner_command_tx.clone(),
             to_ws_sender: self.to_ws_sender.clone(),
+            late_modules: Arc::clone(&self.late_modules),
+            stats: Arc::clone(&self.stats),
+            audit: self.audit.clone(),
         }
     }
 }
@@ -180,6 +374,9 @@ impl<S: AppState> Client<S> {
             module_handles: Arc::new(RwLock::new(HashMap::new())),
             runner_command_tx,
             to_ws_sender: sender,
+            late_modules: Arc::new(LateModules::new(RestartPolicy::default(), HashMap::new())),
+            stats: Arc::new(ClientStatsTracker::new()),
+            audit: None,
         }
     }
 
@@ -195,6 +392,26 @@ impl<S: AppState> Client<S> {
         self.signal.is_connected()
     }
 
+    /// Current lifecycle status of the connection.
+    pub fn status(&self) -> ConnectionStatus {
+        self.signal.status()
+    }
+
+    /// Stream yielding the current status followed by every status change.
+    pub fn status_stream(&self) -> impl Stream<Item = ConnectionStatus> + Send + 'static {
+        self.signal.status_stream()
+    }
+
+    /// Waits until the connection reaches `status`.
+    pub async fn wait_for_status(&self, status: ConnectionStatus) {
+        self.signal.wait_for_status(|s| s == status).await;
+    }
+
+    /// Subscribes to reconnection events (scheduled attempts, reconnects, exhaustion).
+    pub fn reconnect_events(&self) -> broadcast::Receiver<ReconnectEvent> {
+        self.signal.subscribe_reconnect_events()
+    }
+
     /// Retrieves a clonable, typed handle to an already-registered module.
     pub async fn get_handle<M: ApiModule<S>>(&self) -> Option<M::Handle> {
         let handles = self.module_handles.read().await;
@@ -204,6 +421,154 @@ impl<S: AppState> Client<S> {
             .cloned()
     }
 
+    /// Attaches the api module `M` and its rule to the running client.
+    ///
+    /// The module starts right away, receives the messages matching its rule from then on and is
+    /// supervised with the client's restart policy, so plugins can add protocol features without
+    /// rebuilding the client. Its handle is then available through [`Client::get_handle`].
+    ///
+    /// Fails if a module it depends on was not registered before, see [`ApiModule::dependencies`].
+    pub async fn register_module<M: ApiModule<S>>(&self) -> CoreResult<M::Handle> {
+        let mut handles = self.module_handles.write().await;
+        if handles.contains_key(&TypeId::of::<M>()) {
+            return Err(CoreError::ModuleAlreadyRegistered(
+                type_name::<M>().to_string(),
+            ));
+        }
+        if let Some(missing) = M::dependencies().into_iter().find(|dependency| {
+            !handles.contains_key(&dependency.id)
+                && !self.late_modules.registered.contains(&dependency.id)
+        }) {
+            return Err(CoreError::MissingDependency {
+                module: type_name::<M>().to_string(),
+                dependency: missing.name().to_string(),
+            });
+        }
+
+        let modules = &self.late_modules;
+        let capacity = modules
+            .module_channel_capacities
+            .get(&TypeId::of::<M>())
+            .copied()
+            .unwrap_or(modules.channel_capacity);
+        let (cmd_tx, cmd_rx) = bounded_async(capacity.commands);
+        let (cmd_ret_tx, cmd_ret_rx) = bounded_async(capacity.commands);
+        let (msg_tx, msg_rx) = bounded_async(capacity.messages);
+        let state = self.state.clone();
+        let to_ws_tx = match &self.audit {
+            Some(audit) => audit.tagged_sender(module_source::<M>(), self.to_ws_sender.clone()),
+            None => self.to_ws_sender.clone(),
+        };
+        let runner_tx = self.runner_command_tx.clone();
+        let handle = M::create_handle(cmd_tx, cmd_ret_rx);
+
+        if let Some(callback) = M::callback(
+            state.clone(),
+            cmd_rx.clone(),
+            cmd_ret_tx.clone(),
+            msg_rx.clone(),
+            to_ws_tx.clone(),
+        )? {
+            self.late_modules
+                .on_reconnect
+                .write()
+                .await
+                .add_layer(callback);
+        }
+
+        let policy = modules
+            .module_restart_policies
+            .get(&TypeId::of::<M>())
+            .unwrap_or(&modules.restart_policy)
+            .clone();
+        let rule = self
+            .stats
+            .counting_rule(type_name::<M>(), Arc::from(M::rule(state.clone())));
+        let supervised_rule = rule.clone();
+        let counters = self.stats.channel(type_name::<M>(), capacity.messages);
+        let channel = ModuleChannel::new(msg_tx, capacity.overflow, counters);
+        let routing = modules
+            .module_routing
+            .get(&TypeId::of::<M>())
+            .copied()
+            .unwrap_or_default();
+        modules
+            .tasks
+            .lock()
+            .map_err(|e| CoreError::Poison(e.to_string()))?
+            .spawn(async move {
+                supervise(
+                    "ApiModule",
+                    type_name::<M>(),
+                    policy,
+                    supervised_rule,
+                    || {
+                        let mut module = M::new(
+                            state.clone(),
+                            cmd_rx.clone(),
+                            cmd_ret_tx.clone(),
+                            msg_rx.clone(),
+                            to_ws_tx.clone(),
+                            runner_tx.clone(),
+                        );
+                        async move { module.run().await }
+                    },
+                )
+                .await;
+            });
+        insert_by_priority(
+            &mut *modules.rules.write().await,
+            (Box::new(SharedRule(rule)), channel, routing),
+            routing.priority,
+            |(_, _, routing)| routing.priority,
+        );
+
+        handles.insert(TypeId::of::<M>(), Box::new(handle.clone()));
+        info!(target: "Client", "Registered module {}", type_name::<M>());
+        Ok(handle)
+    }
+
+    /// Snapshot of the client's traffic, connection and error counters.
+    pub fn stats(&self) -> ClientStats {
+        self.stats.snapshot()
+    }
+
+    /// Time since the last message was received, `None` before the first one.
+    pub fn last_message_age(&self) -> Option<Duration> {
+        self.stats.last_message_age()
+    }
+
+    /// Messages queued for the socket writer and not sent yet.
+    pub fn outgoing_queue_len(&self) -> usize {
+        self.to_ws_sender.len()
+    }
+
+    /// Messages sent after the one numbered `since` (`0` for all of them), with the module
+    /// sending each one, empty unless the client was built with
+    /// [`ClientBuilder::with_audit_log`](crate::builder::ClientBuilder::with_audit_log).
+    pub fn audit_log(&self, since: u64) -> Vec<AuditRecord> {
+        self.audit
+            .as_ref()
+            .map(|audit| audit.since(since))
+            .unwrap_or_default()
+    }
+
+    /// Sets every counter returned by [`Client::stats`] back to zero.
+    pub fn reset_stats(&self) {
+        self.stats.reset()
+    }
+
+    /// Adds a middleware layer to the running client.
+    ///
+    /// It runs after the middleware given to the builder, from the next message on.
+    pub async fn add_middleware(&self, middleware: Box<dyn WebSocketMiddleware<S>>) {
+        self.late_modules
+            .middleware
+            .write()
+            .await
+            .add_layer(middleware);
+    }
+
     /// Commands the runner to disconnect, clear state, and perform a "hard" reconnect.
     pub async fn disconnect(&self) -> CoreResult<()> {
         Ok(self
@@ -288,9 +653,6 @@ impl<S: AppState> Client<S> {
 }
 
 const CONNECTION_STABLE_RESET_SECS: u64 = 10;
-const BACKOFF_BASE_SECS: u64 = 5;
-const BACKOFF_MAX_SECS: u64 = 3600;
-const BACKOFF_EXPONENT_CAP: u32 = 10;
 
 /// Implementation of the `ClientRunner` for managing WebSocket client connections and session lifecycle.
 pub struct ClientRunner<S: AppState> {
@@ -308,12 +670,96 @@ pub struct ClientRunner<S: AppState> {
     pub(crate) runner_command_rx: AsyncReceiver<RunnerCommand>,
 
     pub(crate) reconnect_attempts: u32,
+    /// Whether a connection already succeeded, later ones are counted as reconnects.
+    pub(crate) has_connected: bool,
 
-    pub(crate) max_allowed_loops: u32,
-    pub(crate) reconnect_delay: std::time::Duration,
+    pub(crate) reconnect_policy: ReconnectPolicy,
+    /// Messages routed to each module when the current session started.
+    pub(crate) session_modules: BTreeMap<String, u64>,
 }
 
 impl<S: AppState> ClientRunner<S> {
+    /// Counts a failed attempt and waits before the next one.
+    ///
+    /// Returns `false` once the runner has to stop.
+    async fn handle_failed_attempt(&mut self, error: String) -> bool {
+        self.reconnect_attempts += 1;
+
+        if self.reconnect_policy.is_exhausted(self.reconnect_attempts) {
+            let diagnostics = self.loop_diagnostics(&error);
+            error!(target: "Runner", "Maximum reconnection attempts ({}) reached: {diagnostics}", self.reconnect_policy.max_attempts);
+            self.signal
+                .emit_reconnect_event(ReconnectEvent::LimitReached(diagnostics));
+            match self.reconnect_policy.limit_action {
+                LimitAction::Abort => {
+                    self.reconnect_policy
+                        .notify_exhausted(self.reconnect_attempts);
+                    self.signal.emit_reconnect_event(ReconnectEvent::Exhausted {
+                        attempts: self.reconnect_attempts,
+                    });
+                    self.signal.set_disconnected();
+                    self.shutdown_requested = true;
+                    return false;
+                }
+                LimitAction::Backoff => {
+                    self.reconnect_attempts = 0;
+                    self.signal.set_status(ConnectionStatus::Reconnecting);
+                    tokio::time::sleep(self.reconnect_policy.max_delay).await;
+                }
+                LimitAction::Reconnect => {
+                    self.reconnect_attempts = 0;
+                    self.signal.set_status(ConnectionStatus::Reconnecting);
+                    tokio::time::sleep(self.reconnect_policy.initial_delay).await;
+                    self.is_hard_disconnect = true;
+                }
+            }
+            return true;
+        }
+
+        let policy = &self.reconnect_policy;
+        let delay = policy.delay_for(self.reconnect_attempts);
+        let max_attempts = (policy.max_attempts > 0).then_some(policy.max_attempts);
+
+        warn!(target: "Runner", "Connection failed (attempt {}/{}): {error}. Retrying in {:?}...",
+            self.reconnect_attempts,
+            max_attempts.map_or_else(|| "∞".to_string(), |m| m.to_string()),
+            delay);
+        self.signal.emit_reconnect_event(ReconnectEvent::Scheduled {
+            attempt: self.reconnect_attempts,
+            max_attempts,
+            delay,
+            error,
+        });
+        self.signal.set_status(ConnectionStatus::Reconnecting);
+        tokio::time::sleep(delay).await;
+        self.is_hard_disconnect = false;
+        true
+    }
+
+    fn loop_diagnostics(&self, last_error: &str) -> LoopDiagnostics {
+        let mut modules: Vec<(String, u64)> = self
+            .router
+            .stats
+            .snapshot()
+            .modules
+            .into_iter()
+            .map(|(name, count)| {
+                let before = self.session_modules.get(&name).copied().unwrap_or(0);
+                (name, count.saturating_sub(before))
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        modules.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        LoopDiagnostics {
+            attempts: self.reconnect_attempts,
+            cycles_last_minute: self.router.stats.cycles_last_minute(),
+            last_error: Some(last_error.to_string()),
+            modules,
+            queued_outgoing: self.to_ws_receiver.len(),
+            action: self.reconnect_policy.limit_action,
+        }
+    }
+
     /// Tear down the current session: run middleware, disconnect connector, abort tasks.
     async fn teardown_session(
         &mut self,
@@ -323,6 +769,13 @@ impl<S: AppState> ClientRunner<S> {
     ) {
         let ctx = MiddlewareContext::new(Arc::clone(&self.state), self.to_ws_sender.clone());
         self.router.middleware_stack.on_disconnect(&ctx).await;
+        self.router
+            .late_modules
+            .middleware
+            .read()
+            .await
+            .on_disconnect(&ctx)
+            .await;
 
         if let Err(e) = self.connector.disconnect().await {
             warn!(target: "Runner", "Connector disconnect failed: {e}");
@@ -365,6 +818,11 @@ impl<S: AppState> ClientRunner<S> {
                 self.shutdown_requested = true;
                 false
             }
+            RunnerCommand::SetStatus(status) => {
+                debug!(target: "Runner", "Status reported: {status}");
+                self.signal.set_status(status);
+                true
+            }
             _ => true,
         }
     }
@@ -397,11 +855,25 @@ impl<S: AppState> ClientRunner<S> {
             let middleware_context =
                 MiddlewareContext::new(Arc::clone(&self.state), self.to_ws_sender.clone());
             debug!(target: "Runner", "Starting connection cycle...");
+            let status = if self.is_hard_disconnect && self.reconnect_attempts == 0 {
+                ConnectionStatus::Connecting
+            } else {
+                ConnectionStatus::Reconnecting
+            };
+            self.signal.set_status(status);
+            self.router.stats.record_cycle();
 
             self.router
                 .middleware_stack
                 .record_connection_attempt(&middleware_context)
                 .await;
+            self.router
+                .late_modules
+                .middleware
+                .read()
+                .await
+                .record_connection_attempt(&middleware_context)
+                .await;
 
             let stream_result = if self.is_hard_disconnect {
                 self.connector.connect(self.state.clone()).await
@@ -412,53 +884,43 @@ impl<S: AppState> ClientRunner<S> {
             let ws_stream = match stream_result {
                 Ok(stream) => stream,
                 Err(e) => {
-                    self.reconnect_attempts += 1;
-
-                    if self.max_allowed_loops > 0
-                        && self.reconnect_attempts >= self.max_allowed_loops
-                    {
-                        error!(target: "Runner", "Maximum reconnection attempts ({}) reached. Shutting down.", self.max_allowed_loops);
-                        self.shutdown_requested = true;
-                        break;
+                    self.router.stats.record_connection_failure();
+                    if self.handle_failed_attempt(e.to_string()).await {
+                        continue;
                     }
-
-                    let base_delay = if self.reconnect_delay.as_secs() > 0 {
-                        self.reconnect_delay.as_secs()
-                    } else {
-                        BACKOFF_BASE_SECS
-                    };
-
-                    let exponent = self.reconnect_attempts.min(BACKOFF_EXPONENT_CAP);
-                    let multiplier = 2u64.saturating_pow(exponent);
-                    let delay_secs = base_delay.saturating_mul(multiplier).min(BACKOFF_MAX_SECS);
-                    let jitter = rand::rng().random_range(0.8..1.2);
-                    let delay = std::time::Duration::from_secs_f64(delay_secs as f64 * jitter);
-
-                    warn!(target: "Runner", "Connection failed (attempt {}/{}): {e}. Retrying in {:?}...",
-                        self.reconnect_attempts,
-                        if self.max_allowed_loops > 0 { self.max_allowed_loops.to_string() } else { "∞".to_string() },
-                        delay);
-                    tokio::time::sleep(delay).await;
-                    self.is_hard_disconnect = false;
-                    continue;
+                    break;
                 }
             };
 
             debug!(target: "Runner", "Connection successful.");
+            self.router.stats.record_connection(self.has_connected);
+            self.has_connected = true;
             self.signal.set_connected();
+            self.signal.emit_reconnect_event(ReconnectEvent::Connected {
+                attempts: self.reconnect_attempts,
+            });
 
             let connection_start = std::time::Instant::now();
             let mut attempts_reset = false;
+            self.session_modules = self.router.stats.snapshot().modules;
             self.router
                 .middleware_stack
                 .on_connect(&middleware_context)
                 .await;
+            self.router
+                .late_modules
+                .middleware
+                .read()
+                .await
+                .on_connect(&middleware_context)
+                .await;
 
             debug!(target: "Runner", "Executing on_connect callback.");
             if let Err(err) =
                 (self.connection_callback.on_connect)(self.state.clone(), &self.to_ws_sender).await
             {
                 warn!(target: "Runner", "on_connect callback failed: {err:#?}");
+                self.signal.set_status(ConnectionStatus::Degraded);
             }
 
             debug!(target: "Runner", "Executing on_reconnect callback.");
@@ -470,37 +932,90 @@ impl<S: AppState> ClientRunner<S> {
             {
                 warn!(target: "Runner", "on_reconnect callback failed: {err:#?}");
             }
+            if let Err(err) = self
+                .router
+                .late_modules
+                .on_reconnect
+                .read()
+                .await
+                .call(self.state.clone(), &self.to_ws_sender)
+                .await
+            {
+                warn!(target: "Runner", "on_reconnect callback of a registered module failed: {err:#?}");
+            }
             self.is_hard_disconnect = false;
 
             let (mut ws_writer, mut ws_reader) = ws_stream.split();
 
-            let writer_task = tokio::spawn({
-                let to_ws_rx = self.to_ws_receiver.clone();
+            let reader_task = tokio::spawn({
+                let to_ws_sender = self.to_ws_sender.clone();
                 let router = Arc::clone(&self.router);
                 let state = Arc::clone(&self.state);
-                let to_ws_sender = self.to_ws_sender.clone();
                 async move {
-                    let middleware_context = MiddlewareContext::new(state, to_ws_sender);
-                    while let Ok(msg) = to_ws_rx.recv().await {
-                        router
-                            .middleware_stack
-                            .on_send(&msg, &middleware_context)
-                            .await;
-                        if ws_writer.send(msg).await.is_err() {
-                            error!(target: "Runner", "WebSocket writer task failed to send message.");
+                    let middleware_context = MiddlewareContext::new(state, to_ws_sender.clone());
+                    // A new assembler per connection, so a packet cut by a reconnection is dropped.
+                    let mut assembler = AttachmentAssembler::new();
+                    while let Some(Ok(msg)) = ws_reader.next().await {
+                        let msg = match router.frame_mode {
+                            FrameMode::Raw => msg,
+                            FrameMode::Reassembled => match assembler.push(msg) {
+                                Some(msg) => msg,
+                                None => continue,
+                            },
+                        };
+                        let Some(frames) = router
+                            .intercept_receive(msg, &middleware_context)
+                            .await
+                            .into_frames()
+                        else {
+                            warn!(target: "Runner", "Middleware dropped the connection.");
                             break;
+                        };
+                        for msg in frames {
+                            if let Err(e) = router.route(Arc::new(msg), &to_ws_sender).await {
+                                warn!(target: "Router", "Error routing message: {:?}", e);
+                            }
                         }
                     }
                 }
             });
 
-            let reader_task = tokio::spawn({
-                let to_ws_sender = self.to_ws_sender.clone();
+            let writer_task = tokio::spawn({
+                let to_ws_rx = self.to_ws_receiver.clone();
                 let router = Arc::clone(&self.router);
+                let state = Arc::clone(&self.state);
+                let to_ws_sender = self.to_ws_sender.clone();
+                // Ending the reader is what the runner sees as a lost connection
+                let reader = reader_task.abort_handle();
                 async move {
-                    while let Some(Ok(msg)) = ws_reader.next().await {
-                        if let Err(e) = router.route(Arc::new(msg), &to_ws_sender).await {
-                            warn!(target: "Router", "Error routing message: {:?}", e);
+                    let middleware_context = MiddlewareContext::new(state, to_ws_sender);
+                    'writer: while let Ok(msg) = to_ws_rx.recv().await {
+                        let Some(frames) = router
+                            .intercept_send(msg, &middleware_context)
+                            .await
+                            .into_frames()
+                        else {
+                            warn!(target: "Runner", "Middleware dropped the connection.");
+                            reader.abort();
+                            break;
+                        };
+                        for msg in frames {
+                            router.stats.record_sent(&msg);
+                            router
+                                .middleware_stack
+                                .on_send(&msg, &middleware_context)
+                                .await;
+                            router
+                                .late_modules
+                                .middleware
+                                .read()
+                                .await
+                                .on_send(&msg, &middleware_context)
+                                .await;
+                            if ws_writer.send(msg).await.is_err() {
+                                error!(target: "Runner", "WebSocket writer task failed to send message.");
+                                break 'writer;
+                            }
                         }
                     }
                 }
@@ -509,6 +1024,7 @@ impl<S: AppState> ClientRunner<S> {
             let mut writer_task_opt = Some(writer_task);
             let mut reader_task_opt: Option<tokio::task::JoinHandle<()>> = Some(reader_task);
             let mut session_active = true;
+            let mut connection_lost = false;
 
             while session_active {
                 if !attempts_reset
@@ -536,14 +1052,34 @@ impl<S: AppState> ClientRunner<S> {
                         warn!(target: "Runner", "Connection lost unexpectedly.");
                         let ctx = MiddlewareContext::new(Arc::clone(&self.state), self.to_ws_sender.clone());
                         self.router.middleware_stack.on_disconnect(&ctx).await;
+                        self.router.late_modules.middleware.read().await.on_disconnect(&ctx).await;
                         if let Some(t) = writer_task_opt.take() { t.abort(); }
                         if let Some(t) = reader_task_opt.take() { t.abort(); }
                         self.signal.set_disconnected();
                         session_active = false;
+                        connection_lost = true;
                     }
                 }
             }
+
+            // A connection lost before it was stable is a failed attempt, so a server dropping
+            // every connection right away does not make the runner spin.
+            let stable = connection_start.elapsed()
+                > std::time::Duration::from_secs(CONNECTION_STABLE_RESET_SECS);
+            if stable {
+                self.reconnect_attempts = 0;
+            } else if connection_lost {
+                let error = format!(
+                    "connection lost after {:.1}s",
+                    connection_start.elapsed().as_secs_f64()
+                );
+                if !self.handle_failed_attempt(error).await {
+                    break;
+                }
+            }
         }
+        self.router.late_modules.abort_all();
+        self.signal.set_disconnected();
         debug!(target: "Runner", "Shutdown complete.");
     }
 }
@@ -17,6 +17,15 @@ pub enum CoreError {
     /// Error for when a module is not found.
     #[error("Module '{0}' not found.")]
     ModuleNotFound(String),
+    /// Error for when a module is registered twice on the same client.
+    #[error("Module '{0}' is already registered.")]
+    ModuleAlreadyRegistered(String),
+    /// Error for when a module is registered without a module it depends on.
+    #[error("Module '{module}' depends on '{dependency}', which is not registered.")]
+    MissingDependency { module: String, dependency: String },
+    /// Error for when modules depend on each other.
+    #[error("Modules depend on each other: {}.", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
 
     #[error("Failed to parse ssid: {0}")]
     SsidParsing(String),
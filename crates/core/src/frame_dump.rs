@@ -0,0 +1,378 @@
+//! Frame level capture of a connection for protocol debugging.
+//!
+//! [`FrameDumpMiddleware`] writes every frame sent and received, with its direction and time,
+//! to a gzip compressed JSON lines file. Text is passed through a [`Redactor`] first, the
+//! default one masks the session of auth messages, so dumps can be attached to bug reports.
+//! [`ReplayConnector`](crate::testing::ReplayConnector) plays a dump back to a client.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+use crate::error::CoreResult;
+use crate::middleware::{MiddlewareContext, WebSocketMiddleware};
+use crate::traits::AppState;
+
+/// Rewrites the text of a frame before it is written to a dump.
+pub type Redactor = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Whether a frame was sent or received by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameDirection {
+    In,
+    Out,
+}
+
+/// WebSocket frame type of a [`FrameRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameKind {
+    Text,
+    Binary,
+    Ping,
+    Pong,
+    Close,
+}
+
+/// One line of a frame dump.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameRecord {
+    /// Unix time of the frame in milliseconds.
+    pub ts: u64,
+    pub dir: FrameDirection,
+    pub kind: FrameKind,
+    /// Text of text frames, hex encoded payload of the other frames.
+    pub data: String,
+}
+
+impl FrameRecord {
+    /// Record of `message`, `None` for raw frames which never reach the middleware.
+    pub fn from_message(dir: FrameDirection, ts: u64, message: &Message) -> Option<Self> {
+        let (kind, data) = match message {
+            Message::Text(text) => (FrameKind::Text, text.to_string()),
+            Message::Binary(data) => (FrameKind::Binary, to_hex(data)),
+            Message::Ping(data) => (FrameKind::Ping, to_hex(data)),
+            Message::Pong(data) => (FrameKind::Pong, to_hex(data)),
+            Message::Close(_) => (FrameKind::Close, String::new()),
+            Message::Frame(_) => return None,
+        };
+        Some(Self {
+            ts,
+            dir,
+            kind,
+            data,
+        })
+    }
+
+    /// The recorded frame, `None` when its payload is not valid hex.
+    pub fn to_message(&self) -> Option<Message> {
+        Some(match self.kind {
+            FrameKind::Text => Message::text(self.data.clone()),
+            FrameKind::Binary => Message::binary(from_hex(&self.data)?),
+            FrameKind::Ping => Message::Ping(from_hex(&self.data)?.into()),
+            FrameKind::Pong => Message::Pong(from_hex(&self.data)?.into()),
+            FrameKind::Close => Message::Close(None),
+        })
+    }
+
+    /// Applies `redactor` to the text of the frame, binary frames included when they are
+    /// valid UTF-8 (e.g. Socket.IO attachments).
+    fn redact(mut self, redactor: &Redactor) -> Self {
+        match self.kind {
+            FrameKind::Text => self.data = redactor(&self.data),
+            FrameKind::Binary => {
+                if let Some(text) = from_hex(&self.data).and_then(|b| String::from_utf8(b).ok()) {
+                    self.data = to_hex(redactor(&text).as_bytes());
+                }
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+/// Masks the value of every `"session"` field, escaped quotes included.
+pub fn redact_session(text: &str) -> String {
+    static SESSION: OnceLock<Regex> = OnceLock::new();
+    let session = SESSION.get_or_init(|| {
+        Regex::new(r#""session"\s*:\s*"(?:[^"\\]|\\.)*""#).expect("valid session regex")
+    });
+    session
+        .replace_all(text, r#""session":"<redacted>""#)
+        .into_owned()
+}
+
+/// Middleware writing every frame of the connection to a gzip compressed dump, see the
+/// [module documentation](self).
+///
+/// The file is written by a background thread and flushed after every burst of frames, so a
+/// dump cut short by a crash still reads up to its last flush. The gzip trailer is written
+/// once the middleware is dropped.
+pub struct FrameDumpMiddleware {
+    jobs: mpsc::Sender<DumpJob>,
+    redactor: Redactor,
+}
+
+enum DumpJob {
+    Record(FrameRecord),
+    /// Answered once the records queued before it are flushed to the file.
+    Flush(oneshot::Sender<()>),
+}
+
+/// Waits for the frames already recorded by a [`FrameDumpMiddleware`] to reach its file.
+#[derive(Clone)]
+pub struct FrameDumpFlusher {
+    jobs: mpsc::Sender<DumpJob>,
+}
+
+impl FrameDumpFlusher {
+    /// Returns once every frame recorded before the call is written and flushed, or `false`
+    /// if the writer stopped on an I/O error.
+    pub async fn flush(&self) -> bool {
+        let (done, flushed) = oneshot::channel();
+        if self.jobs.send(DumpJob::Flush(done)).is_err() {
+            return false;
+        }
+        flushed.await.is_ok()
+    }
+}
+
+impl FrameDumpMiddleware {
+    /// Dumps to `path`, truncating it, with [`redact_session`] as redactor.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::with_redactor(path, Arc::new(redact_session))
+    }
+
+    /// Dumps to `path`, truncating it, passing the text of every frame through `redactor`.
+    pub fn with_redactor(path: impl AsRef<Path>, redactor: Redactor) -> io::Result<Self> {
+        let file = File::create(path.as_ref())?;
+        let out = GzEncoder::new(BufWriter::new(file), Compression::default());
+        let (jobs, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("frame-dump".into())
+            .spawn(move || {
+                if let Err(e) = write_records(out, rx) {
+                    warn!(target: "FrameDump", "Failed to write the frame dump: {}", e);
+                }
+            })?;
+        Ok(Self { jobs, redactor })
+    }
+
+    /// Returns a handle flushing the dump, which stays usable once the middleware is given to
+    /// the client.
+    pub fn flusher(&self) -> FrameDumpFlusher {
+        FrameDumpFlusher {
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    fn record(&self, dir: FrameDirection, message: &Message) {
+        if let Some(record) = FrameRecord::from_message(dir, unix_millis(), message) {
+            // The writer only stops on I/O errors, which it already reported.
+            let _ = self
+                .jobs
+                .send(DumpJob::Record(record.redact(&self.redactor)));
+        }
+    }
+}
+
+#[async_trait]
+impl<S: AppState> WebSocketMiddleware<S> for FrameDumpMiddleware {
+    async fn on_send(&self, message: &Message, _context: &MiddlewareContext<S>) -> CoreResult<()> {
+        self.record(FrameDirection::Out, message);
+        Ok(())
+    }
+
+    async fn on_receive(
+        &self,
+        message: &Message,
+        _context: &MiddlewareContext<S>,
+    ) -> CoreResult<()> {
+        self.record(FrameDirection::In, message);
+        Ok(())
+    }
+}
+
+fn write_records(
+    mut out: GzEncoder<BufWriter<File>>,
+    jobs: mpsc::Receiver<DumpJob>,
+) -> io::Result<()> {
+    while let Ok(first) = jobs.recv() {
+        let mut flushed = Vec::new();
+        for job in std::iter::once(first).chain(jobs.try_iter()) {
+            match job {
+                DumpJob::Record(record) => {
+                    serde_json::to_writer(&mut out, &record)?;
+                    out.write_all(b"\n")?;
+                }
+                DumpJob::Flush(done) => flushed.push(done),
+            }
+        }
+        out.flush()?;
+        for done in flushed {
+            let _ = done.send(());
+        }
+    }
+    out.finish()?.flush()
+}
+
+/// Reads the frames of a dump written by [`FrameDumpMiddleware`], uncompressed dumps too.
+///
+/// A dump cut short (e.g. by a crash) yields the frames written before its end.
+pub fn read_frame_dump(path: impl AsRef<Path>) -> io::Result<Vec<FrameRecord>> {
+    let mut file = BufReader::new(File::open(path.as_ref())?);
+    let gzip = file.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    let reader: Box<dyn Read> = if gzip {
+        Box::new(MultiGzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(reader).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            // The last line of a truncated dump may be incomplete.
+            Err(e) if e.is_eof() => break,
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+    }
+    Ok(records)
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_session() {
+        let auth = r#"42["auth",{"session":"a:4:{s:10:\"session_id\";s:32:\"abc\";}","isDemo":1,"uid":7}]"#;
+        assert_eq!(
+            redact_session(auth),
+            r#"42["auth",{"session":"<redacted>","isDemo":1,"uid":7}]"#
+        );
+        assert_eq!(redact_session("42[\"ps\"]"), "42[\"ps\"]");
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        for message in [
+            Message::text("42[\"ps\"]"),
+            Message::binary(vec![0, 159, 255]),
+            Message::Ping(vec![1, 2].into()),
+            Message::Close(None),
+        ] {
+            let record = FrameRecord::from_message(FrameDirection::In, 5, &message).unwrap();
+            assert_eq!(record.to_message().unwrap(), message);
+        }
+        assert_eq!(from_hex("0g"), None);
+    }
+
+    #[tokio::test]
+    async fn test_dump_is_redacted_and_readable() {
+        let path = std::env::temp_dir().join(format!(
+            "frame-dump-{}-{}.jsonl.gz",
+            std::process::id(),
+            unix_millis()
+        ));
+        let (sender, _receiver) = kanal::bounded_async(1);
+        let context = MiddlewareContext::new(Arc::new(()), sender);
+        let dump = FrameDumpMiddleware::create(&path).unwrap();
+        let auth = Message::text(r#"42["auth",{"session":"secret","isDemo":1}]"#);
+        dump.on_send(&auth, &context).await.unwrap();
+        dump.on_receive(
+            &Message::binary(br#"{"session":"secret"}"#.to_vec()),
+            &context,
+        )
+        .await
+        .unwrap();
+        drop(dump);
+
+        // The writer thread finishes the file once the middleware is dropped.
+        let mut records = Vec::new();
+        for _ in 0..50 {
+            records = read_frame_dump(&path).unwrap_or_default();
+            if records.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        std::fs::remove_file(&path).ok();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].dir, FrameDirection::Out);
+        assert_eq!(
+            records[0].data,
+            r#"42["auth",{"session":"<redacted>","isDemo":1}]"#
+        );
+        assert_eq!(records[1].dir, FrameDirection::In);
+        assert_eq!(
+            records[1].to_message().unwrap(),
+            Message::binary(br#"{"session":"<redacted>"}"#.to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flusher_writes_recorded_frames() {
+        let path = std::env::temp_dir().join(format!(
+            "frame-dump-flush-{}-{}.jsonl.gz",
+            std::process::id(),
+            unix_millis()
+        ));
+        let (sender, _receiver) = kanal::bounded_async(1);
+        let context = MiddlewareContext::new(Arc::new(()), sender);
+        let dump = FrameDumpMiddleware::create(&path).unwrap();
+        let flusher = dump.flusher();
+        for _ in 0..100 {
+            dump.on_send(&Message::text("42[\"ps\"]"), &context)
+                .await
+                .unwrap();
+        }
+
+        // The middleware is still alive, only the flush makes the frames readable.
+        assert!(flusher.flush().await);
+        let records = read_frame_dump(&path).unwrap();
+        drop(dump);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(records.len(), 100);
+    }
+}
@@ -3,31 +3,47 @@
 //! This crate provides the foundational components for building and interacting with binary options tools.
 //!
 //! # Modules
+//! - `audit`: Audit trail of the sent messages and the modules sending them.
 //! - `builder`: Utilities for constructing core objects.
+//! - `channels`: Capacities of the module channels.
 //! - `client`: Client-side logic and abstractions.
 //! - `connector`: Connection management and protocols.
 //! - `error`: Error types and handling utilities.
+//! - `frame_dump`: Redacted, compressed capture of the WebSocket frames.
 //! - `message`: Message definitions and serialization.
 //! - `middleware`: Middleware traits and implementations.
+//! - `reconnect`: Reconnection policy and reconnect events.
+//! - `routing`: Priority and exclusivity of the module rules.
 //! - `statistics`: Statistical analysis and reporting.
+//! - `status`: Connection lifecycle status.
+//! - `supervisor`: Module restart policies and panic isolation.
 //! - `testing`: Testing utilities and mocks.
 //! - `traits`: Core traits and interfaces.
 //! - `signals`: Signal processing and event handling.
+//! - `socketio`: Socket.IO binary attachment reassembly.
 //! - `reimports`: Re-exports for convenience.
 //!
 //! This crate is intended for internal use by higher-level application crates.
 
 pub use binary_options_tools_core_macros::rule as Rule;
+pub mod audit;
 pub mod builder;
 pub mod callback;
+pub mod channels;
 pub mod client;
 pub mod connector;
 pub mod error;
+pub mod frame_dump;
 pub mod message;
 pub mod middleware;
+pub mod reconnect;
+pub mod routing;
 pub mod rules;
 pub mod signals;
+pub mod socketio;
 pub mod statistics;
+pub mod status;
+pub mod supervisor;
 pub mod testing;
 pub mod traits;
 pub mod utils;
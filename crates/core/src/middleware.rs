@@ -9,6 +9,7 @@
 //! - [`WebSocketMiddleware`]: The core trait for implementing middleware
 //! - [`MiddlewareStack`]: A composable stack of middleware layers
 //! - [`MiddlewareContext`]: Context passed to middleware with message and client information
+//! - [`Intercepted`]: What becomes of a frame after the interception hooks
 //!
 //! # Example Usage
 //!
@@ -76,6 +77,37 @@ impl<S: AppState> MiddlewareContext<S> {
     }
 }
 
+/// Outcome of [`WebSocketMiddleware::intercept_send`] and
+/// [`WebSocketMiddleware::intercept_receive`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Intercepted {
+    /// The frame goes through unchanged.
+    Pass(Message),
+    /// The frames processed in place of the intercepted one, in order. Empty drops it.
+    Frames(Vec<Message>),
+    /// Drops the connection as if the server closed it, the runner then reconnects.
+    Disconnect,
+}
+
+impl Intercepted {
+    /// The frames to process, `None` to disconnect.
+    pub fn into_frames(self) -> Option<impl Iterator<Item = Message>> {
+        // A passed frame doesn't allocate, `Vec::new` doesn't
+        let (passed, frames) = match self {
+            Self::Pass(message) => (Some(message), Vec::new()),
+            Self::Frames(frames) => (None, frames),
+            Self::Disconnect => return None,
+        };
+        Some(passed.into_iter().chain(frames))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Send,
+    Receive,
+}
+
 /// Trait for implementing WebSocket middleware.
 ///
 /// Middleware can observe and react to WebSocket messages being sent and received.
@@ -158,6 +190,34 @@ pub trait WebSocketMiddleware<S: AppState>: Send + Sync + 'static {
         Ok(())
     }
 
+    /// Called with every outgoing frame before [`on_send`](Self::on_send), to delay, drop,
+    /// replace or duplicate it, or to drop the connection.
+    ///
+    /// The default passes the frame through. Meant for fault injection in tests, the
+    /// writer waits for this hook before sending anything else.
+    async fn intercept_send(
+        &self,
+        message: Message,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        let _ = context;
+        Intercepted::Pass(message)
+    }
+
+    /// Called with every incoming frame before [`on_receive`](Self::on_receive), to delay,
+    /// drop, replace or duplicate it, or to drop the connection.
+    ///
+    /// The default passes the frame through. Meant for fault injection in tests, the
+    /// reader waits for this hook before routing anything else.
+    async fn intercept_receive(
+        &self,
+        message: Message,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        let _ = context;
+        Intercepted::Pass(message)
+    }
+
     /// Called when a connection attempt is made (before actual connection)
     async fn on_connection_attempt(&self, _context: &MiddlewareContext<S>) -> CoreResult<()> {
         Ok(())
@@ -303,6 +363,63 @@ impl<S: AppState> MiddlewareStack<S> {
         }
     }
 
+    /// Passes outgoing frames through the `intercept_send` hook of every layer, in order.
+    ///
+    /// Every frame produced by a layer goes through the next ones, and a layer asking to
+    /// disconnect ends the interception.
+    pub async fn intercept_send(
+        &self,
+        intercepted: Intercepted,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        self.intercept(intercepted, context, Direction::Send).await
+    }
+
+    /// Passes incoming frames through the `intercept_receive` hook of every layer, in order.
+    ///
+    /// Every frame produced by a layer goes through the next ones, and a layer asking to
+    /// disconnect ends the interception.
+    pub async fn intercept_receive(
+        &self,
+        intercepted: Intercepted,
+        context: &MiddlewareContext<S>,
+    ) -> Intercepted {
+        self.intercept(intercepted, context, Direction::Receive)
+            .await
+    }
+
+    async fn intercept(
+        &self,
+        mut intercepted: Intercepted,
+        context: &MiddlewareContext<S>,
+        direction: Direction,
+    ) -> Intercepted {
+        for middleware in &self.layers {
+            let hook = |message| async {
+                match direction {
+                    Direction::Send => middleware.intercept_send(message, context).await,
+                    Direction::Receive => middleware.intercept_receive(message, context).await,
+                }
+            };
+            intercepted = match intercepted {
+                Intercepted::Pass(message) => hook(message).await,
+                Intercepted::Frames(frames) => {
+                    let mut output = Vec::with_capacity(frames.len());
+                    for frame in frames {
+                        match hook(frame).await {
+                            Intercepted::Pass(message) => output.push(message),
+                            Intercepted::Frames(frames) => output.extend(frames),
+                            Intercepted::Disconnect => return Intercepted::Disconnect,
+                        }
+                    }
+                    Intercepted::Frames(output)
+                }
+                Intercepted::Disconnect => return Intercepted::Disconnect,
+            };
+        }
+        intercepted
+    }
+
     /// Record a connection attempt across all middleware
     pub async fn record_connection_attempt(&self, context: &MiddlewareContext<S>) {
         for (index, middleware) in self.layers.iter().enumerate() {
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::RngExt;
+
+/// What the runner does once [`ReconnectPolicy::max_attempts`] is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitAction {
+    /// Shuts the runner down.
+    #[default]
+    Abort,
+    /// Waits `max_delay`, then starts counting attempts again.
+    Backoff,
+    /// Starts counting attempts again with a fresh connection (`Connector::connect` instead of
+    /// `Connector::reconnect`), after `initial_delay`.
+    Reconnect,
+}
+
+impl LimitAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Abort => "abort",
+            Self::Backoff => "backoff",
+            Self::Reconnect => "reconnect",
+        }
+    }
+}
+
+impl fmt::Display for LimitAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LimitAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abort" => Ok(Self::Abort),
+            "backoff" => Ok(Self::Backoff),
+            "reconnect" => Ok(Self::Reconnect),
+            _ => Err(format!(
+                "Unknown limit action '{s}', expected 'abort', 'backoff' or 'reconnect'"
+            )),
+        }
+    }
+}
+
+/// Callback invoked once the runner gives up reconnecting.
+/// Receives the number of attempts that were made.
+pub type OnExhausted = Arc<dyn Fn(u32) + Send + Sync>;
+
+/// Strategy used by the `ClientRunner` to space out reconnection attempts.
+///
+/// The delay before attempt `n` (starting at 1) is
+/// `initial_delay * multiplier^(n - 1)`, capped at `max_delay` and then
+/// scaled by a random factor in `1 - jitter..1 + jitter`.
+#[derive(Clone)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnection attempt.
+    pub initial_delay: Duration,
+    /// Factor applied to the delay after every failed attempt.
+    pub multiplier: f64,
+    /// Upper bound for the delay (before jitter is applied).
+    pub max_delay: Duration,
+    /// Relative jitter in `0.0..1.0`, e.g. `0.2` for ±20%.
+    pub jitter: f64,
+    /// Maximum number of consecutive failed attempts, 0 means unlimited.
+    ///
+    /// A connection lost before it was stable counts as a failed attempt, so a server
+    /// accepting and dropping every connection does not make the runner spin.
+    pub max_attempts: u32,
+    /// What happens once `max_attempts` is reached.
+    pub limit_action: LimitAction,
+    on_exhausted: Option<OnExhausted>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(5),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(3600),
+            jitter: 0.2,
+            max_attempts: 0,
+            limit_action: LimitAction::Abort,
+            on_exhausted: None,
+        }
+    }
+}
+
+impl fmt::Debug for ReconnectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectPolicy")
+            .field("initial_delay", &self.initial_delay)
+            .field("multiplier", &self.multiplier)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("max_attempts", &self.max_attempts)
+            .field("limit_action", &self.limit_action)
+            .field("on_exhausted", &self.on_exhausted.is_some())
+            .finish()
+    }
+}
+
+impl ReconnectPolicy {
+    /// A policy that retries forever with a fixed delay and no jitter.
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            initial_delay: delay,
+            multiplier: 1.0,
+            max_delay: delay,
+            jitter: 0.0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 0.99);
+        self
+    }
+
+    /// Sets the maximum number of consecutive failed attempts, 0 means unlimited.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets what the runner does once `max_attempts` is reached, it shuts down by default.
+    pub fn with_limit_action(mut self, action: LimitAction) -> Self {
+        self.limit_action = action;
+        self
+    }
+
+    /// Sets a callback invoked when `max_attempts` is reached and the runner shuts down.
+    pub fn on_exhausted(mut self, callback: impl Fn(u32) + Send + Sync + 'static) -> Self {
+        self.on_exhausted = Some(Arc::new(callback));
+        self
+    }
+
+    /// Returns `true` if `attempts` failed attempts exhaust this policy.
+    pub fn is_exhausted(&self, attempts: u32) -> bool {
+        self.max_attempts > 0 && attempts >= self.max_attempts
+    }
+
+    /// Delay before the given attempt (1-based), without jitter.
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let max = self.max_delay.as_secs_f64();
+        if secs.is_finite() && secs < max {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max_delay
+        }
+    }
+
+    /// Delay before the given attempt (1-based), with jitter applied.
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let base = self.base_delay(attempt);
+        if self.jitter <= 0.0 {
+            return base;
+        }
+        let factor = rand::rng().random_range((1.0 - self.jitter)..(1.0 + self.jitter));
+        base.mul_f64(factor)
+    }
+
+    pub(crate) fn notify_exhausted(&self, attempts: u32) {
+        if let Some(callback) = &self.on_exhausted {
+            callback(attempts);
+        }
+    }
+}
+
+/// Reconnection progress reported by the runner, see `Client::reconnect_events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReconnectEvent {
+    /// A connection attempt failed and another one is scheduled after `delay`.
+    Scheduled {
+        attempt: u32,
+        /// `None` when the policy allows unlimited attempts.
+        max_attempts: Option<u32>,
+        delay: Duration,
+        error: String,
+    },
+    /// The connection was (re-)established after `attempts` failed attempts.
+    Connected { attempts: u32 },
+    /// `max_attempts` was reached, the runner applies `diagnostics.action`.
+    LimitReached(LoopDiagnostics),
+    /// The policy gave up after `attempts` failed attempts, the runner is shutting down.
+    Exhausted { attempts: u32 },
+}
+
+/// State of the connection loop when [`ReconnectPolicy::max_attempts`] is reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopDiagnostics {
+    /// Failed attempts in a row, connections lost before they were stable included.
+    pub attempts: u32,
+    /// Connection cycles started in the last minute.
+    pub cycles_last_minute: u32,
+    /// Why the last attempt failed.
+    pub last_error: Option<String>,
+    /// Messages routed to each module during the last session, busiest first.
+    pub modules: Vec<(String, u64)>,
+    /// Outgoing messages still waiting for the socket writer.
+    pub queued_outgoing: usize,
+    /// What the runner does next.
+    pub action: LimitAction,
+}
+
+impl fmt::Display for LoopDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed attempts, {} connection cycles in the last minute",
+            self.attempts, self.cycles_last_minute
+        )?;
+        if let Some(error) = &self.last_error {
+            write!(f, ", last error: {error}")?;
+        }
+        if let Some((module, messages)) = self.modules.first() {
+            write!(f, ", busiest module: {module} ({messages} messages)")?;
+        }
+        write!(
+            f,
+            ", {} outgoing messages queued, action: {}",
+            self.queued_outgoing, self.action
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_secs(1))
+            .with_multiplier(2.0)
+            .with_max_delay(Duration::from_secs(10));
+        assert_eq!(policy.base_delay(1), Duration::from_secs(1));
+        assert_eq!(policy.base_delay(2), Duration::from_secs(2));
+        assert_eq!(policy.base_delay(4), Duration::from_secs(8));
+        assert_eq!(policy.base_delay(5), Duration::from_secs(10));
+        assert_eq!(policy.base_delay(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_secs(10))
+            .with_jitter(0.2);
+        for _ in 0..100 {
+            let delay = policy.delay_for(1);
+            assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(12));
+        }
+        let fixed = ReconnectPolicy::fixed(Duration::from_millis(250));
+        assert_eq!(fixed.delay_for(7), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_exhaustion_callback() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let policy = ReconnectPolicy::default()
+            .with_max_attempts(3)
+            .on_exhausted(move |n| {
+                counter.store(n, Ordering::SeqCst);
+            });
+        assert!(!policy.is_exhausted(2));
+        assert!(policy.is_exhausted(3));
+        policy.notify_exhausted(3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(!ReconnectPolicy::default().is_exhausted(u32::MAX));
+    }
+
+    #[test]
+    fn test_limit_action_round_trip() {
+        for action in [
+            LimitAction::Abort,
+            LimitAction::Backoff,
+            LimitAction::Reconnect,
+        ] {
+            assert_eq!(action.as_str().parse::<LimitAction>(), Ok(action));
+        }
+        assert!("retry".parse::<LimitAction>().is_err());
+        assert_eq!(ReconnectPolicy::default().limit_action, LimitAction::Abort);
+    }
+}
@@ -0,0 +1,51 @@
+/// Where the rule of a module sits in the router, see
+/// [`ClientBuilder::with_module_routing`](crate::builder::ClientBuilder::with_module_routing).
+///
+/// Rules are evaluated from the highest priority to the lowest, rules of the same priority in
+/// the order their modules were registered. Once an exclusive rule matches a message, the rules
+/// after it are not evaluated and their modules never see the message, which saves the work on
+/// busy streams only one module cares about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Routing {
+    pub priority: i32,
+    pub exclusive: bool,
+}
+
+impl Routing {
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Stops the routing of the messages this rule matches.
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+}
+
+/// Inserts `item` after the items with a priority higher than or equal to `priority`.
+pub(crate) fn insert_by_priority<T>(
+    items: &mut Vec<T>,
+    item: T,
+    priority: i32,
+    priority_of: impl Fn(&T) -> i32,
+) {
+    let index = items.partition_point(|other| priority_of(other) >= priority);
+    items.insert(index, item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_by_priority_is_stable() {
+        let mut items = Vec::new();
+        for (name, priority) in [("a", 0), ("b", 10), ("c", 0), ("d", 10), ("e", -5)] {
+            insert_by_priority(&mut items, (name, priority), priority, |item| item.1);
+        }
+        let names: Vec<_> = items.iter().map(|item| item.0).collect();
+        assert_eq!(names, ["b", "d", "a", "c", "e"]);
+    }
+}
@@ -1,55 +1,105 @@
+use futures_util::Stream;
 use std::sync::Arc;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
+
+use crate::reconnect::ReconnectEvent;
+use crate::status::ConnectionStatus;
+
+const RECONNECT_EVENTS_CAPACITY: usize = 64;
 
 #[derive(Clone, Debug)]
 pub struct Signals {
-    connected_watch: Arc<watch::Sender<bool>>,
-    connected_receiver: watch::Receiver<bool>,
+    status_watch: Arc<watch::Sender<ConnectionStatus>>,
+    status_receiver: watch::Receiver<ConnectionStatus>,
+    reconnect_events: broadcast::Sender<ReconnectEvent>,
 }
 
 impl Signals {
     pub fn new() -> Self {
-        let (tx, rx) = watch::channel(false);
+        let (tx, rx) = watch::channel(ConnectionStatus::default());
+        let (reconnect_events, _) = broadcast::channel(RECONNECT_EVENTS_CAPACITY);
         Self {
-            connected_watch: Arc::new(tx),
-            connected_receiver: rx,
+            status_watch: Arc::new(tx),
+            status_receiver: rx,
+            reconnect_events,
+        }
+    }
+
+    /// Publishes a reconnect event. Events are dropped if nobody is subscribed.
+    pub fn emit_reconnect_event(&self, event: ReconnectEvent) {
+        let _ = self.reconnect_events.send(event);
+    }
+
+    /// Subscribes to reconnect events emitted from now on.
+    pub fn subscribe_reconnect_events(&self) -> broadcast::Receiver<ReconnectEvent> {
+        self.reconnect_events.subscribe()
+    }
+
+    /// Updates the connection status, subscribers are only notified on actual changes.
+    pub fn set_status(&self, status: ConnectionStatus) {
+        self.status_watch.send_if_modified(|current| {
+            let changed = *current != status;
+            *current = status;
+            changed
+        });
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        *self.status_receiver.borrow()
+    }
+
+    /// Returns a receiver that observes every status change.
+    pub fn subscribe_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.status_receiver.clone()
+    }
+
+    /// Stream yielding the current status followed by every change.
+    pub fn status_stream(&self) -> impl Stream<Item = ConnectionStatus> + Send + 'static {
+        let rx = self.status_receiver.clone();
+        futures_util::stream::unfold((rx, true), |(mut rx, first)| async move {
+            if !first && rx.changed().await.is_err() {
+                return None;
+            }
+            let status = *rx.borrow_and_update();
+            Some((status, (rx, false)))
+        })
+    }
+
+    /// Waits until the status satisfies `predicate` and returns it.
+    pub async fn wait_for_status(
+        &self,
+        predicate: impl Fn(ConnectionStatus) -> bool,
+    ) -> ConnectionStatus {
+        let mut rx = self.status_receiver.clone();
+        loop {
+            let status = *rx.borrow_and_update();
+            if predicate(status) {
+                return status;
+            }
+            if rx.changed().await.is_err() {
+                return status;
+            }
         }
     }
 
     pub fn set_connected(&self) {
-        let _ = self.connected_watch.send_replace(true);
+        self.set_status(ConnectionStatus::Connected);
     }
 
     pub fn set_disconnected(&self) {
-        let _ = self.connected_watch.send_replace(false);
+        self.set_status(ConnectionStatus::Disconnected);
     }
 
     pub fn is_connected(&self) -> bool {
-        *self.connected_receiver.borrow()
+        self.status().is_connected()
     }
 
     pub async fn wait_connected(&self) {
-        let mut rx = self.connected_receiver.clone();
-        if *rx.borrow_and_update() {
-            return;
-        }
-        while rx.changed().await.is_ok() {
-            if *rx.borrow_and_update() {
-                return;
-            }
-        }
+        self.wait_for_status(|s| s.is_connected()).await;
     }
 
     pub async fn wait_disconnected(&self) {
-        let mut rx = self.connected_receiver.clone();
-        if !*rx.borrow_and_update() {
-            return;
-        }
-        while rx.changed().await.is_ok() {
-            if !*rx.borrow_and_update() {
-                return;
-            }
-        }
+        self.wait_for_status(|s| !s.is_connected()).await;
     }
 }
 
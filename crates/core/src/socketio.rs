@@ -0,0 +1,230 @@
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+/// Maximum number of binary attachments accepted for a single Socket.IO packet.
+pub const MAX_ATTACHMENTS: usize = 256;
+
+/// How the client delivers Socket.IO packets that carry binary attachments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrameMode {
+    /// Frames are routed as received: the `451-[...]` header with its placeholders,
+    /// then one binary frame per attachment.
+    #[default]
+    Raw,
+    /// The header and its attachments are merged into a single `42[...]` (or `43[...]` for
+    /// acks) text frame whose placeholders are replaced by the decoded attachments.
+    Reassembled,
+}
+
+/// Header of a Socket.IO binary event (`45<n>-`) or binary ack (`46<n>-`) packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryHeader {
+    /// `true` for a binary ack, `false` for a binary event.
+    pub ack: bool,
+    /// Number of binary frames following the header.
+    pub attachments: usize,
+    /// Everything after the `-`: optional namespace, optional ack id and the JSON payload.
+    pub body: String,
+}
+
+impl BinaryHeader {
+    /// Parses a text frame, returns `None` if it is not a binary event or ack packet.
+    pub fn parse(text: &str) -> Option<Self> {
+        let rest = text.strip_prefix('4')?;
+        let ack = match rest.chars().next()? {
+            '5' => false,
+            '6' => true,
+            _ => return None,
+        };
+        let rest = &rest[1..];
+        let (count, body) = rest.split_once('-')?;
+        if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(Self {
+            ack,
+            attachments: count.parse().ok()?,
+            body: body.to_string(),
+        })
+    }
+
+    /// Builds the plain event (or ack) frame with every placeholder replaced by its attachment.
+    fn assemble(&self, attachments: &[Vec<u8>]) -> String {
+        let packet = if self.ack { "43" } else { "42" };
+        let Some(start) = self.body.find(['[', '{']) else {
+            return format!("{packet}{}", self.body);
+        };
+        let (prefix, json) = self.body.split_at(start);
+        match serde_json::from_str::<Value>(json) {
+            Ok(mut value) => {
+                replace_placeholders(&mut value, attachments);
+                format!("{packet}{prefix}{value}")
+            }
+            Err(e) => {
+                warn!(target: "SocketIo", "Binary packet payload is not valid JSON: {e}");
+                format!("{packet}{}", self.body)
+            }
+        }
+    }
+}
+
+/// Decodes a binary attachment: JSON if it parses as such (a leading engine.io `0x04`
+/// marker is skipped), otherwise a string if it is UTF-8, otherwise an array of bytes.
+pub fn decode_attachment(data: &[u8]) -> Value {
+    let data = match data {
+        [4, rest @ ..] if serde_json::from_slice::<Value>(rest).is_ok() => rest,
+        data => data,
+    };
+    if let Ok(value) = serde_json::from_slice(data) {
+        return value;
+    }
+    match std::str::from_utf8(data) {
+        Ok(text) => Value::String(text.to_string()),
+        Err(_) => Value::Array(data.iter().map(|b| Value::from(*b)).collect()),
+    }
+}
+
+fn replace_placeholders(value: &mut Value, attachments: &[Vec<u8>]) {
+    match value {
+        Value::Object(map) if map.get("_placeholder") == Some(&Value::Bool(true)) => {
+            let attachment = map
+                .get("num")
+                .and_then(Value::as_u64)
+                .and_then(|num| attachments.get(num as usize));
+            if let Some(data) = attachment {
+                *value = decode_attachment(data);
+            }
+        }
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|v| replace_placeholders(v, attachments)),
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|v| replace_placeholders(v, attachments)),
+        _ => {}
+    }
+}
+
+/// Merges Socket.IO binary packets with their attachments, one instance per connection.
+///
+/// Every frame is passed to [`AttachmentAssembler::push`], which returns the frame to deliver:
+/// frames unrelated to binary packets are returned unchanged, a binary header and its
+/// attachments are held back until the last attachment arrives and then returned as a
+/// single text frame.
+#[derive(Debug, Default)]
+pub struct AttachmentAssembler {
+    pending: Option<(BinaryHeader, Vec<Vec<u8>>)>,
+}
+
+impl AttachmentAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a received frame, returns the frame to deliver or `None` while attachments are
+    /// still missing.
+    pub fn push(&mut self, msg: Message) -> Option<Message> {
+        match &msg {
+            Message::Text(text) => {
+                let Some(header) = BinaryHeader::parse(text.as_str()) else {
+                    return Some(msg);
+                };
+                if header.attachments > MAX_ATTACHMENTS {
+                    warn!(target: "SocketIo", "Binary packet announces {} attachments (max {}), delivering it raw", header.attachments, MAX_ATTACHMENTS);
+                    return Some(msg);
+                }
+                if let Some((previous, received)) = self.pending.take() {
+                    warn!(target: "SocketIo", "Dropping binary packet with {}/{} attachments, a new one started", received.len(), previous.attachments);
+                }
+                if header.attachments == 0 {
+                    return Some(Message::text(header.assemble(&[])));
+                }
+                self.pending = Some((header, Vec::new()));
+                None
+            }
+            Message::Binary(data) => {
+                let Some((header, received)) = self.pending.as_mut() else {
+                    return Some(msg);
+                };
+                received.push(data.to_vec());
+                if received.len() < header.attachments {
+                    return None;
+                }
+                let (header, received) = self.pending.take()?;
+                Some(Message::text(header.assemble(&received)))
+            }
+            _ => Some(msg),
+        }
+    }
+
+    /// Returns `true` while a binary packet waits for attachments.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Drops a partially received packet, e.g. after a reconnection.
+    pub fn reset(&mut self) {
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_binary_header_parse() {
+        let header = BinaryHeader::parse(r#"451-["ev",{"_placeholder":true,"num":0}]"#).unwrap();
+        assert!(!header.ack);
+        assert_eq!(header.attachments, 1);
+        assert_eq!(header.body, r#"["ev",{"_placeholder":true,"num":0}]"#);
+        assert!(BinaryHeader::parse(r#"462-/ns,7[{"a":1}]"#).unwrap().ack);
+        assert_eq!(BinaryHeader::parse(r#"42["ev",{}]"#), None);
+        assert_eq!(BinaryHeader::parse(r#"45-["ev"]"#), None);
+        assert_eq!(BinaryHeader::parse("3"), None);
+    }
+
+    #[test]
+    fn test_assembler_merges_attachments() {
+        let mut assembler = AttachmentAssembler::new();
+        let header = Message::text(
+            r#"452-["updateStream",{"_placeholder":true,"num":0},[{"_placeholder":true,"num":1}]]"#,
+        );
+        assert!(assembler.push(header).is_none());
+        assert!(assembler.is_pending());
+        assert!(assembler
+            .push(Message::binary(br#"[["EURUSD_otc",1,1.1]]"#.to_vec()))
+            .is_none());
+        let merged = assembler.push(Message::binary(vec![0xff, 0x00])).unwrap();
+        assert!(!assembler.is_pending());
+
+        let text = merged.to_text().unwrap();
+        let payload: Value = serde_json::from_str(text.strip_prefix("42").unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            json!(["updateStream", [["EURUSD_otc", 1, 1.1]], [[255, 0]]])
+        );
+    }
+
+    #[test]
+    fn test_assembler_passes_other_frames_through() {
+        let mut assembler = AttachmentAssembler::new();
+        let text = Message::text(r#"42["ev",1]"#);
+        assert_eq!(assembler.push(text.clone()), Some(text));
+        let binary = Message::binary(b"orphan".to_vec());
+        assert_eq!(assembler.push(binary.clone()), Some(binary));
+        assert_eq!(
+            assembler.push(Message::text(r#"450-["ev"]"#)),
+            Some(Message::text(r#"42["ev"]"#))
+        );
+    }
+
+    #[test]
+    fn test_decode_attachment() {
+        assert_eq!(decode_attachment(b"\x04{\"a\":1}"), json!({"a": 1}));
+        assert_eq!(decode_attachment(b"plain"), json!("plain"));
+        assert_eq!(decode_attachment(&[0xc3]), json!([0xc3]));
+    }
+}
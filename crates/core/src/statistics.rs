@@ -1,6 +1,8 @@
+use crate::traits::Rule;
 use kanal::{AsyncReceiver, AsyncSender};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -810,3 +812,409 @@ impl<T> TrackedReceiver<T> {
         result
     }
 }
+
+/// Snapshot of the traffic and health counters of a [`Client`](crate::client::Client).
+///
+/// Counters start at zero when the client is built and when
+/// [`Client::reset_stats`](crate::client::Client::reset_stats) is called.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientStats {
+    /// Seconds since the client was built.
+    pub uptime_seconds: f64,
+    /// Seconds covered by the counters, since the build or the last reset.
+    pub window_seconds: f64,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Successful connections, the first one included.
+    pub connections: u64,
+    /// Successful connections after the first one.
+    pub reconnects: u64,
+    pub connection_failures: u64,
+    /// Errors returned by lightweight handlers.
+    pub handler_errors: u64,
+    /// Messages that could not be routed because a module's channel was closed.
+    pub routing_errors: u64,
+    /// Incoming messages routed to each module, by module name.
+    ///
+    /// Outgoing messages are only counted client wide, as modules share the socket writer.
+    pub modules: BTreeMap<String, u64>,
+    /// Messages each module's rule was evaluated on and matched, by module name.
+    ///
+    /// Rules after an exclusive match are not evaluated, see [`Routing`](crate::routing::Routing).
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleStats>,
+    /// Seconds since the last incoming message, `None` before the first one.
+    ///
+    /// Not affected by [`Client::reset_stats`](crate::client::Client::reset_stats).
+    #[serde(default)]
+    pub last_message_age_seconds: Option<f64>,
+    /// Connection cycles (attempts, failed or not) started in the last minute, a high value
+    /// means the runner is looping.
+    ///
+    /// Not affected by [`Client::reset_stats`](crate::client::Client::reset_stats).
+    #[serde(default)]
+    pub connection_cycles_last_minute: u32,
+    /// Fill level of the message channel of each module, by module name.
+    #[serde(default)]
+    pub channels: BTreeMap<String, ChannelStats>,
+}
+
+/// Work done by the rule of a module.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleStats {
+    pub evaluations: u64,
+    pub hits: u64,
+}
+
+/// How full the message channel of a module got, see [`ChannelCapacity`](crate::channels::ChannelCapacity).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub capacity: usize,
+    /// Most messages waiting in the channel at once.
+    pub high_water_mark: usize,
+    /// Messages routed while the channel was full.
+    pub overflows: u64,
+    /// Messages dropped because the channel was full, with [`Overflow::Drop`](crate::channels::Overflow::Drop).
+    pub dropped: u64,
+}
+
+/// Counters behind [`ClientStats`], shared by the client, its router and its runner.
+#[derive(Debug)]
+pub(crate) struct ClientStatsTracker {
+    built_at: Instant,
+    window_start: std::sync::Mutex<Instant>,
+    messages_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    connections: AtomicU64,
+    reconnects: AtomicU64,
+    connection_failures: AtomicU64,
+    handler_errors: AtomicU64,
+    routing_errors: AtomicU64,
+    modules: std::sync::RwLock<Vec<(String, Arc<RuleCounters>)>>,
+    /// Milliseconds between `built_at` and the last incoming message, plus one, 0 for none.
+    last_received: AtomicU64,
+    /// Start of the connection cycles of the last minute.
+    cycles: std::sync::Mutex<VecDeque<Instant>>,
+    channels: std::sync::RwLock<Vec<(String, Arc<ChannelCounters>)>>,
+}
+
+impl ClientStatsTracker {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            built_at: now,
+            window_start: std::sync::Mutex::new(now),
+            messages_sent: AtomicU64::new(0),
+            messages_received: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            connections: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            connection_failures: AtomicU64::new(0),
+            handler_errors: AtomicU64::new(0),
+            routing_errors: AtomicU64::new(0),
+            modules: std::sync::RwLock::new(Vec::new()),
+            last_received: AtomicU64::new(0),
+            cycles: std::sync::Mutex::new(VecDeque::new()),
+            channels: std::sync::RwLock::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn record_sent(&self, message: &Message) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent
+            .fetch_add(message.len() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self, message: &Message) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(message.len() as u64, Ordering::Relaxed);
+        let elapsed = self.built_at.elapsed().as_millis() as u64;
+        self.last_received.store(elapsed + 1, Ordering::Relaxed);
+    }
+
+    /// Time since the last incoming message, `None` before the first one.
+    pub(crate) fn last_message_age(&self) -> Option<Duration> {
+        match self.last_received.load(Ordering::Relaxed) {
+            0 => None,
+            at => Some(
+                self.built_at
+                    .elapsed()
+                    .saturating_sub(Duration::from_millis(at - 1)),
+            ),
+        }
+    }
+
+    /// Records a successful connection, `reconnect` is `false` for the first one.
+    pub(crate) fn record_connection(&self, reconnect: bool) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        if reconnect {
+            self.reconnects.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the start of a connection cycle.
+    pub(crate) fn record_cycle(&self) {
+        let now = Instant::now();
+        let mut cycles = self.cycles.lock().unwrap_or_else(|e| e.into_inner());
+        cycles.push_back(now);
+        prune_cycles(&mut cycles, now);
+    }
+
+    /// Connection cycles started in the last minute.
+    pub(crate) fn cycles_last_minute(&self) -> u32 {
+        let mut cycles = self.cycles.lock().unwrap_or_else(|e| e.into_inner());
+        prune_cycles(&mut cycles, Instant::now());
+        cycles.len() as u32
+    }
+
+    pub(crate) fn record_connection_failure(&self) {
+        self.connection_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_handler_error(&self) {
+        self.handler_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_routing_error(&self) {
+        self.routing_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Wraps a module's rule so the messages it matches are counted under `name`.
+    pub(crate) fn counting_rule(
+        &self,
+        name: &str,
+        rule: Arc<dyn Rule + Send + Sync>,
+    ) -> Arc<dyn Rule + Send + Sync> {
+        let counters = Arc::new(RuleCounters::default());
+        // A poisoned lock only means a panic elsewhere, the list itself is still valid.
+        let mut modules = self.modules.write().unwrap_or_else(|e| e.into_inner());
+        let name = name.rsplit("::").next().unwrap_or(name).to_string();
+        modules.push((name, counters.clone()));
+        Arc::new(CountingRule { rule, counters })
+    }
+
+    /// Counters of the message channel of module `name`, holding up to `capacity` messages.
+    pub(crate) fn channel(&self, name: &str, capacity: usize) -> Arc<ChannelCounters> {
+        let counters = Arc::new(ChannelCounters {
+            capacity,
+            ..ChannelCounters::default()
+        });
+        let name = name.rsplit("::").next().unwrap_or(name).to_string();
+        self.channels
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, counters.clone()));
+        counters
+    }
+
+    pub(crate) fn snapshot(&self) -> ClientStats {
+        let window_start = *self.window_start.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rules = BTreeMap::new();
+        for (name, counters) in self
+            .modules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            let stats: &mut RuleStats = rules.entry(name.clone()).or_default();
+            stats.evaluations += counters.evaluations.load(Ordering::Relaxed);
+            stats.hits += counters.hits.load(Ordering::Relaxed);
+        }
+        let modules = rules
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.hits))
+            .collect();
+        ClientStats {
+            uptime_seconds: self.built_at.elapsed().as_secs_f64(),
+            window_seconds: window_start.elapsed().as_secs_f64(),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            connections: self.connections.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            connection_failures: self.connection_failures.load(Ordering::Relaxed),
+            handler_errors: self.handler_errors.load(Ordering::Relaxed),
+            routing_errors: self.routing_errors.load(Ordering::Relaxed),
+            modules,
+            rules,
+            last_message_age_seconds: self.last_message_age().map(|age| age.as_secs_f64()),
+            connection_cycles_last_minute: self.cycles_last_minute(),
+            channels: self
+                .channels
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(name, counters)| (name.clone(), counters.snapshot()))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        *self.window_start.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        for counter in [
+            &self.messages_sent,
+            &self.messages_received,
+            &self.bytes_sent,
+            &self.bytes_received,
+            &self.connections,
+            &self.reconnects,
+            &self.connection_failures,
+            &self.handler_errors,
+            &self.routing_errors,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        for (_, counters) in self
+            .modules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            counters.evaluations.store(0, Ordering::Relaxed);
+            counters.hits.store(0, Ordering::Relaxed);
+        }
+        for (_, counters) in self
+            .channels
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            counters.reset();
+        }
+    }
+}
+
+impl Default for ClientStatsTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn prune_cycles(cycles: &mut VecDeque<Instant>, now: Instant) {
+    while cycles
+        .front()
+        .is_some_and(|start| now.duration_since(*start) > Duration::from_secs(60))
+    {
+        cycles.pop_front();
+    }
+}
+
+/// Fill level counters of a module's message channel.
+#[derive(Debug, Default)]
+pub(crate) struct ChannelCounters {
+    capacity: usize,
+    high_water_mark: AtomicUsize,
+    overflows: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl ChannelCounters {
+    pub(crate) fn observe_len(&self, len: usize) {
+        self.high_water_mark.fetch_max(len, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_overflow(&self) {
+        self.overflows.fetch_add(1, Ordering::Relaxed);
+        self.observe_len(self.capacity);
+    }
+
+    pub(crate) fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> ChannelStats {
+        ChannelStats {
+            capacity: self.capacity,
+            high_water_mark: self.high_water_mark.load(Ordering::Relaxed),
+            overflows: self.overflows.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn reset(&self) {
+        self.high_water_mark.store(0, Ordering::Relaxed);
+        self.overflows.store(0, Ordering::Relaxed);
+        self.dropped.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+struct RuleCounters {
+    evaluations: AtomicU64,
+    hits: AtomicU64,
+}
+
+/// Counts the messages evaluated and matched by a module's rule.
+struct CountingRule {
+    rule: Arc<dyn Rule + Send + Sync>,
+    counters: Arc<RuleCounters>,
+}
+
+impl Rule for CountingRule {
+    fn call(&self, msg: &Message) -> bool {
+        self.counters.evaluations.fetch_add(1, Ordering::Relaxed);
+        let matched = self.rule.call(msg);
+        if matched {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        matched
+    }
+
+    fn reset(&self) {
+        self.rule.reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_stats_snapshot_and_reset() {
+        let tracker = ClientStatsTracker::new();
+        assert_eq!(tracker.snapshot().last_message_age_seconds, None);
+        let rule: Arc<dyn Rule + Send + Sync> =
+            Arc::new(|msg: &Message| msg.to_text().is_ok_and(|t| t.starts_with("42")));
+        let rule = tracker.counting_rule("crate::modules::DealsApiModule", rule);
+
+        let message = Message::text("42[\"successopenOrder\"]");
+        tracker.record_received(&message);
+        assert!(rule.call(&message));
+        assert!(!rule.call(&Message::text("2")));
+        tracker.record_sent(&Message::text("3"));
+        tracker.record_connection(false);
+        tracker.record_connection(true);
+        tracker.record_handler_error();
+
+        let stats = tracker.snapshot();
+        assert_eq!(stats.messages_received, 1);
+        assert_eq!(stats.bytes_received, message.len() as u64);
+        assert_eq!(stats.messages_sent, 1);
+        assert_eq!(stats.connections, 2);
+        assert_eq!(stats.reconnects, 1);
+        assert_eq!(stats.handler_errors, 1);
+        assert_eq!(stats.modules.get("DealsApiModule"), Some(&1));
+        assert_eq!(
+            stats.rules["DealsApiModule"],
+            RuleStats {
+                evaluations: 2,
+                hits: 1
+            }
+        );
+
+        tracker.reset();
+        let stats = tracker.snapshot();
+        assert_eq!(stats.messages_received, 0);
+        assert_eq!(stats.connections, 0);
+        assert_eq!(stats.modules.get("DealsApiModule"), Some(&0));
+        assert!(stats.last_message_age_seconds.is_some());
+        assert!(stats.uptime_seconds >= stats.window_seconds);
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle state of a client connection, maintained by the `ClientRunner`.
+///
+/// Transitions driven by the runner are
+/// `Connecting -> Connected -> (Degraded) -> Disconnected -> Reconnecting -> Connected`.
+/// `Authenticated` (and `Degraded` for application level failures) is reported by
+/// modules through `RunnerCommand::SetStatus`, since only the platform knows when a
+/// session is actually usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionStatus {
+    /// First connection attempt (or after an explicit disconnect) is in progress.
+    #[default]
+    Connecting,
+    /// The websocket is open, but the session is not authenticated yet.
+    Connected,
+    /// The session is authenticated and ready to be used.
+    Authenticated,
+    /// The websocket is open but the session is not healthy (e.g. the connect callback failed).
+    Degraded,
+    /// The connection was lost and the runner is trying to re-establish it.
+    Reconnecting,
+    /// Not connected and no attempt is in progress (held, shut down or gave up).
+    Disconnected,
+}
+
+impl ConnectionStatus {
+    /// Returns `true` while the websocket is open.
+    pub fn is_connected(&self) -> bool {
+        matches!(
+            self,
+            ConnectionStatus::Connected
+                | ConnectionStatus::Authenticated
+                | ConnectionStatus::Degraded
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionStatus::Connecting => "connecting",
+            ConnectionStatus::Connected => "connected",
+            ConnectionStatus::Authenticated => "authenticated",
+            ConnectionStatus::Degraded => "degraded",
+            ConnectionStatus::Reconnecting => "reconnecting",
+            ConnectionStatus::Disconnected => "disconnected",
+        }
+    }
+}
+
+impl fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ConnectionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "connecting" => Ok(ConnectionStatus::Connecting),
+            "connected" => Ok(ConnectionStatus::Connected),
+            "authenticated" => Ok(ConnectionStatus::Authenticated),
+            "degraded" => Ok(ConnectionStatus::Degraded),
+            "reconnecting" => Ok(ConnectionStatus::Reconnecting),
+            "disconnected" => Ok(ConnectionStatus::Disconnected),
+            other => Err(format!("Unknown connection status: {other}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            ConnectionStatus::Connecting,
+            ConnectionStatus::Connected,
+            ConnectionStatus::Authenticated,
+            ConnectionStatus::Degraded,
+            ConnectionStatus::Reconnecting,
+            ConnectionStatus::Disconnected,
+        ] {
+            assert_eq!(status.to_string().parse::<ConnectionStatus>(), Ok(status));
+        }
+        assert!("online".parse::<ConnectionStatus>().is_err());
+        assert!(ConnectionStatus::Degraded.is_connected());
+        assert!(!ConnectionStatus::Reconnecting.is_connected());
+    }
+}
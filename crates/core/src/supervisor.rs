@@ -0,0 +1,298 @@
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::FutureExt;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{error, info, warn};
+
+use crate::error::CoreResult;
+use crate::traits::Rule;
+
+/// Controls how the client restarts a module whose `run` loop returned an error or panicked.
+///
+/// Restarts are counted inside a sliding `window`: a failure that happens more than
+/// `window` after the previous one starts a new streak. Once a streak reaches
+/// `escalate_after` failures the module waits `escalated_backoff` instead of `backoff`,
+/// and once it reaches `max_restarts` the module is given up on and stays stopped,
+/// which makes its handle return `ModuleStopped` style errors.
+///
+/// The module is recreated with the same channels, so handles obtained before the
+/// restart keep working, while its routing rule is reset to a clean state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPolicy {
+    /// Delay before restarting a failed module.
+    pub backoff: Duration,
+    /// Delay used once the module failed `escalate_after` times in a row.
+    pub escalated_backoff: Duration,
+    /// Number of rapid failures after which `escalated_backoff` is used, 0 disables escalation.
+    pub escalate_after: u32,
+    /// Failures further apart than this do not count towards the same streak.
+    pub window: Duration,
+    /// Maximum number of rapid failures before the module is given up on, 0 means unlimited.
+    pub max_restarts: u32,
+    /// Whether a panic inside the module should trigger a restart.
+    ///
+    /// Only has an effect with `panic = "unwind"`, with `panic = "abort"` a panic ends the
+    /// process before the supervisor sees it.
+    pub restart_on_panic: bool,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            backoff: Duration::from_secs(1),
+            escalated_backoff: Duration::from_secs(60),
+            escalate_after: 5,
+            window: Duration::from_secs(30),
+            max_restarts: 0,
+            restart_on_panic: true,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// A policy that never restarts the module, the first failure stops it for good.
+    pub fn never() -> Self {
+        Self {
+            max_restarts: 1,
+            restart_on_panic: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_escalation(mut self, after: u32, backoff: Duration) -> Self {
+        self.escalate_after = after;
+        self.escalated_backoff = backoff;
+        self
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets the maximum number of rapid failures, 0 means unlimited.
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    pub fn with_restart_on_panic(mut self, restart: bool) -> Self {
+        self.restart_on_panic = restart;
+        self
+    }
+}
+
+/// Why a supervised module stopped running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleExit {
+    /// `run` returned `Ok`, the module is not restarted.
+    Clean,
+    /// `run` returned an error.
+    Failed(String),
+    /// `run` panicked.
+    Panicked(String),
+}
+
+impl fmt::Display for ModuleExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleExit::Clean => f.write_str("exited cleanly"),
+            ModuleExit::Failed(e) => write!(f, "failed: {e}"),
+            ModuleExit::Panicked(e) => write!(f, "panicked: {e}"),
+        }
+    }
+}
+
+/// Tracks the failure streak of one module and decides whether (and when) to restart it.
+#[derive(Debug)]
+pub(crate) struct RestartTracker {
+    policy: RestartPolicy,
+    failures: u32,
+    last_fail: Option<Instant>,
+}
+
+impl RestartTracker {
+    pub(crate) fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            failures: 0,
+            last_fail: None,
+        }
+    }
+
+    /// Records an exit at `now` and returns the delay before the next restart,
+    /// or `None` if the module must stay stopped.
+    pub(crate) fn on_exit(&mut self, exit: &ModuleExit, now: Instant) -> Option<Duration> {
+        match exit {
+            ModuleExit::Clean => return None,
+            ModuleExit::Panicked(_) if !self.policy.restart_on_panic => return None,
+            _ => {}
+        }
+        match self.last_fail {
+            Some(last) if now.duration_since(last) < self.policy.window => self.failures += 1,
+            _ => self.failures = 1,
+        }
+        self.last_fail = Some(now);
+
+        if self.policy.max_restarts > 0 && self.failures >= self.policy.max_restarts {
+            return None;
+        }
+        if self.is_escalated() {
+            Some(self.policy.escalated_backoff)
+        } else {
+            Some(self.policy.backoff)
+        }
+    }
+
+    pub(crate) fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Returns `true` once the current streak uses the escalated backoff.
+    pub(crate) fn is_escalated(&self) -> bool {
+        self.policy.escalate_after > 0 && self.failures >= self.policy.escalate_after
+    }
+}
+
+/// Rule shared between the router and the supervisor so it can be reset on restart.
+pub(crate) struct SharedRule(pub(crate) Arc<dyn Rule + Send + Sync>);
+
+impl Rule for SharedRule {
+    fn call(&self, msg: &Message) -> bool {
+        self.0.call(msg)
+    }
+
+    fn reset(&self) {
+        self.0.reset()
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Runs the future produced by `spawn` until it exits cleanly or the policy gives up,
+/// catching panics and resetting `rule` before each restart.
+pub(crate) async fn supervise<F, Fut>(
+    kind: &'static str,
+    name: &'static str,
+    policy: RestartPolicy,
+    rule: Arc<dyn Rule + Send + Sync>,
+    mut spawn: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CoreResult<()>>,
+{
+    if cfg!(panic = "abort") && policy.restart_on_panic {
+        warn!(target: "Supervisor", "[{} {}] built with panic = \"abort\", a panic will not be restarted", kind, name);
+    }
+    let mut tracker = RestartTracker::new(policy);
+    loop {
+        let exit = match AssertUnwindSafe(spawn()).catch_unwind().await {
+            Ok(Ok(())) => ModuleExit::Clean,
+            Ok(Err(e)) => ModuleExit::Failed(e.to_string()),
+            Err(payload) => ModuleExit::Panicked(panic_message(payload)),
+        };
+        match tracker.on_exit(&exit, Instant::now()) {
+            None if exit == ModuleExit::Clean => {
+                info!(target: "Supervisor", "[{} {}] exited cleanly", kind, name);
+                break;
+            }
+            None => {
+                error!(target: "Supervisor", "[{} {}] {}, not restarting it anymore", kind, name, exit);
+                break;
+            }
+            Some(delay) => {
+                if tracker.is_escalated() {
+                    error!(target: "Supervisor", "[{} {}] {} ({} rapid failures, check module integrity), restarting in {:?}", kind, name, exit, tracker.failures(), delay);
+                } else {
+                    warn!(target: "Supervisor", "[{} {}] {}, restarting in {:?}", kind, name, exit, delay);
+                }
+                rule.reset();
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CoreError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_restart_tracker_escalates_and_gives_up() {
+        let policy = RestartPolicy::default()
+            .with_escalation(2, Duration::from_secs(10))
+            .with_max_restarts(3);
+        let mut tracker = RestartTracker::new(policy);
+        let start = Instant::now();
+        let failed = ModuleExit::Failed("boom".into());
+        assert_eq!(
+            tracker.on_exit(&failed, start),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            tracker.on_exit(&failed, start + Duration::from_secs(1)),
+            Some(Duration::from_secs(10))
+        );
+        // A failure outside the window starts a new streak.
+        assert_eq!(
+            tracker.on_exit(&failed, start + Duration::from_secs(120)),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            tracker.on_exit(&failed, start + Duration::from_secs(121)),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            tracker.on_exit(&failed, start + Duration::from_secs(122)),
+            None
+        );
+        assert_eq!(tracker.on_exit(&ModuleExit::Clean, start), None);
+    }
+
+    #[test]
+    fn test_never_policy_does_not_restart_panics() {
+        let mut tracker = RestartTracker::new(RestartPolicy::never());
+        let panicked = ModuleExit::Panicked("boom".into());
+        assert_eq!(tracker.on_exit(&panicked, Instant::now()), None);
+    }
+
+    #[tokio::test]
+    async fn test_supervise_restarts_after_panic() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        let rule: Arc<dyn Rule + Send + Sync> = Arc::new(|_: &Message| true);
+        let policy = RestartPolicy::default().with_backoff(Duration::from_millis(1));
+        supervise("Test", "module", policy, rule, move || {
+            let counter = counter.clone();
+            async move {
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => panic!("first run panics"),
+                    1 => Err(CoreError::Other("second run fails".into())),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+}
@@ -11,6 +11,19 @@ use std::time::Duration;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, error, info, warn};
 
+mod chaos;
+mod mock_connector;
+mod replay_connector;
+mod stress;
+
+pub use chaos::{ChaosConfig, ChaosMiddleware, ChaosStats};
+pub use mock_connector::{FrameMatcher, MockConnector, Responder, ScriptStep};
+pub use replay_connector::ReplayConnector;
+pub use stress::{
+    AssertionResult, DisconnectInjection, Operation, StreamFactory, StressAssertion, StressReport,
+    StressScenario, Workload, WorkloadReport,
+};
+
 /// Configuration for the testing wrapper
 #[derive(Debug, Clone)]
 pub struct TestingConfig {
@@ -314,6 +327,26 @@ impl<S: AppState> TestingWrapper<S> {
         self.stats.get_stats().await
     }
 
+    /// Run a load `scenario` against the wrapped client, which must have been started.
+    ///
+    /// Injected disconnects reconnect the client unless the scenario brings its own
+    /// reconnection, and the report includes the connection statistics at the end of the run.
+    pub async fn run_scenario(&self, mut scenario: StressScenario) -> StressReport {
+        let client = self.client.clone();
+        scenario.default_reconnect(Arc::new(move || {
+            let client = client.clone();
+            Box::pin(async move { client.reconnect().await })
+        }));
+        let mut report = scenario.run().await;
+        report.connection = Some(self.get_stats().await);
+        if report.passed() {
+            info!("Stress scenario {} passed", report.scenario);
+        } else {
+            warn!("Stress scenario {} failed\n{report}", report.scenario);
+        }
+        report
+    }
+
     /// Get a reference to the underlying client
     pub fn client(&self) -> &Client<S> {
         &self.client
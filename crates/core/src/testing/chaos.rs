@@ -0,0 +1,425 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+
+use crate::error::CoreResult;
+use crate::middleware::{Intercepted, MiddlewareContext, WebSocketMiddleware};
+use crate::traits::AppState;
+
+/// Probabilities, between 0 and 1, of the faults injected by a [`ChaosMiddleware`].
+///
+/// Every fault is drawn independently for each text or binary frame, control frames are left
+/// alone. The same seed and frames always give the same faults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    pub seed: u64,
+    /// Holds the frame for a random time between `min_delay` and `max_delay`, and with it
+    /// the frames after it.
+    pub delay: f64,
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+    pub drop: f64,
+    pub duplicate: f64,
+    /// Holds the frame back and delivers it after the next one.
+    pub reorder: f64,
+    /// Drops the connection instead of delivering the frame.
+    pub disconnect: f64,
+    /// Applies the faults to the frames received from the server.
+    pub incoming: bool,
+    /// Applies the faults to the frames sent by the client.
+    pub outgoing: bool,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            delay: 0.0,
+            min_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(500),
+            drop: 0.0,
+            duplicate: 0.0,
+            reorder: 0.0,
+            disconnect: 0.0,
+            incoming: true,
+            outgoing: false,
+        }
+    }
+}
+
+impl ChaosConfig {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_delay(mut self, probability: f64, min: Duration, max: Duration) -> Self {
+        self.delay = probability;
+        self.min_delay = min;
+        self.max_delay = max.max(min);
+        self
+    }
+
+    pub fn with_drop(mut self, probability: f64) -> Self {
+        self.drop = probability;
+        self
+    }
+
+    pub fn with_duplicate(mut self, probability: f64) -> Self {
+        self.duplicate = probability;
+        self
+    }
+
+    pub fn with_reorder(mut self, probability: f64) -> Self {
+        self.reorder = probability;
+        self
+    }
+
+    pub fn with_disconnect(mut self, probability: f64) -> Self {
+        self.disconnect = probability;
+        self
+    }
+
+    /// Which directions the faults apply to, only incoming frames by default.
+    pub fn with_directions(mut self, incoming: bool, outgoing: bool) -> Self {
+        self.incoming = incoming;
+        self.outgoing = outgoing;
+        self
+    }
+}
+
+/// Faults injected so far by a [`ChaosMiddleware`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChaosStats {
+    pub delayed: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+    pub reordered: u64,
+    pub disconnects: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    delayed: AtomicU64,
+    dropped: AtomicU64,
+    duplicated: AtomicU64,
+    reordered: AtomicU64,
+    disconnects: AtomicU64,
+}
+
+/// Random choices and the frame held back of one direction.
+struct Lane {
+    rng: StdRng,
+    held: Option<Message>,
+}
+
+struct Shared {
+    enabled: AtomicBool,
+    incoming: Mutex<Lane>,
+    outgoing: Mutex<Lane>,
+    counters: Counters,
+}
+
+/// The faults drawn for a frame.
+struct Faults {
+    delay: Option<Duration>,
+    disconnect: bool,
+    drop: bool,
+    duplicate: bool,
+    reorder: bool,
+}
+
+/// Middleware injecting network faults: delayed, dropped, duplicated and reordered frames, and
+/// dropped connections, so reconnection and reconciliation logic can be tested.
+///
+/// Incoming and outgoing frames draw from separate generators seeded from
+/// [`ChaosConfig::seed`], so a test sees the same faults on every run as long as the frames
+/// come in the same order. Clones share their generators, switch and statistics, so a test
+/// can keep one to read [`ChaosMiddleware::stats`] after handing another to the client.
+///
+/// ```no_run
+/// # use binary_options_tools_core::testing::{ChaosConfig, ChaosMiddleware};
+/// # use std::time::Duration;
+/// let chaos = ChaosMiddleware::new(
+///     ChaosConfig::new(42)
+///         .with_drop(0.05)
+///         .with_reorder(0.05)
+///         .with_delay(0.1, Duration::from_millis(50), Duration::from_millis(800)),
+/// );
+/// // No faults until the client is ready
+/// chaos.set_enabled(false);
+/// ```
+#[derive(Clone)]
+pub struct ChaosMiddleware {
+    config: ChaosConfig,
+    shared: Arc<Shared>,
+}
+
+impl ChaosMiddleware {
+    pub fn new(config: ChaosConfig) -> Self {
+        let lane = |seed| {
+            Mutex::new(Lane {
+                rng: StdRng::seed_from_u64(seed),
+                held: None,
+            })
+        };
+        let shared = Shared {
+            enabled: AtomicBool::new(true),
+            incoming: lane(config.seed),
+            outgoing: lane(config.seed.wrapping_add(1)),
+            counters: Counters::default(),
+        };
+        Self {
+            config,
+            shared: Arc::new(shared),
+        }
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    /// Turns the faults on or off, frames held back are still delivered with the next one.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.shared.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.shared.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            delayed: self.shared.counters.delayed.load(Ordering::Relaxed),
+            dropped: self.shared.counters.dropped.load(Ordering::Relaxed),
+            duplicated: self.shared.counters.duplicated.load(Ordering::Relaxed),
+            reordered: self.shared.counters.reordered.load(Ordering::Relaxed),
+            disconnects: self.shared.counters.disconnects.load(Ordering::Relaxed),
+        }
+    }
+
+    fn draw(&self, rng: &mut StdRng) -> Faults {
+        let config = &self.config;
+        let mut chance =
+            |probability: f64| probability > 0.0 && rng.random_bool(probability.min(1.0));
+        // Drawn in a fixed order so the faults only depend on the seed
+        let delayed = chance(config.delay);
+        let disconnect = chance(config.disconnect);
+        let drop = chance(config.drop);
+        let duplicate = chance(config.duplicate);
+        let reorder = chance(config.reorder);
+        let delay = delayed.then(|| {
+            if config.min_delay >= config.max_delay {
+                config.min_delay
+            } else {
+                rng.random_range(config.min_delay..=config.max_delay)
+            }
+        });
+        Faults {
+            delay,
+            disconnect,
+            drop,
+            duplicate,
+            reorder,
+        }
+    }
+
+    async fn intercept(&self, message: Message, lane: &Mutex<Lane>, applies: bool) -> Intercepted {
+        let chaotic = applies
+            && self.is_enabled()
+            && matches!(message, Message::Text(_) | Message::Binary(_));
+        // The lock is released before sleeping
+        let (faults, held) = {
+            let mut lane = lane.lock().expect("chaos lane lock poisoned");
+            let faults = chaotic.then(|| self.draw(&mut lane.rng));
+            (faults, lane.held.take())
+        };
+        let Some(faults) = faults else {
+            return match held {
+                Some(held) => Intercepted::Frames(vec![message, held]),
+                None => Intercepted::Pass(message),
+            };
+        };
+        if let Some(delay) = faults.delay {
+            self.shared.counters.delayed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(delay).await;
+        }
+        if faults.disconnect {
+            self.shared
+                .counters
+                .disconnects
+                .fetch_add(1, Ordering::Relaxed);
+            debug!(target: "ChaosMiddleware", "Dropping the connection");
+            return Intercepted::Disconnect;
+        }
+        let mut frames = Vec::with_capacity(3);
+        if faults.drop {
+            self.shared.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        } else if faults.reorder && held.is_none() {
+            self.shared
+                .counters
+                .reordered
+                .fetch_add(1, Ordering::Relaxed);
+            if faults.duplicate {
+                self.shared
+                    .counters
+                    .duplicated
+                    .fetch_add(1, Ordering::Relaxed);
+                frames.push(message.clone());
+            }
+            lane.lock().expect("chaos lane lock poisoned").held = Some(message);
+            return Intercepted::Frames(frames);
+        } else {
+            if faults.duplicate {
+                self.shared
+                    .counters
+                    .duplicated
+                    .fetch_add(1, Ordering::Relaxed);
+                frames.push(message.clone());
+            }
+            frames.push(message);
+        }
+        frames.extend(held);
+        Intercepted::Frames(frames)
+    }
+}
+
+#[async_trait]
+impl<S: AppState> WebSocketMiddleware<S> for ChaosMiddleware {
+    async fn intercept_send(&self, message: Message, _: &MiddlewareContext<S>) -> Intercepted {
+        self.intercept(message, &self.shared.outgoing, self.config.outgoing)
+            .await
+    }
+
+    async fn intercept_receive(&self, message: Message, _: &MiddlewareContext<S>) -> Intercepted {
+        self.intercept(message, &self.shared.incoming, self.config.incoming)
+            .await
+    }
+
+    async fn on_disconnect(&self, _: &MiddlewareContext<S>) -> CoreResult<()> {
+        // Frames held back belong to the lost connection
+        for lane in [&self.shared.incoming, &self.shared.outgoing] {
+            lane.lock().expect("chaos lane lock poisoned").held = None;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareStack;
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct TestState;
+
+    #[async_trait]
+    impl AppState for TestState {
+        async fn clear_temporal_data(&self) {}
+    }
+
+    async fn receive_all(config: ChaosConfig, count: usize) -> Vec<Option<Vec<String>>> {
+        let (sender, _receiver) = kanal::bounded_async(1);
+        let context = MiddlewareContext::new(Arc::new(TestState), sender);
+        let mut stack = MiddlewareStack::new();
+        stack.add_layer(Box::new(ChaosMiddleware::new(config)));
+        let mut out = Vec::new();
+        for i in 0..count {
+            let message = Message::text(i.to_string());
+            let frames = stack
+                .intercept_receive(Intercepted::Pass(message), &context)
+                .await
+                .into_frames()
+                .map(|frames| frames.map(|m| m.to_text().unwrap().to_string()).collect());
+            out.push(frames);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_faults() {
+        let all = |config: ChaosConfig| receive_all(config, 3);
+        let frames = |f: &[&[&str]]| -> Vec<Option<Vec<String>>> {
+            f.iter()
+                .map(|f| Some(f.iter().map(|s| s.to_string()).collect()))
+                .collect()
+        };
+        assert_eq!(
+            all(ChaosConfig::new(1)).await,
+            frames(&[&["0"], &["1"], &["2"]])
+        );
+        assert_eq!(
+            all(ChaosConfig::new(1).with_drop(1.0)).await,
+            frames(&[&[], &[], &[]])
+        );
+        assert_eq!(
+            all(ChaosConfig::new(1).with_duplicate(1.0)).await,
+            frames(&[&["0", "0"], &["1", "1"], &["2", "2"]])
+        );
+        // Only one frame is held back at a time
+        assert_eq!(
+            all(ChaosConfig::new(1).with_reorder(1.0)).await,
+            frames(&[&[], &["1", "0"], &[]])
+        );
+        assert_eq!(
+            all(ChaosConfig::new(1).with_disconnect(1.0)).await,
+            [None, None, None]
+        );
+        // Outgoing frames only
+        assert_eq!(
+            all(ChaosConfig::new(1)
+                .with_drop(1.0)
+                .with_directions(false, true))
+            .await,
+            frames(&[&["0"], &["1"], &["2"]])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_same_faults() {
+        let config = |seed| {
+            ChaosConfig::new(seed)
+                .with_drop(0.2)
+                .with_duplicate(0.2)
+                .with_reorder(0.2)
+                .with_disconnect(0.05)
+        };
+        let first = receive_all(config(7), 200).await;
+        assert_eq!(first, receive_all(config(7), 200).await);
+        assert_ne!(first, receive_all(config(8), 200).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay() {
+        let chaos = ChaosMiddleware::new(ChaosConfig::new(3).with_delay(
+            1.0,
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+        ));
+        let (sender, _receiver) = kanal::bounded_async(1);
+        let context = MiddlewareContext::new(Arc::new(TestState), sender);
+        let start = tokio::time::Instant::now();
+        let frames = chaos
+            .intercept_receive(Message::text("tick"), &context)
+            .await;
+        assert_eq!(frames, Intercepted::Frames(vec![Message::text("tick")]));
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+        assert_eq!(chaos.stats().delayed, 1);
+
+        chaos.set_enabled(false);
+        let frames = chaos
+            .intercept_receive(Message::text("tick"), &context)
+            .await;
+        assert_eq!(frames, Intercepted::Pass(Message::text("tick")));
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Notify};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+use crate::connector::{Connector, ConnectorError, ConnectorResult, WsStream};
+use crate::traits::AppState;
+
+/// Matches a frame sent by the client.
+pub type FrameMatcher = Arc<dyn Fn(&Message) -> bool + Send + Sync>;
+
+/// Builds the frames answering a client frame, `None` when it does not apply.
+pub type Responder = Arc<dyn Fn(&Message) -> Option<Vec<Message>> + Send + Sync>;
+
+/// A step of the scripted server conversation.
+#[derive(Clone)]
+pub enum ScriptStep {
+    /// Sends a frame to the client.
+    Send(Message),
+    /// Waits for a client frame accepted by the matcher, frames before it are only recorded.
+    Expect(FrameMatcher),
+    /// Waits before the next step.
+    Delay(Duration),
+    /// Closes the connection.
+    Close,
+}
+
+#[derive(Default)]
+struct Shared {
+    received: Mutex<Vec<Message>>,
+    received_notify: Notify,
+    live: Mutex<Option<mpsc::UnboundedSender<Message>>>,
+    connections: AtomicUsize,
+}
+
+/// A [`Connector`] backed by a local WebSocket server that plays a script instead of talking to
+/// a real platform, so modules can be tested end to end without credentials.
+///
+/// Every connection (reconnects included) replays the script from the start. Responders answer
+/// client frames at any point of the conversation, e.g. an order request with its confirmation,
+/// and [`MockConnector::push`] injects frames into the live connection from the test.
+///
+/// ```no_run
+/// # use binary_options_tools_core::testing::MockConnector;
+/// # use tokio_tungstenite::tungstenite::Message;
+/// let connector = MockConnector::new()
+///     .send(Message::text("0{\"sid\":\"mock\"}"))
+///     .expect_text("40")
+///     .send(Message::text("40{\"sid\":\"mock\"}"))
+///     .respond(|msg| match msg.to_text() {
+///         Ok("2") => Some(vec![Message::text("3")]),
+///         _ => None,
+///     });
+/// ```
+#[derive(Clone, Default)]
+pub struct MockConnector {
+    script: Vec<ScriptStep>,
+    responders: Vec<Responder>,
+    shared: Arc<Shared>,
+}
+
+impl MockConnector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a step to the script.
+    pub fn step(mut self, step: ScriptStep) -> Self {
+        self.script.push(step);
+        self
+    }
+
+    /// Appends steps to the script.
+    pub fn steps(mut self, steps: impl IntoIterator<Item = ScriptStep>) -> Self {
+        self.script.extend(steps);
+        self
+    }
+
+    /// Sends `message` to the client.
+    pub fn send(self, message: Message) -> Self {
+        self.step(ScriptStep::Send(message))
+    }
+
+    /// Waits for a client frame accepted by `matcher`.
+    pub fn expect(self, matcher: impl Fn(&Message) -> bool + Send + Sync + 'static) -> Self {
+        self.step(ScriptStep::Expect(Arc::new(matcher)))
+    }
+
+    /// Waits for a client text frame starting with `prefix`.
+    pub fn expect_text(self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        self.expect(
+            move |msg| matches!(msg, Message::Text(text) if text.starts_with(prefix.as_str())),
+        )
+    }
+
+    /// Waits `duration` before the next step.
+    pub fn delay(self, duration: Duration) -> Self {
+        self.step(ScriptStep::Delay(duration))
+    }
+
+    /// Closes the connection once the previous steps ran.
+    pub fn close(self) -> Self {
+        self.step(ScriptStep::Close)
+    }
+
+    /// Answers every client frame `responder` returns frames for.
+    pub fn respond(
+        mut self,
+        responder: impl Fn(&Message) -> Option<Vec<Message>> + Send + Sync + 'static,
+    ) -> Self {
+        self.responders.push(Arc::new(responder));
+        self
+    }
+
+    /// Sends `message` on the live connection, returns `false` if there is none.
+    pub fn push(&self, message: Message) -> bool {
+        self.shared
+            .live
+            .lock()
+            .expect("live connection lock poisoned")
+            .as_ref()
+            .is_some_and(|live| live.send(message).is_ok())
+    }
+
+    /// Frames sent by the client so far, across all connections.
+    pub fn received(&self) -> Vec<Message> {
+        self.shared
+            .received
+            .lock()
+            .expect("received frames lock poisoned")
+            .clone()
+    }
+
+    /// Text frames sent by the client so far.
+    pub fn received_text(&self) -> Vec<String> {
+        self.received()
+            .iter()
+            .filter_map(|msg| msg.to_text().ok().map(str::to_string))
+            .collect()
+    }
+
+    /// Waits up to `timeout` for a client frame accepted by `matcher`, including the frames
+    /// received before the call.
+    pub async fn wait_for(
+        &self,
+        timeout: Duration,
+        matcher: impl Fn(&Message) -> bool,
+    ) -> Option<Message> {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.shared.received_notify.notified();
+                if let Some(msg) = self.received().into_iter().find(|msg| matcher(msg)) {
+                    return msg;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .ok()
+    }
+
+    /// Number of connections opened so far.
+    pub fn connections(&self) -> usize {
+        self.shared.connections.load(Ordering::SeqCst)
+    }
+}
+
+impl Shared {
+    fn record(&self, message: &Message) {
+        self.received
+            .lock()
+            .expect("received frames lock poisoned")
+            .push(message.clone());
+        self.received_notify.notify_waiters();
+    }
+}
+
+#[async_trait]
+impl<S: AppState> Connector<S> for MockConnector {
+    async fn connect(&self, _state: Arc<S>) -> ConnectorResult<WsStream> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| ConnectorError::Custom(format!("Failed to start mock server: {e}")))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ConnectorError::Custom(format!("Failed to start mock server: {e}")))?;
+        tokio::spawn(serve(
+            listener,
+            self.script.clone(),
+            self.responders.clone(),
+            self.shared.clone(),
+        ));
+        let (stream, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .map_err(|e| ConnectorError::ConnectionFailed(Box::new(e)))?;
+        self.shared.connections.fetch_add(1, Ordering::SeqCst);
+        Ok(stream)
+    }
+
+    async fn disconnect(&self) -> ConnectorResult<()> {
+        // Dropping the sender ends the server side of the connection.
+        self.shared
+            .live
+            .lock()
+            .expect("live connection lock poisoned")
+            .take();
+        Ok(())
+    }
+}
+
+/// Serves a single connection: runs the script and the responders until either side closes.
+async fn serve(
+    listener: TcpListener,
+    script: Vec<ScriptStep>,
+    responders: Vec<Responder>,
+    shared: Arc<Shared>,
+) {
+    let ws = match listener.accept().await {
+        Ok((tcp, _)) => tokio_tungstenite::accept_async(tcp).await,
+        Err(e) => {
+            warn!(target: "MockConnector", "Failed to accept connection: {}", e);
+            return;
+        }
+    };
+    let (mut sink, mut stream) = match ws {
+        Ok(ws) => ws.split(),
+        Err(e) => {
+            warn!(target: "MockConnector", "WebSocket handshake failed: {}", e);
+            return;
+        }
+    };
+    let (live_tx, mut live_rx) = mpsc::unbounded_channel();
+    *shared.live.lock().expect("live connection lock poisoned") = Some(live_tx);
+
+    let mut steps = script.into_iter();
+    let mut waiting: Option<FrameMatcher> = None;
+    'conversation: loop {
+        while waiting.is_none() {
+            match steps.next() {
+                Some(ScriptStep::Send(msg)) => {
+                    if sink.send(msg).await.is_err() {
+                        break 'conversation;
+                    }
+                }
+                Some(ScriptStep::Expect(matcher)) => waiting = Some(matcher),
+                Some(ScriptStep::Delay(duration)) => tokio::time::sleep(duration).await,
+                Some(ScriptStep::Close) => {
+                    let _ = sink.close().await;
+                    break 'conversation;
+                }
+                None => break,
+            }
+        }
+
+        tokio::select! {
+            incoming = stream.next() => {
+                let msg = match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(msg)) => msg,
+                };
+                shared.record(&msg);
+                for responder in &responders {
+                    for reply in responder(&msg).unwrap_or_default() {
+                        if sink.send(reply).await.is_err() {
+                            break 'conversation;
+                        }
+                    }
+                }
+                if waiting.as_ref().is_some_and(|matcher| matcher(&msg)) {
+                    waiting = None;
+                }
+            }
+            pushed = live_rx.recv() => {
+                // `None` means the connector disconnected.
+                let Some(msg) = pushed else { break };
+                if sink.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    debug!(target: "MockConnector", "Mock connection closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_script_waits_for_expected_frames() {
+        let connector = MockConnector::new()
+            .send(Message::text("hello"))
+            .expect_text("ready")
+            .send(Message::text("go"))
+            .respond(|msg| match msg.to_text() {
+                Ok("ping") => Some(vec![Message::text("pong")]),
+                _ => None,
+            });
+        let mut ws = Connector::<()>::connect(&connector, Arc::new(()))
+            .await
+            .unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::text("hello"));
+
+        ws.send(Message::text("ping")).await.unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::text("pong"));
+        ws.send(Message::text("ready")).await.unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::text("go"));
+
+        assert!(connector.push(Message::text("pushed")));
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::text("pushed"));
+        assert_eq!(connector.received_text(), vec!["ping", "ready"]);
+        assert_eq!(connector.connections(), 1);
+    }
+}
@@ -0,0 +1,123 @@
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::connector::{Connector, ConnectorResult, WsStream};
+use crate::frame_dump::{read_frame_dump, FrameDirection, FrameKind, FrameRecord};
+use crate::testing::{MockConnector, ScriptStep};
+use crate::traits::AppState;
+
+/// A [`Connector`] playing back the frames a client received in a dump written by
+/// [`FrameDumpMiddleware`](crate::frame_dump::FrameDumpMiddleware), to reproduce a reported
+/// protocol issue without an account.
+///
+/// Received frames are sent in their order with their original spacing divided by the speed,
+/// a speed of `0` sends them without delay. Frames the client sends are not checked, they are
+/// recorded by the underlying [`MockConnector`], see [`ReplayConnector::mock`].
+///
+/// ```no_run
+/// # use binary_options_tools_core::testing::ReplayConnector;
+/// let connector = ReplayConnector::open("frames.jsonl.gz", 0.0).expect("readable dump");
+/// ```
+#[derive(Clone)]
+pub struct ReplayConnector {
+    mock: MockConnector,
+}
+
+impl ReplayConnector {
+    /// Replays the received frames of `records`.
+    pub fn new(records: &[FrameRecord], speed: f64) -> Self {
+        let mut steps = Vec::new();
+        let mut previous = None;
+        for record in records.iter().filter(|r| r.dir == FrameDirection::In) {
+            if let Some(previous) = previous.replace(record.ts) {
+                let gap = record.ts.saturating_sub(previous);
+                if speed > 0.0 && gap > 0 {
+                    steps.push(ScriptStep::Delay(Duration::from_secs_f64(
+                        gap as f64 / 1000.0 / speed,
+                    )));
+                }
+            }
+            match (record.kind, record.to_message()) {
+                (FrameKind::Close, _) => steps.push(ScriptStep::Close),
+                (_, Some(message)) => steps.push(ScriptStep::Send(message)),
+                (_, None) => {}
+            }
+        }
+        Self {
+            mock: MockConnector::new().steps(steps),
+        }
+    }
+
+    /// Replays the received frames of the dump at `path`.
+    pub fn open(path: impl AsRef<Path>, speed: f64) -> io::Result<Self> {
+        Ok(Self::new(&read_frame_dump(path)?, speed))
+    }
+
+    /// The connector serving the frames, to inspect the frames sent by the client or push
+    /// more frames.
+    pub fn mock(&self) -> &MockConnector {
+        &self.mock
+    }
+}
+
+#[async_trait]
+impl<S: AppState> Connector<S> for ReplayConnector {
+    async fn connect(&self, state: Arc<S>) -> ConnectorResult<WsStream> {
+        Connector::<S>::connect(&self.mock, state).await
+    }
+
+    async fn disconnect(&self) -> ConnectorResult<()> {
+        Connector::<S>::disconnect(&self.mock).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn record(ts: u64, dir: FrameDirection, message: Message) -> FrameRecord {
+        FrameRecord::from_message(dir, ts, &message).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replays_received_frames() {
+        let records = vec![
+            record(1_000, FrameDirection::In, Message::text("0{\"sid\":\"x\"}")),
+            record(1_001, FrameDirection::Out, Message::text("40")),
+            record(
+                1_020,
+                FrameDirection::In,
+                Message::text("40{\"sid\":\"x\"}"),
+            ),
+            record(1_030, FrameDirection::In, Message::binary(vec![1, 2, 3])),
+        ];
+        let connector = ReplayConnector::new(&records, 10.0);
+        let mut ws = Connector::<()>::connect(&connector, Arc::new(()))
+            .await
+            .unwrap();
+        assert_eq!(
+            ws.next().await.unwrap().unwrap(),
+            Message::text("0{\"sid\":\"x\"}")
+        );
+        ws.send(Message::text("40")).await.unwrap();
+        assert_eq!(
+            ws.next().await.unwrap().unwrap(),
+            Message::text("40{\"sid\":\"x\"}")
+        );
+        assert_eq!(
+            ws.next().await.unwrap().unwrap(),
+            Message::binary(vec![1, 2, 3])
+        );
+        let sent = connector
+            .mock()
+            .wait_for(Duration::from_secs(1), |msg| msg.is_text())
+            .await;
+        assert_eq!(sent, Some(Message::text("40")));
+    }
+}
@@ -0,0 +1,659 @@
+//! Scripted load scenarios for a connected client.
+//!
+//! A [`StressScenario`] runs its [`Workload`]s side by side for a fixed duration, optionally
+//! forcing reconnections at random intervals, then checks its [`StressAssertion`]s against
+//! the measured latencies and produces a [`StressReport`].
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use rand::RngExt;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::error::CoreResult;
+use crate::statistics::ConnectionStats;
+
+/// A single measured operation, such as placing a trade.
+pub type Operation = Arc<dyn Fn() -> BoxFuture<'static, CoreResult<()>> + Send + Sync>;
+/// Opens a stream whose items are measured operations, such as the candles of a subscription.
+pub type StreamFactory = Arc<dyn Fn() -> BoxStream<'static, CoreResult<()>> + Send + Sync>;
+
+enum Load {
+    Rate {
+        per_minute: u32,
+        operation: Operation,
+    },
+    Streams {
+        count: usize,
+        factory: StreamFactory,
+    },
+}
+
+/// A named load applied during a scenario.
+pub struct Workload {
+    name: String,
+    load: Load,
+    timeout: Duration,
+}
+
+impl Workload {
+    /// Starts `operation` `per_minute` times a minute, whether the previous ones finished or
+    /// not. Its latency is the time the operation takes.
+    pub fn rate<F, Fut>(name: impl ToString, per_minute: u32, operation: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = CoreResult<()>> + Send + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            load: Load::Rate {
+                per_minute: per_minute.max(1),
+                operation: Arc::new(move || Box::pin(operation())),
+            },
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Keeps `count` streams open, opened again when they end or fail. Its latency is the
+    /// time between two items of a stream, or until the first one.
+    pub fn streams<F, St>(name: impl ToString, count: usize, factory: F) -> Self
+    where
+        F: Fn() -> St + Send + Sync + 'static,
+        St: futures_util::Stream<Item = CoreResult<()>> + Send + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            load: Load::Streams {
+                count,
+                factory: Arc::new(move || Box::pin(factory())),
+            },
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Operations, or stream items, taking longer than `timeout` count as timed out.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Forces a reconnection at a random time between `min_interval` and `max_interval` after
+/// the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DisconnectInjection {
+    pub min_interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl DisconnectInjection {
+    fn next_delay(&self) -> Duration {
+        let (min, max) = (self.min_interval, self.max_interval.max(self.min_interval));
+        if min == max {
+            return min;
+        }
+        rand::rng().random_range(min..=max)
+    }
+}
+
+/// A check of the results of a scenario.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StressAssertion {
+    /// The `percentile` (0 to 100) latency of `workload` is at most `max`.
+    Latency {
+        workload: String,
+        percentile: f64,
+        max: Duration,
+    },
+    /// At most this share (0 to 1) of the operations of `workload` fail or time out.
+    ErrorRate { workload: String, max: f64 },
+    /// `workload` completes at least this many operations per minute.
+    Throughput {
+        workload: String,
+        min_per_minute: f64,
+    },
+}
+
+impl StressAssertion {
+    fn workload(&self) -> &str {
+        match self {
+            Self::Latency { workload, .. }
+            | Self::ErrorRate { workload, .. }
+            | Self::Throughput { workload, .. } => workload,
+        }
+    }
+
+    fn check(&self, report: Option<&WorkloadReport>) -> AssertionResult {
+        let Some(report) = report else {
+            return AssertionResult {
+                assertion: self.clone(),
+                passed: false,
+                actual: format!("no workload named {}", self.workload()),
+            };
+        };
+        let (passed, actual) = match self {
+            Self::Latency {
+                percentile, max, ..
+            } => match report.percentile(*percentile) {
+                Some(latency) => (latency <= *max, format!("{latency:?}")),
+                None => (false, "no completed operation".to_string()),
+            },
+            Self::ErrorRate { max, .. } => {
+                let rate = report.error_rate();
+                (rate <= *max, format!("{:.2}%", rate * 100.0))
+            }
+            Self::Throughput { min_per_minute, .. } => (
+                report.per_minute >= *min_per_minute,
+                format!("{:.1}/min", report.per_minute),
+            ),
+        };
+        AssertionResult {
+            assertion: self.clone(),
+            passed,
+            actual,
+        }
+    }
+}
+
+impl fmt::Display for StressAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latency {
+                workload,
+                percentile,
+                max,
+            } => write!(f, "{workload} p{percentile} latency <= {max:?}"),
+            Self::ErrorRate { workload, max } => {
+                write!(f, "{workload} error rate <= {:.2}%", max * 100.0)
+            }
+            Self::Throughput {
+                workload,
+                min_per_minute,
+            } => write!(f, "{workload} throughput >= {min_per_minute}/min"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssertionResult {
+    pub assertion: StressAssertion,
+    pub passed: bool,
+    /// Measured value, or why it couldn't be measured.
+    pub actual: String,
+}
+
+/// Results of a workload.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WorkloadReport {
+    pub name: String,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub timed_out: u64,
+    /// Successful operations per minute.
+    pub per_minute: f64,
+    pub p50: Option<Duration>,
+    pub p95: Option<Duration>,
+    pub p99: Option<Duration>,
+    pub max: Option<Duration>,
+    /// Latencies of the successful operations, sorted.
+    #[serde(skip)]
+    latencies: Vec<Duration>,
+}
+
+impl WorkloadReport {
+    fn new(name: &str, mut latencies: Vec<Duration>, errors: Errors, elapsed: Duration) -> Self {
+        latencies.sort_unstable();
+        let mut report = Self {
+            name: name.to_string(),
+            succeeded: latencies.len() as u64,
+            failed: errors.failed,
+            timed_out: errors.timed_out,
+            per_minute: latencies.len() as f64 * 60.0 / elapsed.as_secs_f64().max(f64::EPSILON),
+            latencies,
+            ..Default::default()
+        };
+        report.p50 = report.percentile(50.0);
+        report.p95 = report.percentile(95.0);
+        report.p99 = report.percentile(99.0);
+        report.max = report.latencies.last().copied();
+        report
+    }
+
+    /// Nearest-rank `percentile` of the latencies, `None` without successful operations.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.latencies.len() as f64).ceil();
+        let index = (rank as usize).clamp(1, self.latencies.len()) - 1;
+        Some(self.latencies[index])
+    }
+
+    /// Share of the operations that failed or timed out.
+    pub fn error_rate(&self) -> f64 {
+        let errors = self.failed + self.timed_out;
+        let total = self.succeeded + errors;
+        if total == 0 {
+            return 0.0;
+        }
+        errors as f64 / total as f64
+    }
+}
+
+/// Pass/fail report of a scenario.
+#[derive(Debug, Clone, Serialize)]
+pub struct StressReport {
+    pub scenario: String,
+    pub duration: Duration,
+    pub workloads: Vec<WorkloadReport>,
+    pub disconnects_injected: u32,
+    pub assertions: Vec<AssertionResult>,
+    /// Statistics of the connection at the end of the scenario, when run by a
+    /// [`TestingWrapper`](super::TestingWrapper).
+    pub connection: Option<ConnectionStats>,
+}
+
+impl StressReport {
+    /// Whether every assertion passed.
+    pub fn passed(&self) -> bool {
+        self.assertions.iter().all(|result| result.passed)
+    }
+
+    pub fn workload(&self, name: &str) -> Option<&WorkloadReport> {
+        self.workloads.iter().find(|workload| workload.name == name)
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed() { "PASSED" } else { "FAILED" };
+        writeln!(
+            f,
+            "Scenario {} {} in {:.1}s, {} disconnects injected",
+            self.scenario,
+            verdict,
+            self.duration.as_secs_f64(),
+            self.disconnects_injected
+        )?;
+        for workload in &self.workloads {
+            writeln!(
+                f,
+                "  {}: {} ok, {} failed, {} timed out, {:.1}/min, p50 {:?}, p95 {:?}, p99 {:?}, max {:?}",
+                workload.name,
+                workload.succeeded,
+                workload.failed,
+                workload.timed_out,
+                workload.per_minute,
+                workload.p50.unwrap_or_default(),
+                workload.p95.unwrap_or_default(),
+                workload.p99.unwrap_or_default(),
+                workload.max.unwrap_or_default()
+            )?;
+        }
+        for result in &self.assertions {
+            let status = if result.passed { "ok" } else { "FAIL" };
+            writeln!(
+                f,
+                "  [{status}] {} (actual {})",
+                result.assertion, result.actual
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Errors {
+    failed: u64,
+    timed_out: u64,
+}
+
+#[derive(Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    errors: Errors,
+}
+
+impl Samples {
+    fn record(
+        &mut self,
+        result: Result<CoreResult<()>, tokio::time::error::Elapsed>,
+        took: Duration,
+    ) {
+        match result {
+            Ok(Ok(())) => self.latencies.push(took),
+            Ok(Err(e)) => {
+                debug!(target: "StressScenario", "Operation failed: {e}");
+                self.errors.failed += 1;
+            }
+            Err(_) => self.errors.timed_out += 1,
+        }
+    }
+}
+
+/// A load test, see the [module](self) docs.
+pub struct StressScenario {
+    name: String,
+    duration: Duration,
+    workloads: Vec<Workload>,
+    disconnects: Option<(DisconnectInjection, Option<Operation>)>,
+    assertions: Vec<StressAssertion>,
+}
+
+impl StressScenario {
+    pub fn new(name: impl ToString, duration: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            duration,
+            workloads: Vec::new(),
+            disconnects: None,
+            assertions: Vec::new(),
+        }
+    }
+
+    pub fn with_workload(mut self, workload: Workload) -> Self {
+        self.workloads.push(workload);
+        self
+    }
+
+    /// Forces reconnections with `reconnect`. Scenarios run by a
+    /// [`TestingWrapper`](super::TestingWrapper) reconnect its client if `reconnect` is `None`.
+    pub fn with_disconnects<F, Fut>(
+        mut self,
+        injection: DisconnectInjection,
+        reconnect: Option<F>,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = CoreResult<()>> + Send + 'static,
+    {
+        let reconnect =
+            reconnect.map(|reconnect| -> Operation { Arc::new(move || Box::pin(reconnect())) });
+        self.disconnects = Some((injection, reconnect));
+        self
+    }
+
+    pub fn with_assertion(mut self, assertion: StressAssertion) -> Self {
+        self.assertions.push(assertion);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the reconnection of the injected disconnects, if the scenario has none.
+    pub(crate) fn default_reconnect(&mut self, reconnect: Operation) {
+        if let Some((_, op @ None)) = &mut self.disconnects {
+            *op = Some(reconnect);
+        }
+    }
+
+    /// Runs the workloads for the duration of the scenario and checks the assertions.
+    pub async fn run(&self) -> StressReport {
+        info!(target: "StressScenario", "Running scenario {} for {:?}", self.name, self.duration);
+        let start = Instant::now();
+        let deadline = start + self.duration;
+        let mut tasks = JoinSet::new();
+        let mut samples = Vec::new();
+        for workload in &self.workloads {
+            let shared = Arc::new(Mutex::new(Samples::default()));
+            samples.push(shared.clone());
+            match &workload.load {
+                Load::Rate {
+                    per_minute,
+                    operation,
+                } => {
+                    let period = Duration::from_secs(60) / *per_minute;
+                    tasks.spawn(run_rate(
+                        operation.clone(),
+                        period,
+                        workload.timeout,
+                        deadline,
+                        shared,
+                    ));
+                }
+                Load::Streams { count, factory } => {
+                    for _ in 0..*count {
+                        tasks.spawn(run_stream(
+                            factory.clone(),
+                            workload.timeout,
+                            deadline,
+                            shared.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        let disconnects = match &self.disconnects {
+            Some((injection, Some(reconnect))) => {
+                inject_disconnects(*injection, reconnect.clone(), deadline).await
+            }
+            Some((_, None)) => {
+                warn!(target: "StressScenario", "No reconnection to inject disconnects with");
+                tokio::time::sleep_until(deadline).await;
+                0
+            }
+            None => {
+                tokio::time::sleep_until(deadline).await;
+                0
+            }
+        };
+        while tasks.join_next().await.is_some() {}
+        let elapsed = start.elapsed();
+
+        let mut workloads = Vec::new();
+        for (workload, samples) in self.workloads.iter().zip(samples) {
+            let samples = std::mem::take(&mut *samples.lock().await);
+            workloads.push(WorkloadReport::new(
+                &workload.name,
+                samples.latencies,
+                samples.errors,
+                elapsed,
+            ));
+        }
+        let assertions = self
+            .assertions
+            .iter()
+            .map(|assertion| {
+                let report = workloads.iter().find(|w| w.name == assertion.workload());
+                assertion.check(report)
+            })
+            .collect();
+        StressReport {
+            scenario: self.name.clone(),
+            duration: elapsed,
+            workloads,
+            disconnects_injected: disconnects,
+            assertions,
+            connection: None,
+        }
+    }
+}
+
+async fn run_rate(
+    operation: Operation,
+    period: Duration,
+    timeout: Duration,
+    deadline: Instant,
+    samples: Arc<Mutex<Samples>>,
+) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut running = JoinSet::new();
+    loop {
+        tokio::select! {
+            biased;
+            _ = tokio::time::sleep_until(deadline) => break,
+            _ = interval.tick() => {
+                let operation = operation.clone();
+                let samples = samples.clone();
+                running.spawn(async move {
+                    let start = Instant::now();
+                    let result = tokio::time::timeout(timeout, operation()).await;
+                    samples.lock().await.record(result, start.elapsed());
+                });
+            }
+        }
+    }
+    // Operations started before the deadline still count
+    while running.join_next().await.is_some() {}
+}
+
+async fn run_stream(
+    factory: StreamFactory,
+    timeout: Duration,
+    deadline: Instant,
+    samples: Arc<Mutex<Samples>>,
+) {
+    while Instant::now() < deadline {
+        let mut stream = factory();
+        let mut last = Instant::now();
+        loop {
+            let next = tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => return,
+                next = tokio::time::timeout(timeout, stream.next()) => next,
+            };
+            match next {
+                Ok(None) => {
+                    debug!(target: "StressScenario", "Stream ended, opening it again");
+                    samples.lock().await.errors.failed += 1;
+                    break;
+                }
+                Ok(Some(result)) => {
+                    let failed = result.is_err();
+                    samples.lock().await.record(Ok(result), last.elapsed());
+                    if failed {
+                        break;
+                    }
+                }
+                Err(elapsed) => samples.lock().await.record(Err(elapsed), last.elapsed()),
+            }
+            last = Instant::now();
+        }
+    }
+}
+
+async fn inject_disconnects(
+    injection: DisconnectInjection,
+    reconnect: Operation,
+    deadline: Instant,
+) -> u32 {
+    let mut count = 0;
+    loop {
+        let at = Instant::now() + injection.next_delay();
+        if at >= deadline {
+            tokio::time::sleep_until(deadline).await;
+            return count;
+        }
+        tokio::time::sleep_until(at).await;
+        match reconnect().await {
+            Ok(()) => {
+                count += 1;
+                info!(target: "StressScenario", "Injected disconnect #{count}");
+            }
+            Err(e) => warn!(target: "StressScenario", "Failed to inject a disconnect: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CoreError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scenario_report() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let reconnects = Arc::new(AtomicU32::new(0));
+        let reconnected = reconnects.clone();
+        let scenario = StressScenario::new("paused clock", Duration::from_secs(60))
+            .with_workload(Workload::rate("trades", 60, move || {
+                let call = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    if call % 10 == 9 {
+                        return Err(CoreError::Other("rejected".into()));
+                    }
+                    Ok(())
+                }
+            }))
+            .with_workload(
+                Workload::streams("candles", 2, || {
+                    futures_util::stream::repeat_with(|| Ok(())).then(|item| async move {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        item
+                    })
+                })
+                .with_timeout(Duration::from_secs(5)),
+            )
+            .with_disconnects(
+                DisconnectInjection {
+                    min_interval: Duration::from_secs(20),
+                    max_interval: Duration::from_secs(20),
+                },
+                Some(move || {
+                    reconnected.fetch_add(1, Ordering::SeqCst);
+                    async { Ok(()) }
+                }),
+            )
+            .with_assertion(StressAssertion::Latency {
+                workload: "trades".into(),
+                percentile: 95.0,
+                max: Duration::from_millis(150),
+            })
+            .with_assertion(StressAssertion::ErrorRate {
+                workload: "trades".into(),
+                max: 0.05,
+            })
+            .with_assertion(StressAssertion::Throughput {
+                workload: "candles".into(),
+                min_per_minute: 100.0,
+            });
+
+        let report = scenario.run().await;
+        let trades = report.workload("trades").unwrap();
+        assert_eq!(trades.succeeded + trades.failed, 60);
+        assert_eq!(trades.failed, 6);
+        assert_eq!(trades.p95, Some(Duration::from_millis(100)));
+        let candles = report.workload("candles").unwrap();
+        assert!(candles.succeeded >= 118, "{candles:?}");
+        assert_eq!(candles.p99, Some(Duration::from_secs(1)));
+        assert_eq!(report.disconnects_injected, 2);
+        assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+
+        let verdicts: Vec<bool> = report.assertions.iter().map(|a| a.passed).collect();
+        assert_eq!(verdicts, [true, false, true]);
+        assert!(!report.passed());
+        assert!(report
+            .to_string()
+            .contains("[FAIL] trades error rate <= 5.00%"));
+    }
+
+    #[test]
+    fn test_percentile() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let report =
+            WorkloadReport::new("op", latencies, Errors::default(), Duration::from_secs(60));
+        assert_eq!(report.p50, Some(Duration::from_millis(50)));
+        assert_eq!(report.p99, Some(Duration::from_millis(99)));
+        assert_eq!(report.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(report.per_minute, 100.0);
+        assert!(WorkloadReport::default().percentile(50.0).is_none());
+    }
+}
@@ -1,10 +1,12 @@
 use async_trait::async_trait;
 use kanal::{AsyncReceiver, AsyncSender};
+use std::any::{type_name, TypeId};
 use std::fmt::Debug;
 use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::error::CoreResult;
+use crate::status::ConnectionStatus;
 
 #[derive(Debug, Clone, Copy)]
 pub enum RunnerCommand {
@@ -29,6 +31,9 @@ pub enum RunnerCommand {
     Connect,
     /// Attempt to reconnect (alias for Connect with soft semantics).
     Reconnect,
+    /// Report an application level status change, e.g. `Authenticated` once the
+    /// platform accepted the session. Only applied while a session is active.
+    SetStatus(ConnectionStatus),
 }
 
 /// The contract for the application's shared state.
@@ -133,6 +138,35 @@ pub trait ApiModule<S: AppState>: Send + 'static {
     /// this rule also takes the shared state as an argument, allowing for more complex
     /// routing logic that can depend on the current state of the application.
     fn rule(state: Arc<S>) -> Box<dyn Rule + Send + Sync>;
+
+    /// Modules (api or lightweight) filling the parts of the state this module relies on.
+    ///
+    /// [`ClientBuilder::build`](crate::builder::ClientBuilder::build) fails with
+    /// [`CoreError::MissingDependency`](crate::error::CoreError::MissingDependency) if one of
+    /// them is not registered, and starts them before this module.
+    fn dependencies() -> Vec<Dependency> {
+        Vec::new()
+    }
+}
+
+/// A module another module needs, see [`ApiModule::dependencies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dependency {
+    pub(crate) id: TypeId,
+    pub(crate) name: &'static str,
+}
+
+impl Dependency {
+    pub fn on<M: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<M>(),
+            name: type_name::<M>(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
 }
 
 /// A self‐contained module that runs independently,
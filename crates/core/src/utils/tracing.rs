@@ -1,6 +1,18 @@
-use std::{fs::OpenOptions, io::Write, time::Duration};
+use std::{
+    borrow::Cow,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use kanal::{bounded_async, Sender};
+use regex::Regex;
 use serde_json::Value;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::level_filters::LevelFilter;
@@ -8,7 +20,7 @@ use tracing_subscriber::{
     fmt::{self, MakeWriter},
     layer::SubscriberExt,
     util::SubscriberInitExt,
-    Layer, Registry,
+    EnvFilter, Layer, Registry,
 };
 
 use crate::{
@@ -28,13 +40,17 @@ pub fn start_tracing(terminal: bool) -> CoreResult<()> {
             // log-error file, to log the errors that arise
             fmt::layer()
                 .with_ansi(false)
-                .with_writer(error_logs)
+                .with_writer(redacted(error_logs))
                 .with_filter(LevelFilter::WARN),
         );
     if terminal {
-        sub.with(fmt::Layer::default().with_filter(LevelFilter::DEBUG))
-            .try_init()
-            .map_err(|e| CoreError::Tracing(e.to_string()))?;
+        sub.with(
+            fmt::Layer::default()
+                .with_writer(redacted(io::stdout))
+                .with_filter(LevelFilter::DEBUG),
+        )
+        .try_init()
+        .map_err(|e| CoreError::Tracing(e.to_string()))?;
     } else {
         sub.try_init()
             .map_err(|e| CoreError::Tracing(e.to_string()))?;
@@ -55,13 +71,17 @@ pub fn start_tracing_leveled(terminal: bool, level: LevelFilter) -> CoreResult<(
             // log-error file, to log the errors that arise
             fmt::layer()
                 .with_ansi(false)
-                .with_writer(error_logs)
+                .with_writer(redacted(error_logs))
                 .with_filter(LevelFilter::WARN),
         );
     if terminal {
-        sub.with(fmt::Layer::default().with_filter(level))
-            .try_init()
-            .map_err(|e| CoreError::Tracing(e.to_string()))?;
+        sub.with(
+            fmt::Layer::default()
+                .with_writer(redacted(io::stdout))
+                .with_filter(level),
+        )
+        .try_init()
+        .map_err(|e| CoreError::Tracing(e.to_string()))?;
     } else {
         sub.try_init()
             .map_err(|e| CoreError::Tracing(e.to_string()))?;
@@ -109,8 +129,374 @@ pub fn stream_logs_layer(
     let layer = tracing_subscriber::fmt::layer::<Registry>()
         .json()
         .flatten_event(true)
-        .with_writer(writer)
+        .with_writer(redacted(writer))
         .with_filter(level)
         .boxed();
     (layer, receiver)
 }
+
+static REDACTION: AtomicBool = AtomicBool::new(true);
+
+/// Turns the masking of [`redact_logs`] on or off for every [`Redacted`] writer, it is on by
+/// default. Only turn it off for local debugging, logs then contain the session of the account.
+pub fn set_log_redaction(enabled: bool) {
+    REDACTION.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`Redacted`] writers mask the logs.
+pub fn log_redaction() -> bool {
+    REDACTION.load(Ordering::Relaxed)
+}
+
+/// Masks session tokens, session cookies, uids and balances in a formatted log line.
+///
+/// Values are matched in JSON (`"uid":123`), in JSON escaped by `Debug` (`\"uid\":123`) and
+/// in plain text (`uid: 123`, `Balance = 10.5`).
+pub fn redact_logs(text: &str) -> Cow<'_, str> {
+    static PATTERNS: OnceLock<[(Regex, &'static str); 4]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid redaction regex");
+        [
+            (
+                regex(
+                    r#"(\\*"(?:session|sessionToken|ssid)\\*"\s*:\s*)(\\*")(.*?)(\\*"\s*[,}\]])"#,
+                ),
+                "${1}${2}<redacted>${4}",
+            ),
+            (
+                regex(r#"(?i)\b(ci_session|PHPSESSID)=[^;\s\\"']+"#),
+                "${1}=<redacted>",
+            ),
+            (
+                regex(r#"(?i)(\\*"uid\\*"\s*:\s*\\*"?|\buid\s*[:=]\s*)\d+"#),
+                "${1}<redacted>",
+            ),
+            (
+                regex(r#"(?i)(\\*"balance\\*"\s*:\s*\\*"?|\bbalance\s*[:=]\s*)-?\d+(?:\.\d+)?"#),
+                "${1}<redacted>",
+            ),
+        ]
+    });
+    let mut text = Cow::Borrowed(text);
+    for (regex, replacement) in patterns {
+        if let Cow::Owned(replaced) = regex.replace_all(&text, *replacement) {
+            text = Cow::Owned(replaced);
+        }
+    }
+    text
+}
+
+/// Wraps a [`MakeWriter`] so every log line goes through [`redact_logs`] while
+/// [`log_redaction`] is on.
+#[derive(Debug, Clone)]
+pub struct Redacted<W> {
+    inner: W,
+}
+
+/// Masks the logs written to `writer`, see [`Redacted`].
+pub fn redacted<W>(writer: W) -> Redacted<W> {
+    Redacted { inner: writer }
+}
+
+/// Writer of a [`Redacted`], one call to `write` is expected to hold whole log lines.
+pub struct RedactedWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for RedactedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !log_redaction() {
+            return self.inner.write(buf);
+        }
+        match std::str::from_utf8(buf) {
+            Ok(text) => self.inner.write_all(redact_logs(text).as_bytes())?,
+            Err(_) => self.inner.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<'a, W: MakeWriter<'a>> MakeWriter<'a> for Redacted<W> {
+    type Writer = RedactedWriter<W::Writer>;
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedWriter {
+            inner: self.inner.make_writer(),
+        }
+    }
+}
+
+/// Builds a filter with a default `level` and per-target overrides,
+/// e.g. `target_filter("info", [("Supervisor", "warn")])`.
+pub fn target_filter<'a>(
+    level: &str,
+    targets: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> CoreResult<EnvFilter> {
+    let directives = std::iter::once(level.to_string())
+        .chain(
+            targets
+                .into_iter()
+                .map(|(target, level)| format!("{target}={level}")),
+        )
+        .collect::<Vec<_>>()
+        .join(",");
+    EnvFilter::try_new(&directives)
+        .map_err(|e| CoreError::Tracing(format!("Invalid log filter {directives:?}: {e}")))
+}
+
+/// When a [`RotatingFile`] starts a new file, regardless of its size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    Never,
+    Minutely,
+    Hourly,
+    Daily,
+}
+
+impl Rotation {
+    fn period(&self) -> Option<u64> {
+        match self {
+            Rotation::Never => None,
+            Rotation::Minutely => Some(60),
+            Rotation::Hourly => Some(60 * 60),
+            Rotation::Daily => Some(24 * 60 * 60),
+        }
+    }
+
+    /// Index of the period containing `now`, files are rotated when it changes.
+    fn period_index(&self, now: SystemTime) -> u64 {
+        let secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.period().map_or(0, |period| secs / period)
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Ok(Rotation::Never),
+            "minutely" => Ok(Rotation::Minutely),
+            "hourly" => Ok(Rotation::Hourly),
+            "daily" => Ok(Rotation::Daily),
+            _ => Err(CoreError::Tracing(format!(
+                "Invalid rotation {s:?}, expected never, minutely, hourly or daily"
+            ))),
+        }
+    }
+}
+
+/// Log file writer that rotates on size and/or time.
+///
+/// On rotation `logs.log` is renamed to `logs.log.1`, older files are shifted (`.1` to `.2`, ...)
+/// and only the `max_files` most recent rotated files are kept.
+#[derive(Clone)]
+pub struct RotatingFile {
+    inner: Arc<Mutex<RotatingState>>,
+}
+
+struct RotatingState {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    period: u64,
+    rotation: Rotation,
+    max_size: Option<u64>,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Opens (or creates) `path` for appending.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        rotation: Rotation,
+        max_size: Option<u64>,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().append(true).create(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            inner: Arc::new(Mutex::new(RotatingState {
+                path,
+                file,
+                size,
+                period: rotation.period_index(SystemTime::now()),
+                rotation,
+                max_size,
+                max_files,
+            })),
+        })
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+impl RotatingState {
+    fn should_rotate(&self, incoming: usize, now: SystemTime) -> bool {
+        let too_big = self
+            .max_size
+            .is_some_and(|max| self.size > 0 && self.size + incoming as u64 > max);
+        too_big || self.rotation.period_index(now) != self.period
+    }
+
+    fn rotate(&mut self, now: SystemTime) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(rotated_path(&self.path, self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, index);
+                if from.exists() {
+                    std::fs::rename(from, rotated_path(&self.path, index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+        self.file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.period = self.rotation.period_index(now);
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let now = SystemTime::now();
+        if state.should_rotate(buf.len(), now) {
+            state.rotate(now)?;
+        }
+        let written = state.file.write(buf)?;
+        state.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .file
+            .flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingFile {
+    type Writer = RotatingFile;
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file_rotates_on_size() {
+        let dir = std::env::temp_dir().join(format!(
+            "bot-rotation-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("logs.log");
+        let mut file = RotatingFile::new(&path, Rotation::Never, Some(10), 2).unwrap();
+        for line in [
+            "first-line\n",
+            "second-line\n",
+            "third-line\n",
+            "fourth-line\n",
+        ] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth-line\n");
+        assert_eq!(read(rotated_path(&path, 1)), "third-line\n");
+        assert_eq!(read(rotated_path(&path, 2)), "second-line\n");
+        assert!(!rotated_path(&path, 3).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_redact_logs() {
+        let auth = r#"42["auth",{"session":"a:4:{s:10:\"session_id\";}","isDemo":1,"uid":87}]"#;
+        assert_eq!(
+            redact_logs(auth),
+            r#"42["auth",{"session":"<redacted>","isDemo":1,"uid":<redacted>}]"#
+        );
+        // `Debug` of a message escapes its quotes.
+        let debug = format!("Sending: {:?}", Message::text(auth));
+        let redacted = redact_logs(&debug);
+        assert!(!redacted.contains("session_id") && !redacted.contains("87"));
+        assert!(redacted.contains(r#"\"isDemo\":1"#));
+
+        assert_eq!(
+            redact_logs(r#"42["successupdateBalance",{"isDemo":1,"balance":50000.5}]"#),
+            r#"42["successupdateBalance",{"isDemo":1,"balance":<redacted>}]"#
+        );
+        assert_eq!(
+            redact_logs("Parsed Demo SSID. UID: 87, balance = 12.5"),
+            "Parsed Demo SSID. UID: <redacted>, balance = <redacted>"
+        );
+        assert_eq!(
+            redact_logs("Cookie: lang=en; ci_session=a%3A4%3A; theme=dark"),
+            "Cookie: lang=en; ci_session=<redacted>; theme=dark"
+        );
+        assert!(matches!(redact_logs("Connected"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redacted_writer() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let writer = redacted(SharedBuffer(lines.clone()));
+        writer.make_writer().write_all(b"balance: 100\n").unwrap();
+        assert_eq!(lines.lock().unwrap().as_slice(), b"balance: <redacted>\n");
+    }
+
+    #[derive(Clone)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for SharedBuffer {
+        type Writer = SharedBuffer;
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_target_filter() {
+        assert!(target_filter("info", [("Supervisor", "warn")]).is_ok());
+        assert!(target_filter("info", [("Supervisor", "loud")]).is_err());
+        assert_eq!("Daily".parse::<Rotation>().unwrap(), Rotation::Daily);
+        assert!("weekly".parse::<Rotation>().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Tests for the faults injected by the chaos middleware into a running client.
+
+use async_trait::async_trait;
+use binary_options_tools_core::builder::ClientBuilder;
+use binary_options_tools_core::error::CoreResult;
+use binary_options_tools_core::testing::{ChaosConfig, ChaosMiddleware, MockConnector};
+use binary_options_tools_core::traits::{AppState, LightweightModule, Rule, RunnerCommand};
+use kanal::{AsyncReceiver, AsyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, Default)]
+struct Seen {
+    ticks: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl AppState for Seen {
+    async fn clear_temporal_data(&self) {}
+}
+
+struct TicksModule {
+    state: Arc<Seen>,
+    receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<Seen> for TicksModule {
+    fn new(
+        state: Arc<Seen>,
+        _: AsyncSender<Message>,
+        receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { state, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.receiver.recv().await {
+            let text = msg.to_text().unwrap_or_default().to_string();
+            self.state.ticks.lock().unwrap().push(text);
+        }
+        Ok(())
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|msg: &Message| msg.to_text().is_ok_and(|text| text.starts_with("tick")))
+    }
+}
+
+fn ticks() -> MockConnector {
+    MockConnector::new()
+        .send(Message::text("tick 1"))
+        .send(Message::text("tick 2"))
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("condition not reached");
+}
+
+#[tokio::test]
+async fn test_duplicated_frames_are_routed() {
+    let chaos = ChaosMiddleware::new(ChaosConfig::new(1).with_duplicate(1.0));
+    let (client, mut runner) = ClientBuilder::new(ticks(), Seen::default())
+        .with_lightweight_module::<TicksModule>()
+        .with_middleware(Box::new(chaos.clone()))
+        .build()
+        .await
+        .unwrap();
+    let runner = tokio::spawn(async move { runner.run().await });
+
+    let state = client.state.clone();
+    wait_until(|| state.ticks.lock().unwrap().len() >= 4).await;
+    assert_eq!(
+        *state.ticks.lock().unwrap(),
+        ["tick 1", "tick 1", "tick 2", "tick 2"]
+    );
+    assert_eq!(chaos.stats().duplicated, 2);
+    runner.abort();
+}
+
+#[tokio::test]
+async fn test_disconnect_makes_the_runner_reconnect() {
+    let server = ticks();
+    let chaos = ChaosMiddleware::new(ChaosConfig::new(1).with_disconnect(1.0));
+    let (client, mut runner) = ClientBuilder::new(server.clone(), Seen::default())
+        .with_lightweight_module::<TicksModule>()
+        .with_middleware(Box::new(chaos.clone()))
+        .with_reconnect_delay(Duration::from_millis(10))
+        .build()
+        .await
+        .unwrap();
+    let runner = tokio::spawn(async move { runner.run().await });
+
+    wait_until(|| server.connections() >= 3).await;
+    assert!(chaos.stats().disconnects >= 2);
+    // Every connection was dropped on its first frame
+    assert!(client.state.ticks.lock().unwrap().is_empty());
+
+    // Without faults the next connection delivers the ticks
+    chaos.set_enabled(false);
+    let state = client.state.clone();
+    wait_until(|| state.ticks.lock().unwrap().len() >= 2).await;
+    runner.abort();
+}
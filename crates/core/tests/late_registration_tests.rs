@@ -0,0 +1,224 @@
+//! Tests for attaching api modules and middleware to an already built client.
+
+use async_trait::async_trait;
+use binary_options_tools_core::builder::ClientBuilder;
+use binary_options_tools_core::channels::ChannelCapacity;
+use binary_options_tools_core::connector::{Connector, ConnectorError, ConnectorResult, WsStream};
+use binary_options_tools_core::error::{CoreError, CoreResult};
+use binary_options_tools_core::middleware::{MiddlewareContext, WebSocketMiddleware};
+use binary_options_tools_core::traits::{ApiModule, Dependency, Rule, RunnerCommand};
+use kanal::{AsyncReceiver, AsyncSender};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+struct MockConnector;
+
+#[async_trait]
+impl Connector<()> for MockConnector {
+    async fn connect(&self, _: Arc<()>) -> ConnectorResult<WsStream> {
+        Err(ConnectorError::Custom("Mock connector".to_string()))
+    }
+
+    async fn disconnect(&self) -> ConnectorResult<()> {
+        Ok(())
+    }
+}
+
+/// Replies to every command with the command in upper case.
+struct UpperModule {
+    cmd_rx: AsyncReceiver<String>,
+    cmd_ret_tx: AsyncSender<String>,
+}
+
+#[derive(Clone)]
+struct UpperHandle {
+    sender: AsyncSender<String>,
+    receiver: AsyncReceiver<String>,
+}
+
+impl UpperHandle {
+    async fn upper(&self, text: &str) -> CoreResult<String> {
+        self.sender.send(text.to_string()).await?;
+        Ok(self.receiver.recv().await?)
+    }
+}
+
+#[async_trait]
+impl ApiModule<()> for UpperModule {
+    type Command = String;
+    type CommandResponse = String;
+    type Handle = UpperHandle;
+
+    fn new(
+        _state: Arc<()>,
+        cmd_rx: AsyncReceiver<Self::Command>,
+        cmd_ret_tx: AsyncSender<Self::CommandResponse>,
+        _msg_rx: AsyncReceiver<Arc<Message>>,
+        _to_ws: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { cmd_rx, cmd_ret_tx }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        receiver: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        UpperHandle { sender, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(cmd) = self.cmd_rx.recv().await {
+            self.cmd_ret_tx.send(cmd.to_uppercase()).await?;
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<()>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(move |_msg: &Message| false)
+    }
+}
+
+#[tokio::test]
+async fn test_register_module_after_build() {
+    let (client, _runner) = ClientBuilder::new(MockConnector, ())
+        .build()
+        .await
+        .expect("Failed to build client");
+    assert!(client.get_handle::<UpperModule>().await.is_none());
+
+    let handle = client.register_module::<UpperModule>().await.unwrap();
+    assert_eq!(handle.upper("eurusd").await.unwrap(), "EURUSD");
+    assert_eq!(client.stats().modules.get("UpperModule"), Some(&0));
+
+    let stored = client.get_handle::<UpperModule>().await.unwrap();
+    assert_eq!(stored.upper("otc").await.unwrap(), "OTC");
+}
+
+#[tokio::test]
+async fn test_module_channel_capacity_override() {
+    let (client, _runner) = ClientBuilder::new(MockConnector, ())
+        .with_channel_capacity(ChannelCapacity::default().with_messages(64))
+        .with_module_channel_capacity::<UpperModule>(ChannelCapacity::default().with_messages(8))
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    client.register_module::<UpperModule>().await.unwrap();
+    let stats = &client.stats().channels["UpperModule"];
+    assert_eq!(stats.capacity, 8);
+    assert_eq!(stats.high_water_mark, 0);
+}
+
+#[tokio::test]
+async fn test_register_module_twice_fails() {
+    let (client, _runner) = ClientBuilder::new(MockConnector, ())
+        .with_module::<UpperModule>()
+        .build()
+        .await
+        .expect("Failed to build client");
+
+    let err = client.register_module::<UpperModule>().await.err();
+    assert!(matches!(err, Some(CoreError::ModuleAlreadyRegistered(_))));
+}
+
+/// Replies in lower case, needs `UpperModule` to be registered.
+struct LowerModule {
+    cmd_rx: AsyncReceiver<String>,
+    cmd_ret_tx: AsyncSender<String>,
+}
+
+#[async_trait]
+impl ApiModule<()> for LowerModule {
+    type Command = String;
+    type CommandResponse = String;
+    type Handle = UpperHandle;
+
+    fn new(
+        _state: Arc<()>,
+        cmd_rx: AsyncReceiver<Self::Command>,
+        cmd_ret_tx: AsyncSender<Self::CommandResponse>,
+        _msg_rx: AsyncReceiver<Arc<Message>>,
+        _to_ws: AsyncSender<Message>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { cmd_rx, cmd_ret_tx }
+    }
+
+    fn create_handle(
+        sender: AsyncSender<Self::Command>,
+        receiver: AsyncReceiver<Self::CommandResponse>,
+    ) -> Self::Handle {
+        UpperHandle { sender, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(cmd) = self.cmd_rx.recv().await {
+            self.cmd_ret_tx.send(cmd.to_lowercase()).await?;
+        }
+        Ok(())
+    }
+
+    fn rule(_: Arc<()>) -> Box<dyn Rule + Send + Sync> {
+        Box::new(move |_msg: &Message| false)
+    }
+
+    fn dependencies() -> Vec<Dependency> {
+        vec![Dependency::on::<UpperModule>()]
+    }
+}
+
+#[tokio::test]
+async fn test_missing_dependency_fails() {
+    let err = ClientBuilder::new(MockConnector, ())
+        .with_module::<LowerModule>()
+        .build()
+        .await
+        .err();
+    assert!(matches!(err, Some(CoreError::MissingDependency { .. })));
+
+    let (client, _runner) = ClientBuilder::new(MockConnector, ())
+        .build()
+        .await
+        .expect("Failed to build client");
+    let err = client.register_module::<LowerModule>().await.err();
+    assert!(matches!(err, Some(CoreError::MissingDependency { .. })));
+    client.register_module::<UpperModule>().await.unwrap();
+    let handle = client.register_module::<LowerModule>().await.unwrap();
+    assert_eq!(handle.upper("EURUSD").await.unwrap(), "eurusd");
+}
+
+struct AttemptCounter(Arc<AtomicU32>);
+
+#[async_trait]
+impl WebSocketMiddleware<()> for AttemptCounter {
+    async fn on_connection_attempt(&self, _context: &MiddlewareContext<()>) -> CoreResult<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_add_middleware_after_build() {
+    let (client, mut runner) = ClientBuilder::new(MockConnector, ())
+        .with_reconnect_delay(Duration::from_millis(10))
+        .build()
+        .await
+        .expect("Failed to build client");
+    let attempts = Arc::new(AtomicU32::new(0));
+    client
+        .add_middleware(Box::new(AttemptCounter(attempts.clone())))
+        .await;
+
+    let runner = tokio::spawn(async move { runner.run().await });
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while attempts.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("middleware was not called");
+    runner.abort();
+}
@@ -0,0 +1,131 @@
+//! Tests for the priority and exclusivity of module rules.
+
+use async_trait::async_trait;
+use binary_options_tools_core::builder::ClientBuilder;
+use binary_options_tools_core::error::CoreResult;
+use binary_options_tools_core::routing::Routing;
+use binary_options_tools_core::statistics::RuleStats;
+use binary_options_tools_core::testing::MockConnector;
+use binary_options_tools_core::traits::{AppState, LightweightModule, Rule, RunnerCommand};
+use kanal::{AsyncReceiver, AsyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, Default)]
+struct Seen {
+    ticks: Mutex<Vec<String>>,
+    all: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl AppState for Seen {
+    async fn clear_temporal_data(&self) {}
+}
+
+fn text(msg: &Message) -> String {
+    msg.to_text().unwrap_or_default().to_string()
+}
+
+/// Receives the ticks only.
+struct TicksModule {
+    state: Arc<Seen>,
+    receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<Seen> for TicksModule {
+    fn new(
+        state: Arc<Seen>,
+        _: AsyncSender<Message>,
+        receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { state, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.receiver.recv().await {
+            self.state.ticks.lock().unwrap().push(text(&msg));
+        }
+        Ok(())
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|msg: &Message| text(msg).starts_with("tick"))
+    }
+}
+
+/// Receives every message that reaches its rule.
+struct AllModule {
+    state: Arc<Seen>,
+    receiver: AsyncReceiver<Arc<Message>>,
+}
+
+#[async_trait]
+impl LightweightModule<Seen> for AllModule {
+    fn new(
+        state: Arc<Seen>,
+        _: AsyncSender<Message>,
+        receiver: AsyncReceiver<Arc<Message>>,
+        _: AsyncSender<RunnerCommand>,
+    ) -> Self {
+        Self { state, receiver }
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        while let Ok(msg) = self.receiver.recv().await {
+            self.state.all.lock().unwrap().push(text(&msg));
+        }
+        Ok(())
+    }
+
+    fn rule() -> Box<dyn Rule + Send + Sync> {
+        Box::new(|_: &Message| true)
+    }
+}
+
+#[tokio::test]
+async fn test_exclusive_rule_stops_routing() {
+    let server = MockConnector::new()
+        .send(Message::text("tick 1"))
+        .send(Message::text("balance"))
+        .send(Message::text("tick 2"));
+    // Registered first, but evaluated after the higher priority ticks rule.
+    let (client, mut runner) = ClientBuilder::new(server, Seen::default())
+        .with_lightweight_module::<AllModule>()
+        .with_lightweight_module::<TicksModule>()
+        .with_module_routing::<TicksModule>(Routing::default().with_priority(10).exclusive())
+        .build()
+        .await
+        .unwrap();
+    let runner = tokio::spawn(async move { runner.run().await });
+
+    let state = client.state.clone();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while state.ticks.lock().unwrap().len() < 2 || state.all.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("the messages were not routed");
+
+    assert_eq!(*state.ticks.lock().unwrap(), ["tick 1", "tick 2"]);
+    assert_eq!(*state.all.lock().unwrap(), ["balance"]);
+    let stats = client.stats();
+    assert_eq!(
+        stats.rules["TicksModule"],
+        RuleStats {
+            evaluations: 3,
+            hits: 2
+        }
+    );
+    assert_eq!(
+        stats.rules["AllModule"],
+        RuleStats {
+            evaluations: 1,
+            hits: 1
+        }
+    );
+    runner.abort();
+}
@@ -3,6 +3,10 @@
 //! These tests verify the new `DisconnectAndHold` functionality and ensure
 //! the connection lifecycle state machine behaves correctly.
 
+use std::time::Duration;
+
+use binary_options_tools_core::reconnect::ReconnectPolicy;
+use binary_options_tools_core::status::ConnectionStatus;
 use binary_options_tools_core::traits::RunnerCommand;
 
 #[test]
@@ -32,71 +36,72 @@ fn test_runner_command_clone_copy() {
     assert!(matches!(copied, RunnerCommand::DisconnectAndHold));
 }
 
+fn runner_policy() -> ReconnectPolicy {
+    // 5s doubling on every attempt up to 300s, with ±20% jitter
+    ReconnectPolicy::default()
+        .with_initial_delay(Duration::from_secs(5))
+        .with_multiplier(2.0)
+        .with_max_delay(Duration::from_secs(300))
+        .with_jitter(0.2)
+}
+
 #[test]
 fn test_exponential_backoff_calculation() {
-    // Test the backoff calculation logic as used in ClientRunner::run
-    // delay = min(base_delay * 2^attempts, 300) * jitter(0.8..1.2)
-    let base_delay: u64 = 5;
-
-    // Attempt 0: 5 * 2^0 = 5
-    let delay0 = base_delay.saturating_mul(2u64.saturating_pow(0)).min(300);
-    assert_eq!(delay0, 5);
-
-    // Attempt 1: 5 * 2^1 = 10
-    let delay1 = base_delay.saturating_mul(2u64.saturating_pow(1)).min(300);
-    assert_eq!(delay1, 10);
-
-    // Attempt 5: 5 * 2^5 = 160
-    let delay5 = base_delay.saturating_mul(2u64.saturating_pow(5)).min(300);
-    assert_eq!(delay5, 160);
-
-    // Attempt 10 (exponent at cap): 5 * 2^10 = 5120, capped at 300
-    let delay10 = base_delay.saturating_mul(2u64.saturating_pow(10)).min(300);
-    assert_eq!(delay10, 300);
-
-    // Attempt 15 (exponent capped at 10): same as attempt 10
-    let delay15 = base_delay
-        .saturating_mul(2u64.saturating_pow(15u32.min(10)))
-        .min(300);
-    assert_eq!(delay15, 300);
+    // delay = min(initial * multiplier^(attempt - 1), max_delay)
+    let policy = runner_policy();
+
+    // Attempt 1: 5 * 2^0 = 5
+    assert_eq!(policy.base_delay(1), Duration::from_secs(5));
+
+    // Attempt 2: 5 * 2^1 = 10
+    assert_eq!(policy.base_delay(2), Duration::from_secs(10));
+
+    // Attempt 6: 5 * 2^5 = 160
+    assert_eq!(policy.base_delay(6), Duration::from_secs(160));
+
+    // Attempt 7: 5 * 2^6 = 320, capped at 300
+    assert_eq!(policy.base_delay(7), Duration::from_secs(300));
+
+    // Later attempts stay at the cap
+    assert_eq!(policy.base_delay(15), Duration::from_secs(300));
+    assert_eq!(policy.base_delay(u32::MAX), Duration::from_secs(300));
 }
 
 #[test]
 fn test_exponential_backoff_with_large_base_delay() {
-    // Ensure large base delays don't cause overflow due to saturating_mul
-    let base_delay: u64 = 100;
-
-    // Attempt 10: 100 * 2^10 = 102400, capped at 300
-    let delay = base_delay.saturating_mul(2u64.saturating_pow(10)).min(300);
-    assert_eq!(delay, 300);
-
-    // Attempt 20 (exponent capped): same result
-    let delay_capped = base_delay
-        .saturating_mul(2u64.saturating_pow(20u32.min(10)))
-        .min(300);
-    assert_eq!(delay_capped, 300);
+    // An initial delay above the cap is capped from the first attempt on
+    let policy = runner_policy().with_initial_delay(Duration::from_secs(1000));
+    assert_eq!(policy.base_delay(1), Duration::from_secs(300));
+    assert_eq!(policy.base_delay(20), Duration::from_secs(300));
 }
 
 #[test]
 fn test_exponential_backoff_with_zero_base_delay() {
-    // Edge case: base_delay = 0 should still produce valid results
-    let base_delay: u64 = 0;
-
-    let delay = base_delay.saturating_mul(2u64.saturating_pow(5)).min(300);
-    assert_eq!(delay, 0);
+    // Edge case: a zero initial delay should still produce valid results
+    let policy = runner_policy().with_initial_delay(Duration::ZERO);
+    assert_eq!(policy.base_delay(5), Duration::ZERO);
+    assert_eq!(policy.delay_for(5), Duration::ZERO);
 }
 
 #[test]
 fn test_jitter_range() {
-    // Verify jitter stays within expected bounds (0.8 to 1.2)
-    use rand::RngExt;
-
-    let mut rng = rand::rng();
-    for _ in 0..1000 {
-        let jitter = rng.random_range(0.8..1.2);
-        assert!(jitter >= 0.8, "Jitter below minimum: {}", jitter);
-        assert!(jitter < 1.2, "Jitter at or above maximum: {}", jitter);
+    // Jittered delays stay within 0.8..1.2 of the base delay, the cap included
+    let policy = runner_policy();
+    for attempt in [1, 2, 6, 7, 15] {
+        let base = policy.base_delay(attempt);
+        for _ in 0..200 {
+            let delay = policy.delay_for(attempt);
+            assert!(
+                delay >= base.mul_f64(0.8),
+                "Jitter below minimum: {delay:?}"
+            );
+            assert!(
+                delay <= base.mul_f64(1.2),
+                "Jitter above maximum: {delay:?}"
+            );
+        }
     }
+    assert!(policy.delay_for(u32::MAX) <= Duration::from_secs(360));
 }
 
 #[test]
@@ -108,6 +113,7 @@ fn test_command_pattern_matching() {
         RunnerCommand::Shutdown,
         RunnerCommand::Connect,
         RunnerCommand::Reconnect,
+        RunnerCommand::SetStatus(ConnectionStatus::Authenticated),
     ];
 
     let mut disconnect_count = 0;
@@ -115,6 +121,7 @@ fn test_command_pattern_matching() {
     let mut shutdown_count = 0;
     let mut connect_count = 0;
     let mut reconnect_count = 0;
+    let mut status_count = 0;
 
     for cmd in commands {
         match cmd {
@@ -123,6 +130,7 @@ fn test_command_pattern_matching() {
             RunnerCommand::Shutdown => shutdown_count += 1,
             RunnerCommand::Connect => connect_count += 1,
             RunnerCommand::Reconnect => reconnect_count += 1,
+            RunnerCommand::SetStatus(_) => status_count += 1,
         }
     }
 
@@ -131,6 +139,7 @@ fn test_command_pattern_matching() {
     assert_eq!(shutdown_count, 1);
     assert_eq!(connect_count, 1);
     assert_eq!(reconnect_count, 1);
+    assert_eq!(status_count, 1);
 }
 
 #[test]
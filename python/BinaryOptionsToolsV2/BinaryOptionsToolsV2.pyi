@@ -1,4 +1,6 @@
-from typing import Any, Callable, List, Optional, Tuple
+from datetime import datetime, timedelta
+from decimal import Decimal
+from typing import Any, Callable, Dict, List, Optional, Tuple, Union
 
 class Action:
     Call: "Action"
@@ -33,7 +35,18 @@ class RawValidator:
     @staticmethod
     def any(validators: List["RawValidator"]) -> "RawValidator": ...
     @staticmethod
+    def json_path(
+        path: str,
+        eq: Any = None,
+        ne: Any = None,
+        gt: Optional[float] = None,
+        lt: Optional[float] = None,
+        matches: Optional["RawValidator"] = None,
+    ) -> "RawValidator": ...
+    @staticmethod
     def custom(func: Callable[[str], bool]) -> "RawValidator": ...
+    def and_(self, other: "RawValidator") -> "RawValidator": ...
+    def or_(self, other: "RawValidator") -> "RawValidator": ...
     def check(self, msg: str) -> bool: ...
 
 class StreamIterator:
@@ -41,18 +54,27 @@ class StreamIterator:
     def __anext__(self) -> str: ...
     def __iter__(self) -> "StreamIterator": ...
     def __next__(self) -> str: ...
+    async def next_timeout(self, seconds: float) -> Optional[str]: ...
+    def next_timeout_blocking(self, seconds: float) -> Optional[str]: ...
+    async def collect(self, n: int, timeout: Optional[float] = None) -> List[str]: ...
+    def collect_blocking(self, n: int, timeout: Optional[float] = None) -> List[str]: ...
 
 class RawStreamIterator:
     def __aiter__(self) -> "RawStreamIterator": ...
     def __anext__(self) -> str: ...
     def __iter__(self) -> "RawStreamIterator": ...
     def __next__(self) -> str: ...
+    async def next_timeout(self, seconds: float) -> Optional[str]: ...
+    def next_timeout_blocking(self, seconds: float) -> Optional[str]: ...
+    async def collect(self, n: int, timeout: Optional[float] = None) -> List[str]: ...
+    def collect_blocking(self, n: int, timeout: Optional[float] = None) -> List[str]: ...
 
 class RawHandler:
     def id(self) -> str: ...
     async def send_text(self, text: str) -> None: ...
     async def send_binary(self, data: bytes) -> None: ...
     async def send_and_wait(self, message: str) -> str: ...
+    async def send_and_wait_for(self, message: str, correlation_id: str, timeout_secs: float = 30.0) -> str: ...
     async def wait_next(self) -> str: ...
     async def subscribe(self) -> RawStreamIterator: ...
 
@@ -60,6 +82,19 @@ class RawHandle:
     async def create(self, validator: RawValidator, keep_alive_message: Optional[str]) -> RawHandler: ...
     async def remove(self, id: str) -> bool: ...
 
+class CallbackHandle:
+    def cancel(self) -> None: ...
+    def is_active(self) -> bool: ...
+
+class CandleBuffer:
+    def assets(self) -> List[str]: ...
+    def len(self, asset: str) -> int: ...
+    def latest(self, asset: str) -> Optional[str]: ...
+    def to_records(self, asset: Optional[str] = None) -> List[Dict[str, Any]]: ...
+    def to_columns(self, asset: str) -> Dict[str, List[Any]]: ...
+    def close(self) -> None: ...
+    def is_active(self) -> bool: ...
+
 class RawPocketOption:
     def __init__(self, ssid: str) -> None: ...
     @staticmethod
@@ -73,39 +108,103 @@ class RawPocketOption:
     @staticmethod
     async def create_with_config(ssid: str, config: PyConfig) -> "RawPocketOption": ...
     async def wait_for_assets(self, timeout_secs: float) -> None: ...
+    async def wait_for(self, condition: str, timeout_secs: float, asset: Optional[str] = None) -> None: ...
     def is_demo(self) -> bool: ...
-    async def buy(self, asset: str, amount: float, time: int) -> List[str]: ...
-    async def sell(self, asset: str, amount: float, time: int) -> List[str]: ...
+    async def save_session(self, path: str) -> None: ...
+    def stats(self) -> str: ...
+    def reset_stats(self) -> None: ...
+    def audit_log(self, since: int = 0) -> str: ...
+    async def buy(
+        self,
+        asset: str,
+        amount: Union[Decimal, str, float],
+        time: int,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait_secs: float = 0.0,
+    ) -> List[str]: ...
+    async def sell(
+        self,
+        asset: str,
+        amount: Union[Decimal, str, float],
+        time: int,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait_secs: float = 0.0,
+    ) -> List[str]: ...
     async def check_win(self, trade_id: str) -> str: ...
     async def get_deal_end_time(self, trade_id: str) -> Optional[int]: ...
+    async def time_to_expiry(self, trade_id: str) -> float: ...
+    async def deal_countdown_stream(self, trade_id: str) -> RawStreamIterator: ...
+    async def close_deal_early(self, trade_id: str) -> str: ...
+    async def deal_status(
+        self, trade_id: str, wait: bool = False, history_fallback_secs: Optional[float] = None
+    ) -> str: ...
     async def candles(self, asset: str, period: int) -> str: ...
     async def get_candles(self, asset: str, period: int, offset: int) -> str: ...
     async def get_candles_advanced(self, asset: str, period: int, offset: int, time: int) -> str: ...
+    async def get_candles_columns(self, asset: str, period: int, offset: int) -> Dict[str, List]: ...
     async def balance(self) -> float: ...
+    async def balances(self) -> str: ...
+    async def currency(self) -> Optional[str]: ...
     async def open_pending_order(
         self,
         open_type: int,
-        amount: float,
+        amount: Union[Decimal, str, float],
         asset: str,
         open_time: int,
-        open_price: float,
+        open_price: Union[Decimal, str, float],
         timeframe: int,
         min_payout: int,
         command: int,
     ) -> str: ...
     async def closed_deals(self) -> str: ...
+    async def closed_deals_history(self, start: int, end: int) -> str: ...
+    async def ticks_range(self, asset: str, start: int, end: int) -> str: ...
+    async def trade_stats(self) -> str: ...
+    async def reset_trade_stats(self) -> None: ...
+    async def kill_switch_status(self) -> str: ...
+    async def kill_switch_events(self) -> RawStreamIterator: ...
+    async def last_trade_timings(self) -> Optional[str]: ...
+    async def trade_latency(self) -> str: ...
+    async def slippage_stats(self) -> str: ...
     async def get_closed_deal(self, id: str) -> Optional[str]: ...
     async def clear_closed_deals(self) -> None: ...
     async def opened_deals(self) -> str: ...
     async def get_opened_deal(self, id: str) -> Optional[str]: ...
-    async def payout(self) -> str: ...
+    async def get_assets(self) -> str: ...
+    async def asset_schedule(self, asset: str) -> str: ...
+    async def is_tradeable_now(self, asset: str) -> bool: ...
+    async def refresh_assets(self, timeout_secs: float = 30.0) -> str: ...
+    async def assets_changed(self) -> RawStreamIterator: ...
     async def history(self, asset: str, period: int) -> str: ...
     async def compile_candles(self, asset: str, custom_period: int, lookback_period: int) -> str: ...
     async def subscribe_symbol(self, symbol: str) -> StreamIterator: ...
+    async def subscribe_many(self, subscriptions: List[Tuple[str, str, Optional[float]]]) -> RawStreamIterator: ...
+    async def reconnect_events(self) -> RawStreamIterator: ...
+    async def on_reconnect(self, callback: Callable[[str], Any]) -> CallbackHandle: ...
+    async def on_disconnect(self, callback: Callable[[str], Any]) -> CallbackHandle: ...
+    async def candle_buffer(self, assets: List[str], size: int, time: Optional[timedelta] = None) -> CandleBuffer: ...
+    async def scanner(self, assets: List[str], slots: int = 4, dwell_secs: float = 5.0) -> RawStreamIterator: ...
+    async def volatility(self, asset: str, period: int = 14) -> RawStreamIterator: ...
+    async def add_alert(self, asset: str, condition: str, price: float, repeat: bool = False) -> str: ...
+    async def remove_alert(self, alert_id: str) -> bool: ...
+    async def active_alerts(self) -> str: ...
+    async def alert_events(self) -> RawStreamIterator: ...
+    async def parse_failures(self) -> RawStreamIterator: ...
+    async def data_quality_events(self) -> RawStreamIterator: ...
+    def status(self) -> str: ...
+    async def wait_for_status(self, status: str, timeout_secs: float) -> None: ...
+    async def status_stream(self) -> RawStreamIterator: ...
     async def subscribe_symbol_chunked(self, symbol: str, chunk_size: int) -> StreamIterator: ...
-    async def subscribe_symbol_timed(self, symbol: str, time: Any) -> StreamIterator: ...
-    async def subscribe_symbol_time_aligned(self, symbol: str, time: Any) -> StreamIterator: ...
+    async def subscribe_symbol_timed(
+        self, symbol: str, time: Any, gap_fill: Optional[str] = None
+    ) -> StreamIterator: ...
+    async def subscribe_symbol_time_aligned(
+        self, symbol: str, time: Any, gap_fill: Optional[str] = None
+    ) -> StreamIterator: ...
     async def send_raw_message(self, message: str) -> None: ...
+    async def add_middleware(self, callback: Callable[[str, str], Any], queue_size: int = 1024) -> None: ...
     async def create_raw_order(self, message: str, validator: RawValidator) -> str: ...
     async def create_raw_order_with_timeout(self, message: str, validator: RawValidator, timeout: Any) -> str: ...
     async def create_raw_order_with_timeout_and_retry(
@@ -114,12 +213,26 @@ class RawPocketOption:
     async def create_raw_iterator(
         self, message: str, validator: RawValidator, timeout: Optional[Any]
     ) -> RawStreamIterator: ...
+    def timezone(self) -> str: ...
+    def to_timestamp(self, value: Union[datetime, int, float, str]) -> int: ...
+    def to_datetime(self, timestamp: float) -> datetime: ...
     async def get_server_time(self) -> int: ...
+    async def server_time_offset(self) -> int: ...
+    async def server_time_quality(self) -> str: ...
+    async def debug_snapshot(self) -> str: ...
+    async def shutdown_graceful(self, timeout_secs: float) -> int: ...
     async def disconnect(self) -> None: ...
     async def connect(self) -> None: ...
+    async def fatal_error(self) -> str: ...
     async def reconnect(self) -> None: ...
     async def unsubscribe(self, asset: str) -> None: ...
-    async def create_raw_handler(self, validator: RawValidator, keep_alive: Optional[str]) -> RawHandler: ...
+    async def create_raw_handler(
+        self,
+        validator: RawValidator,
+        keep_alive: Optional[str] = None,
+        correlation_path: Optional[str] = None,
+        reassemble: bool = False,
+    ) -> RawHandler: ...
 
 class Logger:
     def __init__(self) -> None: ...
@@ -131,8 +244,17 @@ class Logger:
 class LogBuilder:
     def __init__(self) -> None: ...
     def create_logs_iterator(self, level: str, timeout: Optional[Any]) -> Any: ...
-    def log_file(self, path: str, level: str) -> None: ...
-    def terminal(self, level: str) -> None: ...
+    def log_file(
+        self,
+        path: str = "logs.log",
+        level: str = "DEBUG",
+        json: bool = False,
+        filters: Optional[Dict[str, str]] = None,
+        rotation: Optional[str] = None,
+        max_size: Optional[int] = None,
+        max_files: int = 5,
+    ) -> None: ...
+    def terminal(self, level: str = "DEBUG", json: bool = False, filters: Optional[Dict[str, str]] = None) -> None: ...
     def build(self) -> None: ...
 
 class StreamLogsLayer: ...
@@ -141,6 +263,37 @@ class StreamLogsIterator: ...
 class PyContext:
     async def buy(self, asset: str, amount: float, time: int) -> List[str]: ...
     async def balance(self) -> float: ...
+    def tag_signal(self, trade_id: str, signal: str) -> None: ...
+
+class PyTradeJournal:
+    def __init__(self) -> None: ...
+    def entries(self) -> str: ...
+    def report_json(self) -> str: ...
+    def report_csv(self) -> str: ...
+
+class PyParamSpace:
+    def __init__(self) -> None: ...
+    def add_values(self, name: str, values: List[Any]) -> None: ...
+    def add_range(self, name: str, start: float, end: float, step: float) -> None: ...
+    def size(self) -> int: ...
+    def grid(self) -> str: ...
+
+class PyOptimizer:
+    def __init__(
+        self,
+        client: RawPocketOption,
+        strategy: Callable[[], PyStrategy],
+        candles: str,
+        space: PyParamSpace,
+        initial_balance: float = 1000.0,
+        payout: int = 80,
+    ) -> None: ...
+    def with_walk_forward(self, folds: int, train_ratio: float) -> None: ...
+    def with_random_search(self, samples: int, seed: int = 0) -> None: ...
+    def with_objective(self, objective: str) -> None: ...
+    def with_concurrency(self, concurrency: int) -> None: ...
+    def with_top(self, top: int) -> None: ...
+    async def run(self) -> str: ...
 
 class PyVirtualMarket:
     def __init__(self, initial_balance: float) -> None: ...
@@ -152,6 +305,10 @@ class PyStrategy:
     def on_start(self, ctx: PyContext) -> None: ...
     def on_candle(self, ctx: PyContext, asset: str, candle_json: str) -> None: ...
     def on_balance(self, ctx: PyContext, balance: float) -> None: ...
+    def on_tick(self, ctx: PyContext, asset: str, price: float) -> None: ...
+    def on_deal_opened(self, ctx: PyContext, deal_json: str) -> None: ...
+    def on_deal_closed(self, ctx: PyContext, deal_json: str) -> None: ...
+    def on_params_update(self, ctx: PyContext, params_json: str) -> None: ...
     def trade(self, ctx: PyContext, asset: str, amount: float, timeframe: int, direction: Action) -> List[str]: ...
     def result(self, ctx: PyContext, id: str) -> str: ...
     def add(self, name: str, indicator: Any) -> None: ...
@@ -165,8 +322,41 @@ class PyBot:
     def __init__(
         self, client: RawPocketOption, strategy: PyStrategy, virtual_market: Optional[PyVirtualMarket] = None
     ) -> None: ...
+    def with_journal(self, journal: PyTradeJournal, strategy: str) -> None: ...
     def with_update_interval(self, millis: int) -> None: ...
-    def add_asset(self, asset: str, period: int) -> None: ...
+    def add_asset(self, asset: str, period: int, ticks: bool = False) -> None: ...
+    def set_param(self, key: str, value: Any) -> None: ...
+    def handle(self) -> PyBotHandle: ...
     async def run(self) -> None: ...
 
+class PyBotHandle:
+    def pause(self) -> bool: ...
+    def resume(self) -> bool: ...
+    def stop(self) -> None: ...
+    def status(self) -> str: ...
+    def set_param(self, key: str, value: Any) -> None: ...
+    def params(self) -> str: ...
+    async def stopped(self) -> None: ...
+
 def start_tracing(path: str, level: str, terminal: bool, layers: List[StreamLogsLayer]) -> None: ...
+def set_log_redaction(enabled: bool) -> None: ...
+def resample_candles(candles: str, from_period: int, to_period: int, keep_partial: bool = False) -> str: ...
+def resample_ticks(ticks: List[Tuple[int, float]], period: int, symbol: str, keep_partial: bool = False) -> str: ...
+
+class PocketOptionError(Exception):
+    code: int
+
+class TradeNotFoundError(Exception): ...
+class UninitializedError(Exception): ...
+class NotAllowedError(Exception): ...
+class InvalidParameterError(Exception): ...
+class OperationTimeoutError(PocketOptionError): ...
+class NotAuthorizedError(PocketOptionError): ...
+class AssetInactiveError(PocketOptionError): ...
+class AmountOutOfRangeError(PocketOptionError): ...
+class PayoutTooLowError(PocketOptionError): ...
+class EarlyCloseRejectedError(PocketOptionError): ...
+class ServerRejectedError(PocketOptionError): ...
+class ConnectionLostError(PocketOptionError): ...
+class TradeGuardError(PocketOptionError): ...
+class KillSwitchError(PocketOptionError): ...
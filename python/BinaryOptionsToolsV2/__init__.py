@@ -35,19 +35,43 @@ _rust_exported_names = [
     "RawValidator",
     "RawHandler",
     "RawHandle",
+    "CallbackHandle",
+    "CandleBuffer",
     "Logger",
     "LogBuilder",
     "PyConfig",
     "PyBot",
+    "PyBotHandle",
     "PyStrategy",
     "PyContext",
     "PyVirtualMarket",
+    "PyTradeJournal",
+    "PyParamSpace",
+    "PyOptimizer",
     "Action",
     "StreamLogsIterator",
     "StreamLogsLayer",
     "StreamIterator",
     "RawStreamIterator",
     "start_tracing",
+    "set_log_redaction",
+    "resample_candles",
+    "resample_ticks",
+    "PocketOptionError",
+    "TradeNotFoundError",
+    "UninitializedError",
+    "NotAllowedError",
+    "InvalidParameterError",
+    "OperationTimeoutError",
+    "NotAuthorizedError",
+    "AssetInactiveError",
+    "AmountOutOfRangeError",
+    "PayoutTooLowError",
+    "EarlyCloseRejectedError",
+    "ServerRejectedError",
+    "ConnectionLostError",
+    "TradeGuardError",
+    "KillSwitchError",
 ]
 __rust_all__ = [n for n in _rust_exported_names if n in globals()]
 
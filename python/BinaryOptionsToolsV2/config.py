@@ -24,15 +24,62 @@ class Config:
     max_allowed_loops: int = 100
     sleep_interval: int = 100
     reconnect_time: int = 5
+    reconnect_multiplier: float = 2.0
+    reconnect_max_delay: int = 3600
+    reconnect_jitter: float = 0.2
+    # What happens once max_allowed_loops failed attempts (short sessions included) are reached:
+    # "abort" stops the client, "backoff" waits reconnect_max_delay, "reconnect" rebuilds the connection
+    loop_limit_action: str = "abort"
     connection_initialization_timeout_secs: int = 60
     timeout_secs: int = 30
     urls: List[str] = field(default_factory=list)
     proxy: str = None
+    # Use the proxy of the environment (HTTPS_PROXY, ...) or of the OS when `proxy` is not set
+    system_proxy: bool = False
+    # Host -> IP addresses used instead of DNS, e.g. {"api-eu.po.market": ["104.18.1.1"]}
+    dns_overrides: Dict[str, List[str]] = None
+    # DNS-over-HTTPS endpoint (JSON API) used when the local DNS blocks the servers
+    doh_url: str = None
     user_agent: str = None
     origin: str = None
     sec_websocket_extensions: str = None
     tls_cipher_suites: List[str] = None
     tls_alpn: List[str] = None
+    # Identical trades placed within this window are rejected, 0 disables the check
+    duplicate_trade_window_ms: int = 2000
+    # Per-asset limits on open deals (orders awaiting confirmation included) and milliseconds
+    # between two orders, None and 0 disable them
+    max_open_per_asset: int = None
+    trade_cooldown_ms: int = 0
+    # Daily net profit, and drop of it from its high, at which trades are refused until the
+    # next server midnight, None disables them
+    daily_profit_target: float = None
+    max_drawdown: float = None
+    # Optional modules, disabling the unused ones saves memory and message routing
+    enable_pending_trades: bool = True
+    enable_historical_data: bool = True
+    enable_raw: bool = True
+    # File the session is saved to on shutdown and resumed from on the next start
+    session_path: str = None
+    # File every WebSocket frame is dumped to (gzip, session masked), to attach to bug reports
+    frame_dump: str = None
+    # Number of sent messages kept with the module sending them (see `audit_log`), 0 disables it
+    audit_log: int = 0
+    # Keep-alive ping cadence and payload, and seconds between auth re-sends (0 disables them)
+    keep_alive_interval_ms: int = 20000
+    keep_alive_payload: str = '42["ps"]'
+    keep_alive_reauth_interval_secs: int = 0
+    # Server time offset change (seconds) that discards the previous samples and re-syncs
+    server_time_drift_threshold_secs: int = 5
+    # What is loaded on connect before the client is ready: "minimal", "standard" or "full"
+    init_profile: str = "standard"
+    # Time zone naive datetimes and time strings are read in: "UTC", "local" or an IANA name
+    # like "Europe/Madrid". Times a daylight saving change skips or repeats are resolved by
+    # dst_policy: "earliest", "latest" or "reject"
+    timezone: str = "UTC"
+    dst_policy: str = "earliest"
+    # Return the timestamps of candles, ticks and deals as aware datetimes in `timezone`
+    aware_datetimes: bool = False
 
     # Logging configuration
     terminal_logging: bool = False
@@ -69,15 +116,40 @@ class Config:
         self._pyconfig.max_allowed_loops = self.max_allowed_loops
         self._pyconfig.sleep_interval = self.sleep_interval
         self._pyconfig.reconnect_time = self.reconnect_time
+        self._pyconfig.reconnect_multiplier = self.reconnect_multiplier
+        self._pyconfig.reconnect_max_delay = self.reconnect_max_delay
+        self._pyconfig.reconnect_jitter = self.reconnect_jitter
+        self._pyconfig.loop_limit_action = self.loop_limit_action
         self._pyconfig.connection_initialization_timeout_secs = self.connection_initialization_timeout_secs
         self._pyconfig.timeout_secs = self.timeout_secs
         self._pyconfig.urls = self.urls
         self._pyconfig.proxy = self.proxy
+        self._pyconfig.system_proxy = self.system_proxy
+        self._pyconfig.dns_overrides = self.dns_overrides
+        self._pyconfig.doh_url = self.doh_url
         self._pyconfig.user_agent = self.user_agent
         self._pyconfig.origin = self.origin
         self._pyconfig.sec_websocket_extensions = self.sec_websocket_extensions
         self._pyconfig.tls_cipher_suites = self.tls_cipher_suites
         self._pyconfig.tls_alpn = self.tls_alpn
+        self._pyconfig.duplicate_trade_window_ms = self.duplicate_trade_window_ms
+        self._pyconfig.max_open_per_asset = self.max_open_per_asset
+        self._pyconfig.trade_cooldown_ms = self.trade_cooldown_ms
+        self._pyconfig.daily_profit_target = self.daily_profit_target
+        self._pyconfig.max_drawdown = self.max_drawdown
+        self._pyconfig.enable_pending_trades = self.enable_pending_trades
+        self._pyconfig.enable_historical_data = self.enable_historical_data
+        self._pyconfig.enable_raw = self.enable_raw
+        self._pyconfig.session_path = self.session_path
+        self._pyconfig.frame_dump = self.frame_dump
+        self._pyconfig.audit_log = self.audit_log
+        self._pyconfig.keep_alive_interval_ms = self.keep_alive_interval_ms
+        self._pyconfig.keep_alive_payload = self.keep_alive_payload
+        self._pyconfig.keep_alive_reauth_interval_secs = self.keep_alive_reauth_interval_secs
+        self._pyconfig.server_time_drift_threshold_secs = self.server_time_drift_threshold_secs
+        self._pyconfig.init_profile = self.init_profile
+        self._pyconfig.timezone = self.timezone
+        self._pyconfig.dst_policy = self.dst_policy
 
     def _validate(self):
         """Validate config values, raising ValueError on invalid input."""
@@ -87,10 +159,40 @@ class Config:
             raise ValueError("sleep_interval must be non-negative")
         if self.reconnect_time < 1:
             raise ValueError("reconnect_time must be at least 1 second")
+        if self.reconnect_multiplier < 1.0:
+            raise ValueError("reconnect_multiplier must be at least 1.0")
+        if self.reconnect_max_delay < self.reconnect_time:
+            raise ValueError("reconnect_max_delay must not be lower than reconnect_time")
+        if not 0.0 <= self.reconnect_jitter < 1.0:
+            raise ValueError("reconnect_jitter must be in the range [0.0, 1.0)")
+        if self.loop_limit_action not in ("abort", "backoff", "reconnect"):
+            raise ValueError("loop_limit_action must be one of 'abort', 'backoff' or 'reconnect'")
         if self.connection_initialization_timeout_secs < 1:
             raise ValueError("connection_initialization_timeout_secs must be at least 1")
         if self.timeout_secs < 1:
             raise ValueError("timeout_secs must be at least 1")
+        if self.duplicate_trade_window_ms < 0:
+            raise ValueError("duplicate_trade_window_ms must be non-negative")
+        if self.max_open_per_asset is not None and self.max_open_per_asset < 1:
+            raise ValueError("max_open_per_asset must be at least 1")
+        if self.trade_cooldown_ms < 0:
+            raise ValueError("trade_cooldown_ms must be non-negative")
+        if self.daily_profit_target is not None and self.daily_profit_target <= 0:
+            raise ValueError("daily_profit_target must be positive")
+        if self.max_drawdown is not None and self.max_drawdown <= 0:
+            raise ValueError("max_drawdown must be positive")
+        if self.audit_log < 0:
+            raise ValueError("audit_log must be non-negative")
+        if self.keep_alive_interval_ms < 1:
+            raise ValueError("keep_alive_interval_ms must be positive")
+        if self.keep_alive_reauth_interval_secs < 0:
+            raise ValueError("keep_alive_reauth_interval_secs must be non-negative")
+        if self.server_time_drift_threshold_secs < 1:
+            raise ValueError("server_time_drift_threshold_secs must be positive")
+        if self.init_profile not in ("minimal", "standard", "full"):
+            raise ValueError("init_profile must be one of 'minimal', 'standard' or 'full'")
+        if self.dst_policy not in ("earliest", "latest", "reject"):
+            raise ValueError("dst_policy must be one of 'earliest', 'latest' or 'reject'")
 
     @classmethod
     def from_dict(cls, config_dict: Dict[str, Any]) -> "Config":
@@ -110,9 +212,35 @@ class Config:
             "max_allowed_loops": self.max_allowed_loops,
             "sleep_interval": self.sleep_interval,
             "reconnect_time": self.reconnect_time,
+            "reconnect_multiplier": self.reconnect_multiplier,
+            "reconnect_max_delay": self.reconnect_max_delay,
+            "reconnect_jitter": self.reconnect_jitter,
+            "loop_limit_action": self.loop_limit_action,
             "connection_initialization_timeout_secs": self.connection_initialization_timeout_secs,
             "timeout_secs": self.timeout_secs,
             "urls": self.urls,
+            "system_proxy": self.system_proxy,
+            "dns_overrides": self.dns_overrides,
+            "doh_url": self.doh_url,
+            "duplicate_trade_window_ms": self.duplicate_trade_window_ms,
+            "max_open_per_asset": self.max_open_per_asset,
+            "trade_cooldown_ms": self.trade_cooldown_ms,
+            "daily_profit_target": self.daily_profit_target,
+            "max_drawdown": self.max_drawdown,
+            "enable_pending_trades": self.enable_pending_trades,
+            "enable_historical_data": self.enable_historical_data,
+            "enable_raw": self.enable_raw,
+            "session_path": self.session_path,
+            "frame_dump": self.frame_dump,
+            "audit_log": self.audit_log,
+            "keep_alive_interval_ms": self.keep_alive_interval_ms,
+            "keep_alive_payload": self.keep_alive_payload,
+            "keep_alive_reauth_interval_secs": self.keep_alive_reauth_interval_secs,
+            "server_time_drift_threshold_secs": self.server_time_drift_threshold_secs,
+            "init_profile": self.init_profile,
+            "timezone": self.timezone,
+            "dst_policy": self.dst_policy,
+            "aware_datetimes": self.aware_datetimes,
             "terminal_logging": self.terminal_logging,
             "log_level": self.log_level,
             "extra_duration": self.extra_duration,
@@ -6,6 +6,7 @@ as well as specific classes for Pocket Option trading.
 """
 
 __all__ = [
+    "AssetInfo",
     "asynchronous",
     "login",
     "login_async",
@@ -15,6 +16,7 @@ __all__ = [
     "RawHandlerSync",
     "Validator",
 ]
+from .assets import AssetInfo
 from .tools.login import login, login_async
 from . import asynchronous, synchronous as synchronous
 from .asynchronous import PocketOptionAsync, RawHandler, Validator
@@ -0,0 +1,51 @@
+"""Structured asset metadata returned by `get_assets()`."""
+
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, List
+
+ASSET_TYPES = ("stock", "currency", "commodity", "cryptocurrency", "index")
+
+
+@dataclass(frozen=True)
+class AssetInfo:
+    """A tradable asset.
+
+    Attributes:
+        symbol (str): Symbol used to trade it, e.g. "EURUSD_otc"
+        name (str): Human-readable name, e.g. "EUR/USD OTC"
+        asset_type (str): One of "stock", "currency", "commodity", "cryptocurrency" or "index"
+        payout (int): Payout percentage
+        is_otc (bool): Whether this is an OTC asset
+        is_active (bool): Whether the asset can be traded right now
+        allowed_candles (List[int]): Allowed candle durations in seconds
+    """
+
+    symbol: str
+    name: str
+    asset_type: str
+    payout: int
+    is_otc: bool
+    is_active: bool
+    allowed_candles: List[int] = field(default_factory=list)
+    id: int = 0
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "AssetInfo":
+        candles = [c["time"] if isinstance(c, dict) else c for c in data.get("allowed_candles", [])]
+        return cls(
+            symbol=data["symbol"],
+            name=data.get("name", data["symbol"]),
+            asset_type=data.get("asset_type", ""),
+            payout=data.get("payout", 0),
+            is_otc=data.get("is_otc", False),
+            is_active=data.get("is_active", False),
+            allowed_candles=candles,
+            id=data.get("id", 0),
+        )
+
+    def matches(self, query: str) -> bool:
+        """Whether the symbol or name contains `query`, ignoring case."""
+        query = query.lower()
+        return query in self.symbol.lower() or query in self.name.lower()
@@ -6,13 +6,16 @@ import time
 import warnings
 from collections import deque
 from datetime import datetime, timezone, timedelta
-from typing import TYPE_CHECKING, Dict, List, Optional, Tuple, Union, AsyncGenerator
+from decimal import Decimal
+from typing import TYPE_CHECKING, Any, Callable, Dict, List, Optional, Tuple, Union, AsyncGenerator
 
 from ..config import Config
 from ..validator import Validator
+from .assets import ASSET_TYPES, AssetInfo
+from .tools.arrow_stream import ArrowIpcServer, arrow_batches, candle_columns_to_arrow
 
 if TYPE_CHECKING:
-    from ..BinaryOptionsToolsV2 import Logger, RawPocketOption
+    from ..BinaryOptionsToolsV2 import CallbackHandle, CandleBuffer, Logger, RawPocketOption
 
 if sys.version_info < (3, 10):
 
@@ -29,8 +32,60 @@ class AsyncSubscription:
     def __aiter__(self):
         return self
 
+    def _parse(self, item: str) -> Any:
+        return json.loads(item)
+
     async def __anext__(self):
-        return json.loads(await anext(self.subscription))
+        return self._parse(await anext(self.subscription))
+
+    async def next_timeout(self, seconds: float) -> Optional[Any]:
+        """Waits at most `seconds` for the next item, returns None when none arrived."""
+        item = await self.subscription.next_timeout(seconds)
+        return None if item is None else self._parse(item)
+
+    async def collect(self, n: int, timeout: Optional[float] = None) -> List[Any]:
+        """Waits for up to `n` items, returning the ones received once `timeout` seconds elapsed.
+
+        Raises StopAsyncIteration only when the stream ended before any item was received.
+        """
+        return [self._parse(item) for item in await self.subscription.collect(n, timeout)]
+
+
+class AsyncMultiSubscription(AsyncSubscription):
+    """Asynchronous Iterator over `(asset, candle)` tuples, see `PocketOptionAsync.subscribe_many`"""
+
+    def _parse(self, item: str) -> Tuple[str, Dict]:
+        asset, candle = json.loads(item)
+        return asset, candle
+
+
+SubscriptionSpec = Union[None, str, int, timedelta, Tuple[str, Union[None, int, float, timedelta]]]
+
+# Stakes and prices: `Decimal` and `str` values reach the server exactly as written, floats may
+# not ("2.33" stays 2.33 where a float could become 2.3299999).
+Amount = Union[Decimal, str, int, float]
+
+
+def subscription_spec(asset: str, sub_type: SubscriptionSpec = None) -> Tuple[str, str, Optional[float]]:
+    """Normalizes a `subscribe_many` entry into the `(asset, kind, value)` triple of the Rust client.
+
+    `None` subscribes to every price update, an int to candles of that many updates, a timedelta to
+    clock aligned candles of that period. `(kind, value)` names the kind explicitly: "none", "chunk",
+    "time" or "time_aligned".
+    """
+    if isinstance(sub_type, tuple):
+        kind, value = sub_type
+    elif sub_type is None or sub_type == "none":
+        kind, value = "none", None
+    elif isinstance(sub_type, bool) or not isinstance(sub_type, (int, timedelta)):
+        raise ValueError(f"Invalid subscription type for {asset}: {sub_type!r}")
+    elif isinstance(sub_type, int):
+        kind, value = "chunk", sub_type
+    else:
+        kind, value = "time_aligned", sub_type
+    if isinstance(value, timedelta):
+        value = value.total_seconds()
+    return asset, kind, None if value is None else float(value)
 
 
 class AsyncRawSubscription:
@@ -44,6 +99,14 @@ class AsyncRawSubscription:
     async def __anext__(self):
         return await anext(self.subscription)
 
+    async def next_timeout(self, seconds: float) -> Optional[str]:
+        """Waits at most `seconds` for the next message, returns None when none arrived."""
+        return await self.subscription.next_timeout(seconds)
+
+    async def collect(self, n: int, timeout: Optional[float] = None) -> List[str]:
+        """Waits for up to `n` messages, returning the ones received once `timeout` seconds elapsed."""
+        return await self.subscription.collect(n, timeout)
+
 
 class RawHandler:
     """
@@ -108,6 +171,31 @@ class RawHandler:
         """
         return await self._handler.send_and_wait(message)
 
+    async def send_and_wait_for(self, message: str, correlation_id: str, timeout: float = 30.0) -> str:
+        """
+        Send a message and wait for the response carrying `correlation_id`.
+
+        Only available on handlers created with a `correlation_path`, the response is
+        routed by the id found at that path so several requests can be in flight at once.
+
+        Args:
+            message: Message to send
+            correlation_id: Id the response carries at the handler's correlation path
+            timeout: Seconds to wait for the response
+
+        Returns:
+            str: The response with the given correlation id
+
+        Example:
+            ```python
+            handler = await client.create_raw_handler(
+                Validator.contains('"requestId"'), correlation_path="$.data.requestId"
+            )
+            response = await handler.send_and_wait_for('42["openOrder",{"requestId":"abc"}]', "abc")
+            ```
+        """
+        return await self._handler.send_and_wait_for(message, correlation_id, timeout)
+
     async def wait_next(self) -> str:
         """
         Wait for the next message that matches this handler's validator.
@@ -241,6 +329,12 @@ def sanitize_and_validate_ssid(ssid: str, logger: "Logger") -> str:
 
 
 # This file contains all the async code for the PocketOption Module
+# Keys of the unix timestamps `aware_datetimes` converts in candles, ticks and deals
+_TIMESTAMP_KEYS = ("time", "timestamp", "openTimestamp", "closeTimestamp")
+
+TimeLike = Union[datetime, int, float, str]
+
+
 class PocketOptionAsync:
     def __init__(self, ssid: str, url: Optional[str] = None, config: Optional[Union[Config, dict, str]] = None, **_):
         """
@@ -347,21 +441,102 @@ class PocketOptionAsync:
         """
         await self.shutdown()
 
-    async def _place_trade(self, method, asset: str, amount: float, time: int, check_win: bool) -> Tuple[str, Dict]:
+    def to_timestamp(self, value: TimeLike) -> int:
+        """Converts a time to a unix timestamp in seconds.
+
+        Numbers are unix timestamps and aware datetimes are taken as they are. Naive datetimes
+        and strings without an offset ("2025-03-30 09:30:00", "2025-03-30") are read in
+        `config.timezone`, with the times skipped or repeated by a daylight saving change
+        resolved by `config.dst_policy`.
+        """
+        return self.client.to_timestamp(value)
+
+    def to_datetime(self, timestamp: float) -> datetime:
+        """Converts a unix timestamp to an aware datetime in `config.timezone`."""
+        return self.client.to_datetime(timestamp)
+
+    def _localize(self, records):
+        """Replaces the unix timestamps of candles, ticks or deals with aware datetimes when
+        `config.aware_datetimes` is set."""
+        if not self.config.aware_datetimes or records is None:
+            return records
+        for record in records if isinstance(records, list) else [records]:
+            for key in _TIMESTAMP_KEYS:
+                value = record.get(key)
+                if isinstance(value, (int, float)) and not isinstance(value, bool):
+                    record[key] = self.client.to_datetime(value)
+        return records
+
+    async def _place_trade(
+        self,
+        method,
+        asset: str,
+        amount: Amount,
+        time: int,
+        check_win: bool,
+        allow_duplicate: bool,
+        min_payout: Optional[int] = None,
+        payout_wait: float = 0.0,
+    ) -> Tuple[str, Dict]:
         """Internal helper to place a trade and optionally wait for the result."""
-        trade_id, trade = await method(asset, amount, time)
+        trade_id, trade = await method(asset, amount, time, allow_duplicate, min_payout, payout_wait)
         if check_win:
             return trade_id, await self.check_win(trade_id, timeout_seconds=time + 30)
         trade = json.loads(trade)
         return trade_id, trade
 
-    async def buy(self, asset: str, amount: float, time: int, check_win: bool = False) -> Tuple[str, Dict]:
-        """Places a buy (call) order."""
-        return await self._place_trade(self.client.buy, asset, amount, time, check_win)
+    async def buy(
+        self,
+        asset: str,
+        amount: Amount,
+        time: int,
+        check_win: bool = False,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait: float = 0.0,
+    ) -> Tuple[str, Dict]:
+        """Places a buy (call) order.
+
+        Cancelling the awaiting task skips the order if it was not sent yet; an order that
+        was already sent is still tracked and shows up in the opened/closed deals.
+
+        An identical order placed within `Config.duplicate_trade_window_ms` is rejected
+        unless `allow_duplicate` is set.
+
+        With `min_payout` set, the order raises `PayoutTooLowError` if the asset payout (in
+        percent) is lower, after waiting up to `payout_wait` seconds for it to recover.
+
+        `amount` can be a `Decimal` or a string to avoid float rounding. The money fields of the
+        returned deal (`amount`, `profit`, ...) are decimal strings, read them with `Decimal()`.
+        """
+        return await self._place_trade(
+            self.client.buy, asset, amount, time, check_win, allow_duplicate, min_payout, payout_wait
+        )
 
-    async def sell(self, asset: str, amount: float, time: int, check_win: bool = False) -> Tuple[str, Dict]:
-        """Places a sell (put) order."""
-        return await self._place_trade(self.client.sell, asset, amount, time, check_win)
+    async def sell(
+        self,
+        asset: str,
+        amount: Amount,
+        time: int,
+        check_win: bool = False,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait: float = 0.0,
+    ) -> Tuple[str, Dict]:
+        """Places a sell (put) order.
+
+        Cancelling the awaiting task skips the order if it was not sent yet; an order that
+        was already sent is still tracked and shows up in the opened/closed deals.
+
+        An identical order placed within `Config.duplicate_trade_window_ms` is rejected
+        unless `allow_duplicate` is set.
+
+        With `min_payout` set, the order raises `PayoutTooLowError` if the asset payout (in
+        percent) is lower, after waiting up to `payout_wait` seconds for it to recover.
+        """
+        return await self._place_trade(
+            self.client.sell, asset, amount, time, check_win, allow_duplicate, min_payout, payout_wait
+        )
 
     async def check_win(self, id: str, timeout_seconds: Optional[int] = None) -> dict:
         """
@@ -403,7 +578,7 @@ class PocketOptionAsync:
         try:
             # Use asyncio.wait_for as additional protection against hanging
             trade = await asyncio.wait_for(self._get_trade_result(id), timeout=actual_timeout)
-            return trade
+            return self._localize(trade)
         except asyncio.TimeoutError:
             raise TimeoutError(f"Timeout waiting for trade result for ID: {id}")
 
@@ -414,6 +589,65 @@ class PocketOptionAsync:
         """
         return await self.client.get_deal_end_time(trade_id)
 
+    async def time_to_expiry(self, trade_id: str) -> float:
+        """
+        Returns the seconds left before a deal expires, computed with server time.
+
+        Returns 0 once the deal closed.
+
+        Raises:
+            PocketOptionError: If the deal is unknown.
+        """
+        return await self.client.time_to_expiry(trade_id)
+
+    async def deal_countdown_stream(self, trade_id: str) -> AsyncSubscription:
+        """Streams the seconds left before a deal expires, once per second.
+
+        The first value is sent immediately and the iterator stops after yielding 0.
+
+        Example:
+            ```python
+            async for left in await client.deal_countdown_stream(trade_id):
+                print(f"{left:.0f}s left")
+            ```
+        """
+        return AsyncSubscription(await self.client.deal_countdown_stream(trade_id))
+
+    async def close_deal_early(self, trade_id: str) -> dict:
+        """
+        Closes an opened deal before its expiry.
+
+        Returns:
+            dict: The confirmation with `deal_id`, `returned` (the amount credited back to the
+            balance) and the closed `deal`.
+
+        Raises:
+            EarlyCloseRejectedError: If the server or account type does not allow closing early.
+            PocketOptionError: If the deal is unknown.
+        """
+        return json.loads(await self.client.close_deal_early(trade_id))
+
+    async def deal_status(
+        self, trade_id: str, wait: bool = False, history_fallback_secs: Optional[float] = None
+    ) -> dict:
+        """
+        Returns where a deal is in its lifecycle, without waiting by default.
+
+        Args:
+            trade_id (str): The deal identifier.
+            wait (bool): Wait until the deal closes instead of returning its current status.
+            history_fallback_secs (Optional[float]): When waiting, look the deal up in the server
+                history if its result wasn't received this many seconds after its expiry.
+
+        Returns:
+            dict: `{"status": "open" | "closing" | "closed", "deal": {...}}`, "closing" meaning
+            the deal expired but its result hasn't been received yet.
+
+        Raises:
+            PocketOptionError: If the deal is unknown.
+        """
+        return json.loads(await self.client.deal_status(trade_id, wait, history_fallback_secs))
+
     async def _get_trade_result(self, id: str) -> dict:
         """Internal method to retrieve and classify trade result with timeout protection.
 
@@ -426,7 +660,7 @@ class PocketOptionAsync:
         Returns:
             dict: Trade result dictionary containing:
                 - id (str): The trade identifier
-                - profit (float): The profit/loss amount
+                - profit (str): The profit/loss amount as a decimal string
                 - result (str): Classified outcome ("win", "loss", or "draw")
                 - Additional fields from the server response
 
@@ -523,7 +757,7 @@ class PocketOptionAsync:
         closed, forming = await anext(gen)
         return closed
 
-    async def get_candles_advanced(self, asset: str, period: int, offset: int, time: int) -> List[Dict]:
+    async def get_candles_advanced(self, asset: str, period: int, offset: int, time: TimeLike) -> List[Dict]:
         """
         Retrieves historical candle data for an asset.
 
@@ -531,7 +765,8 @@ class PocketOptionAsync:
             asset (str): Trading asset (e.g., "EURUSD_otc")
             period (int): Historical period in seconds to fetch
             offset (int): Candle timeframe in seconds (e.g., 60 for 1-minute candles)
-            time (int): Time to fetch candles from
+            time (datetime | int | str): Time to fetch candles from, naive datetimes and
+                strings are read in `config.timezone`
 
         Returns:
             List[Dict]: List of candles, each containing:
@@ -550,8 +785,48 @@ class PocketOptionAsync:
               and can introduce gaps if called sequentially during live trading.
               For live gap-free candle feeds, use `get_candles_live()` instead.
         """
-        candles = await self.client.get_candles_advanced(asset, period, offset, time)
-        return json.loads(candles)
+        candles = await self.client.get_candles_advanced(asset, period, offset, self.to_timestamp(time))
+        return self._localize(json.loads(candles))
+
+    async def get_candles_columns(self, asset: str, period: int, offset: int) -> Dict[str, List]:
+        """Retrieves historical candles as one list per column.
+
+        Same candles as `get_candles_advanced()` up to now, built without a JSON row per
+        candle, so large histories load straight into a dataframe.
+
+        Args:
+            asset (str): Trading asset (e.g., "EURUSD_otc")
+            period (int): Historical period in seconds to fetch
+            offset (int): Candle timeframe in seconds (e.g., 60 for 1-minute candles)
+
+        Returns:
+            Dict[str, List]: `timestamp`, `open`, `high`, `low`, `close` and `volume` lists
+
+        Examples:
+            ```python
+            df = pandas.DataFrame(await client.get_candles_columns("EURUSD_otc", 3600, 60))
+            df = polars.DataFrame(await client.get_candles_columns("EURUSD_otc", 3600, 60))
+            ```
+        """
+        return await self.client.get_candles_columns(asset, period, offset)
+
+    async def get_candles_arrow(self, asset: str, period: int, offset: int):
+        """Retrieves historical candles as a `pyarrow.RecordBatch`.
+
+        Needs the `arrow` extra (`pip install BinaryOptionsToolsV2[arrow]`).
+
+        Args:
+            asset (str): Trading asset (e.g., "EURUSD_otc")
+            period (int): Historical period in seconds to fetch
+            offset (int): Candle timeframe in seconds (e.g., 60 for 1-minute candles)
+
+        Returns:
+            pyarrow.RecordBatch: The columns of `get_candles_columns()`
+
+        Raises:
+            ImportError: If pyarrow is not installed
+        """
+        return candle_columns_to_arrow(await self.get_candles_columns(asset, period, offset))
 
     async def get_candles_live(
         self,
@@ -765,6 +1040,34 @@ class PocketOptionAsync:
             await asyncio.sleep(0.1)
         return await self.client.balance()
 
+    async def balances(self) -> Dict[str, Dict]:
+        """
+        Retrieves the last balance reported for each account type.
+
+        Returns:
+            Dict[str, Dict]: Balances keyed by "demo", "real" or "tournament", each with
+            "amount", "currency" and "updated_at". Account types without a reported balance are missing.
+
+        Note:
+            Trades are rejected while the balance of the current account type is unknown.
+        """
+        balances = json.loads(await self.client.balances())
+        for balance in balances.values():
+            balance["amount"] = float(balance["amount"])
+        return balances
+
+    async def currency(self) -> Optional[str]:
+        """
+        Retrieves the account currency code (e.g. "USD").
+
+        Returns:
+            Optional[str]: Currency code, or None if the server has not reported it yet
+
+        Note:
+            Trade amounts are validated against limits specific to this currency.
+        """
+        return await self.client.currency()
+
     async def opened_deals(self) -> List[str]:
         """Retrieves a list of all currently open (active) deals.
 
@@ -836,15 +1139,15 @@ class PocketOptionAsync:
         deal_json = await self.client.get_opened_deal(id)
         if deal_json is None:
             return None
-        return json.loads(deal_json)
+        return self._localize(json.loads(deal_json))
 
     async def open_pending_order(
         self,
         open_type: int,
-        amount: float,
+        amount: Amount,
         asset: str,
-        open_time: Union[int, str],
-        open_price: float,
+        open_time: Union[int, str, datetime],
+        open_price: Amount,
         timeframe: int,
         min_payout: int,
         command: int,
@@ -854,11 +1157,12 @@ class PocketOptionAsync:
 
         Args:
             open_type (int): The type of the pending order.
-            amount (float): The amount to trade.
+            amount (Decimal | str | float): The amount to trade.
             asset (str): The asset symbol (e.g., "EURUSD_otc").
-            open_time (int | str): The server time to open the trade.
-                Can be a Unix timestamp (int) or a formatted string "YYYY-MM-DD HH:MM:SS".
-            open_price (float): The price to open the trade at.
+            open_time (int | str | datetime): The time to open the trade.
+                Can be a Unix timestamp (int), a datetime or a formatted string "YYYY-MM-DD HH:MM:SS".
+                Naive datetimes and strings are read in `config.timezone`.
+            open_price (Decimal | str | float): The price to open the trade at.
             timeframe (int): The duration of the trade in seconds.
             min_payout (int): The minimum payout percentage required.
             command (int): The trade direction (0 for Call, 1 for Put).
@@ -869,6 +1173,10 @@ class PocketOptionAsync:
         # Backward compatibility: If the underlying Rust client still expects an integer
         # but we received a string, try to convert it if it's numeric, or fallback to 0.
         # This handles cases where the binary extension hasn't been updated to support strings.
+        if isinstance(open_time, datetime) or (isinstance(open_time, str) and not open_time.strip().isdigit()):
+            # The server reads the open time in UTC.
+            utc_open_time = datetime.fromtimestamp(self.to_timestamp(open_time), timezone.utc)
+            open_time = utc_open_time.strftime("%Y-%m-%d %H:%M:%S")
         actual_open_time = open_time
         try:
             # We try to call it with the original value first
@@ -992,13 +1300,158 @@ class PocketOptionAsync:
                 async with PocketOptionAsync(ssid) as client:
                     closed_ids = await client.closed_deals()
                     closed = [await client.get_closed_deal(deal_id) for deal_id in closed_ids]
-                    total_pnl = sum(d['profit'] for d in closed)
+                    total_pnl = sum(Decimal(d['profit']) for d in closed)
                     wins = sum(1 for d in closed if d['result'] == 'win')
                     print(f"Total P/L: {total_pnl}, Win rate: {wins}/{len(closed)}")
             ```
         """
         return json.loads(await self.client.closed_deals())
 
+    async def trade_stats(self) -> Dict:
+        """Returns win/loss statistics over the deals closed since the client started.
+
+        Returns:
+            Dict: Statistics including:
+                - total_trades, wins, losses, draws
+                - win_rate: Wins over decided trades (0.0 - 1.0)
+                - rolling_win_rate: Win rate over the last `rolling_window` decided trades
+                - profit_factor: Gross profit / gross loss (None while there are no losses)
+                - net_profit, gross_profit, gross_loss
+                - current_streak: Positive for a win streak, negative for a loss streak
+                - max_win_streak, max_loss_streak
+                - per_asset: Per-asset breakdown with trades, wins, losses, draws, net_profit and win_rate
+
+        Examples:
+            ```python
+            stats = await client.trade_stats()
+            print(f"Win rate: {stats['win_rate']:.1%}, streak: {stats['current_streak']}")
+            ```
+        """
+        return json.loads(await self.client.trade_stats())
+
+    async def reset_trade_stats(self) -> None:
+        """Resets the statistics returned by `trade_stats`, `trade_latency` and `slippage_stats`."""
+        await self.client.reset_trade_stats()
+
+    async def kill_switch_status(self) -> Dict:
+        """Returns the result of the current server day against the kill switch limits.
+
+        The limits are set with `daily_profit_target` and `max_drawdown` in `Config`. Once one
+        is hit every trade raises `KillSwitchError` until the next server midnight.
+
+        Returns:
+            Dict: `day`, `pnl` (net profit of the day), `peak` (its highest value) and `tripped`,
+            None or the event that stopped the trading (see `kill_switch_events`).
+        """
+        return json.loads(await self.client.kill_switch_status())
+
+    async def kill_switch_events(self) -> AsyncSubscription:
+        """Subscribe to the kill switch stopping the trading, at most once per server day.
+
+        Returns:
+            AsyncSubscription yielding dicts with `reason` ("profit_target" or "max_drawdown"),
+            `pnl`, `peak`, `at` and `resets_at`.
+        """
+        return AsyncSubscription(await self.client.kill_switch_events())
+
+    async def last_trade_timings(self) -> Optional[Dict]:
+        """Returns how long each stage of the most recently resolved order took.
+
+        Useful to diagnose `Trade task timeout` errors: a missing `server` value means the
+        server never answered, a large `queued` value points at a congested client.
+
+        Returns:
+            Optional[Dict]: None before the first order, otherwise:
+                - request_id, asset
+                - outcome: "opened", "failed" or "timed_out"
+                - enqueued_at: Unix timestamp in milliseconds
+                - queued: Enqueued to frame sent (ms)
+                - server: Frame sent to server answer (ms), None if it never answered
+                - confirm: Server answer to deal returned (ms)
+                - total: Enqueued to resolved (ms)
+        """
+        timings = await self.client.last_trade_timings()
+        return json.loads(timings) if timings is not None else None
+
+    async def trade_latency(self) -> Dict:
+        """Returns latency histograms for the orders placed since the client started.
+
+        Returns:
+            Dict: `opened`, `failed` and `timed_out` counters, the `last` timings and one
+                histogram per stage (`queued`, `server`, `confirm`, `total`) with
+                `bounds_ms`, `counts`, `count`, `mean_ms` and `max_ms`.
+        """
+        return json.loads(await self.client.trade_latency())
+
+    async def slippage_stats(self) -> Dict:
+        """Returns the slippage of the deals opened since the client started.
+
+        The slippage of a deal is its open price minus the last price of the asset when the
+        order was sent (the other way round for a put), positive when the fill was worse. It is
+        also set as `slippage` on the deal returned by `buy` / `sell`. Only subscribed assets
+        have a price to compare with.
+
+        Returns:
+            Dict: `deals`, `requotes` (deals opened at another price), `adverse` (at a worse
+                price), `mean` and `worst` slippage, and the same figures in `per_asset`.
+        """
+        return json.loads(await self.client.slippage_stats())
+
+    async def closed_deals_history(self, start: TimeLike, end: Optional[TimeLike] = None) -> List[Dict]:
+        """Fetches the account's closed deals from the server for a time range.
+
+        Unlike `closed_deals`, which only knows about deals closed during this session,
+        this queries the server (following pagination) so a restarted bot can rebuild
+        its trade history, e.g. to compute today's PnL.
+
+        Args:
+            start (datetime | int | str): Range start, naive datetimes and strings are read in
+                `config.timezone`
+            end (datetime | int | str, optional): Range end, defaults to now
+
+        Returns:
+            List[Dict]: Closed deals, in the same format as `get_closed_deal`
+
+        Examples:
+            ```python
+            midnight = datetime.now(timezone.utc).replace(hour=0, minute=0, second=0, microsecond=0)
+            deals = await client.closed_deals_history(midnight)
+            print(f"Today's PnL: {sum(Decimal(d['profit']) for d in deals)}")
+            ```
+        """
+
+        end_ts = self.to_timestamp(end) if end is not None else int(time.time())
+        deals = json.loads(await self.client.closed_deals_history(self.to_timestamp(start), end_ts))
+        return self._localize(deals)
+
+    async def ticks_range(self, asset: str, start: TimeLike, end: Optional[TimeLike] = None) -> List[Dict]:
+        """Fetches the tick history of an asset for an arbitrary time range.
+
+        Pages backwards through the server history from `end` until `start` is covered,
+        merging the pages and dropping duplicated ticks, e.g. to build ML datasets.
+
+        Args:
+            asset (str): Trading asset (e.g., "EURUSD_otc")
+            start (datetime | int | str): Range start, naive datetimes and strings are read in
+                `config.timezone`
+            end (datetime | int | str, optional): Range end, defaults to now
+
+        Returns:
+            List[Dict]: Ticks sorted by time, each with `time` (unix seconds, or a datetime
+                with `config.aware_datetimes`) and `price`
+
+        Examples:
+            ```python
+            end = datetime.now(timezone.utc)
+            ticks = await client.ticks_range("EURUSD_otc", end - timedelta(hours=6), end)
+            df = pandas.DataFrame(ticks)
+            ```
+        """
+
+        end_ts = self.to_timestamp(end) if end is not None else int(time.time())
+        ticks = json.loads(await self.client.ticks_range(asset, self.to_timestamp(start), end_ts))
+        return self._localize([{"time": ts, "price": price} for ts, price in ticks])
+
     async def get_closed_deal(self, id: str) -> Optional[Dict]:
         """
         Retrieves details of a specific closed deal by its ID.
@@ -1036,7 +1489,7 @@ class PocketOptionAsync:
         deal_json = await self.client.get_closed_deal(id)
         if deal_json is None:
             return None
-        return json.loads(deal_json)
+        return self._localize(json.loads(deal_json))
 
     async def clear_closed_deals(self) -> None:
         """Removes all closed deals from the client's memory.
@@ -1101,7 +1554,7 @@ class PocketOptionAsync:
             int: If asset is a string, returns the payout for that specific asset
             none: If asset didn't match and valid asset none will be returned
         """
-        payout = json.loads(await self.client.payout())
+        payout = {a.symbol: a.payout for a in await self.get_assets(active_only=True)}
         if isinstance(asset, str):
             return payout.get(asset)
         elif isinstance(asset, list):
@@ -1109,6 +1562,47 @@ class PocketOptionAsync:
         else:
             return payout
 
+    async def get_assets(
+        self,
+        query: Optional[str] = None,
+        asset_type: Optional[str] = None,
+        otc: Optional[bool] = None,
+        active_only: bool = False,
+    ) -> List[AssetInfo]:
+        """
+        Retrieves the assets with their payout, type and allowed candle durations.
+
+        Args:
+            query (Optional[str]): Keeps the assets whose symbol or name contains it, ignoring case
+            asset_type (Optional[str]): Keeps the assets of this type: "stock", "currency",
+                "commodity", "cryptocurrency" or "index"
+            otc (Optional[bool]): Keeps only the OTC assets when True, only the regular ones when False
+            active_only (bool): Keeps only the assets that can be traded right now
+
+        Returns:
+            List[AssetInfo]: The matching assets, sorted by symbol.
+
+        Raises:
+            ValueError: If `asset_type` is unknown.
+
+        Example:
+            ```python
+            for asset in await client.get_assets("eur", otc=True, active_only=True):
+                print(f"{asset.symbol}: {asset.payout}%")
+            ```
+        """
+        if asset_type is not None and asset_type not in ASSET_TYPES:
+            raise ValueError(f"asset_type must be one of {', '.join(ASSET_TYPES)}, got {asset_type!r}")
+        assets = [AssetInfo.from_dict(a) for a in json.loads(await self.client.get_assets())]
+        return [
+            a
+            for a in assets
+            if (query is None or a.matches(query))
+            and (asset_type is None or a.asset_type == asset_type)
+            and (otc is None or a.is_otc == otc)
+            and (a.is_active or not active_only)
+        ]
+
     async def active_assets(self) -> List[Dict]:
         """
         Retrieves a list of all active assets.
@@ -1136,6 +1630,74 @@ class PocketOptionAsync:
         assets = json.loads(assets_json)
         return list(assets.values()) if isinstance(assets, dict) else assets
 
+    async def asset_schedule(self, asset: str) -> Dict:
+        """
+        Returns the trading hours of an asset, inferred from its type and OTC status.
+
+        OTC pairs and cryptocurrencies trade around the clock; other assets follow the forex
+        week or the US stock session. Holidays are not known, the server's `is_active` flag
+        remains authoritative.
+
+        Args:
+            asset (str): Trading asset symbol (e.g., "EURUSD", "#AAPL")
+
+        Returns:
+            Dict: A dictionary with:
+                - symbol: Asset symbol
+                - always_open: True if the asset trades at any time
+                - windows: List of weekly UTC windows with `open_day`, `open_time`,
+                  `close_day` and `close_time` (e.g. "Sun", "21:00:00")
+        """
+        return json.loads(await self.client.asset_schedule(asset))
+
+    async def is_tradeable_now(self, asset: str) -> bool:
+        """
+        Checks whether an asset can be traded right now.
+
+        Args:
+            asset (str): Trading asset symbol
+
+        Returns:
+            bool: True if the server reports the asset active and its schedule is open at
+                the current server time.
+        """
+        return await self.client.is_tradeable_now(asset)
+
+    async def refresh_assets(self, timeout: float = 30.0) -> List[Dict]:
+        """
+        Asks the server for the asset list again instead of waiting for it to be pushed.
+
+        Args:
+            timeout (float): The maximum time to wait for the new list in seconds. Default is 30.0.
+
+        Returns:
+            List[Dict]: Every asset, in the same format as `active_assets()`.
+
+        Raises:
+            TimeoutError: If the list is not received within the timeout period.
+        """
+        assets_json = await self.client.refresh_assets(timeout)
+        assets = json.loads(assets_json)
+        return list(assets.values()) if isinstance(assets, dict) else assets
+
+    async def assets_changed(self) -> AsyncSubscription:
+        """Subscribe to changes between consecutive asset lists.
+
+        Each change is a dict with a `symbol` and a `kind` key:
+            - "opened" / "closed": the asset became tradable or stopped being tradable
+            - "payout_changed": includes the `old` and `new` payout
+            - "added": a new asset, includes `payout` and `is_active`
+            - "removed": the asset is no longer listed
+
+        Example:
+            ```python
+            async for change in await client.assets_changed():
+                if change["kind"] == "opened":
+                    print(f"{change['symbol']} is open")
+            ```
+        """
+        return AsyncSubscription(await self.client.assets_changed())
+
     async def history(self, asset: str, period: int) -> List[Dict]:
         """Retrieves historical price data for an asset.
 
@@ -1186,7 +1748,7 @@ class PocketOptionAsync:
             and may have different availability or latency characteristics. For advanced
             historical data with specific time ranges, consider using `get_candles_advanced()`.
         """
-        return json.loads(await self.client.history(asset, period))
+        return self._localize(json.loads(await self.client.history(asset, period)))
 
     async def compile_candles(self, asset: str, custom_period: int, lookback_period: int) -> List[Dict]:
         """Compiles custom candlesticks from raw tick history.
@@ -1232,7 +1794,7 @@ class PocketOptionAsync:
         if not isinstance(lookback_period, int) or lookback_period <= 0:
             raise ValueError("lookback_period must be a positive integer")
 
-        return json.loads(await self.client.compile_candles(asset, custom_period, lookback_period))
+        return self._localize(json.loads(await self.client.compile_candles(asset, custom_period, lookback_period)))
 
     async def send_raw(self, message: str) -> None:
         """Send a raw Engine.io/Socket.io message directly over the connection."""
@@ -1242,6 +1804,159 @@ class PocketOptionAsync:
         """Subscribe to all incoming WebSocket messages verbatim."""
         return AsyncRawSubscription(await self.client.subscribe_raw())
 
+    async def add_middleware(self, callback, queue_size: int = 1024) -> None:
+        """Observe every WebSocket frame sent or received, e.g. for analytics or debugging.
+
+        Args:
+            callback: Called as `callback(message, direction)` with the frame text and a
+                direction of "send" or "receive". It runs on a background thread, so it must
+                not touch the event loop directly.
+            queue_size: Frames buffered for the callback, frames arriving while the buffer is
+                full are dropped instead of slowing down the connection.
+
+        Example:
+            ```python
+            await client.add_middleware(lambda msg, direction: print(direction, msg[:80]))
+            ```
+        """
+        await self.client.add_middleware(callback, queue_size)
+
+    async def reconnect_events(self) -> AsyncSubscription:
+        """Subscribe to reconnection events from the connection runner.
+
+        Each event is a dict with a `type` key:
+            - "scheduled": a connection attempt failed; includes `attempt`, `max_attempts`
+              (None if unlimited), `delay` (seconds) and `error`
+            - "connected": the connection was (re-)established; includes `attempts`
+            - "limit_reached": `max_allowed_loops` failed attempts were reached; includes
+              `attempts`, `cycles_last_minute`, `last_error`, `modules` ([name, messages]
+              pairs of the last session, busiest first), `queued_outgoing` and the `action`
+              taken (see `Config.loop_limit_action`)
+            - "exhausted": `max_allowed_loops` was reached and the client is shutting down
+
+        Example:
+            ```python
+            async for event in await client.reconnect_events():
+                if event["type"] == "scheduled":
+                    print(f"Reconnect #{event['attempt']} in {event['delay']:.1f}s")
+            ```
+        """
+        return AsyncSubscription(await self.client.reconnect_events())
+
+    async def scanner(self, assets: List[str], slots: int = 4, dwell_secs: float = 5.0) -> AsyncSubscription:
+        """Scan more assets than there are subscription slots.
+
+        The assets are subscribed `slots` at a time for `dwell_secs` each, then make room for
+        the next group. When its window ends, the last candle of every asset is yielded.
+
+        Args:
+            assets: Assets to scan, all must exist.
+            slots: Assets subscribed at the same time, keep it within the subscription limit.
+            dwell_secs: How long each group stays subscribed.
+
+        Returns:
+            AsyncSubscription yielding dicts with `asset`, `candle`, `received_at` and `rotation`.
+
+        Example:
+            ```python
+            async for quote in await client.scanner(["EURUSD_otc", "GBPUSD_otc", "AUDCAD_otc"], slots=2):
+                print(quote["asset"], quote["candle"]["close"])
+            ```
+        """
+        return AsyncSubscription(await self.client.scanner(assets, slots, dwell_secs))
+
+    async def volatility(self, asset: str, period: int = 14) -> AsyncSubscription:
+        """Measure the volatility of an asset's ticks, e.g. to skip dead or overly volatile intervals.
+
+        Args:
+            asset: Asset to measure.
+            period: Number of price changes in the rolling window, at least 2.
+
+        Returns:
+            AsyncSubscription yielding one dict per tick once the window is full, with:
+                - asset, timestamp, price
+                - stddev: standard deviation of the returns, as a fraction of the price
+                - atr: average true range, in price units
+                - spread: estimated spread, None when it cannot be estimated
+
+        Example:
+            ```python
+            async for sample in await client.volatility("EURUSD_otc", period=30):
+                if sample["stddev"] < 1e-5:
+                    print("market is flat")
+            ```
+        """
+        return AsyncSubscription(await self.client.volatility(asset, period))
+
+    async def add_alert(self, asset: str, condition: str, price: float, repeat: bool = False) -> str:
+        """Add a price alert, checked in Rust on every tick of the asset.
+
+        Args:
+            asset: Asset to watch.
+            condition: "crosses_above", "crosses_below", "above" or "below". The crossing
+                conditions need the price to move through `price`, "above" and "below" also fire
+                on the first tick if the price already is on that side.
+            price: Price level of the condition.
+            repeat: Fire every time the condition becomes true again instead of only once.
+
+        Returns:
+            str: The alert id, for `remove_alert` and to match the events of `alert_events`.
+
+        Raises:
+            InvalidParameterError: If the condition is unknown.
+
+        Example:
+            ```python
+            events = await client.alert_events()
+            await client.add_alert("EURUSD_otc", "crosses_above", 1.1)
+            async for event in events:
+                print(event["asset"], event["price"])
+            ```
+        """
+        return await self.client.add_alert(asset, condition, price, repeat)
+
+    async def remove_alert(self, alert_id: str) -> bool:
+        """Remove an alert, returns False if it already fired or never existed."""
+        return await self.client.remove_alert(alert_id)
+
+    async def active_alerts(self) -> List[Dict]:
+        """Alerts still registered, each with `id`, `asset`, `condition` and `mode`."""
+        return json.loads(await self.client.active_alerts())
+
+    async def alert_events(self) -> AsyncSubscription:
+        """Subscribe to the alerts as they fire.
+
+        Returns:
+            AsyncSubscription yielding dicts with `alert_id`, `asset`, `condition`, `price`,
+            `timestamp` and `repeat`.
+        """
+        return AsyncSubscription(await self.client.alert_events())
+
+    async def parse_failures(self) -> AsyncSubscription:
+        """Subscribe to the server frames the client received but could not parse.
+
+        A steady flow of failures usually means PocketOption changed the format of a message.
+
+        Returns:
+            AsyncSubscription yielding dicts with `module`, `raw` (the frame as received),
+            `binary`, `error` and `timestamp`.
+        """
+        return AsyncSubscription(await self.client.parse_failures())
+
+    async def data_quality_events(self) -> AsyncSubscription:
+        """Subscribe to the suspicious candles found in the received data.
+
+        History, `get_candles` results and live subscriptions are checked for timestamps going
+        back in time, candles whose high equals their low and gaps longer than the period.
+        The candles are still returned, use the events to discard them.
+
+        Returns:
+            AsyncSubscription yielding dicts with `asset`, `period`, `timestamp` (start of the
+            candle), `source` (`history`, `candles` or `stream`) and `issue`, whose `kind` is
+            `non_monotonic`, `zero_range` or `gap`.
+        """
+        return AsyncSubscription(await self.client.data_quality_events())
+
     async def subscribe_symbol(self, asset: str) -> AsyncSubscription:
         """Subscribe to real-time raw price updates for an asset.
 
@@ -1253,13 +1968,131 @@ class PocketOptionAsync:
         """Subscribe with chunked candle aggregation (n raw ticks per candle)."""
         return AsyncSubscription(await self.client.subscribe_symbol_chunked(asset, chunk_size))
 
-    async def subscribe_symbol_timed(self, asset: str, time: timedelta) -> AsyncSubscription:
-        """Subscribe with a fixed time-interval candle window."""
-        return AsyncSubscription(await self.client.subscribe_symbol_timed(asset, time))
+    async def subscribe_symbol_timed(
+        self, asset: str, time: timedelta, gap_fill: Optional[str] = None
+    ) -> AsyncSubscription:
+        """Subscribe with a fixed time-interval candle window.
+
+        `gap_fill` sets how the candles missed while disconnected are filled: None or "none"
+        leaves the gap, "flat" inserts flat candles at the last close and "backfill" loads them
+        from the history. Filled candles the client made up have `synthetic` set.
+        """
+        return AsyncSubscription(await self.client.subscribe_symbol_timed(asset, time, gap_fill))
+
+    async def subscribe_symbol_time_aligned(
+        self, asset: str, time: timedelta, gap_fill: Optional[str] = None
+    ) -> AsyncSubscription:
+        """Subscribe with candles aligned to clock boundaries, `gap_fill` as in `subscribe_symbol_timed`."""
+        return AsyncSubscription(await self.client.subscribe_symbol_time_aligned(asset, time, gap_fill))
+
+    async def subscribe_many(
+        self, subscriptions: List[Union[str, Tuple[str, SubscriptionSpec]]]
+    ) -> AsyncMultiSubscription:
+        """Subscribes to several assets at once and iterates over their candles in one stream.
+
+        Args:
+            subscriptions: Assets, or `(asset, sub_type)` pairs. `sub_type` is None for every price
+                update, an int for candles of that many updates, a timedelta for clock aligned
+                candles, or `(kind, value)` with kind "none", "chunk", "time" or "time_aligned".
+
+        Returns:
+            AsyncMultiSubscription: Yields `(asset, candle)` tuples in the order they arrive
+
+        Example:
+            ```python
+            stream = await client.subscribe_many([
+                ("EURUSD_otc", timedelta(minutes=1)),
+                ("GBPUSD_otc", ("time", timedelta(seconds=30))),
+                "AUDUSD_otc",
+            ])
+            async for asset, candle in stream:
+                print(asset, candle["close"])
+            ```
+        """
+        specs = [
+            subscription_spec(entry) if isinstance(entry, str) else subscription_spec(*entry)
+            for entry in subscriptions
+        ]
+        return AsyncMultiSubscription(await self.client.subscribe_many(specs))
+
+    async def candle_buffer(
+        self, assets: Union[str, List[str]], size: int, time: Optional[timedelta] = None
+    ) -> "CandleBuffer":
+        """Keeps the last `size` candles of every asset in a rolling window maintained in Rust.
+
+        Args:
+            assets: Trading asset or list of assets (e.g., "EURUSD_otc")
+            size (int): Number of candles kept per asset
+            time (timedelta, optional): Candle period, aligned to the clock. Each price update
+                is buffered as its own candle when omitted.
 
-    async def subscribe_symbol_time_aligned(self, asset: str, time: timedelta) -> AsyncSubscription:
-        """Subscribe with candles aligned to clock boundaries."""
-        return AsyncSubscription(await self.client.subscribe_symbol_time_aligned(asset, time))
+        Returns:
+            CandleBuffer: Read it with `to_records()`, `to_columns(asset)` or `latest(asset)`,
+            stop it with `close()`
+
+        Example:
+            ```python
+            buffer = await client.candle_buffer(["EURUSD_otc", "GBPUSD_otc"], 200, timedelta(minutes=1))
+            ...
+            df = pandas.DataFrame.from_records(buffer.to_records("EURUSD_otc"))
+            ```
+        """
+        if isinstance(assets, str):
+            assets = [assets]
+        return await self.client.candle_buffer(assets, size, time)
+
+    async def stream_candles_arrow(
+        self, asset: str, time: timedelta, batch_size: int = 100, flush_interval: float = 5.0
+    ) -> AsyncGenerator:
+        """Streams the candles of `subscribe_symbol_time_aligned()` as `pyarrow.RecordBatch` objects.
+
+        Needs the `arrow` extra (`pip install BinaryOptionsToolsV2[arrow]`).
+
+        Args:
+            asset (str): Trading asset (e.g., "EURUSD_otc")
+            time (timedelta): Candle period
+            batch_size (int): Candles per batch
+            flush_interval (float): Seconds after which a partial batch is yielded
+
+        Examples:
+            ```python
+            async for batch in client.stream_candles_arrow("EURUSD_otc", timedelta(seconds=5), 50):
+                model.partial_fit(batch.to_pandas())
+            ```
+        """
+        subscription = await self.subscribe_symbol_time_aligned(asset, time)
+        async for batch in arrow_batches(subscription, batch_size, flush_interval):
+            yield batch
+
+    async def serve_candles_arrow(
+        self,
+        asset: str,
+        time: timedelta,
+        batch_size: int = 100,
+        flush_interval: float = 5.0,
+        host: str = "127.0.0.1",
+        port: int = 0,
+        path: Optional[str] = None,
+    ) -> ArrowIpcServer:
+        """Sends the candles of `stream_candles_arrow()` as an Arrow IPC stream over a local socket.
+
+        Every reader connecting to the socket gets the schema, then each new batch, and can
+        read it with `pyarrow.ipc.open_stream`.
+
+        Args:
+            asset (str): Trading asset (e.g., "EURUSD_otc")
+            time (timedelta): Candle period
+            batch_size (int): Candles per batch
+            flush_interval (float): Seconds after which a partial batch is sent
+            host (str): Interface to listen on
+            port (int): TCP port, 0 picks a free one (see `ArrowIpcServer.address`)
+            path (str, optional): Unix socket to listen on instead of TCP
+
+        Returns:
+            ArrowIpcServer: The running server, `await server.close()` stops it
+        """
+        batches = self.stream_candles_arrow(asset, time, batch_size, flush_interval)
+        return await ArrowIpcServer(batches).start(host, port, path)
 
     async def get_server_time(self) -> int:
         """Retrieves the current server time from Pocket Option.
@@ -1306,6 +2139,39 @@ class PocketOptionAsync:
         """
         return await self.client.get_server_time()
 
+    async def server_time_offset(self) -> int:
+        """Returns the server time minus the local time, in seconds.
+
+        The offset is the median over the latest server timestamps and re-syncs when it
+        drifts further than `Config.server_time_drift_threshold_secs`.
+        """
+        return await self.client.server_time_offset()
+
+    async def server_time_quality(self) -> Dict:
+        """Returns how much the synced server time can be trusted.
+
+        Returns:
+            Dict: `offset` (seconds), `staleness` (milliseconds since the last server timestamp,
+                None before the first one), `jitter` (standard deviation of the offset in
+                seconds), `samples` and `resyncs` (re-syncs caused by drift).
+        """
+        return json.loads(await self.client.server_time_quality())
+
+    async def debug_snapshot(self) -> Dict:
+        """Captures the state of the client, to attach to bug reports or show on dashboards.
+
+        The session token is never included. While log redaction is on (the default) the uid
+        and the balances are left out too and the `uid` of the deals is zeroed.
+
+        Returns:
+            Dict: `taken_at`, `redacted`, `uid`, `demo`, `currency`, `balance`, `balances`,
+                `server_time` (see `server_time_quality`), `connected_url`, `assets` (count),
+                `subscriptions` (asset to subscription kinds), `last_ticks`, `histories`,
+                `opened_deals`, `closed_deals`, `pending_orders` and `pending_market_orders`,
+                the pending orders with their `age_seconds`.
+        """
+        return json.loads(await self.client.debug_snapshot())
+
     async def wait_for_assets(self, timeout: float = 60.0) -> None:
         """
         Waits for the assets to be loaded from the server.
@@ -1318,6 +2184,28 @@ class PocketOptionAsync:
         """
         await self.client.wait_for_assets(timeout)
 
+    async def wait_for(self, condition: str, timeout: float = 60.0, asset: Optional[str] = None) -> None:
+        """
+        Waits until a state condition holds.
+
+        Args:
+            condition (str): One of "assets_loaded", "balance_known", "server_time_synced",
+                "subscription_active" or "first_tick".
+            timeout (float): The maximum time to wait in seconds. Default is 60.0.
+            asset (str, optional): Asset for "subscription_active" and "first_tick".
+
+        Raises:
+            InvalidParameterError: If the condition is unknown or misses its asset.
+            PocketOptionError: If the condition does not hold within the timeout.
+
+        Example:
+            ```python
+            await client.wait_for("balance_known", timeout=10)
+            await client.wait_for("first_tick", asset="EURUSD_otc")
+            ```
+        """
+        await self.client.wait_for(condition, timeout, asset)
+
     async def get_pending_deals(self) -> List[Dict]:
         """Retrieves a list of all pending orders.
 
@@ -1379,6 +2267,98 @@ class PocketOptionAsync:
         """
         return self.client.is_connected()
 
+    async def save_session(self, path: str) -> None:
+        """
+        Saves the assets, server time offset, subscribed assets and opened deals to `path`.
+
+        A client created with `Config.session_path` set to this file starts from them, so the
+        results of deals opened before a restart can be awaited right away. With
+        `session_path` set the session is also saved on `shutdown`.
+        """
+        await self.client.save_session(path)
+
+    def stats(self) -> Dict:
+        """
+        Returns the connection counters since the client started or the last `reset_stats`.
+
+        Returns:
+            Dict: Counters including:
+                - uptime_seconds, window_seconds (time covered by the counters)
+                - messages_sent, messages_received, bytes_sent, bytes_received
+                - connections, reconnects, connection_failures
+                - connection_cycles_last_minute: connection attempts over the last 60 seconds
+                - handler_errors, routing_errors
+                - modules: incoming messages routed to each module, by module name
+                - rules: by module name, messages its rule was evaluated on (`evaluations`)
+                  and matched (`hits`)
+                - channels: by module name, `capacity`, `high_water_mark`, `overflows` and
+                  `dropped` messages of the module's message channel
+        """
+        return json.loads(self.client.stats())
+
+    def reset_stats(self) -> None:
+        """Sets the counters returned by `stats` back to zero."""
+        self.client.reset_stats()
+
+    def audit_log(self, since: int = 0) -> List[Dict]:
+        """
+        Returns the messages sent by the client, to find out which module sent a message.
+
+        Needs `Config.audit_log` set to the number of messages to keep, the list is empty
+        otherwise. Sessions are masked like in the logs.
+
+        Args:
+            since (int): Only return the messages sent after the one with this `seq`.
+
+        Returns:
+            List[Dict]: Oldest first, each with:
+                - seq: position of the message among all the sent ones, starting at 1
+                - time: unix timestamp at which it was sent
+                - source: module that sent it, "client" for `send_raw_message` and callbacks
+                - message: the message text
+        """
+        return json.loads(self.client.audit_log(since))
+
+    def status(self) -> str:
+        """
+        Returns the current connection status.
+
+        Returns:
+            str: One of "connecting", "connected", "authenticated", "degraded",
+                "reconnecting" or "disconnected"
+        """
+        return self.client.status()
+
+    async def wait_for_status(self, status: str, timeout: float = 60.0) -> None:
+        """
+        Waits until the connection reaches the given status.
+
+        Prefer `await client.wait_for_status("authenticated")` over polling
+        `wait_for_assets` to know when the session is ready.
+
+        Args:
+            status: Target status, e.g. "authenticated".
+            timeout: Maximum time to wait in seconds.
+
+        Raises:
+            PocketOptionError: If the status is not reached in time.
+        """
+        await self.client.wait_for_status(status, timeout)
+
+    async def status_stream(self) -> AsyncRawSubscription:
+        """
+        Subscribe to connection status changes.
+
+        The first item is the current status, followed by every change.
+
+        Example:
+            ```python
+            async for status in await client.status_stream():
+                print("connection is now", status)
+            ```
+        """
+        return AsyncRawSubscription(await self.client.status_stream())
+
     def is_ssid_valid(self) -> bool:
         """Returns whether the SSID passed basic format validation during init."""
         return self._ssid_valid
@@ -1414,6 +2394,90 @@ class PocketOptionAsync:
         """
         await self.client.connect()
 
+    async def fatal_error(self) -> Dict:
+        """
+        Waits until the connection runner stops without `shutdown()` being called.
+
+        Returns:
+            Dict: The error, with a `kind` of "not_authorized", "reconnect_exhausted" (with
+            `attempts` and `last_error`), "stopped" or "panicked" (with `message`)
+
+        Note:
+            Never returns while the client keeps running, use `on_fatal` to be notified instead.
+        """
+        return json.loads(await self.client.fatal_error())
+
+    def on_fatal(self, callback: Callable[[Dict], Any]) -> "asyncio.Task":
+        """
+        Calls `callback` with the error once the connection runner stops without `shutdown()`
+        being called, e.g. to restart the client or alert. Coroutine callbacks are awaited.
+
+        Args:
+            callback: Called with the dict returned by `fatal_error()`
+
+        Returns:
+            asyncio.Task: The task waiting for the error, cancel it to remove the callback
+
+        Example:
+            ```python
+            client.on_fatal(lambda error: print("Client died:", error["kind"]))
+            ```
+        """
+
+        async def _watch():
+            result = callback(await self.fatal_error())
+            if asyncio.iscoroutine(result):
+                await result
+
+        return asyncio.ensure_future(_watch())
+
+    async def on_reconnect(self, callback: Callable[[Dict], Any]) -> "CallbackHandle":
+        """
+        Calls `callback` on the running event loop every time the connection is established
+        again, e.g. to re-subscribe or re-warm indicators. Coroutine callbacks are scheduled
+        as tasks.
+
+        Args:
+            callback: Called with the reconnection event, {"type": "connected", "attempts": n}
+
+        Returns:
+            CallbackHandle: Call `cancel()` on it to remove the callback
+
+        Example:
+            ```python
+            async def rewarm(event):
+                await strategy.load_history(client)
+
+            handle = await client.on_reconnect(rewarm)
+            ```
+        """
+
+        def _dispatch(event: str):
+            result = callback(json.loads(event))
+            if asyncio.iscoroutine(result):
+                asyncio.ensure_future(result)
+
+        return await self.client.on_reconnect(_dispatch)
+
+    async def on_disconnect(self, callback: Callable[[str], Any]) -> "CallbackHandle":
+        """
+        Calls `callback` on the running event loop every time an open connection is lost.
+        Coroutine callbacks are scheduled as tasks.
+
+        Args:
+            callback: Called with the new status, "reconnecting" or "disconnected"
+
+        Returns:
+            CallbackHandle: Call `cancel()` on it to remove the callback
+        """
+
+        def _dispatch(status: str):
+            result = callback(status)
+            if asyncio.iscoroutine(result):
+                asyncio.ensure_future(result)
+
+        return await self.client.on_disconnect(_dispatch)
+
     async def reconnect(self) -> None:
         """
         Disconnects and reconnects the client.
@@ -1450,13 +2514,39 @@ class PocketOptionAsync:
         """
         await self.client.shutdown()
 
-    async def create_raw_handler(self, validator: Validator, keep_alive: Optional[str] = None) -> "RawHandler":
+    async def shutdown_graceful(self, timeout: float = 30.0) -> int:
+        """
+        Shuts down the client after letting in-flight trades settle.
+
+        New trades and pending orders are rejected immediately, then the client waits
+        up to `timeout` seconds for pending order confirmations and open trade results
+        before closing the connection.
+
+        Args:
+            timeout: Maximum time to wait for in-flight trades, in seconds.
+
+        Returns:
+            int: Number of trades still unresolved when the connection was closed.
+        """
+        return await self.client.shutdown_graceful(timeout)
+
+    async def create_raw_handler(
+        self,
+        validator: Validator,
+        keep_alive: Optional[str] = None,
+        correlation_path: Optional[str] = None,
+        reassemble: bool = False,
+    ) -> "RawHandler":
         """
         Creates a raw handler for advanced WebSocket message handling.
 
         Args:
             validator: Validator instance to filter incoming messages
             keep_alive: Optional message to send on reconnection
+            correlation_path: Optional JSONPath (e.g. `$.data.requestId`) of the id used by
+                `RawHandler.send_and_wait_for` to route responses to their request
+            reassemble: Receive Socket.IO binary packets (`451-[...]` and its binary frames)
+                as a single `42[...]` message with the binary payload decoded in place
 
         Returns:
             RawHandler: Handler instance for sending/receiving messages
@@ -1476,7 +2566,9 @@ class PocketOptionAsync:
                 print(message)
             ```
         """
-        rust_handler = await self.client.create_raw_handler(validator.raw_validator, keep_alive)
+        rust_handler = await self.client.create_raw_handler(
+            validator.raw_validator, keep_alive, correlation_path, reassemble
+        )
         return RawHandler(rust_handler)
 
     async def send_raw_message(self, message: str) -> None:
@@ -3,11 +3,13 @@ import json
 import threading
 import sys
 import warnings
-from datetime import timedelta
-from typing import Dict, List, Optional, Tuple, Union
+from datetime import datetime, timedelta
+from typing import Any, Callable, Dict, List, Optional, Tuple, Union
 from ..config import Config
 from ..validator import Validator as Validator
-from .asynchronous import PocketOptionAsync as PocketOptionAsync
+from .asynchronous import AsyncMultiSubscription, PocketOptionAsync as PocketOptionAsync
+from .asynchronous import Amount, SubscriptionSpec, subscription_spec
+from .assets import AssetInfo
 
 
 class SyncSubscription:
@@ -21,8 +23,34 @@ class SyncSubscription:
         """Return the async iterator for the subscription."""
         return self.subscription
 
+    def _parse(self, item: str) -> Any:
+        return json.loads(item)
+
     def __next__(self):
-        return json.loads(next(self.subscription))
+        return self._parse(next(self.subscription))
+
+    def next_timeout(self, seconds: float) -> Optional[Any]:
+        """Blocks at most `seconds` for the next item, returns None when none arrived."""
+        item = self.subscription.next_timeout_blocking(seconds)
+        return None if item is None else self._parse(item)
+
+    def collect(self, n: int, timeout: Optional[float] = None) -> List[Any]:
+        """Blocks for up to `n` items, returning the ones received once `timeout` seconds elapsed.
+
+        Raises StopIteration only when the stream ended before any item was received.
+        """
+        return [self._parse(item) for item in self.subscription.collect_blocking(n, timeout)]
+
+
+class SyncMultiSubscription(SyncSubscription):
+    """Iterator over `(asset, candle)` tuples, see `PocketOption.subscribe_many`."""
+
+    def __aiter__(self):
+        return AsyncMultiSubscription(self.subscription)
+
+    def _parse(self, item: str) -> Tuple[str, Dict]:
+        asset, candle = json.loads(item)
+        return asset, candle
 
 
 class SyncRawSubscription:
@@ -38,6 +66,14 @@ class SyncRawSubscription:
     def __next__(self):
         return next(self.subscription)
 
+    def next_timeout(self, seconds: float) -> Optional[str]:
+        """Blocks at most `seconds` for the next message, returns None when none arrived."""
+        return self.subscription.next_timeout_blocking(seconds)
+
+    def collect(self, n: int, timeout: Optional[float] = None) -> List[str]:
+        """Blocks for up to `n` messages, returning the ones received once `timeout` seconds elapsed."""
+        return self.subscription.collect_blocking(n, timeout)
+
 
 class SyncCandleLiveIterator:
     """Synchronous iterator for live candle updates."""
@@ -97,6 +133,19 @@ class RawHandlerSync:
         """
         return self._run(self._handler.send_and_wait(message))
 
+    def send_and_wait_for(self, message: str, correlation_id: str, timeout: float = 30.0) -> str:
+        """Send a text message and wait for the response carrying `correlation_id`.
+
+        Args:
+            message: The text message to send.
+            correlation_id: Id the response carries at the handler's correlation path.
+            timeout: Seconds to wait for the response.
+
+        Returns:
+            The response string with the given correlation id.
+        """
+        return self._run(self._handler.send_and_wait_for(message, correlation_id, timeout))
+
     def wait_next(self) -> str:
         """Wait for the next incoming message.
 
@@ -145,6 +194,14 @@ class SyncRawSubscription:
     def __next__(self):
         return next(self.subscription)
 
+    def next_timeout(self, seconds: float) -> Optional[str]:
+        """Blocks at most `seconds` for the next message, returns None when none arrived."""
+        return self.subscription.next_timeout_blocking(seconds)
+
+    def collect(self, n: int, timeout: Optional[float] = None) -> List[str]:
+        """Blocks for up to `n` messages, returning the ones received once `timeout` seconds elapsed."""
+        return self.subscription.collect_blocking(n, timeout)
+
 
 class PocketOption:
     def __init__(self, ssid: str, url: Optional[str] = None, config: Union[Config, dict, str] = None, **_):
@@ -233,6 +290,16 @@ class PocketOption:
         """
         return self._client.config
 
+    def to_timestamp(self, value: Union[datetime, int, float, str]) -> int:
+        """Convert a time to a unix timestamp, naive datetimes and strings without an offset
+        are read in `config.timezone`.
+        """
+        return self._client.to_timestamp(value)
+
+    def to_datetime(self, timestamp: float) -> datetime:
+        """Convert a unix timestamp to an aware datetime in `config.timezone`."""
+        return self._client.to_datetime(timestamp)
+
     def __enter__(self):
         return self
 
@@ -248,33 +315,61 @@ class PocketOption:
         with self._lock:
             self._cleanup_loop()
 
-    def buy(self, asset: str, amount: float, time: int, check_win: bool = False) -> Tuple[str, Dict]:
+    def buy(
+        self,
+        asset: str,
+        amount: Amount,
+        time: int,
+        check_win: bool = False,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait: float = 0.0,
+    ) -> Tuple[str, Dict]:
         """Place a buy (call) option.
 
         Args:
             asset: The trading asset name (e.g. "EURUSD").
-            amount: The investment amount.
+            amount: The investment amount, a Decimal or str keeps it exact.
             time: The expiration time in seconds.
             check_win: Whether to immediately check the trade result.
+            allow_duplicate: Place the order even if an identical one was just placed.
+            min_payout: Refuse the order if the asset payout (in percent) is lower.
+            payout_wait: Seconds to wait for the payout to recover before refusing.
 
         Returns:
             A tuple of (trade_id, trade_details_dict).
         """
-        return self._run(self._client.buy(asset, amount, time, check_win))
+        return self._run(
+            self._client.buy(asset, amount, time, check_win, allow_duplicate, min_payout, payout_wait)
+        )
 
-    def sell(self, asset: str, amount: float, time: int, check_win: bool = False) -> Tuple[str, Dict]:
+    def sell(
+        self,
+        asset: str,
+        amount: Amount,
+        time: int,
+        check_win: bool = False,
+        allow_duplicate: bool = False,
+        min_payout: Optional[int] = None,
+        payout_wait: float = 0.0,
+    ) -> Tuple[str, Dict]:
         """Place a sell (put) option.
 
         Args:
             asset: The trading asset name.
-            amount: The investment amount.
+            amount: The investment amount, a Decimal or str keeps it exact.
             time: The expiration time in seconds.
             check_win: Whether to immediately check the trade result.
+            allow_duplicate: Place the order even if an identical one was just placed.
+            min_payout: Refuse the order if the asset payout (in percent) is lower.
+            payout_wait: Seconds to wait for the payout to recover before refusing.
 
         Returns:
             A tuple of (trade_id, trade_details_dict).
         """
-        return self._run(self._client.sell(asset, amount, time, check_win))
+        return self._run(
+            self._client.sell(asset, amount, time, check_win, allow_duplicate, min_payout, payout_wait)
+        )
 
     def check_win(self, id: str) -> dict:
         """Check the result of a completed trade.
@@ -298,6 +393,56 @@ class PocketOption:
         """
         return self._run(self._client.get_deal_end_time(trade_id))
 
+    def time_to_expiry(self, trade_id: str) -> float:
+        """Get the seconds left before a deal expires, computed with server time.
+
+        Args:
+            trade_id: The trade identifier.
+
+        Returns:
+            The remaining seconds, 0 once the deal closed.
+        """
+        return self._run(self._client.time_to_expiry(trade_id))
+
+    def deal_status(self, trade_id: str, wait: bool = False, history_fallback_secs: Optional[float] = None) -> dict:
+        """Get where a deal is in its lifecycle.
+
+        Args:
+            trade_id: The trade identifier.
+            wait: Wait until the deal closes instead of returning its current status.
+            history_fallback_secs: When waiting, seconds after the expiry before checking the server history.
+
+        Returns:
+            A dict with the `status` ("open", "closing" or "closed") and the `deal`.
+        """
+        return self._run(self._client.deal_status(trade_id, wait, history_fallback_secs))
+
+    def deal_countdown_stream(self, trade_id: str) -> SyncSubscription:
+        """Stream the seconds left before a deal expires, once per second.
+
+        Args:
+            trade_id: The trade identifier.
+
+        Returns:
+            A SyncSubscription yielding the remaining seconds until it reaches 0.
+        """
+
+        async def _sub():
+            return await self._client.client.deal_countdown_stream(trade_id)
+
+        return SyncSubscription(self._run(_sub()))
+
+    def close_deal_early(self, trade_id: str) -> dict:
+        """Close an opened deal before its expiry.
+
+        Args:
+            trade_id: The trade identifier.
+
+        Returns:
+            A dictionary with `deal_id`, `returned` (the amount credited back) and the closed `deal`.
+        """
+        return self._run(self._client.close_deal_early(trade_id))
+
     def get_candles(self, asset: str, period: int, offset: int) -> List[Dict]:
         """Get historical candle data for an asset.
 
@@ -327,14 +472,15 @@ class PocketOption:
         closed, forming = next(iterator)
         return closed
 
-    def get_candles_advanced(self, asset: str, period: int, offset: int, time: int) -> List[Dict]:
+    def get_candles_advanced(self, asset: str, period: int, offset: int, time: Union[datetime, int, str]) -> List[Dict]:
         """Get historical candle data with a specific reference time.
 
         Args:
             asset: The trading asset name.
             period: The candle period in seconds.
             offset: The offset from the reference time in seconds.
-            time: The reference Unix timestamp.
+            time: The reference time, as a datetime, unix timestamp or time string, naive ones
+                are read in `config.timezone`.
 
         Returns:
             A list of candle dictionaries.
@@ -348,6 +494,23 @@ class PocketOption:
         """
         return self._run(self._client.get_candles_advanced(asset, period, offset, time))
 
+    def get_candles_columns(self, asset: str, period: int, offset: int) -> Dict[str, List]:
+        """Get historical candles as one list per column.
+
+        Args:
+            asset: The trading asset name.
+            period: The historical period in seconds.
+            offset: The candle timeframe in seconds.
+
+        Returns:
+            A dictionary with `timestamp`, `open`, `high`, `low`, `close` and `volume` lists.
+        """
+        return self._run(self._client.get_candles_columns(asset, period, offset))
+
+    def get_candles_arrow(self, asset: str, period: int, offset: int):
+        """Get historical candles as a `pyarrow.RecordBatch`, needs the `arrow` extra."""
+        return self._run(self._client.get_candles_arrow(asset, period, offset))
+
     def candles(self, asset: str, period: int) -> List[Dict]:
         """Get the most recent candles for an asset.
 
@@ -417,6 +580,22 @@ class PocketOption:
         """
         return self._run(self._client.balance())
 
+    def balances(self) -> Dict[str, Dict]:
+        """Get the last balance reported for each account type.
+
+        Returns:
+            Balances keyed by "demo", "real" or "tournament", see `PocketOptionAsync.balances`.
+        """
+        return self._run(self._client.balances())
+
+    def currency(self) -> Optional[str]:
+        """Get the account currency code (e.g. "USD").
+
+        Returns:
+            The currency code, or None if not yet reported by the server.
+        """
+        return self._run(self._client.currency())
+
     def opened_deals(self) -> List[str]:
         """Get a list of currently open deal IDs.
 
@@ -439,10 +618,10 @@ class PocketOption:
     def open_pending_order(
         self,
         open_type: int,
-        amount: float,
+        amount: Amount,
         asset: str,
-        open_time: int,
-        open_price: float,
+        open_time: Union[int, str, datetime],
+        open_price: Amount,
         timeframe: int,
         min_payout: int,
         command: int,
@@ -451,9 +630,10 @@ class PocketOption:
 
         Args:
             open_type: The order type identifier.
-            amount: The investment amount.
+            amount: The investment amount, a Decimal or str keeps it exact.
             asset: The trading asset name.
-            open_time: The scheduled open time.
+            open_time: The scheduled open time, naive datetimes and strings are read in
+                `config.timezone`.
             open_price: The target open price.
             timeframe: The candle timeframe.
             min_payout: The minimum acceptable payout.
@@ -498,6 +678,95 @@ class PocketOption:
         """
         return self._run(self._client.closed_deals())
 
+    def trade_stats(self) -> Dict:
+        """Get win/loss statistics over the deals closed since the client started.
+
+        Returns:
+            A dictionary with win rate, profit factor, streaks and per-asset breakdown.
+        """
+        return self._run(self._client.trade_stats())
+
+    def reset_trade_stats(self) -> None:
+        """Reset the statistics returned by `trade_stats`, `trade_latency` and `slippage_stats`."""
+        self._run(self._client.reset_trade_stats())
+
+    def kill_switch_status(self) -> Dict:
+        """Get the result of the current server day against the kill switch limits.
+
+        Returns:
+            A dictionary with `day`, `pnl`, `peak` and `tripped`.
+        """
+        return self._run(self._client.kill_switch_status())
+
+    def kill_switch_events(self) -> SyncSubscription:
+        """Subscribe to the kill switch stopping the trading.
+
+        Returns:
+            A SyncSubscription yielding dicts with `reason`, `pnl`, `peak`, `at` and `resets_at`.
+        """
+
+        async def _sub():
+            return await self._client.client.kill_switch_events()
+
+        return SyncSubscription(self._run(_sub()))
+
+    def last_trade_timings(self) -> Optional[Dict]:
+        """Get how long each stage of the most recently resolved order took.
+
+        Returns:
+            None before the first order, otherwise a dictionary with the queued, server,
+            confirm and total latencies in milliseconds.
+        """
+        return self._run(self._client.last_trade_timings())
+
+    def trade_latency(self) -> Dict:
+        """Get latency histograms for the orders placed since the client started.
+
+        Returns:
+            A dictionary with outcome counters and one histogram per stage.
+        """
+        return self._run(self._client.trade_latency())
+
+    def slippage_stats(self) -> Dict:
+        """Get the slippage of the deals opened since the client started.
+
+        Returns:
+            A dictionary with requote and adverse fill counters, the mean and worst slippage
+            and a per-asset breakdown.
+        """
+        return self._run(self._client.slippage_stats())
+
+    def closed_deals_history(
+        self, start: Union[datetime, int, str], end: Optional[Union[datetime, int, str]] = None
+    ) -> List[Dict]:
+        """Fetch the account's closed deals from the server for a time range.
+
+        Args:
+            start: Range start, as a datetime, unix timestamp or time string, naive ones are
+                read in `config.timezone`.
+            end: Range end, defaults to now.
+
+        Returns:
+            A list of dictionaries with closed deal details.
+        """
+        return self._run(self._client.closed_deals_history(start, end))
+
+    def ticks_range(
+        self, asset: str, start: Union[datetime, int, str], end: Optional[Union[datetime, int, str]] = None
+    ) -> List[Dict]:
+        """Fetch the tick history of an asset for an arbitrary time range.
+
+        Args:
+            asset: Trading asset (e.g., "EURUSD_otc").
+            start: Range start, as a datetime, unix timestamp or time string, naive ones are
+                read in `config.timezone`.
+            end: Range end, defaults to now.
+
+        Returns:
+            A list of dictionaries with `time` (unix seconds) and `price`, sorted by time.
+        """
+        return self._run(self._client.ticks_range(asset, start, end))
+
     def get_closed_deal(self, trade_id: str) -> Optional[Dict]:
         """Get details of a specific closed deal.
 
@@ -560,6 +829,108 @@ class PocketOption:
         """Subscribe to all incoming WebSocket messages verbatim."""
         return SyncRawSubscription(self._run(self._client.subscribe_raw()))
 
+    def add_middleware(self, callback, queue_size: int = 1024) -> None:
+        """Observe every WebSocket frame sent or received.
+
+        Args:
+            callback: Called as `callback(message, direction)` on a background thread, with a
+                direction of "send" or "receive".
+            queue_size: Frames buffered for the callback, the extra ones are dropped.
+        """
+        self._run(self._client.add_middleware(callback, queue_size))
+
+    def reconnect_events(self) -> SyncSubscription:
+        """Subscribe to reconnection events from the connection runner.
+
+        Returns:
+            A SyncSubscription yielding event dictionaries with a `type` of
+            "scheduled", "connected", "limit_reached" or "exhausted".
+        """
+
+        async def _sub():
+            return await self._client.client.reconnect_events()
+
+        return SyncSubscription(self._run(_sub()))
+
+    def scanner(self, assets: List[str], slots: int = 4, dwell_secs: float = 5.0) -> SyncSubscription:
+        """Scan more assets than there are subscription slots, see `PocketOptionAsync.scanner`.
+
+        Returns:
+            A SyncSubscription yielding dicts with `asset`, `candle`, `received_at` and `rotation`.
+        """
+
+        async def _sub():
+            return await self._client.client.scanner(assets, slots, dwell_secs)
+
+        return SyncSubscription(self._run(_sub()))
+
+    def volatility(self, asset: str, period: int = 14) -> SyncSubscription:
+        """Measure the volatility of an asset's ticks, see `PocketOptionAsync.volatility`.
+
+        Returns:
+            A SyncSubscription yielding dicts with `stddev`, `atr` and `spread`.
+        """
+
+        async def _sub():
+            return await self._client.client.volatility(asset, period)
+
+        return SyncSubscription(self._run(_sub()))
+
+    def add_alert(self, asset: str, condition: str, price: float, repeat: bool = False) -> str:
+        """Add a price alert, see `PocketOptionAsync.add_alert`.
+
+        Returns:
+            The alert id.
+        """
+        return self._run(self._client.add_alert(asset, condition, price, repeat))
+
+    def remove_alert(self, alert_id: str) -> bool:
+        """Remove an alert, returns False if it already fired or never existed."""
+        return self._run(self._client.remove_alert(alert_id))
+
+    def active_alerts(self) -> List[Dict]:
+        """Get the alerts still registered."""
+        return self._run(self._client.active_alerts())
+
+    def alert_events(self) -> SyncSubscription:
+        """Subscribe to the alerts as they fire.
+
+        Returns:
+            A SyncSubscription yielding dicts with `alert_id`, `asset`, `condition`, `price`,
+            `timestamp` and `repeat`.
+        """
+
+        async def _sub():
+            return await self._client.client.alert_events()
+
+        return SyncSubscription(self._run(_sub()))
+
+    def parse_failures(self) -> SyncSubscription:
+        """Subscribe to the server frames the client received but could not parse.
+
+        Returns:
+            A SyncSubscription yielding dicts with `module`, `raw`, `binary`, `error` and
+            `timestamp`.
+        """
+
+        async def _sub():
+            return await self._client.client.parse_failures()
+
+        return SyncSubscription(self._run(_sub()))
+
+    def data_quality_events(self) -> SyncSubscription:
+        """Subscribe to the suspicious candles found in the received data.
+
+        Returns:
+            A SyncSubscription yielding dicts with `asset`, `period`, `timestamp`, `source` and
+            `issue`.
+        """
+
+        async def _sub():
+            return await self._client.client.data_quality_events()
+
+        return SyncSubscription(self._run(_sub()))
+
     def subscribe_symbol(self, asset: str) -> SyncSubscription:
         """Subscribe to real-time price updates for a symbol.
 
@@ -591,38 +962,78 @@ class PocketOption:
 
         return SyncSubscription(self._run(_sub()))
 
-    def subscribe_symbol_timed(self, asset: str, time: timedelta) -> SyncSubscription:
+    def subscribe_symbol_timed(
+        self, asset: str, time: timedelta, gap_fill: Optional[str] = None
+    ) -> SyncSubscription:
         """Subscribe to periodic real-time price updates.
 
         Args:
             asset: The trading asset name to subscribe to.
             time: The interval between updates.
+            gap_fill: How the candles missed while disconnected are filled, "none" (default),
+                "flat" or "backfill".
 
         Returns:
             A SyncSubscription for iterating over timed price updates.
         """
 
         async def _sub():
-            return await self._client.client.subscribe_symbol_timed(asset, time)
+            return await self._client.client.subscribe_symbol_timed(asset, time, gap_fill)
 
         return SyncSubscription(self._run(_sub()))
 
-    def subscribe_symbol_time_aligned(self, asset: str, time: timedelta) -> SyncSubscription:
+    def subscribe_symbol_time_aligned(
+        self, asset: str, time: timedelta, gap_fill: Optional[str] = None
+    ) -> SyncSubscription:
         """Subscribe to time-aligned periodic price updates.
 
         Args:
             asset: The trading asset name to subscribe to.
             time: The interval between updates, aligned to the clock.
+            gap_fill: How the candles missed while disconnected are filled, "none" (default),
+                "flat" or "backfill".
 
         Returns:
             A SyncSubscription for iterating over time-aligned price updates.
         """
 
         async def _sub():
-            return await self._client.client.subscribe_symbol_time_aligned(asset, time)
+            return await self._client.client.subscribe_symbol_time_aligned(asset, time, gap_fill)
 
         return SyncSubscription(self._run(_sub()))
 
+    def subscribe_many(self, subscriptions: List[Union[str, Tuple[str, SubscriptionSpec]]]) -> SyncMultiSubscription:
+        """Subscribe to several assets at once and iterate over their candles in one stream.
+
+        Args:
+            subscriptions: Assets, or `(asset, sub_type)` pairs, see `PocketOptionAsync.subscribe_many`.
+
+        Returns:
+            A SyncMultiSubscription yielding `(asset, candle)` tuples.
+        """
+        specs = [
+            subscription_spec(entry) if isinstance(entry, str) else subscription_spec(*entry)
+            for entry in subscriptions
+        ]
+
+        async def _sub():
+            return await self._client.client.subscribe_many(specs)
+
+        return SyncMultiSubscription(self._run(_sub()))
+
+    def candle_buffer(self, assets: Union[str, List[str]], size: int, time: Optional[timedelta] = None) -> Any:
+        """Keep the last `size` candles of every asset in a rolling window maintained in Rust.
+
+        Args:
+            assets: The trading asset name, or a list of them.
+            size: Number of candles kept per asset.
+            time: Candle period aligned to the clock, every price update is buffered when omitted.
+
+        Returns:
+            CandleBuffer: Read it with `to_records()`, `to_columns(asset)` or `latest(asset)`.
+        """
+        return self._run(self._client.candle_buffer(assets, size, time))
+
     def get_server_time(self) -> int:
         """Get the current server time.
 
@@ -631,6 +1042,27 @@ class PocketOption:
         """
         return self._run(self._client.get_server_time())
 
+    def server_time_offset(self) -> int:
+        """Get the server time minus the local time, in seconds."""
+        return self._run(self._client.server_time_offset())
+
+    def server_time_quality(self) -> Dict:
+        """Get the offset, staleness and jitter of the server time.
+
+        Returns:
+            A dictionary with `offset`, `staleness`, `jitter`, `samples` and `resyncs`.
+        """
+        return self._run(self._client.server_time_quality())
+
+    def debug_snapshot(self) -> Dict:
+        """Capture the state of the client for bug reports and dashboards.
+
+        Returns:
+            A dictionary with the balances, server time, subscriptions, deals and pending
+            orders. Sensitive fields are left out while log redaction is on.
+        """
+        return self._run(self._client.debug_snapshot())
+
     def get_pending_deals(self) -> List[Dict]:
         """Get a list of pending deals.
 
@@ -655,6 +1087,60 @@ class PocketOption:
         """
         return self._client.is_connected()
 
+    def save_session(self, path: str) -> None:
+        """Save the session to `path`, see `PocketOptionAsync.save_session`."""
+        self._run(self._client.save_session(path))
+
+    def stats(self) -> Dict:
+        """Get the connection counters since the client started or the last `reset_stats`.
+
+        Returns:
+            A dictionary with message, byte, reconnect and error counts, and the messages
+            routed to each module.
+        """
+        return self._client.stats()
+
+    def reset_stats(self) -> None:
+        """Set the counters returned by `stats` back to zero."""
+        self._client.reset_stats()
+
+    def audit_log(self, since: int = 0) -> List[Dict]:
+        """Get the messages sent after the one numbered `since`, with the module sending each.
+
+        Needs `Config.audit_log`, see `PocketOptionAsync.audit_log`.
+        """
+        return self._client.audit_log(since)
+
+    def status(self) -> str:
+        """Get the current connection status.
+
+        Returns:
+            One of "connecting", "connected", "authenticated", "degraded",
+            "reconnecting" or "disconnected".
+        """
+        return self._client.status()
+
+    def wait_for_status(self, status: str, timeout: float = 60.0) -> None:
+        """Block until the connection reaches the given status.
+
+        Args:
+            status: Target status, e.g. "authenticated".
+            timeout: Maximum time to wait in seconds (default 60.0).
+        """
+        self._run(self._client.wait_for_status(status, timeout))
+
+    def status_stream(self) -> SyncRawSubscription:
+        """Subscribe to connection status changes.
+
+        Returns:
+            A SyncRawSubscription yielding the current status followed by every change.
+        """
+
+        async def _sub():
+            return await self._client.client.status_stream()
+
+        return SyncRawSubscription(self._run(_sub()))
+
     def wait_for_assets(self, timeout: float = 60.0) -> None:
         """Wait for asset data to finish loading.
 
@@ -663,6 +1149,17 @@ class PocketOption:
         """
         self._run(self._client.wait_for_assets(timeout))
 
+    def wait_for(self, condition: str, timeout: float = 60.0, asset: Optional[str] = None) -> None:
+        """Block until a state condition holds.
+
+        Args:
+            condition: One of "assets_loaded", "balance_known", "server_time_synced",
+                "subscription_active" or "first_tick".
+            timeout: Maximum time to wait in seconds (default 60.0).
+            asset: Asset for "subscription_active" and "first_tick".
+        """
+        self._run(self._client.wait_for(condition, timeout, asset))
+
     def disconnect(self) -> None:
         """Disconnect from the server."""
         self._run(self._client.disconnect())
@@ -671,6 +1168,40 @@ class PocketOption:
         """Connect to the server."""
         self._run(self._client.connect())
 
+    def on_fatal(self, callback: Callable[[Dict], Any]) -> None:
+        """Call `callback` once the connection runner stops without `shutdown()` being called.
+
+        The callback runs on the background event loop thread, see `PocketOptionAsync.on_fatal`.
+
+        Args:
+            callback: Called with the error dict, e.g. {"kind": "not_authorized"}.
+        """
+
+        async def _register():
+            self._client.on_fatal(callback)
+
+        self._run(_register())
+
+    def on_reconnect(self, callback: Callable[[Dict], Any]) -> Any:
+        """Call `callback` every time the connection is established again.
+
+        The callback runs on the background event loop thread, see `PocketOptionAsync.on_reconnect`.
+
+        Returns:
+            CallbackHandle: Call `cancel()` on it to remove the callback.
+        """
+        return self._run(self._client.on_reconnect(callback))
+
+    def on_disconnect(self, callback: Callable[[str], Any]) -> Any:
+        """Call `callback` with the new status every time an open connection is lost.
+
+        The callback runs on the background event loop thread, see `PocketOptionAsync.on_disconnect`.
+
+        Returns:
+            CallbackHandle: Call `cancel()` on it to remove the callback.
+        """
+        return self._run(self._client.on_disconnect(callback))
+
     def reconnect(self) -> None:
         """Disconnect and reconnect to the server."""
         self._run(self._client.reconnect())
@@ -687,17 +1218,40 @@ class PocketOption:
         """Shut down the client and release all resources."""
         self.close()
 
-    def create_raw_handler(self, validator: Validator, keep_alive: Optional[str] = None) -> "RawHandlerSync":
+    def shutdown_graceful(self, timeout: float = 30.0) -> int:
+        """Let in-flight trades settle, then shut down and release all resources.
+
+        Args:
+            timeout: Maximum time to wait for in-flight trades, in seconds.
+
+        Returns:
+            The number of trades still unresolved when the connection was closed.
+        """
+        unresolved = self._run(self._client.shutdown_graceful(timeout))
+        self.close()
+        return unresolved
+
+    def create_raw_handler(
+        self,
+        validator: Validator,
+        keep_alive: Optional[str] = None,
+        correlation_path: Optional[str] = None,
+        reassemble: bool = False,
+    ) -> "RawHandlerSync":
         """Create a synchronous raw WebSocket message handler.
 
         Args:
             validator: A Validator instance for message validation.
             keep_alive: Optional keep-alive message string.
+            correlation_path: Optional JSONPath of the id used by `send_and_wait_for`.
+            reassemble: Receive Socket.IO binary packets as a single reassembled message.
 
         Returns:
             A RawHandlerSync instance wrapping the async raw handler.
         """
-        async_handler = self._run(self._client.create_raw_handler(validator, keep_alive))
+        async_handler = self._run(
+            self._client.create_raw_handler(validator, keep_alive, correlation_path, reassemble)
+        )
         return RawHandlerSync(async_handler, self.loop)
 
     def send_raw_message(self, message: str) -> None:
@@ -760,6 +1314,26 @@ class PocketOption:
         async_iterator = self._run(self._client.create_raw_iterator(message, validator, timeout))
         return SyncRawSubscription(async_iterator)
 
+    def get_assets(
+        self,
+        query: Optional[str] = None,
+        asset_type: Optional[str] = None,
+        otc: Optional[bool] = None,
+        active_only: bool = False,
+    ) -> List[AssetInfo]:
+        """Get the assets with their payout, type and allowed candle durations.
+
+        Args:
+            query: Keeps the assets whose symbol or name contains it, ignoring case.
+            asset_type: Keeps the assets of this type, e.g. "currency".
+            otc: Keeps only the OTC assets when True, only the regular ones when False.
+            active_only: Keeps only the assets that can be traded right now.
+
+        Returns:
+            The matching assets, sorted by symbol.
+        """
+        return self._run(self._client.get_assets(query, asset_type, otc, active_only))
+
     def active_assets(self) -> List[Dict]:
         """Get the list of currently active trading assets.
 
@@ -767,3 +1341,49 @@ class PocketOption:
             A list of dictionaries with active asset details.
         """
         return self._run(self._client.active_assets())
+
+    def asset_schedule(self, asset: str) -> Dict:
+        """Get the trading hours of an asset, inferred from its type and OTC status.
+
+        Args:
+            asset: The trading asset symbol.
+
+        Returns:
+            A dictionary with `always_open` and a list of weekly UTC `windows`.
+        """
+        return self._run(self._client.asset_schedule(asset))
+
+    def is_tradeable_now(self, asset: str) -> bool:
+        """Check whether an asset is active and inside its trading hours.
+
+        Args:
+            asset: The trading asset symbol.
+
+        Returns:
+            True if the asset can be traded at the current server time.
+        """
+        return self._run(self._client.is_tradeable_now(asset))
+
+    def refresh_assets(self, timeout: float = 30.0) -> List[Dict]:
+        """Request the asset list again and return it once received.
+
+        Args:
+            timeout: The maximum time to wait in seconds.
+
+        Returns:
+            A list of dictionaries with every asset.
+        """
+        return self._run(self._client.refresh_assets(timeout))
+
+    def assets_changed(self) -> SyncSubscription:
+        """Subscribe to changes between consecutive asset lists.
+
+        Returns:
+            A SyncSubscription yielding dictionaries with a `symbol` and a `kind` of
+            "added", "removed", "opened", "closed" or "payout_changed".
+        """
+
+        async def _sub():
+            return await self._client.client.assets_changed()
+
+        return SyncSubscription(self._run(_sub()))
@@ -0,0 +1,193 @@
+"""
+Arrow output of PocketOption candles, for ML pipelines.
+
+Live candles of a subscription are grouped into ``pyarrow.RecordBatch`` objects of
+``batch_size`` candles, a partial batch being flushed after ``flush_interval`` seconds.
+The batches can be consumed in Python (``stream_candles_arrow``) or sent as an Arrow IPC
+stream to every reader of a local socket (``serve_candles_arrow``)::
+
+    server = await client.serve_candles_arrow("EURUSD_otc", timedelta(seconds=5), port=9100)
+
+    # In another process
+    import socket, pyarrow as pa
+    reader = pa.ipc.open_stream(socket.create_connection(("127.0.0.1", 9100)).makefile("rb"))
+    for batch in reader:
+        ...
+
+Requires ``pyarrow`` (``pip install BinaryOptionsToolsV2[arrow]``).
+"""
+
+from __future__ import annotations
+
+import asyncio
+import time
+from typing import Any, AsyncIterator, Dict, List, Optional, Set
+
+COLUMNS = ("timestamp", "open", "high", "low", "close", "volume")
+
+
+def _pyarrow():
+    try:
+        import pyarrow as pa
+    except ImportError as e:
+        raise ImportError(
+            "pyarrow is required for Arrow output, install it with `pip install BinaryOptionsToolsV2[arrow]`"
+        ) from e
+    return pa
+
+
+def candle_schema():
+    """Schema of the candle record batches."""
+    pa = _pyarrow()
+    return pa.schema(
+        [
+            ("timestamp", pa.int64()),
+            ("open", pa.float64()),
+            ("high", pa.float64()),
+            ("low", pa.float64()),
+            ("close", pa.float64()),
+            ("volume", pa.float64()),
+        ]
+    )
+
+
+def candle_columns_to_arrow(columns: Dict[str, List]):
+    """Converts the columns of `get_candles_columns()` to a `pyarrow.RecordBatch`."""
+    return _pyarrow().RecordBatch.from_pydict(columns, schema=candle_schema())
+
+
+class CandleBatcher:
+    """Groups candle dictionaries into record batches.
+
+    Args:
+        batch_size (int): Candles per batch
+        flush_interval (float): Seconds after which a partial batch is flushed
+    """
+
+    def __init__(self, batch_size: int = 100, flush_interval: float = 5.0):
+        if batch_size < 1:
+            raise ValueError("batch_size must be positive")
+        if flush_interval <= 0:
+            raise ValueError("flush_interval must be positive")
+        self.batch_size = batch_size
+        self.flush_interval = flush_interval
+        self._columns: Dict[str, List] = {name: [] for name in COLUMNS}
+        self._started: Optional[float] = None
+
+    def __len__(self) -> int:
+        return len(self._columns["timestamp"])
+
+    def add(self, candle: Dict[str, Any]):
+        """Adds a candle, returning the batch once `batch_size` candles are pending."""
+        if self._started is None:
+            self._started = time.monotonic()
+        for name in COLUMNS:
+            self._columns[name].append(candle.get(name))
+        if len(self) >= self.batch_size:
+            return self.flush()
+        return None
+
+    def time_left(self) -> Optional[float]:
+        """Seconds until the pending candles are due, None when there are none."""
+        if self._started is None:
+            return None
+        return max(0.0, self._started + self.flush_interval - time.monotonic())
+
+    def flush(self):
+        """Returns the pending candles as a batch, None when there are none."""
+        if not len(self):
+            return None
+        batch = candle_columns_to_arrow(self._columns)
+        self._columns = {name: [] for name in COLUMNS}
+        self._started = None
+        return batch
+
+
+async def arrow_batches(
+    candles: AsyncIterator[Dict[str, Any]], batch_size: int = 100, flush_interval: float = 5.0
+) -> AsyncIterator[Any]:
+    """Yields the candles of `candles` as record batches, see `CandleBatcher`."""
+    batcher = CandleBatcher(batch_size, flush_interval)
+    iterator = candles.__aiter__()
+    pending: Optional[asyncio.Task] = None
+    try:
+        while True:
+            if pending is None:
+                pending = asyncio.ensure_future(iterator.__anext__())
+            done, _ = await asyncio.wait({pending}, timeout=batcher.time_left())
+            if not done:
+                batch = batcher.flush()
+                if batch is not None:
+                    yield batch
+                continue
+            task, pending = pending, None
+            try:
+                candle = task.result()
+            except StopAsyncIteration:
+                break
+            batch = batcher.add(candle)
+            if batch is not None:
+                yield batch
+        batch = batcher.flush()
+        if batch is not None:
+            yield batch
+    finally:
+        if pending is not None:
+            pending.cancel()
+
+
+class ArrowIpcServer:
+    """Local socket server sending the candle batches as an Arrow IPC stream to every reader.
+
+    A reader joining late gets the schema followed by the batches produced from then on.
+    """
+
+    def __init__(self, batches: AsyncIterator[Any]):
+        self._batches = batches
+        self._schema = candle_schema().serialize().to_pybytes()
+        self._writers: Set[asyncio.StreamWriter] = set()
+        self._server: Optional[asyncio.AbstractServer] = None
+        self._task: Optional[asyncio.Task] = None
+
+    async def start(self, host: str = "127.0.0.1", port: int = 0, path: Optional[str] = None) -> "ArrowIpcServer":
+        """Listens on `host`:`port`, or on the unix socket `path` when set."""
+        if path is not None:
+            self._server = await asyncio.start_unix_server(self._accept, path=path)
+        else:
+            self._server = await asyncio.start_server(self._accept, host, port)
+        self._task = asyncio.ensure_future(self._broadcast())
+        return self
+
+    @property
+    def address(self):
+        """Address the server listens on, e.g. `("127.0.0.1", 9100)`."""
+        return self._server.sockets[0].getsockname()
+
+    async def _accept(self, reader: asyncio.StreamReader, writer: asyncio.StreamWriter) -> None:
+        writer.write(self._schema)
+        self._writers.add(writer)
+
+    async def _broadcast(self) -> None:
+        async for batch in self._batches:
+            message = batch.serialize().to_pybytes()
+            for writer in list(self._writers):
+                try:
+                    writer.write(message)
+                    await writer.drain()
+                except (ConnectionError, OSError):
+                    self._writers.discard(writer)
+                    writer.close()
+
+    async def close(self) -> None:
+        """Stops the stream and ends it for every reader."""
+        if self._task is not None:
+            self._task.cancel()
+            await asyncio.gather(self._task, return_exceptions=True)
+        if self._server is not None:
+            self._server.close()
+            await self._server.wait_closed()
+        for writer in self._writers:
+            # End-of-stream marker
+            writer.write(b"\xff\xff\xff\xff\x00\x00\x00\x00")
+            writer.close()
+        self._writers.clear()
@@ -2,7 +2,7 @@ import json
 import os
 import sys
 import warnings
-from typing import Optional
+from typing import Dict, Optional
 
 
 class LogSubscription:
@@ -57,11 +57,33 @@ class LogBuilder:
     def __init__(self):
         self.builder = _get_rust_attr("LogBuilder")()
 
-    def log_file(self, path: str, level: str) -> None:
-        self.builder.log_file(path, level)
-
-    def terminal(self, level: str) -> None:
-        self.builder.terminal(level)
+    def log_file(
+        self,
+        path: str = "logs.log",
+        level: str = "DEBUG",
+        json: bool = False,
+        filters: Optional[Dict[str, str]] = None,
+        rotation: Optional[str] = None,
+        max_size: Optional[int] = None,
+        max_files: int = 5,
+    ) -> None:
+        """
+        Write logs to a file.
+
+        Args:
+            path: Log file path.
+            level: Default logging level.
+            json: Write one JSON object per line instead of text.
+            filters: Per-target levels overriding `level`, e.g. {"Supervisor": "WARN"}.
+            rotation: Start a new file every "minutely", "hourly" or "daily" period.
+            max_size: Start a new file before it grows past this many bytes.
+            max_files: Number of rotated files to keep (`logs.log.1`, `logs.log.2`, ...).
+        """
+        self.builder.log_file(path, level, json, filters, rotation, max_size, max_files)
+
+    def terminal(self, level: str = "DEBUG", json: bool = False, filters: Optional[Dict[str, str]] = None) -> None:
+        """Write logs to the terminal, `json` and `filters` work like in `log_file`."""
+        self.builder.terminal(level, json, filters)
 
     def build(self) -> None:
         self.builder.build()
@@ -70,7 +92,22 @@ class LogBuilder:
         return self.builder.create_logs_iterator(level, timeout)
 
 
-def start_logs(path: str, level: str = "DEBUG", terminal: bool = True, layers: Optional[list] = None):
+def set_log_redaction(enabled: bool) -> None:
+    """
+    Mask session tokens, session cookies, uids and balances in every log output (the default).
+
+    Only disable it for local debugging, the logs then contain the session of the account.
+    """
+    _get_rust_attr("set_log_redaction")(enabled)
+
+
+def start_logs(
+    path: str,
+    level: str = "DEBUG",
+    terminal: bool = True,
+    layers: Optional[list] = None,
+    redact: bool = True,
+):
     """
     Initialize the logging system.
 
@@ -79,6 +116,7 @@ def start_logs(path: str, level: str = "DEBUG", terminal: bool = True, layers: O
         level: Logging level (default "DEBUG").
         terminal: Whether to display logs in terminal (default True).
         layers: Optional list of layers to initialize.
+        redact: Mask session tokens, uids and balances (default True), see `set_log_redaction`.
     """
     if layers is None:
         layers = []
@@ -86,6 +124,7 @@ def start_logs(path: str, level: str = "DEBUG", terminal: bool = True, layers: O
     start_tracing = _get_rust_attr("start_tracing")
     os.makedirs(path, exist_ok=True)
     try:
+        set_log_redaction(redact)
         start_tracing(path, level, terminal, layers)
     except Exception as e:
         warnings.warn(f"start_logs: {e}", RuntimeWarning, stacklevel=2)
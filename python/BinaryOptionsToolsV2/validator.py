@@ -1,5 +1,5 @@
 import sys
-from typing import Callable, List
+from typing import Any, Callable, List, Optional
 
 
 def _get_raw_validator():
@@ -54,6 +54,35 @@ class Validator:
     def any(validators: List["Validator"]) -> "Validator":
         return Validator(_get_raw_validator().any([item._validator for item in validators]))
 
+    @staticmethod
+    def json_path(
+        path: str,
+        eq: Any = None,
+        ne: Any = None,
+        gt: Optional[float] = None,
+        lt: Optional[float] = None,
+        matches: Optional["Validator"] = None,
+    ) -> "Validator":
+        """Matches messages whose JSON value at `path` satisfies the given condition.
+
+        Socket.IO event frames are matched as `{"event": <name>, "data": <payload>}`, so
+        `Validator.json_path("$.data.requestId", eq=request_id)` matches the answer to a
+        specific order. Without a condition the path only has to exist.
+        """
+        return Validator(
+            _get_raw_validator().json_path(
+                path, eq=eq, ne=ne, gt=gt, lt=lt, matches=matches._validator if matches is not None else None
+            )
+        )
+
+    def and_(self, other: "Validator") -> "Validator":
+        """Returns a validator matching messages accepted by both validators."""
+        return Validator(self._validator.and_(other._validator))
+
+    def or_(self, other: "Validator") -> "Validator":
+        """Returns a validator matching messages accepted by either validator."""
+        return Validator(self._validator.or_(other._validator))
+
     @staticmethod
     def custom(func: Callable[[str], bool]) -> "Validator":
         if not callable(func):
@@ -75,3 +75,21 @@ def test_init_import_fallbacks():
 
     # Restore the module to a clean state by reloading without mocks
     importlib.reload(BinaryOptionsToolsV2)
+
+
+def test_pocket_error_hierarchy():
+    """Specific error classes must stay catchable as PocketOptionError."""
+    if not hasattr(BinaryOptionsToolsV2, "PocketOptionError"):
+        return
+    for name in (
+        "OperationTimeoutError",
+        "NotAuthorizedError",
+        "AssetInactiveError",
+        "AmountOutOfRangeError",
+        "PayoutTooLowError",
+        "EarlyCloseRejectedError",
+        "ServerRejectedError",
+        "ConnectionLostError",
+    ):
+        cls = getattr(BinaryOptionsToolsV2, name)
+        assert issubclass(cls, BinaryOptionsToolsV2.PocketOptionError)
@@ -47,6 +47,106 @@ def test_config_to_dict_json():
     assert '"reconnect_time": 7' in j
 
 
+def test_config_module_flags():
+    cfg = Config.from_dict({"enable_raw": False})
+    assert cfg.enable_pending_trades
+    assert cfg.enable_historical_data
+    assert not cfg.enable_raw
+    assert cfg.to_dict()["enable_raw"] is False
+
+
+def test_config_session_path():
+    cfg = Config.from_dict({"session_path": "session.json"})
+    assert cfg.session_path == "session.json"
+    assert cfg.to_dict()["session_path"] == "session.json"
+    assert Config().session_path is None
+
+
+def test_config_frame_dump():
+    cfg = Config.from_dict({"frame_dump": "frames.jsonl.gz"})
+    assert cfg.frame_dump == "frames.jsonl.gz"
+    assert cfg.to_dict()["frame_dump"] == "frames.jsonl.gz"
+    assert Config().frame_dump is None
+
+
+def test_config_trade_guard():
+    config = Config.from_dict({"max_open_per_asset": 2, "trade_cooldown_ms": 5000})
+    assert config.to_dict()["max_open_per_asset"] == 2
+    assert config.to_dict()["trade_cooldown_ms"] == 5000
+    assert Config().max_open_per_asset is None
+    with pytest.raises(ValueError, match="max_open_per_asset"):
+        Config(max_open_per_asset=0)._validate()
+    with pytest.raises(ValueError, match="trade_cooldown_ms"):
+        Config(trade_cooldown_ms=-1)._validate()
+
+
+def test_config_kill_switch():
+    config = Config.from_dict({"daily_profit_target": 50.0, "max_drawdown": 20.0})
+    assert config.to_dict()["daily_profit_target"] == 50.0
+    assert config.to_dict()["max_drawdown"] == 20.0
+    assert Config().max_drawdown is None
+    with pytest.raises(ValueError, match="max_drawdown"):
+        Config(max_drawdown=0)._validate()
+    with pytest.raises(ValueError, match="daily_profit_target"):
+        Config(daily_profit_target=-5)._validate()
+
+
+def test_config_audit_log():
+    assert Config().audit_log == 0
+    assert Config.from_dict({"audit_log": 500}).to_dict()["audit_log"] == 500
+    with pytest.raises(ValueError, match="audit_log"):
+        Config(audit_log=-1)._validate()
+
+
+def test_config_keep_alive():
+    cfg = Config.from_dict({"keep_alive_interval_ms": 15000, "keep_alive_reauth_interval_secs": 600})
+    assert cfg.keep_alive_payload == '42["ps"]'
+    assert cfg.to_dict()["keep_alive_interval_ms"] == 15000
+    assert cfg.to_dict()["keep_alive_reauth_interval_secs"] == 600
+    with pytest.raises(ValueError, match="keep_alive_interval_ms"):
+        Config(keep_alive_interval_ms=0)._validate()
+
+
+def test_config_server_time_drift_threshold():
+    cfg = Config.from_dict({"server_time_drift_threshold_secs": 3})
+    assert cfg.to_dict()["server_time_drift_threshold_secs"] == 3
+    assert Config().server_time_drift_threshold_secs == 5
+    with pytest.raises(ValueError, match="server_time_drift_threshold_secs"):
+        Config(server_time_drift_threshold_secs=0)._validate()
+
+
+def test_config_system_proxy():
+    assert Config().system_proxy is False
+    cfg = Config.from_dict({"system_proxy": True})
+    assert cfg.to_dict()["system_proxy"] is True
+
+
+def test_config_dns():
+    overrides = {"api-eu.po.market": ["104.18.1.1"]}
+    cfg = Config.from_dict({"dns_overrides": overrides, "doh_url": "https://1.1.1.1/dns-query"})
+    assert cfg.to_dict()["dns_overrides"] == overrides
+    assert cfg.to_dict()["doh_url"] == "https://1.1.1.1/dns-query"
+    assert Config().dns_overrides is None
+
+
+def test_config_init_profile():
+    cfg = Config.from_dict({"init_profile": "minimal"})
+    assert cfg.to_dict()["init_profile"] == "minimal"
+    assert Config().init_profile == "standard"
+    with pytest.raises(ValueError, match="init_profile"):
+        Config(init_profile="fast")._validate()
+
+
+def test_config_timezone():
+    cfg = Config.from_dict({"timezone": "Europe/Madrid", "dst_policy": "reject", "aware_datetimes": True})
+    assert cfg.to_dict()["timezone"] == "Europe/Madrid"
+    assert cfg.to_dict()["dst_policy"] == "reject"
+    assert cfg.to_dict()["aware_datetimes"] is True
+    assert (Config().timezone, Config().dst_policy, Config().aware_datetimes) == ("UTC", "earliest", False)
+    with pytest.raises(ValueError, match="dst_policy"):
+        Config(dst_policy="nearest")._validate()
+
+
 def test_config_update():
     cfg = Config()
     cfg.update({"timeout_secs": 45, "log_level": "ERROR"})
@@ -65,6 +165,14 @@ def test_config_validation_errors():
         Config(connection_initialization_timeout_secs=0)._validate()
     with pytest.raises(ValueError, match="timeout_secs"):
         Config(timeout_secs=0)._validate()
+    with pytest.raises(ValueError, match="reconnect_multiplier"):
+        Config(reconnect_multiplier=0.5)._validate()
+    with pytest.raises(ValueError, match="reconnect_max_delay"):
+        Config(reconnect_time=10, reconnect_max_delay=5)._validate()
+    with pytest.raises(ValueError, match="reconnect_jitter"):
+        Config(reconnect_jitter=1.5)._validate()
+    with pytest.raises(ValueError, match="duplicate_trade_window_ms"):
+        Config(duplicate_trade_window_ms=-1)._validate()
 
 
 def test_config_get_pyconfig_fallback():
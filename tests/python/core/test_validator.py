@@ -116,3 +116,31 @@ def test_validator_get_raw_validator_fallback():
         mock_modules.get.return_value = None
         raw_val = _get_raw_validator()
         assert raw_val is not None
+
+
+def test_validator_json_path():
+    import pytest
+
+    v = Validator.json_path("$.data.requestId", eq="abc")
+    assert v.check('42["successopenOrder",{"requestId":"abc"}]')
+    assert not v.check('42["successopenOrder",{"requestId":"xyz"}]')
+    assert Validator.json_path("$.amount", gt=5).check('{"amount": 10}')
+    assert Validator.json_path("$.event", matches=Validator.starts_with("success")).check('42["successopenOrder",{}]')
+
+    combined = Validator.contains("openOrder").and_(Validator.json_path("$.data.requestId"))
+    assert combined.check('42["successopenOrder",{"requestId":"abc"}]')
+    assert not combined.check('42["successopenOrder",{}]')
+
+    with pytest.raises(Exception):
+        Validator.json_path("data.requestId")
+    with pytest.raises(Exception):
+        Validator.json_path("$.a", eq=1, ne=2)
+
+
+def test_validator_nesting_limit():
+    import pytest
+
+    v = Validator.contains("a")
+    with pytest.raises(Exception, match="nested"):
+        for _ in range(64):
+            v = Validator.ne(v)
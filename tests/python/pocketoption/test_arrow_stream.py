@@ -0,0 +1,76 @@
+"""Tests for the Arrow batching and IPC streaming of candles, no network required."""
+
+import asyncio
+import os
+import sys
+
+import pytest
+
+_source = os.path.join(os.path.dirname(__file__), "../../../python")
+if _source not in sys.path:
+    sys.path.insert(0, _source)
+
+pa = pytest.importorskip("pyarrow")
+
+from BinaryOptionsToolsV2.pocketoption.tools.arrow_stream import (  # noqa: E402
+    ArrowIpcServer,
+    CandleBatcher,
+    arrow_batches,
+)
+
+
+def candle(ts):
+    return {"symbol": "EURUSD_otc", "timestamp": ts, "open": 1.0, "high": 1.2, "low": 0.9, "close": 1.1}
+
+
+async def candles(timestamps, delay=0.0):
+    for ts in timestamps:
+        await asyncio.sleep(delay)
+        yield candle(ts)
+
+
+def test_batcher_groups_candles():
+    batcher = CandleBatcher(batch_size=2)
+    assert batcher.add(candle(1)) is None
+    batch = batcher.add(candle(2))
+    assert batch.num_rows == 2
+    assert batch.column("timestamp").to_pylist() == [1, 2]
+    assert batch.column("volume").to_pylist() == [None, None]
+    assert batcher.flush() is None
+    with pytest.raises(ValueError):
+        CandleBatcher(batch_size=0)
+
+
+@pytest.mark.asyncio
+async def test_arrow_batches_flush_partial_batches():
+    batches = [b async for b in arrow_batches(candles([1, 2, 3]), batch_size=2)]
+    assert [b.num_rows for b in batches] == [2, 1]
+
+    # A slow stream is flushed by the interval before the batch fills up.
+    batches = [b async for b in arrow_batches(candles([1, 2], delay=0.2), batch_size=10, flush_interval=0.05)]
+    assert [b.num_rows for b in batches] == [1, 1]
+
+
+@pytest.mark.asyncio
+async def test_ipc_server_streams_batches():
+    queue = asyncio.Queue()
+
+    async def batches():
+        while True:
+            yield await queue.get()
+
+    server = await ArrowIpcServer(batches()).start()
+    host, port = server.address[:2]
+    reader, writer = await asyncio.open_connection(host, port)
+    await asyncio.sleep(0.05)
+    batcher = CandleBatcher(batch_size=2)
+    batcher.add(candle(1))
+    await queue.put(batcher.add(candle(2)))
+    await asyncio.sleep(0.05)
+    await server.close()
+
+    data = await reader.read()
+    writer.close()
+    received = list(pa.ipc.open_stream(data))
+    assert len(received) == 1
+    assert received[0].column("timestamp").to_pylist() == [1, 2]
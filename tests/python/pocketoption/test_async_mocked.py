@@ -21,13 +21,22 @@ class MockRawClient:
     def __init__(self, *args, **kwargs):
         self._closed = False
         self._connected = True
-
-    async def buy(self, asset, amount, time):
+        self.stats_reset = False
+        self.alerts = {}
+        self.kill_switch_event = {
+            "reason": "max_drawdown",
+            "pnl": "-20",
+            "peak": "0",
+            "at": "2024-01-01T12:00:00Z",
+            "resets_at": "2024-01-02T00:00:00Z",
+        }
+
+    async def buy(self, asset, amount, time, allow_duplicate=False, min_payout=None, payout_wait_secs=0.0):
         return "trade_123", json.dumps(
-            {"asset": asset, "amount": amount, "time": time, "direction": "buy"}
+            {"asset": asset, "amount": str(amount), "time": time, "direction": "buy"}
         )
 
-    async def sell(self, asset, amount, time):
+    async def sell(self, asset, amount, time, allow_duplicate=False, min_payout=None, payout_wait_secs=0.0):
         return "trade_456", json.dumps(
             {"asset": asset, "amount": amount, "time": time, "direction": "sell"}
         )
@@ -42,6 +51,15 @@ class MockRawClient:
             return None
         return int(asyncio.get_event_loop().time()) + 60
 
+    async def close_deal_early(self, trade_id):
+        if trade_id == "closed":
+            raise Exception("Early close of deal closed rejected: deal already closed")
+        return json.dumps({"deal_id": trade_id, "returned": "1.4", "deal": {"id": trade_id}})
+
+    async def deal_status(self, trade_id, wait=False, history_fallback_secs=None):
+        status = "closed" if wait else "open"
+        return json.dumps({"status": status, "deal": {"id": trade_id}})
+
     async def candles(self, asset, period):
         return json.dumps(
             [
@@ -60,9 +78,24 @@ class MockRawClient:
             [{"time": time, "open": 1.1, "high": 1.2, "low": 1.0, "close": 1.15}]
         )
 
+    async def get_candles_columns(self, asset, period, offset):
+        return {
+            "timestamp": [1000, 1060],
+            "open": [1.1, 1.15],
+            "high": [1.2, 1.25],
+            "low": [1.0, 1.1],
+            "close": [1.15, 1.2],
+            "volume": [None, None],
+        }
+
     async def balance(self):
         return 1000.50
 
+    async def balances(self):
+        return json.dumps(
+            {"demo": {"amount": "1000.50", "currency": "USD", "updated_at": "2024-01-01T00:00:00Z"}}
+        )
+
     async def opened_deals(self):
         return json.dumps(
             [{"id": "deal1", "asset": "EURUSD_otc", "amount": 10.0, "status": "open"}]
@@ -106,8 +139,27 @@ class MockRawClient:
     async def clear_closed_deals(self):
         pass
 
-    async def payout(self):
-        return json.dumps({"EURUSD_otc": 85, "GBPUSD_otc": 82, "BTCUSD_otc": 78})
+    async def get_assets(self):
+        def asset(symbol, name, asset_type, payout, is_active=True):
+            return {
+                "id": 1,
+                "symbol": symbol,
+                "name": name,
+                "asset_type": asset_type,
+                "payout": payout,
+                "is_otc": symbol.endswith("_otc"),
+                "is_active": is_active,
+                "allowed_candles": [{"time": 60}, {"time": 300}],
+            }
+
+        return json.dumps(
+            [
+                asset("#AAPL", "Apple", "stock", 70, is_active=False),
+                asset("BTCUSD_otc", "Bitcoin OTC", "cryptocurrency", 78),
+                asset("EURUSD_otc", "EUR/USD OTC", "currency", 85),
+                asset("GBPUSD_otc", "GBP/USD OTC", "currency", 82),
+            ]
+        )
 
     async def active_assets(self):
         return json.dumps(
@@ -146,13 +198,17 @@ class MockRawClient:
 
         return subscription()
 
-    async def subscribe_symbol_timed(self, asset, time):
+    async def subscribe_symbol_timed(self, asset, time, gap_fill=None):
+        self.gap_fill = gap_fill
+
         async def subscription():
             yield json.dumps({"time": 1000, "price": 1.11})
 
         return subscription()
 
-    async def subscribe_symbol_time_aligned(self, asset, time):
+    async def subscribe_symbol_time_aligned(self, asset, time, gap_fill=None):
+        self.gap_fill = gap_fill
+
         async def subscription():
             yield json.dumps({"aligned_time": 1000, "price": 1.11})
 
@@ -167,6 +223,47 @@ class MockRawClient:
     def is_demo(self):
         return True
 
+    async def save_session(self, path):
+        self.saved_session = path
+
+    def stats(self):
+        return json.dumps(
+            {"messages_received": 0 if self.stats_reset else 12, "modules": {"DealsApiModule": 3}}
+        )
+
+    def reset_stats(self):
+        self.stats_reset = True
+
+    def audit_log(self, since=0):
+        records = [
+            {"seq": 1, "time": 1700000000.0, "source": "InitModule", "message": '42["getBalance"]'},
+            {"seq": 2, "time": 1700000001.0, "source": "SubscriptionsApiModule", "message": '42["changeSymbol",{}]'},
+        ]
+        return json.dumps([record for record in records if record["seq"] > since])
+
+    async def slippage_stats(self):
+        return json.dumps(
+            {
+                "deals": 2,
+                "requotes": 1,
+                "adverse": 1,
+                "mean": 0.0001,
+                "worst": 0.0002,
+                "per_asset": {"EURUSD_otc": {"deals": 2, "requotes": 1, "adverse": 1, "mean": 0.0001, "worst": 0.0002}},
+            }
+        )
+
+    async def kill_switch_status(self):
+        return json.dumps({"day": "2024-01-01", "pnl": "-20", "peak": "0", "tripped": self.kill_switch_event})
+
+    async def kill_switch_events(self):
+        event = self.kill_switch_event
+
+        async def subscription():
+            yield json.dumps(event)
+
+        return subscription()
+
     async def disconnect(self):
         self._connected = False
 
@@ -176,18 +273,47 @@ class MockRawClient:
     async def reconnect(self):
         self._connected = True
 
+    async def on_reconnect(self, callback):
+        self.reconnect_callback = callback
+        return MagicMock()
+
+    async def on_disconnect(self, callback):
+        self.disconnect_callback = callback
+        return MagicMock()
+
+    async def subscribe_many(self, subscriptions):
+        self.many_subscriptions = subscriptions
+
+        async def subscription():
+            for asset, _, _ in subscriptions:
+                yield json.dumps([asset, {"symbol": asset, "close": 1.11}])
+
+        return subscription()
+
+    async def candle_buffer(self, assets, size, time=None):
+        buffer = MagicMock()
+        buffer.assets.return_value = sorted(assets)
+        buffer.size = size
+        buffer.time = time
+        return buffer
+
+    async def fatal_error(self):
+        await asyncio.sleep(0.01)
+        return json.dumps({"kind": "reconnect_exhausted", "attempts": 3})
+
     async def unsubscribe(self, asset):
         pass
 
     async def shutdown(self):
         self._closed = True
 
-    async def create_raw_handler(self, validator, keep_alive=None):
+    async def create_raw_handler(self, validator, keep_alive=None, correlation_path=None, reassemble=False):
         mock_handler = MagicMock()
         mock_handler.id.return_value = "handler_123"
         mock_handler.send_text = AsyncMock()
         mock_handler.send_binary = AsyncMock()
         mock_handler.send_and_wait = AsyncMock(return_value='42["response"]')
+        mock_handler.send_and_wait_for = AsyncMock(return_value='42["response",{"requestId":"a"}]')
         mock_handler.wait_next = AsyncMock(return_value='42["message"]')
 
         async def mock_subscribe():
@@ -203,6 +329,83 @@ class MockRawClient:
     async def send_raw(self, message):
         pass
 
+    async def scanner(self, assets, slots=4, dwell_secs=5.0):
+        async def subscription():
+            for rotation, asset in enumerate(assets, start=1):
+                yield json.dumps({"asset": asset, "candle": {"close": 1.1}, "rotation": rotation})
+
+        return subscription()
+
+    async def volatility(self, asset, period=14):
+        async def subscription():
+            yield json.dumps({"asset": asset, "price": 1.1, "stddev": 0.0002, "atr": 0.0003, "spread": None})
+
+        return subscription()
+
+    async def add_alert(self, asset, condition, price, repeat=False):
+        alert_id = f"alert-{len(self.alerts) + 1}"
+        self.alerts[alert_id] = {
+            "id": alert_id,
+            "asset": asset,
+            "condition": {"type": condition, "price": price},
+            "mode": "repeat" if repeat else "once",
+        }
+        return alert_id
+
+    async def remove_alert(self, alert_id):
+        return self.alerts.pop(alert_id, None) is not None
+
+    async def active_alerts(self):
+        return json.dumps(list(self.alerts.values()))
+
+    async def alert_events(self):
+        alerts = list(self.alerts.values())
+
+        async def subscription():
+            for alert in alerts:
+                yield json.dumps(
+                    {
+                        "alert_id": alert["id"],
+                        "asset": alert["asset"],
+                        "condition": alert["condition"],
+                        "price": alert["condition"]["price"],
+                        "repeat": alert["mode"] == "repeat",
+                    }
+                )
+
+        return subscription()
+
+    async def parse_failures(self):
+        async def subscription():
+            yield json.dumps(
+                {
+                    "module": "BalanceModule",
+                    "raw": '{"balance":"lots"}',
+                    "binary": True,
+                    "error": "invalid type",
+                    "timestamp": "2026-01-01T00:00:00Z",
+                }
+            )
+
+        return subscription()
+
+    async def data_quality_events(self):
+        async def subscription():
+            yield json.dumps(
+                {
+                    "asset": "EURUSD_otc",
+                    "period": 60,
+                    "timestamp": 1700000180,
+                    "source": "history",
+                    "issue": {"kind": "gap", "previous": 1700000000, "missing": 2},
+                }
+            )
+
+        return subscription()
+
+    async def add_middleware(self, callback, queue_size=1024):
+        self.middleware = (callback, queue_size)
+
     async def subscribe_raw(self):
         async def subscription():
             yield '42["raw_msg"]'
@@ -459,6 +662,14 @@ class TestBuyAndSell:
         assert trade["asset"] == "EURUSD_otc"
         assert trade["direction"] == "buy"
 
+    @pytest.mark.asyncio
+    async def test_buy_decimal_amount(self, async_client):
+        """Test a Decimal amount reaches the client unchanged."""
+        from decimal import Decimal
+
+        _, trade = await async_client.buy("EURUSD_otc", Decimal("2.33"), 60)
+        assert Decimal(trade["amount"]) == Decimal("2.33")
+
     @pytest.mark.asyncio
     async def test_buy_with_check_win(self, async_client):
         """Test buy with check_win=True."""
@@ -487,6 +698,13 @@ class TestBuyAndSell:
         with pytest.raises(Exception, match="Connection lost"):
             await async_client.buy("EURUSD_otc", 1.0, 60)
 
+    @pytest.mark.asyncio
+    async def test_buy_forwards_min_payout(self, async_client, mock_raw_pocketoption):
+        """Test the payout guard options reach the client."""
+        mock_raw_pocketoption.buy = AsyncMock(return_value=("trade_123", "{}"))
+        await async_client.buy("EURUSD_otc", 1.0, 60, min_payout=85, payout_wait=5.0)
+        mock_raw_pocketoption.buy.assert_awaited_once_with("EURUSD_otc", 1.0, 60, False, 85, 5.0)
+
 
 class TestCheckWin:
     """Tests for check_win method."""
@@ -530,6 +748,178 @@ class TestGetDealEndTime:
         assert end_time is None
 
 
+class TestCloseDealEarly:
+    """Tests for close_deal_early method."""
+
+    @pytest.mark.asyncio
+    async def test_close_deal_early_success(self, async_client):
+        """Test closing a deal early returns the parsed confirmation."""
+        result = await async_client.close_deal_early("trade_123")
+        assert result["deal_id"] == "trade_123"
+        assert result["returned"] == "1.4"
+
+    @pytest.mark.asyncio
+    async def test_close_deal_early_rejected(self, async_client):
+        """Test a rejected early close propagates the error."""
+        with pytest.raises(Exception, match="rejected"):
+            await async_client.close_deal_early("closed")
+
+
+class TestDealStatus:
+    """Tests for deal_status method."""
+
+    @pytest.mark.asyncio
+    async def test_deal_status_without_waiting(self, async_client):
+        status = await async_client.deal_status("trade_123")
+        assert status == {"status": "open", "deal": {"id": "trade_123"}}
+
+    @pytest.mark.asyncio
+    async def test_deal_status_waiting(self, async_client):
+        status = await async_client.deal_status("trade_123", wait=True, history_fallback_secs=10)
+        assert status["status"] == "closed"
+
+
+class TestScanner:
+    """Tests for scanner method."""
+
+    @pytest.mark.asyncio
+    async def test_scanner_yields_quotes(self, async_client):
+        """Test the scanner quotes are parsed."""
+        quotes = [quote async for quote in await async_client.scanner(["EURUSD_otc", "GBPUSD_otc"], slots=1)]
+        assert [q["asset"] for q in quotes] == ["EURUSD_otc", "GBPUSD_otc"]
+        assert quotes[0]["candle"]["close"] == 1.1
+
+
+class TestVolatility:
+    """Tests for volatility method."""
+
+    @pytest.mark.asyncio
+    async def test_volatility_yields_samples(self, async_client):
+        """Test the volatility samples are parsed."""
+        samples = [sample async for sample in await async_client.volatility("EURUSD_otc", period=10)]
+        assert samples[0]["atr"] == 0.0003
+        assert samples[0]["spread"] is None
+
+
+class TestAlerts:
+    """Tests for the price alert methods."""
+
+    @pytest.mark.asyncio
+    async def test_add_and_remove_alert(self, async_client):
+        """Test alerts are listed until removed."""
+        alert_id = await async_client.add_alert("EURUSD_otc", "crosses_above", 1.1, repeat=True)
+        alerts = await async_client.active_alerts()
+        assert alerts[0]["id"] == alert_id
+        assert alerts[0]["mode"] == "repeat"
+        assert await async_client.remove_alert(alert_id) is True
+        assert await async_client.remove_alert(alert_id) is False
+        assert await async_client.active_alerts() == []
+
+    @pytest.mark.asyncio
+    async def test_alert_events(self, async_client):
+        """Test fired alerts are parsed."""
+        alert_id = await async_client.add_alert("EURUSD_otc", "below", 1.05)
+        events = [event async for event in await async_client.alert_events()]
+        assert events[0]["alert_id"] == alert_id
+        assert events[0]["condition"]["type"] == "below"
+        assert events[0]["repeat"] is False
+
+
+class TestParseFailures:
+    """Tests for parse_failures method."""
+
+    @pytest.mark.asyncio
+    async def test_parse_failures(self, async_client):
+        """Test the quarantined frames are parsed."""
+        failures = [failure async for failure in await async_client.parse_failures()]
+        assert failures[0]["module"] == "BalanceModule"
+        assert failures[0]["raw"] == '{"balance":"lots"}'
+        assert failures[0]["binary"] is True
+
+
+class TestDataQualityEvents:
+    """Tests for data_quality_events method."""
+
+    @pytest.mark.asyncio
+    async def test_data_quality_events(self, async_client):
+        """Test the candle issues are parsed."""
+        events = [event async for event in await async_client.data_quality_events()]
+        assert events[0]["asset"] == "EURUSD_otc"
+        assert events[0]["source"] == "history"
+        assert events[0]["issue"] == {"kind": "gap", "previous": 1700000000, "missing": 2}
+
+
+class TestSaveSession:
+    """Tests for save_session method."""
+
+    @pytest.mark.asyncio
+    async def test_save_session(self, async_client):
+        """Test the path is passed to the client."""
+        await async_client.save_session("session.json")
+        assert async_client.client.saved_session == "session.json"
+
+
+class TestStats:
+    """Tests for stats and reset_stats methods."""
+
+    @pytest.mark.asyncio
+    async def test_stats_and_reset(self, async_client):
+        """Test the counters are parsed and can be reset."""
+        stats = async_client.stats()
+        assert stats["messages_received"] == 12
+        assert stats["modules"]["DealsApiModule"] == 3
+        async_client.reset_stats()
+        assert async_client.stats()["messages_received"] == 0
+
+    @pytest.mark.asyncio
+    async def test_audit_log(self, async_client):
+        """Test the audit records are parsed and filtered by sequence number."""
+        assert [record["source"] for record in async_client.audit_log()] == ["InitModule", "SubscriptionsApiModule"]
+        assert [record["seq"] for record in async_client.audit_log(since=1)] == [2]
+
+
+class TestSlippageStats:
+    """Tests for slippage_stats method."""
+
+    @pytest.mark.asyncio
+    async def test_slippage_stats(self, async_client):
+        """Test the slippage statistics are parsed."""
+        slippage = await async_client.slippage_stats()
+        assert slippage["requotes"] == 1
+        assert slippage["per_asset"]["EURUSD_otc"]["worst"] == 0.0002
+
+
+class TestKillSwitch:
+    """Tests for kill_switch_status and kill_switch_events methods."""
+
+    @pytest.mark.asyncio
+    async def test_kill_switch_status(self, async_client):
+        """Test the day result and the trip are parsed."""
+        status = await async_client.kill_switch_status()
+        assert status["day"] == "2024-01-01"
+        assert status["tripped"]["reason"] == "max_drawdown"
+
+    @pytest.mark.asyncio
+    async def test_kill_switch_events(self, async_client):
+        """Test the trip events are parsed."""
+        events = [event async for event in await async_client.kill_switch_events()]
+        assert events[0]["resets_at"] == "2024-01-02T00:00:00Z"
+
+
+class TestAddMiddleware:
+    """Tests for add_middleware method."""
+
+    @pytest.mark.asyncio
+    async def test_add_middleware_forwards_callback(self, async_client):
+        """Test the callback and queue size reach the Rust client."""
+
+        def callback(message, direction):
+            pass
+
+        await async_client.add_middleware(callback, queue_size=16)
+        assert async_client.client.middleware == (callback, 16)
+
+
 class TestCandles:
     """Tests for candles and get_candles methods."""
 
@@ -558,6 +948,22 @@ class TestCandles:
         assert isinstance(candles, list)
         assert len(candles) > 0
 
+    @pytest.mark.asyncio
+    async def test_get_candles_columns(self, async_client):
+        """Test get_candles_columns returns one list per column."""
+        columns = await async_client.get_candles_columns("EURUSD_otc", 3600, 60)
+        assert columns["timestamp"] == [1000, 1060]
+        assert columns["close"] == [1.15, 1.2]
+
+    @pytest.mark.asyncio
+    async def test_get_candles_arrow(self, async_client):
+        """Test get_candles_arrow builds a pyarrow RecordBatch."""
+        pytest.importorskip("pyarrow")
+        batch = await async_client.get_candles_arrow("EURUSD_otc", 3600, 60)
+        assert batch.num_rows == 2
+        assert batch.schema.names == ["timestamp", "open", "high", "low", "close", "volume"]
+        assert batch.column("open").to_pylist() == [1.1, 1.15]
+
     @pytest.mark.asyncio
     async def test_compile_candles_success(self, async_client, mock_raw_pocketoption):
         """Test compile_candles with custom periods."""
@@ -574,6 +980,14 @@ class TestCandles:
         assert "time" in candles[0]
         mock_raw_pocketoption.compile_candles.assert_called_with("EURUSD_otc", 20, 300)
 
+    @pytest.mark.asyncio
+    async def test_ticks_range_success(self, async_client, mock_raw_pocketoption):
+        """Test ticks_range converts the range and the returned pairs."""
+        mock_raw_pocketoption.ticks_range = AsyncMock(return_value=json.dumps([[1000, 1.1], [1001, 1.2]]))
+        ticks = await async_client.ticks_range("EURUSD_otc", 1000, 1001)
+        assert ticks == [{"time": 1000, "price": 1.1}, {"time": 1001, "price": 1.2}]
+        mock_raw_pocketoption.ticks_range.assert_called_with("EURUSD_otc", 1000, 1001)
+
     @pytest.mark.asyncio
     async def test_compile_candles_validation_error(self, async_client):
         """Test compile_candles validation for non-positive periods."""
@@ -608,6 +1022,14 @@ class TestBalance:
         assert isinstance(balance, float)
         assert balance >= 0
 
+    @pytest.mark.asyncio
+    async def test_balances_per_account_type(self, async_client):
+        """Test balances are keyed by account type with float amounts."""
+        balances = await async_client.balances()
+        assert set(balances) == {"demo"}
+        assert balances["demo"]["amount"] == 1000.50
+        assert balances["demo"]["currency"] == "USD"
+
 
 class TestOpenedDeals:
     """Tests for opened_deals method."""
@@ -817,6 +1239,32 @@ class TestPayout:
         assert payouts == []
 
 
+class TestGetAssets:
+    """Tests for get_assets method."""
+
+    @pytest.mark.asyncio
+    async def test_get_assets_structured(self, async_client):
+        assets = await async_client.get_assets()
+        assert [a.symbol for a in assets] == ["#AAPL", "BTCUSD_otc", "EURUSD_otc", "GBPUSD_otc"]
+        eur = assets[2]
+        assert eur.payout == 85
+        assert eur.asset_type == "currency"
+        assert eur.allowed_candles == [60, 300]
+
+    @pytest.mark.asyncio
+    async def test_get_assets_filters(self, async_client):
+        assert [a.symbol for a in await async_client.get_assets("/usd")] == ["EURUSD_otc", "GBPUSD_otc"]
+        assert [a.symbol for a in await async_client.get_assets(asset_type="stock")] == ["#AAPL"]
+        assert len(await async_client.get_assets(otc=True)) == 3
+        assert len(await async_client.get_assets(active_only=True)) == 3
+        with pytest.raises(ValueError):
+            await async_client.get_assets(asset_type="bond")
+
+    @pytest.mark.asyncio
+    async def test_payout_skips_inactive_assets(self, async_client):
+        assert await async_client.payout("#AAPL") is None
+
+
 class TestActiveAssets:
     """Tests for active_assets method."""
 
@@ -897,6 +1345,64 @@ class TestSubscriptions:
         assert sub is not None
         assert hasattr(sub, "__aiter__")
 
+    @pytest.mark.asyncio
+    async def test_subscribe_symbol_time_aligned_gap_fill(self, async_client):
+        """Test the gap filling mode is passed to the client."""
+        await async_client.subscribe_symbol_time_aligned("EURUSD_otc", timedelta(seconds=60), gap_fill="flat")
+        assert async_client.client.gap_fill == "flat"
+
+    @pytest.mark.asyncio
+    async def test_next_timeout_and_collect(self):
+        """Test next_timeout and collect parse the items of the underlying iterator."""
+        from BinaryOptionsToolsV2.pocketoption.asynchronous import AsyncSubscription
+
+        iterator = MagicMock()
+        iterator.next_timeout = AsyncMock(side_effect=[json.dumps({"close": 1.1}), None])
+        iterator.collect = AsyncMock(return_value=[json.dumps({"close": 1.2}), json.dumps({"close": 1.3})])
+        sub = AsyncSubscription(iterator)
+
+        assert await sub.next_timeout(0.5) == {"close": 1.1}
+        assert await sub.next_timeout(0.5) is None
+        candles = await sub.collect(2, timeout=1.0)
+        assert [c["close"] for c in candles] == [1.2, 1.3]
+        iterator.collect.assert_awaited_once_with(2, 1.0)
+
+    @pytest.mark.asyncio
+    async def test_subscribe_many(self, async_client):
+        """Test subscribe_many normalizes the subscription types and yields tagged candles."""
+        sub = await async_client.subscribe_many(
+            [
+                "EURUSD_otc",
+                ("GBPUSD_otc", timedelta(minutes=1)),
+                ("AUDUSD_otc", 10),
+                ("USDJPY_otc", ("time", timedelta(seconds=30))),
+            ]
+        )
+        assert async_client.client.many_subscriptions == [
+            ("EURUSD_otc", "none", None),
+            ("GBPUSD_otc", "time_aligned", 60.0),
+            ("AUDUSD_otc", "chunk", 10.0),
+            ("USDJPY_otc", "time", 30.0),
+        ]
+        asset, candle = await sub.__anext__()
+        assert asset == "EURUSD_otc"
+        assert candle["symbol"] == "EURUSD_otc"
+
+        with pytest.raises(ValueError):
+            await async_client.subscribe_many([("EURUSD_otc", 1.5)])
+
+    @pytest.mark.asyncio
+    async def test_candle_buffer(self, async_client):
+        """Test candle_buffer accepts a single asset or a list of assets."""
+        buffer = await async_client.candle_buffer("EURUSD_otc", 50, timedelta(minutes=1))
+        assert buffer.assets() == ["EURUSD_otc"]
+        assert buffer.size == 50
+        assert buffer.time == timedelta(minutes=1)
+
+        buffer = await async_client.candle_buffer(["GBPUSD_otc", "EURUSD_otc"], 10)
+        assert buffer.assets() == ["EURUSD_otc", "GBPUSD_otc"]
+        assert buffer.time is None
+
 
 class TestGetServerTime:
     """Tests for get_server_time method."""
@@ -967,6 +1473,35 @@ class TestConnectionMethods:
         await async_client.reconnect()
         assert async_client.client._connected is True
 
+    @pytest.mark.asyncio
+    async def test_on_fatal_calls_back_with_the_error(self, async_client):
+        """Test on_fatal passes the parsed error to sync and async callbacks."""
+        errors = []
+
+        async def record(error):
+            errors.append(error)
+
+        await async_client.on_fatal(errors.append)
+        await async_client.on_fatal(record)
+        assert errors == [{"kind": "reconnect_exhausted", "attempts": 3}] * 2
+
+    @pytest.mark.asyncio
+    async def test_on_reconnect_and_on_disconnect(self, async_client):
+        """Test reconnect events are parsed and coroutine callbacks are scheduled."""
+        events = []
+        statuses = []
+
+        async def record(event):
+            events.append(event)
+
+        await async_client.on_reconnect(record)
+        await async_client.on_disconnect(statuses.append)
+        async_client.client.disconnect_callback("reconnecting")
+        async_client.client.reconnect_callback(json.dumps({"type": "connected", "attempts": 2}))
+        await asyncio.sleep(0)
+        assert statuses == ["reconnecting"]
+        assert events == [{"type": "connected", "attempts": 2}]
+
 
 class TestUnsubscribe:
     """Tests for unsubscribe method."""
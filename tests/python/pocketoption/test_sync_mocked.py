@@ -67,7 +67,7 @@ class MockPocketOptionAsync:
     def client(self):
         return self
 
-    async def buy(self, asset, amount, time, check_win=False):
+    async def buy(self, asset, amount, time, check_win=False, allow_duplicate=False, min_payout=None, payout_wait=0.0):
         trade_id, trade = (
             "trade_123",
             {"asset": asset, "amount": amount, "time": time, "direction": "buy"},
@@ -77,7 +77,7 @@ class MockPocketOptionAsync:
             trade["profit"] = 1.5
         return trade_id, trade
 
-    async def sell(self, asset, amount, time, check_win=False):
+    async def sell(self, asset, amount, time, check_win=False, allow_duplicate=False, min_payout=None, payout_wait=0.0):
         trade_id, trade = (
             "trade_456",
             {"asset": asset, "amount": amount, "time": time, "direction": "sell"},
@@ -256,12 +256,13 @@ class MockPocketOptionAsync:
     async def shutdown(self):
         self._closed = True
 
-    async def create_raw_handler(self, validator, keep_alive=None):
+    async def create_raw_handler(self, validator, keep_alive=None, correlation_path=None, reassemble=False):
         mock_handler = MagicMock()
         mock_handler.id.return_value = "handler_123"
         mock_handler.send_text = AsyncMock()
         mock_handler.send_binary = AsyncMock()
         mock_handler.send_and_wait = AsyncMock(return_value="response")
+        mock_handler.send_and_wait_for = AsyncMock(return_value="correlated response")
         mock_handler.wait_next = AsyncMock(return_value="message")
         # subscribe mock
         async_iter = MagicMock()
@@ -876,6 +877,13 @@ class TestActiveAssets:
         assets = sync_client.active_assets()
         assert assets == []
 
+    def test_refresh_assets_passes_timeout(self, sync_client, mock_pocketoption_async):
+        """Test refresh_assets forwards the timeout and returns the new list."""
+        mock_pocketoption_async.refresh_assets = AsyncMock(return_value=[{"symbol": "EURUSD_otc", "payout": 90}])
+        assets = sync_client.refresh_assets(timeout=5.0)
+        mock_pocketoption_async.refresh_assets.assert_awaited_once_with(5.0)
+        assert assets[0]["payout"] == 90
+
 
 class TestHistory:
     """Tests for history method."""
@@ -1047,6 +1055,13 @@ class TestCreateRawHandler:
         response = handler.send_and_wait('42["getServerTime"]')
         assert isinstance(response, str)
 
+    def test_raw_handler_send_and_wait_for(self, sync_client):
+        """Test raw handler send_and_wait_for with a correlation path."""
+        validator = Validator.contains('"requestId"')
+        handler = sync_client.create_raw_handler(validator, correlation_path="$.data.requestId")
+        response = handler.send_and_wait_for('42["openOrder",{"requestId":"a"}]', "a", timeout=5.0)
+        assert response == "correlated response"
+
     def test_raw_handler_wait_next(self, sync_client):
         """Test raw handler wait_next."""
         validator = Validator.starts_with('42["test"')
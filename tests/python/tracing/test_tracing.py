@@ -36,6 +36,25 @@ def test_log_builder(tmp_path):
     # but the file should exist at least.
 
 
+def test_log_builder_json_rotation(tmp_path):
+    builder = LogBuilder()
+    builder.log_file(
+        str(tmp_path / "bot.jsonl"),
+        "INFO",
+        json=True,
+        filters={"Supervisor": "WARN"},
+        rotation="daily",
+        max_size=10_000_000,
+        max_files=3,
+    )
+    assert (tmp_path / "bot.jsonl").exists()
+
+    with pytest.raises(Exception):
+        builder.log_file(str(tmp_path / "bad.log"), rotation="weekly")
+    with pytest.raises(Exception):
+        builder.terminal("INFO", filters={"Supervisor": "loud"})
+
+
 def test_start_logs(tmp_path):
     log_dir = tmp_path / "logs_start"
 
@@ -124,6 +143,15 @@ def test_start_logs_failure():
             start_logs("logs_fail", level="INVALID_LEVEL")
 
 
+def test_start_logs_sets_redaction(tmp_path):
+    from unittest.mock import patch
+
+    with patch("BinaryOptionsToolsV2.tracing._get_rust_attr") as mock_get_attr:
+        start_logs(str(tmp_path / "logs"), redact=False)
+        mock_get_attr.assert_any_call("set_log_redaction")
+        mock_get_attr.return_value.assert_any_call(False)
+
+
 def test_get_rust_attr_fallback():
     from unittest.mock import patch
     from BinaryOptionsToolsV2.tracing import _get_rust_attr